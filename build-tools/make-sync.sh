@@ -26,6 +26,8 @@ cp -r src sea-orm-sync
 cp -r tests sea-orm-sync
 cp examples/quickstart/src/main.rs sea-orm-sync/examples/quickstart/src/main.rs
 rm -rf sea-orm-sync/src/bin
+# the proxy server listens on tokio sockets; there is no blocking counterpart
+rm sea-orm-sync/src/database/proxy_server.rs
 cd sea-orm-sync
 
 replace_rs "s/Pin<Box<dyn Future<Output = Result<Self::Stream<'a>, DbErr>> + 'a + Send>>/Result<Self::Stream<'a>, DbErr>/" src
@@ -34,6 +36,7 @@ replace_rs "s/Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'c>>/Result<T,
 replace_rs 's/Box::pin(async move {/({/' src
 replace_rs 's/Box::pin(async move {/({/' tests
 replace_rs 's/AsyncFnOnce/FnOnce/g' src
+replace_rs 's/AsyncFnMut/FnMut/g' src
 replace_rs 's/transaction_with_config_async/transaction_with_config/g' src tests
 replace_rs 's/transaction_async/transaction/g' src tests
 replace_rs 's/async //' src
@@ -56,6 +59,7 @@ replace_rs "s/type Stream<'a>: Stream/type Stream<'a>: Iterator/" src
 replace_rs 's/: Send {/ {/' src
 replace_rs 's/>: Send$/>/' src
 replace_rs 's/: Sync {/ {/' src
+replace_rs 's/&mut (dyn \([^()]*\) + Send)/\&mut dyn \1/' src
 replace_rs 's/Send + Sync + //' src
 replace_rs 's/ + Sync//' src
 replace_rs 's/ + Send//' src
@@ -67,6 +71,7 @@ replace_rs '/S::Item: Send,/d' src
 replace_rs 's/Box::pin/Box::new/' src
 replace_rs 's/impl Stream</impl Iterator</' src
 replace_rs 's/S: Stream</S: Iterator</' src
+replace_rs 's/futures_util::stream::once(/std::iter::once(/' src
 replace_rs 's/use futures_util::lock::Mutex;/use std::sync::Mutex;/' src
 replace_rs 's/use futures_util::lock::MutexGuard;/use std::sync::MutexGuard;/' src
 replace_rs 's/, pin::Pin//' src
@@ -77,6 +82,10 @@ replace_rs 's/, task::Poll//' src
 replace_rs '/use std::{pin::Pin};/d' src
 replace_rs '/use std::{task::Poll};/d' src
 replace_rs '/use std::{future::Future};/d' src
+replace_rs '/^use std::future::Future;$/d' src
+replace_rs '/^use std::pin::Pin;$/d' src
+replace_rs '/^    future::Future,$/d' src
+replace_rs '/^    pin::Pin,$/d' src
 replace_rs 's/, future::Future//' src
 # delete a `#[cfg(feature = "stream")]` together with the `use futures_util` line it gates,
 # so the attribute does not attach to the following item once the import is removed
@@ -88,8 +97,13 @@ replace_rs '/\/\/\/ use futures_util/d' src
 replace_rs '/use futures_util::future::BoxFuture;/d' src
 replace_rs '/use async_stream::/d' src
 replace_rs '/use futures_util::StreamExt/d' tests
+replace_rs '/use futures_util::TryStreamExt;/d' tests
+replace_rs 's/\.try_collect::<Vec<_>>()/.collect::<Result<Vec<_>, DbErr>>()/' tests
+replace_rs 's/\.try_collect()/.collect()/' tests
+replace_rs '/^#\[cfg(feature = "proxy-server")\]$/{N;N;/proxy_server/d;}' src
 replace_rs 's/self.conn.try_lock()/self.conn.try_lock().ok()/' src
 
 sed_in_place 's/self.conn.try_lock().ok()/self.conn.try_lock()/' ./src/driver/rusqlite.rs
+sed_in_place 's/self.conn.try_lock().ok()/self.conn.try_lock()/' ./src/driver/duckdb.rs
 
 cargo +nightly fmt
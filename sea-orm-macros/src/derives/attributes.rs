@@ -16,6 +16,11 @@ pub mod derive_attr {
         pub schema_name: Option<syn::LitStr>,
         pub table_name: Option<syn::LitStr>,
         pub comment: Option<syn::LitStr>,
+        pub soft_delete: Option<syn::LitStr>,
+        pub version: Option<syn::LitStr>,
+        pub created_at: Option<syn::LitStr>,
+        pub updated_at: Option<syn::LitStr>,
+        pub default_scope: Option<syn::LitStr>,
         pub table_iden: Option<()>,
         pub rename_all: Option<syn::LitStr>,
     }
@@ -1,9 +1,10 @@
 use std::iter::FromIterator;
 
+use heck::ToUpperCamelCase;
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 
-use super::{attributes::derive_attr, impl_iden_for_unit_struct};
+use super::{attributes::derive_attr, impl_iden_for_unit_struct, util::escape_rust_keyword};
 
 struct DeriveEntity {
     column_ident: syn::Ident,
@@ -16,6 +17,11 @@ struct DeriveEntity {
     relation_ident: syn::Ident,
     schema_name: Option<syn::LitStr>,
     table_name: Option<syn::LitStr>,
+    soft_delete: Option<syn::Ident>,
+    version: Option<syn::Ident>,
+    created_at: Option<syn::Ident>,
+    updated_at: Option<syn::Ident>,
+    default_scope: Option<syn::Path>,
 }
 
 impl DeriveEntity {
@@ -41,6 +47,14 @@ impl DeriveEntity {
 
         let table_name = sea_attr.table_name;
         let schema_name = sea_attr.schema_name;
+        let soft_delete = sea_attr.soft_delete.map(column_variant);
+        let version = sea_attr.version.map(column_variant);
+        let created_at = sea_attr.created_at.map(column_variant);
+        let updated_at = sea_attr.updated_at.map(column_variant);
+        let default_scope = sea_attr
+            .default_scope
+            .map(|default_scope| default_scope.parse::<syn::Path>())
+            .transpose()?;
 
         Ok(DeriveEntity {
             column_ident,
@@ -53,6 +67,11 @@ impl DeriveEntity {
             relation_ident,
             schema_name,
             table_name,
+            soft_delete,
+            version,
+            created_at,
+            updated_at,
+            default_scope,
         })
     }
 
@@ -108,9 +127,36 @@ impl DeriveEntity {
             column_ident,
             primary_key_ident,
             relation_ident,
+            soft_delete,
+            version,
+            created_at,
+            updated_at,
+            default_scope,
             ..
         } = self;
 
+        let column_fn = |name: &str, variant: &Option<syn::Ident>| {
+            let name = format_ident!("{name}_column");
+            variant.as_ref().map(|variant| {
+                quote!(
+                    fn #name() -> Option<Self::Column> {
+                        Some(#column_ident::#variant)
+                    }
+                )
+            })
+        };
+        let soft_delete_column = column_fn("soft_delete", soft_delete);
+        let version_column = column_fn("version", version);
+        let created_at_column = column_fn("created_at", created_at);
+        let updated_at_column = column_fn("updated_at", updated_at);
+        let default_scope = default_scope.as_ref().map(|default_scope| {
+            quote!(
+                fn default_scope() -> Option<sea_orm::Condition> {
+                    Some(#default_scope())
+                }
+            )
+        });
+
         quote!(
             #[automatically_derived]
             impl sea_orm::entity::EntityTrait for #ident {
@@ -127,6 +173,16 @@ impl DeriveEntity {
                 type PrimaryKey = #primary_key_ident;
 
                 type Relation = #relation_ident;
+
+                #soft_delete_column
+
+                #version_column
+
+                #created_at_column
+
+                #updated_at_column
+
+                #default_scope
             }
         )
     }
@@ -176,6 +232,12 @@ impl DeriveEntity {
     }
 }
 
+/// The `Column` variant named by a `#[sea_orm(...)]` column argument
+fn column_variant(column: syn::LitStr) -> syn::Ident {
+    let variant = escape_rust_keyword(column.value().to_upper_camel_case());
+    syn::Ident::new(&variant, column.span())
+}
+
 pub fn expand_derive_entity(input: syn::DeriveInput) -> syn::Result<TokenStream> {
     Ok(DeriveEntity::new(input)?.expand())
 }
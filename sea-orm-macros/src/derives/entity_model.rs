@@ -66,6 +66,8 @@ pub fn expand_derive_entity_model(
     let mut comment = quote! {None};
     let mut schema_name = quote! { None };
     let mut table_iden = false;
+    let mut soft_delete = None;
+    let mut default_scope = None;
     let mut model_ex = false;
     let mut rename_all: Option<CaseStyle> = None;
     let mut serde_rename_all: Option<CaseStyle> = None;
@@ -114,6 +116,10 @@ pub fn expand_derive_entity_model(
                     schema_name = quote! { Some(#name) };
                 } else if meta.path.is_ident("table_iden") {
                     table_iden = true;
+                } else if meta.path.is_ident("soft_delete") {
+                    soft_delete = Some(meta.value()?.parse::<LitStr>()?);
+                } else if meta.path.is_ident("default_scope") {
+                    default_scope = Some(meta.value()?.parse::<LitStr>()?);
                 } else if meta.path.is_ident("model_ex") {
                     model_ex = true;
                 } else if meta.path.is_ident("rename_all") {
@@ -126,38 +132,6 @@ pub fn expand_derive_entity_model(
             })
         })?;
 
-    let entity_def = table_name
-        .as_ref()
-        .map(|table_name| {
-            let entity_extra_attr = if model_ex {
-                quote!(#[sea_orm(model_ex = ModelEx, active_model_ex = ActiveModelEx)])
-            } else {
-                quote!()
-            };
-            quote! {
-                #[doc = " Generated by sea-orm-macros"]
-                #[derive(Copy, Clone, Default, Debug, sea_orm::prelude::DeriveEntity)]
-                #entity_extra_attr
-                #vis struct Entity;
-
-                #[automatically_derived]
-                impl sea_orm::prelude::EntityName for Entity {
-                    fn schema_name(&self) -> Option<&str> {
-                        #schema_name
-                    }
-
-                    fn table_name(&self) -> &'static str {
-                        #table_name
-                    }
-
-                    fn comment(&self) -> Option<&str> {
-                        #comment
-                    }
-                }
-            }
-        })
-        .unwrap_or_default();
-
     // generate Column enum and it's ColumnTrait impl
     let mut columns_enum: Punctuated<_, Comma> = Punctuated::new();
     let mut columns_trait: Punctuated<_, Comma> = Punctuated::new();
@@ -167,6 +141,10 @@ pub fn expand_derive_entity_model(
     let mut primary_keys: Punctuated<_, Comma> = Punctuated::new();
     let mut primary_key_types: Punctuated<_, Comma> = Punctuated::new();
     let mut auto_increment: Option<bool> = None;
+    let mut version: Option<LitStr> = None;
+    let mut created_at: Option<LitStr> = None;
+    let mut updated_at: Option<LitStr> = None;
+    let mut columns_current_timestamp: Punctuated<_, Comma> = Punctuated::new();
     #[cfg(feature = "with-json")]
     let mut columns_json_keys: Punctuated<_, Comma> = Punctuated::new();
 
@@ -207,6 +185,9 @@ pub fn expand_derive_entity_model(
                     let mut enum_name = None;
                     let mut is_primary_key = false;
                     let mut is_auto_increment = false;
+                    let mut is_version = false;
+                    let mut is_created_at = false;
+                    let mut is_updated_at = false;
                     let mut extra = None;
                     let mut seaography_ignore = false;
                     #[cfg(feature = "with-json")]
@@ -223,7 +204,7 @@ pub fn expand_derive_entity_model(
                         None
                     };
 
-                    // search for #[sea_orm(primary_key, auto_increment = false, column_type = "String(StringLen::N(255))", default_value = "new user", default_expr = "gen_random_uuid()", column_name = "name", enum_name = "Name", nullable, indexed, unique)]
+                    // search for #[sea_orm(primary_key, auto_increment = false, column_type = "String(StringLen::N(255))", default_value = "new user", default_expr = "gen_random_uuid()", column_name = "name", enum_name = "Name", nullable, indexed, unique, version, created_at, updated_at)]
                     for attr in field.attrs.iter() {
                         if attr.path().is_ident("sea_orm") {
                             // single param
@@ -303,6 +284,12 @@ pub fn expand_derive_entity_model(
                                 } else if meta.path.is_ident("primary_key") {
                                     is_primary_key = true;
                                     primary_key_types.push(field.ty.clone());
+                                } else if meta.path.is_ident("version") {
+                                    is_version = true;
+                                } else if meta.path.is_ident("created_at") {
+                                    is_created_at = true;
+                                } else if meta.path.is_ident("updated_at") {
+                                    is_updated_at = true;
                                 } else if meta.path.is_ident("nullable") {
                                     nullable = true;
                                 } else if meta.path.is_ident("indexed") {
@@ -387,8 +374,32 @@ pub fn expand_derive_entity_model(
                         field_name = enum_name;
                     }
 
+                    for (is_set, column, name) in [
+                        (is_version, &mut version, "version"),
+                        (is_created_at, &mut created_at, "created_at"),
+                        (is_updated_at, &mut updated_at, "updated_at"),
+                    ] {
+                        if !is_set {
+                            continue;
+                        }
+                        if column.is_some() {
+                            return Err(syn::Error::new_spanned(
+                                ident,
+                                format!("an entity can only have one {name} column"),
+                            ));
+                        }
+                        *column = Some(LitStr::new(&field_name.to_string(), ident.span()));
+                    }
+
                     field_name = Ident::new(&escape_rust_keyword(field_name), ident.span());
 
+                    if is_created_at || is_updated_at {
+                        let field_type = &field.ty;
+                        columns_current_timestamp.push(quote! {
+                            Self::#field_name => Some(<#field_type as sea_orm::value::CurrentTimestamp>::now().into())
+                        });
+                    }
+
                     let variant_attrs = match &column_name {
                         Some(column_name) => quote! {
                             #[sea_orm(column_name = #column_name)]
@@ -558,6 +569,57 @@ pub fn expand_derive_entity_model(
         }
     };
 
+    let entity_def = table_name
+        .as_ref()
+        .map(|table_name| {
+            let mut entity_extra_args = Vec::new();
+            if model_ex {
+                entity_extra_args.push(quote!(model_ex = ModelEx, active_model_ex = ActiveModelEx));
+            }
+            if let Some(soft_delete) = &soft_delete {
+                entity_extra_args.push(quote!(soft_delete = #soft_delete));
+            }
+            if let Some(version) = &version {
+                entity_extra_args.push(quote!(version = #version));
+            }
+            if let Some(created_at) = &created_at {
+                entity_extra_args.push(quote!(created_at = #created_at));
+            }
+            if let Some(updated_at) = &updated_at {
+                entity_extra_args.push(quote!(updated_at = #updated_at));
+            }
+            if let Some(default_scope) = &default_scope {
+                entity_extra_args.push(quote!(default_scope = #default_scope));
+            }
+            let entity_extra_attr = if entity_extra_args.is_empty() {
+                quote!()
+            } else {
+                quote!(#[sea_orm(#(#entity_extra_args),*)])
+            };
+            quote! {
+                #[doc = " Generated by sea-orm-macros"]
+                #[derive(Copy, Clone, Default, Debug, sea_orm::prelude::DeriveEntity)]
+                #entity_extra_attr
+                #vis struct Entity;
+
+                #[automatically_derived]
+                impl sea_orm::prelude::EntityName for Entity {
+                    fn schema_name(&self) -> Option<&str> {
+                        #schema_name
+                    }
+
+                    fn table_name(&self) -> &'static str {
+                        #table_name
+                    }
+
+                    fn comment(&self) -> Option<&str> {
+                        #comment
+                    }
+                }
+            }
+        })
+        .unwrap_or_default();
+
     let impl_model_ex = if model_ex {
         quote!()
     } else {
@@ -571,6 +633,19 @@ pub fn expand_derive_entity_model(
         }
     };
 
+    let current_timestamp = if columns_current_timestamp.is_empty() {
+        quote!()
+    } else {
+        quote! {
+            fn current_timestamp(&self) -> Option<sea_orm::Value> {
+                match self {
+                    #columns_current_timestamp,
+                    _ => None,
+                }
+            }
+        }
+    };
+
     let with_json_impls = {
         #[cfg(feature = "with-json")]
         quote! {
@@ -624,6 +699,8 @@ pub fn expand_derive_entity_model(
                 }
             }
 
+            #current_timestamp
+
             #with_json_impls
         }
 
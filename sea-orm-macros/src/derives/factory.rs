@@ -0,0 +1,265 @@
+use super::attributes::compound_attr;
+use super::util::{
+    CardinalityKind, CompoundKind, CompoundType, RelationColumns, async_token, await_token,
+    consume_meta, escape_rust_keyword, is_self_entity, trim_starting_raw_identifier,
+};
+use heck::ToUpperCamelCase;
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{Attribute, Data, LitStr, Type, Visibility};
+
+struct ScalarField {
+    column: Ident,
+    optional: bool,
+}
+
+/// A `belongs_to` relation whose foreign key columns are all required
+struct ParentField<'a> {
+    ident: &'a Ident,
+    compound_type: CompoundType,
+    from: RelationColumns,
+}
+
+impl ScalarField {
+    fn from_field(field: &syn::Field, ident: &Ident) -> syn::Result<Self> {
+        let column = trim_starting_raw_identifier(ident).to_upper_camel_case();
+        let mut column = Ident::new(&escape_rust_keyword(column), ident.span());
+
+        for attr in &field.attrs {
+            if !attr.path().is_ident("sea_orm") {
+                continue;
+            }
+
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("enum_name") {
+                    column = syn::parse_str(&meta.value()?.parse::<LitStr>()?.value())?;
+                } else {
+                    consume_meta(meta);
+                }
+                Ok(())
+            })?;
+        }
+
+        let optional = matches!(
+            &field.ty,
+            Type::Path(type_path)
+                if type_path.path.segments.last().is_some_and(|segment| segment.ident == "Option")
+        );
+
+        Ok(Self { column, optional })
+    }
+}
+
+impl ParentField<'_> {
+    fn expand(&self) -> TokenStream {
+        let ident = self.ident;
+        let entity = &self.compound_type.entity;
+        let columns = &self.from.columns;
+        let parent = quote! {
+            Box::new(<#entity as sea_orm::EntityFactory>::build_ex()?)
+        };
+        let value = match self.compound_type.kind {
+            CompoundKind::BelongsTo(CardinalityKind::Required) => {
+                quote!(sea_orm::ActiveBelongsTo::Set(#parent))
+            }
+            CompoundKind::BelongsTo(CardinalityKind::Optional) => {
+                quote!(sea_orm::ActiveBelongsTo::Set(Some(#parent)))
+            }
+            CompoundKind::HasOne => quote!(sea_orm::ActiveHasOne::Set(Some(#parent))),
+            CompoundKind::HasMany => unreachable!("belongs_to is never paired with HasMany"),
+        };
+
+        quote! {
+            if !(#(self.config.is_set(Column::#columns))||*) {
+                model.#ident = #value;
+            }
+        }
+    }
+}
+
+fn parent_fields<'a>(fields: &'a syn::FieldsNamed) -> syn::Result<Vec<ParentField<'a>>> {
+    let mut scalars = Vec::new();
+    let mut relations = Vec::new();
+
+    for field in &fields.named {
+        let Some(ident) = &field.ident else {
+            return Err(syn::Error::new_spanned(field, "expected named field"));
+        };
+        if let Type::Path(type_path) = &field.ty
+            && let Some(compound_type) = CompoundType::from_type(type_path)?
+        {
+            let attrs =
+                compound_attr::SeaOrm::try_from_attributes(&field.attrs)?.unwrap_or_default();
+            if attrs.belongs_to.is_some()
+                && attrs.self_ref.is_none()
+                && !is_self_entity(&compound_type.entity)
+                && let Some(from) = attrs.from
+            {
+                relations.push(ParentField {
+                    ident,
+                    compound_type,
+                    from: RelationColumns::from_lit(from)?,
+                });
+            }
+        } else {
+            scalars.push(ScalarField::from_field(field, ident)?);
+        }
+    }
+
+    relations
+        .into_iter()
+        .filter_map(|relation| {
+            let mut required = true;
+            for column in &relation.from.columns {
+                match scalars.iter().find(|scalar| scalar.column == *column) {
+                    Some(scalar) => required &= !scalar.optional,
+                    None => {
+                        return Some(Err(syn::Error::new(
+                            relation.from.span,
+                            format!("unknown `from` column `{column}`"),
+                        )));
+                    }
+                }
+            }
+            required.then_some(Ok(relation))
+        })
+        .collect()
+}
+
+/// Method to derive a factory generating default models for an entity
+pub fn expand_derive_factory(
+    vis: &Visibility,
+    ident: &Ident,
+    data: &Data,
+    attrs: &[Attribute],
+) -> syn::Result<TokenStream> {
+    let mut compact = false;
+
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("sea_orm"))
+        .try_for_each(|attr| {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("compact_model") {
+                    compact = true;
+                } else {
+                    consume_meta(meta);
+                }
+                Ok(())
+            })
+        })?;
+
+    if compact {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "DeriveFactory requires an entity defined with #[sea_orm::model]",
+        ));
+    }
+
+    let fields = if let Data::Struct(r#struct) = data
+        && let syn::Fields::Named(fields) = &r#struct.fields
+    {
+        fields
+    } else {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "You can only derive DeriveFactory on structs",
+        ));
+    };
+
+    let async_ = async_token();
+    let await_ = await_token();
+    let build_parents: Vec<TokenStream> = parent_fields(fields)?
+        .iter()
+        .map(ParentField::expand)
+        .collect();
+    let mut_ = if build_parents.is_empty() {
+        quote!()
+    } else {
+        quote!(mut)
+    };
+
+    Ok(quote! {
+        static FACTORY_SEQUENCE: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+
+        #[doc = " Generated by sea-orm-macros"]
+        #[derive(Clone, Debug)]
+        #vis struct Factory {
+            config: sea_orm::FactoryConfig<Entity>,
+        }
+
+        impl Factory {
+            #[doc = " Generated by sea-orm-macros"]
+            pub fn new() -> Self {
+                Self {
+                    config: sea_orm::FactoryConfig::new(&FACTORY_SEQUENCE),
+                }
+            }
+
+            #[doc = " Generated by sea-orm-macros"]
+            pub fn set(mut self, c: Column, v: impl Into<sea_orm::Value>) -> Self {
+                self.config.set(c, v);
+                self
+            }
+
+            #[doc = " Generated by sea-orm-macros"]
+            pub fn sequence(mut self, c: Column, template: &str) -> Self {
+                self.config.sequence(c, template);
+                self
+            }
+
+            #[doc = " Generated by sea-orm-macros"]
+            pub fn build(&self) -> Result<ActiveModelEx, sea_orm::DbErr> {
+                let #mut_ model: ActiveModelEx = self.config.build(self.config.next_sequence())?;
+                #(#build_parents)*
+                Ok(model)
+            }
+
+            #[doc = " Generated by sea-orm-macros"]
+            pub #async_ fn create<'a, C>(&self, db: &'a C) -> Result<ModelEx, sea_orm::DbErr>
+            where
+                C: sea_orm::TransactionTrait,
+            {
+                self.build()?.insert(db)#await_
+            }
+
+            #[doc = " Generated by sea-orm-macros"]
+            pub #async_ fn create_many<'a, C>(&self, count: usize, db: &'a C) -> Result<Vec<ModelEx>, sea_orm::DbErr>
+            where
+                C: sea_orm::TransactionTrait,
+            {
+                use sea_orm::TransactionSession;
+
+                let txn = db.begin()#await_?;
+                let mut models = Vec::with_capacity(count);
+                for _ in 0..count {
+                    models.push(self.create(&txn)#await_?);
+                }
+                txn.commit()#await_?;
+
+                Ok(models)
+            }
+        }
+
+        #[automatically_derived]
+        impl std::default::Default for Factory {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        #[automatically_derived]
+        impl sea_orm::EntityFactory for Entity {
+            fn build_ex() -> Result<ActiveModelEx, sea_orm::DbErr> {
+                Factory::new().build()
+            }
+        }
+
+        impl Entity {
+            #[doc = " Generated by sea-orm-macros"]
+            pub fn factory() -> Factory {
+                Factory::new()
+            }
+        }
+    })
+}
@@ -11,6 +11,7 @@ mod derive_iden;
 mod entity;
 mod entity_loader;
 mod entity_model;
+mod factory;
 mod from_query_result;
 mod into_active_model;
 mod migration;
@@ -36,6 +37,7 @@ pub use column::*;
 pub use derive_iden::*;
 pub use entity::*;
 pub use entity_model::*;
+pub use factory::*;
 pub use from_query_result::*;
 pub use into_active_model::*;
 pub use migration::*;
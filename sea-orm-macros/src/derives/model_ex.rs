@@ -85,6 +85,30 @@ pub fn expand_sea_orm_model(input: ItemStruct, compact: bool) -> syn::Result<Tok
 
     let model_ex = format_ident!("{model}Ex");
 
+    for attr in &mut model_attrs {
+        if !attr.path().is_ident("derive") {
+            continue;
+        }
+
+        let Meta::List(list) = &mut attr.meta else {
+            continue;
+        };
+
+        let mut new_list: Punctuated<_, Comma> = Punctuated::new();
+
+        list.parse_nested_meta(|meta| {
+            if meta.path.is_ident("DeriveFactory") {
+                // the factory needs the relational fields, so only ModelEx derives it
+            } else {
+                new_list.push(meta.path);
+            }
+
+            Ok(())
+        })?;
+
+        *attr = parse_quote!(#[derive( #new_list )]);
+    }
+
     for attr in &mut model_ex_attrs {
         if !attr.path().is_ident("derive") {
             continue;
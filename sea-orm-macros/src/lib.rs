@@ -219,6 +219,68 @@ pub fn derive_active_model_ex(input: TokenStream) -> TokenStream {
         .into()
 }
 
+/// Derive a `Factory` generating models filled with default values, for use in tests.
+///
+/// Columns are filled according to their `ColumnType` (see `FactoryConfig`),
+/// `sequence` takes a template in which `{n}` is replaced by the sequence number of
+/// the model, which increases with every model of the entity built in the process,
+/// and `set` overrides a column with a fixed value.
+/// Parents of required `belongs_to` relations are built by their own factories, so
+/// the parent entity must also derive `DeriveFactory`, unless the foreign key is
+/// overridden. `create` and `create_many` save the whole graph through `ActiveModelEx`.
+///
+/// ## Usage
+///
+/// ```rust,ignore
+/// use sea_orm::entity::prelude::*;
+///
+/// #[sea_orm::model]
+/// #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, DeriveFactory)]
+/// #[sea_orm(table_name = "post")]
+/// pub struct Model {
+///     #[sea_orm(primary_key)]
+///     pub id: i32,
+///     pub user_id: i32,
+///     pub title: String,
+///     #[sea_orm(belongs_to, from = "user_id", to = "id")]
+///     pub author: BelongsTo<super::user::Entity>,
+/// }
+///
+/// // inserts a user, then a post titled "title_{n}" written by that user
+/// let post = post::Entity::factory().create(db).await?;
+///
+/// // inserts three users, with emails such as user2@x.com, user3@x.com and user4@x.com
+/// let users = user::Entity::factory()
+///     .sequence(user::Column::Email, "user{n}@x.com")
+///     .create_many(3, db)
+///     .await?;
+///
+/// // inserts a post written by an existing user
+/// let post = post::Entity::factory()
+///     .set(post::Column::UserId, users[0].id)
+///     .create(db)
+///     .await?;
+/// ```
+#[cfg(feature = "derive")]
+#[proc_macro_derive(DeriveFactory, attributes(sea_orm))]
+pub fn derive_factory(input: TokenStream) -> TokenStream {
+    let DeriveInput {
+        vis,
+        ident,
+        data,
+        attrs,
+        ..
+    } = parse_macro_input!(input as DeriveInput);
+
+    if ident != "ModelEx" {
+        panic!("Struct name must be ModelEx");
+    }
+
+    derives::expand_derive_factory(&vis, &ident, &data, &attrs)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
 /// The DerivePrimaryKey derive macro will implement [PrimaryKeyToColumn]
 /// for PrimaryKey which defines tedious mappings between primary keys and columns.
 /// The [EnumIter] is also derived, allowing iteration over all enum variants.
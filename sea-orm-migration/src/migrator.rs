@@ -270,16 +270,25 @@ where
     M: MigratorTrait + ?Sized,
 {
     let db = manager.get_connection();
+    let lock = lock_migration_table(db, &M::migration_table_name()).await?;
 
-    M::install(db).await?;
+    let res = async {
+        M::install(db).await?;
 
-    exec_up_with(
-        manager,
-        steps,
-        M::get_pending_migrations(db).await?,
-        M::migration_table_name(),
-    )
-    .await
+        exec_up_with(
+            manager,
+            steps,
+            M::get_pending_migrations(db).await?,
+            M::migration_table_name(),
+        )
+        .await
+    }
+    .await;
+
+    match lock {
+        Some(lock) => res.and(lock.release(db).await),
+        None => res,
+    }
 }
 
 async fn exec_down<M>(manager: &SchemaManager<'_>, steps: Option<u32>) -> Result<(), DbErr>
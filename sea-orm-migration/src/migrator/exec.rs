@@ -4,15 +4,87 @@ use std::time::SystemTime;
 use tracing::info;
 
 use super::{Migration, MigrationStatus, queries::*};
-use crate::{SchemaManager, seaql_migrations};
+use crate::{SchemaManager, SchemaManagerConnection, seaql_migrations};
 use sea_orm::sea_query::{
-    Alias, Expr, ExprTrait, ForeignKey, IntoIden, Order, Query, Table, extension::postgres::Type,
+    Alias, ColumnDef, Expr, ExprTrait, ForeignKey, IntoIden, Order, Query, Table,
+    extension::postgres::Type,
 };
 use sea_orm::{
-    ActiveValue, ConnectionTrait, DbBackend, DbErr, DynIden, EntityTrait, FromQueryResult,
-    Iterable, QueryFilter, Schema, Statement, TransactionSession, TransactionTrait,
+    ActiveValue, AdvisoryLock, ConnectionTrait, DatabaseExecutor, DbBackend, DbErr, DynIden,
+    EntityTrait, FromQueryResult, Iterable, QueryFilter, Schema, Statement, TransactionSession,
+    TransactionTrait,
 };
 
+/// A lock held while migrating, so that two instances migrating the same database at once
+/// cannot apply a migration twice
+pub enum MigrationLock {
+    /// An advisory lock named after the migration table
+    Advisory(AdvisoryLock),
+    /// A table named after the migration table, which only one instance can create. SQLite
+    /// takes this one, as its advisory lock would be the write lock of the whole database.
+    Table(DynIden),
+}
+
+impl MigrationLock {
+    /// Release the lock, whether migrating succeeded or not
+    pub async fn release(self, db: &SchemaManagerConnection<'_>) -> Result<(), DbErr> {
+        match self {
+            Self::Advisory(lock) => lock.commit().await,
+            Self::Table(table) => {
+                db.execute(&Table::drop().table(table).to_owned()).await?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Take the migration lock. None is taken inside a transaction or on a mock connection. On
+/// SQLite, an instance fails instead of waiting when another holds the lock, since a lock
+/// left behind by an interrupted migration is only released by dropping its table.
+pub async fn lock_migration_table(
+    db: &SchemaManagerConnection<'_>,
+    migration_table_name: &DynIden,
+) -> Result<Option<MigrationLock>, DbErr> {
+    let conn = match db {
+        DatabaseExecutor::Connection(conn) if !conn.is_mock_connection() => conn,
+        _ => return Ok(None),
+    };
+    if conn.get_database_backend() != DbBackend::Sqlite {
+        return conn
+            .advisory_lock(&migration_table_name.to_string())
+            .await
+            .map(|lock| Some(MigrationLock::Advisory(lock)));
+    }
+
+    let lock_table = Alias::new(format!("{migration_table_name}_lock")).into_iden();
+    let create = Table::create()
+        .table(lock_table.clone())
+        .col(ColumnDef::new("locked_at").big_integer().not_null())
+        .to_owned();
+    if let Err(err) = conn.execute(&create).await {
+        if crate::manager::has_table(db, lock_table.to_string()).await? {
+            return Err(DbErr::Migration(format!(
+                "Another migration holds the lock: table `{lock_table}` exists. If none is \
+                 running, one was interrupted; drop the table to release its lock"
+            )));
+        }
+        return Err(err);
+    }
+    let insert = Query::insert()
+        .into_table(lock_table.clone())
+        .columns(["locked_at"])
+        .values_panic([unix_timestamp().into()])
+        .to_owned();
+    let lock = MigrationLock::Table(lock_table);
+    match conn.execute(&insert).await {
+        Ok(_) => Ok(Some(lock)),
+        Err(err) => {
+            lock.release(db).await?;
+            Err(err)
+        }
+    }
+}
+
 pub async fn get_migration_models<C>(
     db: &C,
     migration_table_name: DynIden,
@@ -209,16 +281,9 @@ async fn insert_migration_record<C: ConnectionTrait>(
     name: &str,
     migration_table_name: DynIden,
 ) -> Result<(), DbErr> {
-    #[cfg(not(feature = "with-time"))]
-    let applied_at = SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .expect("SystemTime before UNIX EPOCH!")
-        .as_secs() as i64;
-    #[cfg(feature = "with-time")]
-    let applied_at = sea_orm::prelude::TimeDateTimeWithTimeZone::now_utc().unix_timestamp();
     seaql_migrations::Entity::insert(seaql_migrations::ActiveModel {
         version: ActiveValue::Set(name.to_owned()),
-        applied_at: ActiveValue::Set(applied_at),
+        applied_at: ActiveValue::Set(unix_timestamp()),
     })
     .table_name(migration_table_name)
     .exec(db)
@@ -226,6 +291,17 @@ async fn insert_migration_record<C: ConnectionTrait>(
     Ok(())
 }
 
+fn unix_timestamp() -> i64 {
+    #[cfg(not(feature = "with-time"))]
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("SystemTime before UNIX EPOCH!")
+        .as_secs() as i64;
+    #[cfg(feature = "with-time")]
+    let now = sea_orm::prelude::TimeDateTimeWithTimeZone::now_utc().unix_timestamp();
+    now
+}
+
 async fn delete_migration_record<C: ConnectionTrait>(
     db: &C,
     name: &str,
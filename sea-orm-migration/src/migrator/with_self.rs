@@ -358,16 +358,25 @@ where
     M: MigratorTraitSelf,
 {
     let db = manager.get_connection();
+    let lock = lock_migration_table(db, &migrator.migration_table_name()).await?;
 
-    migrator.install(db).await?;
+    let res = async {
+        migrator.install(db).await?;
 
-    exec_up_with(
-        manager,
-        steps,
-        migrator.get_pending_migrations(db).await?,
-        migrator.migration_table_name(),
-    )
-    .await
+        exec_up_with(
+            manager,
+            steps,
+            migrator.get_pending_migrations(db).await?,
+            migrator.migration_table_name(),
+        )
+        .await
+    }
+    .await;
+
+    match lock {
+        Some(lock) => res.and(lock.release(db).await),
+        None => res,
+    }
 }
 
 async fn exec_down<M>(
@@ -213,6 +213,23 @@ where
     println!("\nMigrator::up");
     migrator.up(db, Some(1)).await?;
 
+    // on SQLite the lock is a table, dropped once migrating is done; one left behind makes
+    // migrating fail rather than wait
+    let lock_table = format!("{migration_table_name}_lock");
+    assert!(!manager.has_table(&lock_table).await?);
+    if db.get_database_backend() == DbBackend::Sqlite {
+        db.execute_unprepared(&format!(
+            "CREATE TABLE \"{lock_table}\" (\"locked_at\" bigint)"
+        ))
+        .await?;
+        assert!(matches!(
+            migrator.up(db, None).await,
+            Err(DbErr::Migration(_))
+        ));
+        db.execute_unprepared(&format!("DROP TABLE \"{lock_table}\""))
+            .await?;
+    }
+
     println!("\nMigrator::get_pending_migrations");
     let migrations = migrator.get_pending_migrations(db).await?;
     assert_eq!(migrations.len(), 5);
@@ -0,0 +1,862 @@
+use super::{replica::is_replica_safe, transaction::run_async_transaction_callback};
+use crate::{
+    AccessMode, ConnectionTrait, DatabaseConnection, DatabaseTransaction, DbBackend, DbErr,
+    ExecResult, IsolationLevel, QueryResult, Statement, StatementBuilder, TransactionError,
+    TransactionOptions, TransactionSession, TransactionTrait, Value,
+};
+use sea_query::audit::AccessType;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tracing::instrument;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+/// Wrapper of [`DatabaseConnection`] that caches the rows returned by
+/// [`query_one`](ConnectionTrait::query_one) and [`query_all`](ConnectionTrait::query_all).
+///
+/// Results are keyed by the built [`Statement`] and kept for at most `ttl`; at most
+/// `max_entries` statements are cached at a time, evicting the oldest first. The tables
+/// each statement touches are found with [`StatementBuilder::audit`]: a write through this
+/// connection, or through a [`CachedTransaction`] once it commits, evicts every cached
+/// result that read from one of the tables it wrote to. Tables are matched by name,
+/// ignoring the schema.
+///
+/// Only plain `SELECT`s are cached. Raw [`Statement`]s are never served from the cache,
+/// and raw writes, schema statements and anything else that cannot be audited clear the
+/// whole cache. Writes made by other connections are not seen; rely on the TTL or call
+/// [`invalidate`](Self::invalidate).
+#[derive(Debug, Clone)]
+#[cfg_attr(docsrs, doc(cfg(feature = "query-cache")))]
+pub struct CachedConnection {
+    conn: DatabaseConnection,
+    cache: Arc<QueryCache>,
+}
+
+/// Wrapper of [`DatabaseTransaction`] started from a [`CachedConnection`].
+///
+/// Reads bypass the cache, as they may see the transaction's own uncommitted writes.
+/// The tables it writes to are invalidated once the outermost transaction commits.
+#[derive(Debug)]
+#[cfg_attr(docsrs, doc(cfg(feature = "query-cache")))]
+pub struct CachedTransaction {
+    conn: DatabaseTransaction,
+    cache: Arc<QueryCache>,
+    pending: Arc<Mutex<Invalidation>>,
+    outermost: bool,
+}
+
+struct QueryCache {
+    ttl: Duration,
+    max_entries: usize,
+    state: Mutex<CacheState>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<CacheKey, CacheEntry>,
+    /// Bumped on every invalidation, so that a read racing with a write does not
+    /// store a result that is already stale.
+    generation: u64,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    sql: String,
+    values: Vec<Value>,
+    one: bool,
+}
+
+struct CacheEntry {
+    rows: Vec<QueryResult>,
+    tables: HashSet<String>,
+    inserted_at: Instant,
+}
+
+/// Tables written by a transaction, to be invalidated on commit.
+#[derive(Debug, Default)]
+struct Invalidation {
+    all: bool,
+    tables: HashSet<String>,
+}
+
+/// The tables a statement reads or writes, according to its audit.
+enum Access {
+    Read(HashSet<String>),
+    Write(HashSet<String>),
+    Unknown,
+}
+
+impl Access {
+    fn of<S: StatementBuilder>(stmt: &S) -> Self {
+        let Ok(audit) = stmt.audit() else {
+            return Self::Unknown;
+        };
+        let mut reads = HashSet::new();
+        let mut writes = HashSet::new();
+        for request in audit.requests {
+            let table = request.schema_table.1.to_string();
+            match request.access_type {
+                AccessType::Select => reads.insert(table),
+                _ => writes.insert(table),
+            };
+        }
+        if writes.is_empty() {
+            Self::Read(reads)
+        } else {
+            Self::Write(writes)
+        }
+    }
+
+    fn of_raw(stmt: &Statement) -> Self {
+        if is_replica_safe(&stmt.sql) {
+            Self::Read(HashSet::new())
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+impl CacheKey {
+    fn new(stmt: &Statement, one: bool) -> Self {
+        Self {
+            sql: stmt.sql.clone(),
+            values: stmt
+                .values
+                .as_ref()
+                .map(|values| values.0.clone())
+                .unwrap_or_default(),
+            one,
+        }
+    }
+}
+
+impl QueryCache {
+    fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            ttl,
+            max_entries,
+            state: Mutex::default(),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        // The state is left consistent even if a holder panicked.
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn get(&self, key: &CacheKey) -> Option<Vec<QueryResult>> {
+        let mut state = self.state();
+        match state.entries.get(key) {
+            Some(entry) if entry.inserted_at.elapsed() < self.ttl => Some(entry.rows.clone()),
+            Some(_) => {
+                state.entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn generation(&self) -> u64 {
+        self.state().generation
+    }
+
+    fn insert(
+        &self,
+        key: CacheKey,
+        rows: Vec<QueryResult>,
+        tables: HashSet<String>,
+        generation: u64,
+    ) {
+        let mut state = self.state();
+        if state.generation != generation || self.max_entries == 0 {
+            return;
+        }
+        if state.entries.len() >= self.max_entries && !state.entries.contains_key(&key) {
+            let ttl = self.ttl;
+            state
+                .entries
+                .retain(|_, entry| entry.inserted_at.elapsed() < ttl);
+            if state.entries.len() >= self.max_entries {
+                let oldest = state
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.inserted_at)
+                    .map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    state.entries.remove(&oldest);
+                }
+            }
+        }
+        state.entries.insert(
+            key,
+            CacheEntry {
+                rows,
+                tables,
+                inserted_at: Instant::now(),
+            },
+        );
+    }
+
+    fn invalidate(&self, tables: &HashSet<String>) {
+        let mut state = self.state();
+        state.generation += 1;
+        state
+            .entries
+            .retain(|_, entry| entry.tables.is_disjoint(tables));
+    }
+
+    fn clear(&self) {
+        let mut state = self.state();
+        state.generation += 1;
+        state.entries.clear();
+    }
+
+    fn written(&self, access: &Access) {
+        match access {
+            Access::Read(_) => (),
+            Access::Write(tables) => self.invalidate(tables),
+            Access::Unknown => self.clear(),
+        }
+    }
+}
+
+impl std::fmt::Debug for QueryCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueryCache")
+            .field("ttl", &self.ttl)
+            .field("max_entries", &self.max_entries)
+            .field("len", &self.state().entries.len())
+            .finish()
+    }
+}
+
+impl Invalidation {
+    fn record(&mut self, access: &Access) {
+        match access {
+            Access::Read(_) => (),
+            Access::Write(tables) => self.tables.extend(tables.iter().cloned()),
+            Access::Unknown => self.all = true,
+        }
+    }
+}
+
+impl ConnectionTrait for CachedConnection {
+    fn get_database_backend(&self) -> DbBackend {
+        self.conn.get_database_backend()
+    }
+
+    fn execute_raw(&self, stmt: Statement) -> Result<ExecResult, DbErr> {
+        let res = self.conn.execute_raw(stmt);
+        self.cache.clear();
+        res
+    }
+
+    fn execute<S: StatementBuilder>(&self, stmt: &S) -> Result<ExecResult, DbErr> {
+        let access = Access::of(stmt);
+        let res = self.conn.execute(stmt);
+        self.cache.written(&access);
+        res
+    }
+
+    fn execute_unprepared(&self, sql: &str) -> Result<ExecResult, DbErr> {
+        let res = self.conn.execute_unprepared(sql);
+        self.cache.clear();
+        res
+    }
+
+    fn query_one_raw(&self, stmt: Statement) -> Result<Option<QueryResult>, DbErr> {
+        let access = Access::of_raw(&stmt);
+        let res = self.conn.query_one_raw(stmt);
+        self.cache.written(&access);
+        res
+    }
+
+    fn query_one<S: StatementBuilder>(&self, stmt: &S) -> Result<Option<QueryResult>, DbErr> {
+        match Access::of(stmt) {
+            Access::Read(tables) => Ok(self.read(stmt, tables, true)?.pop()),
+            access => {
+                let res = self.conn.query_one(stmt);
+                self.cache.written(&access);
+                res
+            }
+        }
+    }
+
+    fn query_all_raw(&self, stmt: Statement) -> Result<Vec<QueryResult>, DbErr> {
+        let access = Access::of_raw(&stmt);
+        let res = self.conn.query_all_raw(stmt);
+        self.cache.written(&access);
+        res
+    }
+
+    fn query_all<S: StatementBuilder>(&self, stmt: &S) -> Result<Vec<QueryResult>, DbErr> {
+        match Access::of(stmt) {
+            Access::Read(tables) => self.read(stmt, tables, false),
+            access => {
+                let res = self.conn.query_all(stmt);
+                self.cache.written(&access);
+                res
+            }
+        }
+    }
+
+    fn execute_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<ExecResult, DbErr> {
+        let res = self.conn.execute_raw_with_timeout(stmt, timeout);
+        self.cache.clear();
+        res
+    }
+
+    fn execute_with_timeout<S: StatementBuilder>(
+        &self,
+        stmt: &S,
+        timeout: Duration,
+    ) -> Result<ExecResult, DbErr> {
+        let access = Access::of(stmt);
+        let res = self.conn.execute_with_timeout(stmt, timeout);
+        self.cache.written(&access);
+        res
+    }
+
+    fn query_one_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<Option<QueryResult>, DbErr> {
+        let access = Access::of_raw(&stmt);
+        let res = self.conn.query_one_raw_with_timeout(stmt, timeout);
+        self.cache.written(&access);
+        res
+    }
+
+    fn query_all_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<Vec<QueryResult>, DbErr> {
+        let access = Access::of_raw(&stmt);
+        let res = self.conn.query_all_raw_with_timeout(stmt, timeout);
+        self.cache.written(&access);
+        res
+    }
+
+    fn is_mock_connection(&self) -> bool {
+        self.conn.is_mock_connection()
+    }
+}
+
+impl ConnectionTrait for CachedTransaction {
+    fn get_database_backend(&self) -> DbBackend {
+        self.conn.get_database_backend()
+    }
+
+    fn execute_raw(&self, stmt: Statement) -> Result<ExecResult, DbErr> {
+        self.record(&Access::Unknown);
+        self.conn.execute_raw(stmt)
+    }
+
+    fn execute<S: StatementBuilder>(&self, stmt: &S) -> Result<ExecResult, DbErr> {
+        self.record(&Access::of(stmt));
+        self.conn.execute(stmt)
+    }
+
+    fn execute_unprepared(&self, sql: &str) -> Result<ExecResult, DbErr> {
+        self.record(&Access::Unknown);
+        self.conn.execute_unprepared(sql)
+    }
+
+    fn query_one_raw(&self, stmt: Statement) -> Result<Option<QueryResult>, DbErr> {
+        self.record(&Access::of_raw(&stmt));
+        self.conn.query_one_raw(stmt)
+    }
+
+    fn query_one<S: StatementBuilder>(&self, stmt: &S) -> Result<Option<QueryResult>, DbErr> {
+        self.record(&Access::of(stmt));
+        self.conn.query_one(stmt)
+    }
+
+    fn query_all_raw(&self, stmt: Statement) -> Result<Vec<QueryResult>, DbErr> {
+        self.record(&Access::of_raw(&stmt));
+        self.conn.query_all_raw(stmt)
+    }
+
+    fn query_all<S: StatementBuilder>(&self, stmt: &S) -> Result<Vec<QueryResult>, DbErr> {
+        self.record(&Access::of(stmt));
+        self.conn.query_all(stmt)
+    }
+
+    fn execute_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<ExecResult, DbErr> {
+        self.record(&Access::Unknown);
+        self.conn.execute_raw_with_timeout(stmt, timeout)
+    }
+
+    fn execute_with_timeout<S: StatementBuilder>(
+        &self,
+        stmt: &S,
+        timeout: Duration,
+    ) -> Result<ExecResult, DbErr> {
+        self.record(&Access::of(stmt));
+        self.conn.execute_with_timeout(stmt, timeout)
+    }
+
+    fn query_one_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<Option<QueryResult>, DbErr> {
+        self.record(&Access::of_raw(&stmt));
+        self.conn.query_one_raw_with_timeout(stmt, timeout)
+    }
+
+    fn query_all_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<Vec<QueryResult>, DbErr> {
+        self.record(&Access::of_raw(&stmt));
+        self.conn.query_all_raw_with_timeout(stmt, timeout)
+    }
+
+    fn is_mock_connection(&self) -> bool {
+        self.conn.is_mock_connection()
+    }
+}
+
+impl CachedConnection {
+    /// Wrap `conn`, caching each query result for at most `ttl` and keeping at most
+    /// `max_entries` results at a time.
+    pub fn new(conn: DatabaseConnection, ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            conn,
+            cache: Arc::new(QueryCache::new(ttl, max_entries)),
+        }
+    }
+
+    /// Get the wrapped connection. Writes run on it directly do not invalidate the cache.
+    pub fn inner(&self) -> &DatabaseConnection {
+        &self.conn
+    }
+
+    /// Evict every cached result that read from `table`, e.g. after it was modified by
+    /// another process.
+    pub fn invalidate(&self, table: &str) {
+        self.cache.invalidate(&HashSet::from([table.to_owned()]));
+    }
+
+    /// Evict all cached results.
+    pub fn clear(&self) {
+        self.cache.clear();
+    }
+
+    fn read<S: StatementBuilder>(
+        &self,
+        stmt: &S,
+        tables: HashSet<String>,
+        one: bool,
+    ) -> Result<Vec<QueryResult>, DbErr> {
+        let stmt = self.get_database_backend().build(stmt);
+        if tables.is_empty() || !is_replica_safe(&stmt.sql) {
+            return if one {
+                Ok(self.conn.query_one_raw(stmt)?.into_iter().collect())
+            } else {
+                self.conn.query_all_raw(stmt)
+            };
+        }
+
+        let key = CacheKey::new(&stmt, one);
+        if let Some(rows) = self.cache.get(&key) {
+            return Ok(rows);
+        }
+        let generation = self.cache.generation();
+        let rows: Vec<QueryResult> = if one {
+            self.conn.query_one_raw(stmt)?.into_iter().collect()
+        } else {
+            self.conn.query_all_raw(stmt)?
+        };
+        self.cache.insert(key, rows.clone(), tables, generation);
+        Ok(rows)
+    }
+
+    fn wrap(&self, conn: DatabaseTransaction) -> CachedTransaction {
+        CachedTransaction {
+            conn,
+            cache: self.cache.clone(),
+            pending: Default::default(),
+            outermost: true,
+        }
+    }
+
+    /// Execute the function inside a transaction.
+    /// If the function returns an error, the transaction will be rolled back.
+    /// Otherwise, the transaction will be committed.
+    #[instrument(level = "trace", skip(callback))]
+    pub fn transaction<F, T, E>(&self, callback: F) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnOnce(&'c CachedTransaction) -> Result<T, E>,
+        E: std::fmt::Display + std::fmt::Debug,
+    {
+        let transaction = self.begin().map_err(TransactionError::Connection)?;
+        run_async_transaction_callback(transaction, callback)
+    }
+}
+
+impl CachedTransaction {
+    fn record(&self, access: &Access) {
+        self.pending
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .record(access);
+    }
+
+    fn wrap(&self, conn: DatabaseTransaction) -> CachedTransaction {
+        CachedTransaction {
+            conn,
+            cache: self.cache.clone(),
+            pending: self.pending.clone(),
+            outermost: false,
+        }
+    }
+
+    /// Execute the function inside a transaction.
+    /// If the function returns an error, the transaction will be rolled back.
+    /// Otherwise, the transaction will be committed.
+    #[instrument(level = "trace", skip(callback))]
+    pub fn transaction<F, T, E>(&self, callback: F) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnOnce(&'c CachedTransaction) -> Result<T, E>,
+        E: std::fmt::Display + std::fmt::Debug,
+    {
+        let transaction = self.begin().map_err(TransactionError::Connection)?;
+        run_async_transaction_callback(transaction, callback)
+    }
+
+    /// Runs a transaction to completion passing through the result.
+    /// Rolling back the transaction on encountering an error.
+    #[instrument(level = "trace", skip(callback))]
+    fn run<F, T, E>(self, callback: F) -> Result<T, TransactionError<E>>
+    where
+        F: for<'b> FnOnce(&'b CachedTransaction) -> Result<T, E>,
+        E: std::fmt::Display + std::fmt::Debug,
+    {
+        let res = callback(&self).map_err(TransactionError::Transaction);
+        if res.is_ok() {
+            self.commit().map_err(TransactionError::Connection)?;
+        } else {
+            self.rollback().map_err(TransactionError::Connection)?;
+        }
+        res
+    }
+
+    /// Commit a transaction. Committing the outermost transaction invalidates the
+    /// cached results of every table written within it.
+    #[instrument(level = "trace")]
+    pub fn commit(self) -> Result<(), DbErr> {
+        self.conn.commit()?;
+        if self.outermost {
+            let pending =
+                std::mem::take(&mut *self.pending.lock().unwrap_or_else(|err| err.into_inner()));
+            if pending.all {
+                self.cache.clear();
+            } else if !pending.tables.is_empty() {
+                self.cache.invalidate(&pending.tables);
+            }
+        }
+        Ok(())
+    }
+
+    /// Rolls back a transaction explicitly
+    #[instrument(level = "trace")]
+    pub fn rollback(self) -> Result<(), DbErr> {
+        self.conn.rollback()
+    }
+}
+
+impl TransactionTrait for CachedConnection {
+    type Transaction = CachedTransaction;
+
+    #[instrument(level = "trace")]
+    fn begin(&self) -> Result<CachedTransaction, DbErr> {
+        Ok(self.wrap(self.conn.begin()?))
+    }
+
+    #[instrument(level = "trace")]
+    fn begin_with_config(
+        &self,
+        isolation_level: Option<IsolationLevel>,
+        access_mode: Option<AccessMode>,
+    ) -> Result<CachedTransaction, DbErr> {
+        Ok(self.wrap(self.conn.begin_with_config(isolation_level, access_mode)?))
+    }
+
+    #[instrument(level = "trace")]
+    fn begin_with_options(&self, options: TransactionOptions) -> Result<CachedTransaction, DbErr> {
+        Ok(self.wrap(self.conn.begin_with_options(options)?))
+    }
+
+    /// Execute the function inside a transaction.
+    /// If the function returns an error, the transaction will be rolled back. If it does not return an error, the transaction will be committed.
+    #[instrument(level = "trace", skip(callback))]
+    fn transaction<F, T, E>(&self, callback: F) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnOnce(&'c CachedTransaction) -> Result<T, E>,
+        E: std::fmt::Display + std::fmt::Debug,
+    {
+        let transaction = self.begin().map_err(TransactionError::Connection)?;
+        transaction.run(callback)
+    }
+
+    /// Execute the function inside a transaction.
+    /// If the function returns an error, the transaction will be rolled back. If it does not return an error, the transaction will be committed.
+    #[instrument(level = "trace", skip(callback))]
+    fn transaction_with_config<F, T, E>(
+        &self,
+        callback: F,
+        isolation_level: Option<IsolationLevel>,
+        access_mode: Option<AccessMode>,
+    ) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnOnce(&'c CachedTransaction) -> Result<T, E>,
+        E: std::fmt::Display + std::fmt::Debug,
+    {
+        let transaction = self
+            .begin_with_config(isolation_level, access_mode)
+            .map_err(TransactionError::Connection)?;
+        transaction.run(callback)
+    }
+}
+
+impl TransactionTrait for CachedTransaction {
+    type Transaction = CachedTransaction;
+
+    #[instrument(level = "trace")]
+    fn begin(&self) -> Result<CachedTransaction, DbErr> {
+        Ok(self.wrap(self.conn.begin()?))
+    }
+
+    #[instrument(level = "trace")]
+    fn begin_with_config(
+        &self,
+        isolation_level: Option<IsolationLevel>,
+        access_mode: Option<AccessMode>,
+    ) -> Result<CachedTransaction, DbErr> {
+        Ok(self.wrap(self.conn.begin_with_config(isolation_level, access_mode)?))
+    }
+
+    #[instrument(level = "trace")]
+    fn begin_with_options(&self, options: TransactionOptions) -> Result<CachedTransaction, DbErr> {
+        Ok(self.wrap(self.conn.begin_with_options(options)?))
+    }
+
+    /// Execute the function inside a transaction.
+    /// If the function returns an error, the transaction will be rolled back. If it does not return an error, the transaction will be committed.
+    #[instrument(level = "trace", skip(callback))]
+    fn transaction<F, T, E>(&self, callback: F) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnOnce(&'c CachedTransaction) -> Result<T, E>,
+        E: std::fmt::Display + std::fmt::Debug,
+    {
+        let transaction = self.begin().map_err(TransactionError::Connection)?;
+        transaction.run(callback)
+    }
+
+    /// Execute the function inside a transaction.
+    /// If the function returns an error, the transaction will be rolled back. If it does not return an error, the transaction will be committed.
+    #[instrument(level = "trace", skip(callback))]
+    fn transaction_with_config<F, T, E>(
+        &self,
+        callback: F,
+        isolation_level: Option<IsolationLevel>,
+        access_mode: Option<AccessMode>,
+    ) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnOnce(&'c CachedTransaction) -> Result<T, E>,
+        E: std::fmt::Display + std::fmt::Debug,
+    {
+        let transaction = self
+            .begin_with_config(isolation_level, access_mode)
+            .map_err(TransactionError::Connection)?;
+        transaction.run(callback)
+    }
+}
+
+impl TransactionSession for CachedTransaction {
+    fn commit(self) -> Result<(), DbErr> {
+        self.commit()
+    }
+
+    fn rollback(self) -> Result<(), DbErr> {
+        self.rollback()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "mock")]
+mod tests {
+    use super::CachedConnection;
+    use crate::{
+        ConnectionTrait, DbBackend, DbErr, EntityTrait, MockDatabase, MockExecResult, QueryFilter,
+        Statement, Transaction, TransactionTrait, entity::*, tests_cfg::*,
+    };
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    fn cake_model() -> cake::Model {
+        cake::Model {
+            id: 1,
+            name: "Chocolate".to_owned(),
+        }
+    }
+
+    fn select_cake() -> Statement {
+        Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"SELECT "cake"."id", "cake"."name" FROM "cake""#,
+            [],
+        )
+    }
+
+    fn exec_result() -> MockExecResult {
+        MockExecResult {
+            last_insert_id: 0,
+            rows_affected: 1,
+        }
+    }
+
+    #[test]
+    fn test_cache_hit_and_invalidation() -> Result<(), DbErr> {
+        let conn = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([[cake_model()], [cake_model()]])
+            .append_exec_results([exec_result(), exec_result()])
+            .into_connection();
+        let db = CachedConnection::new(conn.clone(), Duration::from_secs(60), 16);
+
+        assert_eq!(cake::Entity::find().all(&db)?, [cake_model()]);
+        assert_eq!(cake::Entity::find().all(&db)?, [cake_model()]);
+
+        // writing to another table keeps the cached rows
+        fruit::Entity::delete_many().exec(&db)?;
+        assert_eq!(cake::Entity::find().all(&db)?, [cake_model()]);
+
+        cake::Entity::delete_many().exec(&db)?;
+        assert_eq!(cake::Entity::find().all(&db)?, [cake_model()]);
+
+        assert_eq!(
+            conn.into_transaction_log(),
+            [
+                Transaction::one(select_cake()),
+                Transaction::from_sql_and_values(DbBackend::Postgres, r#"DELETE FROM "fruit""#, []),
+                Transaction::from_sql_and_values(DbBackend::Postgres, r#"DELETE FROM "cake""#, []),
+                Transaction::one(select_cake()),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cache_key_includes_values() -> Result<(), DbErr> {
+        let conn = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([[cake_model()], [cake_model()]])
+            .into_connection();
+        let db = CachedConnection::new(conn.clone(), Duration::from_secs(60), 16);
+
+        cake::Entity::find_by_id(1).one(&db)?;
+        cake::Entity::find_by_id(1).one(&db)?;
+        cake::Entity::find_by_id(2).one(&db)?;
+
+        assert_eq!(conn.into_transaction_log().len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ttl_and_size_bound() -> Result<(), DbErr> {
+        let conn = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([[cake_model()], [cake_model()]])
+            .into_connection();
+        let db = CachedConnection::new(conn.clone(), Duration::ZERO, 16);
+        cake::Entity::find().all(&db)?;
+        cake::Entity::find().all(&db)?;
+        assert_eq!(conn.into_transaction_log().len(), 2);
+
+        let conn = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([[cake_model()], [cake_model()], [cake_model()]])
+            .into_connection();
+        let db = CachedConnection::new(conn.clone(), Duration::from_secs(60), 1);
+        cake::Entity::find().all(&db)?;
+        cake::Entity::find()
+            .filter(cake::Column::Id.eq(1))
+            .all(&db)?;
+        cake::Entity::find().all(&db)?;
+        assert_eq!(conn.into_transaction_log().len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_transaction_invalidates_on_commit() -> Result<(), DbErr> {
+        let conn = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([[cake_model()], [cake_model()]])
+            .append_exec_results([exec_result()])
+            .into_connection();
+        let db = CachedConnection::new(conn.clone(), Duration::from_secs(60), 16);
+
+        cake::Entity::find().all(&db)?;
+
+        let txn = db.begin()?;
+        cake::Entity::delete_many().exec(&txn)?;
+        // not committed yet, other readers still see the cached rows
+        cake::Entity::find().all(&db)?;
+        txn.commit()?;
+
+        cake::Entity::find().all(&db)?;
+
+        assert_eq!(
+            conn.into_transaction_log(),
+            [
+                Transaction::one(select_cake()),
+                Transaction::many([
+                    Statement::from_string(DbBackend::Postgres, "BEGIN"),
+                    Statement::from_sql_and_values(
+                        DbBackend::Postgres,
+                        r#"DELETE FROM "cake""#,
+                        []
+                    ),
+                    Statement::from_string(DbBackend::Postgres, "COMMIT"),
+                ]),
+                Transaction::one(select_cake()),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_raw_write_clears_cache() -> Result<(), DbErr> {
+        let conn = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([[cake_model()], [cake_model()]])
+            .append_exec_results([exec_result()])
+            .into_connection();
+        let db = CachedConnection::new(conn.clone(), Duration::from_secs(60), 16);
+
+        cake::Entity::find().all(&db)?;
+        db.execute_unprepared("TRUNCATE cake")?;
+        cake::Entity::find().all(&db)?;
+
+        assert_eq!(conn.into_transaction_log().len(), 3);
+
+        Ok(())
+    }
+}
@@ -1,10 +1,16 @@
-use super::transaction::run_async_transaction_callback;
+use super::{
+    advisory_lock,
+    replica::{ReadReplicas, is_replica_safe},
+    retry::run_with_retry,
+    timeout::{map_timeout_err, with_max_execution_time},
+    transaction::run_async_transaction_callback,
+};
 use crate::{
     AccessMode, ConnectionTrait, DatabaseTransaction, ExecResult, IsolationLevel, QueryResult,
-    Schema, SchemaBuilder, Statement, StatementBuilder, TransactionError, TransactionOptions,
-    TransactionTrait, error::*,
+    ReplicaSelection, RetryPolicy, Schema, SchemaBuilder, Statement, StatementBuilder,
+    TransactionError, TransactionOptions, TransactionTrait, error::*,
 };
-use std::fmt::Debug;
+use std::{fmt::Debug, sync::Arc, time::Duration};
 use tracing::instrument;
 use url::Url;
 
@@ -14,12 +20,12 @@ use sqlx::pool::PoolConnection;
 #[cfg(feature = "rusqlite")]
 use crate::driver::rusqlite::{RusqliteInnerConnection, RusqliteSharedConnection};
 
+#[cfg(feature = "duckdb")]
+use crate::driver::duckdb::{DuckdbInnerConnection, DuckdbSharedConnection};
+
 #[cfg(feature = "stream")]
 use crate::StreamTrait;
 
-#[cfg(any(feature = "mock", feature = "proxy"))]
-use std::sync::Arc;
-
 /// A handle to a database — implements [`ConnectionTrait`](crate::ConnectionTrait)
 /// and [`TransactionTrait`](crate::TransactionTrait) so it works with every
 /// query and mutation method in SeaORM.
@@ -34,6 +40,10 @@ pub struct DatabaseConnection {
     /// Driver-specific connection or pool. Held in a field so we can attach
     /// orthogonal state (e.g. RBAC) alongside.
     pub inner: DatabaseConnectionType,
+    /// Read replicas that plain `SELECT`s are routed to, if any.
+    pub(crate) replicas: Option<ReadReplicas>,
+    /// The Postgres schema statements run in, set by [`DatabaseConnection::with_schema`].
+    pub(crate) schema: Option<Arc<str>>,
     #[cfg(feature = "rbac")]
     pub(crate) rbac: crate::RbacEngineMount,
 }
@@ -61,6 +71,10 @@ pub enum DatabaseConnectionType {
     #[cfg(feature = "rusqlite")]
     RusqliteSharedConnection(RusqliteSharedConnection),
 
+    /// Embedded DuckDB connection shared across threads (`duckdb`).
+    #[cfg(feature = "duckdb")]
+    DuckdbSharedConnection(DuckdbSharedConnection),
+
     /// In-memory mock connection used for testing (`mock`).
     #[cfg(feature = "mock")]
     MockDatabaseConnection(Arc<crate::MockDatabaseConnection>),
@@ -77,6 +91,25 @@ pub enum DatabaseConnectionType {
 /// Short alias for [`DatabaseConnection`].
 pub type DbConn = DatabaseConnection;
 
+/// Run `pg_notify`, which unlike `NOTIFY` takes the channel and payload as parameters
+pub(crate) fn pg_notify<C>(db: &C, channel: &str, payload: &str) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    if db.get_database_backend() != DbBackend::Postgres {
+        return Err(DbErr::BackendNotSupported {
+            db: db.get_database_backend().as_str(),
+            ctx: "notify",
+        });
+    }
+    db.execute_raw(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        "SELECT pg_notify($1, $2)",
+        [channel.into(), payload.into()],
+    ))
+    .map(|_| ())
+}
+
 impl Default for DatabaseConnection {
     fn default() -> Self {
         DatabaseConnectionType::Disconnected.into()
@@ -87,6 +120,8 @@ impl From<DatabaseConnectionType> for DatabaseConnection {
     fn from(inner: DatabaseConnectionType) -> Self {
         Self {
             inner,
+            replicas: None,
+            schema: None,
             #[cfg(feature = "rbac")]
             rbac: Default::default(),
         }
@@ -121,6 +156,8 @@ pub(crate) enum InnerConnection {
     Sqlite(PoolConnection<sqlx::Sqlite>),
     #[cfg(feature = "rusqlite")]
     Rusqlite(RusqliteInnerConnection),
+    #[cfg(feature = "duckdb")]
+    Duckdb(DuckdbInnerConnection),
     #[cfg(feature = "mock")]
     Mock(Arc<crate::MockDatabaseConnection>),
     #[cfg(feature = "proxy")]
@@ -141,6 +178,8 @@ impl Debug for DatabaseConnectionType {
                 Self::SqlxSqlitePoolConnection(_) => "SqlxSqlitePoolConnection",
                 #[cfg(feature = "rusqlite")]
                 Self::RusqliteSharedConnection(_) => "RusqliteSharedConnection",
+                #[cfg(feature = "duckdb")]
+                Self::DuckdbSharedConnection(_) => "DuckdbSharedConnection",
                 #[cfg(feature = "mock")]
                 Self::MockDatabaseConnection(_) => "MockDatabaseConnection",
                 #[cfg(feature = "proxy")]
@@ -159,9 +198,17 @@ impl ConnectionTrait for DatabaseConnection {
     #[instrument(level = "trace", skip(stmt))]
     #[allow(unused_variables)]
     fn execute_raw(&self, stmt: Statement) -> Result<ExecResult, DbErr> {
+        if self.schema.is_some() {
+            let txn = self.begin()?;
+            let res = txn.execute_raw(stmt)?;
+            txn.commit()?;
+            return Ok(res);
+        }
+
         super::tracing_spans::with_db_span!(
             "sea_orm.execute",
             self.get_database_backend(),
+            &self.get_span_target(),
             stmt.sql.as_str(),
             record_stmt = self.get_record_stmt_in_spans(),
             {
@@ -174,6 +221,8 @@ impl ConnectionTrait for DatabaseConnection {
                     DatabaseConnectionType::SqlxSqlitePoolConnection(conn) => conn.execute(stmt),
                     #[cfg(feature = "rusqlite")]
                     DatabaseConnectionType::RusqliteSharedConnection(conn) => conn.execute(stmt),
+                    #[cfg(feature = "duckdb")]
+                    DatabaseConnectionType::DuckdbSharedConnection(conn) => conn.execute(stmt),
                     #[cfg(feature = "mock")]
                     DatabaseConnectionType::MockDatabaseConnection(conn) => conn.execute(stmt),
                     #[cfg(feature = "proxy")]
@@ -187,9 +236,17 @@ impl ConnectionTrait for DatabaseConnection {
     #[instrument(level = "trace", skip(sql))]
     #[allow(unused_variables)]
     fn execute_unprepared(&self, sql: &str) -> Result<ExecResult, DbErr> {
+        if self.schema.is_some() {
+            let txn = self.begin()?;
+            let res = txn.execute_unprepared(sql)?;
+            txn.commit()?;
+            return Ok(res);
+        }
+
         super::tracing_spans::with_db_span!(
             "sea_orm.execute_unprepared",
             self.get_database_backend(),
+            &self.get_span_target(),
             sql,
             record_stmt = false,
             {
@@ -210,6 +267,10 @@ impl ConnectionTrait for DatabaseConnection {
                     DatabaseConnectionType::RusqliteSharedConnection(conn) => {
                         conn.execute_unprepared(sql)
                     }
+                    #[cfg(feature = "duckdb")]
+                    DatabaseConnectionType::DuckdbSharedConnection(conn) => {
+                        conn.execute_unprepared(sql)
+                    }
                     #[cfg(feature = "mock")]
                     DatabaseConnectionType::MockDatabaseConnection(conn) => {
                         let db_backend = conn.get_database_backend();
@@ -231,9 +292,20 @@ impl ConnectionTrait for DatabaseConnection {
     #[instrument(level = "trace", skip(stmt))]
     #[allow(unused_variables)]
     fn query_one_raw(&self, stmt: Statement) -> Result<Option<QueryResult>, DbErr> {
+        if let Some(replicas) = self.replicas_for(&stmt) {
+            return replicas.query_one_raw(stmt);
+        }
+        if self.schema.is_some() {
+            let txn = self.begin()?;
+            let res = txn.query_one_raw(stmt)?;
+            txn.commit()?;
+            return Ok(res);
+        }
+
         super::tracing_spans::with_db_span!(
             "sea_orm.query_one",
             self.get_database_backend(),
+            &self.get_span_target(),
             stmt.sql.as_str(),
             record_stmt = self.get_record_stmt_in_spans(),
             {
@@ -248,6 +320,8 @@ impl ConnectionTrait for DatabaseConnection {
                     DatabaseConnectionType::SqlxSqlitePoolConnection(conn) => conn.query_one(stmt),
                     #[cfg(feature = "rusqlite")]
                     DatabaseConnectionType::RusqliteSharedConnection(conn) => conn.query_one(stmt),
+                    #[cfg(feature = "duckdb")]
+                    DatabaseConnectionType::DuckdbSharedConnection(conn) => conn.query_one(stmt),
                     #[cfg(feature = "mock")]
                     DatabaseConnectionType::MockDatabaseConnection(conn) => conn.query_one(stmt),
                     #[cfg(feature = "proxy")]
@@ -261,9 +335,20 @@ impl ConnectionTrait for DatabaseConnection {
     #[instrument(level = "trace", skip(stmt))]
     #[allow(unused_variables)]
     fn query_all_raw(&self, stmt: Statement) -> Result<Vec<QueryResult>, DbErr> {
+        if let Some(replicas) = self.replicas_for(&stmt) {
+            return replicas.query_all_raw(stmt);
+        }
+        if self.schema.is_some() {
+            let txn = self.begin()?;
+            let res = txn.query_all_raw(stmt)?;
+            txn.commit()?;
+            return Ok(res);
+        }
+
         super::tracing_spans::with_db_span!(
             "sea_orm.query_all",
             self.get_database_backend(),
+            &self.get_span_target(),
             stmt.sql.as_str(),
             record_stmt = self.get_record_stmt_in_spans(),
             {
@@ -278,6 +363,8 @@ impl ConnectionTrait for DatabaseConnection {
                     DatabaseConnectionType::SqlxSqlitePoolConnection(conn) => conn.query_all(stmt),
                     #[cfg(feature = "rusqlite")]
                     DatabaseConnectionType::RusqliteSharedConnection(conn) => conn.query_all(stmt),
+                    #[cfg(feature = "duckdb")]
+                    DatabaseConnectionType::DuckdbSharedConnection(conn) => conn.query_all(stmt),
                     #[cfg(feature = "mock")]
                     DatabaseConnectionType::MockDatabaseConnection(conn) => conn.query_all(stmt),
                     #[cfg(feature = "proxy")]
@@ -288,6 +375,108 @@ impl ConnectionTrait for DatabaseConnection {
         )
     }
 
+    fn execute_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<ExecResult, DbErr> {
+        if !self.bounds_in_transaction() {
+            let stmt = with_max_execution_time(stmt, timeout);
+            return map_timeout_err(self.execute_raw(stmt), timeout);
+        }
+        let txn = self.begin()?;
+        let (stmt, armed) = txn.arm_timeout(stmt, timeout, false)?;
+        let res = map_timeout_err(txn.execute_raw(stmt), timeout)?;
+        txn.disarm_timeout(armed)?;
+        txn.commit()?;
+        Ok(res)
+    }
+
+    fn query_one_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<Option<QueryResult>, DbErr> {
+        if let Some(replicas) = self.replicas_for(&stmt) {
+            return replicas.query_one_raw_with_timeout(stmt, timeout);
+        }
+        if !self.bounds_in_transaction() {
+            let stmt = with_max_execution_time(stmt, timeout);
+            return map_timeout_err(self.query_one_raw(stmt), timeout);
+        }
+        let txn = self.begin()?;
+        let (stmt, armed) = txn.arm_timeout(stmt, timeout, false)?;
+        let res = map_timeout_err(txn.query_one_raw(stmt), timeout)?;
+        txn.disarm_timeout(armed)?;
+        txn.commit()?;
+        Ok(res)
+    }
+
+    fn query_all_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<Vec<QueryResult>, DbErr> {
+        if let Some(replicas) = self.replicas_for(&stmt) {
+            return replicas.query_all_raw_with_timeout(stmt, timeout);
+        }
+        if !self.bounds_in_transaction() {
+            let stmt = with_max_execution_time(stmt, timeout);
+            return map_timeout_err(self.query_all_raw(stmt), timeout);
+        }
+        let txn = self.begin()?;
+        let (stmt, armed) = txn.arm_timeout(stmt, timeout, false)?;
+        let res = map_timeout_err(txn.query_all_raw(stmt), timeout)?;
+        txn.disarm_timeout(armed)?;
+        txn.commit()?;
+        Ok(res)
+    }
+
+    fn begin_atomic(&self) -> Result<Option<DatabaseTransaction>, DbErr> {
+        self.begin().map(Some)
+    }
+
+    fn copy_in_raw(
+        &self,
+        sql: &str,
+        data: &mut dyn Iterator<Item = Result<Vec<u8>, DbErr>>,
+    ) -> Result<u64, DbErr> {
+        if self.schema.is_some() {
+            let txn = self.begin()?;
+            let res = txn.copy_in_raw(sql, data)?;
+            txn.commit()?;
+            return Ok(res);
+        }
+
+        match &self.inner {
+            #[cfg(feature = "sqlx-postgres")]
+            DatabaseConnectionType::SqlxPostgresPoolConnection(conn) => conn.copy_in(sql, data),
+            #[allow(unreachable_patterns)]
+            _ => Err(DbErr::BackendNotSupported {
+                db: self.get_database_backend().as_str(),
+                ctx: "COPY",
+            }),
+        }
+    }
+
+    fn copy_out_raw(&self, sql: &str, writer: &mut dyn std::io::Write) -> Result<(), DbErr> {
+        if self.schema.is_some() {
+            let txn = self.begin()?;
+            txn.copy_out_raw(sql, writer)?;
+            return txn.commit();
+        }
+
+        match &self.inner {
+            #[cfg(feature = "sqlx-postgres")]
+            DatabaseConnectionType::SqlxPostgresPoolConnection(conn) => conn.copy_out(sql, writer),
+            #[allow(unreachable_patterns)]
+            _ => Err(DbErr::BackendNotSupported {
+                db: self.get_database_backend().as_str(),
+                ctx: "COPY",
+            }),
+        }
+    }
+
     #[cfg(feature = "mock")]
     fn is_mock_connection(&self) -> bool {
         matches!(
@@ -312,15 +501,24 @@ impl StreamTrait for DatabaseConnection {
     #[allow(unused_variables)]
     fn stream_raw<'a>(&'a self, stmt: Statement) -> Result<Self::Stream<'a>, DbErr> {
         ({
+            if let Some(replicas) = self.replicas_for(&stmt) {
+                return replicas.stream_raw(stmt);
+            }
+
             match &self.inner {
                 #[cfg(feature = "sqlx-mysql")]
                 DatabaseConnectionType::SqlxMySqlPoolConnection(conn) => conn.stream(stmt),
                 #[cfg(feature = "sqlx-postgres")]
-                DatabaseConnectionType::SqlxPostgresPoolConnection(conn) => conn.stream(stmt),
+                DatabaseConnectionType::SqlxPostgresPoolConnection(conn) => match &self.schema {
+                    Some(schema) => conn.stream_in_schema(stmt, schema),
+                    None => conn.stream(stmt),
+                },
                 #[cfg(feature = "sqlx-sqlite")]
                 DatabaseConnectionType::SqlxSqlitePoolConnection(conn) => conn.stream(stmt),
                 #[cfg(feature = "rusqlite")]
                 DatabaseConnectionType::RusqliteSharedConnection(conn) => conn.stream(stmt),
+                #[cfg(feature = "duckdb")]
+                DatabaseConnectionType::DuckdbSharedConnection(conn) => conn.stream(stmt),
                 #[cfg(feature = "mock")]
                 DatabaseConnectionType::MockDatabaseConnection(conn) => {
                     Ok(crate::QueryStream::from((Arc::clone(conn), stmt, None)))
@@ -333,6 +531,31 @@ impl StreamTrait for DatabaseConnection {
             }
         })
     }
+
+    #[instrument(level = "trace", skip(stmt))]
+    fn stream_raw_with_timeout<'a>(
+        &'a self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<Self::Stream<'a>, DbErr> {
+        ({
+            if let Some(replicas) = self.replicas_for(&stmt) {
+                return replicas.stream_raw_with_timeout(stmt, timeout);
+            }
+
+            match &self.inner {
+                #[cfg(feature = "sqlx-postgres")]
+                DatabaseConnectionType::SqlxPostgresPoolConnection(conn) => {
+                    conn.stream_with_timeout(stmt, self.schema.as_deref(), timeout)
+                }
+                #[cfg(feature = "sqlx-sqlite")]
+                DatabaseConnectionType::SqlxSqlitePoolConnection(conn) => {
+                    conn.stream_with_timeout(stmt, timeout)
+                }
+                _ => self.stream_raw(with_max_execution_time(stmt, timeout)),
+            }
+        })
+    }
 }
 
 impl TransactionTrait for DatabaseConnection {
@@ -340,7 +563,7 @@ impl TransactionTrait for DatabaseConnection {
 
     #[instrument(level = "trace")]
     fn begin(&self) -> Result<DatabaseTransaction, DbErr> {
-        match &self.inner {
+        let txn = match &self.inner {
             #[cfg(feature = "sqlx-mysql")]
             DatabaseConnectionType::SqlxMySqlPoolConnection(conn) => conn.begin(None, None),
             #[cfg(feature = "sqlx-postgres")]
@@ -349,6 +572,8 @@ impl TransactionTrait for DatabaseConnection {
             DatabaseConnectionType::SqlxSqlitePoolConnection(conn) => conn.begin(None, None, None),
             #[cfg(feature = "rusqlite")]
             DatabaseConnectionType::RusqliteSharedConnection(conn) => conn.begin(None, None, None),
+            #[cfg(feature = "duckdb")]
+            DatabaseConnectionType::DuckdbSharedConnection(conn) => conn.begin(None, None),
             #[cfg(feature = "mock")]
             DatabaseConnectionType::MockDatabaseConnection(conn) => {
                 DatabaseTransaction::new_mock(Arc::clone(conn), None)
@@ -358,7 +583,8 @@ impl TransactionTrait for DatabaseConnection {
                 DatabaseTransaction::new_proxy(conn.clone(), None)
             }
             DatabaseConnectionType::Disconnected => Err(conn_err("Disconnected")),
-        }
+        }?;
+        self.enter_schema(txn)
     }
 
     #[instrument(level = "trace")]
@@ -367,7 +593,7 @@ impl TransactionTrait for DatabaseConnection {
         _isolation_level: Option<IsolationLevel>,
         _access_mode: Option<AccessMode>,
     ) -> Result<DatabaseTransaction, DbErr> {
-        match &self.inner {
+        let txn = match &self.inner {
             #[cfg(feature = "sqlx-mysql")]
             DatabaseConnectionType::SqlxMySqlPoolConnection(conn) => {
                 conn.begin(_isolation_level, _access_mode)
@@ -384,6 +610,10 @@ impl TransactionTrait for DatabaseConnection {
             DatabaseConnectionType::RusqliteSharedConnection(conn) => {
                 conn.begin(_isolation_level, _access_mode, None)
             }
+            #[cfg(feature = "duckdb")]
+            DatabaseConnectionType::DuckdbSharedConnection(conn) => {
+                conn.begin(_isolation_level, _access_mode)
+            }
             #[cfg(feature = "mock")]
             DatabaseConnectionType::MockDatabaseConnection(conn) => {
                 DatabaseTransaction::new_mock(Arc::clone(conn), None)
@@ -393,7 +623,8 @@ impl TransactionTrait for DatabaseConnection {
                 DatabaseTransaction::new_proxy(conn.clone(), None)
             }
             DatabaseConnectionType::Disconnected => Err(conn_err("Disconnected")),
-        }
+        }?;
+        self.enter_schema(txn)
     }
 
     #[instrument(level = "trace")]
@@ -405,7 +636,7 @@ impl TransactionTrait for DatabaseConnection {
             sqlite_transaction_mode: _sqlite_transaction_mode,
         }: TransactionOptions,
     ) -> Result<DatabaseTransaction, DbErr> {
-        match &self.inner {
+        let txn = match &self.inner {
             #[cfg(feature = "sqlx-mysql")]
             DatabaseConnectionType::SqlxMySqlPoolConnection(conn) => {
                 conn.begin(_isolation_level, _access_mode)
@@ -422,6 +653,10 @@ impl TransactionTrait for DatabaseConnection {
             DatabaseConnectionType::RusqliteSharedConnection(conn) => {
                 conn.begin(_isolation_level, _access_mode, _sqlite_transaction_mode)
             }
+            #[cfg(feature = "duckdb")]
+            DatabaseConnectionType::DuckdbSharedConnection(conn) => {
+                conn.begin(_isolation_level, _access_mode)
+            }
             #[cfg(feature = "mock")]
             DatabaseConnectionType::MockDatabaseConnection(conn) => {
                 DatabaseTransaction::new_mock(Arc::clone(conn), None)
@@ -431,7 +666,8 @@ impl TransactionTrait for DatabaseConnection {
                 DatabaseTransaction::new_proxy(conn.clone(), None)
             }
             DatabaseConnectionType::Disconnected => Err(conn_err("Disconnected")),
-        }
+        }?;
+        self.enter_schema(txn)
     }
 
     /// Execute the function inside a transaction.
@@ -442,6 +678,11 @@ impl TransactionTrait for DatabaseConnection {
         F: for<'c> FnOnce(&'c DatabaseTransaction) -> Result<T, E>,
         E: std::fmt::Display + std::fmt::Debug,
     {
+        if self.schema.is_some() {
+            let transaction = self.begin().map_err(TransactionError::Connection)?;
+            return transaction.run(_callback);
+        }
+
         match &self.inner {
             #[cfg(feature = "sqlx-mysql")]
             DatabaseConnectionType::SqlxMySqlPoolConnection(conn) => {
@@ -459,6 +700,10 @@ impl TransactionTrait for DatabaseConnection {
             DatabaseConnectionType::RusqliteSharedConnection(conn) => {
                 conn.transaction(_callback, None, None)
             }
+            #[cfg(feature = "duckdb")]
+            DatabaseConnectionType::DuckdbSharedConnection(conn) => {
+                conn.transaction(_callback, None, None)
+            }
             #[cfg(feature = "mock")]
             DatabaseConnectionType::MockDatabaseConnection(conn) => {
                 let transaction = DatabaseTransaction::new_mock(Arc::clone(conn), None)
@@ -488,6 +733,13 @@ impl TransactionTrait for DatabaseConnection {
         F: for<'c> FnOnce(&'c DatabaseTransaction) -> Result<T, E>,
         E: std::fmt::Display + std::fmt::Debug,
     {
+        if self.schema.is_some() {
+            let transaction = self
+                .begin_with_config(_isolation_level, _access_mode)
+                .map_err(TransactionError::Connection)?;
+            return transaction.run(_callback);
+        }
+
         match &self.inner {
             #[cfg(feature = "sqlx-mysql")]
             DatabaseConnectionType::SqlxMySqlPoolConnection(conn) => {
@@ -505,6 +757,10 @@ impl TransactionTrait for DatabaseConnection {
             DatabaseConnectionType::RusqliteSharedConnection(conn) => {
                 conn.transaction(_callback, _isolation_level, _access_mode)
             }
+            #[cfg(feature = "duckdb")]
+            DatabaseConnectionType::DuckdbSharedConnection(conn) => {
+                conn.transaction(_callback, _isolation_level, _access_mode)
+            }
             #[cfg(feature = "mock")]
             DatabaseConnectionType::MockDatabaseConnection(conn) => {
                 let transaction = DatabaseTransaction::new_mock(Arc::clone(conn), None)
@@ -637,6 +893,190 @@ impl DatabaseConnection {
         run_async_transaction_callback(transaction, callback)
     }
 
+    /// Execute the function inside a transaction started with `options`, re-running it in
+    /// a fresh transaction when it fails with an error that `policy` deems retryable, such
+    /// as a serialization failure or a deadlock. The transaction is committed once the
+    /// function succeeds; otherwise it is rolled back and the last error is returned.
+    ///
+    /// An error returned by the function is retried if it is a [`DbErr`], or wraps one as
+    /// its [`source`](std::error::Error::source).
+    #[instrument(level = "trace", skip(callback))]
+    pub fn transaction_with_retry<F, T, E>(
+        &self,
+        options: TransactionOptions,
+        policy: RetryPolicy,
+        callback: F,
+    ) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnMut(&'c DatabaseTransaction) -> Result<T, E>,
+        E: std::error::Error + 'static,
+    {
+        run_with_retry(self, options, policy, callback)
+    }
+
+    /// Take the advisory lock on `key`, waiting until no other connection holds it. The lock
+    /// is released when the returned guard is committed or dropped; see [`AdvisoryLock`]
+    /// for how each backend implements it. Fails with [`DbErr::AdvisoryLockNotAcquired`] if
+    /// the database gives up waiting, as MySQL does when the session is killed.
+    ///
+    /// [`AdvisoryLock`]: crate::AdvisoryLock
+    #[instrument(level = "trace")]
+    pub fn advisory_lock(&self, key: &str) -> Result<crate::AdvisoryLock, DbErr> {
+        self.check_advisory_lock_support()?;
+        advisory_lock::acquire(self, key, true)?
+            .ok_or_else(|| DbErr::AdvisoryLockNotAcquired(key.to_owned()))
+    }
+
+    /// Take the advisory lock on `key` if no other connection holds it, returning `None`
+    /// otherwise. See [`advisory_lock`](Self::advisory_lock).
+    #[instrument(level = "trace")]
+    pub fn try_advisory_lock(&self, key: &str) -> Result<Option<crate::AdvisoryLock>, DbErr> {
+        self.check_advisory_lock_support()?;
+        advisory_lock::acquire(self, key, false)
+    }
+
+    fn check_advisory_lock_support(&self) -> Result<(), DbErr> {
+        match &self.inner {
+            #[cfg(feature = "duckdb")]
+            DatabaseConnectionType::DuckdbSharedConnection(_) => Err(DbErr::BackendNotSupported {
+                db: "DuckDB",
+                ctx: "advisory_lock",
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Attach read replicas to this connection.
+    ///
+    /// Plain `SELECT`s run through the returned connection are served by one of `replicas`,
+    /// picked according to `selection`; writes, [`execute_unprepared`], locking reads,
+    /// `SELECT ... INTO`, `SELECT`s calling functions other than the built-in ones known not
+    /// to write or lock, and transactions stay on `self`. To keep a single query on `self`,
+    /// run it through [`use_primary`](Self::use_primary). [`Database::connect`] calls this for you when
+    /// [`ConnectOptions::read_replica`] is set, but any connections can be combined, which is
+    /// handy for testing with SQLite files or [`MockDatabase`] connections.
+    ///
+    /// [`execute_unprepared`]: ConnectionTrait::execute_unprepared
+    /// [`Database::connect`]: crate::Database::connect
+    /// [`ConnectOptions::read_replica`]: crate::ConnectOptions::read_replica
+    /// [`MockDatabase`]: crate::MockDatabase
+    pub fn with_read_replicas<I>(mut self, replicas: I, selection: ReplicaSelection) -> Self
+    where
+        I: IntoIterator<Item = DatabaseConnection>,
+    {
+        let replicas: Vec<_> = replicas.into_iter().map(|c| c.use_primary()).collect();
+        self.replicas = (!replicas.is_empty()).then(|| ReadReplicas::new(replicas, selection));
+        self
+    }
+
+    /// Get a handle to this connection that sends every statement, reads included, to the
+    /// primary. Use it on read-your-own-writes paths where replica lag is not acceptable, or
+    /// for a query calling a function that writes, e.g. `Entity::find().all(&db.use_primary())`.
+    ///
+    /// Without read replicas this is simply a clone of `self`.
+    pub fn use_primary(&self) -> DatabaseConnection {
+        DatabaseConnection {
+            replicas: None,
+            ..self.clone()
+        }
+    }
+
+    /// Whether this connection routes reads to read replicas
+    pub fn has_read_replicas(&self) -> bool {
+        self.replicas.is_some()
+    }
+
+    /// Get a handle to this connection that runs every statement with `schema` as the
+    /// Postgres `search_path`, so entities without a `schema_name`, and unqualified table
+    /// names in raw SQL, resolve to the tables of that schema. The handle shares the pool
+    /// of `self`: take one per request to serve many tenants from a single pool, which
+    /// [`ConnectOptions::set_schema_search_path`] cannot do.
+    ///
+    /// The setting is transaction-local, so it never outlives a statement on the pooled
+    /// connection. A transaction begun on the handle sets it once, after `BEGIN`; outside of
+    /// one, each statement runs in a transaction of its own, so group statements in a
+    /// transaction to save round trips. A stream gets a connection of its own, which is
+    /// closed once the stream is dropped.
+    ///
+    /// Entities with an explicit `schema_name` keep it. Only `schema` is searched, so tables
+    /// shared by all tenants need one, e.g. `schema_name = "public"`.
+    ///
+    /// Returns [`DbErr::BackendNotSupported`] on backends other than Postgres.
+    ///
+    /// [`ConnectOptions::set_schema_search_path`]: crate::ConnectOptions::set_schema_search_path
+    pub fn with_schema<S>(&self, schema: S) -> Result<DatabaseConnection, DbErr>
+    where
+        S: Into<String>,
+    {
+        let backend = self.get_database_backend();
+        #[cfg(feature = "duckdb")]
+        if matches!(
+            self.inner,
+            DatabaseConnectionType::DuckdbSharedConnection(_)
+        ) {
+            return Err(DbErr::BackendNotSupported {
+                db: "DuckDB",
+                ctx: "with_schema",
+            });
+        }
+        if backend != DbBackend::Postgres {
+            return Err(DbErr::BackendNotSupported {
+                db: backend.as_str(),
+                ctx: "with_schema",
+            });
+        }
+
+        let schema: Arc<str> = schema.into().into();
+        Ok(DatabaseConnection {
+            replicas: self.replicas.as_ref().map(|replicas| {
+                replicas.map(|replica| DatabaseConnection {
+                    schema: Some(Arc::clone(&schema)),
+                    ..replica.clone()
+                })
+            }),
+            schema: Some(schema),
+            ..self.clone()
+        })
+    }
+
+    /// The schema set by [`with_schema`](Self::with_schema), if any
+    pub fn get_schema(&self) -> Option<&str> {
+        self.schema.as_deref()
+    }
+
+    /// Point a transaction just begun at the schema of a [`with_schema`](Self::with_schema)
+    /// handle.
+    fn enter_schema(&self, txn: DatabaseTransaction) -> Result<DatabaseTransaction, DbErr> {
+        if let Some(schema) = &self.schema {
+            txn.set_search_path(schema)?;
+        }
+        Ok(txn)
+    }
+
+    fn replicas_for(&self, stmt: &Statement) -> Option<&ReadReplicas> {
+        self.replicas
+            .as_ref()
+            .filter(|_| is_replica_safe(&stmt.sql))
+    }
+
+    /// Whether a per-query timeout needs a transaction of its own: `SET LOCAL`
+    /// only lasts for a transaction, and the SQLite and DuckDB mechanisms have to
+    /// stay on the connection that runs the statement. MySQL bounds a statement
+    /// with a hint, and mock and proxy connections are not bounded at all.
+    fn bounds_in_transaction(&self) -> bool {
+        match &self.inner {
+            #[cfg(feature = "sqlx-postgres")]
+            DatabaseConnectionType::SqlxPostgresPoolConnection(_) => true,
+            #[cfg(feature = "sqlx-sqlite")]
+            DatabaseConnectionType::SqlxSqlitePoolConnection(_) => true,
+            #[cfg(feature = "rusqlite")]
+            DatabaseConnectionType::RusqliteSharedConnection(_) => true,
+            #[cfg(feature = "duckdb")]
+            DatabaseConnectionType::DuckdbSharedConnection(_) => true,
+            _ => false,
+        }
+    }
+
     #[allow(unused)]
     pub(crate) fn get_record_stmt_in_spans(&self) -> bool {
         match &self.inner {
@@ -648,6 +1088,8 @@ impl DatabaseConnection {
             DatabaseConnectionType::SqlxSqlitePoolConnection(conn) => conn.record_stmt_in_spans,
             #[cfg(feature = "rusqlite")]
             DatabaseConnectionType::RusqliteSharedConnection(conn) => conn.record_stmt_in_spans,
+            #[cfg(feature = "duckdb")]
+            DatabaseConnectionType::DuckdbSharedConnection(conn) => conn.record_stmt_in_spans,
             DatabaseConnectionType::Disconnected => true,
             #[cfg(feature = "mock")]
             DatabaseConnectionType::MockDatabaseConnection(_) => true,
@@ -656,6 +1098,63 @@ impl DatabaseConnection {
         }
     }
 
+    #[cfg(feature = "tracing-spans")]
+    fn get_span_target(&self) -> super::tracing_spans::DbTarget {
+        match &self.inner {
+            #[cfg(feature = "sqlx-mysql")]
+            DatabaseConnectionType::SqlxMySqlPoolConnection(conn) => conn.target.clone(),
+            #[cfg(feature = "sqlx-postgres")]
+            DatabaseConnectionType::SqlxPostgresPoolConnection(conn) => conn.target.clone(),
+            #[cfg(feature = "sqlx-sqlite")]
+            DatabaseConnectionType::SqlxSqlitePoolConnection(conn) => conn.target.clone(),
+            #[cfg(feature = "rusqlite")]
+            DatabaseConnectionType::RusqliteSharedConnection(conn) => conn.target.clone(),
+            #[cfg(feature = "duckdb")]
+            DatabaseConnectionType::DuckdbSharedConnection(conn) => conn.target.clone(),
+            _ => Default::default(),
+        }
+    }
+
+    /// Run a query and return the result set as Arrow record batches, taken
+    /// directly from DuckDB's columnar output instead of being converted row by row.
+    ///
+    /// Only supported on a DuckDB connection; convert the batches back into
+    /// models with [`ActiveModelTrait::from_arrow`](crate::ActiveModelTrait::from_arrow).
+    #[cfg(all(feature = "duckdb", feature = "with-arrow"))]
+    pub fn query_arrow(
+        &self,
+        stmt: Statement,
+    ) -> Result<Vec<crate::arrow::array::RecordBatch>, DbErr> {
+        match &self.inner {
+            DatabaseConnectionType::DuckdbSharedConnection(conn) => conn.query_arrow(stmt),
+            _ => Err(DbErr::BackendNotSupported {
+                db: "non-DuckDB",
+                ctx: "query_arrow",
+            }),
+        }
+    }
+
+    /// Listen for Postgres notifications on `channels`, sent by `NOTIFY` or
+    /// [`notify`](Self::notify). The stream holds a connection of its own, taken from
+    /// the pool of the primary, until it is dropped.
+    #[cfg(feature = "sqlx-postgres")]
+    pub fn listen(&self, channels: &[&str]) -> Result<crate::PgNotificationStream, DbErr> {
+        match &self.inner {
+            DatabaseConnectionType::SqlxPostgresPoolConnection(conn) => conn.listen(channels),
+            _ => Err(DbErr::BackendNotSupported {
+                db: "non-Postgres",
+                ctx: "listen",
+            }),
+        }
+    }
+
+    /// Send a Postgres notification with `payload` on `channel`, to every connection
+    /// listening on it. Use [`DatabaseTransaction::notify`] to only send it once a
+    /// transaction commits.
+    pub fn notify(&self, channel: &str, payload: &str) -> Result<(), DbErr> {
+        pg_notify(self, channel, payload)
+    }
+
     /// Get the database backend for this connection
     ///
     /// # Panics
@@ -671,6 +1170,8 @@ impl DatabaseConnection {
             DatabaseConnectionType::SqlxSqlitePoolConnection(_) => DbBackend::Sqlite,
             #[cfg(feature = "rusqlite")]
             DatabaseConnectionType::RusqliteSharedConnection(_) => DbBackend::Sqlite,
+            #[cfg(feature = "duckdb")]
+            DatabaseConnectionType::DuckdbSharedConnection(_) => DbBackend::Postgres,
             #[cfg(feature = "mock")]
             DatabaseConnectionType::MockDatabaseConnection(conn) => conn.get_database_backend(),
             #[cfg(feature = "proxy")]
@@ -694,6 +1195,23 @@ impl DatabaseConnection {
 
     /// Sets a callback to metric this connection
     pub fn set_metric_callback<F>(&mut self, _callback: F)
+    where
+        F: Fn(&crate::metric::Info<'_>) + 'static,
+    {
+        if let Some(replicas) = &mut self.replicas {
+            let callback: crate::metric::Callback = std::sync::Arc::new(_callback);
+            for replica in replicas.conns.iter_mut() {
+                let callback = callback.clone();
+                replica.set_primary_metric_callback(move |info| callback(info));
+            }
+            let callback = callback.clone();
+            self.set_primary_metric_callback(move |info| callback(info));
+            return;
+        }
+        self.set_primary_metric_callback(_callback);
+    }
+
+    fn set_primary_metric_callback<F>(&mut self, _callback: F)
     where
         F: Fn(&crate::metric::Info<'_>) + 'static,
     {
@@ -714,6 +1232,10 @@ impl DatabaseConnection {
             DatabaseConnectionType::RusqliteSharedConnection(conn) => {
                 conn.set_metric_callback(_callback)
             }
+            #[cfg(feature = "duckdb")]
+            DatabaseConnectionType::DuckdbSharedConnection(conn) => {
+                conn.set_metric_callback(_callback)
+            }
             _ => {}
         }
     }
@@ -729,6 +1251,8 @@ impl DatabaseConnection {
             DatabaseConnectionType::SqlxSqlitePoolConnection(conn) => conn.ping(),
             #[cfg(feature = "rusqlite")]
             DatabaseConnectionType::RusqliteSharedConnection(conn) => conn.ping(),
+            #[cfg(feature = "duckdb")]
+            DatabaseConnectionType::DuckdbSharedConnection(conn) => conn.ping(),
             #[cfg(feature = "mock")]
             DatabaseConnectionType::MockDatabaseConnection(conn) => conn.ping(),
             #[cfg(feature = "proxy")]
@@ -737,6 +1261,24 @@ impl DatabaseConnection {
         }
     }
 
+    /// Statistics of the connection pool backing this connection, `None` if it is not
+    /// pool-backed (e.g. `rusqlite`, mock and proxy connections). With read replicas,
+    /// this is the primary's pool.
+    ///
+    /// `waiters` only counts tasks waiting on this [`DatabaseConnection`] and its clones,
+    /// not other handles created from the same SQLx pool.
+    pub fn pool_status(&self) -> Option<crate::metric::PoolStatus> {
+        match &self.inner {
+            #[cfg(feature = "sqlx-mysql")]
+            DatabaseConnectionType::SqlxMySqlPoolConnection(conn) => Some(conn.pool_status()),
+            #[cfg(feature = "sqlx-postgres")]
+            DatabaseConnectionType::SqlxPostgresPoolConnection(conn) => Some(conn.pool_status()),
+            #[cfg(feature = "sqlx-sqlite")]
+            DatabaseConnectionType::SqlxSqlitePoolConnection(conn) => Some(conn.pool_status()),
+            _ => None,
+        }
+    }
+
     /// Explicitly close the database connection.
     /// See [`Self::close_by_ref`] for usage with references.
     pub fn close(self) -> Result<(), DbErr> {
@@ -745,6 +1287,15 @@ impl DatabaseConnection {
 
     /// Explicitly close the database connection
     pub fn close_by_ref(&self) -> Result<(), DbErr> {
+        if let Some(replicas) = &self.replicas {
+            for replica in replicas.conns.iter() {
+                replica.close_primary()?;
+            }
+        }
+        self.close_primary()
+    }
+
+    fn close_primary(&self) -> Result<(), DbErr> {
         match &self.inner {
             #[cfg(feature = "sqlx-mysql")]
             DatabaseConnectionType::SqlxMySqlPoolConnection(conn) => conn.close_by_ref(),
@@ -754,6 +1305,8 @@ impl DatabaseConnection {
             DatabaseConnectionType::SqlxSqlitePoolConnection(conn) => conn.close_by_ref(),
             #[cfg(feature = "rusqlite")]
             DatabaseConnectionType::RusqliteSharedConnection(conn) => conn.close_by_ref(),
+            #[cfg(feature = "duckdb")]
+            DatabaseConnectionType::DuckdbSharedConnection(conn) => conn.close_by_ref(),
             #[cfg(feature = "mock")]
             DatabaseConnectionType::MockDatabaseConnection(_) => {
                 // Nothing to cleanup, we just consume the `DatabaseConnection`
@@ -5,8 +5,7 @@ use crate::{
     TransactionTrait,
 };
 use crate::{Schema, SchemaBuilder};
-use std::future::Future;
-use std::pin::Pin;
+use std::time::Duration;
 
 /// Either a borrowed [`DatabaseConnection`] / [`DatabaseTransaction`], or an
 /// owned [`DatabaseTransaction`].
@@ -78,6 +77,56 @@ impl ConnectionTrait for DatabaseExecutor<'_> {
             DatabaseExecutor::OwnedTransaction(trans) => trans.query_all_raw(stmt),
         }
     }
+
+    fn execute_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<ExecResult, DbErr> {
+        match self {
+            DatabaseExecutor::Connection(conn) => conn.execute_raw_with_timeout(stmt, timeout),
+            DatabaseExecutor::Transaction(trans) => trans.execute_raw_with_timeout(stmt, timeout),
+            DatabaseExecutor::OwnedTransaction(trans) => {
+                trans.execute_raw_with_timeout(stmt, timeout)
+            }
+        }
+    }
+
+    fn query_one_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<Option<QueryResult>, DbErr> {
+        match self {
+            DatabaseExecutor::Connection(conn) => conn.query_one_raw_with_timeout(stmt, timeout),
+            DatabaseExecutor::Transaction(trans) => trans.query_one_raw_with_timeout(stmt, timeout),
+            DatabaseExecutor::OwnedTransaction(trans) => {
+                trans.query_one_raw_with_timeout(stmt, timeout)
+            }
+        }
+    }
+
+    fn query_all_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<Vec<QueryResult>, DbErr> {
+        match self {
+            DatabaseExecutor::Connection(conn) => conn.query_all_raw_with_timeout(stmt, timeout),
+            DatabaseExecutor::Transaction(trans) => trans.query_all_raw_with_timeout(stmt, timeout),
+            DatabaseExecutor::OwnedTransaction(trans) => {
+                trans.query_all_raw_with_timeout(stmt, timeout)
+            }
+        }
+    }
+
+    fn begin_atomic(&self) -> Result<Option<DatabaseTransaction>, DbErr> {
+        match self {
+            DatabaseExecutor::Connection(conn) => conn.begin_atomic(),
+            DatabaseExecutor::Transaction(trans) => trans.begin_atomic(),
+            DatabaseExecutor::OwnedTransaction(trans) => trans.begin_atomic(),
+        }
+    }
 }
 
 impl TransactionTrait for DatabaseExecutor<'_> {
@@ -0,0 +1,1720 @@
+//! Tables, and the evaluation of parsed statements against them
+
+use super::parser::{
+    BinaryOp, ConflictAction, Delete, Expr, Insert, InsertSource, Join, JoinKind, OnConflict,
+    Select, SelectItem, Source, Statement, Update,
+};
+use crate::DbBackend;
+use sea_query::{ColumnType, OptionEnum, Value};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap, HashSet},
+};
+
+/// The tables of a [`MemoryDatabase`](super::MemoryDatabase). Cloned to take a snapshot
+/// when a transaction or savepoint begins.
+#[derive(Debug, Clone)]
+pub(super) struct Database {
+    backend: DbBackend,
+    tables: BTreeMap<String, Table>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Table {
+    name: String,
+    columns: Vec<Column>,
+    /// The columns of the primary key
+    primary_key: Vec<usize>,
+    /// The columns of each unique key, the primary key first
+    unique_keys: Vec<Vec<usize>>,
+    rows: Vec<Vec<Value>>,
+    /// The last value given to an auto-increment column
+    sequence: i128,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Column {
+    name: String,
+    col_type: ColumnType,
+    nullable: bool,
+    auto_increment: bool,
+    default: Option<Expr>,
+    /// Null of the type last written to the column, so that nulls read back into the
+    /// same Rust type as values do
+    null: Value,
+}
+
+/// The rows returned by a statement, with its effect
+#[derive(Debug, Default)]
+pub(super) struct Output {
+    pub rows: Vec<BTreeMap<String, Value>>,
+    pub rows_affected: u64,
+    pub last_insert_id: u64,
+}
+
+#[derive(Debug, Clone)]
+struct Field {
+    /// The table or alias the field can be qualified with
+    table: String,
+    name: String,
+    null: Value,
+}
+
+#[derive(Debug, Default)]
+struct Relation {
+    fields: Vec<Field>,
+    rows: Vec<Vec<Value>>,
+}
+
+/// The row an expression is evaluated against
+#[derive(Clone, Copy)]
+struct Scope<'a> {
+    fields: &'a [Field],
+    row: &'a [Value],
+    /// The rows aggregates run over, when the query is grouped
+    group: Option<&'a [Vec<Value>]>,
+    /// The row of the enclosing query, for correlated subqueries
+    outer: Option<&'a Scope<'a>>,
+}
+
+/// A value as far as comparison and arithmetic are concerned
+#[derive(Debug, Clone, PartialEq)]
+enum Scalar {
+    Null,
+    Bool(bool),
+    Int(i128),
+    Float(f64),
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+/// A value as far as equality is concerned, for keys, grouping and `DISTINCT`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Key {
+    Null,
+    Int(i128),
+    Float(u64),
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl Column {
+    pub(super) fn new(
+        name: String,
+        col_type: ColumnType,
+        nullable: bool,
+        auto_increment: bool,
+        default: Option<Expr>,
+    ) -> Self {
+        let null = template_of(&col_type).unwrap_or(Value::String(None));
+        Self {
+            name,
+            col_type,
+            nullable,
+            auto_increment,
+            default,
+            null,
+        }
+    }
+}
+
+impl Table {
+    /// Create an empty table; keys are given by column names
+    pub(super) fn new(
+        name: String,
+        mut columns: Vec<Column>,
+        primary_key: &[String],
+        unique_keys: &[Vec<String>],
+    ) -> Result<Self, String> {
+        let position = |name: &String| {
+            columns
+                .iter()
+                .position(|c| c.name == *name)
+                .ok_or_else(|| format!("No such column: {name}"))
+        };
+        let primary_key = primary_key
+            .iter()
+            .map(position)
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut keys = Vec::new();
+        if !primary_key.is_empty() {
+            keys.push(primary_key.clone());
+        }
+        for key in unique_keys {
+            let key = key.iter().map(position).collect::<Result<Vec<_>, _>>()?;
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        for &i in &primary_key {
+            columns[i].nullable = false;
+        }
+        Ok(Self {
+            name,
+            columns,
+            primary_key,
+            unique_keys: keys,
+            rows: Vec::new(),
+            sequence: 0,
+        })
+    }
+
+    fn position(&self, name: &str) -> Result<usize, String> {
+        self.columns
+            .iter()
+            .position(|c| c.name == name)
+            .or_else(|| {
+                self.columns
+                    .iter()
+                    .position(|c| c.name.eq_ignore_ascii_case(name))
+            })
+            .ok_or_else(|| format!("No such column: {}.{name}", self.name))
+    }
+
+    fn fields(&self, alias: &str) -> Vec<Field> {
+        self.columns
+            .iter()
+            .map(|c| Field {
+                table: alias.to_owned(),
+                name: c.name.clone(),
+                null: c.null.clone(),
+            })
+            .collect()
+    }
+
+    /// The row with its nulls typed after the columns
+    fn typed(&self, row: &[Value]) -> Vec<Value> {
+        row.iter()
+            .zip(&self.columns)
+            .map(|(value, column)| {
+                if is_null(value) {
+                    column.null.clone()
+                } else {
+                    value.clone()
+                }
+            })
+            .collect()
+    }
+
+    fn relation(&self, alias: &str) -> Relation {
+        Relation {
+            fields: self.fields(alias),
+            rows: self.rows.iter().map(|row| self.typed(row)).collect(),
+        }
+    }
+
+    /// Convert the values of a row to the types of the columns and check they are not
+    /// null where they must not be
+    fn check_row(&mut self, row: Vec<Value>) -> Result<Vec<Value>, String> {
+        let mut checked = Vec::with_capacity(row.len());
+        for (column, value) in self.columns.iter_mut().zip(row) {
+            let value = coerce(value, column);
+            if is_null(&value) {
+                if !column.nullable {
+                    return Err(format!(
+                        "NOT NULL constraint failed: {}.{}",
+                        self.name, column.name
+                    ));
+                }
+            } else {
+                column.null = value.as_null();
+            }
+            checked.push(value);
+        }
+        Ok(checked)
+    }
+
+    /// The row already holding the values `row` has in one of `keys`, and that key
+    fn conflict(
+        &self,
+        row: &[Value],
+        keys: &[&Vec<usize>],
+        skip: Option<usize>,
+    ) -> Option<(usize, Vec<usize>)> {
+        for key in keys {
+            let values: Vec<Key> = key.iter().map(|&i| to_key(&row[i])).collect();
+            if values.contains(&Key::Null) {
+                continue;
+            }
+            for (i, existing) in self.rows.iter().enumerate() {
+                if Some(i) != skip && key.iter().map(|&i| to_key(&existing[i])).eq(values.clone()) {
+                    return Some((i, (*key).clone()));
+                }
+            }
+        }
+        None
+    }
+
+    fn unique_violation(&self, key: &[usize]) -> String {
+        let columns: Vec<String> = key
+            .iter()
+            .map(|&i| format!("{}.{}", self.name, self.columns[i].name))
+            .collect();
+        format!("UNIQUE constraint failed: {}", columns.join(", "))
+    }
+
+    fn check_unique(&self) -> Result<(), String> {
+        for key in &self.unique_keys {
+            let mut seen = HashSet::new();
+            for row in &self.rows {
+                let values: Vec<Key> = key.iter().map(|&i| to_key(&row[i])).collect();
+                if !values.contains(&Key::Null) && !seen.insert(values) {
+                    return Err(self.unique_violation(key));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Relation {
+    fn into_maps(self) -> Vec<BTreeMap<String, Value>> {
+        let Relation { fields, rows } = self;
+        rows.into_iter()
+            .map(|row| {
+                let mut map = BTreeMap::new();
+                for (field, value) in fields.iter().zip(row) {
+                    map.entry(field.name.clone()).or_insert(value);
+                }
+                map
+            })
+            .collect()
+    }
+}
+
+impl<'a> Scope<'a> {
+    fn new(fields: &'a [Field], row: &'a [Value], outer: Option<&'a Scope<'a>>) -> Self {
+        Self {
+            fields,
+            row,
+            group: None,
+            outer,
+        }
+    }
+
+    fn lookup(&self, table: Option<&str>, name: &str) -> Option<Value> {
+        let matches = |field: &Field, exact: bool| {
+            let same = |a: &str, b: &str| {
+                if exact {
+                    a == b
+                } else {
+                    a.eq_ignore_ascii_case(b)
+                }
+            };
+            same(&field.name, name) && table.is_none_or(|table| same(&field.table, table))
+        };
+        let position = self
+            .fields
+            .iter()
+            .position(|field| matches(field, true))
+            .or_else(|| self.fields.iter().position(|field| matches(field, false)));
+        match position {
+            Some(i) => self.row.get(i).cloned(),
+            None => self.outer.and_then(|outer| outer.lookup(table, name)),
+        }
+    }
+}
+
+impl Database {
+    pub(super) fn new(backend: DbBackend) -> Self {
+        Self {
+            backend,
+            tables: BTreeMap::new(),
+        }
+    }
+
+    /// Add `table`, replacing any table of the same name
+    pub(super) fn create_table(&mut self, table: Table) {
+        self.tables.insert(table.name.clone(), table);
+    }
+
+    /// Evaluate an expression that refers to no columns, such as a column default
+    pub(super) fn eval_constant(&self, expr: &Expr) -> Result<Value, String> {
+        self.eval(expr, &Scope::new(&[], &[], None))
+    }
+
+    pub(super) fn execute(&mut self, stmt: &Statement) -> Result<Output, String> {
+        match stmt {
+            Statement::Select(select) => Ok(Output {
+                rows: self.select(select, None)?.into_maps(),
+                ..Default::default()
+            }),
+            Statement::Insert(insert) => self.insert(insert),
+            Statement::Update(update) => self.update(update),
+            Statement::Delete(delete) => self.delete(delete),
+            Statement::Savepoint(_) | Statement::RollbackTo(_) | Statement::Release(_) => {
+                Err("Savepoints are only supported on a connection".to_owned())
+            }
+        }
+    }
+
+    fn table_key(&self, name: &str) -> Result<String, String> {
+        if self.tables.contains_key(name) {
+            return Ok(name.to_owned());
+        }
+        self.tables
+            .keys()
+            .find(|key| key.eq_ignore_ascii_case(name))
+            .cloned()
+            .ok_or_else(|| format!("No such table: {name}"))
+    }
+
+    fn table(&self, name: &str) -> Result<&Table, String> {
+        let key = self.table_key(name)?;
+        Ok(&self.tables[&key])
+    }
+
+    fn select(&self, select: &Select, outer: Option<&Scope<'_>>) -> Result<Relation, String> {
+        let source = self.from(select, outer)?;
+        let fields = source.fields;
+
+        let mut rows = Vec::new();
+        for row in source.rows {
+            if let Some(filter) = &select.filter {
+                let scope = Scope::new(&fields, &row, outer);
+                if truth(&self.eval(filter, &scope)?) != Some(true) {
+                    continue;
+                }
+            }
+            rows.push(row);
+        }
+
+        let grouped = !select.group_by.is_empty()
+            || select
+                .items
+                .iter()
+                .any(|item| matches!(item, SelectItem::Expr(expr, _) if has_aggregate(expr)))
+            || select.having.as_ref().is_some_and(has_aggregate)
+            || select
+                .order_by
+                .iter()
+                .any(|order| has_aggregate(&order.expr));
+        let groups: Vec<Vec<Vec<Value>>> = if !grouped {
+            rows.into_iter().map(|row| vec![row]).collect()
+        } else if select.group_by.is_empty() {
+            vec![rows]
+        } else {
+            let mut groups: Vec<Vec<Vec<Value>>> = Vec::new();
+            let mut index: HashMap<Vec<Key>, usize> = HashMap::new();
+            for row in rows {
+                let scope = Scope::new(&fields, &row, outer);
+                let key = select
+                    .group_by
+                    .iter()
+                    .map(|expr| self.eval(expr, &scope).map(|value| to_key(&value)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                match index.get(&key) {
+                    Some(&i) => groups[i].push(row),
+                    None => {
+                        index.insert(key, groups.len());
+                        groups.push(vec![row]);
+                    }
+                }
+            }
+            groups
+        };
+
+        let mut output_fields = output_fields(&select.items, &fields);
+        let null_row: Vec<Value> = fields.iter().map(|field| field.null.clone()).collect();
+        let mut output: Vec<(Vec<Value>, Vec<Value>)> = Vec::new();
+        for group in &groups {
+            let scope = Scope {
+                fields: &fields,
+                row: group.first().unwrap_or(&null_row),
+                group: grouped.then_some(group.as_slice()),
+                outer,
+            };
+            if let Some(having) = &select.having
+                && truth(&self.eval(having, &scope)?) != Some(true)
+            {
+                continue;
+            }
+            let values = self.project(&select.items, &scope)?;
+            let mut sort_keys = Vec::new();
+            for order in &select.order_by {
+                let output_position = match &order.expr {
+                    Expr::Column(None, name) => output_fields.iter().position(|f| f.name == *name),
+                    _ => None,
+                };
+                sort_keys.push(match output_position {
+                    Some(i) => values[i].clone(),
+                    None => self.eval(&order.expr, &scope)?,
+                });
+            }
+            output.push((values, sort_keys));
+        }
+
+        if select.distinct {
+            let mut seen = HashSet::new();
+            output.retain(|(values, _)| seen.insert(values.iter().map(to_key).collect::<Vec<_>>()));
+        }
+        if !select.order_by.is_empty() {
+            output.sort_by(|(_, a), (_, b)| {
+                for (order, (a, b)) in select.order_by.iter().zip(a.iter().zip(b)) {
+                    let nulls_first = order.nulls_first.unwrap_or(match self.backend {
+                        DbBackend::Postgres => order.desc,
+                        _ => !order.desc,
+                    });
+                    let ordering = match (is_null(a), is_null(b)) {
+                        (true, true) => Ordering::Equal,
+                        (true, false) if nulls_first => Ordering::Less,
+                        (true, false) => Ordering::Greater,
+                        (false, true) if nulls_first => Ordering::Greater,
+                        (false, true) => Ordering::Less,
+                        (false, false) => {
+                            let ordering = compare(a, b).unwrap_or(Ordering::Equal);
+                            if order.desc {
+                                ordering.reverse()
+                            } else {
+                                ordering
+                            }
+                        }
+                    };
+                    if ordering != Ordering::Equal {
+                        return ordering;
+                    }
+                }
+                Ordering::Equal
+            });
+        }
+
+        let offset = self.count(select.offset.as_ref(), outer)?.unwrap_or(0);
+        let limit = self
+            .count(select.limit.as_ref(), outer)?
+            .unwrap_or(usize::MAX);
+        let rows: Vec<Vec<Value>> = output
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .map(|(values, _)| values)
+            .collect();
+
+        for (i, field) in output_fields.iter_mut().enumerate() {
+            if let Some(value) = rows.iter().map(|row| &row[i]).find(|value| !is_null(value)) {
+                field.null = value.as_null();
+            }
+        }
+        Ok(Relation {
+            fields: output_fields,
+            rows,
+        })
+    }
+
+    /// The rows of `FROM` and its joins, combined
+    fn from(&self, select: &Select, outer: Option<&Scope<'_>>) -> Result<Relation, String> {
+        let Some((first, joins)) = select.from.split_first() else {
+            return Ok(Relation {
+                fields: Vec::new(),
+                rows: vec![Vec::new()],
+            });
+        };
+        let mut relation = self.source(first, outer)?;
+        for join in joins {
+            let right = self.source(join, outer)?;
+            relation = self.join(relation, right, join, outer)?;
+        }
+        Ok(relation)
+    }
+
+    fn source(&self, join: &Join, outer: Option<&Scope<'_>>) -> Result<Relation, String> {
+        match &join.source {
+            Source::Table(name) => Ok(self.table(name)?.relation(&join.alias)),
+            Source::Subquery(select) => {
+                let mut relation = self.select(select, outer)?;
+                for field in &mut relation.fields {
+                    field.table = join.alias.clone();
+                }
+                Ok(relation)
+            }
+        }
+    }
+
+    fn join(
+        &self,
+        left: Relation,
+        right: Relation,
+        join: &Join,
+        outer: Option<&Scope<'_>>,
+    ) -> Result<Relation, String> {
+        let mut fields = left.fields.clone();
+        fields.extend(right.fields.iter().cloned());
+        let left_nulls: Vec<Value> = left.fields.iter().map(|f| f.null.clone()).collect();
+        let right_nulls: Vec<Value> = right.fields.iter().map(|f| f.null.clone()).collect();
+
+        let mut rows = Vec::new();
+        let mut right_matched = vec![false; right.rows.len()];
+        for l in &left.rows {
+            let mut matched = false;
+            for (i, r) in right.rows.iter().enumerate() {
+                let row: Vec<Value> = l.iter().chain(r).cloned().collect();
+                let hit = match &join.on {
+                    Some(on) => {
+                        truth(&self.eval(on, &Scope::new(&fields, &row, outer))?) == Some(true)
+                    }
+                    None => true,
+                };
+                if hit {
+                    matched = true;
+                    right_matched[i] = true;
+                    rows.push(row);
+                }
+            }
+            if !matched && join.kind == JoinKind::Left {
+                rows.push(l.iter().chain(&right_nulls).cloned().collect());
+            }
+        }
+        if join.kind == JoinKind::Right {
+            for (r, matched) in right.rows.iter().zip(right_matched) {
+                if !matched {
+                    rows.push(left_nulls.iter().chain(r).cloned().collect());
+                }
+            }
+        }
+        Ok(Relation { fields, rows })
+    }
+
+    fn project(&self, items: &[SelectItem], scope: &Scope<'_>) -> Result<Vec<Value>, String> {
+        let mut values = Vec::new();
+        for item in items {
+            match item {
+                SelectItem::Wildcard(table) => {
+                    for (field, value) in scope.fields.iter().zip(scope.row) {
+                        if table
+                            .as_deref()
+                            .is_none_or(|table| field.table.eq_ignore_ascii_case(table))
+                        {
+                            values.push(value.clone());
+                        }
+                    }
+                }
+                SelectItem::Expr(expr, _) => values.push(self.eval(expr, scope)?),
+            }
+        }
+        Ok(values)
+    }
+
+    /// Evaluate a `LIMIT` or `OFFSET`
+    fn count(
+        &self,
+        expr: Option<&Expr>,
+        outer: Option<&Scope<'_>>,
+    ) -> Result<Option<usize>, String> {
+        let Some(expr) = expr else {
+            return Ok(None);
+        };
+        match scalar(&self.eval(expr, &Scope::new(&[], &[], outer))?) {
+            Scalar::Null => Ok(None),
+            Scalar::Int(n) => Ok(Some(usize::try_from(n.max(0)).unwrap_or(usize::MAX))),
+            other => Err(format!("Expected an integer, found {other:?}")),
+        }
+    }
+
+    fn returning(
+        &self,
+        items: &[SelectItem],
+        fields: &[Field],
+        row: &[Value],
+    ) -> Result<BTreeMap<String, Value>, String> {
+        let values = self.project(items, &Scope::new(fields, row, None))?;
+        let relation = Relation {
+            fields: output_fields(items, fields),
+            rows: vec![values],
+        };
+        Ok(relation.into_maps().remove(0))
+    }
+
+    /// Fill in the columns of a row not given a value, with the next value of an
+    /// auto-increment column or the column default
+    fn build_row(
+        &self,
+        table: &mut Table,
+        values: Vec<Option<Value>>,
+    ) -> Result<Vec<Value>, String> {
+        let mut row = Vec::with_capacity(values.len());
+        for (i, value) in values.into_iter().enumerate() {
+            let column = &table.columns[i];
+            let value = match value {
+                Some(value) if !(column.auto_increment && is_null(&value)) => {
+                    if column.auto_increment
+                        && let Scalar::Int(n) = scalar(&value)
+                    {
+                        table.sequence = table.sequence.max(n);
+                    }
+                    value
+                }
+                _ if column.auto_increment => {
+                    table.sequence += 1;
+                    int_like(&column.null, table.sequence)
+                }
+                _ => match &column.default {
+                    Some(default) => self.eval_constant(default)?,
+                    None => Value::String(None),
+                },
+            };
+            row.push(value);
+        }
+        table.check_row(row)
+    }
+
+    fn insert(&mut self, insert: &Insert) -> Result<Output, String> {
+        let key = self.table_key(&insert.table)?;
+        let mut table = self.tables[&key].clone();
+        let positions = if insert.columns.is_empty() {
+            (0..table.columns.len()).collect()
+        } else {
+            insert
+                .columns
+                .iter()
+                .map(|column| table.position(column))
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        let width = table.columns.len();
+        let sources: Vec<Vec<Option<Value>>> = match &insert.source {
+            InsertSource::Values(rows) => {
+                let scope = Scope::new(&[], &[], None);
+                let mut sources = Vec::new();
+                for row in rows {
+                    // `DEFAULT VALUES`
+                    if row.is_empty() {
+                        sources.push(Vec::new());
+                        continue;
+                    }
+                    let mut values = Vec::with_capacity(row.len());
+                    for expr in row {
+                        values.push(match expr {
+                            Expr::Default => None,
+                            expr => Some(self.eval(expr, &scope)?),
+                        });
+                    }
+                    sources.push(values);
+                }
+                sources
+            }
+            InsertSource::Select(select) => self
+                .select(select, None)?
+                .rows
+                .into_iter()
+                .map(|row| row.into_iter().map(Some).collect())
+                .collect(),
+        };
+        let mut proposed = Vec::with_capacity(sources.len());
+        for values in sources {
+            let mut row = vec![None; width];
+            if !values.is_empty() {
+                if values.len() != positions.len() {
+                    return Err(format!(
+                        "{} values for {} columns",
+                        values.len(),
+                        positions.len()
+                    ));
+                }
+                for (&i, value) in positions.iter().zip(values) {
+                    row[i] = value;
+                }
+            }
+            proposed.push(row);
+        }
+
+        let mut fields = table.fields(&key);
+        fields.extend(table.fields("excluded"));
+        let mut output = Output::default();
+        for values in proposed {
+            let row = self.build_row(&mut table, values)?;
+            let target: Vec<&Vec<usize>> = match &insert.on_conflict {
+                Some(OnConflict { target, .. }) if !target.is_empty() => {
+                    let target = target
+                        .iter()
+                        .map(|column| table.position(column))
+                        .collect::<Result<HashSet<_>, _>>()?;
+                    table
+                        .unique_keys
+                        .iter()
+                        .filter(|key| key.iter().copied().collect::<HashSet<_>>() == target)
+                        .collect()
+                }
+                _ => table.unique_keys.iter().collect(),
+            };
+            let conflict = match &insert.on_conflict {
+                Some(_) => table.conflict(&row, &target, None),
+                None => None,
+            };
+            let row = match (conflict, &insert.on_conflict) {
+                (
+                    Some(_),
+                    Some(OnConflict {
+                        action: ConflictAction::Nothing,
+                        ..
+                    }),
+                ) => continue,
+                (
+                    Some((existing, _)),
+                    Some(OnConflict {
+                        action: ConflictAction::Update { set, filter },
+                        ..
+                    }),
+                ) => {
+                    let current: Vec<Value> = table
+                        .typed(&table.rows[existing])
+                        .into_iter()
+                        .chain(table.typed(&row))
+                        .collect();
+                    let scope = Scope::new(&fields, &current, None);
+                    if let Some(filter) = filter
+                        && truth(&self.eval(filter, &scope)?) != Some(true)
+                    {
+                        continue;
+                    }
+                    let mut updated = table.rows[existing].clone();
+                    for (column, expr) in set {
+                        let i = table.position(column)?;
+                        updated[i] = self.eval(expr, &scope)?;
+                    }
+                    let updated = table.check_row(updated)?;
+                    if let Some((_, key)) = table.conflict(
+                        &updated,
+                        &table.unique_keys.iter().collect::<Vec<_>>(),
+                        Some(existing),
+                    ) {
+                        return Err(table.unique_violation(&key));
+                    }
+                    table.rows[existing] = updated.clone();
+                    updated
+                }
+                _ => {
+                    let keys: Vec<&Vec<usize>> = table.unique_keys.iter().collect();
+                    if let Some((_, key)) = table.conflict(&row, &keys, None) {
+                        return Err(table.unique_violation(&key));
+                    }
+                    table.rows.push(row.clone());
+                    row
+                }
+            };
+            output.rows_affected += 1;
+            if let [pk] = table.primary_key[..]
+                && let Scalar::Int(id) = scalar(&row[pk])
+            {
+                output.last_insert_id = u64::try_from(id).unwrap_or_default();
+            }
+            if let Some(items) = &insert.returning {
+                let row = table.typed(&row);
+                output
+                    .rows
+                    .push(self.returning(items, &fields[..table.columns.len()], &row)?);
+            }
+        }
+        self.tables.insert(key, table);
+        Ok(output)
+    }
+
+    fn update(&mut self, update: &Update) -> Result<Output, String> {
+        let key = self.table_key(&update.table)?;
+        let mut table = self.tables[&key].clone();
+        let fields = table.fields(&update.alias);
+        let set = update
+            .set
+            .iter()
+            .map(|(column, expr)| Ok((table.position(column)?, expr)))
+            .collect::<Result<Vec<_>, String>>()?;
+        let limit = self
+            .count(update.limit.as_ref(), None)?
+            .unwrap_or(usize::MAX);
+
+        let mut changed = Vec::new();
+        for (i, row) in table.relation(&update.alias).rows.into_iter().enumerate() {
+            if changed.len() >= limit {
+                break;
+            }
+            let scope = Scope::new(&fields, &row, None);
+            if let Some(filter) = &update.filter
+                && truth(&self.eval(filter, &scope)?) != Some(true)
+            {
+                continue;
+            }
+            let mut updated = row.clone();
+            for (position, expr) in &set {
+                updated[*position] = self.eval(expr, &scope)?;
+            }
+            changed.push((i, updated));
+        }
+
+        let mut output = Output::default();
+        for (i, row) in changed {
+            let row = table.check_row(row)?;
+            table.rows[i] = row.clone();
+            output.rows_affected += 1;
+            if let Some(items) = &update.returning {
+                output
+                    .rows
+                    .push(self.returning(items, &fields, &table.typed(&row))?);
+            }
+        }
+        table.check_unique()?;
+        self.tables.insert(key, table);
+        Ok(output)
+    }
+
+    fn delete(&mut self, delete: &Delete) -> Result<Output, String> {
+        let key = self.table_key(&delete.table)?;
+        let mut table = self.tables[&key].clone();
+        let fields = table.fields(&delete.alias);
+        let limit = self
+            .count(delete.limit.as_ref(), None)?
+            .unwrap_or(usize::MAX);
+
+        let mut output = Output::default();
+        let mut kept = Vec::new();
+        let typed_rows = table.relation(&delete.alias).rows;
+        for (row, typed) in std::mem::take(&mut table.rows).into_iter().zip(typed_rows) {
+            let matched = output.rows_affected < limit as u64
+                && match &delete.filter {
+                    Some(filter) => {
+                        truth(&self.eval(filter, &Scope::new(&fields, &typed, None))?) == Some(true)
+                    }
+                    None => true,
+                };
+            if !matched {
+                kept.push(row);
+                continue;
+            }
+            output.rows_affected += 1;
+            if let Some(items) = &delete.returning {
+                output.rows.push(self.returning(items, &fields, &typed)?);
+            }
+        }
+        table.rows = kept;
+        self.tables.insert(key, table);
+        Ok(output)
+    }
+
+    fn eval(&self, expr: &Expr, scope: &Scope<'_>) -> Result<Value, String> {
+        match expr {
+            Expr::Column(table, name) => {
+                scope
+                    .lookup(table.as_deref(), name)
+                    .ok_or_else(|| match table {
+                        Some(table) => format!("No such column: {table}.{name}"),
+                        None => format!("No such column: {name}"),
+                    })
+            }
+            Expr::Value(value) => Ok(value.clone()),
+            Expr::Default => Err("DEFAULT is only allowed in VALUES".to_owned()),
+            Expr::Not(expr) => Ok(Value::Bool(truth(&self.eval(expr, scope)?).map(|b| !b))),
+            Expr::Neg(expr) => {
+                let value = self.eval(expr, scope)?;
+                match scalar(&value) {
+                    Scalar::Null => Ok(value),
+                    Scalar::Int(n) => Ok(int_like(&value, -n)),
+                    Scalar::Float(n) => Ok(float_like(&value, -n)),
+                    _ => Err(format!("Cannot negate {value:?}")),
+                }
+            }
+            Expr::Binary(lhs, op, rhs) => self.binary(lhs, *op, rhs, scope),
+            Expr::IsNull(expr, negated) => Ok(Value::Bool(Some(
+                is_null(&self.eval(expr, scope)?) != *negated,
+            ))),
+            Expr::InList(expr, list, negated) => {
+                let lhs = self.eval_row(expr, scope)?;
+                let mut found = Some(false);
+                for item in list {
+                    match row_cmp(&lhs, &self.eval_row(item, scope)?) {
+                        Some(Ordering::Equal) => {
+                            found = Some(true);
+                            break;
+                        }
+                        None => found = None,
+                        Some(_) => {}
+                    }
+                }
+                Ok(Value::Bool(found.map(|found| found != *negated)))
+            }
+            Expr::InSubquery(expr, select, negated) => {
+                let lhs = self.eval_row(expr, scope)?;
+                let mut found = Some(false);
+                for row in self.select(select, Some(scope))?.rows {
+                    match row_cmp(&lhs, &row) {
+                        Some(Ordering::Equal) => {
+                            found = Some(true);
+                            break;
+                        }
+                        None => found = None,
+                        Some(_) => {}
+                    }
+                }
+                Ok(Value::Bool(found.map(|found| found != *negated)))
+            }
+            Expr::Between(expr, low, high, negated) => {
+                let value = self.eval(expr, scope)?;
+                let low = compare(&value, &self.eval(low, scope)?);
+                let high = compare(&value, &self.eval(high, scope)?);
+                let between = match (low, high) {
+                    (Some(low), Some(high)) => {
+                        Some(low != Ordering::Less && high != Ordering::Greater)
+                    }
+                    _ => None,
+                };
+                Ok(Value::Bool(between.map(|between| between != *negated)))
+            }
+            Expr::Like {
+                expr,
+                pattern,
+                escape,
+                negated,
+                case_insensitive,
+            } => {
+                let escape = match escape {
+                    Some(escape) => text(&self.eval(escape, scope)?).and_then(|e| e.chars().next()),
+                    None if self.backend == DbBackend::Sqlite => None,
+                    None => Some('\\'),
+                };
+                let case_insensitive = *case_insensitive || self.backend != DbBackend::Postgres;
+                let value = text(&self.eval(expr, scope)?);
+                let pattern = text(&self.eval(pattern, scope)?);
+                Ok(Value::Bool(match (value, pattern) {
+                    (Some(value), Some(pattern)) => {
+                        Some(like(&value, &pattern, escape, case_insensitive) != *negated)
+                    }
+                    _ => None,
+                }))
+            }
+            Expr::Function {
+                name,
+                args,
+                distinct,
+            } => self.function(name, args, *distinct, scope),
+            Expr::Cast(expr, type_name) => Ok(cast(self.eval(expr, scope)?, type_name)),
+            Expr::Exists(select) => Ok(Value::Bool(Some(
+                !self.select(select, Some(scope))?.rows.is_empty(),
+            ))),
+            Expr::Subquery(select) => {
+                let relation = self.select(select, Some(scope))?;
+                let null = relation
+                    .fields
+                    .first()
+                    .map(|field| field.null.clone())
+                    .unwrap_or(Value::String(None));
+                Ok(relation
+                    .rows
+                    .into_iter()
+                    .next()
+                    .and_then(|row| row.into_iter().next())
+                    .unwrap_or(null))
+            }
+            Expr::Tuple(_) => Err("A row value is not allowed here".to_owned()),
+            Expr::Case {
+                operand,
+                branches,
+                otherwise,
+            } => {
+                let operand = match operand {
+                    Some(operand) => Some(self.eval(operand, scope)?),
+                    None => None,
+                };
+                for (condition, result) in branches {
+                    let condition = self.eval(condition, scope)?;
+                    let hit = match &operand {
+                        Some(operand) => compare(operand, &condition) == Some(Ordering::Equal),
+                        None => truth(&condition) == Some(true),
+                    };
+                    if hit {
+                        return self.eval(result, scope);
+                    }
+                }
+                match otherwise {
+                    Some(otherwise) => self.eval(otherwise, scope),
+                    None => Ok(Value::String(None)),
+                }
+            }
+        }
+    }
+
+    /// Evaluate an expression that may be a row value, such as `("a", "b")`
+    fn eval_row(&self, expr: &Expr, scope: &Scope<'_>) -> Result<Vec<Value>, String> {
+        match expr {
+            Expr::Tuple(exprs) => exprs.iter().map(|expr| self.eval(expr, scope)).collect(),
+            expr => Ok(vec![self.eval(expr, scope)?]),
+        }
+    }
+
+    fn binary(
+        &self,
+        lhs: &Expr,
+        op: BinaryOp,
+        rhs: &Expr,
+        scope: &Scope<'_>,
+    ) -> Result<Value, String> {
+        match op {
+            BinaryOp::And => {
+                let lhs = truth(&self.eval(lhs, scope)?);
+                if lhs == Some(false) {
+                    return Ok(Value::Bool(Some(false)));
+                }
+                let rhs = truth(&self.eval(rhs, scope)?);
+                Ok(Value::Bool(match (lhs, rhs) {
+                    (_, Some(false)) => Some(false),
+                    (Some(true), Some(true)) => Some(true),
+                    _ => None,
+                }))
+            }
+            BinaryOp::Or => {
+                let lhs = truth(&self.eval(lhs, scope)?);
+                if lhs == Some(true) {
+                    return Ok(Value::Bool(Some(true)));
+                }
+                let rhs = truth(&self.eval(rhs, scope)?);
+                Ok(Value::Bool(match (lhs, rhs) {
+                    (_, Some(true)) => Some(true),
+                    (Some(false), Some(false)) => Some(false),
+                    _ => None,
+                }))
+            }
+            BinaryOp::Eq
+            | BinaryOp::NotEq
+            | BinaryOp::Lt
+            | BinaryOp::LtEq
+            | BinaryOp::Gt
+            | BinaryOp::GtEq => {
+                let ordering = row_cmp(&self.eval_row(lhs, scope)?, &self.eval_row(rhs, scope)?);
+                Ok(Value::Bool(ordering.map(|ordering| match op {
+                    BinaryOp::Eq => ordering == Ordering::Equal,
+                    BinaryOp::NotEq => ordering != Ordering::Equal,
+                    BinaryOp::Lt => ordering == Ordering::Less,
+                    BinaryOp::LtEq => ordering != Ordering::Greater,
+                    BinaryOp::Gt => ordering == Ordering::Greater,
+                    _ => ordering != Ordering::Less,
+                })))
+            }
+            BinaryOp::Is | BinaryOp::IsNot => {
+                let lhs = self.eval(lhs, scope)?;
+                let rhs = self.eval(rhs, scope)?;
+                let same = match (is_null(&lhs), is_null(&rhs)) {
+                    (true, true) => true,
+                    (false, false) => compare(&lhs, &rhs) == Some(Ordering::Equal),
+                    _ => false,
+                };
+                Ok(Value::Bool(Some(same != (op == BinaryOp::IsNot))))
+            }
+            BinaryOp::Concat => {
+                let lhs = self.eval(lhs, scope)?;
+                let rhs = self.eval(rhs, scope)?;
+                Ok(Value::String(match (text(&lhs), text(&rhs)) {
+                    (Some(lhs), Some(rhs)) => Some(lhs + &rhs),
+                    _ => None,
+                }))
+            }
+            BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => {
+                arithmetic(op, &self.eval(lhs, scope)?, &self.eval(rhs, scope)?)
+            }
+        }
+    }
+
+    fn function(
+        &self,
+        name: &str,
+        args: &[Expr],
+        distinct: bool,
+        scope: &Scope<'_>,
+    ) -> Result<Value, String> {
+        if is_aggregate(name) {
+            return self.aggregate(name, args, distinct, scope);
+        }
+        if name == "values" {
+            // MySQL's `VALUES(col)` in `ON DUPLICATE KEY UPDATE`
+            return match args {
+                [Expr::Column(_, column)] => scope
+                    .lookup(Some("excluded"), column)
+                    .ok_or_else(|| format!("No such column: {column}")),
+                _ => Err("VALUES() takes a column".to_owned()),
+            };
+        }
+        let values = args
+            .iter()
+            .map(|arg| self.eval(arg, scope))
+            .collect::<Result<Vec<_>, _>>()?;
+        let first = values.first().cloned().unwrap_or(Value::String(None));
+        match name {
+            "coalesce" | "ifnull" => Ok(values
+                .iter()
+                .find(|value| !is_null(value))
+                .or(values.last())
+                .cloned()
+                .unwrap_or(Value::String(None))),
+            "nullif" => match values.get(1) {
+                Some(other) if compare(&first, other) == Some(Ordering::Equal) => {
+                    Ok(first.as_null())
+                }
+                _ => Ok(first),
+            },
+            "lower" => Ok(Value::String(text(&first).map(|s| s.to_lowercase()))),
+            "upper" => Ok(Value::String(text(&first).map(|s| s.to_uppercase()))),
+            "trim" => Ok(Value::String(text(&first).map(|s| s.trim().to_owned()))),
+            "length" | "char_length" | "character_length" => Ok(Value::BigInt(
+                text(&first).map(|s| s.chars().count() as i64),
+            )),
+            "abs" => match scalar(&first) {
+                Scalar::Int(n) => Ok(int_like(&first, n.abs())),
+                Scalar::Float(n) => Ok(float_like(&first, n.abs())),
+                _ => Ok(first),
+            },
+            "concat" => Ok(Value::String(Some(
+                values.iter().filter_map(text).collect::<String>(),
+            ))),
+            "now" | "current_timestamp" | "localtimestamp" => now(),
+            _ => Err(format!("Unsupported function {}()", name.to_uppercase())),
+        }
+    }
+
+    fn aggregate(
+        &self,
+        name: &str,
+        args: &[Expr],
+        distinct: bool,
+        scope: &Scope<'_>,
+    ) -> Result<Value, String> {
+        let rows = scope
+            .group
+            .ok_or_else(|| format!("{}() is not allowed here", name.to_uppercase()))?;
+        let mut values = Vec::new();
+        for row in rows {
+            let scope = Scope { row, ..*scope };
+            match args.first() {
+                Some(arg) => values.push(self.eval(arg, &scope)?),
+                // `COUNT(*)`
+                None => values.push(Value::BigInt(Some(1))),
+            }
+        }
+        values.retain(|value| !is_null(value));
+        if distinct {
+            let mut seen = HashSet::new();
+            values.retain(|value| seen.insert(to_key(value)));
+        }
+        match name {
+            "count" => Ok(Value::BigInt(Some(values.len() as i64))),
+            "sum" | "total" => {
+                let mut values = values.into_iter();
+                let Some(first) = values.next() else {
+                    return Ok(Value::BigInt(None));
+                };
+                let mut sum = if is_integer(&first) {
+                    convert(first, &Value::BigInt(None))
+                } else {
+                    first
+                };
+                for value in values {
+                    sum = arithmetic(BinaryOp::Add, &sum, &value)?;
+                }
+                Ok(sum)
+            }
+            "avg" => {
+                let numbers: Vec<f64> = values
+                    .iter()
+                    .filter_map(|value| number(&scalar(value)))
+                    .collect();
+                Ok(Value::Double(if numbers.is_empty() {
+                    None
+                } else {
+                    Some(numbers.iter().sum::<f64>() / numbers.len() as f64)
+                }))
+            }
+            "min" | "max" => {
+                let mut values = values.into_iter();
+                let mut best = match values.next() {
+                    Some(value) => value,
+                    None => return Ok(Value::String(None)),
+                };
+                let wanted = if name == "min" {
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                };
+                for value in values {
+                    if compare(&value, &best) == Some(wanted) {
+                        best = value;
+                    }
+                }
+                Ok(best)
+            }
+            "group_concat" | "string_agg" => {
+                let separator = match args.get(1) {
+                    Some(separator) => text(&self.eval(separator, scope)?).unwrap_or_default(),
+                    None => ",".to_owned(),
+                };
+                Ok(Value::String(if values.is_empty() {
+                    None
+                } else {
+                    Some(
+                        values
+                            .iter()
+                            .filter_map(text)
+                            .collect::<Vec<_>>()
+                            .join(&separator),
+                    )
+                }))
+            }
+            _ => Err(format!("Unsupported aggregate {}()", name.to_uppercase())),
+        }
+    }
+}
+
+fn is_aggregate(name: &str) -> bool {
+    matches!(
+        name,
+        "count" | "sum" | "total" | "avg" | "min" | "max" | "group_concat" | "string_agg"
+    )
+}
+
+fn has_aggregate(expr: &Expr) -> bool {
+    match expr {
+        Expr::Function { name, args, .. } => is_aggregate(name) || args.iter().any(has_aggregate),
+        Expr::Not(expr) | Expr::Neg(expr) | Expr::IsNull(expr, _) | Expr::Cast(expr, _) => {
+            has_aggregate(expr)
+        }
+        Expr::Binary(lhs, _, rhs) => has_aggregate(lhs) || has_aggregate(rhs),
+        Expr::InList(expr, list, _) => has_aggregate(expr) || list.iter().any(has_aggregate),
+        Expr::InSubquery(expr, _, _) => has_aggregate(expr),
+        Expr::Between(expr, low, high, _) => {
+            has_aggregate(expr) || has_aggregate(low) || has_aggregate(high)
+        }
+        Expr::Like { expr, pattern, .. } => has_aggregate(expr) || has_aggregate(pattern),
+        Expr::Tuple(exprs) => exprs.iter().any(has_aggregate),
+        Expr::Case {
+            operand,
+            branches,
+            otherwise,
+        } => {
+            operand.as_deref().is_some_and(has_aggregate)
+                || branches
+                    .iter()
+                    .any(|(condition, result)| has_aggregate(condition) || has_aggregate(result))
+                || otherwise.as_deref().is_some_and(has_aggregate)
+        }
+        Expr::Column(..) | Expr::Value(_) | Expr::Default | Expr::Exists(_) | Expr::Subquery(_) => {
+            false
+        }
+    }
+}
+
+/// The fields a list of select items produces
+fn output_fields(items: &[SelectItem], fields: &[Field]) -> Vec<Field> {
+    let mut output = Vec::new();
+    for item in items {
+        match item {
+            SelectItem::Wildcard(table) => output.extend(
+                fields
+                    .iter()
+                    .filter(|field| {
+                        table
+                            .as_deref()
+                            .is_none_or(|table| field.table.eq_ignore_ascii_case(table))
+                    })
+                    .cloned(),
+            ),
+            SelectItem::Expr(expr, alias) => {
+                let (name, null) = match expr {
+                    Expr::Column(table, name) => {
+                        let null = fields
+                            .iter()
+                            .find(|field| {
+                                field.name.eq_ignore_ascii_case(name)
+                                    && table
+                                        .as_deref()
+                                        .is_none_or(|table| field.table.eq_ignore_ascii_case(table))
+                            })
+                            .map(|field| field.null.clone());
+                        (name.clone(), null)
+                    }
+                    Expr::Function { name, .. } => (name.clone(), None),
+                    _ => ("?column?".to_owned(), None),
+                };
+                output.push(Field {
+                    table: String::new(),
+                    name: alias.clone().unwrap_or(name),
+                    null: null.unwrap_or(Value::String(None)),
+                });
+            }
+        }
+    }
+    output
+}
+
+fn is_null(value: &Value) -> bool {
+    *value == value.as_null()
+}
+
+fn is_integer(value: &Value) -> bool {
+    matches!(
+        value,
+        Value::TinyInt(_)
+            | Value::SmallInt(_)
+            | Value::Int(_)
+            | Value::BigInt(_)
+            | Value::TinyUnsigned(_)
+            | Value::SmallUnsigned(_)
+            | Value::Unsigned(_)
+            | Value::BigUnsigned(_)
+    )
+}
+
+fn scalar(value: &Value) -> Scalar {
+    match value {
+        Value::Bool(Some(v)) => Scalar::Bool(*v),
+        Value::TinyInt(Some(v)) => Scalar::Int((*v).into()),
+        Value::SmallInt(Some(v)) => Scalar::Int((*v).into()),
+        Value::Int(Some(v)) => Scalar::Int((*v).into()),
+        Value::BigInt(Some(v)) => Scalar::Int((*v).into()),
+        Value::TinyUnsigned(Some(v)) => Scalar::Int((*v).into()),
+        Value::SmallUnsigned(Some(v)) => Scalar::Int((*v).into()),
+        Value::Unsigned(Some(v)) => Scalar::Int((*v).into()),
+        Value::BigUnsigned(Some(v)) => Scalar::Int((*v).into()),
+        Value::Float(Some(v)) => Scalar::Float((*v).into()),
+        Value::Double(Some(v)) => Scalar::Float(*v),
+        Value::String(Some(v)) => Scalar::Text(v.clone()),
+        Value::Char(Some(v)) => Scalar::Text(v.to_string()),
+        Value::Enum(OptionEnum::Some(v)) => Scalar::Text(v.value.to_string()),
+        Value::Bytes(Some(v)) => Scalar::Bytes(v.clone()),
+        value if is_null(value) => Scalar::Null,
+        // other values compare by their literal, which sorts as they do
+        value => {
+            let literal = value.to_string();
+            match literal
+                .strip_prefix('\'')
+                .and_then(|s| s.strip_suffix('\''))
+            {
+                Some(text) => Scalar::Text(text.replace("''", "'")),
+                None => literal
+                    .parse()
+                    .map(Scalar::Int)
+                    .or_else(|_| literal.parse().map(Scalar::Float))
+                    .unwrap_or(Scalar::Text(literal)),
+            }
+        }
+    }
+}
+
+fn number(scalar: &Scalar) -> Option<f64> {
+    match scalar {
+        Scalar::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+        Scalar::Int(n) => Some(*n as f64),
+        Scalar::Float(n) => Some(*n),
+        _ => None,
+    }
+}
+
+fn to_key(value: &Value) -> Key {
+    match scalar(value) {
+        Scalar::Null => Key::Null,
+        Scalar::Bool(b) => Key::Int(b.into()),
+        Scalar::Int(n) => Key::Int(n),
+        Scalar::Float(n) if n.fract() == 0.0 && n.abs() < 1e30 => Key::Int(n as i128),
+        Scalar::Float(n) => Key::Float(n.to_bits()),
+        Scalar::Text(s) => Key::Text(s),
+        Scalar::Bytes(b) => Key::Bytes(b),
+    }
+}
+
+fn truth(value: &Value) -> Option<bool> {
+    match scalar(value) {
+        Scalar::Null => None,
+        Scalar::Bool(b) => Some(b),
+        Scalar::Int(n) => Some(n != 0),
+        Scalar::Float(n) => Some(n != 0.0),
+        Scalar::Text(s) => Some(matches!(
+            s.to_lowercase().as_str(),
+            "t" | "true" | "y" | "yes" | "on" | "1"
+        )),
+        Scalar::Bytes(b) => Some(!b.is_empty()),
+    }
+}
+
+fn text(value: &Value) -> Option<String> {
+    match scalar(value) {
+        Scalar::Null => None,
+        Scalar::Bool(b) => Some(b.to_string()),
+        Scalar::Int(n) => Some(n.to_string()),
+        Scalar::Float(n) => Some(n.to_string()),
+        Scalar::Text(s) => Some(s),
+        Scalar::Bytes(b) => Some(String::from_utf8_lossy(&b).into_owned()),
+    }
+}
+
+/// Order two values, numbers of any type by their value; `None` if either is null
+fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    let rank = |scalar: &Scalar| match scalar {
+        Scalar::Null => 0,
+        Scalar::Bool(_) | Scalar::Int(_) | Scalar::Float(_) => 1,
+        Scalar::Text(_) => 2,
+        Scalar::Bytes(_) => 3,
+    };
+    match (scalar(a), scalar(b)) {
+        (Scalar::Null, _) | (_, Scalar::Null) => None,
+        (Scalar::Int(a), Scalar::Int(b)) => Some(a.cmp(&b)),
+        (Scalar::Text(a), Scalar::Text(b)) => Some(a.cmp(&b)),
+        (Scalar::Bytes(a), Scalar::Bytes(b)) => Some(a.cmp(&b)),
+        (a, b) => match (number(&a), number(&b)) {
+            (Some(a), Some(b)) => a.partial_cmp(&b),
+            _ => Some(rank(&a).cmp(&rank(&b))),
+        },
+    }
+}
+
+/// Order two row values lexicographically; `None` if that depends on a null
+fn row_cmp(a: &[Value], b: &[Value]) -> Option<Ordering> {
+    if a.len() != b.len() {
+        return Some(a.len().cmp(&b.len()));
+    }
+    for (a, b) in a.iter().zip(b) {
+        match compare(a, b)? {
+            Ordering::Equal => {}
+            ordering => return Some(ordering),
+        }
+    }
+    Some(Ordering::Equal)
+}
+
+fn arithmetic(op: BinaryOp, lhs: &Value, rhs: &Value) -> Result<Value, String> {
+    let (a, b) = (scalar(lhs), scalar(rhs));
+    let template = if matches!(a, Scalar::Int(_) | Scalar::Float(_)) || is_null(lhs) {
+        lhs
+    } else {
+        rhs
+    };
+    if a == Scalar::Null || b == Scalar::Null {
+        return Ok(template.as_null());
+    }
+    if let (Scalar::Int(a), Scalar::Int(b)) = (&a, &b) {
+        let n = match op {
+            BinaryOp::Add => a.checked_add(*b),
+            BinaryOp::Sub => a.checked_sub(*b),
+            BinaryOp::Mul => a.checked_mul(*b),
+            BinaryOp::Div | BinaryOp::Mod if *b == 0 => return Ok(template.as_null()),
+            BinaryOp::Div => a.checked_div(*b),
+            _ => a.checked_rem(*b),
+        };
+        return n
+            .map(|n| int_like(template, n))
+            .ok_or_else(|| "Integer overflow".to_owned());
+    }
+    let (Some(a), Some(b)) = (number(&a), number(&b)) else {
+        return Err(format!("Cannot do arithmetic on {lhs:?} and {rhs:?}"));
+    };
+    let n = match op {
+        BinaryOp::Add => a + b,
+        BinaryOp::Sub => a - b,
+        BinaryOp::Mul => a * b,
+        BinaryOp::Div | BinaryOp::Mod if b == 0.0 => return Ok(template.as_null()),
+        BinaryOp::Div => a / b,
+        _ => a % b,
+    };
+    Ok(float_like(template, n))
+}
+
+/// An integer as a value of the same type as `template`
+fn int_like(template: &Value, n: i128) -> Value {
+    let value = match template {
+        Value::Bool(_) => Some(Value::Bool(Some(n != 0))),
+        Value::TinyInt(_) => n.try_into().ok().map(|n| Value::TinyInt(Some(n))),
+        Value::SmallInt(_) => n.try_into().ok().map(|n| Value::SmallInt(Some(n))),
+        Value::Int(_) => n.try_into().ok().map(|n| Value::Int(Some(n))),
+        Value::TinyUnsigned(_) => n.try_into().ok().map(|n| Value::TinyUnsigned(Some(n))),
+        Value::SmallUnsigned(_) => n.try_into().ok().map(|n| Value::SmallUnsigned(Some(n))),
+        Value::Unsigned(_) => n.try_into().ok().map(|n| Value::Unsigned(Some(n))),
+        Value::BigUnsigned(_) => n.try_into().ok().map(|n| Value::BigUnsigned(Some(n))),
+        Value::Float(_) => Some(Value::Float(Some(n as f32))),
+        Value::Double(_) => Some(Value::Double(Some(n as f64))),
+        #[cfg(feature = "with-rust_decimal")]
+        Value::Decimal(_) => n.to_string().parse().ok().map(|n| Value::Decimal(Some(n))),
+        #[cfg(feature = "with-bigdecimal")]
+        Value::BigDecimal(_) => n
+            .to_string()
+            .parse()
+            .ok()
+            .map(|n| Value::BigDecimal(Some(Box::new(n)))),
+        _ => None,
+    };
+    value
+        .or_else(|| n.try_into().ok().map(|n| Value::BigInt(Some(n))))
+        .unwrap_or(Value::Double(Some(n as f64)))
+}
+
+/// A float as a value of the same type as `template`
+fn float_like(template: &Value, n: f64) -> Value {
+    match template {
+        Value::Float(_) => Value::Float(Some(n as f32)),
+        #[cfg(feature = "with-rust_decimal")]
+        Value::Decimal(_) => Value::Decimal(n.to_string().parse().ok()),
+        #[cfg(feature = "with-bigdecimal")]
+        Value::BigDecimal(_) => Value::BigDecimal(n.to_string().parse().ok().map(Box::new)),
+        _ => Value::Double(Some(n)),
+    }
+}
+
+/// Convert a value to the type of `template`, where it is a number, a boolean or a string
+fn convert(value: Value, template: &Value) -> Value {
+    let numeric = is_integer(template)
+        || matches!(
+            template,
+            Value::Bool(_) | Value::Float(_) | Value::Double(_)
+        );
+    match scalar(&value) {
+        Scalar::Null => template.as_null(),
+        Scalar::Text(text) if matches!(template, Value::String(_)) => Value::String(Some(text)),
+        scalar if matches!(template, Value::String(_)) => {
+            Value::String(text(&value).or_else(|| Some(format!("{scalar:?}"))))
+        }
+        Scalar::Bool(b) if numeric => int_like(template, b.into()),
+        Scalar::Int(n) if numeric => int_like(template, n),
+        Scalar::Float(n) if is_integer(template) => int_like(template, n.trunc() as i128),
+        Scalar::Float(n) if numeric => float_like(template, n),
+        Scalar::Text(text) if numeric => {
+            match (text.trim().parse::<i128>(), text.trim().parse::<f64>()) {
+                (Ok(n), _) => int_like(template, n),
+                (_, Ok(n)) if !is_integer(template) => float_like(template, n),
+                _ => value,
+            }
+        }
+        _ => value,
+    }
+}
+
+/// The null of the value type a column type is converted to, for the types that are
+/// unambiguous
+fn template_of(col_type: &ColumnType) -> Option<Value> {
+    Some(match col_type {
+        ColumnType::TinyInteger => Value::TinyInt(None),
+        ColumnType::SmallInteger => Value::SmallInt(None),
+        ColumnType::Integer => Value::Int(None),
+        ColumnType::BigInteger => Value::BigInt(None),
+        ColumnType::TinyUnsigned => Value::TinyUnsigned(None),
+        ColumnType::SmallUnsigned => Value::SmallUnsigned(None),
+        ColumnType::Unsigned => Value::Unsigned(None),
+        ColumnType::BigUnsigned => Value::BigUnsigned(None),
+        ColumnType::Float => Value::Float(None),
+        ColumnType::Double => Value::Double(None),
+        ColumnType::Boolean => Value::Bool(None),
+        ColumnType::String(_) | ColumnType::Text => Value::String(None),
+        _ => return None,
+    })
+}
+
+/// Convert a value written to a column to the column's type
+fn coerce(value: Value, column: &Column) -> Value {
+    #[cfg(feature = "with-chrono")]
+    if let Value::ChronoDateTimeUtc(Some(v)) = &value {
+        // `CURRENT_TIMESTAMP`, into whichever type the column holds
+        match (&column.col_type, &column.null) {
+            (_, Value::ChronoDateTime(_))
+            | (ColumnType::DateTime | ColumnType::Timestamp, Value::String(None)) => {
+                return Value::ChronoDateTime(Some(v.naive_utc()));
+            }
+            (_, Value::ChronoDateTimeWithTimeZone(_)) => {
+                return Value::ChronoDateTimeWithTimeZone(Some(v.fixed_offset()));
+            }
+            (_, Value::ChronoDate(_)) | (ColumnType::Date, Value::String(None)) => {
+                return Value::ChronoDate(Some(v.date_naive()));
+            }
+            (_, Value::ChronoTime(_)) | (ColumnType::Time, Value::String(None)) => {
+                return Value::ChronoTime(Some(v.time()));
+            }
+            _ => {}
+        }
+    }
+    match template_of(&column.col_type) {
+        Some(template) => convert(value, &template),
+        None => value,
+    }
+}
+
+fn cast(value: Value, type_name: &str) -> Value {
+    let template = match type_name.to_lowercase().as_str() {
+        "text" | "varchar" | "char" | "character" | "character varying" | "string" => {
+            Value::String(None)
+        }
+        "integer" | "int" | "int4" | "signed" | "signed integer" => Value::Int(None),
+        "bigint" | "int8" => Value::BigInt(None),
+        "smallint" | "int2" => Value::SmallInt(None),
+        "unsigned" | "unsigned integer" => Value::BigUnsigned(None),
+        "real" | "float4" | "float" => Value::Float(None),
+        "double precision" | "double" | "float8" => Value::Double(None),
+        "boolean" | "bool" => Value::Bool(None),
+        _ => return value,
+    };
+    convert(value, &template)
+}
+
+#[cfg(feature = "with-chrono")]
+fn now() -> Result<Value, String> {
+    Ok(Value::ChronoDateTimeUtc(Some(chrono::Utc::now())))
+}
+
+#[cfg(all(feature = "with-time", not(feature = "with-chrono")))]
+fn now() -> Result<Value, String> {
+    Ok(Value::TimeDateTimeWithTimeZone(Some(
+        time::OffsetDateTime::now_utc(),
+    )))
+}
+
+#[cfg(not(any(feature = "with-chrono", feature = "with-time")))]
+fn now() -> Result<Value, String> {
+    Err("CURRENT_TIMESTAMP needs the `with-chrono` or `with-time` feature".to_owned())
+}
+
+/// Match `text` against a `LIKE` pattern
+fn like(text: &str, pattern: &str, escape: Option<char>, case_insensitive: bool) -> bool {
+    enum Pattern {
+        Any,
+        One,
+        Char(char),
+    }
+
+    let fold = |s: &str| {
+        if case_insensitive {
+            s.to_lowercase()
+        } else {
+            s.to_owned()
+        }
+    };
+    let text: Vec<char> = fold(text).chars().collect();
+    let mut tokens = Vec::new();
+    let mut chars = fold(pattern).chars().collect::<Vec<_>>().into_iter();
+    while let Some(c) = chars.next() {
+        tokens.push(match c {
+            c if Some(c) == escape => match chars.next() {
+                Some(c) => Pattern::Char(c),
+                None => Pattern::Char(c),
+            },
+            '%' => Pattern::Any,
+            '_' => Pattern::One,
+            c => Pattern::Char(c),
+        });
+    }
+
+    let (mut t, mut p) = (0, 0);
+    let mut backtrack = None;
+    while t < text.len() {
+        match tokens.get(p) {
+            Some(Pattern::One) => {
+                t += 1;
+                p += 1;
+            }
+            Some(Pattern::Char(c)) if *c == text[t] => {
+                t += 1;
+                p += 1;
+            }
+            Some(Pattern::Any) => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            _ => match backtrack {
+                Some((star, from)) => {
+                    p = star + 1;
+                    t = from + 1;
+                    backtrack = Some((star, from + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    tokens[p..]
+        .iter()
+        .all(|token| matches!(token, Pattern::Any))
+}
@@ -0,0 +1,1204 @@
+//! Parser for the SQL that SeaQuery builds for SeaORM, on any of the three backends.
+//! Bound values are resolved while parsing, so the syntax tree holds [`Value`]s.
+
+use crate::DbBackend;
+use sea_query::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// A keyword or unquoted identifier
+    Word(String),
+    /// A quoted identifier
+    Quoted(String),
+    /// A string literal
+    Str(String),
+    Number(String),
+    /// A bound value, by its index in the statement's values
+    Param(usize),
+    Symbol(&'static str),
+}
+
+#[derive(Debug, Clone)]
+pub(super) enum Statement {
+    Select(Box<Select>),
+    Insert(Box<Insert>),
+    Update(Box<Update>),
+    Delete(Box<Delete>),
+    Savepoint(String),
+    RollbackTo(String),
+    Release(String),
+}
+
+#[derive(Debug, Clone, Default)]
+pub(super) struct Select {
+    pub distinct: bool,
+    pub items: Vec<SelectItem>,
+    /// The `FROM` source first, then the joined ones
+    pub from: Vec<Join>,
+    pub filter: Option<Expr>,
+    pub group_by: Vec<Expr>,
+    pub having: Option<Expr>,
+    pub order_by: Vec<OrderBy>,
+    pub limit: Option<Expr>,
+    pub offset: Option<Expr>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) enum SelectItem {
+    /// `*`, or `"table".*`
+    Wildcard(Option<String>),
+    Expr(Expr, Option<String>),
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Join {
+    pub kind: JoinKind,
+    pub source: Source,
+    pub alias: String,
+    pub on: Option<Expr>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum JoinKind {
+    Inner,
+    Left,
+    Right,
+    Cross,
+}
+
+#[derive(Debug, Clone)]
+pub(super) enum Source {
+    Table(String),
+    Subquery(Box<Select>),
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct OrderBy {
+    pub expr: Expr,
+    pub desc: bool,
+    pub nulls_first: Option<bool>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Insert {
+    pub table: String,
+    pub columns: Vec<String>,
+    pub source: InsertSource,
+    pub on_conflict: Option<OnConflict>,
+    pub returning: Option<Vec<SelectItem>>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) enum InsertSource {
+    Values(Vec<Vec<Expr>>),
+    Select(Box<Select>),
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct OnConflict {
+    /// The columns of the unique key to check; any unique key if empty
+    pub target: Vec<String>,
+    pub action: ConflictAction,
+}
+
+#[derive(Debug, Clone)]
+pub(super) enum ConflictAction {
+    Nothing,
+    Update {
+        set: Vec<(String, Expr)>,
+        filter: Option<Expr>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Update {
+    pub table: String,
+    pub alias: String,
+    pub set: Vec<(String, Expr)>,
+    pub filter: Option<Expr>,
+    pub limit: Option<Expr>,
+    pub returning: Option<Vec<SelectItem>>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Delete {
+    pub table: String,
+    pub alias: String,
+    pub filter: Option<Expr>,
+    pub limit: Option<Expr>,
+    pub returning: Option<Vec<SelectItem>>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) enum Expr {
+    /// A column, with the table or alias it was qualified with
+    Column(Option<String>, String),
+    Value(Value),
+    /// `DEFAULT` in a row of `VALUES`
+    Default,
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    Binary(Box<Expr>, BinaryOp, Box<Expr>),
+    IsNull(Box<Expr>, bool),
+    InList(Box<Expr>, Vec<Expr>, bool),
+    InSubquery(Box<Expr>, Box<Select>, bool),
+    Between(Box<Expr>, Box<Expr>, Box<Expr>, bool),
+    Like {
+        expr: Box<Expr>,
+        pattern: Box<Expr>,
+        escape: Option<Box<Expr>>,
+        negated: bool,
+        case_insensitive: bool,
+    },
+    /// A function call; `COUNT(*)` has no arguments
+    Function {
+        name: String,
+        args: Vec<Expr>,
+        distinct: bool,
+    },
+    Cast(Box<Expr>, String),
+    Exists(Box<Select>),
+    Subquery(Box<Select>),
+    Tuple(Vec<Expr>),
+    Case {
+        operand: Option<Box<Expr>>,
+        branches: Vec<(Expr, Expr)>,
+        otherwise: Option<Box<Expr>>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum BinaryOp {
+    And,
+    Or,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    /// `IS`, comparing nulls as equal
+    Is,
+    IsNot,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Concat,
+}
+
+/// Words that end an expression or a list, and so cannot be a bare alias
+const KEYWORDS: &[&str] = &[
+    "and",
+    "as",
+    "asc",
+    "between",
+    "by",
+    "cross",
+    "desc",
+    "do",
+    "else",
+    "end",
+    "escape",
+    "for",
+    "from",
+    "full",
+    "group",
+    "having",
+    "ilike",
+    "in",
+    "inner",
+    "is",
+    "join",
+    "left",
+    "like",
+    "limit",
+    "lock",
+    "not",
+    "nulls",
+    "offset",
+    "on",
+    "or",
+    "order",
+    "outer",
+    "returning",
+    "right",
+    "set",
+    "then",
+    "union",
+    "values",
+    "when",
+    "where",
+];
+
+pub(super) fn parse(backend: DbBackend, sql: &str, values: &[Value]) -> Result<Statement, String> {
+    let tokens = tokenize(backend, sql)?;
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        values,
+    };
+    let stmt = parser.statement()?;
+    parser.eat_symbol(";");
+    match parser.peek() {
+        None => Ok(stmt),
+        Some(token) => Err(format!("Unexpected {token:?} in `{sql}`")),
+    }
+}
+
+fn tokenize(backend: DbBackend, sql: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = sql.chars().peekable();
+    let mut next_param = 0;
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '-' if chars.peek() == Some(&'-') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut star = false;
+                for c in chars.by_ref() {
+                    if star && c == '/' {
+                        break;
+                    }
+                    star = c == '*';
+                }
+            }
+            '\'' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') if backend == DbBackend::MySql => match chars.next() {
+                            Some('n') => text.push('\n'),
+                            Some('r') => text.push('\r'),
+                            Some('t') => text.push('\t'),
+                            Some('0') => text.push('\0'),
+                            Some(c) => text.push(c),
+                            None => return Err("Unterminated string literal".to_owned()),
+                        },
+                        Some('\'') if chars.peek() == Some(&'\'') => {
+                            chars.next();
+                            text.push('\'');
+                        }
+                        Some('\'') => break,
+                        Some(c) => text.push(c),
+                        None => return Err("Unterminated string literal".to_owned()),
+                    }
+                }
+                tokens.push(Token::Str(text));
+            }
+            '"' | '`' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some(q) if q == c => {
+                            if chars.peek() == Some(&c) {
+                                chars.next();
+                                name.push(c);
+                            } else {
+                                break;
+                            }
+                        }
+                        Some(q) => name.push(q),
+                        None => return Err("Unterminated quoted identifier".to_owned()),
+                    }
+                }
+                tokens.push(Token::Quoted(name));
+            }
+            '$' if backend == DbBackend::Postgres
+                && chars.peek().is_some_and(|c| c.is_ascii_digit()) =>
+            {
+                let mut n = 0usize;
+                while let Some(d) = chars.peek().and_then(|c| c.to_digit(10)) {
+                    n = n * 10 + d as usize;
+                    chars.next();
+                }
+                tokens.push(Token::Param(n.saturating_sub(1)));
+            }
+            '?' if backend != DbBackend::Postgres => {
+                tokens.push(Token::Param(next_param));
+                next_param += 1;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut word = String::from(c);
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_' || c == '$') {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+            c if c.is_ascii_digit() => {
+                let mut number = String::from(c);
+                while let Some(&c) = chars.peek() {
+                    let exponent_sign = (c == '-' || c == '+') && number.ends_with(['e', 'E']);
+                    if !(c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || exponent_sign) {
+                        break;
+                    }
+                    number.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Number(number));
+            }
+            c => {
+                let next = chars.peek().copied();
+                let symbol = match (c, next) {
+                    ('<', Some('>')) => "<>",
+                    ('<', Some('=')) => "<=",
+                    ('>', Some('=')) => ">=",
+                    ('!', Some('=')) => "!=",
+                    ('|', Some('|')) => "||",
+                    (':', Some(':')) => "::",
+                    ('=', Some('=')) => "==",
+                    _ => "",
+                };
+                if symbol.is_empty() {
+                    let symbol = match c {
+                        '(' => "(",
+                        ')' => ")",
+                        ',' => ",",
+                        '.' => ".",
+                        '*' => "*",
+                        '+' => "+",
+                        '-' => "-",
+                        '/' => "/",
+                        '%' => "%",
+                        '=' => "=",
+                        '<' => "<",
+                        '>' => ">",
+                        ';' => ";",
+                        '[' => "[",
+                        ']' => "]",
+                        c => return Err(format!("Unexpected character `{c}`")),
+                    };
+                    tokens.push(Token::Symbol(symbol));
+                } else {
+                    chars.next();
+                    tokens.push(Token::Symbol(symbol));
+                }
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    values: &'a [Value],
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + offset)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn is_word_at(&self, offset: usize, word: &str) -> bool {
+        matches!(self.peek_at(offset), Some(Token::Word(w)) if w.eq_ignore_ascii_case(word))
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        self.is_word_at(0, word)
+    }
+
+    fn is_symbol(&self, symbol: &str) -> bool {
+        matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol)
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        let found = self.is_word(word);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn eat_words(&mut self, words: &[&str]) -> bool {
+        let found = words
+            .iter()
+            .enumerate()
+            .all(|(i, word)| self.is_word_at(i, word));
+        if found {
+            self.pos += words.len();
+        }
+        found
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        let found = self.is_symbol(symbol);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect_word(&mut self, word: &str) -> Result<(), String> {
+        if self.eat_word(word) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("`{}`", word.to_uppercase())))
+        }
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), String> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("`{symbol}`")))
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> String {
+        match self.peek() {
+            Some(token) => format!("Expected {expected}, found {token:?}"),
+            None => format!("Expected {expected}, found the end of the statement"),
+        }
+    }
+
+    fn is_keyword(&self) -> bool {
+        matches!(self.peek(), Some(Token::Word(w)) if KEYWORDS.iter().any(|k| w.eq_ignore_ascii_case(k)))
+    }
+
+    fn ident(&mut self) -> Result<String, String> {
+        match self.peek() {
+            Some(Token::Quoted(name)) => {
+                let name = name.clone();
+                self.pos += 1;
+                Ok(name)
+            }
+            Some(Token::Word(name)) if !self.is_keyword() => {
+                let name = name.clone();
+                self.pos += 1;
+                Ok(name)
+            }
+            _ => Err(self.unexpected("an identifier")),
+        }
+    }
+
+    /// A possibly schema-qualified name, of which only the last part is kept
+    fn object_name(&mut self) -> Result<String, String> {
+        let mut name = self.ident()?;
+        while self.is_symbol(".") && !matches!(self.peek_at(1), Some(Token::Symbol("*"))) {
+            self.pos += 1;
+            name = self.ident()?;
+        }
+        Ok(name)
+    }
+
+    fn alias(&mut self) -> Result<Option<String>, String> {
+        if self.eat_word("as") {
+            return self.ident().map(Some);
+        }
+        match self.peek() {
+            Some(Token::Quoted(_)) => self.ident().map(Some),
+            Some(Token::Word(_)) if !self.is_keyword() => self.ident().map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    fn statement(&mut self) -> Result<Statement, String> {
+        if self.is_word("select") || self.is_symbol("(") {
+            Ok(Statement::Select(Box::new(self.select()?)))
+        } else if self.eat_word("insert") {
+            Ok(Statement::Insert(Box::new(self.insert()?)))
+        } else if self.eat_word("update") {
+            Ok(Statement::Update(Box::new(self.update()?)))
+        } else if self.eat_word("delete") {
+            Ok(Statement::Delete(Box::new(self.delete()?)))
+        } else if self.eat_word("savepoint") {
+            Ok(Statement::Savepoint(self.ident()?))
+        } else if self.eat_word("release") {
+            self.eat_word("savepoint");
+            Ok(Statement::Release(self.ident()?))
+        } else if self.eat_words(&["rollback", "to"]) {
+            self.eat_word("savepoint");
+            Ok(Statement::RollbackTo(self.ident()?))
+        } else {
+            Err(self.unexpected("a SELECT, INSERT, UPDATE, DELETE or SAVEPOINT statement"))
+        }
+    }
+
+    fn select(&mut self) -> Result<Select, String> {
+        if self.eat_symbol("(") {
+            let select = self.select()?;
+            self.expect_symbol(")")?;
+            return Ok(select);
+        }
+        self.expect_word("select")?;
+        let mut select = Select {
+            distinct: self.eat_word("distinct"),
+            ..Default::default()
+        };
+        self.eat_word("all");
+        select.items = self.select_items()?;
+        if self.eat_word("from") {
+            let (source, alias) = self.source()?;
+            select.from.push(Join {
+                kind: JoinKind::Cross,
+                source,
+                alias,
+                on: None,
+            });
+            loop {
+                let kind = if self.eat_symbol(",") || self.eat_words(&["cross", "join"]) {
+                    JoinKind::Cross
+                } else if self.eat_word("join") || self.eat_words(&["inner", "join"]) {
+                    JoinKind::Inner
+                } else if self.eat_words(&["left", "join"])
+                    || self.eat_words(&["left", "outer", "join"])
+                {
+                    JoinKind::Left
+                } else if self.eat_words(&["right", "join"])
+                    || self.eat_words(&["right", "outer", "join"])
+                {
+                    JoinKind::Right
+                } else {
+                    break;
+                };
+                let (source, alias) = self.source()?;
+                let on = if kind != JoinKind::Cross && self.eat_word("on") {
+                    Some(self.expr()?)
+                } else {
+                    None
+                };
+                select.from.push(Join {
+                    kind,
+                    source,
+                    alias,
+                    on,
+                });
+            }
+        }
+        if self.eat_word("where") {
+            select.filter = Some(self.expr()?);
+        }
+        if self.eat_words(&["group", "by"]) {
+            select.group_by = self.expr_list()?;
+        }
+        if self.eat_word("having") {
+            select.having = Some(self.expr()?);
+        }
+        if self.eat_words(&["order", "by"]) {
+            select.order_by = self.order_by()?;
+        }
+        (select.limit, select.offset) = self.limit()?;
+        if self.is_word("union") {
+            return Err("UNION is not supported".to_owned());
+        }
+        self.skip_locking();
+        Ok(select)
+    }
+
+    fn select_items(&mut self) -> Result<Vec<SelectItem>, String> {
+        let mut items = Vec::new();
+        loop {
+            if self.eat_symbol("*") {
+                items.push(SelectItem::Wildcard(None));
+            } else if matches!(self.peek(), Some(Token::Word(_) | Token::Quoted(_)))
+                && matches!(self.peek_at(1), Some(Token::Symbol(".")))
+                && matches!(self.peek_at(2), Some(Token::Symbol("*")))
+            {
+                let table = self.ident()?;
+                self.pos += 2;
+                items.push(SelectItem::Wildcard(Some(table)));
+            } else {
+                let expr = self.expr()?;
+                items.push(SelectItem::Expr(expr, self.alias()?));
+            }
+            if !self.eat_symbol(",") {
+                return Ok(items);
+            }
+        }
+    }
+
+    fn source(&mut self) -> Result<(Source, String), String> {
+        if self.eat_symbol("(") {
+            let select = self.select()?;
+            self.expect_symbol(")")?;
+            let alias = self
+                .alias()?
+                .ok_or_else(|| "A subquery in FROM must have an alias".to_owned())?;
+            Ok((Source::Subquery(Box::new(select)), alias))
+        } else {
+            let table = self.object_name()?;
+            let alias = self.alias()?.unwrap_or_else(|| table.clone());
+            Ok((Source::Table(table), alias))
+        }
+    }
+
+    fn order_by(&mut self) -> Result<Vec<OrderBy>, String> {
+        let mut order_by = Vec::new();
+        loop {
+            let expr = self.expr()?;
+            let desc = if self.eat_word("desc") {
+                true
+            } else {
+                self.eat_word("asc");
+                false
+            };
+            let nulls_first = if self.eat_words(&["nulls", "first"]) {
+                Some(true)
+            } else if self.eat_words(&["nulls", "last"]) {
+                Some(false)
+            } else {
+                None
+            };
+            order_by.push(OrderBy {
+                expr,
+                desc,
+                nulls_first,
+            });
+            if !self.eat_symbol(",") {
+                return Ok(order_by);
+            }
+        }
+    }
+
+    fn limit(&mut self) -> Result<(Option<Expr>, Option<Expr>), String> {
+        let mut limit = None;
+        let mut offset = None;
+        if self.eat_word("limit") {
+            let first = self.expr()?;
+            if self.eat_symbol(",") {
+                offset = Some(first);
+                limit = Some(self.expr()?);
+            } else {
+                limit = Some(first);
+            }
+        }
+        if self.eat_word("offset") {
+            offset = Some(self.expr()?);
+        }
+        Ok((limit, offset))
+    }
+
+    /// Row locks mean nothing here, so `FOR UPDATE` and the like are skipped
+    fn skip_locking(&mut self) {
+        if self.is_word("for") || self.is_words_lock_in_share_mode() {
+            while self.peek().is_some() && !self.is_symbol(")") && !self.is_symbol(";") {
+                self.pos += 1;
+            }
+        }
+    }
+
+    fn is_words_lock_in_share_mode(&self) -> bool {
+        self.is_word("lock") && self.is_word_at(1, "in")
+    }
+
+    fn returning(&mut self) -> Result<Option<Vec<SelectItem>>, String> {
+        if self.eat_word("returning") {
+            Ok(Some(self.select_items()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn insert(&mut self) -> Result<Insert, String> {
+        let mut ignore = self.eat_word("ignore") || self.eat_words(&["or", "ignore"]);
+        self.expect_word("into")?;
+        let table = self.object_name()?;
+        self.alias()?;
+        let mut columns = Vec::new();
+        if self.is_symbol("(") && !self.is_word_at(1, "select") {
+            self.pos += 1;
+            loop {
+                columns.push(self.object_name()?);
+                if !self.eat_symbol(",") {
+                    break;
+                }
+            }
+            self.expect_symbol(")")?;
+        }
+        let source = if self.eat_words(&["default", "values"]) {
+            InsertSource::Values(vec![Vec::new()])
+        } else if self.eat_word("values") {
+            let mut rows = Vec::new();
+            loop {
+                self.expect_symbol("(")?;
+                let mut row = Vec::new();
+                if !self.eat_symbol(")") {
+                    loop {
+                        row.push(if self.eat_word("default") {
+                            Expr::Default
+                        } else {
+                            self.expr()?
+                        });
+                        if !self.eat_symbol(",") {
+                            break;
+                        }
+                    }
+                    self.expect_symbol(")")?;
+                }
+                rows.push(row);
+                if !self.eat_symbol(",") {
+                    break;
+                }
+            }
+            InsertSource::Values(rows)
+        } else {
+            InsertSource::Select(Box::new(self.select()?))
+        };
+        let mut on_conflict = None;
+        if self.eat_words(&["on", "conflict"]) {
+            let mut target = Vec::new();
+            if self.eat_symbol("(") {
+                loop {
+                    target.push(self.object_name()?);
+                    if !self.eat_symbol(",") {
+                        break;
+                    }
+                }
+                self.expect_symbol(")")?;
+            }
+            if self.eat_word("where") {
+                self.expr()?;
+            }
+            self.expect_word("do")?;
+            let action = if self.eat_word("nothing") {
+                ConflictAction::Nothing
+            } else {
+                self.expect_word("update")?;
+                self.expect_word("set")?;
+                let set = self.assignments()?;
+                let filter = if self.eat_word("where") {
+                    Some(self.expr()?)
+                } else {
+                    None
+                };
+                ConflictAction::Update { set, filter }
+            };
+            on_conflict = Some(OnConflict { target, action });
+        } else if self.eat_words(&["on", "duplicate", "key", "update"]) {
+            on_conflict = Some(OnConflict {
+                target: Vec::new(),
+                action: ConflictAction::Update {
+                    set: self.assignments()?,
+                    filter: None,
+                },
+            });
+            ignore = false;
+        }
+        if ignore {
+            on_conflict = Some(OnConflict {
+                target: Vec::new(),
+                action: ConflictAction::Nothing,
+            });
+        }
+        Ok(Insert {
+            table,
+            columns,
+            source,
+            on_conflict,
+            returning: self.returning()?,
+        })
+    }
+
+    fn assignments(&mut self) -> Result<Vec<(String, Expr)>, String> {
+        let mut set = Vec::new();
+        loop {
+            let column = self.object_name()?;
+            self.expect_symbol("=")?;
+            set.push((column, self.expr()?));
+            if !self.eat_symbol(",") {
+                return Ok(set);
+            }
+        }
+    }
+
+    fn update(&mut self) -> Result<Update, String> {
+        let table = self.object_name()?;
+        let alias = self.alias()?.unwrap_or_else(|| table.clone());
+        self.expect_word("set")?;
+        let set = self.assignments()?;
+        let filter = if self.eat_word("where") {
+            Some(self.expr()?)
+        } else {
+            None
+        };
+        if self.eat_words(&["order", "by"]) {
+            self.order_by()?;
+        }
+        let (limit, _) = self.limit()?;
+        Ok(Update {
+            table,
+            alias,
+            set,
+            filter,
+            limit,
+            returning: self.returning()?,
+        })
+    }
+
+    fn delete(&mut self) -> Result<Delete, String> {
+        self.expect_word("from")?;
+        let table = self.object_name()?;
+        let alias = self.alias()?.unwrap_or_else(|| table.clone());
+        let filter = if self.eat_word("where") {
+            Some(self.expr()?)
+        } else {
+            None
+        };
+        if self.eat_words(&["order", "by"]) {
+            self.order_by()?;
+        }
+        let (limit, _) = self.limit()?;
+        Ok(Delete {
+            table,
+            alias,
+            filter,
+            limit,
+            returning: self.returning()?,
+        })
+    }
+
+    fn expr_list(&mut self) -> Result<Vec<Expr>, String> {
+        let mut exprs = Vec::new();
+        loop {
+            exprs.push(self.expr()?);
+            if !self.eat_symbol(",") {
+                return Ok(exprs);
+            }
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.and_expr()?;
+        while self.eat_word("or") {
+            let rhs = self.and_expr()?;
+            lhs = Expr::Binary(Box::new(lhs), BinaryOp::Or, Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn and_expr(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.not_expr()?;
+        while self.eat_word("and") {
+            let rhs = self.not_expr()?;
+            lhs = Expr::Binary(Box::new(lhs), BinaryOp::And, Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn not_expr(&mut self) -> Result<Expr, String> {
+        if self.eat_word("not") {
+            Ok(Expr::Not(Box::new(self.not_expr()?)))
+        } else {
+            self.comparison()
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let lhs = self.concat_expr()?;
+        let op = match self.peek() {
+            Some(Token::Symbol("=" | "==")) => Some(BinaryOp::Eq),
+            Some(Token::Symbol("<>" | "!=")) => Some(BinaryOp::NotEq),
+            Some(Token::Symbol("<")) => Some(BinaryOp::Lt),
+            Some(Token::Symbol("<=")) => Some(BinaryOp::LtEq),
+            Some(Token::Symbol(">")) => Some(BinaryOp::Gt),
+            Some(Token::Symbol(">=")) => Some(BinaryOp::GtEq),
+            _ => None,
+        };
+        if let Some(op) = op {
+            self.pos += 1;
+            let rhs = self.concat_expr()?;
+            return Ok(Expr::Binary(Box::new(lhs), op, Box::new(rhs)));
+        }
+        if self.eat_word("is") {
+            let negated = self.eat_word("not");
+            if self.eat_word("null") {
+                return Ok(Expr::IsNull(Box::new(lhs), negated));
+            }
+            let op = if negated {
+                BinaryOp::IsNot
+            } else {
+                BinaryOp::Is
+            };
+            let rhs = self.concat_expr()?;
+            return Ok(Expr::Binary(Box::new(lhs), op, Box::new(rhs)));
+        }
+        let negated = if self.is_word("not")
+            && ["in", "between", "like", "ilike"]
+                .iter()
+                .any(|word| self.is_word_at(1, word))
+        {
+            self.pos += 1;
+            true
+        } else {
+            false
+        };
+        if self.eat_word("in") {
+            self.expect_symbol("(")?;
+            if self.is_word("select") {
+                let select = self.select()?;
+                self.expect_symbol(")")?;
+                return Ok(Expr::InSubquery(Box::new(lhs), Box::new(select), negated));
+            }
+            let list = if self.is_symbol(")") {
+                Vec::new()
+            } else {
+                self.expr_list()?
+            };
+            self.expect_symbol(")")?;
+            return Ok(Expr::InList(Box::new(lhs), list, negated));
+        }
+        if self.eat_word("between") {
+            let low = self.concat_expr()?;
+            self.expect_word("and")?;
+            let high = self.concat_expr()?;
+            return Ok(Expr::Between(
+                Box::new(lhs),
+                Box::new(low),
+                Box::new(high),
+                negated,
+            ));
+        }
+        let case_insensitive = self.is_word("ilike");
+        if self.eat_word("like") || self.eat_word("ilike") {
+            let pattern = self.concat_expr()?;
+            let escape = if self.eat_word("escape") {
+                Some(Box::new(self.concat_expr()?))
+            } else {
+                None
+            };
+            return Ok(Expr::Like {
+                expr: Box::new(lhs),
+                pattern: Box::new(pattern),
+                escape,
+                negated,
+                case_insensitive,
+            });
+        }
+        Ok(lhs)
+    }
+
+    fn concat_expr(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.additive()?;
+        while self.eat_symbol("||") {
+            let rhs = self.additive()?;
+            lhs = Expr::Binary(Box::new(lhs), BinaryOp::Concat, Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn additive(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.multiplicative()?;
+        loop {
+            let op = if self.eat_symbol("+") {
+                BinaryOp::Add
+            } else if self.eat_symbol("-") {
+                BinaryOp::Sub
+            } else {
+                return Ok(lhs);
+            };
+            let rhs = self.multiplicative()?;
+            lhs = Expr::Binary(Box::new(lhs), op, Box::new(rhs));
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.unary()?;
+        loop {
+            let op = if self.eat_symbol("*") {
+                BinaryOp::Mul
+            } else if self.eat_symbol("/") {
+                BinaryOp::Div
+            } else if self.eat_symbol("%") {
+                BinaryOp::Mod
+            } else {
+                return Ok(lhs);
+            };
+            let rhs = self.unary()?;
+            lhs = Expr::Binary(Box::new(lhs), op, Box::new(rhs));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat_symbol("-") {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        if self.eat_symbol("+") {
+            return self.unary();
+        }
+        let mut expr = self.primary()?;
+        while self.eat_symbol("::") {
+            expr = Expr::Cast(Box::new(expr), self.type_name()?);
+        }
+        Ok(expr)
+    }
+
+    fn type_name(&mut self) -> Result<String, String> {
+        let mut name = match self.next() {
+            Some(Token::Word(word)) => word.to_lowercase(),
+            Some(Token::Quoted(name)) => name,
+            other => return Err(format!("Expected a type name, found {other:?}")),
+        };
+        // multi-word types such as `double precision` or `signed integer`
+        while let Some(Token::Word(word)) = self.peek() {
+            if self.is_keyword() {
+                break;
+            }
+            name = format!("{name} {}", word.to_lowercase());
+            self.pos += 1;
+        }
+        if self.eat_symbol("(") {
+            while !self.eat_symbol(")") {
+                if self.next().is_none() {
+                    return Err("Unterminated type name".to_owned());
+                }
+            }
+        }
+        if self.eat_symbol("[") {
+            self.expect_symbol("]")?;
+        }
+        Ok(name)
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        let token = self
+            .next()
+            .ok_or_else(|| "Expected an expression, found the end of the statement".to_owned())?;
+        match token {
+            Token::Param(i) => {
+                let value = self
+                    .values
+                    .get(i)
+                    .ok_or_else(|| format!("No value bound for parameter {}", i + 1))?;
+                Ok(Expr::Value(value.clone()))
+            }
+            Token::Str(text) => Ok(Expr::Value(Value::String(Some(text)))),
+            Token::Number(number) => {
+                if let Ok(n) = number.parse::<i64>() {
+                    Ok(Expr::Value(Value::BigInt(Some(n))))
+                } else {
+                    number
+                        .parse::<f64>()
+                        .map(|n| Expr::Value(Value::Double(Some(n))))
+                        .map_err(|_| format!("Invalid number `{number}`"))
+                }
+            }
+            Token::Symbol("(") => {
+                if self.is_word("select") {
+                    let select = self.select()?;
+                    self.expect_symbol(")")?;
+                    return Ok(Expr::Subquery(Box::new(select)));
+                }
+                let mut exprs = self.expr_list()?;
+                self.expect_symbol(")")?;
+                if exprs.len() == 1 {
+                    Ok(exprs.remove(0))
+                } else {
+                    Ok(Expr::Tuple(exprs))
+                }
+            }
+            Token::Quoted(name) => self.column(name),
+            Token::Word(word) => self.word(word),
+            token => Err(format!("Expected an expression, found {token:?}")),
+        }
+    }
+
+    fn word(&mut self, word: String) -> Result<Expr, String> {
+        let lower = word.to_lowercase();
+        match lower.as_str() {
+            "null" => return Ok(Expr::Value(Value::String(None))),
+            "true" => return Ok(Expr::Value(Value::Bool(Some(true)))),
+            "false" => return Ok(Expr::Value(Value::Bool(Some(false)))),
+            "current_timestamp" | "current_date" | "current_time" | "localtimestamp"
+                if !self.is_symbol("(") =>
+            {
+                return Ok(Expr::Function {
+                    name: lower,
+                    args: Vec::new(),
+                    distinct: false,
+                });
+            }
+            "exists" => {
+                self.expect_symbol("(")?;
+                let select = self.select()?;
+                self.expect_symbol(")")?;
+                return Ok(Expr::Exists(Box::new(select)));
+            }
+            "cast" => {
+                self.expect_symbol("(")?;
+                let expr = self.expr()?;
+                self.expect_word("as")?;
+                let type_name = self.type_name()?;
+                self.expect_symbol(")")?;
+                return Ok(Expr::Cast(Box::new(expr), type_name));
+            }
+            "case" => return self.case(),
+            _ => {}
+        }
+        if self.eat_symbol("(") {
+            let distinct = self.eat_word("distinct");
+            let args = if self.eat_symbol("*") || self.is_symbol(")") {
+                Vec::new()
+            } else {
+                self.expr_list()?
+            };
+            self.expect_symbol(")")?;
+            return Ok(Expr::Function {
+                name: lower,
+                args,
+                distinct,
+            });
+        }
+        if KEYWORDS.contains(&lower.as_str()) {
+            return Err(format!("Expected an expression, found `{word}`"));
+        }
+        self.column(word)
+    }
+
+    fn column(&mut self, first: String) -> Result<Expr, String> {
+        let mut parts = vec![first];
+        while self.is_symbol(".") && !matches!(self.peek_at(1), Some(Token::Symbol("*"))) {
+            self.pos += 1;
+            parts.push(self.ident()?);
+        }
+        let name = parts.pop().unwrap_or_default();
+        Ok(Expr::Column(parts.pop(), name))
+    }
+
+    fn case(&mut self) -> Result<Expr, String> {
+        let operand = if self.is_word("when") {
+            None
+        } else {
+            Some(Box::new(self.expr()?))
+        };
+        let mut branches = Vec::new();
+        while self.eat_word("when") {
+            let condition = self.expr()?;
+            self.expect_word("then")?;
+            branches.push((condition, self.expr()?));
+        }
+        let otherwise = if self.eat_word("else") {
+            Some(Box::new(self.expr()?))
+        } else {
+            None
+        };
+        self.expect_word("end")?;
+        Ok(Expr::Case {
+            operand,
+            branches,
+            otherwise,
+        })
+    }
+}
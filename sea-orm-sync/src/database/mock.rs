@@ -3,8 +3,9 @@ use crate::{
     ExecResultHolder, Iden, IdenStatic, Iterable, MockDatabaseConnection, MockDatabaseTrait,
     ModelTrait, QueryResult, QueryResultRow, SelectA, SelectB, Statement, error::*,
 };
+use regex::Regex;
 use sea_query::{Value, ValueType, Values};
-use std::{collections::BTreeMap, sync::Arc};
+use std::{collections::BTreeMap, fmt::Write, sync::Arc};
 use tracing::instrument;
 
 /// Scripted in-memory database for unit tests.
@@ -15,6 +16,11 @@ use tracing::instrument;
 /// you can pass to your code, then inspect what was executed via
 /// [`into_transaction_log`](crate::MockDatabaseConnection::into_transaction_log)
 /// on the connection.
+///
+/// Results can instead be tied to the statements they answer with
+/// [`expect_query`](Self::expect_query) / [`expect_exec`](Self::expect_exec), so tests
+/// don't depend on the order statements run in. Statements no expectation matches are
+/// answered by the results appended in order.
 #[derive(Debug)]
 pub struct MockDatabase {
     db_backend: DbBackend,
@@ -22,6 +28,61 @@ pub struct MockDatabase {
     transaction_log: Vec<Transaction>,
     exec_results: Vec<Result<MockExecResult, DbErr>>,
     query_results: Vec<Result<Vec<MockRow>, DbErr>>,
+    exec_expectations: Vec<MockExecExpectation>,
+    query_expectations: Vec<MockQueryExpectation>,
+    /// Statements answered by an expectation, which take no result appended in order
+    expected_execs: usize,
+    expected_queries: usize,
+    history: Vec<(Statement, Answer)>,
+}
+
+/// Selects the statements an expectation set on a [`MockDatabase`] answers: by exact
+/// SQL, by a regex on the SQL or by the table it works on, optionally also requiring
+/// the values bound to it.
+#[derive(Debug, Clone)]
+pub struct StatementMatcher {
+    sql: SqlMatcher,
+    values: Option<Values>,
+}
+
+#[derive(Debug, Clone)]
+enum SqlMatcher {
+    Exact(String),
+    Regex(Regex),
+    Table(String, Regex),
+}
+
+#[derive(Debug)]
+struct Expectation {
+    matcher: StatementMatcher,
+    times: usize,
+    calls: usize,
+}
+
+/// An expectation set with [`MockDatabase::expect_query`]. It answers the `times`
+/// statements it matches, once by default, with the rows given to
+/// [`returning`](Self::returning).
+#[derive(Debug)]
+pub struct MockQueryExpectation {
+    expectation: Expectation,
+    result: Result<Vec<MockRow>, DbErr>,
+}
+
+/// An expectation set with [`MockDatabase::expect_exec`]. It answers the `times`
+/// statements it matches, once by default, with the result given to
+/// [`returning`](Self::returning).
+#[derive(Debug)]
+pub struct MockExecExpectation {
+    expectation: Expectation,
+    result: Result<MockExecResult, DbErr>,
+}
+
+/// How a statement run against a [`MockDatabase`] was answered
+#[derive(Debug)]
+enum Answer {
+    Expected(String),
+    InOrder,
+    Unexpected,
 }
 
 /// Canned [`ExecResult`](crate::ExecResult)-equivalent returned by a
@@ -76,6 +137,11 @@ impl MockDatabase {
             transaction_log: Vec::new(),
             exec_results: Vec::new(),
             query_results: Vec::new(),
+            exec_expectations: Vec::new(),
+            query_expectations: Vec::new(),
+            expected_execs: 0,
+            expected_queries: 0,
+            history: Vec::new(),
         }
     }
 
@@ -125,17 +191,238 @@ impl MockDatabase {
         self.query_results.extend(vec.into_iter().map(Result::Err));
         self
     }
-}
 
-impl MockDatabaseTrait for MockDatabase {
-    #[instrument(level = "trace", skip(statement))]
-    fn execute(&mut self, counter: usize, statement: Statement) -> Result<ExecResult, DbErr> {
+    /// Expect a query matching `matcher`, answered with no rows unless told otherwise
+    ///
+    /// ```
+    /// # use sea_orm::{error::*, tests_cfg::*, *};
+    /// #
+    /// # #[cfg(feature = "mock")]
+    /// # pub fn main() -> Result<(), DbErr> {
+    /// #
+    /// let mut db = MockDatabase::new(DbBackend::Postgres);
+    /// db.expect_query(StatementMatcher::table("fruit"))
+    ///     .returning([fruit::Model {
+    ///         id: 1,
+    ///         name: "Apple".to_owned(),
+    ///         cake_id: Some(1),
+    ///     }]);
+    /// db.expect_query(StatementMatcher::table("cake").with_values([1.into(), 1u64.into()]))
+    ///     .returning([cake::Model {
+    ///         id: 1,
+    ///         name: "Apple Pie".to_owned(),
+    ///     }]);
+    /// let db = db.into_connection();
+    ///
+    /// // the order they run in does not matter
+    /// let cake = cake::Entity::find_by_id(1).one(&db)?;
+    /// let fruits = fruit::Entity::find().all(&db)?;
+    /// assert_eq!(cake.map(|cake| cake.id), Some(1));
+    /// assert_eq!(fruits.len(), 1);
+    ///
+    /// db.as_mock_connection().verify();
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn expect_query(&mut self, matcher: StatementMatcher) -> &mut MockQueryExpectation {
+        self.query_expectations.push(MockQueryExpectation {
+            expectation: Expectation::new(matcher),
+            result: Ok(Vec::new()),
+        });
+        let last = self.query_expectations.len() - 1;
+        &mut self.query_expectations[last]
+    }
+
+    /// Expect a statement other than a query matching `matcher`, answered with a
+    /// [`MockExecResult::default`] unless told otherwise
+    pub fn expect_exec(&mut self, matcher: StatementMatcher) -> &mut MockExecExpectation {
+        self.exec_expectations.push(MockExecExpectation {
+            expectation: Expectation::new(matcher),
+            result: Ok(MockExecResult::default()),
+        });
+        let last = self.exec_expectations.len() - 1;
+        &mut self.exec_expectations[last]
+    }
+
+    fn record(&mut self, statement: Statement, answer: Answer) {
+        self.history.push((statement.clone(), answer));
         if let Some(transaction) = &mut self.transaction {
             transaction.push(statement);
         } else {
             self.transaction_log.push(Transaction::one(statement));
         }
+    }
+}
+
+impl StatementMatcher {
+    /// Match statements whose SQL is exactly `sql`
+    pub fn sql<T>(sql: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self::new(SqlMatcher::Exact(sql.into()))
+    }
+
+    /// Match statements whose SQL matches the regular expression `pattern`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pattern` is not a valid regular expression.
+    pub fn regex(pattern: &str) -> Self {
+        let regex = Regex::new(pattern).expect("Invalid regular expression");
+        Self::new(SqlMatcher::Regex(regex))
+    }
+
+    /// Match statements that select from, insert into, update or delete from the
+    /// table `name`, or join it
+    ///
+    /// # Panics
+    ///
+    /// Never in practice: the regular expression it builds is always valid.
+    pub fn table<T>(name: T) -> Self
+    where
+        T: Into<String>,
+    {
+        let name = name.into();
+        let pattern = format!(
+            r#"(?i)\b(?:FROM|INTO|UPDATE|JOIN)\s+(?:[`"]?\w+[`"]?\.)?[`"]?{}[`"]?(?:[\s,;()]|$)"#,
+            regex::escape(&name)
+        );
+        let regex = Regex::new(&pattern).expect("Invalid table name pattern");
+        Self::new(SqlMatcher::Table(name, regex))
+    }
+
+    /// Also require the values bound to the statement to be `values`
+    pub fn with_values<I>(mut self, values: I) -> Self
+    where
+        I: IntoIterator<Item = Value>,
+    {
+        self.values = Some(Values(values.into_iter().collect()));
+        self
+    }
+
+    fn new(sql: SqlMatcher) -> Self {
+        Self { sql, values: None }
+    }
+
+    /// Whether `statement` is one this selects
+    pub fn matches(&self, statement: &Statement) -> bool {
+        let sql_matches = match &self.sql {
+            SqlMatcher::Exact(sql) => statement.sql == *sql,
+            SqlMatcher::Regex(regex) | SqlMatcher::Table(_, regex) => {
+                regex.is_match(&statement.sql)
+            }
+        };
+        sql_matches
+            && self.values.as_ref().is_none_or(|values| {
+                statement.values.as_ref().unwrap_or(&Values(Vec::new())) == values
+            })
+    }
+}
+
+impl std::fmt::Display for StatementMatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.sql {
+            SqlMatcher::Exact(sql) => write!(f, "sql `{sql}`")?,
+            SqlMatcher::Regex(regex) => write!(f, "regex `{regex}`")?,
+            SqlMatcher::Table(name, _) => write!(f, "table `{name}`")?,
+        }
+        if let Some(values) = &self.values {
+            write!(f, " with values {:?}", values.0)?;
+        }
+        Ok(())
+    }
+}
+
+impl Expectation {
+    fn new(matcher: StatementMatcher) -> Self {
+        Self {
+            matcher,
+            times: 1,
+            calls: 0,
+        }
+    }
+
+    /// Whether `statement` is a match and this has calls to spare
+    fn accepts(&self, statement: &Statement) -> bool {
+        self.calls < self.times && self.matcher.matches(statement)
+    }
+
+    fn unmet(&self, kind: &str) -> Option<String> {
+        (self.calls < self.times).then(|| {
+            format!(
+                "{kind} matching {}: expected {} time(s), matched {}",
+                self.matcher, self.times, self.calls
+            )
+        })
+    }
+}
+
+impl MockQueryExpectation {
+    /// Answer the matching queries with `rows`
+    pub fn returning<T, I>(&mut self, rows: I) -> &mut Self
+    where
+        T: IntoMockRow,
+        I: IntoIterator<Item = T>,
+    {
+        self.result = Ok(rows.into_iter().map(IntoMockRow::into_mock_row).collect());
+        self
+    }
+
+    /// Answer the matching queries with `err`
+    pub fn returning_error(&mut self, err: DbErr) -> &mut Self {
+        self.result = Err(err);
+        self
+    }
+
+    /// Expect `n` matching queries rather than one
+    pub fn times(&mut self, n: usize) -> &mut Self {
+        self.expectation.times = n;
+        self
+    }
+}
+
+impl MockExecExpectation {
+    /// Answer the matching statements with `result`
+    pub fn returning(&mut self, result: MockExecResult) -> &mut Self {
+        self.result = Ok(result);
+        self
+    }
+
+    /// Answer the matching statements with `err`
+    pub fn returning_error(&mut self, err: DbErr) -> &mut Self {
+        self.result = Err(err);
+        self
+    }
+
+    /// Expect `n` matching statements rather than one
+    pub fn times(&mut self, n: usize) -> &mut Self {
+        self.expectation.times = n;
+        self
+    }
+}
+
+impl MockDatabaseTrait for MockDatabase {
+    #[instrument(level = "trace", skip(statement))]
+    fn execute(&mut self, counter: usize, statement: Statement) -> Result<ExecResult, DbErr> {
+        if let Some(expectation) = self
+            .exec_expectations
+            .iter_mut()
+            .find(|e| e.expectation.accepts(&statement))
+        {
+            expectation.expectation.calls += 1;
+            let answer = Answer::Expected(expectation.expectation.matcher.to_string());
+            let result = expectation.result.clone();
+            self.expected_execs += 1;
+            self.record(statement, answer);
+            return result.map(|result| ExecResult {
+                result: ExecResultHolder::Mock(result),
+            });
+        }
+        let counter = counter - self.expected_execs;
         if counter < self.exec_results.len() {
+            self.record(statement, Answer::InOrder);
             match std::mem::replace(
                 &mut self.exec_results[counter],
                 Err(exec_err("this value has been consumed already")),
@@ -146,31 +433,42 @@ impl MockDatabaseTrait for MockDatabase {
                 Err(err) => Err(err),
             }
         } else {
+            self.record(statement, Answer::Unexpected);
             Err(exec_err("`exec_results` buffer is empty"))
         }
     }
 
     #[instrument(level = "trace", skip(statement))]
     fn query(&mut self, counter: usize, statement: Statement) -> Result<Vec<QueryResult>, DbErr> {
-        if let Some(transaction) = &mut self.transaction {
-            transaction.push(statement);
-        } else {
-            self.transaction_log.push(Transaction::one(statement));
+        let into_query_results = |rows: Vec<MockRow>| {
+            rows.into_iter()
+                .map(|row| QueryResult {
+                    row: QueryResultRow::Mock(row),
+                })
+                .collect()
+        };
+        if let Some(expectation) = self
+            .query_expectations
+            .iter_mut()
+            .find(|e| e.expectation.accepts(&statement))
+        {
+            expectation.expectation.calls += 1;
+            let answer = Answer::Expected(expectation.expectation.matcher.to_string());
+            let result = expectation.result.clone();
+            self.expected_queries += 1;
+            self.record(statement, answer);
+            return result.map(into_query_results);
         }
+        let counter = counter - self.expected_queries;
         if counter < self.query_results.len() {
-            match std::mem::replace(
+            self.record(statement, Answer::InOrder);
+            std::mem::replace(
                 &mut self.query_results[counter],
                 Err(query_err("this value has been consumed already")),
-            ) {
-                Ok(result) => Ok(result
-                    .into_iter()
-                    .map(|row| QueryResult {
-                        row: QueryResultRow::Mock(row),
-                    })
-                    .collect()),
-                Err(err) => Err(err),
-            }
+            )
+            .map(into_query_results)
         } else {
+            self.record(statement, Answer::Unexpected);
             Err(query_err("`query_results` buffer is empty."))
         }
     }
@@ -187,10 +485,10 @@ impl MockDatabaseTrait for MockDatabase {
     fn commit(&mut self) {
         match self.transaction.as_mut() {
             Some(transaction) => {
-                if transaction.commit(self.db_backend) {
-                    if let Some(transaction) = self.transaction.take() {
-                        self.transaction_log.push(transaction.into_transaction());
-                    }
+                if transaction.commit(self.db_backend)
+                    && let Some(transaction) = self.transaction.take()
+                {
+                    self.transaction_log.push(transaction.into_transaction());
                 }
             }
             None => panic!("There is no open transaction to commit"),
@@ -201,16 +499,24 @@ impl MockDatabaseTrait for MockDatabase {
     fn rollback(&mut self) {
         match self.transaction.as_mut() {
             Some(transaction) => {
-                if transaction.rollback(self.db_backend) {
-                    if let Some(transaction) = self.transaction.take() {
-                        self.transaction_log.push(transaction.into_transaction());
-                    }
+                if transaction.rollback(self.db_backend)
+                    && let Some(transaction) = self.transaction.take()
+                {
+                    self.transaction_log.push(transaction.into_transaction());
                 }
             }
             None => panic!("There is no open transaction to rollback"),
         }
     }
 
+    #[instrument(level = "trace")]
+    fn savepoint(&mut self, stmt: Statement) {
+        match self.transaction.as_mut() {
+            Some(transaction) => transaction.push(stmt),
+            None => panic!("There is no open transaction to set a savepoint in"),
+        }
+    }
+
     fn drain_transaction_log(&mut self) -> Vec<Transaction> {
         std::mem::take(&mut self.transaction_log)
     }
@@ -222,6 +528,44 @@ impl MockDatabaseTrait for MockDatabase {
     fn ping(&self) -> Result<(), DbErr> {
         Ok(())
     }
+
+    fn verify(&self) -> Result<(), String> {
+        let unmet: Vec<_> = self
+            .query_expectations
+            .iter()
+            .filter_map(|e| e.expectation.unmet("query"))
+            .chain(
+                self.exec_expectations
+                    .iter()
+                    .filter_map(|e| e.expectation.unmet("exec")),
+            )
+            .collect();
+        let unexpected = self
+            .history
+            .iter()
+            .filter(|(_, answer)| matches!(answer, Answer::Unexpected))
+            .count();
+        if unmet.is_empty() && unexpected == 0 {
+            return Ok(());
+        }
+
+        let mut report = format!(
+            "MockDatabase expectations not met: {} unmet, {} unexpected statement(s)\n",
+            unmet.len(),
+            unexpected
+        );
+        for (statement, answer) in &self.history {
+            let _ = match answer {
+                Answer::Expected(matcher) => writeln!(report, "  {statement}  ({matcher})"),
+                Answer::InOrder => writeln!(report, "  {statement}"),
+                Answer::Unexpected => writeln!(report, "+ {statement}  (unexpected)"),
+            };
+        }
+        for unmet in unmet {
+            let _ = writeln!(report, "- {unmet}");
+        }
+        Err(report)
+    }
 }
 
 impl MockRow {
@@ -442,8 +786,8 @@ mod tests {
     #[cfg(feature = "sync")]
     use crate::util::StreamShim;
     use crate::{
-        DbBackend, DbErr, IntoMockRow, MockDatabase, Statement, Transaction, TransactionError,
-        TransactionTrait, entity::*, error::*, tests_cfg::*,
+        DbBackend, DbErr, IntoMockRow, MockDatabase, MockExecResult, Statement, StatementMatcher,
+        Transaction, TransactionError, TransactionTrait, entity::*, error::*, tests_cfg::*,
     };
     // In the sync variant `StreamShim` provides `try_next`; `futures_util` isn't a dependency there.
     #[cfg(not(feature = "sync"))]
@@ -623,6 +967,162 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_notify() -> Result<(), DbErr> {
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_exec_results([MockExecResult::default(), MockExecResult::default()])
+            .into_connection();
+
+        db.notify("jobs", "42")?;
+        let txn = db.begin()?;
+        txn.notify("cache", "cake")?;
+        txn.commit()?;
+
+        let notify = |channel: &str, payload: &str| {
+            Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                "SELECT pg_notify($1, $2)",
+                [channel.into(), payload.into()],
+            )
+        };
+        assert_eq!(
+            db.into_transaction_log(),
+            [
+                Transaction::one(notify("jobs", "42")),
+                Transaction::many([
+                    Statement::from_string(DbBackend::Postgres, "BEGIN"),
+                    notify("cache", "cake"),
+                    Statement::from_string(DbBackend::Postgres, "COMMIT"),
+                ]),
+            ]
+        );
+
+        let db = MockDatabase::new(DbBackend::MySql).into_connection();
+        assert!(matches!(
+            db.notify("jobs", "42"),
+            Err(DbErr::BackendNotSupported { ctx: "notify", .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_advisory_lock() -> Result<(), DbErr> {
+        let row = |acquired: bool| {
+            std::collections::BTreeMap::from([("acquired", acquired.into())]).into_mock_row()
+        };
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([[row(false)], [row(true)]])
+            .into_connection();
+
+        assert!(db.try_advisory_lock("leader")?.is_none());
+        let lock = db.try_advisory_lock("leader")?.expect("the lock is free");
+        assert_eq!(lock.key(), "leader");
+        lock.commit()?;
+
+        let try_lock = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "SELECT pg_try_advisory_xact_lock(hashtextextended($1, 0))",
+            ["leader".into()],
+        );
+        assert_eq!(
+            db.into_transaction_log(),
+            [
+                Transaction::many([
+                    Statement::from_string(DbBackend::Postgres, "BEGIN"),
+                    try_lock.clone(),
+                    Statement::from_string(DbBackend::Postgres, "ROLLBACK"),
+                ]),
+                Transaction::many([
+                    Statement::from_string(DbBackend::Postgres, "BEGIN"),
+                    try_lock,
+                    Statement::from_string(DbBackend::Postgres, "COMMIT"),
+                ]),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_schema() -> Result<(), DbErr> {
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_exec_results([MockExecResult::default(), MockExecResult::default()])
+            .append_query_results([Vec::<cake::Model>::new(), Vec::new()])
+            .into_connection();
+        let tenant = db.with_schema("tenant_a")?;
+        assert_eq!(tenant.get_schema(), Some("tenant_a"));
+        assert_eq!(db.get_schema(), None);
+
+        cake::Entity::find().all(&tenant)?;
+        let txn = tenant.begin()?;
+        cake::Entity::find().all(&txn)?;
+        txn.commit()?;
+
+        let search_path = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "SELECT set_config('search_path', $1, true)",
+            ["\"tenant_a\"".into()],
+        );
+        let select = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"SELECT "cake"."id", "cake"."name" FROM "cake""#,
+            [],
+        );
+        let in_schema = Transaction::many([
+            Statement::from_string(DbBackend::Postgres, "BEGIN"),
+            search_path,
+            select,
+            Statement::from_string(DbBackend::Postgres, "COMMIT"),
+        ]);
+        assert_eq!(db.into_transaction_log(), [in_schema.clone(), in_schema]);
+
+        let db = MockDatabase::new(DbBackend::MySql).into_connection();
+        assert!(matches!(
+            db.with_schema("tenant_a"),
+            Err(DbErr::BackendNotSupported {
+                ctx: "with_schema",
+                ..
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_named_savepoint() -> Result<(), DbErr> {
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_exec_results([MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .into_connection();
+
+        let txn = db.begin()?;
+        txn.savepoint("before_import")?;
+        cake::Entity::delete_many().exec(&txn)?;
+        txn.rollback_to("before_import")?;
+        txn.release("before_import")?;
+        txn.commit()?;
+
+        assert_eq!(
+            db.into_transaction_log(),
+            [Transaction::many([
+                Statement::from_string(DbBackend::Postgres, "BEGIN"),
+                Statement::from_string(DbBackend::Postgres, r#"SAVEPOINT "before_import""#),
+                Statement::from_sql_and_values(DbBackend::Postgres, r#"DELETE FROM "cake""#, []),
+                Statement::from_string(
+                    DbBackend::Postgres,
+                    r#"ROLLBACK TO SAVEPOINT "before_import""#
+                ),
+                Statement::from_string(DbBackend::Postgres, r#"RELEASE SAVEPOINT "before_import""#),
+                Statement::from_string(DbBackend::Postgres, "COMMIT"),
+            ])]
+        );
+
+        Ok(())
+    }
+
     #[test]
     #[cfg(feature = "stream")]
     fn test_stream_1() -> Result<(), DbErr> {
@@ -871,4 +1371,75 @@ mod tests {
 
         assert_eq!(model.save(&db), Err(exec_err("this is a mock exec error")));
     }
+
+    #[test]
+    fn test_expectations() -> Result<(), DbErr> {
+        let apple = fruit::Model {
+            id: 1,
+            name: "Apple".to_owned(),
+            cake_id: None,
+        };
+        let cheese_cake = cake::Model {
+            id: 2,
+            name: "Cheese Cake".to_owned(),
+        };
+
+        let mut db =
+            MockDatabase::new(DbBackend::Postgres).append_query_results([[cheese_cake.clone()]]);
+        db.expect_exec(StatementMatcher::regex(r#"^DELETE FROM "cake""#))
+            .returning(MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 2,
+            })
+            .times(2);
+        db.expect_query(StatementMatcher::table("fruit").with_values([1.into(), 1u64.into()]))
+            .returning([apple.clone()]);
+        db.expect_query(StatementMatcher::sql(
+            r#"SELECT "fruit"."id", "fruit"."name", "fruit"."cake_id" FROM "fruit""#,
+        ))
+        .returning_error(query_err("no fruit"));
+        let db = db.into_connection();
+
+        assert_eq!(fruit::Entity::find().all(&db), Err(query_err("no fruit")));
+        for _ in 0..2 {
+            assert_eq!(cake::Entity::delete_many().exec(&db)?.rows_affected, 2);
+        }
+        // answered by the result appended in order
+        assert_eq!(cake::Entity::find().all(&db)?, [cheese_cake]);
+        assert_eq!(fruit::Entity::find_by_id(1).one(&db)?, Some(apple));
+
+        db.as_mock_connection().verify();
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_expectations_not_met() -> Result<(), DbErr> {
+        let mut db = MockDatabase::new(DbBackend::Postgres);
+        db.expect_query(StatementMatcher::table("fruit")).times(2);
+        let db = db.into_connection();
+
+        fruit::Entity::find().all(&db)?;
+        assert!(cake::Entity::find().all(&db).is_err());
+
+        let report = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            db.as_mock_connection().verify()
+        }))
+        .expect_err("expectations were not met")
+        .downcast::<String>()
+        .expect("a report");
+        assert_eq!(
+            *report,
+            [
+                "MockDatabase expectations not met: 1 unmet, 1 unexpected statement(s)",
+                r#"  SELECT "fruit"."id", "fruit"."name", "fruit"."cake_id" FROM "fruit"  (table `fruit`)"#,
+                r#"+ SELECT "cake"."id", "cake"."name" FROM "cake"  (unexpected)"#,
+                "- query matching table `fruit`: expected 2 time(s), matched 1",
+                "",
+            ]
+            .join("\n")
+        );
+
+        Ok(())
+    }
 }
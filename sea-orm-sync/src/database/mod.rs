@@ -8,38 +8,67 @@ use sqlx::postgres::PgConnectOptions;
 #[cfg(feature = "sqlx-sqlite")]
 use sqlx::sqlite::SqliteConnectOptions;
 
+mod advisory_lock;
+#[cfg(feature = "query-cache")]
+mod cache;
 mod connection;
 mod db_connection;
 mod executor;
+#[cfg(feature = "memory-db")]
+#[cfg_attr(docsrs, doc(cfg(feature = "memory-db")))]
+mod memory;
 #[cfg(feature = "mock")]
 #[cfg_attr(docsrs, doc(cfg(feature = "mock")))]
 mod mock;
 #[cfg(feature = "proxy")]
 #[cfg_attr(docsrs, doc(cfg(feature = "proxy")))]
 mod proxy;
+#[cfg(all(feature = "proxy", feature = "with-json"))]
+mod proxy_protocol;
+#[cfg(all(feature = "mock", feature = "proxy", feature = "with-json"))]
+mod recording;
+mod replica;
 #[cfg(feature = "rbac")]
 mod restricted_connection;
+mod retry;
 #[cfg(all(feature = "schema-sync", feature = "rusqlite"))]
 mod sea_schema_rusqlite;
 #[cfg(all(feature = "schema-sync", feature = "sqlx-dep"))]
 mod sea_schema_shim;
+mod shard;
 mod statement;
 #[cfg(feature = "stream")]
 mod stream;
-mod tracing_spans;
-mod transaction;
-
+pub(crate) mod timeout;
+pub(crate) mod tracing_spans;
+pub(crate) mod transaction;
+#[cfg(all(feature = "proxy", feature = "with-json"))]
+mod value_serde;
+
+pub use advisory_lock::AdvisoryLock;
+#[cfg(feature = "query-cache")]
+pub use cache::{CachedConnection, CachedTransaction};
 pub use connection::*;
 pub use db_connection::*;
 pub use executor::*;
+#[cfg(feature = "memory-db")]
+#[cfg_attr(docsrs, doc(cfg(feature = "memory-db")))]
+pub use memory::MemoryDatabase;
 #[cfg(feature = "mock")]
 #[cfg_attr(docsrs, doc(cfg(feature = "mock")))]
 pub use mock::*;
 #[cfg(feature = "proxy")]
 #[cfg_attr(docsrs, doc(cfg(feature = "proxy")))]
 pub use proxy::*;
+#[cfg(all(feature = "proxy", feature = "with-json"))]
+pub use proxy_protocol::*;
+#[cfg(all(feature = "mock", feature = "proxy", feature = "with-json"))]
+pub use recording::*;
+pub use replica::ReplicaSelection;
 #[cfg(feature = "rbac")]
 pub use restricted_connection::*;
+pub use retry::RetryPolicy;
+pub use shard::{ShardedConnection, UnroutedStatement};
 pub use statement::*;
 use std::borrow::Cow;
 #[cfg(feature = "stream")]
@@ -113,10 +142,17 @@ pub struct ConnectOptions {
     /// Statement timeout (PostgreSQL only)
     pub(crate) statement_timeout: Option<Duration>,
     pub(crate) test_before_acquire: bool,
+    /// If set, a pooled connection is pinged before being handed out only when it has been
+    /// idle for at least this long (see [`ConnectOptions::test_before_acquire_if_idle_for`]).
+    pub(crate) test_before_acquire_if_idle_for: Option<Duration>,
     /// Only establish connections to the DB as needed. If set to `true`, the db connection will
     /// be created using SQLx's [connect_lazy](https://docs.rs/sqlx/latest/sqlx/struct.Pool.html#method.connect_lazy)
     /// method.
     pub(crate) connect_lazy: bool,
+    /// URIs of read replicas; reads are routed to them, writes stay on `url`
+    pub(crate) read_replicas: Vec<String>,
+    /// How a replica is picked for each read
+    pub(crate) replica_selection: ReplicaSelection,
 
     #[debug(skip)]
     pub(crate) after_connect: AfterConnectCallback,
@@ -139,6 +175,16 @@ pub struct ConnectOptions {
     #[cfg(feature = "sqlx-sqlite")]
     #[debug(skip)]
     pub(crate) sqlite_opts_fn: Option<Arc<dyn Fn(SqliteConnectOptions) -> SqliteConnectOptions>>,
+
+    #[cfg(feature = "sqlx-mysql")]
+    #[debug(skip)]
+    pub(crate) mysql_before_acquire_fn: Option<crate::driver::BeforeAcquireFn<sqlx::MySql>>,
+    #[cfg(feature = "sqlx-postgres")]
+    #[debug(skip)]
+    pub(crate) pg_before_acquire_fn: Option<crate::driver::BeforeAcquireFn<sqlx::Postgres>>,
+    #[cfg(feature = "sqlx-sqlite")]
+    #[debug(skip)]
+    pub(crate) sqlite_before_acquire_fn: Option<crate::driver::BeforeAcquireFn<sqlx::Sqlite>>,
 }
 
 impl Database {
@@ -149,8 +195,31 @@ impl Database {
     where
         C: Into<ConnectOptions>,
     {
-        let opt: ConnectOptions = opt.into();
+        let mut opt: ConnectOptions = opt.into();
+
+        let replica_selection = opt.replica_selection;
+        let replica_opts: Vec<ConnectOptions> = std::mem::take(&mut opt.read_replicas)
+            .into_iter()
+            .map(|url| {
+                let mut replica_opt = opt.clone();
+                replica_opt.url = url;
+                replica_opt
+            })
+            .collect();
+
+        let conn = Self::connect_one(opt)?;
+        if replica_opts.is_empty() {
+            return Ok(conn);
+        }
+
+        let mut replicas = Vec::with_capacity(replica_opts.len());
+        for replica_opt in replica_opts {
+            replicas.push(Self::connect_one(replica_opt)?);
+        }
+        Ok(conn.with_read_replicas(replicas, replica_selection))
+    }
 
+    fn connect_one(opt: ConnectOptions) -> Result<DatabaseConnection, DbErr> {
         if url::Url::parse(&opt.url).is_err() {
             return Err(conn_err(format!(
                 "The connection string '{}' cannot be parsed.",
@@ -174,6 +243,10 @@ impl Database {
         if DbBackend::Sqlite.is_prefix_of(&opt.url) {
             return crate::driver::rusqlite::RusqliteConnector::connect(opt);
         }
+        #[cfg(feature = "duckdb")]
+        if crate::DuckdbConnector::accepts(&opt.url) {
+            return crate::DuckdbConnector::connect(opt);
+        }
         #[cfg(feature = "mock")]
         if crate::MockDatabaseConnector::accepts(&opt.url) {
             return crate::MockDatabaseConnector::connect(&opt.url);
@@ -248,7 +321,10 @@ impl ConnectOptions {
             application_name: None,
             statement_timeout: None,
             test_before_acquire: true,
+            test_before_acquire_if_idle_for: None,
             connect_lazy: false,
+            read_replicas: Vec::new(),
+            replica_selection: ReplicaSelection::RoundRobin,
             after_connect: None,
             #[cfg(feature = "sqlx-mysql")]
             mysql_pool_opts_fn: None,
@@ -262,6 +338,12 @@ impl ConnectOptions {
             pg_opts_fn: None,
             #[cfg(feature = "sqlx-sqlite")]
             sqlite_opts_fn: None,
+            #[cfg(feature = "sqlx-mysql")]
+            mysql_before_acquire_fn: None,
+            #[cfg(feature = "sqlx-postgres")]
+            pg_before_acquire_fn: None,
+            #[cfg(feature = "sqlx-sqlite")]
+            sqlite_before_acquire_fn: None,
         }
     }
 
@@ -441,11 +523,78 @@ impl ConnectOptions {
     }
 
     /// If true, the connection will be pinged upon acquiring from the pool (default true).
+    ///
+    /// See [`test_before_acquire_if_idle_for`](Self::test_before_acquire_if_idle_for) for the
+    /// cheaper "only ping stale connections" variant.
     pub fn test_before_acquire(&mut self, value: bool) -> &mut Self {
         self.test_before_acquire = value;
         self
     }
 
+    /// Get whether a pooled connection is pinged on every acquire (default true).
+    pub fn get_test_before_acquire(&self) -> bool {
+        self.test_before_acquire
+    }
+
+    /// Ping a pooled connection before handing it out, but only once it has been idle for at
+    /// least `idle`.
+    ///
+    /// [`test_before_acquire`](Self::test_before_acquire) pings on *every* acquire, which adds
+    /// a round-trip to each checkout. This shorthand instead pings only connections that have
+    /// been idle long enough to plausibly have been dropped by the server, a proxy, or a
+    /// firewall — the common failure mode — while letting hot connections through untouched.
+    ///
+    /// Calling this sets [`test_before_acquire`](Self::test_before_acquire) to `false` (SQLx
+    /// runs the per-acquire ping *and* this hook, so leaving it enabled would ping on every
+    /// acquire regardless and defeat the threshold).
+    ///
+    /// It composes with a per-backend [`map_sqlx_postgres_before_acquire`] callback (and its
+    /// MySQL / SQLite counterparts): the idle-ping runs first, then your callback.
+    ///
+    /// # Expands to
+    /// With no per-backend callback set, this is exactly the following configuration on the
+    /// underlying [`sqlx::pool::PoolOptions`]:
+    ///
+    /// ```ignore
+    /// pool_options
+    ///     .test_before_acquire(false)
+    ///     .before_acquire(move |conn, meta| {
+    ///         ({
+    ///             if meta.idle_for >= idle {
+    ///                 conn.ping()?;
+    ///             }
+    ///             Ok(true)
+    ///         })
+    ///     })
+    /// ```
+    ///
+    /// Applies only to pools built through [`Database::connect`]. Pools adopted via
+    /// `SqlxPostgresConnector::from_sqlx_postgres_pool` (and the MySQL / SQLite equivalents)
+    /// bypass [`ConnectOptions`] entirely — configure `before_acquire` on your own
+    /// [`sqlx::pool::PoolOptions`] in that case.
+    ///
+    /// # Example
+    /// ```
+    /// # use sea_orm::ConnectOptions;
+    /// # use std::time::Duration;
+    /// let mut opt = ConnectOptions::new("postgres://localhost/db");
+    /// opt.test_before_acquire_if_idle_for(Duration::from_secs(30));
+    /// assert_eq!(opt.get_test_before_acquire(), false);
+    /// ```
+    ///
+    /// [`map_sqlx_postgres_before_acquire`]: Self::map_sqlx_postgres_before_acquire
+    pub fn test_before_acquire_if_idle_for(&mut self, idle: Duration) -> &mut Self {
+        self.test_before_acquire = false;
+        self.test_before_acquire_if_idle_for = Some(idle);
+        self
+    }
+
+    /// Get the idle threshold set by
+    /// [`test_before_acquire_if_idle_for`](Self::test_before_acquire_if_idle_for), if any.
+    pub fn get_test_before_acquire_if_idle_for(&self) -> Option<Duration> {
+        self.test_before_acquire_if_idle_for
+    }
+
     /// If set to `true`, the db connection pool will be created using SQLx's
     /// [connect_lazy](https://docs.rs/sqlx/latest/sqlx/struct.Pool.html#method.connect_lazy) method.
     pub fn connect_lazy(&mut self, value: bool) -> &mut Self {
@@ -458,6 +607,59 @@ impl ConnectOptions {
         self.connect_lazy
     }
 
+    /// Add the URI of a read replica.
+    ///
+    /// When at least one replica is set, [`Database::connect`] opens a pool for each of them
+    /// with the same options as the primary, and the returned [`DatabaseConnection`] sends
+    /// plain `SELECT` queries to a replica. Writes, [`execute_unprepared`], locking reads and
+    /// everything inside a [`DatabaseTransaction`] go to the primary. Use
+    /// [`DatabaseConnection::use_primary`] to read your own writes.
+    ///
+    /// [`execute_unprepared`]: crate::ConnectionTrait::execute_unprepared
+    ///
+    /// # Example
+    /// ```
+    /// # use sea_orm::{ConnectOptions, ReplicaSelection};
+    /// let mut opt = ConnectOptions::new("postgres://primary/db");
+    /// opt.read_replica("postgres://replica-1/db")
+    ///     .read_replica("postgres://replica-2/db")
+    ///     .replica_selection(ReplicaSelection::LeastBusy);
+    /// assert_eq!(opt.get_read_replicas().len(), 2);
+    /// ```
+    pub fn read_replica<T>(&mut self, url: T) -> &mut Self
+    where
+        T: Into<String>,
+    {
+        self.read_replicas.push(url.into());
+        self
+    }
+
+    /// Add the URIs of several read replicas; see [`read_replica`](Self::read_replica).
+    pub fn read_replicas<I, T>(&mut self, urls: I) -> &mut Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.read_replicas.extend(urls.into_iter().map(Into::into));
+        self
+    }
+
+    /// Get the URIs of the read replicas
+    pub fn get_read_replicas(&self) -> &[String] {
+        &self.read_replicas
+    }
+
+    /// Set how a read replica is picked for each read (default round-robin)
+    pub fn replica_selection(&mut self, value: ReplicaSelection) -> &mut Self {
+        self.replica_selection = value;
+        self
+    }
+
+    /// Get how a read replica is picked for each read
+    pub fn get_replica_selection(&self) -> ReplicaSelection {
+        self.replica_selection
+    }
+
     /// Set a callback function that will be called after a new connection is established.
     pub fn after_connect<F>(&mut self, f: F) -> &mut Self
     where
@@ -542,4 +744,96 @@ impl ConnectOptions {
         self.sqlite_pool_opts_fn = Some(Arc::new(f));
         self
     }
+
+    #[cfg(feature = "sqlx-mysql")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sqlx-mysql")))]
+    /// Set a `before_acquire` callback run on an idle pooled MySQL connection before it is
+    /// handed out.
+    ///
+    /// Return `Ok(true)` to use the connection, or `Ok(false)`/`Err(_)` to discard it and let
+    /// the pool try another (opening a fresh one if needed). Composes after the idle-ping
+    /// installed by
+    /// [`test_before_acquire_if_idle_for`](Self::test_before_acquire_if_idle_for) — that runs
+    /// first, then this callback.
+    ///
+    /// Applies only to pools built through [`Database::connect`], not to pools adopted via
+    /// `SqlxMySqlConnector::from_sqlx_mysql_pool`.
+    pub fn map_sqlx_mysql_before_acquire<F>(&mut self, f: F) -> &mut Self
+    where
+        F: for<'c> Fn(
+                &'c mut sqlx::mysql::MySqlConnection,
+                sqlx::pool::PoolConnectionMetadata,
+            )
+                -> futures_util::future::BoxFuture<'c, Result<bool, sqlx::Error>>
+            + 'static,
+    {
+        self.mysql_before_acquire_fn = Some(Arc::new(f));
+        self
+    }
+
+    #[cfg(feature = "sqlx-postgres")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sqlx-postgres")))]
+    /// Set a `before_acquire` callback run on an idle pooled PostgreSQL connection before it
+    /// is handed out.
+    ///
+    /// Return `Ok(true)` to use the connection, or `Ok(false)`/`Err(_)` to discard it and let
+    /// the pool try another (opening a fresh one if needed). Composes after the idle-ping
+    /// installed by
+    /// [`test_before_acquire_if_idle_for`](Self::test_before_acquire_if_idle_for) — that runs
+    /// first, then this callback.
+    ///
+    /// Applies only to pools built through [`Database::connect`], not to pools adopted via
+    /// `SqlxPostgresConnector::from_sqlx_postgres_pool`.
+    ///
+    /// # Example
+    /// ```
+    /// # use sea_orm::ConnectOptions;
+    /// # use std::time::Duration;
+    /// let mut opt = ConnectOptions::new("postgres://localhost/db");
+    /// opt.map_sqlx_postgres_before_acquire(|_conn, meta| {
+    ///     ({
+    ///         // Discard (and transparently replace) connections older than 10 minutes,
+    ///         // rather than pinging on every acquire.
+    ///         Ok(meta.age < Duration::from_secs(600))
+    ///     })
+    /// });
+    /// ```
+    pub fn map_sqlx_postgres_before_acquire<F>(&mut self, f: F) -> &mut Self
+    where
+        F: for<'c> Fn(
+                &'c mut sqlx::postgres::PgConnection,
+                sqlx::pool::PoolConnectionMetadata,
+            )
+                -> futures_util::future::BoxFuture<'c, Result<bool, sqlx::Error>>
+            + 'static,
+    {
+        self.pg_before_acquire_fn = Some(Arc::new(f));
+        self
+    }
+
+    #[cfg(feature = "sqlx-sqlite")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sqlx-sqlite")))]
+    /// Set a `before_acquire` callback run on an idle pooled SQLite connection before it is
+    /// handed out.
+    ///
+    /// Return `Ok(true)` to use the connection, or `Ok(false)`/`Err(_)` to discard it and let
+    /// the pool try another (opening a fresh one if needed). Composes after the idle-ping
+    /// installed by
+    /// [`test_before_acquire_if_idle_for`](Self::test_before_acquire_if_idle_for) — that runs
+    /// first, then this callback.
+    ///
+    /// Applies only to pools built through [`Database::connect`], not to pools adopted via
+    /// `SqlxSqliteConnector::from_sqlx_sqlite_pool`.
+    pub fn map_sqlx_sqlite_before_acquire<F>(&mut self, f: F) -> &mut Self
+    where
+        F: for<'c> Fn(
+                &'c mut sqlx::sqlite::SqliteConnection,
+                sqlx::pool::PoolConnectionMetadata,
+            )
+                -> futures_util::future::BoxFuture<'c, Result<bool, sqlx::Error>>
+            + 'static,
+    {
+        self.sqlite_before_acquire_fn = Some(Arc::new(f));
+        self
+    }
 }
@@ -0,0 +1,795 @@
+use crate::{
+    ConnectionTrait, DatabaseConnection, DatabaseConnectionType, DatabaseTransaction, DbBackend,
+    DbErr, ProxyDatabaseConnection, ProxyDatabaseTrait, ProxyExecResult, ProxyRow, Statement,
+    TransactionTrait, error::*, from_query_result_to_proxy_row,
+};
+use sea_query::Values;
+use serde::{Deserialize, Serialize};
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::{
+        Arc, Mutex, MutexGuard,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+/// The version of the proxy protocol spoken by [`ProxyServer`] and [`ProxyClient`].
+/// Requests and responses of any other version are refused.
+pub const PROXY_PROTOCOL_VERSION: u32 = 1;
+
+/// A request of the proxy protocol, sent by a [`ProxyClient`] to a [`ProxyServer`] as JSON
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "proxy", feature = "with-json"))))]
+pub struct ProxyRequest {
+    /// The protocol version of the client
+    pub version: u32,
+    /// The transaction to run the command in, `None` to run it on the connection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<u64>,
+    /// The command to run
+    pub command: ProxyCommand,
+}
+
+/// A command of a [`ProxyRequest`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "proxy", feature = "with-json"))))]
+pub enum ProxyCommand {
+    /// Run a query, answered with [`ProxyReply::Rows`]
+    Query {
+        /// The SQL of the statement
+        sql: String,
+        /// The values bound to the statement
+        #[serde(default, with = "super::value_serde::values")]
+        values: Option<Values>,
+    },
+    /// Run any other statement, answered with [`ProxyReply::Exec`]
+    Execute {
+        /// The SQL of the statement
+        sql: String,
+        /// The values bound to the statement
+        #[serde(default, with = "super::value_serde::values")]
+        values: Option<Values>,
+    },
+    /// Begin a transaction, nested in the transaction of the request if any, answered
+    /// with [`ProxyReply::Transaction`]
+    Begin,
+    /// Commit the transaction of the request, answered with [`ProxyReply::Done`]
+    Commit,
+    /// Roll back the transaction of the request, answered with [`ProxyReply::Done`]
+    Rollback,
+    /// Check that the database is available, answered with [`ProxyReply::Pong`]
+    Ping,
+}
+
+/// A response of the proxy protocol, sent by a [`ProxyServer`] as JSON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "proxy", feature = "with-json"))))]
+pub struct ProxyResponse {
+    /// The protocol version of the server
+    pub version: u32,
+    /// The outcome of the command
+    pub reply: ProxyReply,
+}
+
+/// The outcome of a [`ProxyCommand`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "proxy", feature = "with-json"))))]
+pub enum ProxyReply {
+    /// The rows returned by a query
+    Rows {
+        /// The rows, with their values typed
+        rows: Vec<ProxyRow>,
+    },
+    /// The result of a statement
+    Exec(ProxyExecResult),
+    /// A transaction has begun
+    Transaction {
+        /// The id to send along with the commands to run in the transaction
+        id: u64,
+    },
+    /// The transaction has been committed or rolled back
+    Done,
+    /// The database is available
+    Pong {
+        /// The backend of the database
+        #[serde(with = "super::value_serde::backend_name")]
+        db_backend: DbBackend,
+    },
+    /// The command failed
+    Error {
+        /// The kind of [`DbErr`] the command failed with
+        error: ProxyErrorKind,
+        /// The error message
+        message: String,
+    },
+}
+
+/// The kind of [`DbErr`] carried by [`ProxyReply::Error`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "proxy", feature = "with-json"))))]
+pub enum ProxyErrorKind {
+    /// [`DbErr::Conn`]
+    Conn,
+    /// [`DbErr::Exec`]
+    Exec,
+    /// [`DbErr::Query`]
+    Query,
+    /// Any other error, turned into [`DbErr::Custom`]
+    Other,
+}
+
+impl ProxyRequest {
+    /// Create a request of the current protocol version
+    pub fn new(transaction: Option<u64>, command: ProxyCommand) -> Self {
+        Self {
+            version: PROXY_PROTOCOL_VERSION,
+            transaction,
+            command,
+        }
+    }
+}
+
+impl ProxyResponse {
+    /// Create a response of the current protocol version
+    pub fn new(reply: ProxyReply) -> Self {
+        Self {
+            version: PROXY_PROTOCOL_VERSION,
+            reply,
+        }
+    }
+}
+
+impl From<&DbErr> for ProxyReply {
+    fn from(err: &DbErr) -> Self {
+        let (error, message) = match err {
+            DbErr::Conn(err) => (ProxyErrorKind::Conn, err.to_string()),
+            DbErr::Exec(err) => (ProxyErrorKind::Exec, err.to_string()),
+            DbErr::Query(err) => (ProxyErrorKind::Query, err.to_string()),
+            err => (ProxyErrorKind::Other, err.to_string()),
+        };
+        Self::Error { error, message }
+    }
+}
+
+impl ProxyErrorKind {
+    /// Turn the error message back into a [`DbErr`]
+    pub fn into_err(self, message: String) -> DbErr {
+        match self {
+            Self::Conn => conn_err(message),
+            Self::Exec => exec_err(message),
+            Self::Query => query_err(message),
+            Self::Other => DbErr::Custom(message),
+        }
+    }
+}
+
+/// Answers [`ProxyRequest`]s by running them on a [`DatabaseConnection`], keeping the
+/// transactions begun by its clients under increasing ids.
+///
+/// Use it in process as the [`ProxyTransport`] of a [`ProxyClient`], or behind any
+/// transport with [`handle_json`](Self::handle_json). With the `proxy-server` feature,
+/// `serve_proxy` exposes it over a Unix socket or local HTTP.
+///
+/// A transaction stays open until a client commits or rolls it back, or until it has gone
+/// unused for longer than the [idle timeout](Self::idle_timeout); requests answered through
+/// a [`ProxySession`] also roll back the transactions of the session when it closes.
+/// Commands of a transaction have to be sent one at a time; ending a transaction while one
+/// of its commands is running is refused.
+#[derive(Debug, Clone)]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "proxy", feature = "with-json"))))]
+pub struct ProxyServer {
+    db: DatabaseConnection,
+    transactions: Arc<Mutex<HashMap<u64, OpenTransaction>>>,
+    next_id: Arc<AtomicU64>,
+    idle_timeout: Duration,
+}
+
+#[derive(Debug)]
+struct OpenTransaction {
+    txn: Arc<DatabaseTransaction>,
+    last_used: Instant,
+}
+
+/// The transactions begun through one connection to a [`ProxyServer`], rolled back if
+/// they are still open when the connection [closes](Self::close)
+#[derive(Debug)]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "proxy", feature = "with-json"))))]
+pub struct ProxySession {
+    server: ProxyServer,
+    transactions: Vec<u64>,
+}
+
+impl ProxyServer {
+    /// Transactions left unused for longer than this are rolled back, unless set otherwise
+    /// with [`ProxyServer::idle_timeout`]
+    pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+    /// Create a server answering requests with `db`
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self {
+            db,
+            transactions: Default::default(),
+            next_id: Arc::new(AtomicU64::new(1)),
+            idle_timeout: Self::DEFAULT_IDLE_TIMEOUT,
+        }
+    }
+
+    /// Roll back transactions once no command has been run in them for `timeout`, so that
+    /// a client that went away does not keep them open
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = timeout;
+        self
+    }
+
+    /// Start a session, for the requests of one connection
+    pub fn session(&self) -> ProxySession {
+        ProxySession {
+            server: self.clone(),
+            transactions: Vec::new(),
+        }
+    }
+
+    /// Roll back the transactions that have gone unused for longer than the idle timeout.
+    /// Run before answering every request, and periodically by `serve_proxy`.
+    pub fn roll_back_idle(&self) {
+        let idle: Vec<_> = {
+            let mut transactions = self.lock();
+            let mut ids: Vec<_> = transactions
+                .iter()
+                .filter(|(_, open)| {
+                    open.last_used.elapsed() > self.idle_timeout
+                        && Arc::strong_count(&open.txn) == 1
+                })
+                .map(|(id, _)| *id)
+                .collect();
+            ids.sort_unstable();
+            ids.into_iter()
+                .filter_map(|id| transactions.remove(&id))
+                .collect()
+        };
+        // nested transactions were begun later, roll them back first
+        for open in idle.into_iter().rev() {
+            let Ok(txn) = Arc::try_unwrap(open.txn) else {
+                continue;
+            };
+            if let Err(err) = txn.rollback() {
+                tracing::warn!("Failed to roll back idle proxy transaction: {err}");
+            }
+        }
+    }
+
+    /// Answer a request
+    pub fn handle(&self, request: ProxyRequest) -> ProxyResponse {
+        self.roll_back_idle();
+        if request.version != PROXY_PROTOCOL_VERSION {
+            return ProxyResponse::new(ProxyReply::from(&conn_err(format!(
+                "Unsupported proxy protocol version {}",
+                request.version
+            ))));
+        }
+        let reply = match self.run(request.transaction, request.command) {
+            Ok(reply) => reply,
+            Err(err) => ProxyReply::from(&err),
+        };
+        ProxyResponse::new(reply)
+    }
+
+    /// Answer a request encoded as JSON, with a response encoded as JSON. A request that
+    /// cannot be decoded is answered with [`ProxyReply::Error`].
+    pub fn handle_json(&self, request: &str) -> Result<String, DbErr> {
+        let response = match serde_json::from_str(request) {
+            Ok(request) => self.handle(request),
+            Err(err) => ProxyResponse::new(ProxyReply::from(&json_err(err))),
+        };
+        serde_json::to_string(&response).map_err(json_err)
+    }
+
+    fn run(&self, transaction: Option<u64>, command: ProxyCommand) -> Result<ProxyReply, DbErr> {
+        let db_backend = self.db.get_database_backend();
+        match command {
+            ProxyCommand::Query { sql, values } => {
+                let stmt = Statement {
+                    sql,
+                    values,
+                    db_backend,
+                };
+                let rows = match self.transaction(transaction)? {
+                    Some(txn) => txn.query_all_raw(stmt)?,
+                    None => self.db.query_all_raw(stmt)?,
+                };
+                Ok(ProxyReply::Rows {
+                    rows: rows.iter().map(from_query_result_to_proxy_row).collect(),
+                })
+            }
+            ProxyCommand::Execute { sql, values } => {
+                let stmt = Statement {
+                    sql,
+                    values,
+                    db_backend,
+                };
+                let result = match self.transaction(transaction)? {
+                    Some(txn) => txn.execute_raw(stmt)?,
+                    None => self.db.execute_raw(stmt)?,
+                };
+                Ok(ProxyReply::Exec(result.into()))
+            }
+            ProxyCommand::Begin => {
+                let txn = match self.transaction(transaction)? {
+                    Some(txn) => txn.begin()?,
+                    None => self.db.begin()?,
+                };
+                let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+                self.lock().insert(
+                    id,
+                    OpenTransaction {
+                        txn: Arc::new(txn),
+                        last_used: Instant::now(),
+                    },
+                );
+                Ok(ProxyReply::Transaction { id })
+            }
+            ProxyCommand::Commit => {
+                self.take(transaction)?.commit()?;
+                Ok(ProxyReply::Done)
+            }
+            ProxyCommand::Rollback => {
+                self.take(transaction)?.rollback()?;
+                Ok(ProxyReply::Done)
+            }
+            ProxyCommand::Ping => {
+                self.db.ping()?;
+                Ok(ProxyReply::Pong { db_backend })
+            }
+        }
+    }
+
+    fn transaction(&self, id: Option<u64>) -> Result<Option<Arc<DatabaseTransaction>>, DbErr> {
+        let Some(id) = id else {
+            return Ok(None);
+        };
+        match self.lock().get_mut(&id) {
+            Some(open) => {
+                open.last_used = Instant::now();
+                Ok(Some(Arc::clone(&open.txn)))
+            }
+            None => Err(conn_err(format!("Unknown transaction {id}"))),
+        }
+    }
+
+    fn take(&self, id: Option<u64>) -> Result<DatabaseTransaction, DbErr> {
+        let id = id.ok_or_else(|| conn_err("No transaction to end"))?;
+        let mut transactions = self.lock();
+        let open = transactions
+            .remove(&id)
+            .ok_or_else(|| conn_err(format!("Unknown transaction {id}")))?;
+        Arc::try_unwrap(open.txn).map_err(|txn| {
+            transactions.insert(
+                id,
+                OpenTransaction {
+                    txn,
+                    last_used: open.last_used,
+                },
+            );
+            conn_err(format!("Transaction {id} is in use"))
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<u64, OpenTransaction>> {
+        self.transactions
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl ProxySession {
+    /// Answer a request, keeping track of the transactions it begins and ends
+    pub fn handle(&mut self, request: ProxyRequest) -> ProxyResponse {
+        let ends = match request.command {
+            ProxyCommand::Commit | ProxyCommand::Rollback => request.transaction,
+            _ => None,
+        };
+        let response = self.server.handle(request);
+        match response.reply {
+            ProxyReply::Transaction { id } => self.transactions.push(id),
+            ProxyReply::Done => self.transactions.retain(|id| Some(*id) != ends),
+            _ => {}
+        }
+        response
+    }
+
+    /// Answer a request encoded as JSON, like [`ProxyServer::handle_json`]
+    pub fn handle_json(&mut self, request: &str) -> Result<String, DbErr> {
+        let response = match serde_json::from_str(request) {
+            Ok(request) => self.handle(request),
+            Err(err) => ProxyResponse::new(ProxyReply::from(&json_err(err))),
+        };
+        serde_json::to_string(&response).map_err(json_err)
+    }
+
+    /// End the session, rolling back the transactions it left open
+    pub fn close(self) {
+        // nested transactions were begun later, roll them back first
+        for id in self.transactions.into_iter().rev() {
+            let res = match self.server.take(Some(id)) {
+                Ok(txn) => txn.rollback(),
+                Err(err) => Err(err),
+            };
+            if let Err(err) = res {
+                tracing::warn!("Failed to roll back proxy transaction {id}: {err}");
+            }
+        }
+    }
+}
+
+/// Carries the JSON encoded [`ProxyRequest`]s of a [`ProxyClient`] to a [`ProxyServer`]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "proxy", feature = "with-json"))))]
+pub trait ProxyTransport: Debug {
+    /// Send a request, and wait for the response
+    fn send(&self, request: String) -> Result<String, DbErr>;
+}
+
+impl ProxyTransport for ProxyServer {
+    fn send(&self, request: String) -> Result<String, DbErr> {
+        self.handle_json(&request)
+    }
+}
+
+/// A [`ProxyDatabaseTrait`] sending every statement to a [`ProxyServer`] through a
+/// [`ProxyTransport`].
+///
+/// The client keeps the ids of the transactions it has begun as a stack; statements run
+/// in the innermost one. As with the other proxy connections, the transactions are shared
+/// by the [`DatabaseConnection`] and its clones. A transaction dropped without commit is
+/// rolled back before the next request.
+#[derive(Debug)]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "proxy", feature = "with-json"))))]
+pub struct ProxyClient<T> {
+    transport: T,
+    state: Mutex<ClientState>,
+}
+
+#[derive(Debug, Default)]
+struct ClientState {
+    /// The open transactions, innermost last; `Err` if the server could not begin it
+    transactions: Vec<Result<u64, String>>,
+    /// The dropped transactions, to be rolled back
+    abandoned: Vec<u64>,
+}
+
+impl<T> ProxyClient<T>
+where
+    T: ProxyTransport,
+{
+    /// Create a client sending requests through `transport`
+    pub fn new(transport: T) -> Self {
+        Self {
+            transport,
+            state: Default::default(),
+        }
+    }
+
+    /// Create a [`DatabaseConnection`] sending requests through `transport`, with the
+    /// backend reported by the server
+    pub fn connect(transport: T) -> Result<DatabaseConnection, DbErr>
+    where
+        T: 'static,
+    {
+        let client = Self::new(transport);
+        let db_backend = client.db_backend()?;
+        Ok(client.into_connection(db_backend))
+    }
+
+    /// Ask the server for the backend of its database
+    pub fn db_backend(&self) -> Result<DbBackend, DbErr> {
+        match self.request(None, ProxyCommand::Ping)? {
+            ProxyReply::Pong { db_backend } => Ok(db_backend),
+            reply => Err(unexpected(reply)),
+        }
+    }
+
+    /// Create a [`DatabaseConnection`] for a server with the given backend
+    pub fn into_connection(self, db_backend: DbBackend) -> DatabaseConnection
+    where
+        T: 'static,
+    {
+        DatabaseConnectionType::ProxyDatabaseConnection(Arc::new(ProxyDatabaseConnection::new(
+            db_backend,
+            Arc::new(Box::new(self)),
+        )))
+        .into()
+    }
+
+    fn request(
+        &self,
+        transaction: Option<u64>,
+        command: ProxyCommand,
+    ) -> Result<ProxyReply, DbErr> {
+        let request =
+            serde_json::to_string(&ProxyRequest::new(transaction, command)).map_err(json_err)?;
+        let response = self.transport.send(request)?;
+        let response: ProxyResponse = serde_json::from_str(&response).map_err(json_err)?;
+        if response.version != PROXY_PROTOCOL_VERSION {
+            return Err(conn_err(format!(
+                "Unsupported proxy protocol version {}",
+                response.version
+            )));
+        }
+        match response.reply {
+            ProxyReply::Error { error, message } => Err(error.into_err(message)),
+            reply => Ok(reply),
+        }
+    }
+
+    /// Roll back the transactions dropped since the last request
+    fn flush(&self) {
+        let abandoned = std::mem::take(&mut self.state().abandoned);
+        for id in abandoned.into_iter().rev() {
+            if let Err(err) = self.request(Some(id), ProxyCommand::Rollback) {
+                tracing::warn!("Failed to roll back proxy transaction {id}: {err}");
+            }
+        }
+    }
+
+    /// The innermost open transaction
+    fn current(&self) -> Result<Option<u64>, DbErr> {
+        match self.state().transactions.last() {
+            Some(Ok(id)) => Ok(Some(*id)),
+            Some(Err(message)) => Err(conn_err(message)),
+            None => Ok(None),
+        }
+    }
+
+    fn end(&self, command: ProxyCommand) {
+        self.flush();
+        let Some(Ok(id)) = self.state().transactions.pop() else {
+            return;
+        };
+        if let Err(err) = self.request(Some(id), command) {
+            tracing::warn!("Failed to end proxy transaction {id}: {err}");
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, ClientState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl<T> ProxyDatabaseTrait for ProxyClient<T>
+where
+    T: ProxyTransport,
+{
+    fn query(&self, statement: Statement) -> Result<Vec<ProxyRow>, DbErr> {
+        self.flush();
+        let command = ProxyCommand::Query {
+            sql: statement.sql,
+            values: statement.values,
+        };
+        match self.request(self.current()?, command)? {
+            ProxyReply::Rows { rows } => Ok(rows),
+            reply => Err(unexpected(reply)),
+        }
+    }
+
+    fn execute(&self, statement: Statement) -> Result<ProxyExecResult, DbErr> {
+        self.flush();
+        let command = ProxyCommand::Execute {
+            sql: statement.sql,
+            values: statement.values,
+        };
+        match self.request(self.current()?, command)? {
+            ProxyReply::Exec(result) => Ok(result),
+            reply => Err(unexpected(reply)),
+        }
+    }
+
+    fn begin(&self) {
+        self.flush();
+        // a transaction that failed to begin is kept on the stack, so that the statements
+        // meant for it fail instead of running outside of it
+        let transaction = match self.current() {
+            Ok(parent) => match self.request(parent, ProxyCommand::Begin) {
+                Ok(ProxyReply::Transaction { id }) => Ok(id),
+                Ok(reply) => Err(unexpected(reply).to_string()),
+                Err(err) => {
+                    tracing::warn!("Failed to begin proxy transaction: {err}");
+                    Err(err.to_string())
+                }
+            },
+            Err(err) => Err(err.to_string()),
+        };
+        self.state().transactions.push(transaction);
+    }
+
+    fn commit(&self) {
+        self.end(ProxyCommand::Commit)
+    }
+
+    fn rollback(&self) {
+        self.end(ProxyCommand::Rollback)
+    }
+
+    fn start_rollback(&self) {
+        let mut state = self.state();
+        if let Some(Ok(id)) = state.transactions.pop() {
+            state.abandoned.push(id);
+        }
+    }
+
+    fn ping(&self) -> Result<(), DbErr> {
+        self.flush();
+        self.db_backend().map(|_| ())
+    }
+}
+
+fn unexpected(reply: ProxyReply) -> DbErr {
+    conn_err(format!("Unexpected proxy reply: {reply:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DbBackend, MockDatabase, MockExecResult, Transaction, entity::*, tests_cfg::cake};
+    use pretty_assertions::assert_eq;
+    use sea_query::{Expr, Value};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_proxy_protocol_json() {
+        let request = ProxyRequest::new(
+            Some(2),
+            ProxyCommand::Query {
+                sql: r#"SELECT "id" FROM "cake" WHERE "id" = $1"#.to_owned(),
+                values: Some(Values(vec![1i64.into()])),
+            },
+        );
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "version": 1,
+                "transaction": 2,
+                "command": {
+                    "kind": "query",
+                    "sql": r#"SELECT "id" FROM "cake" WHERE "id" = $1"#,
+                    "values": [{ "BigInt": 1 }],
+                },
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<ProxyRequest>(json).unwrap(),
+            request
+        );
+
+        let row = ProxyRow::new(BTreeMap::from([
+            ("id".to_owned(), Value::BigInt(Some(1))),
+            ("name".to_owned(), Value::String(None)),
+            ("price".to_owned(), Value::Float(Some(1.5))),
+        ]));
+        let json = serde_json::to_string(&ProxyResponse::new(ProxyReply::Rows {
+            rows: vec![row.clone()],
+        }))
+        .unwrap();
+        let ProxyReply::Rows { rows } = serde_json::from_str::<ProxyResponse>(&json).unwrap().reply
+        else {
+            panic!("expected rows in {json}");
+        };
+        assert_eq!(rows[0].values, row.values);
+    }
+
+    #[test]
+    fn test_proxy_client_server() -> Result<(), DbErr> {
+        let mock = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([[cake::Model {
+                id: 1,
+                name: "Cheese Cake".to_owned(),
+            }]])
+            .append_exec_results([MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .into_connection();
+        let server = ProxyServer::new(mock.clone());
+        let db = ProxyClient::connect(server.clone())?;
+
+        assert_eq!(db.get_database_backend(), DbBackend::Postgres);
+        assert_eq!(
+            cake::Entity::find().all(&db)?,
+            [cake::Model {
+                id: 1,
+                name: "Cheese Cake".to_owned(),
+            }]
+        );
+
+        let txn = db.begin()?;
+        let res = cake::Entity::update_many()
+            .col_expr(cake::Column::Name, Expr::value("Cake"))
+            .exec(&txn)?;
+        assert_eq!(res.rows_affected, 1);
+        txn.commit()?;
+
+        let txn = db.begin()?;
+        drop(txn);
+        db.ping()?;
+
+        let response = server.handle(ProxyRequest::new(Some(1), ProxyCommand::Commit));
+        assert!(matches!(
+            response.reply,
+            ProxyReply::Error {
+                error: ProxyErrorKind::Conn,
+                ..
+            }
+        ));
+
+        assert_eq!(
+            mock.into_transaction_log(),
+            [
+                Transaction::from_sql_and_values(
+                    DbBackend::Postgres,
+                    r#"SELECT "cake"."id", "cake"."name" FROM "cake""#,
+                    []
+                ),
+                Transaction::many([
+                    Statement::from_string(DbBackend::Postgres, "BEGIN"),
+                    Statement::from_sql_and_values(
+                        DbBackend::Postgres,
+                        r#"UPDATE "cake" SET "name" = $1"#,
+                        ["Cake".into()]
+                    ),
+                    Statement::from_string(DbBackend::Postgres, "COMMIT"),
+                ]),
+                Transaction::many([
+                    Statement::from_string(DbBackend::Postgres, "BEGIN"),
+                    Statement::from_string(DbBackend::Postgres, "ROLLBACK"),
+                ]),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_proxy_server_abandoned_transactions() {
+        let mock = MockDatabase::new(DbBackend::Postgres).into_connection();
+        let begin = ProxyRequest::new(None, ProxyCommand::Begin);
+
+        // left open by a session that closes
+        let server = ProxyServer::new(mock.clone());
+        let mut session = server.session();
+        let response = session.handle(begin.clone());
+        assert!(matches!(response.reply, ProxyReply::Transaction { .. }));
+        session.close();
+
+        // left unused for longer than the idle timeout
+        let server = server.idle_timeout(Duration::ZERO);
+        let ProxyReply::Transaction { id } = server.handle(begin).reply else {
+            panic!("no transaction begun");
+        };
+        server.handle(ProxyRequest::new(None, ProxyCommand::Ping));
+        let response = server.handle(ProxyRequest::new(Some(id), ProxyCommand::Commit));
+        assert!(matches!(
+            response.reply,
+            ProxyReply::Error {
+                error: ProxyErrorKind::Conn,
+                ..
+            }
+        ));
+
+        let rolled_back = Transaction::many([
+            Statement::from_string(DbBackend::Postgres, "BEGIN"),
+            Statement::from_string(DbBackend::Postgres, "ROLLBACK"),
+        ]);
+        assert_eq!(
+            mock.into_transaction_log(),
+            [rolled_back.clone(), rolled_back]
+        );
+    }
+}
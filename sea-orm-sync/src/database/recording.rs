@@ -0,0 +1,626 @@
+use super::transaction::run_async_transaction_callback;
+use crate::{
+    AccessMode, ConnectionTrait, DatabaseConnection, DatabaseTransaction, DbBackend, DbErr,
+    ExecResult, IsolationLevel, MockDatabase, MockExecResult, ProxyExecResult, ProxyRow,
+    QueryResult, RuntimeErr, Statement, StatementMatcher, TransactionError, TransactionOptions,
+    TransactionSession, TransactionTrait, Value, from_query_result_to_proxy_row,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+use tracing::instrument;
+
+/// Wrapper of [`DatabaseConnection`] that records every statement run through it, along
+/// with the rows or the [`ExecResult`] it got back, into a [`Cassette`].
+///
+/// Record a test once against a real database and [`save`](Self::save) the cassette;
+/// later runs can [`Cassette::load`] it and replay it on a [`MockDatabase`] with
+/// [`Cassette::into_connection`], which answers each recorded statement with the
+/// recorded result as long as the SQL and the values match exactly.
+///
+/// Statements run in a [`RecordingTransaction`] are recorded too, but `BEGIN` and
+/// `COMMIT` are not, as the mock keeps track of transactions on its own. Errors are
+/// recorded by message only, and replayed as [`DbErr::Query`] or [`DbErr::Exec`].
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(feature = "mock", feature = "proxy", feature = "with-json")))
+)]
+pub struct RecordingConnection {
+    conn: DatabaseConnection,
+    cassette: Arc<Mutex<Cassette>>,
+}
+
+/// Wrapper of [`DatabaseTransaction`] started from a [`RecordingConnection`], recording
+/// into the same [`Cassette`].
+#[derive(Debug)]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(feature = "mock", feature = "proxy", feature = "with-json")))
+)]
+pub struct RecordingTransaction {
+    conn: DatabaseTransaction,
+    cassette: Arc<Mutex<Cassette>>,
+}
+
+/// The statements recorded by a [`RecordingConnection`], in the order they ran.
+/// Rows are kept in the serde representation of [`ProxyRow`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(feature = "mock", feature = "proxy", feature = "with-json")))
+)]
+pub struct Cassette {
+    /// The backend the statements were recorded against
+    #[serde(with = "super::value_serde::backend_name")]
+    pub db_backend: DbBackend,
+    /// The recorded statements
+    pub entries: Vec<CassetteEntry>,
+}
+
+/// A statement recorded in a [`Cassette`], with its outcome
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CassetteEntry {
+    /// A query, answered with rows
+    Query {
+        /// The SQL of the statement
+        sql: String,
+        /// The values bound to the statement
+        #[serde(default, with = "super::value_serde::vec")]
+        values: Vec<Value>,
+        /// The rows returned
+        #[serde(default)]
+        rows: Vec<ProxyRow>,
+        /// The error message, if the query failed
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    /// Any other statement, answered with an [`ExecResult`]
+    Exec {
+        /// The SQL of the statement
+        sql: String,
+        /// The values bound to the statement
+        #[serde(default, with = "super::value_serde::vec")]
+        values: Vec<Value>,
+        /// The result of the statement
+        #[serde(default)]
+        result: ProxyExecResult,
+        /// The error message, if the statement failed
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+}
+
+impl RecordingConnection {
+    /// Wrap `conn`, recording into an empty cassette
+    pub fn new(conn: DatabaseConnection) -> Self {
+        let cassette = Cassette::new(conn.get_database_backend());
+        Self {
+            conn,
+            cassette: Arc::new(Mutex::new(cassette)),
+        }
+    }
+
+    /// Get the wrapped connection. Statements run on it directly are not recorded.
+    pub fn inner(&self) -> &DatabaseConnection {
+        &self.conn
+    }
+
+    /// Get a copy of what has been recorded so far
+    pub fn cassette(&self) -> Cassette {
+        lock(&self.cassette).clone()
+    }
+
+    /// Save what has been recorded so far to the JSON file at `path`
+    pub fn save<P>(&self, path: P) -> Result<(), DbErr>
+    where
+        P: AsRef<Path>,
+    {
+        lock(&self.cassette).save(path)
+    }
+
+    fn wrap(&self, conn: DatabaseTransaction) -> RecordingTransaction {
+        RecordingTransaction {
+            conn,
+            cassette: self.cassette.clone(),
+        }
+    }
+
+    /// Execute the function inside a transaction.
+    /// If the function returns an error, the transaction will be rolled back.
+    /// Otherwise, the transaction will be committed.
+    #[instrument(level = "trace", skip(callback))]
+    pub fn transaction<F, T, E>(&self, callback: F) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnOnce(&'c RecordingTransaction) -> Result<T, E>,
+        E: std::fmt::Display + std::fmt::Debug,
+    {
+        let transaction = self.begin().map_err(TransactionError::Connection)?;
+        run_async_transaction_callback(transaction, callback)
+    }
+}
+
+impl RecordingTransaction {
+    fn wrap(&self, conn: DatabaseTransaction) -> RecordingTransaction {
+        RecordingTransaction {
+            conn,
+            cassette: self.cassette.clone(),
+        }
+    }
+
+    /// Execute the function inside a transaction.
+    /// If the function returns an error, the transaction will be rolled back.
+    /// Otherwise, the transaction will be committed.
+    #[instrument(level = "trace", skip(callback))]
+    pub fn transaction<F, T, E>(&self, callback: F) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnOnce(&'c RecordingTransaction) -> Result<T, E>,
+        E: std::fmt::Display + std::fmt::Debug,
+    {
+        let transaction = self.begin().map_err(TransactionError::Connection)?;
+        run_async_transaction_callback(transaction, callback)
+    }
+
+    /// Runs a transaction to completion passing through the result.
+    /// Rolling back the transaction on encountering an error.
+    #[instrument(level = "trace", skip(callback))]
+    fn run<F, T, E>(self, callback: F) -> Result<T, TransactionError<E>>
+    where
+        F: for<'b> FnOnce(&'b RecordingTransaction) -> Result<T, E>,
+        E: std::fmt::Display + std::fmt::Debug,
+    {
+        let res = callback(&self).map_err(TransactionError::Transaction);
+        if res.is_ok() {
+            self.commit().map_err(TransactionError::Connection)?;
+        } else {
+            self.rollback().map_err(TransactionError::Connection)?;
+        }
+        res
+    }
+
+    /// Commit a transaction atomically
+    #[instrument(level = "trace")]
+    pub fn commit(self) -> Result<(), DbErr> {
+        self.conn.commit()
+    }
+
+    /// Rolls back a transaction explicitly
+    #[instrument(level = "trace")]
+    pub fn rollback(self) -> Result<(), DbErr> {
+        self.conn.rollback()
+    }
+}
+
+impl Cassette {
+    /// Create an empty cassette for `db_backend`
+    pub fn new(db_backend: DbBackend) -> Self {
+        Self {
+            db_backend,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Load a cassette from the JSON file at `path`
+    pub fn load<P>(path: P) -> Result<Self, DbErr>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).map_err(|e| {
+            DbErr::Custom(format!("Failed to read cassette {}: {e}", path.display()))
+        })?;
+        serde_json::from_str(&json).map_err(|e| DbErr::Json(e.to_string()))
+    }
+
+    /// Save the cassette to the JSON file at `path`
+    pub fn save<P>(&self, path: P) -> Result<(), DbErr>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let json = serde_json::to_string_pretty(self).map_err(|e| DbErr::Json(e.to_string()))?;
+        std::fs::write(path, json)
+            .map_err(|e| DbErr::Custom(format!("Failed to write cassette {}: {e}", path.display())))
+    }
+
+    /// Build a [`MockDatabase`] expecting each recorded statement, with the same SQL and
+    /// values, and answering it with the recorded result. A statement recorded several
+    /// times is answered with its results in the order they were recorded.
+    pub fn into_mock_database(self) -> MockDatabase {
+        let mut db = MockDatabase::new(self.db_backend);
+        for entry in self.entries {
+            match entry {
+                CassetteEntry::Query {
+                    sql,
+                    values,
+                    rows,
+                    error,
+                } => {
+                    let expectation =
+                        db.expect_query(StatementMatcher::sql(sql).with_values(values));
+                    match error {
+                        Some(error) => {
+                            expectation.returning_error(DbErr::Query(RuntimeErr::Internal(error)))
+                        }
+                        None => expectation.returning(rows.into_iter().map(BTreeMap::from)),
+                    };
+                }
+                CassetteEntry::Exec {
+                    sql,
+                    values,
+                    result,
+                    error,
+                } => {
+                    let expectation =
+                        db.expect_exec(StatementMatcher::sql(sql).with_values(values));
+                    match error {
+                        Some(error) => {
+                            expectation.returning_error(DbErr::Exec(RuntimeErr::Internal(error)))
+                        }
+                        None => expectation.returning(MockExecResult {
+                            last_insert_id: result.last_insert_id,
+                            rows_affected: result.rows_affected,
+                        }),
+                    };
+                }
+            }
+        }
+        db
+    }
+
+    /// Replay the cassette on a mock connection; see
+    /// [`into_mock_database`](Self::into_mock_database). Call
+    /// [`MockDatabaseConnection::verify`](crate::MockDatabaseConnection::verify) at the end
+    /// of the test to check every recorded statement ran again.
+    pub fn into_connection(self) -> DatabaseConnection {
+        self.into_mock_database().into_connection()
+    }
+
+    fn record_query(&mut self, stmt: &Statement, res: Result<&[QueryResult], &DbErr>) {
+        let (rows, error) = match res {
+            Ok(rows) => (
+                rows.iter().map(from_query_result_to_proxy_row).collect(),
+                None,
+            ),
+            Err(err) => (Vec::new(), Some(error_message(err))),
+        };
+        self.entries.push(CassetteEntry::Query {
+            sql: stmt.sql.clone(),
+            values: statement_values(stmt),
+            rows,
+            error,
+        });
+    }
+
+    fn record_exec(&mut self, stmt: &Statement, res: &Result<ExecResult, DbErr>) {
+        let (result, error) = match res {
+            Ok(result) => (result.into(), None),
+            Err(err) => (ProxyExecResult::default(), Some(error_message(err))),
+        };
+        self.entries.push(CassetteEntry::Exec {
+            sql: stmt.sql.clone(),
+            values: statement_values(stmt),
+            result,
+            error,
+        });
+    }
+}
+
+fn lock(cassette: &Mutex<Cassette>) -> std::sync::MutexGuard<'_, Cassette> {
+    cassette.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+fn statement_values(stmt: &Statement) -> Vec<Value> {
+    stmt.values
+        .as_ref()
+        .map(|values| values.0.clone())
+        .unwrap_or_default()
+}
+
+fn error_message(err: &DbErr) -> String {
+    match err {
+        DbErr::Query(err) | DbErr::Exec(err) => err.to_string(),
+        err => err.to_string(),
+    }
+}
+
+/// Records the statements run on a connection into a shared [`Cassette`]
+trait Recorder {
+    fn cassette(&self) -> &Mutex<Cassette>;
+
+    fn record_query(&self, stmt: &Statement, res: &Result<Vec<QueryResult>, DbErr>) {
+        lock(self.cassette()).record_query(stmt, res.as_deref());
+    }
+
+    fn record_query_one(&self, stmt: &Statement, res: &Result<Option<QueryResult>, DbErr>) {
+        lock(self.cassette()).record_query(stmt, res.as_ref().map(Option::as_slice));
+    }
+
+    fn record_exec(&self, stmt: &Statement, res: &Result<ExecResult, DbErr>) {
+        lock(self.cassette()).record_exec(stmt, res);
+    }
+}
+
+impl Recorder for RecordingConnection {
+    fn cassette(&self) -> &Mutex<Cassette> {
+        &self.cassette
+    }
+}
+
+impl Recorder for RecordingTransaction {
+    fn cassette(&self) -> &Mutex<Cassette> {
+        &self.cassette
+    }
+}
+
+impl ConnectionTrait for RecordingConnection {
+    fn get_database_backend(&self) -> DbBackend {
+        self.conn.get_database_backend()
+    }
+
+    fn execute_raw(&self, stmt: Statement) -> Result<ExecResult, DbErr> {
+        let res = self.conn.execute_raw(stmt.clone());
+        self.record_exec(&stmt, &res);
+        res
+    }
+
+    fn execute_unprepared(&self, sql: &str) -> Result<ExecResult, DbErr> {
+        let res = self.conn.execute_unprepared(sql);
+        self.record_exec(
+            &Statement::from_string(self.get_database_backend(), sql),
+            &res,
+        );
+        res
+    }
+
+    fn query_one_raw(&self, stmt: Statement) -> Result<Option<QueryResult>, DbErr> {
+        let res = self.conn.query_one_raw(stmt.clone());
+        self.record_query_one(&stmt, &res);
+        res
+    }
+
+    fn query_all_raw(&self, stmt: Statement) -> Result<Vec<QueryResult>, DbErr> {
+        let res = self.conn.query_all_raw(stmt.clone());
+        self.record_query(&stmt, &res);
+        res
+    }
+
+    fn support_returning(&self) -> bool {
+        self.conn.support_returning()
+    }
+
+    fn is_mock_connection(&self) -> bool {
+        self.conn.is_mock_connection()
+    }
+}
+
+impl ConnectionTrait for RecordingTransaction {
+    fn get_database_backend(&self) -> DbBackend {
+        self.conn.get_database_backend()
+    }
+
+    fn execute_raw(&self, stmt: Statement) -> Result<ExecResult, DbErr> {
+        let res = self.conn.execute_raw(stmt.clone());
+        self.record_exec(&stmt, &res);
+        res
+    }
+
+    fn execute_unprepared(&self, sql: &str) -> Result<ExecResult, DbErr> {
+        let res = self.conn.execute_unprepared(sql);
+        self.record_exec(
+            &Statement::from_string(self.get_database_backend(), sql),
+            &res,
+        );
+        res
+    }
+
+    fn query_one_raw(&self, stmt: Statement) -> Result<Option<QueryResult>, DbErr> {
+        let res = self.conn.query_one_raw(stmt.clone());
+        self.record_query_one(&stmt, &res);
+        res
+    }
+
+    fn query_all_raw(&self, stmt: Statement) -> Result<Vec<QueryResult>, DbErr> {
+        let res = self.conn.query_all_raw(stmt.clone());
+        self.record_query(&stmt, &res);
+        res
+    }
+
+    fn support_returning(&self) -> bool {
+        self.conn.support_returning()
+    }
+
+    fn is_mock_connection(&self) -> bool {
+        self.conn.is_mock_connection()
+    }
+}
+
+impl TransactionTrait for RecordingConnection {
+    type Transaction = RecordingTransaction;
+
+    #[instrument(level = "trace")]
+    fn begin(&self) -> Result<RecordingTransaction, DbErr> {
+        Ok(self.wrap(self.conn.begin()?))
+    }
+
+    #[instrument(level = "trace")]
+    fn begin_with_config(
+        &self,
+        isolation_level: Option<IsolationLevel>,
+        access_mode: Option<AccessMode>,
+    ) -> Result<RecordingTransaction, DbErr> {
+        Ok(self.wrap(self.conn.begin_with_config(isolation_level, access_mode)?))
+    }
+
+    #[instrument(level = "trace")]
+    fn begin_with_options(
+        &self,
+        options: TransactionOptions,
+    ) -> Result<RecordingTransaction, DbErr> {
+        Ok(self.wrap(self.conn.begin_with_options(options)?))
+    }
+
+    /// Execute the function inside a transaction.
+    /// If the function returns an error, the transaction will be rolled back. If it does not return an error, the transaction will be committed.
+    #[instrument(level = "trace", skip(callback))]
+    fn transaction<F, T, E>(&self, callback: F) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnOnce(&'c RecordingTransaction) -> Result<T, E>,
+        E: std::fmt::Display + std::fmt::Debug,
+    {
+        let transaction = self.begin().map_err(TransactionError::Connection)?;
+        transaction.run(callback)
+    }
+
+    /// Execute the function inside a transaction.
+    /// If the function returns an error, the transaction will be rolled back. If it does not return an error, the transaction will be committed.
+    #[instrument(level = "trace", skip(callback))]
+    fn transaction_with_config<F, T, E>(
+        &self,
+        callback: F,
+        isolation_level: Option<IsolationLevel>,
+        access_mode: Option<AccessMode>,
+    ) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnOnce(&'c RecordingTransaction) -> Result<T, E>,
+        E: std::fmt::Display + std::fmt::Debug,
+    {
+        let transaction = self
+            .begin_with_config(isolation_level, access_mode)
+            .map_err(TransactionError::Connection)?;
+        transaction.run(callback)
+    }
+}
+
+impl TransactionTrait for RecordingTransaction {
+    type Transaction = RecordingTransaction;
+
+    #[instrument(level = "trace")]
+    fn begin(&self) -> Result<RecordingTransaction, DbErr> {
+        Ok(self.wrap(self.conn.begin()?))
+    }
+
+    #[instrument(level = "trace")]
+    fn begin_with_config(
+        &self,
+        isolation_level: Option<IsolationLevel>,
+        access_mode: Option<AccessMode>,
+    ) -> Result<RecordingTransaction, DbErr> {
+        Ok(self.wrap(self.conn.begin_with_config(isolation_level, access_mode)?))
+    }
+
+    #[instrument(level = "trace")]
+    fn begin_with_options(
+        &self,
+        options: TransactionOptions,
+    ) -> Result<RecordingTransaction, DbErr> {
+        Ok(self.wrap(self.conn.begin_with_options(options)?))
+    }
+
+    /// Execute the function inside a transaction.
+    /// If the function returns an error, the transaction will be rolled back. If it does not return an error, the transaction will be committed.
+    #[instrument(level = "trace", skip(callback))]
+    fn transaction<F, T, E>(&self, callback: F) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnOnce(&'c RecordingTransaction) -> Result<T, E>,
+        E: std::fmt::Display + std::fmt::Debug,
+    {
+        let transaction = self.begin().map_err(TransactionError::Connection)?;
+        transaction.run(callback)
+    }
+
+    /// Execute the function inside a transaction.
+    /// If the function returns an error, the transaction will be rolled back. If it does not return an error, the transaction will be committed.
+    #[instrument(level = "trace", skip(callback))]
+    fn transaction_with_config<F, T, E>(
+        &self,
+        callback: F,
+        isolation_level: Option<IsolationLevel>,
+        access_mode: Option<AccessMode>,
+    ) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnOnce(&'c RecordingTransaction) -> Result<T, E>,
+        E: std::fmt::Display + std::fmt::Debug,
+    {
+        let transaction = self
+            .begin_with_config(isolation_level, access_mode)
+            .map_err(TransactionError::Connection)?;
+        transaction.run(callback)
+    }
+}
+
+impl TransactionSession for RecordingTransaction {
+    fn commit(self) -> Result<(), DbErr> {
+        self.commit()
+    }
+
+    fn rollback(self) -> Result<(), DbErr> {
+        self.rollback()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{entity::*, tests_cfg::cake};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_record_and_replay() -> Result<(), DbErr> {
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([[cake::Model {
+                id: 1,
+                name: "Cheese Cake".to_owned(),
+            }]])
+            .append_exec_results([MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .into_connection();
+        let recording = RecordingConnection::new(db);
+
+        let cakes = cake::Entity::find().all(&recording)?;
+        recording
+            .transaction::<_, _, DbErr>(|txn| {
+                ({
+                    cake::Entity::delete_by_id(1).exec(txn)?;
+                    Ok(())
+                })
+            })
+            .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+        let json =
+            serde_json::to_string(&recording.cassette()).map_err(|e| DbErr::Json(e.to_string()))?;
+        let cassette: Cassette =
+            serde_json::from_str(&json).map_err(|e| DbErr::Json(e.to_string()))?;
+        assert_eq!(cassette.entries.len(), 2);
+
+        let replay = cassette.into_connection();
+        assert_eq!(cake::Entity::find().all(&replay)?, cakes);
+        let res = cake::Entity::delete_by_id(1).exec(&replay)?;
+        assert_eq!(res.rows_affected, 1);
+        replay.as_mock_connection().verify();
+
+        Ok(())
+    }
+    #[test]
+    fn test_insert_past_bind_limit() {
+        let recording =
+            RecordingConnection::new(MockDatabase::new(DbBackend::Postgres).into_connection());
+
+        // the statements it would be split into cannot run atomically without bypassing the recording
+        let models = (1..=40000).map(|id| cake::ActiveModel {
+            id: Set(id),
+            name: Set(format!("Cake {id}")),
+        });
+        assert!(matches!(
+            cake::Entity::insert_many(models).exec(&recording),
+            Err(DbErr::BackendNotSupported { .. })
+        ));
+        assert!(recording.cassette().entries.is_empty());
+    }
+}
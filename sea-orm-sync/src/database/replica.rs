@@ -0,0 +1,517 @@
+use crate::{
+    ConnectionTrait, DatabaseConnection, DbErr, QueryResult, Statement,
+    sql_tokens::{Token, Word, is_keyword, tokenize},
+};
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+/// How a [`DatabaseConnection`] with read replicas picks the replica that
+/// serves the next read. Set with [`ConnectOptions::replica_selection`](crate::ConnectOptions::replica_selection).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ReplicaSelection {
+    /// Cycle through the replicas in order (default).
+    #[default]
+    RoundRobin,
+    /// Pick the replica with the fewest reads currently in flight through
+    /// this connection; ties are broken round-robin.
+    LeastBusy,
+}
+
+/// The set of read replicas attached to a primary [`DatabaseConnection`].
+#[derive(Debug, Clone)]
+pub(crate) struct ReadReplicas {
+    pub(crate) conns: Vec<DatabaseConnection>,
+    selection: ReplicaSelection,
+    state: Arc<ReplicaState>,
+}
+
+#[derive(Debug)]
+struct ReplicaState {
+    next: AtomicUsize,
+    in_flight: Vec<AtomicUsize>,
+}
+
+/// Marks a replica as busy until dropped.
+struct InFlight<'a>(&'a AtomicUsize);
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+impl ReadReplicas {
+    pub(crate) fn new(conns: Vec<DatabaseConnection>, selection: ReplicaSelection) -> Self {
+        let in_flight = conns.iter().map(|_| AtomicUsize::new(0)).collect();
+        Self {
+            conns,
+            selection,
+            state: Arc::new(ReplicaState {
+                next: AtomicUsize::new(0),
+                in_flight,
+            }),
+        }
+    }
+
+    /// The same replicas, each replaced by `f(replica)`, sharing the selection state.
+    pub(crate) fn map<F>(&self, f: F) -> Self
+    where
+        F: FnMut(&DatabaseConnection) -> DatabaseConnection,
+    {
+        Self {
+            conns: self.conns.iter().map(f).collect(),
+            selection: self.selection,
+            state: Arc::clone(&self.state),
+        }
+    }
+
+    fn pick(&self) -> (&DatabaseConnection, InFlight<'_>) {
+        let len = self.conns.len();
+        let start = self.state.next.fetch_add(1, Ordering::Relaxed) % len;
+        let index = match self.selection {
+            ReplicaSelection::RoundRobin => start,
+            ReplicaSelection::LeastBusy => (0..len)
+                .map(|offset| (start + offset) % len)
+                .min_by_key(|i| self.state.in_flight[*i].load(Ordering::Acquire))
+                .unwrap_or(start),
+        };
+        let counter = &self.state.in_flight[index];
+        counter.fetch_add(1, Ordering::AcqRel);
+        (&self.conns[index], InFlight(counter))
+    }
+
+    pub(crate) fn query_one_raw(&self, stmt: Statement) -> Result<Option<QueryResult>, DbErr> {
+        let (conn, _guard) = self.pick();
+        conn.query_one_raw(stmt)
+    }
+
+    pub(crate) fn query_all_raw(&self, stmt: Statement) -> Result<Vec<QueryResult>, DbErr> {
+        let (conn, _guard) = self.pick();
+        conn.query_all_raw(stmt)
+    }
+
+    pub(crate) fn query_one_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<Option<QueryResult>, DbErr> {
+        let (conn, _guard) = self.pick();
+        conn.query_one_raw_with_timeout(stmt, timeout)
+    }
+
+    pub(crate) fn query_all_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<Vec<QueryResult>, DbErr> {
+        let (conn, _guard) = self.pick();
+        conn.query_all_raw_with_timeout(stmt, timeout)
+    }
+
+    #[cfg(feature = "stream")]
+    pub(crate) fn stream_raw(&self, stmt: Statement) -> Result<crate::QueryStream, DbErr> {
+        use crate::StreamTrait;
+
+        let (conn, _guard) = self.pick();
+        conn.stream_raw(stmt)
+    }
+
+    #[cfg(feature = "stream")]
+    pub(crate) fn stream_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<crate::QueryStream, DbErr> {
+        use crate::StreamTrait;
+
+        let (conn, _guard) = self.pick();
+        conn.stream_raw_with_timeout(stmt, timeout)
+    }
+}
+
+/// Whether a statement only reads data and can therefore be served by a replica.
+///
+/// Only plain `SELECT`s qualify, and only if every function they call is a built-in one
+/// known not to write or lock, since e.g. `nextval` or `pg_advisory_lock` cannot run on
+/// a replica. `SELECT ... INTO`, locking reads (`FOR UPDATE`, `FOR SHARE`, ...) and
+/// anything else, including `INSERT ... RETURNING` issued through `query_one`, stay on
+/// the primary.
+pub(crate) fn is_replica_safe(sql: &str) -> bool {
+    let tokens = tokenize(sql);
+    if !is_keyword(&tokens, 0, "SELECT") {
+        return false;
+    }
+    tokens.iter().enumerate().all(|(i, token)| {
+        let Token::Word(word) = token else {
+            return true;
+        };
+        let locks = (word.is_keyword("FOR")
+            && ["UPDATE", "NO", "KEY", "SHARE"]
+                .iter()
+                .any(|keyword| is_keyword(&tokens, i + 1, keyword)))
+            || (word.is_keyword("LOCK") && is_keyword(&tokens, i + 1, "IN"));
+        let calls = matches!(tokens.get(i + 1), Some(Token::Open));
+        !locks && !word.is_keyword("INTO") && (!calls || is_read_only_call(word))
+    })
+}
+
+/// Whether `word` followed by a parenthesis is a keyword, a type or a built-in function
+/// that reads only
+fn is_read_only_call(word: &Word<'_>) -> bool {
+    const READ_ONLY: &[&str] = &[
+        // keywords followed by a parenthesized expression, list or subquery
+        "AGAINST",
+        "ALL",
+        "AND",
+        "ANY",
+        "AS",
+        "BETWEEN",
+        "BY",
+        "CASE",
+        "DISTINCT",
+        "ELSE",
+        "ESCAPE",
+        "EXCEPT",
+        "EXISTS",
+        "FILTER",
+        "FROM",
+        "GROUP",
+        "HAVING",
+        "ILIKE",
+        "IN",
+        "INTERSECT",
+        "INTERVAL",
+        "IS",
+        "JOIN",
+        "LATERAL",
+        "LIKE",
+        "LIMIT",
+        "MATCH",
+        "NOT",
+        "OFFSET",
+        "ON",
+        "OR",
+        "OVER",
+        "ROW",
+        "SELECT",
+        "SOME",
+        "THEN",
+        "UNION",
+        "USING",
+        "VALUES",
+        "WHEN",
+        "WHERE",
+        // types with a length or precision
+        "BINARY",
+        "BIT",
+        "CHAR",
+        "CHARACTER",
+        "DATETIME",
+        "DECIMAL",
+        "FLOAT",
+        "NUMERIC",
+        "TIME",
+        "TIMESTAMP",
+        "TIMESTAMPTZ",
+        "VARBINARY",
+        "VARCHAR",
+        // aggregates and window functions
+        "ARRAY_AGG",
+        "AVG",
+        "BIT_AND",
+        "BIT_OR",
+        "BIT_XOR",
+        "BOOL_AND",
+        "BOOL_OR",
+        "COUNT",
+        "CUME_DIST",
+        "DENSE_RANK",
+        "EVERY",
+        "FIRST_VALUE",
+        "GROUP_CONCAT",
+        "JSON_AGG",
+        "JSON_ARRAYAGG",
+        "JSON_OBJECT_AGG",
+        "JSON_OBJECTAGG",
+        "JSONB_AGG",
+        "JSONB_OBJECT_AGG",
+        "LAG",
+        "LAST_VALUE",
+        "LEAD",
+        "MAX",
+        "MIN",
+        "NTH_VALUE",
+        "NTILE",
+        "PERCENT_RANK",
+        "RANK",
+        "ROW_NUMBER",
+        "STDDEV",
+        "STDDEV_POP",
+        "STDDEV_SAMP",
+        "STRING_AGG",
+        "SUM",
+        "TOTAL",
+        "VAR_POP",
+        "VAR_SAMP",
+        "VARIANCE",
+        // conditionals and conversions
+        "CAST",
+        "COALESCE",
+        "CONVERT",
+        "GREATEST",
+        "IF",
+        "IFNULL",
+        "IIF",
+        "ISNULL",
+        "LEAST",
+        "NULLIF",
+        // numbers
+        "ABS",
+        "CEIL",
+        "CEILING",
+        "EXP",
+        "FLOOR",
+        "LN",
+        "LOG",
+        "LOG10",
+        "MOD",
+        "POW",
+        "POWER",
+        "ROUND",
+        "SIGN",
+        "SQRT",
+        "TRUNC",
+        "TRUNCATE",
+        // text
+        "BTRIM",
+        "CHAR_LENGTH",
+        "CHARACTER_LENGTH",
+        "CONCAT",
+        "CONCAT_WS",
+        "HEX",
+        "INSTR",
+        "LEFT",
+        "LENGTH",
+        "LOCATE",
+        "LOWER",
+        "LPAD",
+        "LTRIM",
+        "MD5",
+        "OCTET_LENGTH",
+        "POSITION",
+        "REGEXP_LIKE",
+        "REGEXP_REPLACE",
+        "REPEAT",
+        "REPLACE",
+        "REVERSE",
+        "RIGHT",
+        "RPAD",
+        "RTRIM",
+        "SPLIT_PART",
+        "STARTS_WITH",
+        "STRPOS",
+        "SUBSTR",
+        "SUBSTRING",
+        "TRIM",
+        "UPPER",
+        // dates and times
+        "AGE",
+        "CURRENT_TIMESTAMP",
+        "DATE",
+        "DATE_FORMAT",
+        "DATE_PART",
+        "DATE_TRUNC",
+        "DATEDIFF",
+        "DAY",
+        "EXTRACT",
+        "HOUR",
+        "JULIANDAY",
+        "MINUTE",
+        "MONTH",
+        "NOW",
+        "SECOND",
+        "STRFTIME",
+        "TIMESTAMPDIFF",
+        "TO_CHAR",
+        "TO_DATE",
+        "TO_TIMESTAMP",
+        "UNIXEPOCH",
+        "YEAR",
+        // JSON and arrays
+        "ARRAY",
+        "ARRAY_LENGTH",
+        "ARRAY_POSITION",
+        "ARRAY_TO_STRING",
+        "CARDINALITY",
+        "JSON",
+        "JSON_ARRAY",
+        "JSON_ARRAY_LENGTH",
+        "JSON_BUILD_ARRAY",
+        "JSON_BUILD_OBJECT",
+        "JSON_CONTAINS",
+        "JSON_EXTRACT",
+        "JSON_EXTRACT_PATH",
+        "JSON_EXTRACT_PATH_TEXT",
+        "JSON_OBJECT",
+        "JSON_TYPEOF",
+        "JSON_UNQUOTE",
+        "JSON_VALUE",
+        "JSONB_ARRAY_LENGTH",
+        "JSONB_BUILD_ARRAY",
+        "JSONB_BUILD_OBJECT",
+        "JSONB_EXTRACT_PATH",
+        "JSONB_EXTRACT_PATH_TEXT",
+        "JSONB_TYPEOF",
+        "STRING_TO_ARRAY",
+        "TO_JSON",
+        "TO_JSONB",
+        "UNNEST",
+    ];
+    READ_ONLY
+        .iter()
+        .any(|name| word.text.eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+#[cfg(feature = "mock")]
+mod tests {
+    use super::is_replica_safe;
+    use crate::{
+        DbBackend, DbErr, EntityTrait, MockDatabase, MockExecResult, ReplicaSelection, Statement,
+        Transaction, TransactionTrait, tests_cfg::*,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn cake_model() -> cake::Model {
+        cake::Model {
+            id: 1,
+            name: "Chocolate".to_owned(),
+        }
+    }
+
+    fn select_cake() -> Statement {
+        Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"SELECT "cake"."id", "cake"."name" FROM "cake""#,
+            [],
+        )
+    }
+
+    #[test]
+    fn test_is_replica_safe() {
+        assert!(is_replica_safe("SELECT 1"));
+        assert!(is_replica_safe("  select * from cake"));
+        assert!(!is_replica_safe("SELECT * FROM cake FOR UPDATE"));
+        assert!(!is_replica_safe("SELECT * FROM cake LOCK IN SHARE MODE"));
+        assert!(!is_replica_safe(
+            "INSERT INTO cake (name) VALUES ('x') RETURNING id"
+        ));
+        assert!(!is_replica_safe("UPDATE cake SET name = 'x'"));
+        assert!(!is_replica_safe(""));
+
+        // keywords in literals and quoted identifiers do not count
+        assert!(is_replica_safe(
+            r#"SELECT "into", 'for update' FROM "cake" WHERE "name" = 'nextval(1)'"#
+        ));
+        assert!(!is_replica_safe("SELECT * INTO cake_copy FROM cake"));
+        assert!(!is_replica_safe("SELECT id INTO @id FROM cake LIMIT 1"));
+        assert!(!is_replica_safe("SELECT * FROM cake FOR NO KEY UPDATE"));
+        assert!(!is_replica_safe("SELECT nextval('cake_id_seq')"));
+        assert!(!is_replica_safe("SELECT setval('cake_id_seq', 10)"));
+        assert!(!is_replica_safe("SELECT pg_advisory_lock(1)"));
+        assert!(!is_replica_safe("SELECT GET_LOCK('leader', 0)"));
+        assert!(!is_replica_safe("SELECT public.my_function(id) FROM cake"));
+        assert!(is_replica_safe(
+            r#"SELECT COUNT(*), MAX("id"), LOWER("name") FROM "cake" WHERE "id" IN (1, 2) AND EXISTS (SELECT 1 FROM "fruit") GROUP BY LOWER("name")"#
+        ));
+        assert!(is_replica_safe(
+            "SELECT CAST(`id` AS CHAR(10)), COALESCE(`name`, '') FROM `cake`"
+        ));
+    }
+
+    #[test]
+    fn test_round_robin_reads() -> Result<(), DbErr> {
+        let primary = MockDatabase::new(DbBackend::Postgres)
+            .append_exec_results([MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .into_connection();
+        let replica_1 = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([[cake_model()], [cake_model()]])
+            .into_connection();
+        let replica_2 = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([[cake_model()]])
+            .into_connection();
+
+        let db = primary.clone().with_read_replicas(
+            [replica_1.clone(), replica_2.clone()],
+            ReplicaSelection::RoundRobin,
+        );
+
+        cake::Entity::find().all(&db)?;
+        cake::Entity::find().all(&db)?;
+        cake::Entity::find().all(&db)?;
+        cake::Entity::delete_many().exec(&db)?;
+
+        assert_eq!(
+            replica_1.into_transaction_log(),
+            [
+                Transaction::one(select_cake()),
+                Transaction::one(select_cake())
+            ]
+        );
+        assert_eq!(
+            replica_2.into_transaction_log(),
+            [Transaction::one(select_cake())]
+        );
+        assert_eq!(
+            primary.into_transaction_log(),
+            [Transaction::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"DELETE FROM "cake""#,
+                []
+            )]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_primary_for_transactions_and_use_primary() -> Result<(), DbErr> {
+        let primary = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([[cake_model()], [cake_model()]])
+            .into_connection();
+        let replica = MockDatabase::new(DbBackend::Postgres).into_connection();
+
+        let db = primary
+            .clone()
+            .with_read_replicas([replica.clone()], ReplicaSelection::LeastBusy);
+
+        let txn = db.begin()?;
+        cake::Entity::find().all(&txn)?;
+        txn.commit()?;
+
+        cake::Entity::find().all(&db.use_primary())?;
+
+        assert_eq!(replica.into_transaction_log(), []);
+        assert_eq!(
+            primary.into_transaction_log(),
+            [
+                Transaction::many([
+                    Statement::from_string(DbBackend::Postgres, "BEGIN"),
+                    select_cake(),
+                    Statement::from_string(DbBackend::Postgres, "COMMIT"),
+                ]),
+                Transaction::one(select_cake()),
+            ]
+        );
+
+        Ok(())
+    }
+}
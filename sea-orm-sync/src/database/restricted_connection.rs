@@ -14,9 +14,8 @@ use crate::{
     },
 };
 use std::{
-    future::Future,
-    pin::Pin,
     sync::{Arc, RwLock},
+    time::Duration,
 };
 use tracing::instrument;
 
@@ -88,6 +87,33 @@ impl ConnectionTrait for RestrictedConnection {
         self.user_can_run(stmt)?;
         self.conn.query_all(stmt)
     }
+
+    fn execute_with_timeout<S: StatementBuilder>(
+        &self,
+        stmt: &S,
+        timeout: Duration,
+    ) -> Result<ExecResult, DbErr> {
+        self.user_can_run(stmt)?;
+        self.conn.execute_with_timeout(stmt, timeout)
+    }
+
+    fn query_one_with_timeout<S: StatementBuilder>(
+        &self,
+        stmt: &S,
+        timeout: Duration,
+    ) -> Result<Option<QueryResult>, DbErr> {
+        self.user_can_run(stmt)?;
+        self.conn.query_one_with_timeout(stmt, timeout)
+    }
+
+    fn query_all_with_timeout<S: StatementBuilder>(
+        &self,
+        stmt: &S,
+        timeout: Duration,
+    ) -> Result<Vec<QueryResult>, DbErr> {
+        self.user_can_run(stmt)?;
+        self.conn.query_all_with_timeout(stmt, timeout)
+    }
 }
 
 impl ConnectionTrait for RestrictedTransaction {
@@ -133,6 +159,33 @@ impl ConnectionTrait for RestrictedTransaction {
         self.user_can_run(stmt)?;
         self.conn.query_all(stmt)
     }
+
+    fn execute_with_timeout<S: StatementBuilder>(
+        &self,
+        stmt: &S,
+        timeout: Duration,
+    ) -> Result<ExecResult, DbErr> {
+        self.user_can_run(stmt)?;
+        self.conn.execute_with_timeout(stmt, timeout)
+    }
+
+    fn query_one_with_timeout<S: StatementBuilder>(
+        &self,
+        stmt: &S,
+        timeout: Duration,
+    ) -> Result<Option<QueryResult>, DbErr> {
+        self.user_can_run(stmt)?;
+        self.conn.query_one_with_timeout(stmt, timeout)
+    }
+
+    fn query_all_with_timeout<S: StatementBuilder>(
+        &self,
+        stmt: &S,
+        timeout: Duration,
+    ) -> Result<Vec<QueryResult>, DbErr> {
+        self.user_can_run(stmt)?;
+        self.conn.query_all_with_timeout(stmt, timeout)
+    }
 }
 
 impl RestrictedConnection {
@@ -453,6 +506,25 @@ impl RestrictedTransaction {
     pub fn rollback(self) -> Result<(), DbErr> {
         self.conn.rollback()
     }
+
+    /// Set a savepoint named `name`, see [`DatabaseTransaction::savepoint`]
+    #[instrument(level = "trace")]
+    pub fn savepoint(&self, name: &str) -> Result<(), DbErr> {
+        self.conn.savepoint(name)
+    }
+
+    /// Undo everything done since the savepoint `name` was set, see
+    /// [`DatabaseTransaction::rollback_to`]
+    #[instrument(level = "trace")]
+    pub fn rollback_to(&self, name: &str) -> Result<(), DbErr> {
+        self.conn.rollback_to(name)
+    }
+
+    /// Remove the savepoint `name`, see [`DatabaseTransaction::release`]
+    #[instrument(level = "trace")]
+    pub fn release(&self, name: &str) -> Result<(), DbErr> {
+        self.conn.release(name)
+    }
 }
 
 impl RbacEngineMount {
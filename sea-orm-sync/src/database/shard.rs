@@ -0,0 +1,821 @@
+use crate::{
+    ConnectionTrait, DatabaseConnection, DbBackend, DbErr, ExecResult, ExecResultHolder,
+    QueryResult, Statement, StatementBuilder, Value, build_with_condition,
+    sql_tokens::{Token, Word, is_keyword, tokenize},
+};
+#[cfg(not(feature = "sync"))]
+use futures_util::future::try_join_all;
+use sea_query::{BinOper, Expr, IntoIden};
+use std::{collections::BTreeSet, fmt, ops::Range, sync::Arc, time::Duration};
+
+/// What a [`ShardedConnection`] does with a statement it cannot route to a single shard.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum UnroutedStatement {
+    /// Fail with [`DbErr::Unroutable`]
+    #[default]
+    Error,
+    /// Run it concurrently on every shard that may hold matching rows, and merge the
+    /// results: rows are concatenated in shard order, `query_one` returns the first row
+    /// found and the affected row counts are summed. Ordering and limits only hold
+    /// within a shard. `INSERT`s are never fanned out.
+    FanOut,
+}
+
+/// Routes every statement to one of several [`DatabaseConnection`]s, the shards, by the
+/// value of a shard key column such as `tenant_id`.
+///
+/// The key is read from the statement itself, so
+/// `Entity::find().filter(Column::TenantId.eq(x))` runs on the shard that the resolver
+/// picks for `x`, and an `INSERT` on the shard of the key in its values.
+///
+/// The `WHERE` clause of a `SELECT`, `UPDATE` or `DELETE` built with SeaQuery is
+/// followed through `AND`, `OR` and `IN`: the key compared for equality with a value,
+/// or listed in `IN`, narrows the rows down to the shards of those values. Conditions
+/// on other columns, `NOT` and subqueries do not narrow them down. Raw SQL is only
+/// routed when the key is compared for equality with bound values, in a condition
+/// without `OR` or `NOT`. A statement whose rows may be on several shards, including
+/// one with keys the resolver has no shard for, is handled according to
+/// [`UnroutedStatement`].
+///
+/// A transaction cannot span shards: begin one on [`shard_for`](Self::shard_for) the key.
+#[derive(Clone)]
+pub struct ShardedConnection {
+    shards: Vec<DatabaseConnection>,
+    shard_key: String,
+    resolver: ShardResolver,
+    unrouted: UnroutedStatement,
+}
+
+type ShardResolver = Arc<dyn Fn(&Value) -> Option<usize>>;
+
+/// Where a statement runs.
+#[derive(Debug, PartialEq, Eq)]
+enum Route {
+    Shard(usize),
+    /// Fanned out to these shards
+    Shards(Vec<usize>),
+}
+
+/// The shards the rows of a statement are on, or why they cannot be told.
+type Shards = Result<BTreeSet<usize>, String>;
+
+impl fmt::Debug for ShardedConnection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShardedConnection")
+            .field("shards", &self.shards)
+            .field("shard_key", &self.shard_key)
+            .field("unrouted", &self.unrouted)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ShardedConnection {
+    /// Shard by `shard_key`, a column name or an entity's `Column`. `resolver` maps a value
+    /// of the key to the index of its shard in `shards`, or `None` if it has none.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is empty.
+    pub fn new<I, K, F>(shards: I, shard_key: K, resolver: F) -> Self
+    where
+        I: IntoIterator<Item = DatabaseConnection>,
+        K: IntoIden,
+        F: Fn(&Value) -> Option<usize> + 'static,
+    {
+        let shards: Vec<_> = shards.into_iter().collect();
+        assert!(
+            !shards.is_empty(),
+            "ShardedConnection needs at least one shard"
+        );
+        Self {
+            shards,
+            shard_key: shard_key.into_iden().to_string(),
+            resolver: Arc::new(resolver),
+            unrouted: UnroutedStatement::default(),
+        }
+    }
+
+    /// Set what to do with statements that cannot be routed to a single shard
+    pub fn unrouted(mut self, unrouted: UnroutedStatement) -> Self {
+        self.unrouted = unrouted;
+        self
+    }
+
+    /// The shards, in the order the resolver indexes them
+    pub fn shards(&self) -> &[DatabaseConnection] {
+        &self.shards
+    }
+
+    /// The shard holding the rows whose shard key is `key`, e.g. to begin a transaction on
+    pub fn shard_for<V>(&self, key: V) -> Result<&DatabaseConnection, DbErr>
+    where
+        V: Into<Value>,
+    {
+        let key = key.into();
+        self.resolve(&key)
+            .map(|index| &self.shards[index])
+            .map_err(DbErr::Unroutable)
+    }
+
+    fn resolve(&self, key: &Value) -> Result<usize, String> {
+        (self.resolver)(key)
+            .filter(|index| *index < self.shards.len())
+            .ok_or_else(|| format!("no shard for {key:?}"))
+    }
+
+    /// The shards holding the rows matching `condition`
+    fn condition_shards(&self, condition: &Expr) -> Shards {
+        let unfiltered = || format!("the condition does not filter on `{}`", self.shard_key);
+        let Expr::Binary(left, oper, right) = condition else {
+            return Err(unfiltered());
+        };
+        match (left.as_ref(), oper, right.as_ref()) {
+            (left, BinOper::And, right) => {
+                match (self.condition_shards(left), self.condition_shards(right)) {
+                    (Ok(left), Ok(right)) => Ok(&left & &right),
+                    (Ok(shards), Err(_)) | (Err(_), Ok(shards)) => Ok(shards),
+                    (Err(reason), Err(_)) => Err(reason),
+                }
+            }
+            (left, BinOper::Or, right) => {
+                Ok(&self.condition_shards(left)? | &self.condition_shards(right)?)
+            }
+            (column, BinOper::Equal, value) | (value, BinOper::Equal, column)
+                if self.is_shard_key(column) =>
+            {
+                match bound_value(value) {
+                    Some(value) => Ok(BTreeSet::from([self.resolve(value)?])),
+                    None => Err(unfiltered()),
+                }
+            }
+            (column, BinOper::In, Expr::Tuple(values)) if self.is_shard_key(column) => values
+                .iter()
+                .map(|value| match bound_value(value) {
+                    Some(value) => self.resolve(value),
+                    None => Err(unfiltered()),
+                })
+                .collect(),
+            _ => Err(unfiltered()),
+        }
+    }
+
+    fn is_shard_key(&self, expr: &Expr) -> bool {
+        matches!(expr, Expr::Column(column)
+            if column.column().is_some_and(|name| name.to_string() == self.shard_key))
+    }
+
+    /// The shards of the values the shard key takes in raw SQL
+    fn sql_shards(&self, tokens: &[Token<'_>], values: &[Value]) -> Shards {
+        shard_keys(tokens, &self.shard_key)?
+            .into_iter()
+            .map(|param| match values.get(param) {
+                Some(key) => self.resolve(key),
+                None => Err(format!("no value bound for `{}`", self.shard_key)),
+            })
+            .collect()
+    }
+
+    fn route(&self, shards: Shards, insert: bool) -> Result<Route, DbErr> {
+        let (reason, shards) = match shards {
+            Ok(shards) if shards.len() == 1 => {
+                return Ok(Route::Shard(shards.into_iter().next().unwrap_or_default()));
+            }
+            Ok(shards) => (
+                format!("the values of `{}` are not on one shard", self.shard_key),
+                shards.into_iter().collect(),
+            ),
+            Err(reason) => (reason, (0..self.shards.len()).collect()),
+        };
+        match self.unrouted {
+            UnroutedStatement::FanOut if !insert => Ok(Route::Shards(shards)),
+            _ => Err(DbErr::Unroutable(reason)),
+        }
+    }
+
+    fn route_sql(&self, sql: &str, values: &[Value]) -> Result<Route, DbErr> {
+        let tokens = tokenize(sql);
+        let insert = is_keyword(&tokens, 0, "INSERT");
+        self.route(self.sql_shards(&tokens, values), insert)
+    }
+
+    fn route_stmt(&self, stmt: &Statement) -> Result<Route, DbErr> {
+        let values = stmt.values.as_ref().map(|v| v.0.as_slice()).unwrap_or(&[]);
+        self.route_sql(&stmt.sql, values)
+    }
+
+    /// Build `stmt` and route it by its `WHERE` condition where it has one, by its SQL
+    /// otherwise.
+    fn route_builder<S: StatementBuilder>(&self, stmt: &S) -> Result<(Route, Statement), DbErr> {
+        let (statement, condition) = build_with_condition(stmt, &self.get_database_backend());
+        let route = match condition {
+            Some(condition) => self.route(self.condition_shards(&condition.into()), false)?,
+            None => self.route_stmt(&statement)?,
+        };
+        Ok((route, statement))
+    }
+
+    fn execute_routed(
+        &self,
+        route: Route,
+        stmt: Statement,
+        timeout: Option<Duration>,
+    ) -> Result<ExecResult, DbErr> {
+        match route {
+            Route::Shard(index) => execute_on(&self.shards[index], stmt, timeout),
+            Route::Shards(indices) => {
+                let results = try_join_all(
+                    indices
+                        .into_iter()
+                        .map(|index| execute_on(&self.shards[index], stmt.clone(), timeout)),
+                )?;
+                Ok(fanned_out(
+                    results.iter().map(ExecResult::rows_affected).sum(),
+                ))
+            }
+        }
+    }
+
+    fn query_one_routed(
+        &self,
+        route: Route,
+        stmt: Statement,
+        timeout: Option<Duration>,
+    ) -> Result<Option<QueryResult>, DbErr> {
+        match route {
+            Route::Shard(index) => query_one_on(&self.shards[index], stmt, timeout),
+            Route::Shards(indices) => {
+                let rows = try_join_all(
+                    indices
+                        .into_iter()
+                        .map(|index| query_one_on(&self.shards[index], stmt.clone(), timeout)),
+                )?;
+                Ok(rows.into_iter().flatten().next())
+            }
+        }
+    }
+
+    fn query_all_routed(
+        &self,
+        route: Route,
+        stmt: Statement,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<QueryResult>, DbErr> {
+        match route {
+            Route::Shard(index) => query_all_on(&self.shards[index], stmt, timeout),
+            Route::Shards(indices) => {
+                let rows = try_join_all(
+                    indices
+                        .into_iter()
+                        .map(|index| query_all_on(&self.shards[index], stmt.clone(), timeout)),
+                )?;
+                Ok(rows.into_iter().flatten().collect())
+            }
+        }
+    }
+}
+
+impl ConnectionTrait for ShardedConnection {
+    fn get_database_backend(&self) -> DbBackend {
+        self.shards[0].get_database_backend()
+    }
+
+    fn execute_raw(&self, stmt: Statement) -> Result<ExecResult, DbErr> {
+        let route = self.route_stmt(&stmt)?;
+        self.execute_routed(route, stmt, None)
+    }
+
+    fn execute<S: StatementBuilder>(&self, stmt: &S) -> Result<ExecResult, DbErr> {
+        let (route, stmt) = self.route_builder(stmt)?;
+        self.execute_routed(route, stmt, None)
+    }
+
+    fn execute_unprepared(&self, sql: &str) -> Result<ExecResult, DbErr> {
+        match self.route_sql(sql, &[])? {
+            Route::Shard(index) => self.shards[index].execute_unprepared(sql),
+            Route::Shards(indices) => {
+                let results = try_join_all(
+                    indices
+                        .into_iter()
+                        .map(|index| self.shards[index].execute_unprepared(sql)),
+                )?;
+                Ok(fanned_out(
+                    results.iter().map(ExecResult::rows_affected).sum(),
+                ))
+            }
+        }
+    }
+
+    fn query_one_raw(&self, stmt: Statement) -> Result<Option<QueryResult>, DbErr> {
+        let route = self.route_stmt(&stmt)?;
+        self.query_one_routed(route, stmt, None)
+    }
+
+    fn query_one<S: StatementBuilder>(&self, stmt: &S) -> Result<Option<QueryResult>, DbErr> {
+        let (route, stmt) = self.route_builder(stmt)?;
+        self.query_one_routed(route, stmt, None)
+    }
+
+    fn query_all_raw(&self, stmt: Statement) -> Result<Vec<QueryResult>, DbErr> {
+        let route = self.route_stmt(&stmt)?;
+        self.query_all_routed(route, stmt, None)
+    }
+
+    fn query_all<S: StatementBuilder>(&self, stmt: &S) -> Result<Vec<QueryResult>, DbErr> {
+        let (route, stmt) = self.route_builder(stmt)?;
+        self.query_all_routed(route, stmt, None)
+    }
+
+    fn execute_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<ExecResult, DbErr> {
+        let route = self.route_stmt(&stmt)?;
+        self.execute_routed(route, stmt, Some(timeout))
+    }
+
+    fn execute_with_timeout<S: StatementBuilder>(
+        &self,
+        stmt: &S,
+        timeout: Duration,
+    ) -> Result<ExecResult, DbErr> {
+        let (route, stmt) = self.route_builder(stmt)?;
+        self.execute_routed(route, stmt, Some(timeout))
+    }
+
+    fn query_one_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<Option<QueryResult>, DbErr> {
+        let route = self.route_stmt(&stmt)?;
+        self.query_one_routed(route, stmt, Some(timeout))
+    }
+
+    fn query_one_with_timeout<S: StatementBuilder>(
+        &self,
+        stmt: &S,
+        timeout: Duration,
+    ) -> Result<Option<QueryResult>, DbErr> {
+        let (route, stmt) = self.route_builder(stmt)?;
+        self.query_one_routed(route, stmt, Some(timeout))
+    }
+
+    fn query_all_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<Vec<QueryResult>, DbErr> {
+        let route = self.route_stmt(&stmt)?;
+        self.query_all_routed(route, stmt, Some(timeout))
+    }
+
+    fn query_all_with_timeout<S: StatementBuilder>(
+        &self,
+        stmt: &S,
+        timeout: Duration,
+    ) -> Result<Vec<QueryResult>, DbErr> {
+        let (route, stmt) = self.route_builder(stmt)?;
+        self.query_all_routed(route, stmt, Some(timeout))
+    }
+
+    fn support_returning(&self) -> bool {
+        self.shards[0].support_returning()
+    }
+
+    fn is_mock_connection(&self) -> bool {
+        self.shards[0].is_mock_connection()
+    }
+}
+
+fn execute_on(
+    shard: &DatabaseConnection,
+    stmt: Statement,
+    timeout: Option<Duration>,
+) -> Result<ExecResult, DbErr> {
+    match timeout {
+        Some(timeout) => shard.execute_raw_with_timeout(stmt, timeout),
+        None => shard.execute_raw(stmt),
+    }
+}
+
+fn query_one_on(
+    shard: &DatabaseConnection,
+    stmt: Statement,
+    timeout: Option<Duration>,
+) -> Result<Option<QueryResult>, DbErr> {
+    match timeout {
+        Some(timeout) => shard.query_one_raw_with_timeout(stmt, timeout),
+        None => shard.query_one_raw(stmt),
+    }
+}
+
+fn query_all_on(
+    shard: &DatabaseConnection,
+    stmt: Statement,
+    timeout: Option<Duration>,
+) -> Result<Vec<QueryResult>, DbErr> {
+    match timeout {
+        Some(timeout) => shard.query_all_raw_with_timeout(stmt, timeout),
+        None => shard.query_all_raw(stmt),
+    }
+}
+
+/// Without a runtime, the shards are queried one after another
+#[cfg(feature = "sync")]
+fn try_join_all<T>(results: impl IntoIterator<Item = Result<T, DbErr>>) -> Result<Vec<T>, DbErr> {
+    results.into_iter().collect()
+}
+
+fn fanned_out(rows_affected: u64) -> ExecResult {
+    ExecResult {
+        result: ExecResultHolder::FannedOut { rows_affected },
+    }
+}
+
+/// The value bound in `expr`, if it is one
+fn bound_value(expr: &Expr) -> Option<&Value> {
+    match expr {
+        Expr::Value(value) => Some(value),
+        Expr::AsEnum(_, expr) => bound_value(expr),
+        _ => None,
+    }
+}
+
+fn is_column(word: &Word<'_>, column: &str) -> bool {
+    if word.quoted {
+        word.text == column
+    } else {
+        word.text.eq_ignore_ascii_case(column)
+    }
+}
+
+/// The indices of the values the shard key `column` takes in raw SQL, or why they
+/// cannot be told.
+fn shard_keys(tokens: &[Token<'_>], column: &str) -> Result<Vec<usize>, String> {
+    let params = if is_keyword(tokens, 0, "INSERT") {
+        inserted_keys(tokens, column)?
+    } else {
+        filtered_keys(tokens, column)?
+    };
+    if params.is_empty() {
+        return Err(format!("the statement does not filter on `{column}`"));
+    }
+    Ok(params)
+}
+
+/// The index of each placeholder in the statement's values, by token
+fn param_indices(tokens: &[Token<'_>]) -> Vec<Option<usize>> {
+    let mut next = 0;
+    tokens
+        .iter()
+        .map(|token| match token {
+            Token::Placeholder(Some(n)) => Some(n.saturating_sub(1)),
+            Token::Placeholder(None) => {
+                next += 1;
+                Some(next - 1)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// The values of `column` in the rows of an `INSERT ... VALUES`.
+fn inserted_keys(tokens: &[Token<'_>], column: &str) -> Result<Vec<usize>, String> {
+    let params = param_indices(tokens);
+    let open = tokens
+        .iter()
+        .position(|t| matches!(t, Token::Open))
+        .ok_or("the INSERT has no column list")?;
+    let values = (open..tokens.len())
+        .find(|i| is_keyword(tokens, *i, "VALUES"))
+        .ok_or("the INSERT has no VALUES")?;
+    let position = tokens[open + 1..values]
+        .iter()
+        .filter(|t| !matches!(t, Token::Comma | Token::Close))
+        .position(|t| matches!(t, Token::Word(word) if is_column(word, column)))
+        .ok_or_else(|| format!("the INSERT does not set `{column}`"))?;
+
+    let mut keys = Vec::new();
+    let mut i = values + 1;
+    while let Some(Token::Open) = tokens.get(i) {
+        let (row, after) = split_group(tokens, i + 1);
+        match row.get(position) {
+            Some(item) if item.len() == 1 && params[item.start].is_some() => {
+                keys.extend(params[item.start]);
+            }
+            _ => return Err(format!("`{column}` is not a value in every row")),
+        }
+        match tokens.get(after) {
+            Some(Token::Comma) => i = after + 1,
+            _ => break,
+        }
+    }
+    Ok(keys)
+}
+
+/// Split the tokens from `start`, just after an opening parenthesis, into the ranges of
+/// the comma separated items up to the matching closing one, and the index after it.
+fn split_group(tokens: &[Token<'_>], start: usize) -> (Vec<Range<usize>>, usize) {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut item = start;
+    for (i, token) in tokens.iter().enumerate().skip(start) {
+        match token {
+            Token::Open => depth += 1,
+            Token::Close if depth == 0 => {
+                items.push(item..i);
+                return (items, i + 1);
+            }
+            Token::Close => depth -= 1,
+            Token::Comma if depth == 0 => {
+                items.push(item..i);
+                item = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(item..tokens.len());
+    (items, tokens.len())
+}
+
+/// The values `column` is compared for equality with in the conditions of a statement.
+fn filtered_keys(tokens: &[Token<'_>], column: &str) -> Result<Vec<usize>, String> {
+    let params = param_indices(tokens);
+    let start = if is_keyword(tokens, 0, "UPDATE") {
+        (0..tokens.len())
+            .find(|i| is_keyword(tokens, *i, "WHERE"))
+            .ok_or("the UPDATE has no WHERE clause")?
+    } else {
+        0
+    };
+
+    let is_key = |i: usize| matches!(&tokens[i], Token::Word(word) if is_column(word, column));
+    let mut keys = Vec::new();
+    for i in start..tokens.len() {
+        if is_keyword(tokens, i, "OR") {
+            return Err("the condition has an OR".to_owned());
+        }
+        if is_keyword(tokens, i, "NOT") && !(i > 0 && is_keyword(tokens, i - 1, "IS")) {
+            return Err("the condition has a NOT".to_owned());
+        }
+        if !matches!(tokens[i], Token::Equals) || i == 0 || i + 1 == tokens.len() {
+            continue;
+        }
+        match (params[i - 1], params[i + 1]) {
+            (None, Some(param)) if is_key(i - 1) => keys.push(param),
+            (Some(param), None) if is_key(i + 1) => keys.push(param),
+            _ => {}
+        }
+    }
+    Ok(keys)
+}
+
+#[cfg(test)]
+#[cfg(feature = "mock")]
+mod tests {
+    use super::*;
+    use crate::{
+        MockDatabase, MockExecResult, QueryTrait, Transaction, entity::*, query::*, tests_cfg::cake,
+    };
+    use pretty_assertions::assert_eq;
+    use sea_query::{Condition, LogicalChainOper, Query};
+
+    fn keys(sql: &str) -> Result<Vec<usize>, String> {
+        shard_keys(&tokenize(sql), "tenant_id")
+    }
+
+    #[test]
+    fn test_shard_keys() {
+        assert_eq!(
+            keys(
+                r#"SELECT "cake"."id" FROM "cake" WHERE "cake"."id" > $1 AND "cake"."tenant_id" = $2"#
+            ),
+            Ok(vec![1])
+        );
+        assert_eq!(
+            keys("SELECT `id` FROM `cake` WHERE `name` = '?' AND `tenant_id` = ? LIMIT ?"),
+            Ok(vec![0])
+        );
+        assert_eq!(
+            keys(r#"UPDATE "cake" SET "tenant_id" = ? WHERE "tenant_id" = ? AND "id" = ?"#),
+            Ok(vec![1])
+        );
+        assert_eq!(
+            keys(
+                r#"INSERT INTO "cake" ("name", "tenant_id") VALUES ($1, $2), ($3, $4) RETURNING "id""#
+            ),
+            Ok(vec![1, 3])
+        );
+        assert_eq!(
+            keys(r#"SELECT "id" FROM "cake" WHERE "tenant_id" = ? AND "name" IS NOT NULL"#),
+            Ok(vec![0])
+        );
+
+        assert!(keys(r#"SELECT "id" FROM "cake""#).is_err());
+        assert!(keys(r#"SELECT "id" FROM "cake" WHERE "tenant_id" >= $1"#).is_err());
+        assert!(keys(r#"SELECT "id" FROM "cake" WHERE "tenant_id" IN ($1, $2)"#).is_err());
+        assert!(keys(r#"SELECT "id" FROM "cake" WHERE "tenant_id" = $1 OR "id" = $2"#).is_err());
+        assert!(keys(r#"SELECT "id" FROM "cake" WHERE NOT ("tenant_id" = $1)"#).is_err());
+        assert!(keys(r#"UPDATE "cake" SET "tenant_id" = $1"#).is_err());
+        assert!(keys(r#"INSERT INTO "cake" ("name", "tenant_id") VALUES ($1, DEFAULT)"#).is_err());
+    }
+
+    fn cake(id: i32) -> cake::Model {
+        cake::Model {
+            id,
+            name: format!("cake {id}"),
+        }
+    }
+
+    /// Shard by cake id: odd ids on the first shard, even ones on the second
+    fn sharded(shards: [MockDatabase; 2]) -> ShardedConnection {
+        ShardedConnection::new(
+            shards.map(MockDatabase::into_connection),
+            cake::Column::Id,
+            |value| match value {
+                Value::Int(Some(id)) => Some(((id + 1) % 2) as usize),
+                _ => None,
+            },
+        )
+    }
+
+    #[test]
+    fn test_route_condition() {
+        let db = sharded([
+            MockDatabase::new(DbBackend::Postgres),
+            MockDatabase::new(DbBackend::Postgres),
+        ]);
+        let route = |query: &dyn Fn(Select<cake::Entity>) -> Select<cake::Entity>| {
+            db.route_builder(&query(cake::Entity::find()).into_query())
+                .map(|(route, _)| route)
+        };
+
+        assert_eq!(
+            route(&|q| q.filter(cake::Column::Id.is_in([1, 3, 5]))),
+            Ok(Route::Shard(0))
+        );
+        assert_eq!(
+            route(&|q| q.filter(cake::Column::Id.eq(2).or(cake::Column::Id.eq(4)))),
+            Ok(Route::Shard(1))
+        );
+        assert_eq!(
+            route(&|q| {
+                q.filter(cake::Column::Name.contains("chocolate"))
+                    .filter(cake::Column::Id.eq(3))
+            }),
+            Ok(Route::Shard(0))
+        );
+        assert_eq!(
+            route(&|q| {
+                q.filter(
+                    Condition::any()
+                        .add(cake::Column::Id.is_in([1, 2]))
+                        .add(cake::Column::Id.eq(3)),
+                )
+                .filter(cake::Column::Id.eq(1))
+            }),
+            Ok(Route::Shard(0))
+        );
+        // the subquery does not filter the rows of the statement
+        assert!(matches!(
+            route(&|q| {
+                q.filter(
+                    cake::Column::Name.in_subquery(
+                        Query::select()
+                            .column(cake::Column::Name)
+                            .from(cake::Entity)
+                            .and_where(cake::Column::Id.eq(1))
+                            .to_owned(),
+                    ),
+                )
+            }),
+            Err(DbErr::Unroutable(_))
+        ));
+        assert!(matches!(
+            route(&|q| q.filter(cake::Column::Id.is_in([1, 2]))),
+            Err(DbErr::Unroutable(_))
+        ));
+        assert!(matches!(
+            route(&|q| q.filter(cake::Column::Id.eq(1).or(cake::Column::Name.eq("cake")))),
+            Err(DbErr::Unroutable(_))
+        ));
+        assert!(matches!(
+            route(&|q| q.filter(cake::Column::Id.ne(1))),
+            Err(DbErr::Unroutable(_))
+        ));
+
+        // AND binds tighter than OR in a chain of conditions
+        let chained = Query::select()
+            .column(cake::Column::Id)
+            .from(cake::Entity)
+            .and_or_where(LogicalChainOper::And(cake::Column::Name.eq("cake")))
+            .and_or_where(LogicalChainOper::Or(cake::Column::Id.eq(2)))
+            .to_owned();
+        assert!(matches!(
+            db.route_builder(&chained),
+            Err(DbErr::Unroutable(_))
+        ));
+        let chained = Query::update()
+            .table(cake::Entity)
+            .value(cake::Column::Name, "cake")
+            .and_or_where(LogicalChainOper::And(cake::Column::Id.eq(1)))
+            .and_or_where(LogicalChainOper::Or(cake::Column::Id.eq(3)))
+            .to_owned();
+        assert_eq!(
+            db.route_builder(&chained).map(|(route, _)| route),
+            Ok(Route::Shard(0))
+        );
+
+        let db = db.unrouted(UnroutedStatement::FanOut);
+        assert_eq!(
+            db.route_builder(
+                &cake::Entity::delete_many()
+                    .filter(cake::Column::Id.is_in([1, 2]))
+                    .into_query()
+            )
+            .map(|(route, _)| route),
+            Ok(Route::Shards(vec![0, 1]))
+        );
+    }
+
+    #[test]
+    fn test_routed() -> Result<(), DbErr> {
+        let db = sharded([
+            MockDatabase::new(DbBackend::Postgres).append_query_results([[cake(1)]]),
+            MockDatabase::new(DbBackend::Postgres)
+                .append_query_results([[cake(2)]])
+                .append_exec_results([MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                }]),
+        ]);
+
+        assert_eq!(cake::Entity::find_by_id(1).one(&db)?, Some(cake(1)));
+        assert_eq!(cake::Entity::find_by_id(2).one(&db)?, Some(cake(2)));
+        let res = cake::Entity::delete_by_id(4).exec(&db)?;
+        assert_eq!(res.rows_affected, 1);
+        assert!(db.shard_for(3)?.is_mock_connection());
+        assert!(matches!(
+            db.shard_for(Option::<i32>::None),
+            Err(DbErr::Unroutable(_))
+        ));
+
+        let first = db.shards()[0].clone().into_transaction_log();
+        let second = db.shards()[1].clone().into_transaction_log();
+        assert_eq!(first.len(), 1);
+        assert_eq!(second.len(), 2);
+        assert_eq!(
+            second[1],
+            Transaction::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"DELETE FROM "cake" WHERE "cake"."id" = $1"#,
+                [4i32.into()]
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unrouted() -> Result<(), DbErr> {
+        let db = sharded([
+            MockDatabase::new(DbBackend::Postgres),
+            MockDatabase::new(DbBackend::Postgres),
+        ]);
+        assert!(matches!(
+            cake::Entity::find().all(&db),
+            Err(DbErr::Unroutable(_))
+        ));
+
+        let exec = MockExecResult {
+            last_insert_id: 0,
+            rows_affected: 2,
+        };
+        let db = sharded([
+            MockDatabase::new(DbBackend::Postgres)
+                .append_query_results([[cake(1), cake(3)]])
+                .append_exec_results([exec.clone()]),
+            MockDatabase::new(DbBackend::Postgres)
+                .append_query_results([[cake(2)]])
+                .append_exec_results([exec]),
+        ])
+        .unrouted(UnroutedStatement::FanOut);
+
+        assert_eq!(cake::Entity::find().all(&db)?, [cake(1), cake(3), cake(2)]);
+        let res = cake::Entity::delete_many().exec(&db)?;
+        assert_eq!(res.rows_affected, 4);
+
+        // there is no telling which shard a row without a key belongs to
+        assert!(matches!(
+            cake::Entity::insert(cake::ActiveModel {
+                name: Set("cake".to_owned()),
+                ..Default::default()
+            })
+            .exec(&db),
+            Err(DbErr::Unroutable(_))
+        ));
+
+        Ok(())
+    }
+}
@@ -10,7 +10,10 @@ type PinBoxStream<'a> = Box<dyn Iterator<Item = Result<QueryResult, DbErr>> + 'a
 pub(crate) struct MetricStream<'a> {
     metric_callback: &'a Option<crate::metric::Callback>,
     stmt: &'a Statement,
+    in_transaction: bool,
     elapsed: Option<Duration>,
+    rows: u64,
+    failed: bool,
     stream: PinBoxStream<'a>,
 }
 
@@ -19,6 +22,7 @@ impl<'a> MetricStream<'a> {
     pub(crate) fn new<S>(
         metric_callback: &'a Option<crate::metric::Callback>,
         stmt: &'a Statement,
+        in_transaction: bool,
         elapsed: Option<Duration>,
         stream: S,
     ) -> Self
@@ -28,7 +32,10 @@ impl<'a> MetricStream<'a> {
         MetricStream {
             metric_callback,
             stmt,
+            in_transaction,
             elapsed,
+            rows: 0,
+            failed: false,
             stream: Box::new(stream),
         }
     }
@@ -51,6 +58,11 @@ impl Stream for MetricStream<'_> {
         if let (Some(_start), Some(elapsed)) = (_start, &mut this.elapsed) {
             *elapsed += _start.elapsed().unwrap_or_default();
         }
+        match &res {
+            Poll::Ready(Some(Ok(_))) => this.rows += 1,
+            Poll::Ready(Some(Err(_))) => this.failed = true,
+            _ => (),
+        }
         res
     }
 }
@@ -68,6 +80,11 @@ impl Iterator for MetricStream<'_> {
         if let (Some(_start), Some(elapsed)) = (_start, &mut self.elapsed) {
             *elapsed += _start.elapsed().unwrap_or_default();
         }
+        match &res {
+            Some(Ok(_)) => self.rows += 1,
+            Some(Err(_)) => self.failed = true,
+            None => (),
+        }
         res
     }
 }
@@ -78,7 +95,10 @@ impl Drop for MetricStream<'_> {
             let info = crate::metric::Info {
                 elapsed,
                 statement: self.stmt,
-                failed: false,
+                failed: self.failed,
+                rows: (!self.failed).then_some(self.rows),
+                in_transaction: self.in_transaction,
+                acquire_elapsed: None,
             };
             callback(&info);
         }
@@ -56,7 +56,7 @@ impl TransactionStream<'_> {
                         .map_ok(Into::into)
                         .map_err(sqlx_error_to_query_err);
                     let elapsed = start.map(|s| s.elapsed().unwrap_or_default());
-                    MetricStream::new(_metric_callback, stmt, elapsed, stream)
+                    MetricStream::new(_metric_callback, stmt, true, elapsed, stream)
                 }
                 #[cfg(feature = "sqlx-postgres")]
                 InnerConnection::Postgres(c) => {
@@ -67,7 +67,7 @@ impl TransactionStream<'_> {
                         .map_ok(Into::into)
                         .map_err(sqlx_error_to_query_err);
                     let elapsed = start.map(|s| s.elapsed().unwrap_or_default());
-                    MetricStream::new(_metric_callback, stmt, elapsed, stream)
+                    MetricStream::new(_metric_callback, stmt, true, elapsed, stream)
                 }
                 #[cfg(feature = "sqlx-sqlite")]
                 InnerConnection::Sqlite(c) => {
@@ -78,26 +78,26 @@ impl TransactionStream<'_> {
                         .map_ok(Into::into)
                         .map_err(sqlx_error_to_query_err);
                     let elapsed = start.map(|s| s.elapsed().unwrap_or_default());
-                    MetricStream::new(_metric_callback, stmt, elapsed, stream)
+                    MetricStream::new(_metric_callback, stmt, true, elapsed, stream)
                 }
                 #[cfg(feature = "mock")]
                 InnerConnection::Mock(c) => {
                     let start = _metric_callback.is_some().then(std::time::SystemTime::now);
                     let stream = c.fetch(stmt);
                     let elapsed = start.map(|s| s.elapsed().unwrap_or_default());
-                    MetricStream::new(_metric_callback, stmt, elapsed, stream)
+                    MetricStream::new(_metric_callback, stmt, true, elapsed, stream)
                 }
                 #[cfg(feature = "proxy")]
                 InnerConnection::Proxy(c) => {
                     let start = _metric_callback.is_some().then(std::time::SystemTime::now);
-                    let stream = futures_util::stream::once({
+                    let stream = std::iter::once({
                         Err(DbErr::BackendNotSupported {
                             db: "Proxy",
                             ctx: "TransactionStream",
                         })
                     });
                     let elapsed = start.map(|s| s.elapsed().unwrap_or_default());
-                    MetricStream::new(_metric_callback, stmt, elapsed, stream)
+                    MetricStream::new(_metric_callback, stmt, true, elapsed, stream)
                 }
                 #[allow(unreachable_patterns)]
                 _ => unreachable!(),
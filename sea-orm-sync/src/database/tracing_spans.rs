@@ -24,9 +24,40 @@
 //! let cakes = Cake::find().all(&db)?;  // Generates a span
 //! ```
 
+use std::sync::Arc;
+
+/// The database a connection talks to, recorded on its spans as the OpenTelemetry
+/// `db.namespace` and `server.address` attributes.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(not(feature = "tracing-spans"), allow(dead_code))]
+pub(crate) struct DbTarget {
+    namespace: Option<Arc<str>>,
+    server_address: Option<Arc<str>>,
+}
+
+#[cfg_attr(not(feature = "tracing-spans"), allow(dead_code))]
+impl DbTarget {
+    pub(crate) fn new(namespace: Option<&str>, server_address: Option<&str>) -> Self {
+        Self {
+            namespace: namespace.filter(|s| !s.is_empty()).map(Into::into),
+            server_address: server_address.filter(|s| !s.is_empty()).map(Into::into),
+        }
+    }
+
+    /// The database name, or the file of an embedded database
+    pub(crate) fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
+
+    /// Host name or socket path of the database server
+    pub(crate) fn server_address(&self) -> Option<&str> {
+        self.server_address.as_deref()
+    }
+}
+
 #[cfg(feature = "tracing-spans")]
 mod inner {
-    use crate::DbBackend;
+    use crate::{DbBackend, DbErr, SqlErr};
 
     /// Database operation type, following OpenTelemetry conventions.
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -48,7 +79,7 @@ mod inner {
         ///
         /// This function is allocation-free and uses case-insensitive comparison.
         pub fn from_sql(sql: &str) -> Self {
-            let first_word = sql.trim_start().split_whitespace().next().unwrap_or("");
+            let first_word = sql.split_whitespace().next().unwrap_or("");
             if first_word.eq_ignore_ascii_case("SELECT") {
                 DbOperation::Select
             } else if first_word.eq_ignore_ascii_case("INSERT") {
@@ -84,17 +115,77 @@ mod inner {
         }
     }
 
+    /// The parent span of the statements run in a transaction
+    pub(crate) fn transaction_span(backend: DbBackend, target: &super::DbTarget) -> tracing::Span {
+        tracing::info_span!(
+            "sea_orm.transaction",
+            otel.kind = "client",
+            db.system = db_system_name(backend),
+            db.namespace = target.namespace(),
+            server.address = target.server_address(),
+            otel.status_code = tracing::field::Empty,
+        )
+    }
+
+    /// The span of waiting for a connection from a pool
+    pub(crate) fn acquire_span(backend: DbBackend, target: &super::DbTarget) -> tracing::Span {
+        tracing::info_span!(
+            "sea_orm.acquire",
+            otel.kind = "client",
+            db.system = db_system_name(backend),
+            db.namespace = target.namespace(),
+            server.address = target.server_address(),
+            otel.status_code = tracing::field::Empty,
+            error.type = tracing::field::Empty,
+            exception.message = tracing::field::Empty,
+        )
+    }
+
+    /// The table a statement operates on, for `db.collection.name`.
+    /// `None` if the statement touches no table or more than one.
+    pub(crate) fn collection_name(sql: &str) -> Option<String> {
+        let mut tables = crate::metric::statement_tables(sql);
+        if tables.len() == 1 {
+            tables.pop()
+        } else {
+            None
+        }
+    }
+
+    /// A low-cardinality class of the error, for `error.type`
+    pub(crate) fn error_type(err: &DbErr) -> &'static str {
+        match err.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(_)) => "unique_constraint_violation",
+            Some(SqlErr::ForeignKeyConstraintViolation(_)) => "foreign_key_constraint_violation",
+            Some(SqlErr::NotNullViolation(_)) => "not_null_violation",
+            Some(SqlErr::CheckConstraintViolation(_)) => "check_constraint_violation",
+            Some(SqlErr::SerializationFailure(_)) => "serialization_failure",
+            Some(SqlErr::Deadlock(_)) => "deadlock",
+            Some(SqlErr::LockTimeout(_)) => "lock_timeout",
+            Some(SqlErr::StatementTimeout(_)) => "statement_timeout",
+            Some(SqlErr::ValueOutOfRange(_)) => "value_out_of_range",
+            None => match err {
+                DbErr::ConnectionAcquire(_) => "connection_acquire",
+                DbErr::Conn(_) => "connection",
+                DbErr::Exec(_) => "exec",
+                DbErr::Query(_) => "query",
+                DbErr::RecordNotFound(_) => "record_not_found",
+                DbErr::QueryTimeout(_) => "query_timeout",
+                DbErr::Type(_) | DbErr::TryIntoErr { .. } => "type",
+                _ => "_OTHER",
+            },
+        }
+    }
+
     /// Record query result on a span (success/failure status and error message).
-    pub(crate) fn record_query_result<T, E: std::fmt::Display>(
-        span: &tracing::Span,
-        result: &Result<T, E>,
-    ) {
+    pub(crate) fn record_query_result<T>(span: &tracing::Span, result: &Result<T, DbErr>) {
         match result {
             Ok(_) => {
                 span.record("otel.status_code", "OK");
             }
             Err(e) => {
                 span.record("otel.status_code", "ERROR");
+                span.record("error.type", error_type(e));
                 span.record("exception.message", tracing::field::display(e));
             }
         }
@@ -109,22 +200,40 @@ pub(crate) use inner::*;
 /// Arguments:
 /// - `$name`: Span name (e.g., "sea_orm.execute", "sea_orm.query_one")
 /// - `$backend`: DbBackend value
-/// - `$sql`: SQL statement string (used for operation parsing)
+/// - `$target`: `&DbTarget` of the connection
+/// - `$sql`: SQL statement string (used for operation and collection parsing)
+/// - `parent`: parent span, the current span if omitted
 ///
 /// Note: `db.statement` is set to Empty. Call `span.record("db.statement", sql)`
 /// separately if the query is parameterized (safe to log).
 #[cfg(feature = "tracing-spans")]
 macro_rules! db_span {
-    ($name:expr, $backend:expr, $sql:expr) => {{
+    ($name:expr, $backend:expr, $target:expr, $sql:expr) => {
+        $crate::database::tracing_spans::db_span!(
+            $name,
+            $backend,
+            $target,
+            $sql,
+            parent = &::tracing::Span::current()
+        )
+    };
+    ($name:expr, $backend:expr, $target:expr, $sql:expr, parent = $parent:expr) => {{
         let sql: &str = $sql;
+        let target: &$crate::database::tracing_spans::DbTarget = $target;
         let op = $crate::database::tracing_spans::DbOperation::from_sql(sql);
         ::tracing::info_span!(
+            parent: $parent,
             $name,
             otel.kind = "client",
             db.system = $crate::database::tracing_spans::db_system_name($backend),
+            db.namespace = target.namespace(),
+            server.address = target.server_address(),
             db.operation = %op,
+            db.operation.name = %op,
+            db.collection.name = $crate::database::tracing_spans::collection_name(sql),
             db.statement = ::tracing::field::Empty,
             otel.status_code = ::tracing::field::Empty,
+            error.type = ::tracing::field::Empty,
             exception.message = ::tracing::field::Empty,
         )
     }};
@@ -140,14 +249,33 @@ pub(crate) use db_span;
 /// # Arguments
 /// - `$name`: span name (e.g., "sea_orm.execute")
 /// - `$backend`: DbBackend
+/// - `$target`: `&DbTarget` of the connection
 /// - `$sql`: &str used for db.operation parsing
+/// - `parent`: parent span, the current span if omitted
 /// - `record_stmt`: whether to record `db.statement`
 /// - `$fut`: the future to execute
 macro_rules! with_db_span {
-    ($name:expr, $backend:expr, $sql:expr, record_stmt = $record_stmt:expr, $fut:expr) => {{
+    ($name:expr, $backend:expr, $target:expr, $sql:expr, record_stmt = $record_stmt:expr, $fut:expr) => {
+        $crate::database::tracing_spans::with_db_span!(
+            $name,
+            $backend,
+            $target,
+            $sql,
+            parent = &::tracing::Span::current(),
+            record_stmt = $record_stmt,
+            $fut
+        )
+    };
+    ($name:expr, $backend:expr, $target:expr, $sql:expr, parent = $parent:expr, record_stmt = $record_stmt:expr, $fut:expr) => {{
         #[cfg(all(feature = "tracing-spans", not(feature = "sync")))]
         {
-            let span = $crate::database::tracing_spans::db_span!($name, $backend, $sql);
+            let span = $crate::database::tracing_spans::db_span!(
+                $name,
+                $backend,
+                $target,
+                $sql,
+                parent = $parent
+            );
             if $record_stmt {
                 span.record("db.statement", $sql);
             }
@@ -157,11 +285,19 @@ macro_rules! with_db_span {
         }
         #[cfg(all(feature = "tracing-spans", feature = "sync"))]
         {
-            let span = $crate::database::tracing_spans::db_span!($name, $backend, $sql);
+            let span = $crate::database::tracing_spans::db_span!(
+                $name,
+                $backend,
+                $target,
+                $sql,
+                parent = $parent
+            );
             if $record_stmt {
                 span.record("db.statement", $sql);
             }
-            span.in_scope(|| $fut)
+            let result = span.in_scope(|| $fut);
+            $crate::database::tracing_spans::record_query_result(&span, &result);
+            result
         }
         #[cfg(not(feature = "tracing-spans"))]
         {
@@ -176,6 +312,7 @@ pub(crate) use with_db_span;
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{DbBackend, DbErr};
 
     #[test]
     fn test_db_operation_from_sql() {
@@ -228,4 +365,37 @@ mod tests {
         assert_eq!(DbOperation::Delete.to_string(), "DELETE");
         assert_eq!(DbOperation::Execute.to_string(), "EXECUTE");
     }
+
+    #[test]
+    fn test_collection_name() {
+        assert_eq!(
+            collection_name("SELECT * FROM users WHERE id = 1"),
+            Some("users".to_owned())
+        );
+        assert_eq!(
+            collection_name(r#"INSERT INTO "cake" ("name") VALUES ('x')"#),
+            Some("cake".to_owned())
+        );
+        assert_eq!(
+            collection_name("SELECT * FROM cake JOIN fruit ON cake.id = fruit.cake_id"),
+            None
+        );
+        assert_eq!(collection_name("SELECT 1"), None);
+    }
+
+    #[test]
+    fn test_error_type() {
+        assert_eq!(
+            error_type(&DbErr::RecordNotFound("cake".to_owned())),
+            "record_not_found"
+        );
+        assert_eq!(error_type(&DbErr::Custom("oops".to_owned())), "_OTHER");
+    }
+
+    #[test]
+    fn test_db_target() {
+        let target = DbTarget::new(Some("bakery"), Some(""));
+        assert_eq!(target.namespace(), Some("bakery"));
+        assert_eq!(target.server_address(), None);
+    }
 }
@@ -0,0 +1,361 @@
+//! Serde representation of [`Value`], keeping its variant so that it decodes back into
+//! the same type. `sea-query` only derives one behind its own `serde` feature.
+
+use sea_query::{Enum, OptionEnum, Value};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize)]
+enum ValueRepr {
+    Bool(Option<bool>),
+    TinyInt(Option<i8>),
+    SmallInt(Option<i16>),
+    Int(Option<i32>),
+    BigInt(Option<i64>),
+    TinyUnsigned(Option<u8>),
+    SmallUnsigned(Option<u16>),
+    Unsigned(Option<u32>),
+    BigUnsigned(Option<u64>),
+    Float(Option<f32>),
+    Double(Option<f64>),
+    String(Option<String>),
+    Enum {
+        type_name: String,
+        value: Option<String>,
+    },
+    Char(Option<char>),
+    Bytes(Option<Vec<u8>>),
+    Json(Option<serde_json::Value>),
+    #[cfg(feature = "with-chrono")]
+    ChronoDate(Option<chrono::NaiveDate>),
+    #[cfg(feature = "with-chrono")]
+    ChronoTime(Option<chrono::NaiveTime>),
+    #[cfg(feature = "with-chrono")]
+    ChronoDateTime(Option<chrono::NaiveDateTime>),
+    #[cfg(feature = "with-chrono")]
+    ChronoDateTimeUtc(Option<chrono::DateTime<chrono::Utc>>),
+    #[cfg(feature = "with-chrono")]
+    ChronoDateTimeLocal(Option<chrono::DateTime<chrono::Local>>),
+    #[cfg(feature = "with-chrono")]
+    ChronoDateTimeWithTimeZone(Option<chrono::DateTime<chrono::FixedOffset>>),
+    #[cfg(feature = "with-time")]
+    TimeDate(Option<time::Date>),
+    #[cfg(feature = "with-time")]
+    TimeTime(Option<time::Time>),
+    #[cfg(feature = "with-time")]
+    TimeDateTime(Option<time::PrimitiveDateTime>),
+    #[cfg(feature = "with-time")]
+    TimeDateTimeWithTimeZone(Option<time::OffsetDateTime>),
+    #[cfg(feature = "with-uuid")]
+    Uuid(Option<uuid::Uuid>),
+    #[cfg(feature = "with-rust_decimal")]
+    Decimal(Option<rust_decimal::Decimal>),
+    #[cfg(feature = "with-bigdecimal")]
+    BigDecimal(Option<bigdecimal::BigDecimal>),
+    /// The element type is kept as a null value of that type
+    #[cfg(feature = "postgres-array")]
+    Array {
+        element: Box<ValueRepr>,
+        values: Option<Vec<ValueRepr>>,
+    },
+    #[cfg(feature = "postgres-vector")]
+    Vector(Option<pgvector::Vector>),
+    #[cfg(feature = "with-ipnetwork")]
+    IpNetwork(Option<ipnetwork::IpNetwork>),
+    #[cfg(feature = "with-mac_address")]
+    MacAddress(Option<mac_address::MacAddress>),
+}
+
+impl From<&Value> for ValueRepr {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Bool(v) => Self::Bool(*v),
+            Value::TinyInt(v) => Self::TinyInt(*v),
+            Value::SmallInt(v) => Self::SmallInt(*v),
+            Value::Int(v) => Self::Int(*v),
+            Value::BigInt(v) => Self::BigInt(*v),
+            Value::TinyUnsigned(v) => Self::TinyUnsigned(*v),
+            Value::SmallUnsigned(v) => Self::SmallUnsigned(*v),
+            Value::Unsigned(v) => Self::Unsigned(*v),
+            Value::BigUnsigned(v) => Self::BigUnsigned(*v),
+            Value::Float(v) => Self::Float(*v),
+            Value::Double(v) => Self::Double(*v),
+            Value::String(v) => Self::String(v.clone()),
+            Value::Enum(OptionEnum::Some(v)) => Self::Enum {
+                type_name: v.type_name.to_string(),
+                value: Some(v.value.to_string()),
+            },
+            Value::Enum(OptionEnum::None(type_name)) => Self::Enum {
+                type_name: type_name.to_string(),
+                value: None,
+            },
+            Value::Char(v) => Self::Char(*v),
+            Value::Bytes(v) => Self::Bytes(v.clone()),
+            Value::Json(v) => Self::Json(v.as_deref().cloned()),
+            #[cfg(feature = "with-chrono")]
+            Value::ChronoDate(v) => Self::ChronoDate(*v),
+            #[cfg(feature = "with-chrono")]
+            Value::ChronoTime(v) => Self::ChronoTime(*v),
+            #[cfg(feature = "with-chrono")]
+            Value::ChronoDateTime(v) => Self::ChronoDateTime(*v),
+            #[cfg(feature = "with-chrono")]
+            Value::ChronoDateTimeUtc(v) => Self::ChronoDateTimeUtc(*v),
+            #[cfg(feature = "with-chrono")]
+            Value::ChronoDateTimeLocal(v) => Self::ChronoDateTimeLocal(*v),
+            #[cfg(feature = "with-chrono")]
+            Value::ChronoDateTimeWithTimeZone(v) => Self::ChronoDateTimeWithTimeZone(*v),
+            #[cfg(feature = "with-time")]
+            Value::TimeDate(v) => Self::TimeDate(*v),
+            #[cfg(feature = "with-time")]
+            Value::TimeTime(v) => Self::TimeTime(*v),
+            #[cfg(feature = "with-time")]
+            Value::TimeDateTime(v) => Self::TimeDateTime(*v),
+            #[cfg(feature = "with-time")]
+            Value::TimeDateTimeWithTimeZone(v) => Self::TimeDateTimeWithTimeZone(*v),
+            #[cfg(feature = "with-uuid")]
+            Value::Uuid(v) => Self::Uuid(*v),
+            #[cfg(feature = "with-rust_decimal")]
+            Value::Decimal(v) => Self::Decimal(*v),
+            #[cfg(feature = "with-bigdecimal")]
+            Value::BigDecimal(v) => Self::BigDecimal(v.as_deref().cloned()),
+            #[cfg(feature = "postgres-array")]
+            Value::Array(ty, v) => Self::Array {
+                element: Box::new(Self::from(&null_of(ty))),
+                values: v.as_ref().map(|v| v.iter().map(Self::from).collect()),
+            },
+            #[cfg(feature = "postgres-vector")]
+            Value::Vector(v) => Self::Vector(v.clone()),
+            #[cfg(feature = "with-ipnetwork")]
+            Value::IpNetwork(v) => Self::IpNetwork(*v),
+            #[cfg(feature = "with-mac_address")]
+            Value::MacAddress(v) => Self::MacAddress(*v),
+        }
+    }
+}
+
+impl From<ValueRepr> for Value {
+    fn from(value: ValueRepr) -> Self {
+        match value {
+            ValueRepr::Bool(v) => Self::Bool(v),
+            ValueRepr::TinyInt(v) => Self::TinyInt(v),
+            ValueRepr::SmallInt(v) => Self::SmallInt(v),
+            ValueRepr::Int(v) => Self::Int(v),
+            ValueRepr::BigInt(v) => Self::BigInt(v),
+            ValueRepr::TinyUnsigned(v) => Self::TinyUnsigned(v),
+            ValueRepr::SmallUnsigned(v) => Self::SmallUnsigned(v),
+            ValueRepr::Unsigned(v) => Self::Unsigned(v),
+            ValueRepr::BigUnsigned(v) => Self::BigUnsigned(v),
+            ValueRepr::Float(v) => Self::Float(v),
+            ValueRepr::Double(v) => Self::Double(v),
+            ValueRepr::String(v) => Self::String(v),
+            ValueRepr::Enum {
+                type_name,
+                value: Some(value),
+            } => Self::Enum(OptionEnum::Some(Box::new(Enum {
+                type_name: type_name.into(),
+                value: value.into(),
+            }))),
+            ValueRepr::Enum {
+                type_name,
+                value: None,
+            } => Self::Enum(OptionEnum::None(type_name.into())),
+            ValueRepr::Char(v) => Self::Char(v),
+            ValueRepr::Bytes(v) => Self::Bytes(v),
+            ValueRepr::Json(v) => Self::Json(v.map(Box::new)),
+            #[cfg(feature = "with-chrono")]
+            ValueRepr::ChronoDate(v) => Self::ChronoDate(v),
+            #[cfg(feature = "with-chrono")]
+            ValueRepr::ChronoTime(v) => Self::ChronoTime(v),
+            #[cfg(feature = "with-chrono")]
+            ValueRepr::ChronoDateTime(v) => Self::ChronoDateTime(v),
+            #[cfg(feature = "with-chrono")]
+            ValueRepr::ChronoDateTimeUtc(v) => Self::ChronoDateTimeUtc(v),
+            #[cfg(feature = "with-chrono")]
+            ValueRepr::ChronoDateTimeLocal(v) => Self::ChronoDateTimeLocal(v),
+            #[cfg(feature = "with-chrono")]
+            ValueRepr::ChronoDateTimeWithTimeZone(v) => Self::ChronoDateTimeWithTimeZone(v),
+            #[cfg(feature = "with-time")]
+            ValueRepr::TimeDate(v) => Self::TimeDate(v),
+            #[cfg(feature = "with-time")]
+            ValueRepr::TimeTime(v) => Self::TimeTime(v),
+            #[cfg(feature = "with-time")]
+            ValueRepr::TimeDateTime(v) => Self::TimeDateTime(v),
+            #[cfg(feature = "with-time")]
+            ValueRepr::TimeDateTimeWithTimeZone(v) => Self::TimeDateTimeWithTimeZone(v),
+            #[cfg(feature = "with-uuid")]
+            ValueRepr::Uuid(v) => Self::Uuid(v),
+            #[cfg(feature = "with-rust_decimal")]
+            ValueRepr::Decimal(v) => Self::Decimal(v),
+            #[cfg(feature = "with-bigdecimal")]
+            ValueRepr::BigDecimal(v) => Self::BigDecimal(v.map(Box::new)),
+            #[cfg(feature = "postgres-array")]
+            ValueRepr::Array { element, values } => Self::Array(
+                Value::from(*element).array_type(),
+                values.map(|v| Box::new(v.into_iter().map(Value::from).collect())),
+            ),
+            #[cfg(feature = "postgres-vector")]
+            ValueRepr::Vector(v) => Self::Vector(v),
+            #[cfg(feature = "with-ipnetwork")]
+            ValueRepr::IpNetwork(v) => Self::IpNetwork(v),
+            #[cfg(feature = "with-mac_address")]
+            ValueRepr::MacAddress(v) => Self::MacAddress(v),
+        }
+    }
+}
+
+/// A null value of the element type of an array
+#[cfg(feature = "postgres-array")]
+fn null_of(ty: &sea_query::ArrayType) -> Value {
+    use sea_query::ArrayType;
+
+    match ty {
+        ArrayType::Bool => Value::Bool(None),
+        ArrayType::TinyInt => Value::TinyInt(None),
+        ArrayType::SmallInt => Value::SmallInt(None),
+        ArrayType::Int => Value::Int(None),
+        ArrayType::BigInt => Value::BigInt(None),
+        ArrayType::TinyUnsigned => Value::TinyUnsigned(None),
+        ArrayType::SmallUnsigned => Value::SmallUnsigned(None),
+        ArrayType::Unsigned => Value::Unsigned(None),
+        ArrayType::BigUnsigned => Value::BigUnsigned(None),
+        ArrayType::Float => Value::Float(None),
+        ArrayType::Double => Value::Double(None),
+        ArrayType::String => Value::String(None),
+        ArrayType::Enum(type_name) => Value::Enum(OptionEnum::None(type_name.as_ref().clone())),
+        ArrayType::Char => Value::Char(None),
+        ArrayType::Bytes => Value::Bytes(None),
+        ArrayType::Json => Value::Json(None),
+        #[cfg(feature = "with-chrono")]
+        ArrayType::ChronoDate => Value::ChronoDate(None),
+        #[cfg(feature = "with-chrono")]
+        ArrayType::ChronoTime => Value::ChronoTime(None),
+        #[cfg(feature = "with-chrono")]
+        ArrayType::ChronoDateTime => Value::ChronoDateTime(None),
+        #[cfg(feature = "with-chrono")]
+        ArrayType::ChronoDateTimeUtc => Value::ChronoDateTimeUtc(None),
+        #[cfg(feature = "with-chrono")]
+        ArrayType::ChronoDateTimeLocal => Value::ChronoDateTimeLocal(None),
+        #[cfg(feature = "with-chrono")]
+        ArrayType::ChronoDateTimeWithTimeZone => Value::ChronoDateTimeWithTimeZone(None),
+        #[cfg(feature = "with-time")]
+        ArrayType::TimeDate => Value::TimeDate(None),
+        #[cfg(feature = "with-time")]
+        ArrayType::TimeTime => Value::TimeTime(None),
+        #[cfg(feature = "with-time")]
+        ArrayType::TimeDateTime => Value::TimeDateTime(None),
+        #[cfg(feature = "with-time")]
+        ArrayType::TimeDateTimeWithTimeZone => Value::TimeDateTimeWithTimeZone(None),
+        #[cfg(feature = "with-uuid")]
+        ArrayType::Uuid => Value::Uuid(None),
+        #[cfg(feature = "with-rust_decimal")]
+        ArrayType::Decimal => Value::Decimal(None),
+        #[cfg(feature = "with-bigdecimal")]
+        ArrayType::BigDecimal => Value::BigDecimal(None),
+        #[cfg(feature = "with-ipnetwork")]
+        ArrayType::IpNetwork => Value::IpNetwork(None),
+        #[cfg(feature = "with-mac_address")]
+        ArrayType::MacAddress => Value::MacAddress(None),
+    }
+}
+
+/// `#[serde(with = "value_serde::map")]` for a map of column names to values
+pub(crate) mod map {
+    use super::*;
+
+    pub(crate) fn serialize<S>(
+        values: &BTreeMap<String, Value>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let values: BTreeMap<&str, ValueRepr> = values
+            .iter()
+            .map(|(k, v)| (k.as_str(), ValueRepr::from(v)))
+            .collect();
+        values.serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<BTreeMap<String, Value>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let values = BTreeMap::<String, ValueRepr>::deserialize(deserializer)?;
+        Ok(values.into_iter().map(|(k, v)| (k, v.into())).collect())
+    }
+}
+
+/// `#[serde(with = "value_serde::vec")]` for a list of values
+#[cfg(feature = "mock")]
+pub(crate) mod vec {
+    use super::*;
+
+    pub(crate) fn serialize<S>(values: &[Value], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let values: Vec<ValueRepr> = values.iter().map(ValueRepr::from).collect();
+        values.serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Vec<Value>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let values = Vec::<ValueRepr>::deserialize(deserializer)?;
+        Ok(values.into_iter().map(Value::from).collect())
+    }
+}
+
+/// `#[serde(with = "value_serde::values")]` for the values bound to a statement, keeping
+/// `None` apart from an empty list
+pub(crate) mod values {
+    use super::*;
+    use sea_query::Values;
+
+    pub(crate) fn serialize<S>(values: &Option<Values>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let values: Option<Vec<ValueRepr>> = values
+            .as_ref()
+            .map(|values| values.0.iter().map(ValueRepr::from).collect());
+        values.serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Option<Values>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let values = Option::<Vec<ValueRepr>>::deserialize(deserializer)?;
+        Ok(values.map(|values| Values(values.into_iter().map(Value::from).collect())))
+    }
+}
+
+/// `#[serde(with = "value_serde::backend_name")]` for a [`DbBackend`](crate::DbBackend), by its name
+pub(crate) mod backend_name {
+    use crate::DbBackend;
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub(crate) fn serialize<S>(db_backend: &DbBackend, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(db_backend.as_str())
+    }
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<DbBackend, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let name = String::deserialize(deserializer)?;
+        match name.as_str() {
+            "MySql" => Ok(DbBackend::MySql),
+            "Postgres" => Ok(DbBackend::Postgres),
+            "Sqlite" => Ok(DbBackend::Sqlite),
+            _ => Err(D::Error::custom(format!(
+                "unknown database backend `{name}`"
+            ))),
+        }
+    }
+}
@@ -0,0 +1,1133 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+use std::{
+    ops::Deref,
+    sync::{Arc, Mutex, MutexGuard, TryLockError, mpsc},
+    time::Duration,
+};
+use tracing::{debug, instrument};
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+use ::duckdb::{
+    Config, Row, params_from_iter,
+    types::{FromSql, FromSqlError, Type, Value},
+};
+pub use ::duckdb::{Connection as DuckdbConnection, Error as DuckdbError};
+pub use OwnedRow as DuckdbRow;
+
+use crate::{
+    AccessMode, ColIdx, ConnectOptions, DatabaseConnection, DatabaseConnectionType,
+    DatabaseTransaction, InnerConnection, IsolationLevel, Statement, TransactionError,
+    TransactionOptions, database::tracing_spans::DbTarget, error::*, executor::*,
+};
+
+#[cfg(feature = "stream")]
+use crate::QueryStream;
+
+/// A helper class to connect to an embedded DuckDB database
+#[derive(Debug)]
+pub struct DuckdbConnector;
+
+const DEFAULT_ACQUIRE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A shared DuckDB connection.
+///
+/// DuckDB is an embedded, in-process database, so like the `rusqlite` driver this
+/// holds a single [`DuckdbConnection`] behind an `Arc<Mutex<State>>` instead of a
+/// pool. [`acquire`](Self::acquire) spins with `try_lock` until the connection is
+/// available or the `acquire_timeout` deadline expires; the connection is *loaned*
+/// out for the duration of a transaction or streaming query.
+///
+/// Statements are rendered in the PostgreSQL dialect
+/// ([`DbBackend::Postgres`](crate::DbBackend::Postgres)), which DuckDB understands,
+/// so `get_database_backend()` reports `Postgres` for this connection.
+#[derive(Clone)]
+pub struct DuckdbSharedConnection {
+    pub(crate) conn: Arc<Mutex<State>>,
+    acquire_timeout: Duration,
+    metric_callback: Option<crate::metric::Callback>,
+    pub(crate) record_stmt_in_spans: bool,
+    pub(crate) target: DbTarget,
+}
+
+/// A connection loaned out of a [`DuckdbSharedConnection`] for a transaction or
+/// streaming query. Returned to the shared mutex on [`Drop`].
+///
+/// Nested transactions are issued as `SAVEPOINT`s, the same way as the `rusqlite`
+/// driver; DuckDB reports an error for them if it does not support savepoints.
+pub struct DuckdbInnerConnection {
+    conn: State,
+    loan: Arc<Mutex<State>>,
+    transaction_depth: u32,
+}
+
+#[derive(Debug)]
+pub struct DuckdbExecResult {
+    pub(crate) rows_affected: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct OwnedRow {
+    pub columns: Vec<Arc<str>>,
+    pub values: Vec<Value>,
+}
+
+#[derive(Debug, Default)]
+pub enum State {
+    Idle(DuckdbConnection),
+    Loaned,
+    #[default]
+    Disconnected,
+}
+
+impl OwnedRow {
+    pub fn columns(&self) -> &[Arc<str>] {
+        &self.columns
+    }
+
+    pub fn from_row(columns: Vec<Arc<str>>, row: &Row) -> OwnedRow {
+        let mut values = Vec::new();
+
+        for i in 0..columns.len() {
+            let v: Value = row.get_unwrap(i);
+            values.push(match v {
+                // ENUM values decode like the string they hold
+                Value::Enum(s) => Value::Text(s),
+                v => v,
+            });
+        }
+
+        OwnedRow { columns, values }
+    }
+
+    pub fn try_get<T: FromDuckdb, I: ColIdx>(&self, idx: I) -> Result<T, TryGetError> {
+        let (idx, col, value) = if let Some(idx) = idx.as_usize() {
+            (*idx, None, &self.values[*idx])
+        } else if let Some(name) = idx.as_str() {
+            if let Some(idx) = self.columns.iter().position(|c| c.deref() == name) {
+                (idx, Some(name), &self.values[idx])
+            } else {
+                return Err(TryGetError::Null(format!(
+                    "column `{name}` does not exist in row"
+                )));
+            }
+        } else {
+            unreachable!("ColIdx must be either usize or str")
+        };
+        T::from_duckdb(value)
+            .map_err(|err| match err {
+                FromSqlError::OutOfRange(i) => DuckdbError::IntegralValueOutOfRange(idx, i),
+                FromSqlError::InvalidType => DuckdbError::InvalidColumnType(
+                    idx,
+                    col.map(|c| c.to_owned()).unwrap_or_default(),
+                    value_type(value),
+                ),
+                err => DuckdbError::FromSqlConversionFailure(idx, value_type(value), Box::new(err)),
+            })
+            .map_err(|err| TryGetError::DbErr(query_err(err)))
+    }
+}
+
+/// `Value::data_type` is not implemented for container values
+fn value_type(value: &Value) -> Type {
+    match value {
+        Value::List(_) | Value::Array(_) | Value::Struct(_) | Value::Map(_) | Value::Union(_) => {
+            Type::Any
+        }
+        value => value.data_type(),
+    }
+}
+
+/// A Rust type that can be decoded from a DuckDB result value.
+///
+/// Types with a `duckdb` [`FromSql`] implementation delegate to it; the rest
+/// (`time`, `bigdecimal`, `DateTime<FixedOffset>`) are converted here.
+pub trait FromDuckdb: Sized {
+    /// Decode `value`, which is never a container (list, struct, ...) value.
+    fn from_duckdb(value: &Value) -> Result<Self, FromSqlError>;
+}
+
+impl<T: FromDuckdb> FromDuckdb for Option<T> {
+    fn from_duckdb(value: &Value) -> Result<Self, FromSqlError> {
+        match value {
+            Value::Null => Ok(None),
+            value => T::from_duckdb(value).map(Some),
+        }
+    }
+}
+
+macro_rules! from_duckdb_via_from_sql {
+    ( $($type: ty),* $(,)? ) => {
+        $(
+            impl FromDuckdb for $type {
+                fn from_duckdb(value: &Value) -> Result<Self, FromSqlError> {
+                    match value {
+                        Value::List(_)
+                        | Value::Array(_)
+                        | Value::Struct(_)
+                        | Value::Map(_)
+                        | Value::Union(_)
+                        | Value::Enum(_) => Err(FromSqlError::InvalidType),
+                        value => FromSql::column_result(value.into()),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+from_duckdb_via_from_sql!(
+    bool,
+    i8,
+    i16,
+    i32,
+    i64,
+    u8,
+    u16,
+    u32,
+    u64,
+    f32,
+    f64,
+    String,
+    Vec<u8>
+);
+
+#[cfg(feature = "with-json")]
+from_duckdb_via_from_sql!(serde_json::Value);
+
+#[cfg(feature = "with-chrono")]
+from_duckdb_via_from_sql!(
+    chrono::NaiveDate,
+    chrono::NaiveTime,
+    chrono::NaiveDateTime,
+    chrono::DateTime<chrono::Utc>,
+    chrono::DateTime<chrono::Local>,
+);
+
+#[cfg(feature = "with-chrono")]
+impl FromDuckdb for chrono::DateTime<chrono::FixedOffset> {
+    fn from_duckdb(value: &Value) -> Result<Self, FromSqlError> {
+        chrono::DateTime::<chrono::Utc>::from_duckdb(value).map(Into::into)
+    }
+}
+
+#[cfg(feature = "with-uuid")]
+from_duckdb_via_from_sql!(uuid::Uuid);
+
+#[cfg(feature = "with-rust_decimal")]
+from_duckdb_via_from_sql!(rust_decimal::Decimal);
+
+#[cfg(feature = "with-bigdecimal")]
+impl FromDuckdb for bigdecimal::BigDecimal {
+    fn from_duckdb(value: &Value) -> Result<Self, FromSqlError> {
+        let text = match value {
+            Value::Decimal(d) => d.to_string(),
+            Value::Text(s) => s.to_owned(),
+            Value::Float(f) => f.to_string(),
+            Value::Double(f) => f.to_string(),
+            value => i64::from_duckdb(value)?.to_string(),
+        };
+        text.parse()
+            .map_err(|err| FromSqlError::Other(Box::new(err)))
+    }
+}
+
+/// Microseconds since the Unix epoch of a `DATE` / `TIMESTAMP` value
+#[cfg(feature = "with-time")]
+fn unix_micros(value: &Value) -> Result<i64, FromSqlError> {
+    match value {
+        Value::Timestamp(unit, t) => Ok(unit.to_micros(*t)),
+        Value::Date32(days) => Ok(*days as i64 * 86_400_000_000),
+        _ => Err(FromSqlError::InvalidType),
+    }
+}
+
+#[cfg(feature = "with-time")]
+fn offset_date_time(value: &Value) -> Result<time::OffsetDateTime, FromSqlError> {
+    time::OffsetDateTime::from_unix_timestamp_nanos(unix_micros(value)? as i128 * 1000)
+        .map_err(|err| FromSqlError::Other(Box::new(err)))
+}
+
+#[cfg(feature = "with-time")]
+impl FromDuckdb for time::Date {
+    fn from_duckdb(value: &Value) -> Result<Self, FromSqlError> {
+        offset_date_time(value).map(|v| v.date())
+    }
+}
+
+#[cfg(feature = "with-time")]
+impl FromDuckdb for time::Time {
+    fn from_duckdb(value: &Value) -> Result<Self, FromSqlError> {
+        match value {
+            Value::Time64(unit, t) => {
+                Ok(time::Time::MIDNIGHT + time::Duration::microseconds(unit.to_micros(*t)))
+            }
+            value => offset_date_time(value).map(|v| v.time()),
+        }
+    }
+}
+
+#[cfg(feature = "with-time")]
+impl FromDuckdb for time::PrimitiveDateTime {
+    fn from_duckdb(value: &Value) -> Result<Self, FromSqlError> {
+        offset_date_time(value).map(|v| time::PrimitiveDateTime::new(v.date(), v.time()))
+    }
+}
+
+#[cfg(feature = "with-time")]
+impl FromDuckdb for time::OffsetDateTime {
+    fn from_duckdb(value: &Value) -> Result<Self, FromSqlError> {
+        offset_date_time(value)
+    }
+}
+
+impl std::fmt::Debug for DuckdbSharedConnection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DuckdbSharedConnection {{ conn: {:?} }}", self.conn)
+    }
+}
+
+impl std::fmt::Debug for DuckdbInnerConnection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DuckdbInnerConnection {{ conn: {:?} }}", self.conn)
+    }
+}
+
+impl From<DuckdbConnection> for DuckdbSharedConnection {
+    fn from(conn: DuckdbConnection) -> Self {
+        DuckdbSharedConnection {
+            conn: Arc::new(Mutex::new(State::Idle(conn))),
+            acquire_timeout: DEFAULT_ACQUIRE_TIMEOUT,
+            metric_callback: None,
+            record_stmt_in_spans: true,
+            target: DbTarget::default(),
+        }
+    }
+}
+
+impl From<DuckdbSharedConnection> for DatabaseConnection {
+    fn from(conn: DuckdbSharedConnection) -> Self {
+        DatabaseConnectionType::DuckdbSharedConnection(conn).into()
+    }
+}
+
+impl DuckdbConnector {
+    /// Check if the URI provided corresponds to `duckdb:` for a DuckDB database
+    pub fn accepts(string: &str) -> bool {
+        string.starts_with("duckdb:")
+    }
+
+    /// Open the DuckDB database file given by the connection string.
+    ///
+    /// `duckdb://path/to/file.db` (or `duckdb:path/to/file.db`) opens a file, creating
+    /// it if needed; `duckdb::memory:` opens an in-memory database. The only supported
+    /// parameter is `access_mode` (`automatic`, `read_only` or `read_write`).
+    #[instrument(level = "trace")]
+    pub fn connect(options: ConnectOptions) -> Result<DatabaseConnection, DbErr> {
+        let acquire_timeout = options.acquire_timeout.unwrap_or(DEFAULT_ACQUIRE_TIMEOUT);
+        let record_stmt_in_spans = options.get_record_stmt_in_spans();
+        let after_conn = options.after_connect;
+
+        let raw = options
+            .url
+            .trim_start_matches("duckdb://")
+            .trim_start_matches("duckdb:");
+
+        let (path, query) = match raw.split_once('?') {
+            Some((path, query)) => (path, query),
+            None => (raw, ""),
+        };
+
+        let mut config = Config::default();
+        for kv in query.split('&').filter(|kv| !kv.is_empty()) {
+            let mode = match kv.strip_prefix("access_mode=") {
+                Some("automatic") => ::duckdb::AccessMode::Automatic,
+                Some("read_only") => ::duckdb::AccessMode::ReadOnly,
+                Some("read_write") => ::duckdb::AccessMode::ReadWrite,
+                Some(other) => {
+                    return Err(DbErr::Conn(RuntimeErr::Internal(format!(
+                        "unknown DuckDB access_mode: {other}"
+                    ))));
+                }
+                None => {
+                    return Err(DbErr::Conn(RuntimeErr::Internal(format!(
+                        "unsupported DuckDB connection parameter: {kv}"
+                    ))));
+                }
+            };
+            config = config.access_mode(mode).map_err(conn_err)?;
+        }
+
+        let conn = match path {
+            "" | ":memory:" => DuckdbConnection::open_in_memory_with_flags(config),
+            path => DuckdbConnection::open_with_flags(path, config),
+        }
+        .map_err(conn_err)?;
+
+        let target = DbTarget::new(Some(path).filter(|path| *path != ":memory:"), None);
+        let conn = DuckdbSharedConnection {
+            conn: Arc::new(Mutex::new(State::Idle(conn))),
+            acquire_timeout,
+            metric_callback: None,
+            record_stmt_in_spans,
+            target,
+        };
+        let conn: DatabaseConnection = conn.into();
+
+        if let Some(cb) = after_conn {
+            cb(conn.clone())?;
+        }
+
+        Ok(conn)
+    }
+
+    /// Convert a DuckDB connection to a [DatabaseConnection]
+    pub fn from_duckdb_connection(conn: DuckdbConnection) -> DatabaseConnection {
+        let conn: DuckdbSharedConnection = conn.into();
+        conn.into()
+    }
+}
+
+impl DuckdbSharedConnection {
+    /// Wait for the connection to become idle and lock it
+    pub fn acquire(&self) -> Result<MutexGuard<'_, State>, DbErr> {
+        let deadline = Instant::now() + self.acquire_timeout;
+        loop {
+            match self.conn.try_lock() {
+                Ok(state) => match *state {
+                    State::Idle(_) => return Ok(state),
+                    State::Loaned => (), // borrowed for streaming or transaction
+                    State::Disconnected => {
+                        return Err(DbErr::ConnectionAcquire(ConnAcquireErr::ConnectionClosed));
+                    }
+                },
+                Err(TryLockError::WouldBlock) => (),
+                Err(TryLockError::Poisoned(_)) => {
+                    return Err(DbErr::ConnectionAcquire(ConnAcquireErr::ConnectionClosed));
+                }
+            }
+            if Instant::now() >= deadline {
+                return Err(DbErr::ConnectionAcquire(ConnAcquireErr::Timeout));
+            }
+            std::thread::yield_now();
+        }
+    }
+
+    fn loan(&self) -> Result<DuckdbInnerConnection, DbErr> {
+        let conn = {
+            let mut conn = self.acquire()?;
+            conn.loan()
+        };
+        Ok(DuckdbInnerConnection {
+            conn: State::Idle(conn),
+            loan: self.conn.clone(),
+            transaction_depth: 0,
+        })
+    }
+
+    /// Execute a [Statement] on a DuckDB backend
+    #[instrument(level = "trace", skip(stmt))]
+    pub fn execute(&self, stmt: Statement) -> Result<ExecResult, DbErr> {
+        debug!("{}", stmt);
+
+        let start = Instant::now();
+        let conn = self.acquire()?;
+        let acquire_elapsed = start.elapsed();
+        crate::metric::metric!(self.metric_callback, &stmt, false, Some(acquire_elapsed), {
+            execute(conn.conn(), &stmt)
+        })
+    }
+
+    /// Execute an unprepared SQL statement on a DuckDB backend
+    #[instrument(level = "trace", skip(sql))]
+    pub fn execute_unprepared(&self, sql: &str) -> Result<ExecResult, DbErr> {
+        debug!("{}", sql);
+
+        let conn = self.acquire()?;
+        execute_unprepared(conn.conn(), sql)
+    }
+
+    /// Get one result from a SQL query. Returns [Option::None] if no match was found
+    #[instrument(level = "trace", skip(stmt))]
+    pub fn query_one(&self, stmt: Statement) -> Result<Option<QueryResult>, DbErr> {
+        debug!("{}", stmt);
+
+        let start = Instant::now();
+        let conn = self.acquire()?;
+        let acquire_elapsed = start.elapsed();
+        crate::metric::metric!(self.metric_callback, &stmt, false, Some(acquire_elapsed), {
+            query(conn.conn(), &stmt, Some(1)).map(|rows| rows.into_iter().next())
+        })
+    }
+
+    /// Get the results of a query returning them as a Vec<[QueryResult]>
+    #[instrument(level = "trace", skip(stmt))]
+    pub fn query_all(&self, stmt: Statement) -> Result<Vec<QueryResult>, DbErr> {
+        debug!("{}", stmt);
+
+        let start = Instant::now();
+        let conn = self.acquire()?;
+        let acquire_elapsed = start.elapsed();
+        crate::metric::metric!(self.metric_callback, &stmt, false, Some(acquire_elapsed), {
+            query(conn.conn(), &stmt, None)
+        })
+    }
+
+    /// Run a query and collect the result set as Arrow record batches, straight from
+    /// DuckDB's columnar output without going through [`QueryResult`].
+    ///
+    /// The batches can be turned into models with
+    /// [`ActiveModelTrait::from_arrow`](crate::ActiveModelTrait::from_arrow).
+    #[cfg(feature = "with-arrow")]
+    #[instrument(level = "trace", skip(stmt))]
+    pub fn query_arrow(
+        &self,
+        stmt: Statement,
+    ) -> Result<Vec<sea_orm_arrow::arrow::array::RecordBatch>, DbErr> {
+        debug!("{}", stmt);
+
+        let start = Instant::now();
+        let conn = self.acquire()?;
+        let acquire_elapsed = start.elapsed();
+        crate::metric::metric!(self.metric_callback, &stmt, false, Some(acquire_elapsed), {
+            query_arrow(conn.conn(), &stmt)
+        })
+    }
+
+    /// Stream the results of executing a SQL query
+    #[instrument(level = "trace", skip(stmt))]
+    #[cfg(feature = "stream")]
+    pub fn stream(&self, stmt: Statement) -> Result<QueryStream, DbErr> {
+        debug!("{}", stmt);
+
+        Ok(QueryStream::build(
+            stmt,
+            InnerConnection::Duckdb(self.loan()?),
+            self.metric_callback.clone(),
+        ))
+    }
+
+    /// Bundle a set of SQL statements that execute together.
+    ///
+    /// DuckDB transactions are always serializable, so `isolation_level` is ignored;
+    /// `access_mode` is ignored as well.
+    #[instrument(level = "trace")]
+    pub fn begin(
+        &self,
+        isolation_level: Option<IsolationLevel>,
+        access_mode: Option<AccessMode>,
+    ) -> Result<DatabaseTransaction, DbErr> {
+        let conn = self.loan()?;
+        DatabaseTransaction::begin(
+            Arc::new(Mutex::new(InnerConnection::Duckdb(conn))),
+            crate::DbBackend::Postgres,
+            self.metric_callback.clone(),
+            self.record_stmt_in_spans,
+            self.target.clone(),
+            TransactionOptions {
+                isolation_level,
+                access_mode,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Create a DuckDB transaction
+    #[instrument(level = "trace", skip(callback))]
+    pub fn transaction<F, T, E>(
+        &self,
+        callback: F,
+        isolation_level: Option<IsolationLevel>,
+        access_mode: Option<AccessMode>,
+    ) -> Result<T, TransactionError<E>>
+    where
+        F: for<'b> FnOnce(&'b DatabaseTransaction) -> Result<T, E>,
+        E: std::fmt::Display + std::fmt::Debug,
+    {
+        self.begin(isolation_level, access_mode)
+            .map_err(|e| TransactionError::Connection(e))?
+            .run(callback)
+    }
+
+    pub(crate) fn set_metric_callback<F>(&mut self, callback: F)
+    where
+        F: Fn(&crate::metric::Info<'_>) + 'static,
+    {
+        self.metric_callback = Some(Arc::new(callback));
+    }
+
+    /// Checks if a connection to the database is still valid.
+    pub fn ping(&self) -> Result<(), DbErr> {
+        let conn = self.acquire()?;
+        conn.conn().execute_batch("SELECT 1").map_err(conn_err)
+    }
+
+    /// Explicitly close the DuckDB connection.
+    /// See [`Self::close_by_ref`] for usage with references.
+    pub fn close(self) -> Result<(), DbErr> {
+        self.close_by_ref()
+    }
+
+    /// Explicitly close the DuckDB connection
+    pub fn close_by_ref(&self) -> Result<(), DbErr> {
+        let mut conn = self.acquire()?;
+        *conn = State::Disconnected;
+        Ok(())
+    }
+}
+
+impl DuckdbInnerConnection {
+    /// Interrupt the running statement once `timeout` has passed, unless the
+    /// returned sender is dropped first.
+    pub(crate) fn interrupt_after(&self, timeout: Duration) -> mpsc::Sender<()> {
+        let handle = self.conn.conn().interrupt_handle();
+        let (sender, receiver) = mpsc::channel::<()>();
+        std::thread::spawn(move || {
+            if let Err(mpsc::RecvTimeoutError::Timeout) = receiver.recv_timeout(timeout) {
+                handle.interrupt();
+            }
+        });
+        sender
+    }
+
+    #[instrument(level = "trace", skip(metric_callback, stmt))]
+    pub fn execute(
+        &self,
+        stmt: Statement,
+        metric_callback: &Option<crate::metric::Callback>,
+    ) -> Result<ExecResult, DbErr> {
+        debug!("{}", stmt);
+
+        crate::metric::metric!(metric_callback, &stmt, true, None, {
+            execute(self.conn.conn(), &stmt)
+        })
+    }
+
+    #[instrument(level = "trace", skip(sql))]
+    pub(crate) fn execute_unprepared(&self, sql: &str) -> Result<ExecResult, DbErr> {
+        debug!("{}", sql);
+
+        execute_unprepared(self.conn.conn(), sql)
+    }
+
+    #[instrument(level = "trace", skip(metric_callback, stmt))]
+    pub fn query_one(
+        &self,
+        stmt: Statement,
+        metric_callback: &Option<crate::metric::Callback>,
+    ) -> Result<Option<QueryResult>, DbErr> {
+        debug!("{}", stmt);
+
+        crate::metric::metric!(metric_callback, &stmt, true, None, {
+            query(self.conn.conn(), &stmt, Some(1)).map(|rows| rows.into_iter().next())
+        })
+    }
+
+    #[instrument(level = "trace", skip(metric_callback, stmt))]
+    pub fn query_all(
+        &self,
+        stmt: Statement,
+        metric_callback: &Option<crate::metric::Callback>,
+    ) -> Result<Vec<QueryResult>, DbErr> {
+        debug!("{}", stmt);
+
+        crate::metric::metric!(metric_callback, &stmt, true, None, {
+            query(self.conn.conn(), &stmt, None)
+        })
+    }
+
+    #[instrument(level = "trace", skip(stmt))]
+    pub(crate) fn stream(&self, stmt: &Statement) -> Result<Vec<QueryResult>, DbErr> {
+        debug!("{}", stmt);
+
+        query(self.conn.conn(), stmt, None)
+    }
+
+    #[instrument(level = "trace")]
+    pub(crate) fn begin(&mut self) -> Result<(), DbErr> {
+        if self.transaction_depth == 0 {
+            self.execute_unprepared("BEGIN")?;
+        } else {
+            self.execute_unprepared(&format!("SAVEPOINT sp{}", self.transaction_depth))?;
+        }
+        self.transaction_depth += 1;
+        Ok(())
+    }
+
+    #[instrument(level = "trace")]
+    pub(crate) fn commit(&mut self) -> Result<(), DbErr> {
+        if self.transaction_depth == 1 {
+            self.execute_unprepared("COMMIT")?;
+        } else {
+            self.execute_unprepared(&format!(
+                "RELEASE SAVEPOINT sp{}",
+                self.transaction_depth - 1
+            ))?;
+        }
+        self.transaction_depth -= 1;
+        Ok(())
+    }
+
+    #[instrument(level = "trace")]
+    pub(crate) fn rollback(&mut self) -> Result<(), DbErr> {
+        if self.transaction_depth == 1 {
+            self.execute_unprepared("ROLLBACK")?;
+        } else {
+            self.execute_unprepared(&format!("ROLLBACK TO sp{}", self.transaction_depth - 1))?;
+        }
+        self.transaction_depth -= 1;
+        Ok(())
+    }
+
+    #[instrument(level = "trace")]
+    pub(crate) fn start_rollback(&mut self) -> Result<(), DbErr> {
+        if self.transaction_depth > 0 {
+            self.rollback()?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for DuckdbInnerConnection {
+    fn drop(&mut self) {
+        let mut loan = self.loan.lock().unwrap();
+        loan.return_(self.conn.loan());
+    }
+}
+
+impl State {
+    fn conn(&self) -> &DuckdbConnection {
+        match self {
+            State::Idle(conn) => conn,
+            _ => panic!("No connection"),
+        }
+    }
+
+    fn loan(&mut self) -> DuckdbConnection {
+        let mut conn = State::Loaned;
+        std::mem::swap(&mut conn, self);
+        match conn {
+            State::Idle(conn) => conn,
+            _ => panic!("No connection"),
+        }
+    }
+
+    fn return_(&mut self, conn: DuckdbConnection) {
+        *self = State::Idle(conn);
+    }
+}
+
+impl From<OwnedRow> for QueryResult {
+    fn from(row: OwnedRow) -> QueryResult {
+        QueryResult {
+            row: QueryResultRow::Duckdb(row),
+        }
+    }
+}
+
+impl From<DuckdbExecResult> for ExecResult {
+    fn from(result: DuckdbExecResult) -> ExecResult {
+        ExecResult {
+            result: ExecResultHolder::Duckdb(result),
+        }
+    }
+}
+
+fn execute(conn: &DuckdbConnection, stmt: &Statement) -> Result<ExecResult, DbErr> {
+    let values = sql_values(stmt)?;
+    let rows_affected = match rewrite_identity_columns(&stmt.sql) {
+        Some((sequences, sql)) => {
+            for sequence in sequences {
+                conn.execute_batch(&sequence).map_err(exec_err)?;
+            }
+            conn.execute(&sql, params_from_iter(values))
+        }
+        None => conn
+            .prepare_cached(&stmt.sql)
+            .and_then(|mut sql| sql.execute(params_from_iter(values))),
+    }
+    .map_err(exec_err)?;
+
+    Ok(DuckdbExecResult {
+        rows_affected: rows_affected as u64,
+    }
+    .into())
+}
+
+fn execute_unprepared(conn: &DuckdbConnection, sql: &str) -> Result<ExecResult, DbErr> {
+    conn.execute_batch(sql).map_err(exec_err)?;
+    Ok(DuckdbExecResult { rows_affected: 0 }.into())
+}
+
+fn query(
+    conn: &DuckdbConnection,
+    stmt: &Statement,
+    limit: Option<usize>,
+) -> Result<Vec<QueryResult>, DbErr> {
+    let values = sql_values(stmt)?;
+    let mut sql = conn.prepare_cached(&stmt.sql).map_err(query_err)?;
+    let mut rows = sql.query(params_from_iter(values)).map_err(query_err)?;
+    let columns: Vec<Arc<str>> = match rows.as_ref() {
+        Some(sql) => sql.column_names().into_iter().map(Arc::from).collect(),
+        None => Vec::new(),
+    };
+
+    let mut out = Vec::new();
+    while let Some(row) = rows.next().map_err(query_err)? {
+        out.push(OwnedRow::from_row(columns.clone(), row).into());
+        if limit.is_some_and(|limit| out.len() >= limit) {
+            break;
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(feature = "with-arrow")]
+fn query_arrow(
+    conn: &DuckdbConnection,
+    stmt: &Statement,
+) -> Result<Vec<sea_orm_arrow::arrow::array::RecordBatch>, DbErr> {
+    let values = sql_values(stmt)?;
+    let mut sql = conn.prepare_cached(&stmt.sql).map_err(query_err)?;
+    let batches = sql
+        .query_arrow(params_from_iter(values))
+        .map_err(query_err)?;
+    Ok(batches.collect())
+}
+
+#[cfg(feature = "with-arrow")]
+impl crate::metric::MetricRows for Vec<sea_orm_arrow::arrow::array::RecordBatch> {
+    fn metric_rows(&self) -> u64 {
+        self.iter().map(|batch| batch.num_rows() as u64).sum()
+    }
+}
+
+const IDENTITY: &str = " GENERATED BY DEFAULT AS IDENTITY";
+
+/// DuckDB has no identity columns, so `CREATE TABLE` statements rendered by the
+/// PostgreSQL backend for auto-increment primary keys are rewritten to draw their
+/// default from a sequence named `<table>_<column>_seq`, the way PostgreSQL names
+/// the sequence behind an identity column.
+///
+/// Returns the `CREATE SEQUENCE` statements to run first and the rewritten
+/// `CREATE TABLE`, or `None` if the statement needs no rewriting.
+fn rewrite_identity_columns(sql: &str) -> Option<(Vec<String>, String)> {
+    let rest = sql.strip_prefix("CREATE TABLE ")?;
+    if !rest.contains(IDENTITY) {
+        return None;
+    }
+    let rest = rest.strip_prefix("IF NOT EXISTS ").unwrap_or(rest);
+    let table = &rest[..rest.find(" (")?];
+    let (schema, table) = match table.rfind("\".\"") {
+        Some(i) => (&table[..i + 2], &table[i + 2..]),
+        None => ("", table),
+    };
+    let table = table.trim_matches('"');
+
+    let mut sequences = Vec::new();
+    let mut rewritten = String::with_capacity(sql.len());
+    let mut last = 0;
+    for (pos, _) in sql.match_indices(IDENTITY) {
+        let before = &sql[..pos];
+        let end = before.rfind('"')?;
+        let start = before[..end].rfind('"')?;
+        let column = &before[start + 1..end];
+        let sequence = format!("{schema}\"{table}_{column}_seq\"");
+
+        sequences.push(format!("CREATE SEQUENCE IF NOT EXISTS {sequence}"));
+        rewritten.push_str(&sql[last..pos]);
+        rewritten.push_str(&format!(
+            " DEFAULT nextval('{}')",
+            sequence.replace('\'', "''")
+        ));
+        last = pos + IDENTITY.len();
+    }
+    rewritten.push_str(&sql[last..]);
+
+    Some((sequences, rewritten))
+}
+
+pub(crate) fn sql_values(stmt: &Statement) -> Result<Vec<Value>, DbErr> {
+    match &stmt.values {
+        Some(values) => values.iter().map(to_duckdb_value).collect(),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Convert a [`sea_query::Value`] into a DuckDB parameter.
+///
+/// Temporal values are bound as strings in the same formats the `duckdb` crate
+/// uses for its own `chrono` support, and cast by DuckDB to the parameter type.
+fn to_duckdb_value(value: &sea_query::Value) -> Result<Value, DbErr> {
+    use sea_query::Value as V;
+
+    fn opt<T>(v: &Option<T>, f: impl FnOnce(&T) -> Value) -> Value {
+        v.as_ref().map(f).unwrap_or(Value::Null)
+    }
+
+    Ok(match value {
+        V::Bool(v) => opt(v, |v| Value::Boolean(*v)),
+        V::TinyInt(v) => opt(v, |v| Value::TinyInt(*v)),
+        V::SmallInt(v) => opt(v, |v| Value::SmallInt(*v)),
+        V::Int(v) => opt(v, |v| Value::Int(*v)),
+        V::BigInt(v) => opt(v, |v| Value::BigInt(*v)),
+        V::TinyUnsigned(v) => opt(v, |v| Value::UTinyInt(*v)),
+        V::SmallUnsigned(v) => opt(v, |v| Value::USmallInt(*v)),
+        V::Unsigned(v) => opt(v, |v| Value::UInt(*v)),
+        V::BigUnsigned(v) => opt(v, |v| Value::UBigInt(*v)),
+        V::Float(v) => opt(v, |v| Value::Float(*v)),
+        V::Double(v) => opt(v, |v| Value::Double(*v)),
+        V::String(v) => opt(v, |v| Value::Text(v.clone())),
+        V::Char(v) => opt(v, |v| Value::Text(v.to_string())),
+        V::Bytes(v) => opt(v, |v| Value::Blob(v.clone())),
+        V::Enum(sea_query::OptionEnum::Some(v)) => Value::Text(v.value.to_string()),
+        V::Enum(sea_query::OptionEnum::None(_)) => Value::Null,
+        #[cfg(feature = "with-json")]
+        V::Json(v) => opt(v, |v| Value::Text(v.to_string())),
+        #[cfg(feature = "with-chrono")]
+        V::ChronoDate(v) => opt(v, |v| Value::Text(chrono_fmt::date(v))),
+        #[cfg(feature = "with-chrono")]
+        V::ChronoTime(v) => opt(v, |v| Value::Text(chrono_fmt::time(v))),
+        #[cfg(feature = "with-chrono")]
+        V::ChronoDateTime(v) => opt(v, |v| Value::Text(chrono_fmt::date_time(v))),
+        #[cfg(feature = "with-chrono")]
+        V::ChronoDateTimeUtc(v) => opt(v, |v| Value::Text(chrono_fmt::date_time_utc(v))),
+        #[cfg(feature = "with-chrono")]
+        V::ChronoDateTimeLocal(v) => opt(v, |v| Value::Text(chrono_fmt::date_time_utc(v))),
+        #[cfg(feature = "with-chrono")]
+        V::ChronoDateTimeWithTimeZone(v) => opt(v, |v| Value::Text(chrono_fmt::date_time_utc(v))),
+        #[cfg(feature = "with-time")]
+        V::TimeDate(v) => opt(v, |v| Value::Text(time_fmt::date(v))),
+        #[cfg(feature = "with-time")]
+        V::TimeTime(v) => opt(v, |v| Value::Text(time_fmt::time(v))),
+        #[cfg(feature = "with-time")]
+        V::TimeDateTime(v) => opt(v, |v| Value::Text(time_fmt::date_time(v))),
+        #[cfg(feature = "with-time")]
+        V::TimeDateTimeWithTimeZone(v) => opt(v, |v| Value::Text(time_fmt::date_time_utc(v))),
+        #[cfg(feature = "with-uuid")]
+        V::Uuid(v) => opt(v, |v| Value::Text(v.hyphenated().to_string())),
+        #[cfg(feature = "with-rust_decimal")]
+        V::Decimal(v) => opt(v, |v| Value::Text(v.to_string())),
+        #[cfg(feature = "with-bigdecimal")]
+        V::BigDecimal(v) => opt(v, |v| Value::Text(v.to_string())),
+        #[cfg(feature = "with-ipnetwork")]
+        V::IpNetwork(v) => opt(v, |v| Value::Text(v.to_string())),
+        #[cfg(feature = "with-mac_address")]
+        V::MacAddress(v) => opt(v, |v| Value::Text(v.to_string())),
+        #[allow(unreachable_patterns)]
+        other => {
+            return Err(DbErr::Type(format!(
+                "{other:?} cannot be bound as a DuckDB parameter"
+            )));
+        }
+    })
+}
+
+#[cfg(feature = "with-chrono")]
+mod chrono_fmt {
+    use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike};
+
+    pub(super) fn date(v: &NaiveDate) -> String {
+        format!("{:04}-{:02}-{:02}", v.year(), v.month(), v.day())
+    }
+
+    pub(super) fn time(v: &NaiveTime) -> String {
+        format!(
+            "{:02}:{:02}:{:02}.{:06}",
+            v.hour(),
+            v.minute(),
+            v.second(),
+            v.nanosecond() / 1_000
+        )
+    }
+
+    pub(super) fn date_time(v: &NaiveDateTime) -> String {
+        format!("{} {}", date(&v.date()), time(&v.time()))
+    }
+
+    pub(super) fn date_time_utc<Tz: TimeZone>(v: &DateTime<Tz>) -> String {
+        format!("{}+00:00", date_time(&v.naive_utc()))
+    }
+}
+
+#[cfg(feature = "with-time")]
+mod time_fmt {
+    use time::{Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+    pub(super) fn date(v: &Date) -> String {
+        format!("{:04}-{:02}-{:02}", v.year(), v.month() as u8, v.day())
+    }
+
+    pub(super) fn time(v: &Time) -> String {
+        format!(
+            "{:02}:{:02}:{:02}.{:06}",
+            v.hour(),
+            v.minute(),
+            v.second(),
+            v.microsecond()
+        )
+    }
+
+    pub(super) fn date_time(v: &PrimitiveDateTime) -> String {
+        format!("{} {}", date(&v.date()), time(&v.time()))
+    }
+
+    pub(super) fn date_time_utc(v: &OffsetDateTime) -> String {
+        let v = v.to_offset(UtcOffset::UTC);
+        format!("{} {}+00:00", date(&v.date()), time(&v.time()))
+    }
+}
+
+#[cfg(feature = "proxy")]
+pub(crate) fn from_duckdb_row_to_proxy_row(row: &OwnedRow) -> crate::ProxyRow {
+    use sea_query::Value as V;
+    crate::ProxyRow {
+        values: row
+            .columns
+            .iter()
+            .zip(&row.values)
+            .map(|(c, v)| {
+                (
+                    c.to_string(),
+                    match v {
+                        Value::Null => V::String(None),
+                        Value::Boolean(v) => V::Bool(Some(*v)),
+                        Value::TinyInt(v) => V::TinyInt(Some(*v)),
+                        Value::SmallInt(v) => V::SmallInt(Some(*v)),
+                        Value::Int(v) => V::Int(Some(*v)),
+                        Value::BigInt(v) => V::BigInt(Some(*v)),
+                        Value::UTinyInt(v) => V::TinyUnsigned(Some(*v)),
+                        Value::USmallInt(v) => V::SmallUnsigned(Some(*v)),
+                        Value::UInt(v) => V::Unsigned(Some(*v)),
+                        Value::UBigInt(v) => V::BigUnsigned(Some(*v)),
+                        Value::Float(v) => V::Float(Some(*v)),
+                        Value::Double(v) => V::Double(Some(*v)),
+                        Value::HugeInt(v) => V::String(Some(v.to_string())),
+                        Value::UHugeInt(v) => V::String(Some(v.to_string())),
+                        Value::Decimal(v) => V::String(Some(v.to_string())),
+                        Value::Text(v) | Value::Enum(v) => V::String(Some(v.clone())),
+                        Value::Blob(v) => V::Bytes(Some(v.clone())),
+                        #[cfg(feature = "with-chrono")]
+                        Value::Date32(_) => V::ChronoDate(chrono::NaiveDate::from_duckdb(v).ok()),
+                        #[cfg(feature = "with-chrono")]
+                        Value::Time64(..) => V::ChronoTime(chrono::NaiveTime::from_duckdb(v).ok()),
+                        #[cfg(feature = "with-chrono")]
+                        Value::Timestamp(..) => {
+                            V::ChronoDateTime(chrono::NaiveDateTime::from_duckdb(v).ok())
+                        }
+                        #[cfg(feature = "with-json")]
+                        Value::List(_)
+                        | Value::Array(_)
+                        | Value::Struct(_)
+                        | Value::Map(_)
+                        | Value::Union(_) => V::Json(Some(Box::new(duckdb_value_to_json(v)))),
+                        v => unreachable!("Unknown DuckDB value {v:?}"),
+                    },
+                )
+            })
+            .collect(),
+    }
+}
+
+/// Convert a DuckDB result value into JSON, recursing into container values
+#[cfg(feature = "with-json")]
+pub(crate) fn duckdb_value_to_json(value: &Value) -> serde_json::Value {
+    use serde_json::json;
+
+    match value {
+        Value::Null => json!(null),
+        Value::Boolean(v) => json!(v),
+        Value::TinyInt(v) => json!(v),
+        Value::SmallInt(v) => json!(v),
+        Value::Int(v) => json!(v),
+        Value::BigInt(v) => json!(v),
+        Value::UTinyInt(v) => json!(v),
+        Value::USmallInt(v) => json!(v),
+        Value::UInt(v) => json!(v),
+        Value::UBigInt(v) => json!(v),
+        Value::HugeInt(v) => i64::try_from(*v)
+            .map(|v| json!(v))
+            .unwrap_or_else(|_| json!(v.to_string())),
+        Value::UHugeInt(v) => u64::try_from(*v)
+            .map(|v| json!(v))
+            .unwrap_or_else(|_| json!(v.to_string())),
+        Value::Float(v) => json!(v),
+        Value::Double(v) => json!(v),
+        Value::Decimal(v) => json!(v.to_string()),
+        Value::Text(v) | Value::Enum(v) => json!(v),
+        Value::Blob(v) | Value::Geometry(v) => json!(v),
+        #[cfg(feature = "with-chrono")]
+        Value::Date32(_) => json!(chrono::NaiveDate::from_duckdb(value).ok()),
+        #[cfg(feature = "with-chrono")]
+        Value::Time64(..) => json!(chrono::NaiveTime::from_duckdb(value).ok()),
+        #[cfg(feature = "with-chrono")]
+        Value::Timestamp(..) => json!(chrono::NaiveDateTime::from_duckdb(value).ok()),
+        #[cfg(not(feature = "with-chrono"))]
+        Value::Date32(v) => json!(v),
+        #[cfg(not(feature = "with-chrono"))]
+        Value::Time64(unit, v) | Value::Timestamp(unit, v) => json!(unit.to_micros(*v)),
+        Value::Interval {
+            months,
+            days,
+            nanos,
+        } => json!({ "months": months, "days": days, "nanos": nanos }),
+        Value::List(v) | Value::Array(v) => v.iter().map(duckdb_value_to_json).collect(),
+        Value::Struct(v) => v
+            .iter()
+            .map(|(k, v)| (k.to_owned(), duckdb_value_to_json(v)))
+            .collect::<serde_json::Map<_, _>>()
+            .into(),
+        Value::Map(v) => v
+            .iter()
+            .map(|(k, v)| json!([duckdb_value_to_json(k), duckdb_value_to_json(v)]))
+            .collect(),
+        Value::Union(v) => duckdb_value_to_json(v),
+        #[allow(unreachable_patterns)]
+        _ => json!(null),
+    }
+}
+
+fn conn_err(err: DuckdbError) -> DbErr {
+    DbErr::Conn(RuntimeErr::Duckdb(err.into()))
+}
+
+fn exec_err(err: DuckdbError) -> DbErr {
+    DbErr::Exec(RuntimeErr::Duckdb(err.into()))
+}
+
+fn query_err(err: DuckdbError) -> DbErr {
+    DbErr::Query(RuntimeErr::Duckdb(err.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rewrite_identity_columns;
+
+    #[test]
+    fn test_rewrite_identity_columns() {
+        assert_eq!(
+            rewrite_identity_columns(
+                r#"CREATE TABLE "cake" ( "id" integer GENERATED BY DEFAULT AS IDENTITY NOT NULL PRIMARY KEY, "name" varchar NOT NULL )"#
+            ),
+            Some((
+                vec![r#"CREATE SEQUENCE IF NOT EXISTS "cake_id_seq""#.to_owned()],
+                r#"CREATE TABLE "cake" ( "id" integer DEFAULT nextval('"cake_id_seq"') NOT NULL PRIMARY KEY, "name" varchar NOT NULL )"#.to_owned()
+            ))
+        );
+        assert_eq!(
+            rewrite_identity_columns(
+                r#"CREATE TABLE IF NOT EXISTS "public"."fruit" ( "id" bigint GENERATED BY DEFAULT AS IDENTITY NOT NULL PRIMARY KEY )"#
+            ),
+            Some((
+                vec![r#"CREATE SEQUENCE IF NOT EXISTS "public"."fruit_id_seq""#.to_owned()],
+                r#"CREATE TABLE IF NOT EXISTS "public"."fruit" ( "id" bigint DEFAULT nextval('"public"."fruit_id_seq"') NOT NULL PRIMARY KEY )"#.to_owned()
+            ))
+        );
+        assert_eq!(
+            rewrite_identity_columns(r#"CREATE TABLE "cake" ( "id" integer NOT NULL )"#),
+            None
+        );
+        assert_eq!(rewrite_identity_columns(r#"SELECT 1"#), None);
+    }
+}
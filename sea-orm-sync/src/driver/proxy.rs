@@ -146,14 +146,17 @@ impl crate::DatabaseTransaction {
     ) -> Result<crate::DatabaseTransaction, DbErr> {
         use std::sync::Mutex;
         let backend = inner.get_database_backend();
+        let inner = match inner.proxy.transaction_connection() {
+            Some(proxy) => Arc::new(crate::ProxyDatabaseConnection::new(backend, proxy)),
+            None => inner,
+        };
         Self::begin(
             Arc::new(Mutex::new(crate::InnerConnection::Proxy(inner))),
             backend,
             metric_callback,
             true,
-            None,
-            None,
-            None,
+            Default::default(),
+            Default::default(),
         )
     }
 }
@@ -1,4 +1,28 @@
-use crate::{ConnAcquireErr, ConnectOptions, DbErr, RuntimeErr};
+use crate::{
+    ConnAcquireErr, ConnectOptions, DbBackend, DbErr, RuntimeErr, database::tracing_spans::DbTarget,
+};
+use sqlx::pool::PoolConnection;
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+/// Callback stored for a `before_acquire` hook on [`ConnectOptions`].
+///
+/// This mirrors the signature accepted by SQLx's
+/// [`PoolOptions::before_acquire`][sqlx::pool::PoolOptions::before_acquire] for the given
+/// database backend `DB`. Note the fully-qualified [`futures_util::future::BoxFuture`]: the
+/// crate-local `BoxFuture` alias collapses to `T` under the `sync` feature, which would not
+/// match SQLx's signature.
+pub(crate) type BeforeAcquireFn<DB> = Arc<
+    dyn for<'c> Fn(
+        &'c mut <DB as sqlx::Database>::Connection,
+        sqlx::pool::PoolConnectionMetadata,
+    ) -> futures_util::future::BoxFuture<'c, Result<bool, sqlx::Error>>,
+>;
 
 /// Converts an [sqlx::error] execution error to a [DbErr]
 pub fn sqlx_error_to_exec_err(err: sqlx::Error) -> DbErr {
@@ -35,6 +59,52 @@ pub fn sqlx_conn_acquire_err(sqlx_err: sqlx::Error) -> DbErr {
     }
 }
 
+/// Counts the tasks waiting for a connection from a pool, which SQLx does not expose
+#[derive(Debug, Default)]
+pub(crate) struct PoolWaiters(AtomicU32);
+
+impl PoolWaiters {
+    /// Acquire a connection from `pool`, counting the caller as a waiter until it gets one.
+    /// Also returns how long the wait took.
+    #[allow(unused_variables)]
+    pub(crate) fn acquire<DB>(
+        &self,
+        pool: &sqlx::Pool<DB>,
+        backend: DbBackend,
+        target: &DbTarget,
+    ) -> Result<(PoolConnection<DB>, Duration), DbErr>
+    where
+        DB: sqlx::Database,
+    {
+        struct Waiting<'a>(&'a AtomicU32);
+
+        impl Drop for Waiting<'_> {
+            fn drop(&mut self) {
+                self.0.fetch_sub(1, Ordering::Relaxed);
+            }
+        }
+
+        self.0.fetch_add(1, Ordering::Relaxed);
+        let _waiting = Waiting(&self.0);
+        let start = Instant::now();
+        let acquire = { pool.acquire().map_err(sqlx_conn_acquire_err) };
+        #[cfg(feature = "tracing-spans")]
+        let conn = {
+            let span = crate::database::tracing_spans::acquire_span(backend, target);
+            let conn = tracing::Instrument::instrument(acquire, span.clone());
+            crate::database::tracing_spans::record_query_result(&span, &conn);
+            conn
+        };
+        #[cfg(not(feature = "tracing-spans"))]
+        let conn = acquire;
+        Ok((conn?, start.elapsed()))
+    }
+
+    pub(crate) fn count(&self) -> u32 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
 impl ConnectOptions {
     /// Convert [ConnectOptions] into [sqlx::pool::PoolOptions]
     pub fn sqlx_pool_options<DB>(self) -> sqlx::pool::PoolOptions<DB>
@@ -63,4 +133,104 @@ impl ConnectOptions {
         opt = opt.test_before_acquire(self.test_before_acquire);
         opt
     }
+
+    /// Install the composed `before_acquire` hook onto a [`sqlx::pool::PoolOptions`].
+    ///
+    /// SQLx exposes a single `before_acquire` slot whose setter *replaces* rather than
+    /// composes, and offers no getter to read it back. To let the idle-ping shorthand
+    /// ([`ConnectOptions::test_before_acquire_if_idle_for`]) coexist with a user-provided
+    /// per-backend callback ([`ConnectOptions::map_sqlx_postgres_before_acquire`] and
+    /// friends), this composes both into one closure: the idle-ping runs first, then the
+    /// user callback. When a ping threshold is set, `test_before_acquire` is forced off so
+    /// the connection is pinged only past the threshold rather than on every acquire.
+    ///
+    /// Returns `opt` untouched when neither option is configured, so callers that opt into
+    /// nothing get byte-for-byte the previous behavior.
+    pub(crate) fn apply_before_acquire<DB>(
+        mut opt: sqlx::pool::PoolOptions<DB>,
+        ping_after_idle: Option<Duration>,
+        user_cb: Option<BeforeAcquireFn<DB>>,
+    ) -> sqlx::pool::PoolOptions<DB>
+    where
+        DB: sqlx::Database,
+    {
+        use sqlx::Connection;
+
+        if ping_after_idle.is_none() && user_cb.is_none() {
+            return opt;
+        }
+        if ping_after_idle.is_some() {
+            opt = opt.test_before_acquire(false);
+        }
+        opt.before_acquire(move |conn, meta| {
+            let user_cb = user_cb.clone();
+            ({
+                if let Some(threshold) = ping_after_idle {
+                    // `idle_for` is `Copy`; read it before `meta` is moved into the user callback.
+                    // `>=` matches the "idle for at least `threshold`" contract documented on
+                    // `ConnectOptions::test_before_acquire_if_idle_for`.
+                    if meta.idle_for >= threshold {
+                        conn.ping()?;
+                    }
+                }
+                match user_cb {
+                    Some(user_cb) => user_cb(conn, meta),
+                    None => Ok(true),
+                }
+            })
+        })
+    }
+}
+
+#[cfg(all(test, feature = "sqlx-postgres"))]
+mod tests {
+    use crate::ConnectOptions;
+    use sqlx::Connection;
+    use std::time::Duration;
+
+    #[test]
+    fn idle_shorthand_disables_test_before_acquire() {
+        let mut opt = ConnectOptions::new("postgres://localhost/db");
+        assert!(opt.get_test_before_acquire());
+        assert_eq!(opt.get_test_before_acquire_if_idle_for(), None);
+
+        opt.test_before_acquire_if_idle_for(Duration::from_secs(30));
+        assert!(!opt.get_test_before_acquire());
+        assert_eq!(
+            opt.get_test_before_acquire_if_idle_for(),
+            Some(Duration::from_secs(30))
+        );
+    }
+
+    #[test]
+    fn compose_shorthand_and_user_callback() {
+        let mut opt = ConnectOptions::new("postgres://localhost/db");
+        opt.test_before_acquire_if_idle_for(Duration::from_secs(30))
+            .map_sqlx_postgres_before_acquire(|conn, _meta| {
+                ({
+                    conn.ping()?;
+                    Ok(true)
+                })
+            });
+
+        // Composing both into SQLx's single `before_acquire` slot type-checks and returns a
+        // usable `PoolOptions`. Behavioral ping timing requires a live pool, covered elsewhere.
+        let pool_opts = ConnectOptions::apply_before_acquire::<sqlx::Postgres>(
+            sqlx::pool::PoolOptions::new(),
+            opt.get_test_before_acquire_if_idle_for(),
+            opt.pg_before_acquire_fn.clone(),
+        );
+        let _ = pool_opts;
+    }
+
+    #[test]
+    fn apply_before_acquire_noop_when_unset() {
+        // With neither option set, the helper must return the options untouched.
+        let opts = ConnectOptions::apply_before_acquire::<sqlx::Postgres>(
+            sqlx::pool::PoolOptions::new(),
+            None,
+            None,
+        );
+        let _ = opts;
+    }
 }
@@ -1,12 +1,11 @@
 use log::LevelFilter;
 use sea_query::Values;
-use std::sync::Mutex;
-use std::{fmt::Write, sync::Arc};
+use std::{fmt::Write, sync::Arc, time::Duration};
 
 use sqlx::{
-    Connection, Executor, PgPool, Postgres,
+    Connection, Executor, PgConnection, PgPool, Postgres,
     pool::PoolConnection,
-    postgres::{PgConnectOptions, PgQueryResult, PgRow},
+    postgres::{PgConnectOptions, PgListener, PgQueryResult, PgRow},
 };
 
 use sea_query_sqlx::SqlxValues;
@@ -14,24 +13,46 @@ use tracing::instrument;
 
 use crate::{
     AccessMode, ConnectOptions, DatabaseConnection, DatabaseConnectionType, DatabaseTransaction,
-    IsolationLevel, Statement, TransactionError, debug_print, error::*, executor::*,
+    IsolationLevel, Statement, TransactionError, TransactionOptions, debug_print, error::*,
+    executor::*,
 };
 
 use super::sqlx_common::*;
+use crate::database::tracing_spans::DbTarget;
 
 #[cfg(feature = "stream")]
-use crate::QueryStream;
+use crate::{
+    DbBackend, QueryStream,
+    database::{timeout::timeout_millis, transaction::quote_identifier},
+};
 
 /// Defines the [sqlx::postgres] connector
 #[derive(Debug)]
 pub struct SqlxPostgresConnector;
 
+/// A notification received on a channel listened to with
+/// [`DatabaseConnection::listen`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgNotification {
+    /// The channel the notification was sent on
+    pub channel: String,
+    /// The payload, empty if none was given
+    pub payload: String,
+    /// Process ID of the backend that sent the notification
+    pub process_id: u32,
+}
+
+/// Notifications on the channels passed to [`DatabaseConnection::listen`]
+pub type PgNotificationStream = Pin<Box<dyn Stream<Item = Result<PgNotification, DbErr>>>>;
+
 /// Defines a sqlx PostgreSQL pool
 #[derive(Clone)]
 pub struct SqlxPostgresPoolConnection {
     pub(crate) pool: PgPool,
     metric_callback: Option<crate::metric::Callback>,
+    waiters: Arc<PoolWaiters>,
     pub(crate) record_stmt_in_spans: bool,
+    pub(crate) target: DbTarget,
 }
 
 impl std::fmt::Debug for SqlxPostgresPoolConnection {
@@ -43,8 +64,10 @@ impl std::fmt::Debug for SqlxPostgresPoolConnection {
 impl From<PgPool> for SqlxPostgresPoolConnection {
     fn from(pool: PgPool) -> Self {
         SqlxPostgresPoolConnection {
+            target: db_target(&pool),
             pool,
             metric_callback: None,
+            waiters: Default::default(),
             record_stmt_in_spans: true,
         }
     }
@@ -117,7 +140,14 @@ impl SqlxPostgresConnector {
         let lazy = options.connect_lazy;
         let after_connect = options.after_connect.clone();
         let pg_pool_opts_fn = options.pg_pool_opts_fn.clone();
+        let pg_before_acquire = options.pg_before_acquire_fn.clone();
+        let ping_after_idle = options.test_before_acquire_if_idle_for;
         let mut pool_options = options.sqlx_pool_options();
+        pool_options = crate::ConnectOptions::apply_before_acquire::<sqlx::Postgres>(
+            pool_options,
+            ping_after_idle,
+            pg_before_acquire,
+        );
 
         if let Some(sql) = set_search_path_sql {
             pool_options = pool_options.after_connect(move |conn, _| {
@@ -138,8 +168,10 @@ impl SqlxPostgresConnector {
 
         let conn: DatabaseConnection =
             DatabaseConnectionType::SqlxPostgresPoolConnection(SqlxPostgresPoolConnection {
+                target: db_target(&pool),
                 pool,
                 metric_callback: None,
+                waiters: Default::default(),
                 record_stmt_in_spans,
             })
             .into();
@@ -156,23 +188,36 @@ impl SqlxPostgresConnector {
     /// Instantiate a sqlx pool connection to a [DatabaseConnection]
     pub fn from_sqlx_postgres_pool(pool: PgPool) -> DatabaseConnection {
         DatabaseConnectionType::SqlxPostgresPoolConnection(SqlxPostgresPoolConnection {
+            target: db_target(&pool),
             pool,
             metric_callback: None,
+            waiters: Default::default(),
             record_stmt_in_spans: true,
         })
         .into()
     }
 }
 
+/// The database `pool` connects to, recorded on spans
+fn db_target(pool: &PgPool) -> DbTarget {
+    let options = pool.connect_options();
+    DbTarget::new(options.get_database(), Some(options.get_host()))
+}
+
 impl SqlxPostgresPoolConnection {
+    fn acquire(&self) -> Result<(PoolConnection<Postgres>, Duration), DbErr> {
+        self.waiters
+            .acquire(&self.pool, crate::DbBackend::Postgres, &self.target)
+    }
+
     /// Execute a [Statement] on a PostgreSQL backend
     #[instrument(level = "trace", skip(stmt))]
     pub fn execute(&self, stmt: Statement) -> Result<ExecResult, DbErr> {
         debug_print!("{}", stmt);
 
         let query = sqlx_query(&stmt);
-        let mut conn = self.pool.acquire().map_err(sqlx_conn_acquire_err)?;
-        crate::metric::metric!(self.metric_callback, &stmt, {
+        let (mut conn, acquire_elapsed) = self.acquire()?;
+        crate::metric::metric!(self.metric_callback, &stmt, false, Some(acquire_elapsed), {
             match query.execute(&mut *conn) {
                 Ok(res) => Ok(res.into()),
                 Err(err) => Err(sqlx_error_to_exec_err(err)),
@@ -185,7 +230,7 @@ impl SqlxPostgresPoolConnection {
     pub fn execute_unprepared(&self, sql: &str) -> Result<ExecResult, DbErr> {
         debug_print!("{}", sql);
 
-        let conn = &mut self.pool.acquire().map_err(sqlx_conn_acquire_err)?;
+        let conn = &mut self.acquire()?.0;
         match conn.execute(sqlx::AssertSqlSafe(sql.to_owned())) {
             Ok(res) => Ok(res.into()),
             Err(err) => Err(sqlx_error_to_exec_err(err)),
@@ -198,8 +243,8 @@ impl SqlxPostgresPoolConnection {
         debug_print!("{}", stmt);
 
         let query = sqlx_query(&stmt);
-        let mut conn = self.pool.acquire().map_err(sqlx_conn_acquire_err)?;
-        crate::metric::metric!(self.metric_callback, &stmt, {
+        let (mut conn, acquire_elapsed) = self.acquire()?;
+        crate::metric::metric!(self.metric_callback, &stmt, false, Some(acquire_elapsed), {
             match query.fetch_one(&mut *conn) {
                 Ok(row) => Ok(Some(row.into())),
                 Err(err) => match err {
@@ -216,8 +261,8 @@ impl SqlxPostgresPoolConnection {
         debug_print!("{}", stmt);
 
         let query = sqlx_query(&stmt);
-        let mut conn = self.pool.acquire().map_err(sqlx_conn_acquire_err)?;
-        crate::metric::metric!(self.metric_callback, &stmt, {
+        let (mut conn, acquire_elapsed) = self.acquire()?;
+        crate::metric::metric!(self.metric_callback, &stmt, false, Some(acquire_elapsed), {
             match query.fetch_all(&mut *conn) {
                 Ok(rows) => Ok(rows.into_iter().map(|r| r.into()).collect()),
                 Err(err) => Err(sqlx_error_to_query_err(err)),
@@ -231,7 +276,59 @@ impl SqlxPostgresPoolConnection {
     pub fn stream(&self, stmt: Statement) -> Result<QueryStream, DbErr> {
         debug_print!("{}", stmt);
 
-        let conn = self.pool.acquire().map_err(sqlx_conn_acquire_err)?;
+        let (conn, _) = self.acquire()?;
+        Ok(QueryStream::from((
+            conn,
+            stmt,
+            self.metric_callback.clone(),
+        )))
+    }
+
+    /// Stream the results of a query run with `schema` as the `search_path`, on a connection
+    /// that is closed afterwards, so the setting does not find its way back into the pool
+    #[instrument(level = "trace", skip(stmt))]
+    #[cfg(feature = "stream")]
+    pub fn stream_in_schema(&self, stmt: Statement, schema: &str) -> Result<QueryStream, DbErr> {
+        debug_print!("{}", stmt);
+
+        let (mut conn, _) = self.acquire()?;
+        conn.close_on_drop();
+        sqlx::query("SELECT set_config('search_path', $1, false)")
+            .bind(quote_identifier(DbBackend::Postgres, schema))
+            .execute(&mut *conn)
+            .map_err(sqlx_error_to_query_err)?;
+        Ok(QueryStream::from((
+            conn,
+            stmt,
+            self.metric_callback.clone(),
+        )))
+    }
+
+    /// Stream the results of a query cancelled once it has run for longer than `timeout`,
+    /// on a connection that is closed afterwards, so the `statement_timeout` (and the
+    /// `search_path`, if there is a `schema`) does not find its way back into the pool
+    #[instrument(level = "trace", skip(stmt))]
+    #[cfg(feature = "stream")]
+    pub fn stream_with_timeout(
+        &self,
+        stmt: Statement,
+        schema: Option<&str>,
+        timeout: Duration,
+    ) -> Result<QueryStream, DbErr> {
+        debug_print!("{}", stmt);
+
+        let (mut conn, _) = self.acquire()?;
+        conn.close_on_drop();
+        sqlx::query("SELECT set_config('statement_timeout', $1, false)")
+            .bind(timeout_millis(timeout).to_string())
+            .execute(&mut *conn)
+            .map_err(sqlx_error_to_query_err)?;
+        if let Some(schema) = schema {
+            sqlx::query("SELECT set_config('search_path', $1, false)")
+                .bind(quote_identifier(DbBackend::Postgres, schema))
+                .execute(&mut *conn)
+                .map_err(sqlx_error_to_query_err)?;
+        }
         Ok(QueryStream::from((
             conn,
             stmt,
@@ -239,6 +336,29 @@ impl SqlxPostgresPoolConnection {
         )))
     }
 
+    /// Run a `COPY ... FROM STDIN` statement, feeding it the chunks of `data`.
+    /// Returns the number of rows copied.
+    #[instrument(level = "trace", skip(data))]
+    pub fn copy_in(
+        &self,
+        sql: &str,
+        data: &mut dyn Iterator<Item = Result<Vec<u8>, DbErr>>,
+    ) -> Result<u64, DbErr> {
+        debug_print!("{}", sql);
+
+        let conn = &mut self.acquire()?.0;
+        copy_in(conn, sql, data)
+    }
+
+    /// Run a `COPY ... TO STDOUT` statement, writing what it outputs to `writer`.
+    #[instrument(level = "trace", skip(writer))]
+    pub fn copy_out(&self, sql: &str, writer: &mut dyn std::io::Write) -> Result<(), DbErr> {
+        debug_print!("{}", sql);
+
+        let conn = &mut self.acquire()?.0;
+        copy_out(conn, sql, writer)
+    }
+
     /// Bundle a set of SQL statements that execute together.
     #[instrument(level = "trace")]
     pub fn begin(
@@ -246,11 +366,12 @@ impl SqlxPostgresPoolConnection {
         isolation_level: Option<IsolationLevel>,
         access_mode: Option<AccessMode>,
     ) -> Result<DatabaseTransaction, DbErr> {
-        let conn = self.pool.acquire().map_err(sqlx_conn_acquire_err)?;
+        let (conn, _) = self.acquire()?;
         DatabaseTransaction::new_postgres(
             conn,
             self.metric_callback.clone(),
             self.record_stmt_in_spans,
+            self.target.clone(),
             isolation_level,
             access_mode,
         )
@@ -268,11 +389,12 @@ impl SqlxPostgresPoolConnection {
         F: for<'b> FnOnce(&'b DatabaseTransaction) -> Result<T, E>,
         E: std::fmt::Display + std::fmt::Debug,
     {
-        let conn = self.pool.acquire().map_err(sqlx_conn_acquire_err)?;
+        let (conn, _) = self.acquire()?;
         let transaction = DatabaseTransaction::new_postgres(
             conn,
             self.metric_callback.clone(),
             self.record_stmt_in_spans,
+            self.target.clone(),
             isolation_level,
             access_mode,
         )
@@ -287,9 +409,38 @@ impl SqlxPostgresPoolConnection {
         self.metric_callback = Some(Arc::new(callback));
     }
 
+    /// Statistics of the connection pool
+    pub fn pool_status(&self) -> crate::metric::PoolStatus {
+        crate::metric::PoolStatus {
+            size: self.pool.size(),
+            idle: self.pool.num_idle() as u32,
+            max_size: self.pool.options().get_max_connections(),
+            waiters: self.waiters.count(),
+        }
+    }
+
+    /// Listen for notifications on `channels`, on a connection of its own taken from the
+    /// pool. The connection is re-established should it be lost, but notifications
+    /// sent in the meantime are missed.
+    #[instrument(level = "trace")]
+    pub fn listen(&self, channels: &[&str]) -> Result<PgNotificationStream, DbErr> {
+        let mut listener = PgListener::connect_with(&self.pool).map_err(sqlx_error_to_conn_err)?;
+        listener
+            .listen_all(channels.iter().copied())
+            .map_err(sqlx_error_to_exec_err)?;
+        Ok(Box::new(listener.into_stream().map(|res| {
+            res.map(|notification| PgNotification {
+                channel: notification.channel().to_owned(),
+                payload: notification.payload().to_owned(),
+                process_id: notification.process_id(),
+            })
+            .map_err(sqlx_error_to_query_err)
+        })))
+    }
+
     /// Checks if a connection to the database is still valid.
     pub fn ping(&self) -> Result<(), DbErr> {
-        let conn = &mut self.pool.acquire().map_err(sqlx_conn_acquire_err)?;
+        let conn = &mut self.acquire()?.0;
         match conn.ping() {
             Ok(_) => Ok(()),
             Err(err) => Err(sqlx_error_to_conn_err(err)),
@@ -312,7 +463,7 @@ impl SqlxPostgresPoolConnection {
 impl From<PgRow> for QueryResult {
     fn from(row: PgRow) -> QueryResult {
         QueryResult {
-            row: QueryResultRow::SqlxPostgres(row),
+            row: QueryResultRow::SqlxPostgres(Arc::new(row)),
         }
     }
 }
@@ -357,6 +508,44 @@ pub(crate) fn set_transaction_config(
     Ok(())
 }
 
+pub(crate) fn copy_in(
+    conn: &mut PgConnection,
+    sql: &str,
+    data: &mut dyn Iterator<Item = Result<Vec<u8>, DbErr>>,
+) -> Result<u64, DbErr> {
+    let mut copy = conn.copy_in_raw(sql).map_err(sqlx_error_to_exec_err)?;
+    for chunk in data {
+        let res = match chunk {
+            Ok(chunk) => copy.send(chunk).map(|_| ()).map_err(sqlx_error_to_exec_err),
+            Err(err) => Err(err),
+        };
+        if let Err(err) = res {
+            // end the `COPY`, so that the connection can be used again; the error that made
+            // us give up is the one worth returning
+            let _ = copy.abort(err.to_string());
+            return Err(err);
+        }
+    }
+    copy.finish().map_err(sqlx_error_to_exec_err)
+}
+
+pub(crate) fn copy_out(
+    conn: &mut PgConnection,
+    sql: &str,
+    writer: &mut dyn std::io::Write,
+) -> Result<(), DbErr> {
+    let mut stream = conn.copy_out_raw(sql).map_err(sqlx_error_to_query_err)?;
+    while let Some(chunk) = stream.next() {
+        let chunk = chunk.map_err(sqlx_error_to_query_err)?;
+        writer
+            .write_all(&chunk)
+            .map_err(|e| DbErr::Query(RuntimeErr::Internal(format!("COPY output: {e}"))))?;
+    }
+    writer
+        .flush()
+        .map_err(|e| DbErr::Query(RuntimeErr::Internal(format!("COPY output: {e}"))))
+}
+
 #[cfg(feature = "stream")]
 impl
     From<(
@@ -385,6 +574,7 @@ impl crate::DatabaseTransaction {
         inner: PoolConnection<sqlx::Postgres>,
         metric_callback: Option<crate::metric::Callback>,
         record_stmt_in_spans: bool,
+        target: DbTarget,
         isolation_level: Option<IsolationLevel>,
         access_mode: Option<AccessMode>,
     ) -> Result<crate::DatabaseTransaction, DbErr> {
@@ -393,9 +583,12 @@ impl crate::DatabaseTransaction {
             crate::DbBackend::Postgres,
             metric_callback,
             record_stmt_in_spans,
-            isolation_level,
-            access_mode,
-            None,
+            target,
+            TransactionOptions {
+                isolation_level,
+                access_mode,
+                ..Default::default()
+            },
         )
     }
 }
@@ -572,7 +765,8 @@ pub(crate) fn from_sqlx_postgres_row_to_proxy_row(row: &sqlx::postgres::PgRow) -
                         #[cfg(feature = "with-bigdecimal")]
                         "NUMERIC" => Value::BigDecimal(
                             row.try_get::<Option<bigdecimal::BigDecimal>, _>(c.ordinal())
-                                .expect("Failed to get numeric"),
+                                .expect("Failed to get numeric")
+                                .map(Box::new),
                         ),
                         #[cfg(all(
                             feature = "with-rust_decimal",
@@ -590,7 +784,7 @@ pub(crate) fn from_sqlx_postgres_row_to_proxy_row(row: &sqlx::postgres::PgRow) -
                                 .map(|vals| {
                                     Box::new(
                                         vals.into_iter()
-                                            .map(|val| Value::BigDecimal(Some(val)))
+                                            .map(|val| Value::BigDecimal(Some(Box::new(val))))
                                             .collect(),
                                     )
                                 }),
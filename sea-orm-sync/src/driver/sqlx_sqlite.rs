@@ -1,7 +1,7 @@
 use log::LevelFilter;
 use sea_query::Values;
-use std::sync::Arc;
 use std::sync::Mutex;
+use std::{sync::Arc, time::Duration};
 
 use sqlx::{
     Connection, Executor, Sqlite, SqlitePool,
@@ -14,11 +14,15 @@ use tracing::{instrument, warn};
 
 use crate::{
     AccessMode, ConnectOptions, DatabaseConnection, DatabaseConnectionType, DatabaseTransaction,
-    IsolationLevel, SqliteTransactionMode, Statement, TransactionError, debug_print, error::*,
-    executor::*, sqlx_error_to_exec_err,
+    IsolationLevel, SqliteTransactionMode, Statement, TransactionError, TransactionOptions,
+    debug_print, error::*, executor::*, sqlx_error_to_exec_err,
 };
 
 use super::sqlx_common::*;
+use crate::database::{
+    timeout::{Deadline, PROGRESS_HANDLER_OPS},
+    tracing_spans::DbTarget,
+};
 
 #[cfg(feature = "stream")]
 use crate::QueryStream;
@@ -32,7 +36,9 @@ pub struct SqlxSqliteConnector;
 pub struct SqlxSqlitePoolConnection {
     pub(crate) pool: SqlitePool,
     metric_callback: Option<crate::metric::Callback>,
+    waiters: Arc<PoolWaiters>,
     pub(crate) record_stmt_in_spans: bool,
+    pub(crate) target: DbTarget,
 }
 
 impl std::fmt::Debug for SqlxSqlitePoolConnection {
@@ -44,8 +50,10 @@ impl std::fmt::Debug for SqlxSqlitePoolConnection {
 impl From<SqlitePool> for SqlxSqlitePoolConnection {
     fn from(pool: SqlitePool) -> Self {
         SqlxSqlitePoolConnection {
+            target: db_target(&pool),
             pool,
             metric_callback: None,
+            waiters: Default::default(),
             record_stmt_in_spans: true,
         }
     }
@@ -99,7 +107,14 @@ impl SqlxSqliteConnector {
         let after_conn = options.after_connect.clone();
         let connect_lazy = options.connect_lazy;
         let sqlite_pool_opts_fn = options.sqlite_pool_opts_fn.clone();
+        let sqlite_before_acquire = options.sqlite_before_acquire_fn.clone();
+        let ping_after_idle = options.test_before_acquire_if_idle_for;
         let mut pool_options = options.sqlx_pool_options();
+        pool_options = crate::ConnectOptions::apply_before_acquire::<sqlx::Sqlite>(
+            pool_options,
+            ping_after_idle,
+            sqlite_before_acquire,
+        );
 
         if let Some(f) = &sqlite_pool_opts_fn {
             pool_options = f(pool_options);
@@ -114,8 +129,10 @@ impl SqlxSqliteConnector {
         };
 
         let pool = SqlxSqlitePoolConnection {
+            target: db_target(&pool),
             pool,
             metric_callback: None,
+            waiters: Default::default(),
             record_stmt_in_spans,
         };
 
@@ -140,23 +157,36 @@ impl SqlxSqliteConnector {
     /// Instantiate a sqlx pool connection to a [DatabaseConnection]
     pub fn from_sqlx_sqlite_pool(pool: SqlitePool) -> DatabaseConnection {
         DatabaseConnectionType::SqlxSqlitePoolConnection(SqlxSqlitePoolConnection {
+            target: db_target(&pool),
             pool,
             metric_callback: None,
+            waiters: Default::default(),
             record_stmt_in_spans: true,
         })
         .into()
     }
 }
 
+/// The database `pool` connects to, recorded on spans
+fn db_target(pool: &SqlitePool) -> DbTarget {
+    let options = pool.connect_options();
+    DbTarget::new(options.get_filename().to_str(), None)
+}
+
 impl SqlxSqlitePoolConnection {
+    fn acquire(&self) -> Result<(PoolConnection<Sqlite>, Duration), DbErr> {
+        self.waiters
+            .acquire(&self.pool, crate::DbBackend::Sqlite, &self.target)
+    }
+
     /// Execute a [Statement] on a SQLite backend
     #[instrument(level = "trace", skip(stmt))]
     pub fn execute(&self, stmt: Statement) -> Result<ExecResult, DbErr> {
         debug_print!("{}", stmt);
 
         let query = sqlx_query(&stmt);
-        let mut conn = self.pool.acquire().map_err(sqlx_conn_acquire_err)?;
-        crate::metric::metric!(self.metric_callback, &stmt, {
+        let (mut conn, acquire_elapsed) = self.acquire()?;
+        crate::metric::metric!(self.metric_callback, &stmt, false, Some(acquire_elapsed), {
             match query.execute(&mut *conn) {
                 Ok(res) => Ok(res.into()),
                 Err(err) => Err(sqlx_error_to_exec_err(err)),
@@ -169,7 +199,7 @@ impl SqlxSqlitePoolConnection {
     pub fn execute_unprepared(&self, sql: &str) -> Result<ExecResult, DbErr> {
         debug_print!("{}", sql);
 
-        let conn = &mut self.pool.acquire().map_err(sqlx_conn_acquire_err)?;
+        let conn = &mut self.acquire()?.0;
         match conn.execute(sqlx::AssertSqlSafe(sql.to_owned())) {
             Ok(res) => Ok(res.into()),
             Err(err) => Err(sqlx_error_to_exec_err(err)),
@@ -182,8 +212,8 @@ impl SqlxSqlitePoolConnection {
         debug_print!("{}", stmt);
 
         let query = sqlx_query(&stmt);
-        let mut conn = self.pool.acquire().map_err(sqlx_conn_acquire_err)?;
-        crate::metric::metric!(self.metric_callback, &stmt, {
+        let (mut conn, acquire_elapsed) = self.acquire()?;
+        crate::metric::metric!(self.metric_callback, &stmt, false, Some(acquire_elapsed), {
             match query.fetch_one(&mut *conn) {
                 Ok(row) => Ok(Some(row.into())),
                 Err(err) => match err {
@@ -200,8 +230,8 @@ impl SqlxSqlitePoolConnection {
         debug_print!("{}", stmt);
 
         let query = sqlx_query(&stmt);
-        let mut conn = self.pool.acquire().map_err(sqlx_conn_acquire_err)?;
-        crate::metric::metric!(self.metric_callback, &stmt, {
+        let (mut conn, acquire_elapsed) = self.acquire()?;
+        crate::metric::metric!(self.metric_callback, &stmt, false, Some(acquire_elapsed), {
             match query.fetch_all(&mut *conn) {
                 Ok(rows) => Ok(rows.into_iter().map(|r| r.into()).collect()),
                 Err(err) => Err(sqlx_error_to_query_err(err)),
@@ -215,7 +245,7 @@ impl SqlxSqlitePoolConnection {
     pub fn stream(&self, stmt: Statement) -> Result<QueryStream, DbErr> {
         debug_print!("{}", stmt);
 
-        let conn = self.pool.acquire().map_err(sqlx_conn_acquire_err)?;
+        let (conn, _) = self.acquire()?;
         Ok(QueryStream::from((
             conn,
             stmt,
@@ -223,6 +253,28 @@ impl SqlxSqlitePoolConnection {
         )))
     }
 
+    /// Stream the results of a query interrupted once it has run for longer than
+    /// `timeout`; the deadline is disarmed once the stream is dropped
+    #[instrument(level = "trace", skip(stmt))]
+    #[cfg(feature = "stream")]
+    pub fn stream_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<QueryStream, DbErr> {
+        debug_print!("{}", stmt);
+
+        let (mut conn, _) = self.acquire()?;
+        let (deadline, guard) = Deadline::new(timeout);
+        set_deadline(&mut conn, Some(deadline))?;
+        Ok(QueryStream::build_with_deadline(
+            stmt,
+            crate::InnerConnection::Sqlite(conn),
+            self.metric_callback.clone(),
+            Some(guard),
+        ))
+    }
+
     /// Bundle a set of SQL statements that execute together.
     #[instrument(level = "trace")]
     pub fn begin(
@@ -231,11 +283,12 @@ impl SqlxSqlitePoolConnection {
         access_mode: Option<AccessMode>,
         sqlite_transaction_mode: Option<SqliteTransactionMode>,
     ) -> Result<DatabaseTransaction, DbErr> {
-        let conn = self.pool.acquire().map_err(sqlx_conn_acquire_err)?;
+        let (conn, _) = self.acquire()?;
         DatabaseTransaction::new_sqlite(
             conn,
             self.metric_callback.clone(),
             self.record_stmt_in_spans,
+            self.target.clone(),
             isolation_level,
             access_mode,
             sqlite_transaction_mode,
@@ -254,11 +307,12 @@ impl SqlxSqlitePoolConnection {
         F: for<'b> FnOnce(&'b DatabaseTransaction) -> Result<T, E>,
         E: std::fmt::Display + std::fmt::Debug,
     {
-        let conn = self.pool.acquire().map_err(sqlx_conn_acquire_err)?;
+        let (conn, _) = self.acquire()?;
         let transaction = DatabaseTransaction::new_sqlite(
             conn,
             self.metric_callback.clone(),
             self.record_stmt_in_spans,
+            self.target.clone(),
             isolation_level,
             access_mode,
             None,
@@ -274,9 +328,19 @@ impl SqlxSqlitePoolConnection {
         self.metric_callback = Some(Arc::new(callback));
     }
 
+    /// Statistics of the connection pool
+    pub fn pool_status(&self) -> crate::metric::PoolStatus {
+        crate::metric::PoolStatus {
+            size: self.pool.size(),
+            idle: self.pool.num_idle() as u32,
+            max_size: self.pool.options().get_max_connections(),
+            waiters: self.waiters.count(),
+        }
+    }
+
     /// Checks if a connection to the database is still valid.
     pub fn ping(&self) -> Result<(), DbErr> {
-        let conn = &mut self.pool.acquire().map_err(sqlx_conn_acquire_err)?;
+        let conn = &mut self.acquire()?.0;
         match conn.ping() {
             Ok(_) => Ok(()),
             Err(err) => Err(sqlx_error_to_conn_err(err)),
@@ -299,7 +363,7 @@ impl SqlxSqlitePoolConnection {
 impl From<SqliteRow> for QueryResult {
     fn from(row: SqliteRow) -> QueryResult {
         QueryResult {
-            row: QueryResultRow::SqlxSqlite(row),
+            row: QueryResultRow::SqlxSqlite(Arc::new(row)),
         }
     }
 }
@@ -334,6 +398,22 @@ pub(crate) fn set_transaction_config(
     Ok(())
 }
 
+/// Interrupt the statements that follow once `deadline` has expired; `None`
+/// removes the progress handler again.
+pub(crate) fn set_deadline(
+    conn: &mut PoolConnection<Sqlite>,
+    deadline: Option<Deadline>,
+) -> Result<(), DbErr> {
+    let mut handle = conn.lock_handle().map_err(sqlx_error_to_exec_err)?;
+    match deadline {
+        Some(deadline) => {
+            handle.set_progress_handler(PROGRESS_HANDLER_OPS, move || !deadline.expired())
+        }
+        None => handle.remove_progress_handler(),
+    }
+    Ok(())
+}
+
 #[cfg(feature = "sqlite-use-returning-for-3_35")]
 fn get_version(conn: &SqlxSqlitePoolConnection) -> Result<String, DbErr> {
     let stmt = Statement {
@@ -374,6 +454,7 @@ impl crate::DatabaseTransaction {
         inner: PoolConnection<sqlx::Sqlite>,
         metric_callback: Option<crate::metric::Callback>,
         record_stmt_in_spans: bool,
+        target: DbTarget,
         isolation_level: Option<IsolationLevel>,
         access_mode: Option<AccessMode>,
         sqlite_transaction_mode: Option<SqliteTransactionMode>,
@@ -383,9 +464,12 @@ impl crate::DatabaseTransaction {
             crate::DbBackend::Sqlite,
             metric_callback,
             record_stmt_in_spans,
-            isolation_level,
-            access_mode,
-            sqlite_transaction_mode,
+            target,
+            TransactionOptions {
+                isolation_level,
+                access_mode,
+                sqlite_transaction_mode,
+            },
         )
     }
 }
@@ -422,14 +506,12 @@ pub(crate) fn from_sqlx_sqlite_row_to_proxy_row(row: &sqlx::sqlite::SqliteRow) -
 
                         "TEXT" => Value::String(
                             row.try_get::<Option<String>, _>(c.ordinal())
-                                .expect("Failed to get string")
-                                .map(Box::new),
+                                .expect("Failed to get string"),
                         ),
 
                         "BLOB" => Value::Bytes(
                             row.try_get::<Option<Vec<u8>>, _>(c.ordinal())
-                                .expect("Failed to get bytes")
-                                .map(Box::new),
+                                .expect("Failed to get bytes"),
                         ),
 
                         #[cfg(feature = "with-chrono")]
@@ -438,8 +520,7 @@ pub(crate) fn from_sqlx_sqlite_row_to_proxy_row(row: &sqlx::sqlite::SqliteRow) -
 
                             Value::ChronoDateTimeUtc(
                                 row.try_get::<Option<DateTime<Utc>>, _>(c.ordinal())
-                                    .expect("Failed to get timestamp")
-                                    .map(Box::new),
+                                    .expect("Failed to get timestamp"),
                             )
                         }
                         #[cfg(all(feature = "with-time", not(feature = "with-chrono")))]
@@ -447,8 +528,7 @@ pub(crate) fn from_sqlx_sqlite_row_to_proxy_row(row: &sqlx::sqlite::SqliteRow) -
                             use time::OffsetDateTime;
                             Value::TimeDateTimeWithTimeZone(
                                 row.try_get::<Option<OffsetDateTime>, _>(c.ordinal())
-                                    .expect("Failed to get timestamp")
-                                    .map(Box::new),
+                                    .expect("Failed to get timestamp"),
                             )
                         }
                         #[cfg(feature = "with-chrono")]
@@ -456,8 +536,7 @@ pub(crate) fn from_sqlx_sqlite_row_to_proxy_row(row: &sqlx::sqlite::SqliteRow) -
                             use chrono::NaiveDate;
                             Value::ChronoDate(
                                 row.try_get::<Option<NaiveDate>, _>(c.ordinal())
-                                    .expect("Failed to get date")
-                                    .map(Box::new),
+                                    .expect("Failed to get date"),
                             )
                         }
                         #[cfg(all(feature = "with-time", not(feature = "with-chrono")))]
@@ -465,8 +544,7 @@ pub(crate) fn from_sqlx_sqlite_row_to_proxy_row(row: &sqlx::sqlite::SqliteRow) -
                             use time::Date;
                             Value::TimeDate(
                                 row.try_get::<Option<Date>, _>(c.ordinal())
-                                    .expect("Failed to get date")
-                                    .map(Box::new),
+                                    .expect("Failed to get date"),
                             )
                         }
 
@@ -475,8 +553,7 @@ pub(crate) fn from_sqlx_sqlite_row_to_proxy_row(row: &sqlx::sqlite::SqliteRow) -
                             use chrono::NaiveTime;
                             Value::ChronoTime(
                                 row.try_get::<Option<NaiveTime>, _>(c.ordinal())
-                                    .expect("Failed to get time")
-                                    .map(Box::new),
+                                    .expect("Failed to get time"),
                             )
                         }
                         #[cfg(all(feature = "with-time", not(feature = "with-chrono")))]
@@ -484,8 +561,7 @@ pub(crate) fn from_sqlx_sqlite_row_to_proxy_row(row: &sqlx::sqlite::SqliteRow) -
                             use time::Time;
                             Value::TimeTime(
                                 row.try_get::<Option<Time>, _>(c.ordinal())
-                                    .expect("Failed to get time")
-                                    .map(Box::new),
+                                    .expect("Failed to get time"),
                             )
                         }
 
@@ -532,6 +532,16 @@ pub trait ColumnTrait: IdenStatic + Iterable + FromStr {
         cast_enum_as(val, &self.def(), save_enum_as)
     }
 
+    /// The current time as a value of this column, for
+    /// [`EntityTrait::created_at_column`](crate::EntityTrait::created_at_column) and
+    /// [`EntityTrait::updated_at_column`](crate::EntityTrait::updated_at_column).
+    /// `DeriveEntityModel` implements it for the columns marked with
+    /// `#[sea_orm(created_at)]` or `#[sea_orm(updated_at)]`, whose type must
+    /// implement [`CurrentTimestamp`](crate::value::CurrentTimestamp).
+    fn current_timestamp(&self) -> Option<Value> {
+        None
+    }
+
     /// JSON key used for this column when (de)serializing the model.
     #[cfg(feature = "with-json")]
     fn json_key(&self) -> &'static str {
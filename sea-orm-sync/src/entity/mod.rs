@@ -136,6 +136,7 @@ mod base_entity;
 pub(crate) mod column;
 mod column_def;
 pub mod compound;
+mod factory;
 mod identity;
 mod link;
 mod model;
@@ -160,6 +161,7 @@ pub use base_entity::*;
 pub use column::*;
 pub use column_def::*;
 pub use compound::{BelongsToCardinality, EntityLoaderTrait};
+pub use factory::*;
 pub use identity::*;
 pub use link::*;
 pub use model::*;
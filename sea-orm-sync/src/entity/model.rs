@@ -2,7 +2,7 @@ use crate::{
     ActiveModelBehavior, ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, DeleteResult,
     EntityTrait, IntoActiveModel, Iterable, Linked, PrimaryKeyArity, PrimaryKeyToColumn,
     PrimaryKeyTrait, QueryFilter, QueryResult, Related, Select, SelectModel, SelectorRaw,
-    Statement, TryGetError, find_linked_recursive,
+    Statement, TryGetError, UpdateResult, find_linked_recursive,
 };
 pub use sea_query::Value;
 use sea_query::{ArrayType, ValueTuple};
@@ -81,6 +81,16 @@ pub trait ModelTrait: Clone + Debug {
         self.into_active_model().delete(db)
     }
 
+    /// Bring back a soft deleted model, see [`ActiveModelTrait::restore`]
+    fn restore<'a, A, C>(self, db: &'a C) -> Result<UpdateResult, DbErr>
+    where
+        Self: IntoActiveModel<A>,
+        C: ConnectionTrait,
+        A: ActiveModelTrait<Entity = Self::Entity> + ActiveModelBehavior + 'a,
+    {
+        self.into_active_model().restore(db)
+    }
+
     /// Get the primary key value of the Model
     fn get_primary_key_value(&self) -> ValueTuple {
         let mut cols = <Self::Entity as EntityTrait>::PrimaryKey::iter();
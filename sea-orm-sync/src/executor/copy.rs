@@ -0,0 +1,531 @@
+use crate::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, CopyFormat, CopyIn, DbBackend, EntityName,
+    EntityTrait, IdenStatic, InsertMany, Iterable, ModelTrait, QueryTrait, Select,
+    database::transaction::quote_identifier, error::*,
+};
+use sea_query::{ColumnType, PostgresQueryBuilder, Value};
+
+/// Rows sent to the server in each chunk of `COPY` data
+const COPY_CHUNK_ROWS: usize = 1000;
+
+/// Signature, flags and header extension length opening `COPY` binary data
+const COPY_BINARY_HEADER: &[u8] = b"PGCOPY\n\xff\r\n\0\0\0\0\0\0\0\0\0";
+
+/// Field count marking the end of `COPY` binary data
+const COPY_BINARY_TRAILER: &[u8] = &(-1i16).to_be_bytes();
+
+impl<A> CopyIn<A>
+where
+    A: ActiveModelTrait,
+{
+    /// Execute the copy, returning the number of rows inserted.
+    pub fn exec<C>(self, db: &C) -> Result<u64, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let Self { models, format } = self;
+
+        // neither `COPY` nor a multi-row `INSERT` can fall back to a column's default for
+        // some rows only, so the rows are sent in runs setting the same columns, in order
+        let mut groups: Vec<(Vec<bool>, Vec<A>)> = Vec::new();
+        for am in models {
+            let set: Vec<_> = <A::Entity as EntityTrait>::Column::iter()
+                .map(|col| !am.is_not_set(col))
+                .collect();
+            match groups.last_mut().filter(|(columns, _)| *columns == set) {
+                Some((_, group)) => group.push(am),
+                None => groups.push((set, vec![am])),
+            }
+        }
+
+        if groups.len() <= 1 {
+            let Some((_, models)) = groups.pop() else {
+                return Ok(0);
+            };
+            return copy_in(db, models, format);
+        }
+        let txn = db.begin_atomic()?;
+        let mut rows = 0;
+        for (_, models) in groups {
+            rows += match &txn {
+                Some(txn) => copy_in(txn, models, format)?,
+                None => copy_in(db, models, format)?,
+            };
+        }
+        if let Some(txn) = txn {
+            txn.commit()?;
+        }
+        Ok(rows)
+    }
+}
+
+/// Copy in models that all set the same columns
+fn copy_in<A, C>(db: &C, models: Vec<A>, format: CopyFormat) -> Result<u64, DbErr>
+where
+    A: ActiveModelTrait,
+    C: ConnectionTrait,
+{
+    let columns: Vec<_> = <A::Entity as EntityTrait>::Column::iter()
+        .filter(|col| models.first().is_some_and(|am| !am.is_not_set(*col)))
+        .collect();
+    let backend = db.get_database_backend();
+    let supported = match format {
+        CopyFormat::Csv => true,
+        CopyFormat::Binary => cfg!(feature = "sqlx-postgres"),
+    };
+    let mut models = models.into_iter();
+
+    if backend == DbBackend::Postgres && !columns.is_empty() && supported {
+        let entity = A::Entity::default();
+        let mut table = String::new();
+        if let Some(schema) = entity.schema_name() {
+            table.push_str(&quote_identifier(backend, schema));
+            table.push('.');
+        }
+        table.push_str(&quote_identifier(backend, entity.table_name()));
+        let column_list = columns
+            .iter()
+            .map(|col| quote_identifier(backend, col.as_str()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = match format {
+            CopyFormat::Csv => format!("COPY {table} ({column_list}) FROM STDIN WITH (FORMAT csv)"),
+            CopyFormat::Binary => {
+                format!("COPY {table} ({column_list}) FROM STDIN WITH (FORMAT binary)")
+            }
+        };
+        let column_types: Vec<_> = columns
+            .iter()
+            .map(|col| col.def().get_column_type().clone())
+            .collect();
+
+        let mut started = false;
+        let mut finished = false;
+        let mut data = std::iter::from_fn(|| {
+            if finished {
+                return None;
+            }
+            let mut chunk = Vec::new();
+            if format == CopyFormat::Binary && !started {
+                chunk.extend_from_slice(COPY_BINARY_HEADER);
+            }
+            started = true;
+            for am in models.by_ref().take(COPY_CHUNK_ROWS) {
+                let values = columns.iter().map(|col| am.get(*col).into_value());
+                match format {
+                    CopyFormat::Csv => {
+                        write_csv_row(&mut chunk, values.map(|value| copy_text(&value?)))
+                    }
+                    CopyFormat::Binary => {
+                        if let Err(err) = write_binary_row(&mut chunk, values, &column_types) {
+                            finished = true;
+                            return Some(Err(err));
+                        }
+                    }
+                }
+            }
+            if models.len() == 0 {
+                finished = true;
+                if format == CopyFormat::Binary {
+                    chunk.extend_from_slice(COPY_BINARY_TRAILER);
+                }
+            }
+            (!chunk.is_empty()).then_some(Ok(chunk))
+        });
+        match db.copy_in_raw(&sql, &mut data) {
+            Err(DbErr::BackendNotSupported { .. }) => {}
+            res => return res,
+        }
+    }
+
+    InsertMany::many(models).exec_without_returning(db)
+}
+
+impl<E> Select<E>
+where
+    E: EntityTrait,
+{
+    /// Write the selected rows to `writer` as CSV, with a header line naming the columns.
+    ///
+    /// On Postgres this runs `COPY (...) TO STDOUT`, so the rows are formatted by the
+    /// database and never loaded into memory as Models. Elsewhere the Models are fetched
+    /// with [`Select::all`] and formatted the way `COPY` expects its input. Either way,
+    /// `writer` is written to from code, so it should not block for long.
+    ///
+    /// ```
+    /// # use sea_orm::{error::*, tests_cfg::*, *};
+    /// #
+    /// # #[cfg(feature = "mock")]
+    /// # pub fn main() -> Result<(), DbErr> {
+    /// #
+    /// # let db = MockDatabase::new(DbBackend::MySql)
+    /// #     .append_query_results([[
+    /// #         cake::Model {
+    /// #             id: 1,
+    /// #             name: "Apple Pie".to_owned(),
+    /// #         },
+    /// #         cake::Model {
+    /// #             id: 2,
+    /// #             name: "Cheese, Cake".to_owned(),
+    /// #         },
+    /// #     ]])
+    /// #     .into_connection();
+    /// #
+    /// let mut csv = Vec::new();
+    /// cake::Entity::find().copy_out(&db, &mut csv)?;
+    ///
+    /// assert_eq!(csv, b"id,name\n1,Apple Pie\n2,\"Cheese, Cake\"\n");
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn copy_out<C, W>(self, db: &C, writer: &mut W) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+        W: std::io::Write,
+    {
+        if db.get_database_backend() == DbBackend::Postgres {
+            let sql = format!(
+                "COPY ({}) TO STDOUT WITH (FORMAT csv, HEADER)",
+                self.clone().into_query().to_string(PostgresQueryBuilder)
+            );
+            match db.copy_out_raw(&sql, writer) {
+                Err(DbErr::BackendNotSupported { .. }) => {}
+                res => return res,
+            }
+        }
+
+        let models = self.all(db)?;
+        let mut csv = Vec::new();
+        write_csv_row(
+            &mut csv,
+            E::Column::iter().map(|col| Some(col.as_str().to_owned())),
+        );
+        for model in models {
+            write_csv_row(
+                &mut csv,
+                E::Column::iter().map(|col| copy_text(&model.get(col))),
+            );
+        }
+        writer
+            .write_all(&csv)
+            .and_then(|()| writer.flush())
+            .map_err(|e| DbErr::Query(RuntimeErr::Internal(format!("COPY output: {e}"))))
+    }
+}
+
+/// Append a line of CSV to `buf`, `None` standing for `NULL`
+fn write_csv_row<I>(buf: &mut Vec<u8>, fields: I)
+where
+    I: IntoIterator<Item = Option<String>>,
+{
+    for (i, field) in fields.into_iter().enumerate() {
+        if i > 0 {
+            buf.push(b',');
+        }
+        let Some(field) = field else {
+            continue;
+        };
+        // an unquoted empty field is NULL, and `\.` alone on a line ends the data
+        if field.is_empty() || field == "\\." || field.contains([',', '"', '\n', '\r']) {
+            buf.push(b'"');
+            buf.extend_from_slice(field.replace('"', "\"\"").as_bytes());
+            buf.push(b'"');
+        } else {
+            buf.extend_from_slice(field.as_bytes());
+        }
+    }
+    buf.push(b'\n');
+}
+
+/// The text form of `value` Postgres accepts as `COPY` input, `None` if it is null
+fn copy_text(value: &Value) -> Option<String> {
+    if *value == value.as_null() {
+        return None;
+    }
+    Some(match value {
+        Value::Bool(Some(b)) => if *b { "t" } else { "f" }.to_owned(),
+        Value::String(Some(s)) => s.clone(),
+        Value::Char(Some(c)) => c.to_string(),
+        Value::Enum(sea_query::OptionEnum::Some(v)) => v.value.to_string(),
+        Value::Bytes(Some(bytes)) => {
+            let mut text = String::from("\\x");
+            for b in bytes {
+                text.push_str(&format!("{b:02x}"));
+            }
+            text
+        }
+        #[cfg(feature = "with-json")]
+        Value::Json(Some(json)) => json.to_string(),
+        #[cfg(feature = "postgres-array")]
+        Value::Array(_, Some(values)) => {
+            let elements: Vec<_> = values
+                .iter()
+                .map(|value| match copy_text(value) {
+                    None => "NULL".to_owned(),
+                    Some(text) if matches!(value, Value::Array(..)) => text,
+                    Some(text) => {
+                        format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+                    }
+                })
+                .collect();
+            format!("{{{}}}", elements.join(","))
+        }
+        // other values print as a bare or single-quoted literal
+        value => {
+            let literal = value.to_string();
+            match literal
+                .strip_prefix('\'')
+                .and_then(|s| s.strip_suffix('\''))
+            {
+                Some(text) => text.replace("''", "'"),
+                None => literal,
+            }
+        }
+    })
+}
+
+/// Append a tuple of `COPY` binary data to `buf`, each value in the binary form of the
+/// type of its column
+#[cfg(feature = "sqlx-postgres")]
+fn write_binary_row<I>(
+    buf: &mut Vec<u8>,
+    values: I,
+    column_types: &[ColumnType],
+) -> Result<(), DbErr>
+where
+    I: IntoIterator<Item = Option<Value>>,
+{
+    use sqlx::{Encode, Postgres, postgres::PgArgumentBuffer};
+
+    fn encode<'q, T: Encode<'q, Postgres>>(value: T) -> Result<Option<Vec<u8>>, DbErr> {
+        let mut buf = PgArgumentBuffer::default();
+        let is_null = value
+            .encode(&mut buf)
+            .map_err(|e| DbErr::Type(format!("COPY binary value: {e}")))?;
+        Ok((!is_null.is_null()).then(|| buf.to_vec()))
+    }
+
+    let field_count = i16::try_from(column_types.len())
+        .map_err(|_| DbErr::Type("COPY binary row: too many columns".to_owned()))?;
+    buf.extend_from_slice(&field_count.to_be_bytes());
+    for (value, column_type) in values.into_iter().zip(column_types) {
+        #[cfg(not(feature = "with-json"))]
+        let _ = column_type;
+        let field = match value {
+            None => None,
+            Some(value) if value == value.as_null() => None,
+            Some(value) => match value {
+                Value::Bool(Some(b)) => encode(b)?,
+                Value::TinyInt(Some(i)) => encode(i16::from(i))?,
+                Value::SmallInt(Some(i)) => encode(i)?,
+                Value::Int(Some(i)) => encode(i)?,
+                Value::BigInt(Some(i)) => encode(i)?,
+                Value::TinyUnsigned(Some(i)) => encode(i16::from(i))?,
+                Value::SmallUnsigned(Some(i)) => encode(i32::from(i))?,
+                Value::Unsigned(Some(i)) => encode(i64::from(i))?,
+                Value::BigUnsigned(Some(i)) => encode(i64::try_from(i).map_err(|_| {
+                    DbErr::Type(format!("COPY binary value: {i} overflows BIGINT"))
+                })?)?,
+                Value::Float(Some(f)) => encode(f)?,
+                Value::Double(Some(d)) => encode(d)?,
+                // the binary form of text, enum labels and bytea is the bytes themselves
+                Value::String(Some(s)) => Some(s.into_bytes()),
+                Value::Char(Some(c)) => Some(c.to_string().into_bytes()),
+                Value::Enum(sea_query::OptionEnum::Some(v)) => Some(v.value.as_bytes().to_vec()),
+                Value::Bytes(Some(bytes)) => Some(bytes),
+                // `jsonb` is versioned, `json` is plain text
+                #[cfg(feature = "with-json")]
+                Value::Json(Some(json)) => match column_type {
+                    ColumnType::JsonBinary => Some([&[1], json.to_string().as_bytes()].concat()),
+                    _ => Some(json.to_string().into_bytes()),
+                },
+                #[cfg(feature = "with-chrono")]
+                Value::ChronoDate(Some(v)) => encode(v)?,
+                #[cfg(feature = "with-chrono")]
+                Value::ChronoTime(Some(v)) => encode(v)?,
+                #[cfg(feature = "with-chrono")]
+                Value::ChronoDateTime(Some(v)) => encode(v)?,
+                #[cfg(feature = "with-chrono")]
+                Value::ChronoDateTimeUtc(Some(v)) => encode(v)?,
+                #[cfg(feature = "with-chrono")]
+                Value::ChronoDateTimeLocal(Some(v)) => encode(v)?,
+                #[cfg(feature = "with-chrono")]
+                Value::ChronoDateTimeWithTimeZone(Some(v)) => encode(v)?,
+                #[cfg(feature = "with-time")]
+                Value::TimeDate(Some(v)) => encode(v)?,
+                #[cfg(feature = "with-time")]
+                Value::TimeTime(Some(v)) => encode(v)?,
+                #[cfg(feature = "with-time")]
+                Value::TimeDateTime(Some(v)) => encode(v)?,
+                #[cfg(feature = "with-time")]
+                Value::TimeDateTimeWithTimeZone(Some(v)) => encode(v)?,
+                #[cfg(feature = "with-uuid")]
+                Value::Uuid(Some(v)) => encode(v)?,
+                #[cfg(feature = "with-rust_decimal")]
+                Value::Decimal(Some(v)) => encode(v)?,
+                #[cfg(feature = "with-bigdecimal")]
+                Value::BigDecimal(Some(v)) => encode(*v)?,
+                #[cfg(feature = "with-ipnetwork")]
+                Value::IpNetwork(Some(v)) => encode(v)?,
+                #[cfg(feature = "with-mac_address")]
+                Value::MacAddress(Some(v)) => encode(v)?,
+                value => {
+                    return Err(DbErr::Type(format!(
+                        "COPY binary value: {value:?} is not supported"
+                    )));
+                }
+            },
+        };
+        match field {
+            Some(field) => {
+                let len = i32::try_from(field.len())
+                    .map_err(|_| DbErr::Type("COPY binary value: too long".to_owned()))?;
+                buf.extend_from_slice(&len.to_be_bytes());
+                buf.extend_from_slice(&field);
+            }
+            None => buf.extend_from_slice(&(-1i32).to_be_bytes()),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "sqlx-postgres"))]
+fn write_binary_row<I>(_: &mut Vec<u8>, _: I, _: &[ColumnType]) -> Result<(), DbErr>
+where
+    I: IntoIterator<Item = Option<Value>>,
+{
+    unreachable!("binary COPY is only attempted with the `sqlx-postgres` feature")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        DbBackend, DbErr, EntityTrait, MockDatabase, MockExecResult, NotSet, Set, Transaction,
+        tests_cfg::cake,
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn copy_in_fallback_chunked() -> Result<(), DbErr> {
+        let db = MockDatabase::new(DbBackend::MySql)
+            .append_exec_results([
+                MockExecResult {
+                    last_insert_id: 32767,
+                    rows_affected: 32767,
+                },
+                MockExecResult {
+                    last_insert_id: 40000,
+                    rows_affected: 7233,
+                },
+            ])
+            .into_connection();
+
+        let models = (1..=40000).map(|id| cake::ActiveModel {
+            id: Set(id),
+            name: Set(format!("Cake {id}")),
+        });
+        assert_eq!(cake::Entity::copy_in(models).exec(&db)?, 40000);
+
+        let log = db.into_transaction_log();
+        let statements = log[0].statements();
+        assert_eq!(statements.len(), 4);
+        assert_eq!(statements[0].sql, "BEGIN");
+        assert_eq!(statements[3].sql, "COMMIT");
+
+        Ok(())
+    }
+
+    #[test]
+    fn copy_in_unset_columns() -> Result<(), DbErr> {
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_exec_results([
+                MockExecResult {
+                    last_insert_id: 2,
+                    rows_affected: 2,
+                },
+                MockExecResult {
+                    last_insert_id: 10,
+                    rows_affected: 1,
+                },
+            ])
+            .into_connection();
+
+        let models = [
+            (NotSet, "Apple Pie"),
+            (NotSet, "Choco Pie"),
+            (Set(10), "Cheese Cake"),
+        ]
+        .map(|(id, name)| cake::ActiveModel {
+            id,
+            name: Set(name.to_owned()),
+        });
+        assert_eq!(cake::Entity::copy_in(models).exec(&db)?, 3);
+
+        // the cake without an id is not given a NULL one
+        assert_eq!(
+            db.into_transaction_log(),
+            [Transaction::many([
+                crate::Statement::from_string(DbBackend::Postgres, "BEGIN"),
+                crate::Statement::from_sql_and_values(
+                    DbBackend::Postgres,
+                    r#"INSERT INTO "cake" ("name") VALUES ($1), ($2)"#,
+                    ["Apple Pie".into(), "Choco Pie".into()],
+                ),
+                crate::Statement::from_sql_and_values(
+                    DbBackend::Postgres,
+                    r#"INSERT INTO "cake" ("id", "name") VALUES ($1, $2)"#,
+                    [10.into(), "Cheese Cake".into()],
+                ),
+                crate::Statement::from_string(DbBackend::Postgres, "COMMIT"),
+            ])]
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "sqlx-postgres")]
+    #[test]
+    fn binary_row() -> Result<(), DbErr> {
+        use sea_query::{ColumnType, Value};
+
+        let mut buf = Vec::new();
+        super::write_binary_row(
+            &mut buf,
+            [
+                Some(Value::Int(Some(5))),
+                Some(Value::String(Some("ab".to_owned()))),
+                Some(Value::BigInt(None)),
+                None,
+            ],
+            &[
+                ColumnType::Integer,
+                ColumnType::Text,
+                ColumnType::BigInteger,
+                ColumnType::Text,
+            ],
+        )?;
+        assert_eq!(
+            buf,
+            [
+                &[0, 4][..],
+                &[0, 0, 0, 4, 0, 0, 0, 5],
+                &[0, 0, 0, 2, b'a', b'b'],
+                &[255, 255, 255, 255],
+                &[255, 255, 255, 255],
+            ]
+            .concat()
+        );
+
+        assert!(matches!(
+            super::write_binary_row(
+                &mut Vec::new(),
+                [Some(Value::BigUnsigned(Some(u64::MAX)))],
+                &[ColumnType::BigInteger],
+            ),
+            Err(DbErr::Type(_))
+        ));
+
+        Ok(())
+    }
+}
@@ -1,8 +1,9 @@
 use super::ReturningSelector;
+use crate::database::timeout::TimeoutConnection;
 use crate::{
-    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbBackend, EntityTrait, Insert, InsertMany,
-    IntoActiveModel, Iterable, PrimaryKeyToColumn, PrimaryKeyTrait, SelectModel, TryFromU64,
-    TryInsert, error::*,
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseTransaction, DbBackend, EntityTrait,
+    Insert, InsertMany, IntoActiveModel, Iterable, PrimaryKeyToColumn, PrimaryKeyTrait,
+    SelectModel, TryFromU64, TryInsert, error::*,
 };
 use sea_query::{FromValueTuple, Iden, InsertStatement, Query, ReturningClause, ValueTuple};
 use std::marker::PhantomData;
@@ -98,17 +99,21 @@ where
     A: ActiveModelTrait,
 {
     /// Execute an insert operation
+    ///
+    /// Like [`InsertMany::exec`], more rows than fit in one statement are inserted by several.
     pub fn exec<C>(self, db: &C) -> Result<TryInsertResult<InsertResult<A>>, DbErr>
     where
         C: ConnectionTrait,
     {
-        if self.empty {
+        if self.insert_struct.empty {
             return Ok(TryInsertResult::Empty);
         }
         let res = self.insert_struct.exec(db);
         match res {
-            Ok(res) => Ok(TryInsertResult::Inserted(res)),
-            Err(DbErr::RecordNotInserted) => Ok(TryInsertResult::Conflicted),
+            Ok(InsertManyResult {
+                last_insert_id: Some(last_insert_id),
+            }) => Ok(TryInsertResult::Inserted(InsertResult { last_insert_id })),
+            Ok(_) | Err(DbErr::RecordNotInserted) => Ok(TryInsertResult::Conflicted),
             Err(err) => Err(err),
         }
     }
@@ -119,7 +124,7 @@ where
     where
         C: ConnectionTrait,
     {
-        if self.empty {
+        if self.insert_struct.empty {
             return Ok(TryInsertResult::Empty);
         }
         let res = self.insert_struct.exec_without_returning(db);
@@ -131,6 +136,8 @@ where
     }
 
     /// Execute an insert operation and return the inserted model (use `RETURNING` syntax if supported)
+    ///
+    /// If the rows are inserted by several statements, the model is the first one inserted.
     pub fn exec_with_returning<C>(
         self,
         db: &C,
@@ -139,15 +146,29 @@ where
         <A::Entity as EntityTrait>::Model: IntoActiveModel<A>,
         C: ConnectionTrait,
     {
-        if self.empty {
+        if self.insert_struct.empty {
             return Ok(TryInsertResult::Empty);
         }
-        let res = self.insert_struct.exec_with_returning(db);
-        match res {
-            Ok(res) => Ok(TryInsertResult::Inserted(res)),
-            Err(DbErr::RecordNotInserted) => Ok(TryInsertResult::Conflicted),
-            Err(err) => Err(err),
+        let (inserts, txn) = self.insert_struct.into_statements(db)?;
+        let mut model = None;
+        for insert in inserts {
+            let res = match &txn {
+                Some(txn) => insert.exec_with_returning(txn),
+                None => insert.exec_with_returning(db),
+            };
+            match res {
+                Ok(res) => {
+                    model.get_or_insert(res);
+                }
+                Err(DbErr::RecordNotInserted) => {}
+                Err(err) => return Err(err),
+            }
         }
+        commit(txn)?;
+        Ok(match model {
+            Some(model) => TryInsertResult::Inserted(model),
+            None => TryInsertResult::Conflicted,
+        })
     }
 
     /// Execute an insert operation and return primary keys of inserted models
@@ -159,7 +180,7 @@ where
         <A::Entity as EntityTrait>::Model: IntoActiveModel<A>,
         C: ConnectionTrait,
     {
-        if self.empty {
+        if self.insert_struct.empty {
             return Ok(TryInsertResult::Empty);
         }
 
@@ -180,11 +201,11 @@ where
         <A::Entity as EntityTrait>::Model: IntoActiveModel<A>,
         C: ConnectionTrait,
     {
-        if self.empty {
+        if self.insert_struct.empty {
             return Ok(TryInsertResult::Empty);
         }
 
-        let res = self.insert_struct.exec_with_returning_many(db);
+        let res = self.insert_struct.exec_with_returning(db);
         match res {
             Ok(res) => Ok(TryInsertResult::Inserted(res)),
             Err(DbErr::RecordNotInserted) => Ok(TryInsertResult::Conflicted),
@@ -203,6 +224,7 @@ where
         C: ConnectionTrait,
         A: 'a,
     {
+        let db = &TimeoutConnection::new(db, self.timeout);
         // so that self is dropped before entering await
         let mut query = self.query;
         if db.support_returning() {
@@ -218,6 +240,7 @@ where
         C: ConnectionTrait,
         A: 'a,
     {
+        let db = &TimeoutConnection::new(db, self.timeout);
         Inserter::<A>::new(self.primary_key, self.query).exec_without_returning(db)
     }
 
@@ -235,6 +258,7 @@ where
         C: ConnectionTrait,
         A: 'a,
     {
+        let db = &TimeoutConnection::new(db, self.timeout);
         Inserter::<A>::new(self.primary_key, self.query).exec_with_returning(db)
     }
 
@@ -248,6 +272,7 @@ where
         C: ConnectionTrait,
         A: 'a,
     {
+        let db = &TimeoutConnection::new(db, self.timeout);
         Inserter::<A>::new(self.primary_key, self.query).exec_with_returning_keys(db)
     }
 
@@ -261,6 +286,7 @@ where
         C: ConnectionTrait,
         A: 'a,
     {
+        let db = &TimeoutConnection::new(db, self.timeout);
         Inserter::<A>::new(self.primary_key, self.query).exec_with_returning_many(db)
     }
 }
@@ -270,6 +296,10 @@ where
     A: ActiveModelTrait,
 {
     /// Execute an insert operation
+    ///
+    /// More rows than fit in one statement are inserted by several, in a transaction.
+    /// Those that insert nothing, e.g. when all their rows conflict, are skipped; it fails
+    /// with [`DbErr::RecordNotInserted`] only if none of them inserted a row.
     pub fn exec<C>(self, db: &C) -> Result<InsertManyResult<A>, DbErr>
     where
         C: ConnectionTrait,
@@ -279,13 +309,24 @@ where
                 last_insert_id: None,
             });
         }
-        let res = self.into_one().exec(db);
-        match res {
-            Ok(r) => Ok(InsertManyResult {
-                last_insert_id: Some(r.last_insert_id),
-            }),
-            Err(err) => Err(err),
+        let (inserts, txn) = self.into_statements(db)?;
+        let mut last_insert_id = None;
+        for insert in inserts {
+            let res = match &txn {
+                Some(txn) => insert.exec(txn),
+                None => insert.exec(db),
+            };
+            match res {
+                Ok(res) => last_insert_id = Some(res.last_insert_id),
+                Err(DbErr::RecordNotInserted) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        if last_insert_id.is_none() {
+            return Err(DbErr::RecordNotInserted);
         }
+        commit(txn)?;
+        Ok(InsertManyResult { last_insert_id })
     }
 
     /// Execute an insert operation without returning (don't use `RETURNING` syntax)
@@ -297,7 +338,16 @@ where
         if self.empty {
             return Ok(0);
         }
-        self.into_one().exec_without_returning(db)
+        let (inserts, txn) = self.into_statements(db)?;
+        let mut rows_affected = 0;
+        for insert in inserts {
+            rows_affected += match &txn {
+                Some(txn) => insert.exec_without_returning(txn)?,
+                None => insert.exec_without_returning(db)?,
+            };
+        }
+        commit(txn)?;
+        Ok(rows_affected)
     }
 
     /// Execute an insert operation and return all inserted models
@@ -312,8 +362,16 @@ where
         if self.empty {
             return Ok(Vec::new());
         }
-
-        self.into_one().exec_with_returning_many(db)
+        let (inserts, txn) = self.into_statements(db)?;
+        let mut models = Vec::new();
+        for insert in inserts {
+            models.extend(match &txn {
+                Some(txn) => insert.exec_with_returning_many(txn)?,
+                None => insert.exec_with_returning_many(db)?,
+            });
+        }
+        commit(txn)?;
+        Ok(models)
     }
 
     /// Alias to [`InsertMany::exec_with_returning`].
@@ -329,11 +387,7 @@ where
         <A::Entity as EntityTrait>::Model: IntoActiveModel<A>,
         C: ConnectionTrait,
     {
-        if self.empty {
-            return Ok(Vec::new());
-        }
-
-        self.into_one().exec_with_returning_many(db)
+        self.exec_with_returning(db)
     }
 
     /// Execute an insert operation and return primary keys of inserted models
@@ -348,8 +402,40 @@ where
         if self.empty {
             return Ok(Vec::new());
         }
+        let (inserts, txn) = self.into_statements(db)?;
+        let mut keys = Vec::new();
+        for insert in inserts {
+            keys.extend(match &txn {
+                Some(txn) => insert.exec_with_returning_keys(txn)?,
+                None => insert.exec_with_returning_keys(db)?,
+            });
+        }
+        commit(txn)?;
+        Ok(keys)
+    }
 
-        self.into_one().exec_with_returning_keys(db)
+    /// Split into statements within the bind parameter limit of the backend, beginning a
+    /// transaction to run them in if there is more than one
+    fn into_statements<C>(
+        self,
+        db: &C,
+    ) -> Result<(Vec<Insert<A>>, Option<DatabaseTransaction>), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let columns = self.rows.first().map_or(0, Vec::len);
+        let max_rows = max_rows_per_insert(db.get_database_backend(), columns);
+        if self.rows.len() <= max_rows {
+            return Ok((vec![self.into_one()], None));
+        }
+        Ok((self.into_chunks(max_rows), db.begin_atomic()?))
+    }
+}
+
+fn commit(txn: Option<DatabaseTransaction>) -> Result<(), DbErr> {
+    match txn {
+        Some(txn) => txn.commit(),
+        None => Ok(()),
     }
 }
 
@@ -593,3 +679,13 @@ where
             .select_as(c.into_column().into_returning_expr(db_backend))
     }))
 }
+
+/// The most rows a multi-row `INSERT` of `columns` columns can carry without going over
+/// the limit the backend puts on bind parameters in a statement
+pub(crate) fn max_rows_per_insert(db_backend: DbBackend, columns: usize) -> usize {
+    let max_params = match db_backend {
+        DbBackend::Sqlite => 32766,
+        DbBackend::MySql | DbBackend::Postgres => 65535,
+    };
+    (max_params / columns.max(1)).max(1)
+}
@@ -1,4 +1,5 @@
 mod consolidate;
+mod copy;
 mod cursor;
 mod delete;
 mod execute;
@@ -27,21 +27,24 @@ use sqlx::{TypeInfo, ValueRef};
 /// / [`query_all`](crate::ConnectionTrait::query_all). Read columns from it
 /// with [`QueryResult::try_get`] / [`try_get_by`](Self::try_get_by) or
 /// materialise into a struct via [`FromQueryResult`](crate::FromQueryResult).
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct QueryResult {
     pub(crate) row: QueryResultRow,
 }
 
 #[allow(clippy::enum_variant_names)]
+#[derive(Clone)]
 pub(crate) enum QueryResultRow {
     #[cfg(feature = "sqlx-mysql")]
-    SqlxMySql(sqlx::mysql::MySqlRow),
+    SqlxMySql(Arc<sqlx::mysql::MySqlRow>),
     #[cfg(feature = "sqlx-postgres")]
-    SqlxPostgres(sqlx::postgres::PgRow),
+    SqlxPostgres(Arc<sqlx::postgres::PgRow>),
     #[cfg(feature = "sqlx-sqlite")]
-    SqlxSqlite(sqlx::sqlite::SqliteRow),
+    SqlxSqlite(Arc<sqlx::sqlite::SqliteRow>),
     #[cfg(feature = "rusqlite")]
     Rusqlite(crate::driver::rusqlite::RusqliteRow),
+    #[cfg(feature = "duckdb")]
+    Duckdb(crate::driver::duckdb::DuckdbRow),
     #[cfg(feature = "mock")]
     Mock(crate::MockRow),
     #[cfg(feature = "proxy")]
@@ -211,6 +214,8 @@ impl QueryResult {
             }
             #[cfg(feature = "rusqlite")]
             QueryResultRow::Rusqlite(row) => row.columns().iter().map(|c| c.to_string()).collect(),
+            #[cfg(feature = "duckdb")]
+            QueryResultRow::Duckdb(row) => row.columns().iter().map(|c| c.to_string()).collect(),
             #[cfg(feature = "mock")]
             QueryResultRow::Mock(row) => row
                 .clone()
@@ -291,6 +296,8 @@ impl Debug for QueryResultRow {
             Self::SqlxSqlite(_) => write!(f, "QueryResultRow::SqlxSqlite cannot be inspected"),
             #[cfg(feature = "rusqlite")]
             Self::Rusqlite(row) => write!(f, "{row:?}"),
+            #[cfg(feature = "duckdb")]
+            Self::Duckdb(row) => write!(f, "{row:?}"),
             #[cfg(feature = "mock")]
             Self::Mock(row) => write!(f, "{row:?}"),
             #[cfg(feature = "proxy")]
@@ -444,6 +451,10 @@ macro_rules! try_getable_all {
                     QueryResultRow::Rusqlite(row) => row
                         .try_get::<Option<$type>, _>(idx)
                         .and_then(|opt| opt.ok_or_else(|| err_null_idx_col(idx))),
+                    #[cfg(feature = "duckdb")]
+                    QueryResultRow::Duckdb(row) => row
+                        .try_get::<Option<$type>, _>(idx)
+                        .and_then(|opt| opt.ok_or_else(|| err_null_idx_col(idx))),
                     #[cfg(feature = "mock")]
                     QueryResultRow::Mock(row) => row.try_get(idx).map_err(|e| {
                         debug_print!("{:#?}", e.to_string());
@@ -488,6 +499,10 @@ macro_rules! try_getable_unsigned {
                     QueryResultRow::Rusqlite(row) => row
                         .try_get::<Option<$type>, _>(idx)
                         .and_then(|opt| opt.ok_or_else(|| err_null_idx_col(idx))),
+                    #[cfg(feature = "duckdb")]
+                    QueryResultRow::Duckdb(row) => row
+                        .try_get::<Option<$type>, _>(idx)
+                        .and_then(|opt| opt.ok_or_else(|| err_null_idx_col(idx))),
                     #[cfg(feature = "mock")]
                     QueryResultRow::Mock(row) => row.try_get(idx).map_err(|e| {
                         debug_print!("{:#?}", e.to_string());
@@ -535,6 +550,10 @@ macro_rules! try_getable_mysql {
                         stringify!($type)
                     ))
                     .into()),
+                    #[cfg(feature = "duckdb")]
+                    QueryResultRow::Duckdb(row) => row
+                        .try_get::<Option<$type>, _>(idx)
+                        .and_then(|opt| opt.ok_or_else(|| err_null_idx_col(idx))),
                     #[cfg(feature = "mock")]
                     QueryResultRow::Mock(row) => row.try_get(idx).map_err(|e| {
                         debug_print!("{:#?}", e.to_string());
@@ -583,6 +602,10 @@ macro_rules! try_getable_postgres {
                         stringify!($type)
                     ))
                     .into()),
+                    #[cfg(feature = "duckdb")]
+                    QueryResultRow::Duckdb(_) => {
+                        Err(type_err(format!("{} unsupported by duckdb", stringify!($type))).into())
+                    }
                     #[cfg(feature = "mock")]
                     QueryResultRow::Mock(row) => row.try_get(idx).map_err(|e| {
                         debug_print!("{:#?}", e.to_string());
@@ -631,6 +654,10 @@ macro_rules! try_getable_date_time {
                         .try_get::<Option<$type>, _>(idx)
                         .and_then(|opt| opt.ok_or_else(|| err_null_idx_col(idx)))
                         .map(|v| v.into()),
+                    #[cfg(feature = "duckdb")]
+                    QueryResultRow::Duckdb(row) => row
+                        .try_get::<Option<$type>, _>(idx)
+                        .and_then(|opt| opt.ok_or_else(|| err_null_idx_col(idx))),
                     #[cfg(feature = "mock")]
                     QueryResultRow::Mock(row) => row.try_get(idx).map_err(|e| {
                         debug_print!("{:#?}", e.to_string());
@@ -744,6 +771,10 @@ impl TryGetable for Decimal {
                     None => Err(err_null_idx_col(idx)),
                 }
             }
+            #[cfg(feature = "duckdb")]
+            QueryResultRow::Duckdb(row) => row
+                .try_get::<Option<Decimal>, _>(idx)
+                .and_then(|opt| opt.ok_or_else(|| err_null_idx_col(idx))),
             #[cfg(feature = "mock")]
             #[allow(unused_variables)]
             QueryResultRow::Mock(row) => row.try_get(idx).map_err(|e| {
@@ -812,6 +843,10 @@ impl TryGetable for BigDecimal {
                     None => Err(err_null_idx_col(idx)),
                 }
             }
+            #[cfg(feature = "duckdb")]
+            QueryResultRow::Duckdb(row) => row
+                .try_get::<Option<BigDecimal>, _>(idx)
+                .and_then(|opt| opt.ok_or_else(|| err_null_idx_col(idx))),
             #[cfg(feature = "mock")]
             #[allow(unused_variables)]
             QueryResultRow::Mock(row) => row.try_get(idx).map_err(|e| {
@@ -883,6 +918,10 @@ macro_rules! try_getable_uuid {
                     QueryResultRow::Rusqlite(row) => row
                         .try_get::<Option<uuid::Uuid>, _>(idx)
                         .and_then(|opt| opt.ok_or_else(|| err_null_idx_col(idx))),
+                    #[cfg(feature = "duckdb")]
+                    QueryResultRow::Duckdb(row) => row
+                        .try_get::<Option<uuid::Uuid>, _>(idx)
+                        .and_then(|opt| opt.ok_or_else(|| err_null_idx_col(idx))),
                     #[cfg(feature = "mock")]
                     #[allow(unused_variables)]
                     QueryResultRow::Mock(row) => row.try_get::<uuid::Uuid, _>(idx).map_err(|e| {
@@ -967,6 +1006,10 @@ impl TryGetable for u32 {
             QueryResultRow::Rusqlite(row) => row
                 .try_get::<Option<u32>, _>(idx)
                 .and_then(|opt| opt.ok_or_else(|| err_null_idx_col(idx))),
+            #[cfg(feature = "duckdb")]
+            QueryResultRow::Duckdb(row) => row
+                .try_get::<Option<u32>, _>(idx)
+                .and_then(|opt| opt.ok_or_else(|| err_null_idx_col(idx))),
             #[cfg(feature = "mock")]
             #[allow(unused_variables)]
             QueryResultRow::Mock(row) => row.try_get(idx).map_err(|e| {
@@ -1018,6 +1061,10 @@ impl TryGetable for String {
             QueryResultRow::Rusqlite(row) => row
                 .try_get::<Option<String>, _>(idx)
                 .and_then(|opt| opt.ok_or_else(|| err_null_idx_col(idx))),
+            #[cfg(feature = "duckdb")]
+            QueryResultRow::Duckdb(row) => row
+                .try_get::<Option<String>, _>(idx)
+                .and_then(|opt| opt.ok_or_else(|| err_null_idx_col(idx))),
             #[cfg(feature = "mock")]
             QueryResultRow::Mock(row) => row.try_get(idx).map_err(|e| {
                 debug_print!("{:#?}", e.to_string());
@@ -1073,6 +1120,12 @@ mod postgres_array {
                             stringify!($type)
                         ))
                         .into()),
+                        #[cfg(feature = "duckdb")]
+                        QueryResultRow::Duckdb(_) => Err(type_err(format!(
+                            "{} unsupported by duckdb",
+                            stringify!($type)
+                        ))
+                        .into()),
                         #[cfg(feature = "mock")]
                         #[allow(unused_variables)]
                         QueryResultRow::Mock(row) => row.try_get(idx).map_err(|e| {
@@ -1178,6 +1231,12 @@ mod postgres_array {
                             stringify!($type)
                         ))
                         .into()),
+                        #[cfg(feature = "duckdb")]
+                        QueryResultRow::Duckdb(_) => Err(type_err(format!(
+                            "{} unsupported by duckdb",
+                            stringify!($type)
+                        ))
+                        .into()),
                         #[cfg(feature = "mock")]
                         QueryResultRow::Mock(row) => {
                             row.try_get::<Vec<uuid::Uuid>, _>(idx).map_err(|e| {
@@ -1244,6 +1303,10 @@ mod postgres_array {
                 QueryResultRow::Rusqlite(_) => {
                     Err(type_err(format!("{} unsupported by rusqlite", stringify!($type))).into())
                 }
+                #[cfg(feature = "duckdb")]
+                QueryResultRow::Duckdb(_) => {
+                    Err(type_err(format!("{} unsupported by duckdb", stringify!($type))).into())
+                }
                 #[cfg(feature = "mock")]
                 #[allow(unused_variables)]
                 QueryResultRow::Mock(row) => row.try_get(idx).map_err(|e| {
@@ -1283,6 +1346,8 @@ impl TryGetable for pgvector::Vector {
             }
             #[cfg(feature = "rusqlite")]
             QueryResultRow::Rusqlite(_) => Err(type_err("Vector unsupported by rusqlite").into()),
+            #[cfg(feature = "duckdb")]
+            QueryResultRow::Duckdb(_) => Err(type_err("Vector unsupported by duckdb").into()),
             #[cfg(feature = "mock")]
             QueryResultRow::Mock(row) => row.try_get::<pgvector::Vector, _>(idx).map_err(|e| {
                 debug_print!("{:#?}", e.to_string());
@@ -1512,6 +1577,13 @@ where
                 .and_then(|json| {
                     serde_json::from_value(json).map_err(|e| crate::error::json_err(e).into())
                 }),
+            #[cfg(feature = "duckdb")]
+            QueryResultRow::Duckdb(row) => row
+                .try_get::<Option<serde_json::Value>, _>(idx)?
+                .ok_or_else(|| err_null_idx_col(idx))
+                .and_then(|json| {
+                    serde_json::from_value(json).map_err(|e| crate::error::json_err(e).into())
+                }),
             #[cfg(feature = "mock")]
             QueryResultRow::Mock(row) => row
                 .try_get::<serde_json::Value, I>(idx)
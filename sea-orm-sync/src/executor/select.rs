@@ -2,18 +2,21 @@ use super::{
     consolidate_query_result, consolidate_query_result_chain, consolidate_query_result_quad_star,
     consolidate_query_result_tee,
 };
+use crate::database::timeout::TimeoutConnection;
 use crate::{
     ConnectionTrait, DbBackend, EntityTrait, FromQueryResult, IdenStatic, PartialModelTrait,
-    QueryResult, QuerySelect, Select, SelectA, SelectB, SelectTwo, SelectTwoMany,
+    QueryResult, QuerySelect, QueryTrait, Select, SelectA, SelectB, SelectTwo, SelectTwoMany,
     SelectTwoRequired, Statement, TryGetableMany, error::*,
 };
 
 #[cfg(feature = "stream")]
 pub use crate::StreamTrait;
+#[cfg(feature = "stream")]
+use crate::database::timeout::{map_timeout_err, timeout_err};
 
 use itertools::Itertools;
 use sea_query::SelectStatement;
-use std::marker::PhantomData;
+use std::{marker::PhantomData, time::Duration};
 
 mod five;
 mod four;
@@ -41,6 +44,7 @@ where
 {
     pub(crate) query: SelectStatement,
     selector: PhantomData<S>,
+    pub(crate) timeout: Option<Duration>,
 }
 
 /// Like [`Selector`] but executes a raw [`Statement`] (e.g. built with the
@@ -267,10 +271,7 @@ where
     where
         M: FromQueryResult,
     {
-        Selector {
-            query: self.query,
-            selector: PhantomData,
-        }
+        self.into_selector()
     }
 
     /// Return a [Selector] from `Self` that wraps a [SelectModel] with a [PartialModel](PartialModelTrait)
@@ -315,10 +316,7 @@ where
     /// Get a selectable Model as a [JsonValue] for SQL JSON operations
     #[cfg(feature = "with-json")]
     pub fn into_json(self) -> Selector<SelectModel<JsonValue>> {
-        Selector {
-            query: self.query,
-            selector: PhantomData,
-        }
+        self.into_selector()
     }
 
     /// ```
@@ -424,10 +422,7 @@ where
         T: TryGetableMany,
         C: strum::IntoEnumIterator + sea_query::Iden,
     {
-        Selector {
-            query: self.query,
-            selector: PhantomData,
-        }
+        self.into_selector()
     }
 
     /// ```
@@ -521,10 +516,7 @@ where
     where
         T: TryGetableMany,
     {
-        Selector {
-            query: self.query,
-            selector: PhantomData,
-        }
+        self.into_selector()
     }
 
     /// Get one Model from the SELECT query
@@ -567,6 +559,18 @@ where
     {
         self.into_partial_model().stream(db)
     }
+
+    fn into_selector<S>(self) -> Selector<S>
+    where
+        S: SelectorTrait,
+    {
+        let timeout = self.timeout;
+        Selector {
+            query: self.into_query(),
+            selector: PhantomData,
+            timeout,
+        }
+    }
 }
 
 impl<E, F> SelectTwo<E, F>
@@ -583,6 +587,7 @@ where
         Selector {
             query: self.query,
             selector: PhantomData,
+            timeout: self.timeout,
         }
     }
 
@@ -604,6 +609,7 @@ where
         Selector {
             query: self.query,
             selector: PhantomData,
+            timeout: self.timeout,
         }
     }
 
@@ -664,6 +670,7 @@ where
         Selector {
             query: self.query,
             selector: PhantomData,
+            timeout: self.timeout,
         }
     }
 
@@ -709,6 +716,7 @@ where
         Selector {
             query: self.query,
             selector: PhantomData,
+            timeout: self.timeout,
         }
     }
 
@@ -730,6 +738,7 @@ where
         Selector {
             query: self.query,
             selector: PhantomData,
+            timeout: self.timeout,
         }
     }
 
@@ -785,12 +794,20 @@ where
         builder.build(&self.query)
     }
 
+    /// Cancel the query once it has run for longer than `timeout`, failing with
+    /// [`DbErr::QueryTimeout`](crate::DbErr::QueryTimeout)
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
     /// Get an item from the Select query
     pub fn one<C>(mut self, db: &C) -> Result<Option<S::Item>, DbErr>
     where
         C: ConnectionTrait,
     {
         self.query.limit(1);
+        let db = &TimeoutConnection::new(db, self.timeout);
         let row = db.query_one(&self.query)?;
         match row {
             Some(row) => Ok(Some(S::from_raw_query_result(row)?)),
@@ -803,6 +820,7 @@ where
     where
         C: ConnectionTrait,
     {
+        let db = &TimeoutConnection::new(db, self.timeout);
         db.query_all(&self.query)?
             .into_iter()
             .map(|row| S::from_raw_query_result(row))
@@ -816,19 +834,30 @@ where
         C: ConnectionTrait + StreamTrait,
         S: 'b,
     {
-        let stream = db.stream(&self.query)?;
+        let timeout = self.timeout;
+        let stream = match timeout {
+            Some(timeout) => {
+                let stmt = StreamTrait::get_database_backend(db).build(&self.query);
+                map_timeout_err(db.stream_raw_with_timeout(stmt, timeout), timeout)?
+            }
+            None => db.stream(&self.query)?,
+        };
+        let map_err = move |err| match timeout {
+            Some(timeout) => timeout_err(err, timeout),
+            None => err,
+        };
 
         #[cfg(not(feature = "sync"))]
         {
-            Ok(Box::new(stream.and_then(|row| {
+            Ok(Box::new(stream.map_err(map_err).and_then(|row| {
                 futures_util::future::ready(S::from_raw_query_result(row))
             })))
         }
         #[cfg(feature = "sync")]
         {
-            Ok(Box::new(
-                stream.map(|item| item.and_then(S::from_raw_query_result)),
-            ))
+            Ok(Box::new(stream.map(move |item| {
+                item.map_err(map_err).and_then(S::from_raw_query_result)
+            })))
         }
     }
 }
@@ -1108,3 +1137,84 @@ where
         }
     }
 }
+
+#[cfg(test)]
+#[cfg(feature = "mock")]
+mod tests {
+    use crate::entity::prelude::*;
+    use crate::{DbBackend, MockDatabase, Transaction, tests_cfg::*};
+    use std::time::Duration;
+
+    const HINT: &str = "SELECT /*+ MAX_EXECUTION_TIME(1500) */ ";
+
+    fn assert_bounded(log: Vec<Transaction>, count: usize) {
+        assert_eq!(log.len(), count);
+        for txn in log {
+            for stmt in txn.statements() {
+                assert!(stmt.sql.starts_with(HINT), "{}", stmt.sql);
+            }
+        }
+    }
+
+    #[test]
+    fn timeout_of_related_selects() -> Result<(), DbErr> {
+        let timeout = Duration::from_millis(1500);
+        let db = MockDatabase::new(DbBackend::MySql)
+            .append_query_results((0..8).map(|_| Vec::<cake::Model>::new()))
+            .into_connection();
+
+        cake::Entity::find()
+            .timeout(timeout)
+            .find_also_related(fruit::Entity)
+            .all(&db)?;
+        cake::Entity::find()
+            .timeout(timeout)
+            .find_with_related(fruit::Entity)
+            .all(&db)?;
+        cake::Entity::find()
+            .find_also_related(fruit::Entity)
+            .timeout(timeout)
+            .one(&db)?;
+
+        let select = cake_filling::Entity::find()
+            .timeout(timeout)
+            .find_also_related(cake::Entity)
+            .find_also(cake_filling::Entity, filling::Entity);
+        select.clone().all(&db)?;
+        select.clone().consolidate().all(&db)?;
+        let select = select.find_also(filling::Entity, ingredient::Entity);
+        select.clone().all(&db)?;
+        let select = select.find_also(cake_filling::Entity, cake_filling_price::Entity);
+        select.clone().all(&db)?;
+        select.find_also(cake::Entity, fruit::Entity).all(&db)?;
+
+        assert_bounded(db.into_transaction_log(), 8);
+        Ok(())
+    }
+
+    #[cfg(feature = "stream")]
+    #[test]
+    fn timeout_of_stream() -> Result<(), DbErr> {
+        #[cfg(feature = "sync")]
+        use crate::util::StreamShim;
+        #[cfg(not(feature = "sync"))]
+        use futures_util::TryStreamExt;
+
+        let db = MockDatabase::new(DbBackend::MySql)
+            .append_query_results([[cake::Model {
+                id: 1,
+                name: "Chocolate Forest".to_owned(),
+            }]])
+            .into_connection();
+
+        let mut stream = cake::Entity::find()
+            .timeout(Duration::from_millis(1500))
+            .stream(&db)?;
+        assert_eq!(stream.try_next()?.map(|cake| cake.id), Some(1));
+        assert_eq!(stream.try_next()?, None);
+        drop(stream);
+
+        assert_bounded(db.into_transaction_log(), 1);
+        Ok(())
+    }
+}
@@ -14,17 +14,25 @@ where
     I: EntityTrait,
     TOP: Topology,
 {
-    pub(crate) fn new(query: SelectStatement) -> Self {
-        Self::new_without_prepare(query).prepare_select()
+    pub(crate) fn new(query: SelectStatement, timeout: Option<Duration>) -> Self {
+        Self::new_without_prepare(query, timeout).prepare_select()
     }
 
-    pub(crate) fn new_without_prepare(query: SelectStatement) -> Self {
+    pub(crate) fn new_without_prepare(query: SelectStatement, timeout: Option<Duration>) -> Self {
         Self {
             query,
             entity: PhantomData,
+            timeout,
         }
     }
 
+    /// Cancel the query once it has run for longer than `timeout`, see
+    /// [`Select::timeout`](crate::Select::timeout)
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
     fn prepare_select(mut self) -> Self {
         prepare_select_col::<I, _, _>(&mut self, SelectE);
         self
@@ -36,9 +44,11 @@ where
         J: EntityTrait,
         T: EntityTrait + Related<J>,
     {
+        let timeout = self.timeout;
         SelectSix::new(
             self.join_join(JoinType::LeftJoin, T::to(), T::via())
                 .into_query(),
+            timeout,
         )
     }
 }
@@ -130,6 +140,7 @@ where
         Selector {
             query: self.query,
             selector: PhantomData,
+            timeout: self.timeout,
         }
     }
 
@@ -159,6 +170,7 @@ where
         Selector {
             query: self.query,
             selector: PhantomData,
+            timeout: self.timeout,
         }
     }
 
@@ -13,17 +13,25 @@ where
     H: EntityTrait,
     TOP: Topology,
 {
-    pub(crate) fn new(query: SelectStatement) -> Self {
-        Self::new_without_prepare(query).prepare_select()
+    pub(crate) fn new(query: SelectStatement, timeout: Option<Duration>) -> Self {
+        Self::new_without_prepare(query, timeout).prepare_select()
     }
 
-    pub(crate) fn new_without_prepare(query: SelectStatement) -> Self {
+    pub(crate) fn new_without_prepare(query: SelectStatement, timeout: Option<Duration>) -> Self {
         Self {
             query,
             entity: PhantomData,
+            timeout,
         }
     }
 
+    /// Cancel the query once it has run for longer than `timeout`, see
+    /// [`Select::timeout`](crate::Select::timeout)
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
     fn prepare_select(mut self) -> Self {
         prepare_select_col::<H, _, _>(&mut self, SelectD);
         self
@@ -35,9 +43,11 @@ where
         I: EntityTrait,
         T: EntityTrait + Related<I>,
     {
+        let timeout = self.timeout;
         SelectFive::new(
             self.join_join(JoinType::LeftJoin, T::to(), T::via())
                 .into_query(),
+            timeout,
         )
     }
 }
@@ -123,6 +133,7 @@ where
         Selector {
             query: self.query,
             selector: PhantomData,
+            timeout: self.timeout,
         }
     }
 
@@ -150,6 +161,7 @@ where
         Selector {
             query: self.query,
             selector: PhantomData,
+            timeout: self.timeout,
         }
     }
 
@@ -247,6 +259,7 @@ where
         SelectFourMany {
             query: self.query,
             entity: self.entity,
+            timeout: self.timeout,
         }
     }
 }
@@ -270,6 +283,7 @@ where
         Selector {
             query: self.query,
             selector: PhantomData,
+            timeout: self.timeout,
         }
     }
 }
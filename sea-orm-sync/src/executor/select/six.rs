@@ -15,17 +15,25 @@ where
     J: EntityTrait,
     TOP: Topology,
 {
-    pub(crate) fn new(query: SelectStatement) -> Self {
-        Self::new_without_prepare(query).prepare_select()
+    pub(crate) fn new(query: SelectStatement, timeout: Option<Duration>) -> Self {
+        Self::new_without_prepare(query, timeout).prepare_select()
     }
 
-    pub(crate) fn new_without_prepare(query: SelectStatement) -> Self {
+    pub(crate) fn new_without_prepare(query: SelectStatement, timeout: Option<Duration>) -> Self {
         Self {
             query,
             entity: PhantomData,
+            timeout,
         }
     }
 
+    /// Cancel the query once it has run for longer than `timeout`, see
+    /// [`Select::timeout`](crate::Select::timeout)
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
     fn prepare_select(mut self) -> Self {
         prepare_select_col::<J, _, _>(&mut self, SelectF);
         self
@@ -125,6 +133,7 @@ where
         Selector {
             query: self.query,
             selector: PhantomData,
+            timeout: self.timeout,
         }
     }
 
@@ -157,6 +166,7 @@ where
         Selector {
             query: self.query,
             selector: PhantomData,
+            timeout: self.timeout,
         }
     }
 
@@ -694,6 +694,9 @@ pub mod entity;
 /// Error types returned by SeaORM operations.
 pub mod error;
 mod executor;
+#[cfg(feature = "fixtures")]
+#[cfg_attr(docsrs, doc(cfg(feature = "fixtures")))]
+pub mod fixtures;
 /// Per-query metric collection hooks.
 pub mod metric;
 pub mod query;
@@ -701,6 +704,7 @@ pub mod query;
 #[cfg_attr(docsrs, doc(cfg(feature = "rbac")))]
 pub mod rbac;
 pub mod schema;
+mod sql_tokens;
 /// Helpers for working with [`sea_query::Value`].
 pub mod value;
 
@@ -721,10 +725,11 @@ pub use schema::*;
 #[cfg(feature = "macros")]
 pub use sea_orm_macros::{
     DeriveActiveEnum, DeriveActiveModel, DeriveActiveModelBehavior, DeriveActiveModelEx,
-    DeriveArrowSchema, DeriveColumn, DeriveDisplay, DeriveEntity, DeriveEntityModel, DeriveIden,
-    DeriveIntoActiveModel, DeriveMigrationName, DeriveModel, DeriveModelEx, DerivePartialModel,
-    DerivePrimaryKey, DeriveRelatedEntity, DeriveRelation, DeriveValueType, FromJsonQueryResult,
-    FromQueryResult, raw_sql, sea_orm_compact_model as compact_model, sea_orm_model as model,
+    DeriveArrowSchema, DeriveColumn, DeriveDisplay, DeriveEntity, DeriveEntityModel, DeriveFactory,
+    DeriveIden, DeriveIntoActiveModel, DeriveMigrationName, DeriveModel, DeriveModelEx,
+    DerivePartialModel, DerivePrimaryKey, DeriveRelatedEntity, DeriveRelation, DeriveValueType,
+    FromJsonQueryResult, FromQueryResult, raw_sql, sea_orm_compact_model as compact_model,
+    sea_orm_model as model,
 };
 
 pub use sea_query;
@@ -738,3 +743,6 @@ pub use sea_orm_arrow::arrow;
 
 #[cfg(feature = "sqlx-dep")]
 pub use sqlx;
+
+#[cfg(feature = "duckdb")]
+pub use ::duckdb;
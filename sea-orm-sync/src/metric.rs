@@ -1,3 +1,4 @@
+use crate::sql_tokens::{Token, Word, is_keyword, tokenize};
 use std::{sync::Arc, time::Duration};
 
 pub(crate) type Callback = Arc<dyn Fn(&Info<'_>)>;
@@ -6,6 +7,7 @@ pub(crate) type Callback = Arc<dyn Fn(&Info<'_>)>;
 pub(crate) use inner::metric;
 
 #[derive(Debug)]
+#[non_exhaustive]
 /// Information about a single query execution, passed to the callback
 /// registered via [`DatabaseConnection::set_metric_callback`](crate::DatabaseConnection::set_metric_callback).
 pub struct Info<'a> {
@@ -15,12 +17,229 @@ pub struct Info<'a> {
     pub statement: &'a crate::Statement,
     /// `true` if the query returned an error
     pub failed: bool,
+    /// Number of rows returned by a query, or affected by an execution.
+    /// `None` if the query failed.
+    pub rows: Option<u64>,
+    /// `true` if the statement ran inside a transaction
+    pub in_transaction: bool,
+    /// How long it took to acquire a connection before running the statement.
+    /// `None` if no connection had to be acquired, e.g. inside a transaction.
+    pub acquire_elapsed: Option<Duration>,
+}
+
+/// The kind of a SQL statement, see [`Info::operation`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Operation {
+    /// `SELECT`
+    Select,
+    /// `INSERT` or `REPLACE`
+    Insert,
+    /// `UPDATE`
+    Update,
+    /// `DELETE`
+    Delete,
+    /// Schema changes: `CREATE`, `ALTER`, `DROP`, `TRUNCATE` and the like
+    Ddl,
+    /// Anything else, e.g. `BEGIN` or `SET`
+    Other,
+}
+
+/// Statistics of a connection pool, see
+/// [`DatabaseConnection::pool_status`](crate::DatabaseConnection::pool_status)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PoolStatus {
+    /// Number of connections currently open, idle or in use
+    pub size: u32,
+    /// Number of open connections not in use
+    pub idle: u32,
+    /// Maximum number of connections the pool will open
+    pub max_size: u32,
+    /// Number of tasks currently waiting to acquire a connection
+    pub waiters: u32,
+}
+
+impl Info<'_> {
+    /// The kind of statement executed, judged by its leading keyword.
+    /// A `WITH` query is classified by the statement following its common table expressions.
+    pub fn operation(&self) -> Operation {
+        let tokens = tokenize(&self.statement.sql);
+        let mut words = tokens.iter().filter_map(|token| match token {
+            Token::Word(word) if !word.quoted => Some(word),
+            _ => None,
+        });
+        let keyword = match words.next() {
+            // skip over the common table expressions, whose bodies are all parenthesized
+            Some(word) if word.text.eq_ignore_ascii_case("WITH") => {
+                words.find(|word| word.depth == 0 && is_statement_keyword(word))
+            }
+            word => word,
+        };
+        let Some(keyword) = keyword else {
+            return Operation::Other;
+        };
+        match keyword.text.to_ascii_uppercase().as_str() {
+            "SELECT" | "VALUES" => Operation::Select,
+            "INSERT" | "REPLACE" => Operation::Insert,
+            "UPDATE" => Operation::Update,
+            "DELETE" => Operation::Delete,
+            "CREATE" | "ALTER" | "DROP" | "TRUNCATE" | "RENAME" | "COMMENT" => Operation::Ddl,
+            _ => Operation::Other,
+        }
+    }
+
+    /// Names of the tables the statement reads or writes, in order of appearance and
+    /// without duplicates. Schema-qualified names keep their schema, e.g. `public.cake`.
+    ///
+    /// This is a lightweight scan for the names following `FROM`, `JOIN`, `INTO`, `UPDATE`
+    /// and `TABLE`, it does not fully parse the SQL.
+    pub fn tables(&self) -> Vec<String> {
+        statement_tables(&self.statement.sql)
+    }
+}
+
+/// Names of the tables `sql` reads or writes, see [`Info::tables`]
+pub(crate) fn statement_tables(sql: &str) -> Vec<String> {
+    let tokens = tokenize(sql);
+    let mut tables: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let follows_keyword = match &tokens[i] {
+            Token::Word(word) => {
+                ["FROM", "JOIN", "INTO", "UPDATE", "TABLE"]
+                    .iter()
+                    .any(|keyword| word.text.eq_ignore_ascii_case(keyword))
+                    && !word.quoted
+            }
+            _ => false,
+        };
+        i += 1;
+        if !follows_keyword {
+            continue;
+        }
+        loop {
+            // `CREATE TABLE IF NOT EXISTS`, `DROP TABLE IF EXISTS`, `DELETE FROM ONLY`
+            while ["IF", "NOT", "EXISTS", "ONLY"]
+                .iter()
+                .any(|keyword| is_keyword(&tokens, i, keyword))
+            {
+                i += 1;
+            }
+            let Some(name) = table_name(&tokens, &mut i) else {
+                break;
+            };
+            if !tables.contains(&name) {
+                tables.push(name);
+            }
+            // `FROM a, b` lists more tables, possibly aliased
+            if is_keyword(&tokens, i, "AS") {
+                i += 1;
+            }
+            if matches!(tokens.get(i), Some(Token::Word(word)) if !is_reserved(word)) {
+                i += 1;
+            }
+            if matches!(tokens.get(i), Some(Token::Comma)) {
+                i += 1;
+            } else {
+                break;
+            }
+        }
+    }
+    tables
+}
+
+/// Read a possibly schema-qualified table name starting at `tokens[*i]`
+fn table_name(tokens: &[Token<'_>], i: &mut usize) -> Option<String> {
+    let mut name = match tokens.get(*i) {
+        Some(Token::Word(word)) if !is_reserved(word) => word.text.to_owned(),
+        _ => return None,
+    };
+    *i += 1;
+    while let (Some(Token::Dot), Some(Token::Word(word))) = (tokens.get(*i), tokens.get(*i + 1)) {
+        name.push('.');
+        name.push_str(word.text);
+        *i += 2;
+    }
+    Some(name)
+}
+
+fn is_statement_keyword(word: &Word<'_>) -> bool {
+    ["SELECT", "INSERT", "UPDATE", "DELETE", "VALUES"]
+        .iter()
+        .any(|keyword| word.text.eq_ignore_ascii_case(keyword))
+}
+
+/// Keywords that may follow a table name, and thus are not an alias
+fn is_reserved(word: &Word<'_>) -> bool {
+    const RESERVED: &[&str] = &[
+        "AS",
+        "CROSS",
+        "DEFAULT",
+        "EXCEPT",
+        "FOR",
+        "FROM",
+        "FULL",
+        "GROUP",
+        "HAVING",
+        "INNER",
+        "INTERSECT",
+        "JOIN",
+        "LEFT",
+        "LIMIT",
+        "NATURAL",
+        "OFFSET",
+        "ON",
+        "ORDER",
+        "OUTER",
+        "RETURNING",
+        "RIGHT",
+        "SELECT",
+        "SET",
+        "UNION",
+        "USING",
+        "VALUES",
+        "WHERE",
+        "WINDOW",
+    ];
+    !word.quoted
+        && RESERVED
+            .iter()
+            .any(|keyword| word.text.eq_ignore_ascii_case(keyword))
+}
+
+/// Number of rows a query result carries, reported as [`Info::rows`]
+pub(crate) trait MetricRows {
+    fn metric_rows(&self) -> u64;
+}
+
+impl MetricRows for crate::ExecResult {
+    fn metric_rows(&self) -> u64 {
+        self.rows_affected()
+    }
+}
+
+impl MetricRows for Option<crate::QueryResult> {
+    fn metric_rows(&self) -> u64 {
+        self.is_some() as u64
+    }
+}
+
+impl MetricRows for Vec<crate::QueryResult> {
+    fn metric_rows(&self) -> u64 {
+        self.len() as u64
+    }
+}
+
+#[allow(dead_code)]
+pub(crate) fn metric_rows<T: MetricRows, E>(res: &Result<T, E>) -> Option<u64> {
+    res.as_ref().ok().map(MetricRows::metric_rows)
 }
 
 mod inner {
     #[allow(unused_macros)]
     macro_rules! metric {
-        ($metric_callback:expr, $stmt:expr, $code:block) => {{
+        ($metric_callback:expr, $stmt:expr, $in_transaction:expr, $acquire_elapsed:expr, $code:block) => {{
             let _start = $metric_callback.is_some().then(std::time::SystemTime::now);
             let res = $code;
             if let (Some(_start), Some(callback)) = (_start, $metric_callback.as_deref()) {
@@ -28,6 +247,9 @@ mod inner {
                     elapsed: _start.elapsed().unwrap_or_default(),
                     statement: $stmt,
                     failed: res.is_err(),
+                    rows: crate::metric::metric_rows(&res),
+                    in_transaction: $in_transaction,
+                    acquire_elapsed: $acquire_elapsed,
                 };
                 callback(&info);
             }
@@ -36,3 +258,75 @@ mod inner {
     }
     pub(crate) use metric;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Info, Operation};
+    use crate::{DbBackend, Statement};
+    use pretty_assertions::assert_eq;
+
+    fn parse(sql: &str) -> (Operation, Vec<String>) {
+        let statement = Statement::from_string(DbBackend::Postgres, sql);
+        let info = Info {
+            elapsed: Default::default(),
+            statement: &statement,
+            failed: false,
+            rows: None,
+            in_transaction: false,
+            acquire_elapsed: None,
+        };
+        (info.operation(), info.tables())
+    }
+
+    #[test]
+    fn test_operation_and_tables() {
+        assert_eq!(
+            parse(
+                r#"SELECT "cake"."id", "cake"."name" FROM "cake" LEFT JOIN "fruit" ON "cake"."id" = "fruit"."cake_id" WHERE "cake"."name" LIKE 'from x'"#
+            ),
+            (
+                Operation::Select,
+                vec!["cake".to_owned(), "fruit".to_owned()]
+            )
+        );
+        assert_eq!(
+            parse(r#"INSERT INTO "public"."cake" ("name") VALUES ($1) RETURNING "id""#),
+            (Operation::Insert, vec!["public.cake".to_owned()])
+        );
+        assert_eq!(
+            parse("UPDATE `cake` SET `name` = ? WHERE `id` = ?"),
+            (Operation::Update, vec!["cake".to_owned()])
+        );
+        assert_eq!(
+            parse(r#"DELETE FROM "cake" WHERE "id" IN (SELECT "cake_id" FROM "fruit")"#),
+            (
+                Operation::Delete,
+                vec!["cake".to_owned(), "fruit".to_owned()]
+            )
+        );
+        assert_eq!(
+            parse(r#"CREATE TABLE IF NOT EXISTS "cake" ( "id" integer )"#),
+            (Operation::Ddl, vec!["cake".to_owned()])
+        );
+        assert_eq!(
+            parse("SELECT c.id FROM cake c, fruit AS f WHERE c.id = f.cake_id"),
+            (
+                Operation::Select,
+                vec!["cake".to_owned(), "fruit".to_owned()]
+            )
+        );
+        assert_eq!(
+            parse(
+                r#"WITH "cte" AS (SELECT "id" FROM "cake") DELETE FROM "fruit" WHERE "cake_id" IN (SELECT "id" FROM "cte")"#
+            ),
+            (
+                Operation::Delete,
+                vec!["cake".to_owned(), "fruit".to_owned(), "cte".to_owned()]
+            )
+        );
+        assert_eq!(
+            parse("-- comment\nBEGIN"),
+            (Operation::Other, Vec::<String>::new())
+        );
+    }
+}
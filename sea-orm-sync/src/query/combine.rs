@@ -4,7 +4,7 @@ use crate::{
 };
 use core::marker::PhantomData;
 use sea_query::{Iden, IntoIden, Order, SelectExpr, SelectStatement, SimpleExpr};
-use std::borrow::Cow;
+use std::{borrow::Cow, time::Duration};
 
 macro_rules! select_def {
     ( $ident: ident, $str: expr ) => {
@@ -85,7 +85,8 @@ where
         F: EntityTrait,
     {
         self = self.apply_alias(SelectA.as_str());
-        SelectTwo::new(self.into_query())
+        let timeout = self.timeout;
+        SelectTwo::new(self.into_query(), timeout)
     }
 
     /// Only used by Entity loader
@@ -95,7 +96,8 @@ where
         F: EntityTrait,
     {
         self = self.apply_alias(SelectA.as_str());
-        SelectTwo::new_without_prepare(self.into_query())
+        let timeout = self.timeout;
+        SelectTwo::new_without_prepare(self.into_query(), timeout)
     }
 
     /// Makes a SELECT operation in conjunction to another relation
@@ -104,7 +106,8 @@ where
         F: EntityTrait,
     {
         self = self.apply_alias(SelectA.as_str());
-        SelectTwoMany::new(self.into_query())
+        let timeout = self.timeout;
+        SelectTwoMany::new(self.into_query(), timeout)
     }
 
     /// Selects extra Entity and returns it together with the Entity from `Self`
@@ -113,7 +116,8 @@ where
         F: EntityTrait,
     {
         self = self.apply_alias(SelectA.as_str());
-        SelectTwoRequired::new(self.into_query())
+        let timeout = self.timeout;
+        SelectTwoRequired::new(self.into_query(), timeout)
     }
 }
 
@@ -122,17 +126,25 @@ where
     E: EntityTrait,
     F: EntityTrait,
 {
-    pub(crate) fn new(query: SelectStatement) -> Self {
-        Self::new_without_prepare(query).prepare_select()
+    pub(crate) fn new(query: SelectStatement, timeout: Option<Duration>) -> Self {
+        Self::new_without_prepare(query, timeout).prepare_select()
     }
 
-    pub(crate) fn new_without_prepare(query: SelectStatement) -> Self {
+    pub(crate) fn new_without_prepare(query: SelectStatement, timeout: Option<Duration>) -> Self {
         Self {
             query,
             entity: PhantomData,
+            timeout,
         }
     }
 
+    /// Cancel the query once it has run for longer than `timeout`, see
+    /// [`Select::timeout`](crate::Select::timeout)
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
     fn prepare_select(mut self) -> Self {
         prepare_select_col::<F, _, _>(&mut self, SelectB);
         self
@@ -144,19 +156,27 @@ where
     E: EntityTrait,
     F: EntityTrait,
 {
-    pub(crate) fn new(query: SelectStatement) -> Self {
-        Self::new_without_prepare(query)
+    pub(crate) fn new(query: SelectStatement, timeout: Option<Duration>) -> Self {
+        Self::new_without_prepare(query, timeout)
             .prepare_select()
             .prepare_order_by()
     }
 
-    pub(crate) fn new_without_prepare(query: SelectStatement) -> Self {
+    pub(crate) fn new_without_prepare(query: SelectStatement, timeout: Option<Duration>) -> Self {
         Self {
             query,
             entity: PhantomData,
+            timeout,
         }
     }
 
+    /// Cancel the query once it has run for longer than `timeout`, see
+    /// [`Select::timeout`](crate::Select::timeout)
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
     fn prepare_select(mut self) -> Self {
         prepare_select_col::<F, _, _>(&mut self, SelectB);
         self
@@ -175,17 +195,25 @@ where
     E: EntityTrait,
     F: EntityTrait,
 {
-    pub(crate) fn new(query: SelectStatement) -> Self {
-        Self::new_without_prepare(query).prepare_select()
+    pub(crate) fn new(query: SelectStatement, timeout: Option<Duration>) -> Self {
+        Self::new_without_prepare(query, timeout).prepare_select()
     }
 
-    pub(crate) fn new_without_prepare(query: SelectStatement) -> Self {
+    pub(crate) fn new_without_prepare(query: SelectStatement, timeout: Option<Duration>) -> Self {
         Self {
             query,
             entity: PhantomData,
+            timeout,
         }
     }
 
+    /// Cancel the query once it has run for longer than `timeout`, see
+    /// [`Select::timeout`](crate::Select::timeout)
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
     fn prepare_select(mut self) -> Self {
         prepare_select_col::<F, Self, _>(&mut self, SelectB);
         self
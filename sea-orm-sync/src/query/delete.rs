@@ -1,11 +1,13 @@
 use crate::{
     ActiveModelTrait, ActiveValue, ColumnTrait, DbBackend, DbErr, EntityTrait, IntoActiveModel,
-    Iterable, PrimaryKeyToColumn, PrimaryKeyTrait, QueryFilter, QueryTrait,
-    query::column_tuple_in_condition,
+    Iterable, PrimaryKeyToColumn, PrimaryKeyTrait, QueryFilter, QueryTrait, Statement,
+    StatementBuilder,
+    query::{WhereCondition, column_tuple_in_condition},
     sea_query::{IntoValueTuple, ValueTuple},
 };
 use core::marker::PhantomData;
-use sea_query::DeleteStatement;
+use sea_query::{DeleteStatement, Expr, UpdateStatement};
+use std::time::Duration;
 
 /// Type-level entry point for `DELETE` builders, e.g.
 /// `Delete::one(model)` and `Delete::many(Entity)`. You normally call
@@ -31,6 +33,7 @@ pub struct DeleteOne<E: EntityTrait>(pub(crate) Result<ValidatedDeleteOne<E>, Db
 pub struct ValidatedDeleteOne<E: EntityTrait> {
     pub(crate) query: DeleteStatement,
     pub(crate) entity: PhantomData<E>,
+    pub(crate) force_delete: bool,
 }
 
 impl<E: EntityTrait> TryFrom<DeleteOne<E>> for ValidatedDeleteOne<E> {
@@ -46,6 +49,12 @@ impl<E: EntityTrait> DeleteOne<E> {
     pub fn validate(self) -> Result<ValidatedDeleteOne<E>, DbErr> {
         self.try_into()
     }
+
+    /// Delete the row even if the entity has a
+    /// [soft delete column](EntityTrait::soft_delete_column), see [`DeleteMany::force_delete`]
+    pub fn force_delete(self) -> Self {
+        Self(self.0.map(ValidatedDeleteOne::force_delete))
+    }
 }
 
 /// Multi-row `DELETE` builder, returned by
@@ -58,6 +67,8 @@ where
 {
     pub(crate) query: DeleteStatement,
     pub(crate) entity: PhantomData<E>,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) force_delete: bool,
 }
 
 impl Delete {
@@ -111,6 +122,7 @@ impl Delete {
                 .from_table(A::Entity::default().table_ref())
                 .to_owned(),
             entity: PhantomData,
+            force_delete: false,
         };
         // Build the SQL condition from the primary key columns.
         for key in <A::Entity as EntityTrait>::PrimaryKey::iter() {
@@ -135,6 +147,7 @@ impl Delete {
                 .from_table(entity.table_ref())
                 .to_owned(),
             entity: PhantomData,
+            force_delete: false,
         }
     }
 
@@ -160,10 +173,29 @@ impl Delete {
                 .from_table(entity.table_ref())
                 .to_owned(),
             entity: PhantomData,
+            timeout: None,
+            force_delete: false,
         }
     }
 }
 
+/// The `UPDATE` run instead of `query`, setting the soft delete column of the rows it
+/// deletes, if `E` has one. Only the conditions of `query` are carried over.
+fn soft_delete_query<E>(query: &DeleteStatement) -> Option<UpdateStatement>
+where
+    E: EntityTrait,
+{
+    let col = E::soft_delete_column()?;
+    Some(
+        UpdateStatement::new()
+            .table(E::default().table_ref())
+            .value(col, Expr::current_timestamp())
+            .cond_where(col.is_null())
+            .cond_where(query.where_condition())
+            .to_owned(),
+    )
+}
+
 impl<E> QueryFilter for ValidatedDeleteOne<E>
 where
     E: EntityTrait,
@@ -186,10 +218,96 @@ where
     }
 }
 
+impl<E> ValidatedDeleteOne<E>
+where
+    E: EntityTrait,
+{
+    /// Delete the row even if the entity has a
+    /// [soft delete column](EntityTrait::soft_delete_column), see [`DeleteMany::force_delete`]
+    pub fn force_delete(mut self) -> Self {
+        self.force_delete = true;
+        self
+    }
+
+    /// The `UPDATE` run instead of the `DELETE`, see [`DeleteMany::soft_delete_query`]
+    pub fn soft_delete_query(&self) -> Option<UpdateStatement> {
+        if self.force_delete {
+            return None;
+        }
+        soft_delete_query::<E>(&self.query)
+    }
+}
+
 impl<E> DeleteMany<E>
 where
     E: EntityTrait,
 {
+    /// Cancel the statement once it has run for longer than `timeout`, failing with
+    /// [`DbErr::QueryTimeout`](crate::DbErr::QueryTimeout). Enforced by the database,
+    /// see [`ConnectionTrait::execute_raw_with_timeout`](crate::ConnectionTrait::execute_raw_with_timeout);
+    /// MySQL can only bound `SELECT`s, so there it has no effect.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Delete the rows even if the entity has a [soft delete column](EntityTrait::soft_delete_column),
+    /// instead of setting it to the current time
+    ///
+    /// ```
+    /// # #[cfg(feature = "with-chrono")]
+    /// # {
+    /// use sea_orm::{DbBackend, entity::*, query::*, tests_cfg::cake_review};
+    ///
+    /// assert_eq!(
+    ///     cake_review::Entity::delete_many()
+    ///         .filter(cake_review::Column::CakeId.eq(1))
+    ///         .build(DbBackend::Postgres)
+    ///         .to_string(),
+    ///     r#"UPDATE "cake_review" SET "deleted_at" = CURRENT_TIMESTAMP WHERE "cake_review"."deleted_at" IS NULL AND "cake_review"."cake_id" = 1"#,
+    /// );
+    /// assert_eq!(
+    ///     cake_review::Entity::delete_many()
+    ///         .filter(cake_review::Column::CakeId.eq(1))
+    ///         .force_delete()
+    ///         .build(DbBackend::Postgres)
+    ///         .to_string(),
+    ///     r#"DELETE FROM "cake_review" WHERE "cake_review"."cake_id" = 1"#,
+    /// );
+    /// # }
+    /// ```
+    pub fn force_delete(mut self) -> Self {
+        self.force_delete = true;
+        self
+    }
+
+    /// The `UPDATE` run instead of the `DELETE` if the entity has a
+    /// [soft delete column](EntityTrait::soft_delete_column), setting it to the current
+    /// time on the rows matching the conditions of the `DELETE`. `None` if the rows are
+    /// deleted.
+    ///
+    /// ```
+    /// # #[cfg(feature = "with-chrono")]
+    /// # {
+    /// use sea_orm::{DbBackend, entity::*, query::*, sea_query::PostgresQueryBuilder, tests_cfg::{cake, cake_review}};
+    ///
+    /// let mut delete = cake_review::Entity::delete_many();
+    /// QueryTrait::query(&mut delete).and_where(cake_review::Column::CakeId.eq(1));
+    /// assert_eq!(
+    ///     delete.soft_delete_query().unwrap().to_string(PostgresQueryBuilder),
+    ///     r#"UPDATE "cake_review" SET "deleted_at" = CURRENT_TIMESTAMP WHERE "cake_review"."deleted_at" IS NULL AND "cake_review"."cake_id" = 1"#,
+    /// );
+    /// assert_eq!(delete.force_delete().soft_delete_query(), None);
+    /// assert_eq!(cake::Entity::delete_many().soft_delete_query(), None);
+    /// # }
+    /// ```
+    pub fn soft_delete_query(&self) -> Option<UpdateStatement> {
+        if self.force_delete {
+            return None;
+        }
+        soft_delete_query::<E>(&self.query)
+    }
+
     /// Filter by vector of IDs by primary key
     ///
     /// # Panics
@@ -199,7 +317,7 @@ where
     where
         I: IntoIterator<Item = <E::PrimaryKey as PrimaryKeyTrait>::ValueType>,
     {
-        self.query.cond_where(
+        self.filter_mut(
             column_tuple_in_condition(
                 &E::default().table_ref(),
                 &E::primary_key_identity(),
@@ -219,7 +337,7 @@ where
     ///
     /// Panic if `ValueTuple` arity does not match primary key
     pub fn filter_by_value_tuples(mut self, values: &[ValueTuple], db_backend: DbBackend) -> Self {
-        self.query.cond_where(
+        self.filter_mut(
             column_tuple_in_condition(
                 &E::default().table_ref(),
                 &E::primary_key_identity(),
@@ -242,6 +360,8 @@ where
         &mut self.query
     }
 
+    /// The `DELETE`, which is only run as is without a
+    /// [`soft_delete_query`](Self::soft_delete_query); that takes the conditions of it
     fn as_query(&self) -> &DeleteStatement {
         &self.query
     }
@@ -249,6 +369,15 @@ where
     fn into_query(self) -> DeleteStatement {
         self.query
     }
+
+    /// The `UPDATE` of [`soft_delete_query`](Self::soft_delete_query) where there is one,
+    /// as that is what runs, the `DELETE` of [`as_query`](QueryTrait::as_query) otherwise
+    fn build(&self, db_backend: DbBackend) -> Statement {
+        match self.soft_delete_query() {
+            Some(soft_delete) => StatementBuilder::build(&soft_delete, &db_backend),
+            None => StatementBuilder::build(&self.query, &db_backend),
+        }
+    }
 }
 
 impl<E> QueryTrait for DeleteMany<E>
@@ -261,6 +390,8 @@ where
         &mut self.query
     }
 
+    /// The `DELETE`, which is only run as is without a
+    /// [`soft_delete_query`](Self::soft_delete_query); that takes the conditions of it
     fn as_query(&self) -> &DeleteStatement {
         &self.query
     }
@@ -268,6 +399,15 @@ where
     fn into_query(self) -> DeleteStatement {
         self.query
     }
+
+    /// The `UPDATE` of [`soft_delete_query`](Self::soft_delete_query) where there is one,
+    /// as that is what runs, the `DELETE` of [`as_query`](QueryTrait::as_query) otherwise
+    fn build(&self, db_backend: DbBackend) -> Statement {
+        match self.soft_delete_query() {
+            Some(soft_delete) => StatementBuilder::build(&soft_delete, &db_backend),
+            None => StatementBuilder::build(&self.query, &db_backend),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -311,4 +451,47 @@ mod tests {
             r#"DELETE FROM "fruit" WHERE "fruit"."name" LIKE '%Cheese%'"#,
         );
     }
+
+    #[test]
+    #[cfg(feature = "with-chrono")]
+    fn delete_soft() {
+        use crate::tests_cfg::cake_review;
+
+        assert_eq!(
+            cake_review::Entity::delete_by_id(1)
+                .build(DbBackend::Postgres)
+                .to_string(),
+            r#"UPDATE "cake_review" SET "deleted_at" = CURRENT_TIMESTAMP WHERE "cake_review"."deleted_at" IS NULL AND "cake_review"."id" = 1"#,
+        );
+        assert_eq!(
+            cake_review::Entity::delete_by_id(1)
+                .force_delete()
+                .build(DbBackend::Postgres)
+                .to_string(),
+            r#"DELETE FROM "cake_review" WHERE "cake_review"."id" = 1"#,
+        );
+        assert_eq!(
+            cake_review::Entity::delete_many()
+                .filter_by_ids([1, 2])
+                .build(DbBackend::Postgres)
+                .to_string(),
+            r#"UPDATE "cake_review" SET "deleted_at" = CURRENT_TIMESTAMP WHERE "cake_review"."deleted_at" IS NULL AND "cake_review"."id" IN (1, 2)"#,
+        );
+
+        // conditions added to the statement directly are kept too
+        let mut delete = cake_review::Entity::delete_many();
+        QueryTrait::query(&mut delete).and_where(cake_review::Column::CakeId.eq(1));
+        assert_eq!(
+            delete.build(DbBackend::Postgres).to_string(),
+            r#"UPDATE "cake_review" SET "deleted_at" = CURRENT_TIMESTAMP WHERE "cake_review"."deleted_at" IS NULL AND "cake_review"."cake_id" = 1"#,
+        );
+        assert_eq!(
+            delete
+                .filter(cake_review::Column::Id.gt(2))
+                .force_delete()
+                .build(DbBackend::Postgres)
+                .to_string(),
+            r#"DELETE FROM "cake_review" WHERE "cake_review"."cake_id" = 1 AND "cake_review"."id" > 2"#,
+        );
+    }
 }
@@ -1,9 +1,11 @@
+use super::{set_insert_timestamps, set_upsert_updated_at};
 use crate::{
     ActiveModelTrait, ActiveValue, ColumnTrait, EntityName, EntityTrait, IntoActiveModel, Iterable,
     PrimaryKeyTrait, QueryTrait,
 };
 use core::marker::PhantomData;
 use sea_query::{Expr, InsertStatement, Keyword, OnConflict, SimpleExpr, Value, ValueTuple};
+use std::{sync::OnceLock, time::Duration};
 
 /// Single-row `INSERT` builder, returned by
 /// [`EntityTrait::insert`](crate::EntityTrait::insert). Chain `.on_conflict(...)`
@@ -17,6 +19,7 @@ where
     pub(crate) query: InsertStatement,
     pub(crate) primary_key: Option<ValueTuple>,
     pub(crate) model: PhantomData<A>,
+    pub(crate) timeout: Option<Duration>,
 }
 
 /// Multi-row `INSERT` builder, returned by
@@ -28,10 +31,16 @@ pub struct InsertMany<A>
 where
     A: ActiveModelTrait,
 {
+    /// The statement without its rows, which are kept apart so that it can be split
     pub(crate) query: InsertStatement,
+    /// The rows of the statement, unless [`QueryTrait::query`] has moved them into it
+    pub(crate) rows: Vec<Vec<SimpleExpr>>,
+    /// The statement with all its rows, built on first use by [`QueryTrait::as_query`]
+    with_rows: OnceLock<InsertStatement>,
     pub(crate) primary_key: Option<ValueTuple>,
     pub(crate) empty: bool,
     pub(crate) model: PhantomData<A>,
+    pub(crate) timeout: Option<Duration>,
 }
 
 /// Wrapper of [`Insert`] / [`InsertMany`], treats "no row inserted/id returned" as a normal outcome.
@@ -47,8 +56,7 @@ pub struct TryInsert<A>
 where
     A: ActiveModelTrait,
 {
-    pub(crate) insert_struct: Insert<A>,
-    pub(crate) empty: bool,
+    pub(crate) insert_struct: InsertMany<A>,
 }
 
 impl<A> Insert<A>
@@ -95,6 +103,7 @@ where
             .or_default_values();
 
         let mut am: A = m.into_active_model();
+        set_insert_timestamps(&mut am);
         let primary_key =
             if !<<A::Entity as EntityTrait>::PrimaryKey as PrimaryKeyTrait>::auto_increment() {
                 am.get_primary_key_value()
@@ -123,6 +132,7 @@ where
             query,
             primary_key,
             model: PhantomData,
+            timeout: None,
         }
     }
 
@@ -156,6 +166,15 @@ where
         InsertMany::many(models)
     }
 
+    /// Cancel the statement once it has run for longer than `timeout`, failing with
+    /// [`DbErr::QueryTimeout`](crate::DbErr::QueryTimeout). Enforced by the database,
+    /// see [`ConnectionTrait::execute_raw_with_timeout`](crate::ConnectionTrait::execute_raw_with_timeout);
+    /// MySQL can only bound `SELECT`s, so there it has no effect.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
     /// Set ON CONFLICT logic
     ///
     /// on conflict do nothing
@@ -212,7 +231,8 @@ where
     ///     r#"INSERT INTO "cake" ("id", "name") VALUES (2, 'Orange') ON CONFLICT ("name") DO UPDATE SET "name" = "excluded"."name""#,
     /// );
     /// ```
-    pub fn on_conflict(mut self, on_conflict: OnConflict) -> Self {
+    pub fn on_conflict(mut self, mut on_conflict: OnConflict) -> Self {
+        set_upsert_updated_at::<A::Entity>(&mut on_conflict);
         self.query.on_conflict(on_conflict);
         self
     }
@@ -325,6 +345,7 @@ where
 
         for model in models.into_iter() {
             let mut am: A = model.into_active_model();
+            set_insert_timestamps(&mut am);
             primary_key =
                 if !<<A::Entity as EntityTrait>::PrimaryKey as PrimaryKeyTrait>::auto_increment() {
                     am.get_primary_key_value()
@@ -355,35 +376,54 @@ where
             query.columns(columns.iter().cloned().flatten());
         }
 
-        for values in all_values {
-            // since we've aligned the column set, this never panics
-            query.values_panic(values.into_iter().enumerate().filter_map(|(i, v)| {
-                if columns[i].is_some() {
-                    // only if the column is used
-                    if !matches!(v, SimpleExpr::Keyword(Keyword::Null)) {
-                        // use the value expression
-                        Some(v)
-                    } else {
-                        // use null as standin, which must be Some
-                        null_value[i].clone().map(SimpleExpr::Value)
-                    }
-                } else {
-                    None
-                }
-            }));
-        }
+        let rows = all_values
+            .into_iter()
+            .map(|values| {
+                values
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(|(i, v)| {
+                        if columns[i].is_some() {
+                            // only if the column is used
+                            if !matches!(v, SimpleExpr::Keyword(Keyword::Null)) {
+                                // use the value expression
+                                Some(v)
+                            } else {
+                                // use null as standin, which must be Some
+                                null_value[i].clone().map(SimpleExpr::Value)
+                            }
+                        } else {
+                            None
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
 
         Self {
             query,
+            rows,
+            with_rows: OnceLock::new(),
             primary_key,
             empty,
             model: PhantomData,
+            timeout: None,
         }
     }
 
+    /// Cancel the statement once it has run for longer than `timeout`, failing with
+    /// [`DbErr::QueryTimeout`](crate::DbErr::QueryTimeout). Enforced by the database,
+    /// see [`ConnectionTrait::execute_raw_with_timeout`](crate::ConnectionTrait::execute_raw_with_timeout);
+    /// MySQL can only bound `SELECT`s, so there it has no effect.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
     /// Set ON CONFLICT logic
-    pub fn on_conflict(mut self, on_conflict: OnConflict) -> Self {
-        self.query.on_conflict(on_conflict);
+    pub fn on_conflict(mut self, mut on_conflict: OnConflict) -> Self {
+        set_upsert_updated_at::<A::Entity>(&mut on_conflict);
+        self.statement().on_conflict(on_conflict);
         self
     }
 
@@ -416,7 +456,7 @@ where
         let primary_keys = <A::Entity as EntityTrait>::PrimaryKey::iter();
         let mut on_conflict = OnConflict::columns(columns);
         on_conflict.do_nothing_on(primary_keys);
-        self.query.on_conflict(on_conflict);
+        self.statement().on_conflict(on_conflict);
         TryInsert::from_many(self)
     }
 
@@ -458,30 +498,82 @@ where
     where
         A: ActiveModelTrait,
     {
-        self.query.on_conflict(on_conflict_primary_key::<A>());
+        self.statement().on_conflict(on_conflict_primary_key::<A>());
 
         TryInsert::from_many(self)
     }
 
+    /// The statement without its rows
+    fn statement(&mut self) -> &mut InsertStatement {
+        self.with_rows.take();
+        &mut self.query
+    }
+
     /// panic when self is empty
     pub(crate) fn into_one(self) -> Insert<A> {
         assert!(!self.empty);
 
         let Self {
             query,
+            rows,
             primary_key,
-            empty: _,
             model,
+            timeout,
+            ..
         } = self;
 
         Insert {
+            query: with_rows(query, rows),
+            primary_key,
+            model,
+            timeout,
+        }
+    }
+
+    /// Split into statements of at most `max_rows` rows each
+    pub(crate) fn into_chunks(self, max_rows: usize) -> Vec<Insert<A>> {
+        let Self {
             query,
+            rows,
             primary_key,
             model,
+            timeout,
+            ..
+        } = self;
+
+        let mut chunks = Vec::new();
+        let mut rows = rows.into_iter().peekable();
+        while rows.peek().is_some() {
+            chunks.push(Insert {
+                query: with_rows(query.clone(), rows.by_ref().take(max_rows)),
+                primary_key: primary_key.clone(),
+                model,
+                timeout,
+            });
         }
+        chunks
     }
 }
 
+fn with_rows<I>(mut query: InsertStatement, rows: I) -> InsertStatement
+where
+    I: IntoIterator<Item = Vec<SimpleExpr>>,
+{
+    with_rows_mut(&mut query, rows);
+    query
+}
+
+fn with_rows_mut<I>(query: &mut InsertStatement, rows: I) -> &mut InsertStatement
+where
+    I: IntoIterator<Item = Vec<SimpleExpr>>,
+{
+    for row in rows {
+        // since we've aligned the column set, this never panics
+        query.values_panic(row);
+    }
+    query
+}
+
 impl<A> QueryTrait for Insert<A>
 where
     A: ActiveModelTrait,
@@ -507,16 +599,20 @@ where
 {
     type QueryStatement = InsertStatement;
 
+    /// The statement with all its rows. They are moved into the statement, so
+    /// that the insert can no longer be split into chunks when it is executed.
     fn query(&mut self) -> &mut InsertStatement {
-        &mut self.query
+        let rows = std::mem::take(&mut self.rows);
+        with_rows_mut(self.statement(), rows)
     }
 
     fn as_query(&self) -> &InsertStatement {
-        &self.query
+        self.with_rows
+            .get_or_init(|| with_rows(self.query.clone(), self.rows.iter().cloned()))
     }
 
     fn into_query(self) -> InsertStatement {
-        self.query
+        with_rows(self.query, self.rows)
     }
 }
 
@@ -525,30 +621,40 @@ where
     A: ActiveModelTrait,
 {
     fn from_one(insert: Insert<A>) -> Self {
-        Self {
-            insert_struct: insert,
-            empty: false,
-        }
-    }
-
-    fn from_many(insert: InsertMany<A>) -> Self {
-        let InsertMany {
+        let Insert {
             query,
             primary_key,
-            empty,
             model,
+            timeout,
         } = insert;
 
+        // the row stays in the statement, a single row is never split
+        Self::from_many(InsertMany {
+            query,
+            rows: Vec::new(),
+            with_rows: OnceLock::new(),
+            primary_key,
+            empty: false,
+            model,
+            timeout,
+        })
+    }
+
+    fn from_many(insert: InsertMany<A>) -> Self {
         Self {
-            insert_struct: Insert {
-                query,
-                primary_key,
-                model,
-            },
-            empty,
+            insert_struct: insert,
         }
     }
 
+    /// Cancel the statement once it has run for longer than `timeout`, failing with
+    /// [`DbErr::QueryTimeout`](crate::DbErr::QueryTimeout). Enforced by the database,
+    /// see [`ConnectionTrait::execute_raw_with_timeout`](crate::ConnectionTrait::execute_raw_with_timeout);
+    /// MySQL can only bound `SELECT`s, so there it has no effect.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.insert_struct.timeout = Some(timeout);
+        self
+    }
+
     /// Try insert one item
     pub fn one<M>(m: M) -> Self
     where
@@ -567,15 +673,31 @@ where
     }
 
     /// Set ON CONFLICT logic
-    pub fn on_conflict(mut self, on_conflict: OnConflict) -> Insert<A> {
-        self.insert_struct.query.on_conflict(on_conflict);
-        self.insert_struct
+    pub fn on_conflict(mut self, mut on_conflict: OnConflict) -> Insert<A> {
+        set_upsert_updated_at::<A::Entity>(&mut on_conflict);
+        self.insert_struct.statement().on_conflict(on_conflict);
+
+        let InsertMany {
+            query,
+            rows,
+            primary_key,
+            model,
+            timeout,
+            ..
+        } = self.insert_struct;
+
+        Insert {
+            query: with_rows(query, rows),
+            primary_key,
+            model,
+            timeout,
+        }
     }
 
     /// Set ON CONFLICT on primary key do nothing, but with MySQL specific polyfill.
     pub fn on_conflict_do_nothing(mut self) -> Self {
         self.insert_struct
-            .query
+            .statement()
             .on_conflict(on_conflict_primary_key::<A>());
 
         self
@@ -589,7 +711,7 @@ where
         let primary_keys = <A::Entity as EntityTrait>::PrimaryKey::iter();
         let mut on_conflict = OnConflict::columns(columns);
         on_conflict.do_nothing_on(primary_keys);
-        self.insert_struct.query.on_conflict(on_conflict);
+        self.insert_struct.statement().on_conflict(on_conflict);
         self
     }
 }
@@ -601,15 +723,15 @@ where
     type QueryStatement = InsertStatement;
 
     fn query(&mut self) -> &mut InsertStatement {
-        &mut self.insert_struct.query
+        self.insert_struct.query()
     }
 
     fn as_query(&self) -> &InsertStatement {
-        &self.insert_struct.query
+        self.insert_struct.as_query()
     }
 
     fn into_query(self) -> InsertStatement {
-        self.insert_struct.query
+        self.insert_struct.into_query()
     }
 }
 
@@ -903,4 +1025,185 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn insert_many_chunked() -> Result<(), DbErr> {
+        use crate::{DbBackend, MockDatabase};
+
+        let models: Vec<_> = (1..=40000)
+            .map(|id| cake::Model {
+                id,
+                name: format!("Cake {id}"),
+            })
+            .collect();
+
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([models[..32767].to_vec(), models[32767..].to_vec()])
+            .into_connection();
+
+        let inserted = cake::Entity::insert_many(
+            models
+                .iter()
+                .cloned()
+                .map(IntoActiveModel::into_active_model),
+        )
+        .on_conflict(OnConflict::column(cake::Column::Id).do_nothing().to_owned())
+        .exec_with_returning(&db)?;
+        assert_eq!(inserted, models);
+
+        // two statements of at most 65535 parameters, run in one transaction
+        let log = db.into_transaction_log();
+        assert_eq!(log.len(), 1);
+        let statements = log[0].statements();
+        assert_eq!(statements.len(), 4);
+        assert_eq!(statements[0].sql, "BEGIN");
+        assert_eq!(statements[3].sql, "COMMIT");
+        for (statement, rows) in statements[1..3].iter().zip([32767, 7233]) {
+            assert!(
+                statement
+                    .sql
+                    .contains(r#"ON CONFLICT ("id") DO NOTHING RETURNING"#)
+            );
+            assert_eq!(statement.values.as_ref().map(|v| v.0.len()), Some(rows * 2));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn try_insert_chunked() -> Result<(), DbErr> {
+        use crate::{DbBackend, MockDatabase, TryInsertResult};
+
+        let models: Vec<_> = (1..=40000)
+            .map(|id| cake::Model {
+                id,
+                name: format!("Cake {id}"),
+            })
+            .collect();
+
+        // every row of the first statement conflicts
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([Vec::new(), vec![models[39999].clone()]])
+            .into_connection();
+
+        let res = cake::Entity::insert_many(
+            models
+                .iter()
+                .cloned()
+                .map(IntoActiveModel::into_active_model),
+        )
+        .on_conflict_do_nothing()
+        .exec(&db)?;
+        assert!(matches!(res, TryInsertResult::Inserted(res) if res.last_insert_id == 40000));
+
+        let log = db.into_transaction_log();
+        let statements = log[0].statements();
+        assert_eq!(statements.len(), 4);
+        for (statement, rows) in statements[1..3].iter().zip([32767, 7233]) {
+            assert!(statement.sql.contains(r#"ON CONFLICT ("id") DO NOTHING"#));
+            assert_eq!(statement.values.as_ref().map(|v| v.0.len()), Some(rows * 2));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn insert_many_query() {
+        let mut insert = cake::Entity::insert_many([
+            cake::ActiveModel {
+                id: ActiveValue::set(1),
+                name: ActiveValue::set("Apple Pie".to_owned()),
+            },
+            cake::ActiveModel {
+                id: ActiveValue::set(2),
+                name: ActiveValue::set("Orange Scone".to_owned()),
+            },
+        ]);
+        QueryTrait::query(&mut insert).returning_col(cake::Column::Id);
+        assert_eq!(
+            insert.build(DbBackend::Postgres).to_string(),
+            r#"INSERT INTO "cake" ("id", "name") VALUES (1, 'Apple Pie'), (2, 'Orange Scone') RETURNING "id""#,
+        );
+    }
+
+    #[test]
+    fn insert_timestamps() {
+        use crate::{Value, tests_cfg::note, value::CurrentTimestamp};
+
+        let now = <i64 as CurrentTimestamp>::now();
+        let statement = Insert::one(note::ActiveModel {
+            body: Set("Buy milk".to_owned()),
+            created_at: Set(1),
+            ..Default::default()
+        })
+        .build(DbBackend::Postgres);
+        assert_eq!(
+            statement.sql,
+            r#"INSERT INTO "note" ("body", "created_at", "updated_at") VALUES ($1, $2, $3)"#,
+        );
+        let values = statement.values.unwrap().0;
+        assert_eq!(values[..2], [Value::from("Buy milk"), Value::from(1i64)]);
+        assert!(matches!(values[2], Value::BigInt(Some(updated_at)) if updated_at >= now));
+
+        let statement = Insert::many([
+            note::ActiveModel {
+                body: Set("Buy milk".to_owned()),
+                ..Default::default()
+            },
+            note::ActiveModel {
+                body: Set("Buy eggs".to_owned()),
+                ..Default::default()
+            },
+        ])
+        .build(DbBackend::Postgres);
+        assert_eq!(
+            statement.sql,
+            r#"INSERT INTO "note" ("body", "created_at", "updated_at") VALUES ($1, $2, $3), ($4, $5, $6)"#,
+        );
+        let values = statement.values.unwrap().0;
+        for i in [1, 2, 4, 5] {
+            assert!(matches!(values[i], Value::BigInt(Some(timestamp)) if timestamp >= now));
+        }
+    }
+    #[test]
+    fn upsert_timestamps() {
+        use crate::tests_cfg::note;
+
+        let model = || note::ActiveModel {
+            id: Set(1),
+            body: Set("Buy milk".to_owned()),
+            created_at: Set(1),
+            updated_at: Set(2),
+        };
+
+        assert_eq!(
+            Insert::one(model())
+                .on_conflict(
+                    OnConflict::column(note::Column::Id)
+                        .update_column(note::Column::Body)
+                        .to_owned()
+                )
+                .build(DbBackend::Postgres)
+                .to_string(),
+            r#"INSERT INTO "note" ("id", "body", "created_at", "updated_at") VALUES (1, 'Buy milk', 1, 2) ON CONFLICT ("id") DO UPDATE SET "body" = "excluded"."body", "updated_at" = "excluded"."updated_at""#,
+        );
+        assert_eq!(
+            Insert::many([model()])
+                .on_conflict(
+                    OnConflict::column(note::Column::Id)
+                        .update_columns([note::Column::Body, note::Column::UpdatedAt])
+                        .to_owned()
+                )
+                .build(DbBackend::Postgres)
+                .to_string(),
+            r#"INSERT INTO "note" ("id", "body", "created_at", "updated_at") VALUES (1, 'Buy milk', 1, 2) ON CONFLICT ("id") DO UPDATE SET "body" = "excluded"."body", "updated_at" = "excluded"."updated_at""#,
+        );
+        assert_eq!(
+            Insert::one(model())
+                .on_conflict(OnConflict::column(note::Column::Id).do_nothing().to_owned())
+                .build(DbBackend::Postgres)
+                .to_string(),
+            r#"INSERT INTO "note" ("id", "body", "created_at", "updated_at") VALUES (1, 'Buy milk', 1, 2) ON CONFLICT ("id") DO NOTHING"#,
+        );
+    }
 }
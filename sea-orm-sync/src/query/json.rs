@@ -247,6 +247,21 @@ impl FromQueryResult for JsonValue {
                 }
                 Ok(JsonValue::Object(map))
             }
+            #[cfg(feature = "duckdb")]
+            crate::QueryResultRow::Duckdb(row) => {
+                for (i, column) in row.columns.iter().enumerate() {
+                    let column = if !column.starts_with(pre) {
+                        continue;
+                    } else {
+                        column.replacen(pre, "", 1)
+                    };
+                    map.insert(
+                        column,
+                        crate::driver::duckdb::duckdb_value_to_json(&row.values[i]),
+                    );
+                }
+                Ok(JsonValue::Object(map))
+            }
             #[cfg(feature = "mock")]
             crate::QueryResultRow::Mock(row) => {
                 for (column, value) in row.clone().into_column_value_tuples() {
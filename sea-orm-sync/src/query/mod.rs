@@ -20,6 +20,7 @@
 //! macro.
 
 pub(crate) mod combine;
+mod copy;
 mod debug;
 mod delete;
 mod helper;
@@ -32,8 +33,10 @@ mod select;
 mod traits;
 mod update;
 mod util;
+mod where_condition;
 
 pub use combine::{SelectA, SelectB, SelectC};
+pub use copy::*;
 pub use debug::*;
 pub use delete::*;
 pub use helper::*;
@@ -45,6 +48,7 @@ pub use select::*;
 pub use traits::*;
 pub use update::*;
 pub(crate) use util::*;
+pub(crate) use where_condition::WhereCondition;
 
 pub use crate::{
     ConnectionTrait, CursorTrait, InsertResult, PaginatorTrait, SelectExt, Statement,
@@ -5,6 +5,7 @@ use crate::{
 use core::fmt::Debug;
 use core::marker::PhantomData;
 use sea_query::{FunctionCall, IntoColumnRef, SelectStatement, SimpleExpr};
+use std::{sync::OnceLock, time::Duration};
 
 /// A `SELECT` query against entity `E`. Returned by
 /// [`EntityTrait::find`](crate::EntityTrait::find); chain filters, joins,
@@ -19,6 +20,21 @@ where
     pub(crate) query: SelectStatement,
     pub(crate) entity: PhantomData<E>,
     pub(crate) linked_index: usize,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) trashed: Trashed,
+    pub(crate) unscoped: bool,
+    /// `query` with the conditions of the default scope and on the soft delete column
+    /// added, built by [`as_query`](QueryTrait::as_query) and cleared on every change
+    pub(crate) scoped: OnceLock<SelectStatement>,
+}
+
+/// Which soft deleted rows a [`Select`] returns, see [`EntityTrait::soft_delete_column`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Trashed {
+    #[default]
+    Exclude,
+    Include,
+    Only,
 }
 
 /// A `SELECT` joining two entities, yielding `(E::Model, Option<F::Model>)`
@@ -32,6 +48,7 @@ where
 {
     pub(crate) query: SelectStatement,
     pub(crate) entity: PhantomData<(E, F)>,
+    pub(crate) timeout: Option<Duration>,
 }
 
 /// A `SELECT` joining two entities, with results grouped into
@@ -45,6 +62,7 @@ where
 {
     pub(crate) query: SelectStatement,
     pub(crate) entity: PhantomData<(E, F)>,
+    pub(crate) timeout: Option<Duration>,
 }
 
 /// A `SELECT` joining two entities where both sides are required, yielding
@@ -57,6 +75,7 @@ where
 {
     pub(crate) query: SelectStatement,
     pub(crate) entity: PhantomData<(E, F)>,
+    pub(crate) timeout: Option<Duration>,
 }
 
 /// Marker trait describing how 3+ tables are joined: from one centre entity
@@ -87,6 +106,7 @@ where
 {
     pub(crate) query: SelectStatement,
     pub(crate) entity: PhantomData<(E, F, G, TOP)>,
+    pub(crate) timeout: Option<Duration>,
 }
 
 /// Like [`SelectThree`], but results are consolidated under the left model:
@@ -101,6 +121,7 @@ where
 {
     pub(crate) query: SelectStatement,
     pub(crate) entity: PhantomData<(E, F, G, TOP)>,
+    pub(crate) timeout: Option<Duration>,
 }
 
 /// Four-way join select.
@@ -115,6 +136,7 @@ where
 {
     pub(crate) query: SelectStatement,
     pub(crate) entity: PhantomData<(E, F, G, H, TOP)>,
+    pub(crate) timeout: Option<Duration>,
 }
 
 /// Like [`SelectFour`], but results are consolidated under the left model.
@@ -129,6 +151,7 @@ where
 {
     pub(crate) query: SelectStatement,
     pub(crate) entity: PhantomData<(E, F, G, H, TOP)>,
+    pub(crate) timeout: Option<Duration>,
 }
 
 /// Five-way join select.
@@ -144,6 +167,7 @@ where
 {
     pub(crate) query: SelectStatement,
     pub(crate) entity: PhantomData<(E, F, G, H, I, TOP)>,
+    pub(crate) timeout: Option<Duration>,
 }
 
 /// Six-way join select.
@@ -160,6 +184,7 @@ where
 {
     pub(crate) query: SelectStatement,
     pub(crate) entity: PhantomData<(E, F, G, H, I, J, TOP)>,
+    pub(crate) timeout: Option<Duration>,
 }
 
 /// Conversion into a [`SimpleExpr`]. Implemented for entity columns so they
@@ -186,6 +211,7 @@ macro_rules! impl_query_trait {
             type QueryStatement = SelectStatement;
 
             fn query(&mut self) -> &mut SelectStatement {
+                self.scoped.take();
                 &mut self.query
             }
         }
@@ -277,6 +303,10 @@ where
             query: SelectStatement::new(),
             entity: PhantomData,
             linked_index: 0,
+            timeout: None,
+            trashed: Trashed::Exclude,
+            unscoped: false,
+            scoped: OnceLock::new(),
         }
         .prepare_select()
         .prepare_from()
@@ -312,11 +342,113 @@ where
     pub fn order_by_id(mut self, order: Order) -> Self {
         for key in E::PrimaryKey::iter() {
             let col = key.into_column();
-            self.query
-                .order_by_expr(col.into_simple_expr(), order.clone());
+            QueryTrait::query(&mut self).order_by_expr(col.into_simple_expr(), order.clone());
         }
         self
     }
+
+    /// Cancel the query once it has run for longer than `timeout`, failing with
+    /// [`DbErr::QueryTimeout`](crate::DbErr::QueryTimeout). Enforced by the database,
+    /// see [`ConnectionTrait::query_all_raw_with_timeout`](crate::ConnectionTrait::query_all_raw_with_timeout).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Also return the rows that are soft deleted, see [`EntityTrait::soft_delete_column`]
+    ///
+    /// ```
+    /// # #[cfg(feature = "with-chrono")]
+    /// # {
+    /// use sea_orm::{DbBackend, entity::*, query::*, tests_cfg::cake_review};
+    ///
+    /// assert_eq!(
+    ///     cake_review::Entity::find().build(DbBackend::Postgres).to_string(),
+    ///     r#"SELECT "cake_review"."id", "cake_review"."cake_id", "cake_review"."body", "cake_review"."deleted_at" FROM "cake_review" WHERE "cake_review"."deleted_at" IS NULL"#
+    /// );
+    /// assert_eq!(
+    ///     cake_review::Entity::find()
+    ///         .with_trashed()
+    ///         .build(DbBackend::Postgres)
+    ///         .to_string(),
+    ///     r#"SELECT "cake_review"."id", "cake_review"."cake_id", "cake_review"."body", "cake_review"."deleted_at" FROM "cake_review""#
+    /// );
+    /// # }
+    /// ```
+    pub fn with_trashed(mut self) -> Self {
+        self.trashed = Trashed::Include;
+        self.scoped.take();
+        self
+    }
+
+    /// Only return the rows that are soft deleted, see [`EntityTrait::soft_delete_column`]
+    ///
+    /// ```
+    /// # #[cfg(feature = "with-chrono")]
+    /// # {
+    /// use sea_orm::{DbBackend, entity::*, query::*, tests_cfg::cake_review};
+    ///
+    /// assert_eq!(
+    ///     cake_review::Entity::find()
+    ///         .only_trashed()
+    ///         .build(DbBackend::Postgres)
+    ///         .to_string(),
+    ///     r#"SELECT "cake_review"."id", "cake_review"."cake_id", "cake_review"."body", "cake_review"."deleted_at" FROM "cake_review" WHERE "cake_review"."deleted_at" IS NOT NULL"#
+    /// );
+    /// # }
+    /// ```
+    pub fn only_trashed(mut self) -> Self {
+        self.trashed = Trashed::Only;
+        self.scoped.take();
+        self
+    }
+
+    /// Leave out the [default scope](EntityTrait::default_scope) of the entity. Soft
+    /// deleted rows are still left out, unless asked for with [`with_trashed`](Self::with_trashed).
+    ///
+    /// ```
+    /// use sea_orm::{DbBackend, entity::*, query::*, tests_cfg::article};
+    ///
+    /// assert_eq!(
+    ///     article::Entity::find().build(DbBackend::Postgres).to_string(),
+    ///     r#"SELECT "article"."id", "article"."user_id", "article"."title", "article"."published" FROM "article" WHERE "article"."published" = TRUE"#
+    /// );
+    /// assert_eq!(
+    ///     article::Entity::find()
+    ///         .unscoped()
+    ///         .build(DbBackend::Postgres)
+    ///         .to_string(),
+    ///     r#"SELECT "article"."id", "article"."user_id", "article"."title", "article"."published" FROM "article""#
+    /// );
+    /// ```
+    pub fn unscoped(mut self) -> Self {
+        self.unscoped = true;
+        self.scoped.take();
+        self
+    }
+
+    /// Add the conditions of the [default scope](EntityTrait::default_scope) and on the
+    /// [soft delete column](EntityTrait::soft_delete_column) to `query`, as configured.
+    /// They are added with `and_where`, the same as the caller may have done through
+    /// [`query`](QueryTrait::query).
+    fn add_scope(&self, query: &mut SelectStatement) {
+        if !self.unscoped
+            && let Some(scope) = E::default_scope()
+        {
+            query.and_where(scope.into());
+        }
+        if let Some(col) = E::soft_delete_column() {
+            match self.trashed {
+                Trashed::Exclude => {
+                    query.and_where(col.is_null());
+                }
+                Trashed::Include => {}
+                Trashed::Only => {
+                    query.and_where(col.is_not_null());
+                }
+            }
+        }
+    }
 }
 
 impl<E> QueryTrait for Select<E>
@@ -325,13 +457,26 @@ where
 {
     type QueryStatement = SelectStatement;
     fn query(&mut self) -> &mut SelectStatement {
+        self.scoped.take();
         &mut self.query
     }
+    /// The statement with the conditions of the [default scope](EntityTrait::default_scope)
+    /// and on the [soft delete column](EntityTrait::soft_delete_column) added, which are
+    /// only added once the statement is taken, as a condition cannot be taken back
     fn as_query(&self) -> &SelectStatement {
-        &self.query
+        self.scoped.get_or_init(|| {
+            let mut query = self.query.clone();
+            self.add_scope(&mut query);
+            query
+        })
     }
-    fn into_query(self) -> SelectStatement {
-        self.query
+    fn into_query(mut self) -> SelectStatement {
+        if let Some(query) = self.scoped.take() {
+            return query;
+        }
+        let mut query = core::mem::take(&mut self.query);
+        self.add_scope(&mut query);
+        query
     }
 }
 
@@ -359,3 +504,55 @@ macro_rules! select_two {
 select_two!(SelectTwo);
 select_two!(SelectTwoMany);
 select_two!(SelectTwoRequired);
+
+#[cfg(test)]
+mod tests {
+    use crate::tests_cfg::article;
+    use crate::{ColumnTrait, DbBackend, EntityTrait, QueryFilter, QueryTrait};
+    use pretty_assertions::assert_eq;
+    use sea_query::{ConditionalStatement, ExprTrait};
+
+    #[test]
+    fn scope_after_and_where() {
+        let mut select = article::Entity::find();
+        QueryTrait::query(&mut select).and_where(article::Column::UserId.into_expr().eq(1));
+        assert_eq!(
+            select
+                .filter(article::Column::Title.contains("sea"))
+                .build(DbBackend::Postgres)
+                .to_string(),
+            [
+                r#"SELECT "article"."id", "article"."user_id", "article"."title", "article"."published" FROM "article""#,
+                r#"WHERE "article"."user_id" = 1 AND "article"."title" LIKE '%sea%' AND "article"."published" = TRUE"#,
+            ]
+            .join(" ")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "with-chrono")]
+    fn soft_delete_after_and_where() {
+        use crate::tests_cfg::cake_review;
+
+        let mut select = cake_review::Entity::find();
+        QueryTrait::query(&mut select).and_where(cake_review::Column::CakeId.into_expr().eq(1));
+        assert_eq!(
+            select.build(DbBackend::Postgres).to_string(),
+            [
+                r#"SELECT "cake_review"."id", "cake_review"."cake_id", "cake_review"."body", "cake_review"."deleted_at" FROM "cake_review""#,
+                r#"WHERE "cake_review"."cake_id" = 1 AND "cake_review"."deleted_at" IS NULL"#,
+            ]
+            .join(" ")
+        );
+        let mut select = cake_review::Entity::find().only_trashed();
+        QueryTrait::query(&mut select).and_where(cake_review::Column::CakeId.into_expr().eq(1));
+        assert_eq!(
+            select.build(DbBackend::Postgres).to_string(),
+            [
+                r#"SELECT "cake_review"."id", "cake_review"."cake_id", "cake_review"."body", "cake_review"."deleted_at" FROM "cake_review""#,
+                r#"WHERE "cake_review"."cake_id" = 1 AND "cake_review"."deleted_at" IS NOT NULL"#,
+            ]
+            .join(" ")
+        );
+    }
+}
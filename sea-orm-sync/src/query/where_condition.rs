@@ -0,0 +1,203 @@
+use sea_query::{
+    BinOper, Condition, ConditionHolder, ConditionHolderContents, DeleteStatement, EscapeBuilder,
+    ExplainStatement, Expr, ExprTrait, LogicalChainOper, Oper, OperLeftAssocDecider,
+    PrecedenceDecider, QueryBuilder, Quote, QuotedBuilder, SelectInto, SelectStatement, SqlWriter,
+    SubQueryStatement, TableRef, TableRefBuilder, UnionType, UpdateStatement,
+};
+use std::cell::{Cell, RefCell};
+
+/// The `WHERE` condition of a `SELECT`, `UPDATE` or `DELETE`, which sea-query keeps
+/// to itself. Soft deletes turn it into an `UPDATE`, and
+/// [`ShardedConnection`](crate::ShardedConnection) routes by it.
+pub(crate) trait WhereCondition {
+    /// The condition the rows of the statement are filtered by, empty if they are not
+    fn where_condition(&self) -> Condition;
+}
+
+macro_rules! impl_where_condition {
+    ($($stmt: ty),*) => {
+        $(
+            impl WhereCondition for $stmt {
+                fn where_condition(&self) -> Condition {
+                    let finder = WhereFinder::default();
+                    self.build_any(&finder);
+                    finder.into_condition()
+                }
+            }
+        )*
+    };
+}
+
+impl_where_condition!(SelectStatement, UpdateStatement, DeleteStatement);
+
+/// A query builder that writes nothing, but keeps the `WHERE` condition of the
+/// statement it builds. Subqueries are never built, so theirs are not mistaken for it.
+#[derive(Default)]
+struct WhereFinder {
+    condition: RefCell<Option<Condition>>,
+    union: Cell<bool>,
+}
+
+impl WhereFinder {
+    fn into_condition(self) -> Condition {
+        match self.condition.into_inner() {
+            // the rows of a union are not all filtered by its first `SELECT`
+            Some(condition) if !self.union.get() => condition,
+            _ => Condition::all(),
+        }
+    }
+}
+
+impl QueryBuilder for WhereFinder {
+    fn prepare_condition(
+        &self,
+        condition: &ConditionHolder,
+        keyword: &str,
+        _: &mut impl SqlWriter,
+    ) {
+        if keyword != "WHERE" {
+            return;
+        }
+        let condition = match &condition.contents {
+            ConditionHolderContents::Empty => Condition::all(),
+            ConditionHolderContents::Condition(condition) => condition.clone(),
+            // AND binds tighter than OR: `a AND b OR c` is `(a AND b) OR c`
+            ConditionHolderContents::Chain(chain) => {
+                let mut any: Vec<Expr> = Vec::new();
+                for (i, oper) in chain.iter().enumerate() {
+                    match oper {
+                        LogicalChainOper::Or(expr) if i > 0 => any.push(expr.clone()),
+                        LogicalChainOper::And(expr) | LogicalChainOper::Or(expr) => {
+                            let all = match any.pop() {
+                                Some(all) => all.and(expr.clone()),
+                                None => expr.clone(),
+                            };
+                            any.push(all);
+                        }
+                    }
+                }
+                Condition::all().add_option(any.into_iter().reduce(|any, all| any.or(all)))
+            }
+        };
+        self.condition.borrow_mut().get_or_insert(condition);
+    }
+
+    fn prepare_union_statement(&self, _: UnionType, _: &SelectStatement, _: &mut impl SqlWriter) {
+        self.union.set(true);
+    }
+
+    fn prepare_table_ref(&self, _: &TableRef, _: &mut impl SqlWriter) {}
+
+    fn prepare_query_statement(&self, _: &SubQueryStatement, _: &mut impl SqlWriter) {}
+
+    fn prepare_select_into(&self, _: &SelectInto, _: &mut impl SqlWriter) {}
+
+    fn prepare_explain_statement(&self, _: &ExplainStatement, _: &mut impl SqlWriter) {}
+
+    fn prepare_value(&self, _: sea_query::Value, _: &mut impl SqlWriter) {}
+}
+
+impl QuotedBuilder for WhereFinder {
+    fn quote(&self) -> Quote {
+        Quote::new(b'"')
+    }
+}
+
+impl EscapeBuilder for WhereFinder {}
+
+impl TableRefBuilder for WhereFinder {}
+
+impl OperLeftAssocDecider for WhereFinder {
+    fn well_known_left_associative(&self, _: &BinOper) -> bool {
+        false
+    }
+}
+
+impl PrecedenceDecider for WhereFinder {
+    fn inner_expr_well_known_greater_precedence(&self, _: &Expr, _: &Oper) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests_cfg::{cake, fruit};
+    use crate::{ColumnTrait, EntityTrait, QueryFilter, QueryTrait};
+    use pretty_assertions::assert_eq;
+    use sea_query::{Alias, PostgresQueryBuilder, Query};
+
+    fn where_sql(stmt: &impl WhereCondition) -> String {
+        Query::select()
+            .expr(Expr::val(1))
+            .cond_where(stmt.where_condition())
+            .to_string(PostgresQueryBuilder)
+    }
+
+    #[test]
+    fn where_condition_of_statement() {
+        let subquery = Query::select()
+            .column(fruit::Column::CakeId)
+            .from(fruit::Entity)
+            .and_where(fruit::Column::Name.eq("apple"))
+            .to_owned();
+        assert_eq!(
+            where_sql(
+                &cake::Entity::find()
+                    .filter(cake::Column::Id.in_subquery(subquery.clone()))
+                    .filter(cake::Column::Name.eq("cheese"))
+                    .into_query()
+            ),
+            [
+                r#"SELECT 1 WHERE "cake"."id" IN (SELECT "cake_id" FROM "fruit" WHERE "fruit"."name" = 'apple')"#,
+                r#"AND "cake"."name" = 'cheese'"#,
+            ]
+            .join(" ")
+        );
+
+        // only the condition of the statement itself, not of the tables it reads
+        assert_eq!(
+            where_sql(
+                &Query::select()
+                    .column(cake::Column::Id)
+                    .from_subquery(subquery.clone(), Alias::new("apple"))
+                    .to_owned()
+            ),
+            "SELECT 1 WHERE TRUE"
+        );
+        // the rows of a union are not all filtered by the condition
+        assert_eq!(
+            where_sql(
+                &Query::select()
+                    .column(cake::Column::Id)
+                    .from(cake::Entity)
+                    .and_where(cake::Column::Id.eq(1))
+                    .union(UnionType::All, subquery)
+                    .to_owned()
+            ),
+            "SELECT 1 WHERE TRUE"
+        );
+
+        assert_eq!(
+            where_sql(
+                &cake::Entity::delete_many()
+                    .filter(cake::Column::Id.eq(1))
+                    .into_query()
+            ),
+            r#"SELECT 1 WHERE "cake"."id" = 1"#
+        );
+        // AND binds tighter than OR in a chain of conditions
+        assert_eq!(
+            where_sql(
+                &Query::update()
+                    .table(cake::Entity)
+                    .value(cake::Column::Name, "cheese")
+                    .and_or_where(LogicalChainOper::And(cake::Column::Id.eq(1)))
+                    .and_or_where(LogicalChainOper::And(cake::Column::Name.eq("apple")))
+                    .and_or_where(LogicalChainOper::Or(cake::Column::Id.eq(2)))
+                    .to_owned()
+            ),
+            r#"SELECT 1 WHERE ("cake"."id" = 1 AND "cake"."name" = 'apple') OR "cake"."id" = 2"#
+        );
+    }
+}
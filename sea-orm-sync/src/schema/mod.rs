@@ -13,7 +13,7 @@ mod builder;
 mod entity;
 #[cfg(feature = "serde_json")]
 mod json;
-mod topology;
+pub(crate) mod topology;
 
 pub use builder::*;
 use topology::*;
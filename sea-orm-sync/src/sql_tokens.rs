@@ -0,0 +1,147 @@
+//! A lightweight SQL tokenizer, for the scans of statements that do not need a parser:
+//! metrics, read replica routing and the shard routing of raw SQL.
+
+/// An identifier or keyword
+#[derive(Debug)]
+pub(crate) struct Word<'a> {
+    pub text: &'a str,
+    /// Whether it was quoted, and thus is an identifier
+    pub quoted: bool,
+    /// How many parentheses it is nested in
+    pub depth: usize,
+}
+
+#[derive(Debug)]
+pub(crate) enum Token<'a> {
+    Word(Word<'a>),
+    Dot,
+    Comma,
+    Open,
+    Close,
+    /// `=`, on its own rather than part of `<=`, `>=` or `!=`
+    Equals,
+    /// A bound value: `$1` numbered from one, or `?` by its position
+    Placeholder(Option<usize>),
+    /// Literals, operators and the like
+    Other,
+}
+
+impl Word<'_> {
+    /// Whether this is `keyword`, which must be unquoted
+    pub fn is_keyword(&self, keyword: &str) -> bool {
+        !self.quoted && self.text.eq_ignore_ascii_case(keyword)
+    }
+}
+
+/// Split `sql` into identifiers and keywords, skipping string literals and comments
+pub(crate) fn tokenize(sql: &str) -> Vec<Token<'_>> {
+    let bytes = sql.as_bytes();
+    let mut tokens = Vec::new();
+    let mut depth = 0usize;
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        match c {
+            b'\'' => {
+                i += 1;
+                while i < bytes.len() {
+                    if bytes[i] == b'\'' {
+                        if bytes.get(i + 1) == Some(&b'\'') {
+                            i += 1;
+                        } else {
+                            break;
+                        }
+                    }
+                    i += 1;
+                }
+                i += 1;
+                tokens.push(Token::Other);
+            }
+            b'"' | b'`' => {
+                let start = i + 1;
+                i = start;
+                while i < bytes.len() && bytes[i] != c {
+                    i += 1;
+                }
+                tokens.push(Token::Word(Word {
+                    text: &sql[start..i],
+                    quoted: true,
+                    depth,
+                }));
+                i += 1;
+            }
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i += 2;
+                while i < bytes.len() && !(bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/')) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            b'(' => {
+                depth += 1;
+                i += 1;
+                tokens.push(Token::Open);
+            }
+            b')' => {
+                depth = depth.saturating_sub(1);
+                i += 1;
+                tokens.push(Token::Close);
+            }
+            b'.' => {
+                i += 1;
+                tokens.push(Token::Dot);
+            }
+            b',' => {
+                i += 1;
+                tokens.push(Token::Comma);
+            }
+            b'=' => {
+                i += 1;
+                match tokens.last() {
+                    Some(Token::Other) if matches!(bytes[i - 2], b'<' | b'>' | b'!') => {}
+                    _ => tokens.push(Token::Equals),
+                }
+            }
+            b'?' => {
+                i += 1;
+                tokens.push(Token::Placeholder(None));
+            }
+            b'$' if bytes.get(i + 1).is_some_and(u8::is_ascii_digit) => {
+                let start = i + 1;
+                i = start;
+                while i < bytes.len() && bytes[i].is_ascii_digit() {
+                    i += 1;
+                }
+                tokens.push(Token::Placeholder(sql[start..i].parse().ok()));
+            }
+            c if c.is_ascii_alphabetic() || c == b'_' => {
+                let start = i;
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                    i += 1;
+                }
+                tokens.push(Token::Word(Word {
+                    text: &sql[start..i],
+                    quoted: false,
+                    depth,
+                }));
+            }
+            c if c.is_ascii_whitespace() => i += 1,
+            _ => {
+                // multi-byte characters are never part of a keyword, skip them whole
+                i += sql[i..].chars().next().map_or(1, char::len_utf8);
+                tokens.push(Token::Other);
+            }
+        }
+    }
+    tokens
+}
+
+/// Whether `tokens[i]` is `keyword`
+pub(crate) fn is_keyword(tokens: &[Token<'_>], i: usize, keyword: &str) -> bool {
+    matches!(tokens.get(i), Some(Token::Word(word)) if word.is_keyword(keyword))
+}
@@ -0,0 +1,37 @@
+use crate as sea_orm;
+use crate::entity::prelude::*;
+
+#[sea_orm::compact_model]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "cake_review", soft_delete = "deleted_at")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub cake_id: i32,
+    pub body: String,
+    pub deleted_at: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::cake::Entity",
+        from = "Column::CakeId",
+        to = "super::cake::Column::Id"
+    )]
+    Cake,
+}
+
+impl Related<super::cake::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Cake.def()
+    }
+}
+
+impl Related<Entity> for super::cake::Entity {
+    fn to() -> RelationDef {
+        Relation::Cake.def().rev()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,6 +1,10 @@
 use super::impl_timestamp;
 use crate as sea_orm;
-use crate::{DbErr, TryGetError, prelude::TimeDateTimeWithTimeZone};
+use crate::{
+    DbErr, TryGetError,
+    prelude::{TimeDateTime, TimeDateTimeWithTimeZone},
+    value::CurrentTimestamp,
+};
 use std::ops::{Deref, DerefMut};
 
 /// An `OffsetDateTime` stored as a Unix timestamp (seconds) `i64` in the database.
@@ -33,6 +37,31 @@ super::impl_serde_with_i64!(
     to_timestamp_millis
 );
 
+impl CurrentTimestamp for TimeDateTime {
+    fn now() -> Self {
+        let now = TimeDateTimeWithTimeZone::now_utc();
+        Self::new(now.date(), now.time())
+    }
+}
+
+impl CurrentTimestamp for TimeDateTimeWithTimeZone {
+    fn now() -> Self {
+        Self::now_utc()
+    }
+}
+
+impl CurrentTimestamp for TimeUnixTimestamp {
+    fn now() -> Self {
+        Self(TimeDateTimeWithTimeZone::now_utc())
+    }
+}
+
+impl CurrentTimestamp for TimeUnixTimestampMillis {
+    fn now() -> Self {
+        Self(TimeDateTimeWithTimeZone::now_utc())
+    }
+}
+
 fn from_timestamp(ts: i64) -> Option<TimeUnixTimestamp> {
     TimeDateTimeWithTimeZone::from_unix_timestamp(ts)
         .ok()
@@ -0,0 +1,59 @@
+#![allow(unused_imports, dead_code)]
+
+pub mod common;
+
+pub use common::{TestContext, features::*, setup::*};
+use pretty_assertions::assert_eq;
+use sea_orm::{DatabaseConnection, entity::prelude::*, entity::*};
+
+#[sea_orm_macros::test]
+fn main() -> Result<(), DbErr> {
+    let ctx = TestContext::new("advisory_lock_tests");
+    create_insert_default_table(&ctx.db)?;
+    work_through_lock(&ctx.db)?;
+    #[cfg(any(feature = "sqlx-mysql", feature = "sqlx-postgres"))]
+    lock_is_exclusive(&ctx.db)?;
+    ctx.delete();
+
+    Ok(())
+}
+
+pub fn work_through_lock(db: &DatabaseConnection) -> Result<(), DbErr> {
+    use insert_default::*;
+
+    let lock = db.advisory_lock("insert_default")?;
+    assert_eq!(lock.key(), "insert_default");
+    ActiveModel { id: Set(1) }.insert(&*lock)?;
+    lock.commit()?;
+
+    // dropping the guard rolls back the work done through it
+    let lock = db.advisory_lock("insert_default")?;
+    ActiveModel { id: Set(2) }.insert(&*lock)?;
+    drop(lock);
+
+    assert_eq!(Entity::find().all(db)?, [Model { id: 1 }]);
+
+    Ok(())
+}
+
+pub fn lock_is_exclusive(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let lock = db.advisory_lock("leader")?;
+    assert!(db.try_advisory_lock("leader")?.is_none());
+
+    // a different key is not held
+    let other = db.try_advisory_lock("follower")?;
+    assert!(other.is_some());
+    drop(other);
+
+    // committing the guard releases the lock, and so does dropping it
+    lock.commit()?;
+    let lock = db.try_advisory_lock("leader")?;
+    assert!(lock.is_some());
+    drop(lock);
+    let lock = db
+        .try_advisory_lock("leader")?
+        .expect("the lock is released when its guard is dropped");
+    lock.commit()?;
+
+    Ok(())
+}
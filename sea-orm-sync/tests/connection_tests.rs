@@ -170,3 +170,72 @@ pub fn rusqlite_connects_file_uri_with_sqlite_query_parameters() {
 
     db.ping().unwrap();
 }
+
+#[sea_orm_macros::test]
+pub fn connection_metric_info() {
+    use sea_orm::{ConnectionTrait, Statement, TransactionTrait, metric::Operation};
+    use std::sync::{Arc, Mutex};
+
+    let ctx = TestContext::new("connection_metric_info");
+    let mut db = ctx.db.clone();
+    let backend = db.get_database_backend();
+
+    let infos = Arc::new(Mutex::new(Vec::new()));
+    let sink = infos.clone();
+    db.set_metric_callback(move |info| {
+        sink.lock().unwrap().push((
+            info.operation(),
+            info.tables(),
+            info.rows,
+            info.in_transaction,
+            info.acquire_elapsed.is_some(),
+        ))
+    });
+
+    let stmt = |sql: &str| Statement::from_string(backend, sql);
+    db.execute_raw(stmt("CREATE TABLE metric_info (id integer)"))
+        .unwrap();
+    db.execute_raw(stmt("INSERT INTO metric_info (id) VALUES (1), (2)"))
+        .unwrap();
+    db.query_all_raw(stmt("SELECT id FROM metric_info"))
+        .unwrap();
+    let txn = db.begin().unwrap();
+    txn.execute_raw(stmt("DELETE FROM metric_info WHERE id = 1"))
+        .unwrap();
+    txn.commit().unwrap();
+
+    let table = || vec!["metric_info".to_owned()];
+    assert_eq!(
+        infos.lock().unwrap()[..],
+        [
+            (Operation::Ddl, table(), Some(0), false, true),
+            (Operation::Insert, table(), Some(2), false, true),
+            (Operation::Select, table(), Some(2), false, true),
+            (Operation::Delete, table(), Some(1), true, false),
+        ]
+    );
+
+    ctx.delete();
+}
+
+#[sea_orm_macros::test]
+pub fn connection_pool_status() {
+    let ctx = TestContext::new("connection_pool_status");
+
+    let status = ctx.db.pool_status();
+    if cfg!(any(
+        feature = "sqlx-mysql",
+        feature = "sqlx-postgres",
+        feature = "sqlx-sqlite"
+    )) {
+        let status = status.expect("pool-backed connection");
+        assert!(status.size >= 1);
+        assert!(status.idle <= status.size);
+        assert!(status.size <= status.max_size);
+        assert_eq!(status.waiters, 0);
+    } else {
+        assert_eq!(status, None);
+    }
+
+    ctx.delete();
+}
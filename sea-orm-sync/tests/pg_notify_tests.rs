@@ -0,0 +1,41 @@
+#![allow(unused_imports, dead_code)]
+
+pub mod common;
+
+pub use common::{TestContext, setup::*};
+use pretty_assertions::assert_eq;
+use sea_orm::{DbErr, TransactionTrait};
+
+#[sea_orm_macros::test]
+#[cfg(feature = "sqlx-postgres")]
+fn listen_notify() -> Result<(), DbErr> {
+    use sea_orm::PgNotification;
+
+    let ctx = TestContext::new("listen_notify_tests");
+    let mut notifications = ctx.db.listen(&["jobs", "cache"])?;
+
+    ctx.db.notify("jobs", "42")?;
+
+    // Only sent once the transaction commits
+    let txn = ctx.db.begin()?;
+    txn.notify("cache", "cake")?;
+    txn.rollback()?;
+
+    let txn = ctx.db.begin()?;
+    txn.notify("cache", "fruit")?;
+    txn.commit()?;
+
+    let mut next = || {
+        let PgNotification {
+            channel, payload, ..
+        } = notifications.next().unwrap()?;
+        Ok::<_, DbErr>((channel, payload))
+    };
+    assert_eq!(next()?, ("jobs".to_owned(), "42".to_owned()));
+    assert_eq!(next()?, ("cache".to_owned(), "fruit".to_owned()));
+
+    drop(notifications);
+    ctx.delete();
+
+    Ok(())
+}
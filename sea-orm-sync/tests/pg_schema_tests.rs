@@ -0,0 +1,70 @@
+#![allow(unused_imports, dead_code)]
+
+pub mod common;
+
+pub use common::{TestContext, features::*, setup::*};
+use pretty_assertions::assert_eq;
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, DbErr, Schema, Statement, TransactionTrait,
+    entity::prelude::*, entity::*,
+};
+
+#[sea_orm_macros::test]
+#[cfg(feature = "sqlx-postgres")]
+fn main() -> Result<(), DbErr> {
+    let ctx = TestContext::new("pg_schema_tests");
+    let tenant_a = create_tenant(&ctx.db, "tenant_a")?;
+    let tenant_b = create_tenant(&ctx.db, "tenant_b")?;
+    tenants_are_isolated(&tenant_a, &tenant_b)?;
+    search_path_is_not_leaked(&ctx.db)?;
+    ctx.delete();
+
+    Ok(())
+}
+
+pub fn create_tenant(db: &DatabaseConnection, schema: &str) -> Result<DatabaseConnection, DbErr> {
+    db.execute_unprepared(&format!(r#"CREATE SCHEMA "{schema}""#))?;
+    let tenant = db.with_schema(schema)?;
+    let backend = tenant.get_database_backend();
+    tenant.execute(&Schema::new(backend).create_table_from_entity(insert_default::Entity))?;
+
+    Ok(tenant)
+}
+
+pub fn tenants_are_isolated(
+    tenant_a: &DatabaseConnection,
+    tenant_b: &DatabaseConnection,
+) -> Result<(), DbErr> {
+    use insert_default::*;
+
+    ActiveModel { id: Set(1) }.insert(tenant_a)?;
+    let txn = tenant_b.begin()?;
+    ActiveModel { id: Set(2) }.insert(&txn)?;
+    ActiveModel { id: Set(3) }.insert(&txn)?;
+    txn.commit()?;
+
+    assert_eq!(Entity::find().all(tenant_a)?, [Model { id: 1 }]);
+    assert_eq!(
+        Entity::find()
+            .stream(tenant_b)?
+            .collect::<Result<Vec<_>, DbErr>>()?,
+        [Model { id: 2 }, Model { id: 3 }]
+    );
+
+    Ok(())
+}
+
+pub fn search_path_is_not_leaked(db: &DatabaseConnection) -> Result<(), DbErr> {
+    for _ in 0..5 {
+        let row = db
+            .query_one_raw(Statement::from_string(
+                db.get_database_backend(),
+                "SELECT current_setting('search_path') AS search_path",
+            ))?
+            .unwrap();
+        let search_path: String = row.try_get("", "search_path")?;
+        assert!(!search_path.contains("tenant"), "{search_path}");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+#![allow(unused_imports, dead_code)]
+
+pub mod common;
+
+pub use common::{TestContext, features::*, setup::*};
+use pretty_assertions::assert_eq;
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, Statement, TransactionTrait,
+    entity::prelude::*,
+    entity::*,
+    query::*,
+    sea_query::{Expr, ExprTrait},
+};
+use std::time::Duration;
+
+#[sea_orm_macros::test]
+fn main() -> Result<(), DbErr> {
+    let ctx = TestContext::new("query_timeout_tests");
+    create_insert_default_table(&ctx.db)?;
+    query_within_timeout(&ctx.db)?;
+    #[cfg(any(
+        feature = "sqlx-sqlite",
+        feature = "sqlx-postgres",
+        feature = "rusqlite"
+    ))]
+    query_exceeds_timeout(&ctx.db)?;
+    #[cfg(any(feature = "sqlx-sqlite", feature = "sqlx-postgres"))]
+    stream_exceeds_timeout(&ctx.db)?;
+    ctx.delete();
+
+    Ok(())
+}
+
+pub fn query_within_timeout(db: &DatabaseConnection) -> Result<(), DbErr> {
+    use insert_default::*;
+
+    let timeout = Duration::from_secs(10);
+
+    Entity::insert_many([
+        ActiveModel { id: Set(1) },
+        ActiveModel { id: Set(2) },
+        ActiveModel { id: Set(3) },
+    ])
+    .timeout(timeout)
+    .exec(db)?;
+
+    Entity::update_many()
+        .col_expr(Column::Id, Expr::col(Column::Id).add(10))
+        .filter(Column::Id.eq(3))
+        .timeout(timeout)
+        .exec(db)?;
+
+    Entity::delete_many()
+        .filter(Column::Id.eq(2))
+        .timeout(timeout)
+        .exec(db)?;
+
+    assert_eq!(
+        Entity::find()
+            .order_by_asc(Column::Id)
+            .timeout(timeout)
+            .all(db)?,
+        [Model { id: 1 }, Model { id: 13 }]
+    );
+
+    let txn = db.begin()?;
+    assert_eq!(
+        Entity::find_by_id(1).timeout(timeout).one(&txn)?,
+        Some(Model { id: 1 })
+    );
+    txn.commit()?;
+
+    Ok(())
+}
+
+pub fn query_exceeds_timeout(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let timeout = Duration::from_millis(50);
+    let stmt = Statement::from_string(
+        db.get_database_backend(),
+        "WITH RECURSIVE c(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM c WHERE n < 1000000000) SELECT COUNT(*) FROM c",
+    );
+
+    assert!(matches!(
+        db.query_one_raw_with_timeout(stmt, timeout),
+        Err(DbErr::QueryTimeout(t)) if t == timeout
+    ));
+
+    // the connection is usable again, without a limit
+    let stmt = Statement::from_string(db.get_database_backend(), "SELECT 1 AS one");
+    assert!(db.query_one_raw(stmt)?.is_some());
+
+    Ok(())
+}
+
+pub fn stream_exceeds_timeout(db: &DatabaseConnection) -> Result<(), DbErr> {
+    use insert_default::*;
+
+    let timeout = Duration::from_millis(50);
+    let res: Result<Vec<i64>, DbErr> = Entity::find()
+        .select_only()
+        .expr(Expr::cust(
+            "(WITH RECURSIVE c(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM c WHERE n < 1000000000) SELECT COUNT(*) FROM c)",
+        ))
+        .into_tuple::<i64>()
+        .timeout(timeout)
+        .stream(db)
+        ?
+        .collect()
+        ;
+    assert!(matches!(res, Err(DbErr::QueryTimeout(t)) if t == timeout));
+
+    // the pool hands out connections without a limit
+    let stmt = Statement::from_string(db.get_database_backend(), "SELECT 1 AS one");
+    assert!(db.query_one_raw(stmt)?.is_some());
+
+    Ok(())
+}
@@ -41,6 +41,11 @@ pub fn test_error(db: &DatabaseConnection) {
         error.sql_err(),
         Some(SqlErr::UniqueConstraintViolation(_))
     ));
+    #[cfg(any(feature = "sqlx-postgres", feature = "sqlx-sqlite"))]
+    assert!(matches!(
+        error.sql_err(),
+        Some(SqlErr::UniqueConstraintViolation(detail)) if detail.table.as_deref() == Some("cake")
+    ));
 
     let fk_cake = cake::ActiveModel {
         name: Set("fk error Cake".to_owned()),
@@ -60,6 +65,24 @@ pub fn test_error(db: &DatabaseConnection) {
         Some(SqlErr::ForeignKeyConstraintViolation(_))
     ));
 
+    let nameless_cake = cake::ActiveModel {
+        price: Set(rust_dec(10.25)),
+        gluten_free: Set(false),
+        serial: Set(Uuid::new_v4()),
+        ..Default::default()
+    };
+
+    let not_null_error = nameless_cake
+        .insert(db)
+        .expect_err("inserting should fail due to missing name");
+
+    match not_null_error.sql_err() {
+        Some(SqlErr::NotNullViolation(detail)) => {
+            assert_eq!(detail.column.as_deref(), Some("name"));
+        }
+        err => panic!("expected a not null violation, got {err:?}"),
+    }
+
     let invalid_error = DbErr::Custom("random error".to_string());
     assert_eq!(invalid_error.sql_err(), None)
 }
@@ -939,3 +939,36 @@ pub fn transaction_begin_with_options() -> Result<(), DbErr> {
     ctx.delete();
     Ok(())
 }
+
+#[sea_orm_macros::test]
+pub fn transaction_named_savepoint() -> Result<(), DbErr> {
+    let ctx = TestContext::new("transaction_named_savepoint_test");
+    create_bakery_table(&ctx.db)?;
+
+    let txn = ctx.db.begin()?;
+
+    seaside_bakery().save(&txn)?;
+
+    txn.savepoint("before_import")?;
+    top_bakery().save(&txn)?;
+    assert_eq!(bakery::Entity::find().all(&txn)?.len(), 2);
+
+    // Skip the bad row, keeping the work done before the savepoint
+    txn.rollback_to("before_import")?;
+    assert_eq!(bakery::Entity::find().all(&txn)?.len(), 1);
+
+    // The savepoint survives a rollback to it
+    top_bakery().save(&txn)?;
+    txn.rollback_to("before_import")?;
+    assert_eq!(bakery::Entity::find().all(&txn)?.len(), 1);
+
+    top_bakery().save(&txn)?;
+    txn.release("before_import")?;
+
+    txn.commit()?;
+
+    assert_eq!(bakery::Entity::find().all(&ctx.db)?.len(), 2);
+
+    ctx.delete();
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use crate::{
+    ConnectionTrait, DatabaseConnection, DatabaseTransaction, DbBackend, DbErr, SqlErr,
+    SqliteTransactionMode, Statement, TransactionOptions, TransactionTrait,
+};
+use std::{ops::Deref, time::Duration};
+
+/// How long to wait before trying to take the SQLite write lock again, at first and at most
+const BUSY_BACKOFF: (Duration, Duration) = (Duration::from_millis(10), Duration::from_millis(500));
+
+/// A lock on a key, shared by every process using the database: only one
+/// [`DatabaseConnection::advisory_lock`](crate::DatabaseConnection::advisory_lock) can hold
+/// it at a time, so it can elect a leader among several instances of a service.
+///
+/// The lock lives in a transaction on a connection of its own, which the guard derefs to.
+/// [`commit`](Self::commit) commits that transaction and releases the lock; dropping the
+/// guard rolls it back and releases the lock as well.
+///
+/// | Backend  | Lock |
+/// |----------|------|
+/// | Postgres | `pg_advisory_xact_lock` on a 64-bit hash of the key |
+/// | MySQL    | `GET_LOCK`, for keys of at most 64 characters |
+/// | SQLite   | `BEGIN IMMEDIATE` |
+///
+/// SQLite has no advisory locks, so there the lock is the write lock of the whole
+/// database, whatever the key: other connections cannot write while it is held, and on a
+/// connection pool of one (the default for `sqlite::memory:`) nothing else can run at all.
+/// Keep it short, or run the locked work through the guard.
+#[derive(Debug)]
+pub struct AdvisoryLock {
+    txn: Option<DatabaseTransaction>,
+    key: String,
+}
+
+impl AdvisoryLock {
+    /// The key this lock is held on
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Commit the work done through the lock's transaction and release the lock
+    pub async fn commit(mut self) -> Result<(), DbErr> {
+        let Some(txn) = self.txn.take() else {
+            return Ok(());
+        };
+        if txn.get_database_backend() == DbBackend::MySql {
+            txn.query_one_raw(Statement::from_sql_and_values(
+                DbBackend::MySql,
+                "SELECT RELEASE_LOCK(?)",
+                [self.key.as_str().into()],
+            ))
+            .await?;
+        }
+        txn.commit().await
+    }
+}
+
+impl Deref for AdvisoryLock {
+    type Target = DatabaseTransaction;
+
+    fn deref(&self) -> &Self::Target {
+        self.txn
+            .as_ref()
+            .expect("AdvisoryLock is only emptied by commit")
+    }
+}
+
+impl Drop for AdvisoryLock {
+    fn drop(&mut self) {
+        if let Some(txn) = &self.txn {
+            // a MySQL lock belongs to the session, not the transaction
+            txn.discard_connection();
+        }
+    }
+}
+
+/// Take the lock on `key`, waiting for it to be released if `wait`; otherwise return
+/// `None` if it is held elsewhere.
+pub(crate) async fn acquire(
+    db: &DatabaseConnection,
+    key: &str,
+    wait: bool,
+) -> Result<Option<AdvisoryLock>, DbErr> {
+    let txn = match begin(db, wait).await? {
+        Some(txn) => txn,
+        None => return Ok(None),
+    };
+
+    let stmt = match txn.get_database_backend() {
+        DbBackend::Postgres => Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            if wait {
+                "SELECT TRUE FROM pg_advisory_xact_lock(hashtextextended($1, 0))"
+            } else {
+                "SELECT pg_try_advisory_xact_lock(hashtextextended($1, 0))"
+            },
+            [key.into()],
+        ),
+        DbBackend::MySql => Statement::from_sql_and_values(
+            DbBackend::MySql,
+            "SELECT GET_LOCK(?, ?)",
+            [key.into(), if wait { -1 } else { 0 }.into()],
+        ),
+        DbBackend::Sqlite => {
+            return Ok(Some(AdvisoryLock {
+                txn: Some(txn),
+                key: key.to_owned(),
+            }));
+        }
+    };
+
+    let acquired = match txn.query_one_raw(stmt).await? {
+        Some(row) if txn.get_database_backend() == DbBackend::MySql => {
+            row.try_get_by_index::<Option<i64>>(0)? == Some(1)
+        }
+        Some(row) => row.try_get_by_index::<bool>(0)?,
+        None => false,
+    };
+    if !acquired {
+        return Ok(None);
+    }
+
+    Ok(Some(AdvisoryLock {
+        txn: Some(txn),
+        key: key.to_owned(),
+    }))
+}
+
+/// Begin the transaction the lock is held in. On SQLite that takes the write lock, so
+/// `None` is returned when the database stays busy, unless waiting for it, in which case
+/// it is tried again after a growing pause.
+async fn begin(db: &DatabaseConnection, wait: bool) -> Result<Option<DatabaseTransaction>, DbErr> {
+    let options = TransactionOptions {
+        sqlite_transaction_mode: Some(SqliteTransactionMode::Immediate),
+        ..Default::default()
+    };
+    let (mut backoff, max_backoff) = BUSY_BACKOFF;
+    loop {
+        match db.begin_with_options(options).await {
+            Ok(txn) => return Ok(Some(txn)),
+            Err(err) if matches!(err.sql_err(), Some(SqlErr::LockTimeout(_))) => {
+                if !wait {
+                    return Ok(None);
+                }
+                super::retry::sleep(backoff).await;
+                backoff = (backoff * 2).min(max_backoff);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
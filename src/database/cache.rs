@@ -0,0 +1,910 @@
+use super::{replica::is_replica_safe, transaction::run_async_transaction_callback};
+use crate::{
+    AccessMode, ConnectionTrait, DatabaseConnection, DatabaseTransaction, DbBackend, DbErr,
+    ExecResult, IsolationLevel, QueryResult, Statement, StatementBuilder, TransactionError,
+    TransactionOptions, TransactionSession, TransactionTrait, Value,
+};
+use sea_query::audit::AccessType;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tracing::instrument;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+/// Wrapper of [`DatabaseConnection`] that caches the rows returned by
+/// [`query_one`](ConnectionTrait::query_one) and [`query_all`](ConnectionTrait::query_all).
+///
+/// Results are keyed by the built [`Statement`] and kept for at most `ttl`; at most
+/// `max_entries` statements are cached at a time, evicting the oldest first. The tables
+/// each statement touches are found with [`StatementBuilder::audit`]: a write through this
+/// connection, or through a [`CachedTransaction`] once it commits, evicts every cached
+/// result that read from one of the tables it wrote to. Tables are matched by name,
+/// ignoring the schema.
+///
+/// Only plain `SELECT`s are cached. Raw [`Statement`]s are never served from the cache,
+/// and raw writes, schema statements and anything else that cannot be audited clear the
+/// whole cache. Writes made by other connections are not seen; rely on the TTL or call
+/// [`invalidate`](Self::invalidate).
+#[derive(Debug, Clone)]
+#[cfg_attr(docsrs, doc(cfg(feature = "query-cache")))]
+pub struct CachedConnection {
+    conn: DatabaseConnection,
+    cache: Arc<QueryCache>,
+}
+
+/// Wrapper of [`DatabaseTransaction`] started from a [`CachedConnection`].
+///
+/// Reads bypass the cache, as they may see the transaction's own uncommitted writes.
+/// The tables it writes to are invalidated once the outermost transaction commits.
+#[derive(Debug)]
+#[cfg_attr(docsrs, doc(cfg(feature = "query-cache")))]
+pub struct CachedTransaction {
+    conn: DatabaseTransaction,
+    cache: Arc<QueryCache>,
+    pending: Arc<Mutex<Invalidation>>,
+    outermost: bool,
+}
+
+struct QueryCache {
+    ttl: Duration,
+    max_entries: usize,
+    state: Mutex<CacheState>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<CacheKey, CacheEntry>,
+    /// Bumped on every invalidation, so that a read racing with a write does not
+    /// store a result that is already stale.
+    generation: u64,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    sql: String,
+    values: Vec<Value>,
+    one: bool,
+}
+
+struct CacheEntry {
+    rows: Vec<QueryResult>,
+    tables: HashSet<String>,
+    inserted_at: Instant,
+}
+
+/// Tables written by a transaction, to be invalidated on commit.
+#[derive(Debug, Default)]
+struct Invalidation {
+    all: bool,
+    tables: HashSet<String>,
+}
+
+/// The tables a statement reads or writes, according to its audit.
+enum Access {
+    Read(HashSet<String>),
+    Write(HashSet<String>),
+    Unknown,
+}
+
+impl Access {
+    fn of<S: StatementBuilder>(stmt: &S) -> Self {
+        let Ok(audit) = stmt.audit() else {
+            return Self::Unknown;
+        };
+        let mut reads = HashSet::new();
+        let mut writes = HashSet::new();
+        for request in audit.requests {
+            let table = request.schema_table.1.to_string();
+            match request.access_type {
+                AccessType::Select => reads.insert(table),
+                _ => writes.insert(table),
+            };
+        }
+        if writes.is_empty() {
+            Self::Read(reads)
+        } else {
+            Self::Write(writes)
+        }
+    }
+
+    fn of_raw(stmt: &Statement) -> Self {
+        if is_replica_safe(&stmt.sql) {
+            Self::Read(HashSet::new())
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+impl CacheKey {
+    fn new(stmt: &Statement, one: bool) -> Self {
+        Self {
+            sql: stmt.sql.clone(),
+            values: stmt
+                .values
+                .as_ref()
+                .map(|values| values.0.clone())
+                .unwrap_or_default(),
+            one,
+        }
+    }
+}
+
+impl QueryCache {
+    fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            ttl,
+            max_entries,
+            state: Mutex::default(),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        // The state is left consistent even if a holder panicked.
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn get(&self, key: &CacheKey) -> Option<Vec<QueryResult>> {
+        let mut state = self.state();
+        match state.entries.get(key) {
+            Some(entry) if entry.inserted_at.elapsed() < self.ttl => Some(entry.rows.clone()),
+            Some(_) => {
+                state.entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn generation(&self) -> u64 {
+        self.state().generation
+    }
+
+    fn insert(
+        &self,
+        key: CacheKey,
+        rows: Vec<QueryResult>,
+        tables: HashSet<String>,
+        generation: u64,
+    ) {
+        let mut state = self.state();
+        if state.generation != generation || self.max_entries == 0 {
+            return;
+        }
+        if state.entries.len() >= self.max_entries && !state.entries.contains_key(&key) {
+            let ttl = self.ttl;
+            state
+                .entries
+                .retain(|_, entry| entry.inserted_at.elapsed() < ttl);
+            if state.entries.len() >= self.max_entries {
+                let oldest = state
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.inserted_at)
+                    .map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    state.entries.remove(&oldest);
+                }
+            }
+        }
+        state.entries.insert(
+            key,
+            CacheEntry {
+                rows,
+                tables,
+                inserted_at: Instant::now(),
+            },
+        );
+    }
+
+    fn invalidate(&self, tables: &HashSet<String>) {
+        let mut state = self.state();
+        state.generation += 1;
+        state
+            .entries
+            .retain(|_, entry| entry.tables.is_disjoint(tables));
+    }
+
+    fn clear(&self) {
+        let mut state = self.state();
+        state.generation += 1;
+        state.entries.clear();
+    }
+
+    fn written(&self, access: &Access) {
+        match access {
+            Access::Read(_) => (),
+            Access::Write(tables) => self.invalidate(tables),
+            Access::Unknown => self.clear(),
+        }
+    }
+}
+
+impl std::fmt::Debug for QueryCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueryCache")
+            .field("ttl", &self.ttl)
+            .field("max_entries", &self.max_entries)
+            .field("len", &self.state().entries.len())
+            .finish()
+    }
+}
+
+impl Invalidation {
+    fn record(&mut self, access: &Access) {
+        match access {
+            Access::Read(_) => (),
+            Access::Write(tables) => self.tables.extend(tables.iter().cloned()),
+            Access::Unknown => self.all = true,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ConnectionTrait for CachedConnection {
+    fn get_database_backend(&self) -> DbBackend {
+        self.conn.get_database_backend()
+    }
+
+    async fn execute_raw(&self, stmt: Statement) -> Result<ExecResult, DbErr> {
+        let res = self.conn.execute_raw(stmt).await;
+        self.cache.clear();
+        res
+    }
+
+    async fn execute<S: StatementBuilder>(&self, stmt: &S) -> Result<ExecResult, DbErr> {
+        let access = Access::of(stmt);
+        let res = self.conn.execute(stmt).await;
+        self.cache.written(&access);
+        res
+    }
+
+    async fn execute_unprepared(&self, sql: &str) -> Result<ExecResult, DbErr> {
+        let res = self.conn.execute_unprepared(sql).await;
+        self.cache.clear();
+        res
+    }
+
+    async fn query_one_raw(&self, stmt: Statement) -> Result<Option<QueryResult>, DbErr> {
+        let access = Access::of_raw(&stmt);
+        let res = self.conn.query_one_raw(stmt).await;
+        self.cache.written(&access);
+        res
+    }
+
+    async fn query_one<S: StatementBuilder>(&self, stmt: &S) -> Result<Option<QueryResult>, DbErr> {
+        match Access::of(stmt) {
+            Access::Read(tables) => Ok(self.read(stmt, tables, true).await?.pop()),
+            access => {
+                let res = self.conn.query_one(stmt).await;
+                self.cache.written(&access);
+                res
+            }
+        }
+    }
+
+    async fn query_all_raw(&self, stmt: Statement) -> Result<Vec<QueryResult>, DbErr> {
+        let access = Access::of_raw(&stmt);
+        let res = self.conn.query_all_raw(stmt).await;
+        self.cache.written(&access);
+        res
+    }
+
+    async fn query_all<S: StatementBuilder>(&self, stmt: &S) -> Result<Vec<QueryResult>, DbErr> {
+        match Access::of(stmt) {
+            Access::Read(tables) => self.read(stmt, tables, false).await,
+            access => {
+                let res = self.conn.query_all(stmt).await;
+                self.cache.written(&access);
+                res
+            }
+        }
+    }
+
+    async fn execute_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<ExecResult, DbErr> {
+        let res = self.conn.execute_raw_with_timeout(stmt, timeout).await;
+        self.cache.clear();
+        res
+    }
+
+    async fn execute_with_timeout<S: StatementBuilder>(
+        &self,
+        stmt: &S,
+        timeout: Duration,
+    ) -> Result<ExecResult, DbErr> {
+        let access = Access::of(stmt);
+        let res = self.conn.execute_with_timeout(stmt, timeout).await;
+        self.cache.written(&access);
+        res
+    }
+
+    async fn query_one_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<Option<QueryResult>, DbErr> {
+        let access = Access::of_raw(&stmt);
+        let res = self.conn.query_one_raw_with_timeout(stmt, timeout).await;
+        self.cache.written(&access);
+        res
+    }
+
+    async fn query_all_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<Vec<QueryResult>, DbErr> {
+        let access = Access::of_raw(&stmt);
+        let res = self.conn.query_all_raw_with_timeout(stmt, timeout).await;
+        self.cache.written(&access);
+        res
+    }
+
+    fn is_mock_connection(&self) -> bool {
+        self.conn.is_mock_connection()
+    }
+}
+
+#[async_trait::async_trait]
+impl ConnectionTrait for CachedTransaction {
+    fn get_database_backend(&self) -> DbBackend {
+        self.conn.get_database_backend()
+    }
+
+    async fn execute_raw(&self, stmt: Statement) -> Result<ExecResult, DbErr> {
+        self.record(&Access::Unknown);
+        self.conn.execute_raw(stmt).await
+    }
+
+    async fn execute<S: StatementBuilder>(&self, stmt: &S) -> Result<ExecResult, DbErr> {
+        self.record(&Access::of(stmt));
+        self.conn.execute(stmt).await
+    }
+
+    async fn execute_unprepared(&self, sql: &str) -> Result<ExecResult, DbErr> {
+        self.record(&Access::Unknown);
+        self.conn.execute_unprepared(sql).await
+    }
+
+    async fn query_one_raw(&self, stmt: Statement) -> Result<Option<QueryResult>, DbErr> {
+        self.record(&Access::of_raw(&stmt));
+        self.conn.query_one_raw(stmt).await
+    }
+
+    async fn query_one<S: StatementBuilder>(&self, stmt: &S) -> Result<Option<QueryResult>, DbErr> {
+        self.record(&Access::of(stmt));
+        self.conn.query_one(stmt).await
+    }
+
+    async fn query_all_raw(&self, stmt: Statement) -> Result<Vec<QueryResult>, DbErr> {
+        self.record(&Access::of_raw(&stmt));
+        self.conn.query_all_raw(stmt).await
+    }
+
+    async fn query_all<S: StatementBuilder>(&self, stmt: &S) -> Result<Vec<QueryResult>, DbErr> {
+        self.record(&Access::of(stmt));
+        self.conn.query_all(stmt).await
+    }
+
+    async fn execute_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<ExecResult, DbErr> {
+        self.record(&Access::Unknown);
+        self.conn.execute_raw_with_timeout(stmt, timeout).await
+    }
+
+    async fn execute_with_timeout<S: StatementBuilder>(
+        &self,
+        stmt: &S,
+        timeout: Duration,
+    ) -> Result<ExecResult, DbErr> {
+        self.record(&Access::of(stmt));
+        self.conn.execute_with_timeout(stmt, timeout).await
+    }
+
+    async fn query_one_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<Option<QueryResult>, DbErr> {
+        self.record(&Access::of_raw(&stmt));
+        self.conn.query_one_raw_with_timeout(stmt, timeout).await
+    }
+
+    async fn query_all_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<Vec<QueryResult>, DbErr> {
+        self.record(&Access::of_raw(&stmt));
+        self.conn.query_all_raw_with_timeout(stmt, timeout).await
+    }
+
+    fn is_mock_connection(&self) -> bool {
+        self.conn.is_mock_connection()
+    }
+}
+
+impl CachedConnection {
+    /// Wrap `conn`, caching each query result for at most `ttl` and keeping at most
+    /// `max_entries` results at a time.
+    pub fn new(conn: DatabaseConnection, ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            conn,
+            cache: Arc::new(QueryCache::new(ttl, max_entries)),
+        }
+    }
+
+    /// Get the wrapped connection. Writes run on it directly do not invalidate the cache.
+    pub fn inner(&self) -> &DatabaseConnection {
+        &self.conn
+    }
+
+    /// Evict every cached result that read from `table`, e.g. after it was modified by
+    /// another process.
+    pub fn invalidate(&self, table: &str) {
+        self.cache.invalidate(&HashSet::from([table.to_owned()]));
+    }
+
+    /// Evict all cached results.
+    pub fn clear(&self) {
+        self.cache.clear();
+    }
+
+    async fn read<S: StatementBuilder>(
+        &self,
+        stmt: &S,
+        tables: HashSet<String>,
+        one: bool,
+    ) -> Result<Vec<QueryResult>, DbErr> {
+        let stmt = self.get_database_backend().build(stmt);
+        if tables.is_empty() || !is_replica_safe(&stmt.sql) {
+            return if one {
+                Ok(self.conn.query_one_raw(stmt).await?.into_iter().collect())
+            } else {
+                self.conn.query_all_raw(stmt).await
+            };
+        }
+
+        let key = CacheKey::new(&stmt, one);
+        if let Some(rows) = self.cache.get(&key) {
+            return Ok(rows);
+        }
+        let generation = self.cache.generation();
+        let rows: Vec<QueryResult> = if one {
+            self.conn.query_one_raw(stmt).await?.into_iter().collect()
+        } else {
+            self.conn.query_all_raw(stmt).await?
+        };
+        self.cache.insert(key, rows.clone(), tables, generation);
+        Ok(rows)
+    }
+
+    fn wrap(&self, conn: DatabaseTransaction) -> CachedTransaction {
+        CachedTransaction {
+            conn,
+            cache: self.cache.clone(),
+            pending: Default::default(),
+            outermost: true,
+        }
+    }
+
+    /// Execute the function inside a transaction.
+    /// If the function returns an error, the transaction will be rolled back.
+    /// Otherwise, the transaction will be committed.
+    #[instrument(level = "trace", skip(callback))]
+    pub async fn transaction_async<F, T, E>(&self, callback: F) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> AsyncFnOnce(&'c CachedTransaction) -> Result<T, E> + Send,
+        T: Send,
+        E: std::fmt::Display + std::fmt::Debug + Send,
+    {
+        let transaction = self.begin().await.map_err(TransactionError::Connection)?;
+        run_async_transaction_callback(transaction, callback).await
+    }
+}
+
+impl CachedTransaction {
+    fn record(&self, access: &Access) {
+        self.pending
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .record(access);
+    }
+
+    fn wrap(&self, conn: DatabaseTransaction) -> CachedTransaction {
+        CachedTransaction {
+            conn,
+            cache: self.cache.clone(),
+            pending: self.pending.clone(),
+            outermost: false,
+        }
+    }
+
+    /// Execute the function inside a transaction.
+    /// If the function returns an error, the transaction will be rolled back.
+    /// Otherwise, the transaction will be committed.
+    #[instrument(level = "trace", skip(callback))]
+    pub async fn transaction_async<F, T, E>(&self, callback: F) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> AsyncFnOnce(&'c CachedTransaction) -> Result<T, E> + Send,
+        T: Send,
+        E: std::fmt::Display + std::fmt::Debug + Send,
+    {
+        let transaction = self.begin().await.map_err(TransactionError::Connection)?;
+        run_async_transaction_callback(transaction, callback).await
+    }
+
+    /// Runs a transaction to completion passing through the result.
+    /// Rolling back the transaction on encountering an error.
+    #[instrument(level = "trace", skip(callback))]
+    async fn run<F, T, E>(self, callback: F) -> Result<T, TransactionError<E>>
+    where
+        F: for<'b> FnOnce(
+                &'b CachedTransaction,
+            ) -> Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'b>>
+            + Send,
+        T: Send,
+        E: std::fmt::Display + std::fmt::Debug + Send,
+    {
+        let res = callback(&self).await.map_err(TransactionError::Transaction);
+        if res.is_ok() {
+            self.commit().await.map_err(TransactionError::Connection)?;
+        } else {
+            self.rollback()
+                .await
+                .map_err(TransactionError::Connection)?;
+        }
+        res
+    }
+
+    /// Commit a transaction. Committing the outermost transaction invalidates the
+    /// cached results of every table written within it.
+    #[instrument(level = "trace")]
+    pub async fn commit(self) -> Result<(), DbErr> {
+        self.conn.commit().await?;
+        if self.outermost {
+            let pending =
+                std::mem::take(&mut *self.pending.lock().unwrap_or_else(|err| err.into_inner()));
+            if pending.all {
+                self.cache.clear();
+            } else if !pending.tables.is_empty() {
+                self.cache.invalidate(&pending.tables);
+            }
+        }
+        Ok(())
+    }
+
+    /// Rolls back a transaction explicitly
+    #[instrument(level = "trace")]
+    pub async fn rollback(self) -> Result<(), DbErr> {
+        self.conn.rollback().await
+    }
+}
+
+#[async_trait::async_trait]
+impl TransactionTrait for CachedConnection {
+    type Transaction = CachedTransaction;
+
+    #[instrument(level = "trace")]
+    async fn begin(&self) -> Result<CachedTransaction, DbErr> {
+        Ok(self.wrap(self.conn.begin().await?))
+    }
+
+    #[instrument(level = "trace")]
+    async fn begin_with_config(
+        &self,
+        isolation_level: Option<IsolationLevel>,
+        access_mode: Option<AccessMode>,
+    ) -> Result<CachedTransaction, DbErr> {
+        Ok(self.wrap(
+            self.conn
+                .begin_with_config(isolation_level, access_mode)
+                .await?,
+        ))
+    }
+
+    #[instrument(level = "trace")]
+    async fn begin_with_options(
+        &self,
+        options: TransactionOptions,
+    ) -> Result<CachedTransaction, DbErr> {
+        Ok(self.wrap(self.conn.begin_with_options(options).await?))
+    }
+
+    /// Execute the function inside a transaction.
+    /// If the function returns an error, the transaction will be rolled back. If it does not return an error, the transaction will be committed.
+    #[instrument(level = "trace", skip(callback))]
+    async fn transaction<F, T, E>(&self, callback: F) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnOnce(
+                &'c CachedTransaction,
+            ) -> Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'c>>
+            + Send,
+        T: Send,
+        E: std::fmt::Display + std::fmt::Debug + Send,
+    {
+        let transaction = self.begin().await.map_err(TransactionError::Connection)?;
+        transaction.run(callback).await
+    }
+
+    /// Execute the function inside a transaction.
+    /// If the function returns an error, the transaction will be rolled back. If it does not return an error, the transaction will be committed.
+    #[instrument(level = "trace", skip(callback))]
+    async fn transaction_with_config<F, T, E>(
+        &self,
+        callback: F,
+        isolation_level: Option<IsolationLevel>,
+        access_mode: Option<AccessMode>,
+    ) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnOnce(
+                &'c CachedTransaction,
+            ) -> Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'c>>
+            + Send,
+        T: Send,
+        E: std::fmt::Display + std::fmt::Debug + Send,
+    {
+        let transaction = self
+            .begin_with_config(isolation_level, access_mode)
+            .await
+            .map_err(TransactionError::Connection)?;
+        transaction.run(callback).await
+    }
+}
+
+#[async_trait::async_trait]
+impl TransactionTrait for CachedTransaction {
+    type Transaction = CachedTransaction;
+
+    #[instrument(level = "trace")]
+    async fn begin(&self) -> Result<CachedTransaction, DbErr> {
+        Ok(self.wrap(self.conn.begin().await?))
+    }
+
+    #[instrument(level = "trace")]
+    async fn begin_with_config(
+        &self,
+        isolation_level: Option<IsolationLevel>,
+        access_mode: Option<AccessMode>,
+    ) -> Result<CachedTransaction, DbErr> {
+        Ok(self.wrap(
+            self.conn
+                .begin_with_config(isolation_level, access_mode)
+                .await?,
+        ))
+    }
+
+    #[instrument(level = "trace")]
+    async fn begin_with_options(
+        &self,
+        options: TransactionOptions,
+    ) -> Result<CachedTransaction, DbErr> {
+        Ok(self.wrap(self.conn.begin_with_options(options).await?))
+    }
+
+    /// Execute the function inside a transaction.
+    /// If the function returns an error, the transaction will be rolled back. If it does not return an error, the transaction will be committed.
+    #[instrument(level = "trace", skip(callback))]
+    async fn transaction<F, T, E>(&self, callback: F) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnOnce(
+                &'c CachedTransaction,
+            ) -> Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'c>>
+            + Send,
+        T: Send,
+        E: std::fmt::Display + std::fmt::Debug + Send,
+    {
+        let transaction = self.begin().await.map_err(TransactionError::Connection)?;
+        transaction.run(callback).await
+    }
+
+    /// Execute the function inside a transaction.
+    /// If the function returns an error, the transaction will be rolled back. If it does not return an error, the transaction will be committed.
+    #[instrument(level = "trace", skip(callback))]
+    async fn transaction_with_config<F, T, E>(
+        &self,
+        callback: F,
+        isolation_level: Option<IsolationLevel>,
+        access_mode: Option<AccessMode>,
+    ) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnOnce(
+                &'c CachedTransaction,
+            ) -> Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'c>>
+            + Send,
+        T: Send,
+        E: std::fmt::Display + std::fmt::Debug + Send,
+    {
+        let transaction = self
+            .begin_with_config(isolation_level, access_mode)
+            .await
+            .map_err(TransactionError::Connection)?;
+        transaction.run(callback).await
+    }
+}
+
+#[async_trait::async_trait]
+impl TransactionSession for CachedTransaction {
+    async fn commit(self) -> Result<(), DbErr> {
+        self.commit().await
+    }
+
+    async fn rollback(self) -> Result<(), DbErr> {
+        self.rollback().await
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "mock")]
+mod tests {
+    use super::CachedConnection;
+    use crate::{
+        ConnectionTrait, DbBackend, DbErr, EntityTrait, MockDatabase, MockExecResult, QueryFilter,
+        Statement, Transaction, TransactionTrait, entity::*, tests_cfg::*,
+    };
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    fn cake_model() -> cake::Model {
+        cake::Model {
+            id: 1,
+            name: "Chocolate".to_owned(),
+        }
+    }
+
+    fn select_cake() -> Statement {
+        Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"SELECT "cake"."id", "cake"."name" FROM "cake""#,
+            [],
+        )
+    }
+
+    fn exec_result() -> MockExecResult {
+        MockExecResult {
+            last_insert_id: 0,
+            rows_affected: 1,
+        }
+    }
+
+    #[smol_potat::test]
+    async fn test_cache_hit_and_invalidation() -> Result<(), DbErr> {
+        let conn = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([[cake_model()], [cake_model()]])
+            .append_exec_results([exec_result(), exec_result()])
+            .into_connection();
+        let db = CachedConnection::new(conn.clone(), Duration::from_secs(60), 16);
+
+        assert_eq!(cake::Entity::find().all(&db).await?, [cake_model()]);
+        assert_eq!(cake::Entity::find().all(&db).await?, [cake_model()]);
+
+        // writing to another table keeps the cached rows
+        fruit::Entity::delete_many().exec(&db).await?;
+        assert_eq!(cake::Entity::find().all(&db).await?, [cake_model()]);
+
+        cake::Entity::delete_many().exec(&db).await?;
+        assert_eq!(cake::Entity::find().all(&db).await?, [cake_model()]);
+
+        assert_eq!(
+            conn.into_transaction_log(),
+            [
+                Transaction::one(select_cake()),
+                Transaction::from_sql_and_values(DbBackend::Postgres, r#"DELETE FROM "fruit""#, []),
+                Transaction::from_sql_and_values(DbBackend::Postgres, r#"DELETE FROM "cake""#, []),
+                Transaction::one(select_cake()),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[smol_potat::test]
+    async fn test_cache_key_includes_values() -> Result<(), DbErr> {
+        let conn = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([[cake_model()], [cake_model()]])
+            .into_connection();
+        let db = CachedConnection::new(conn.clone(), Duration::from_secs(60), 16);
+
+        cake::Entity::find_by_id(1).one(&db).await?;
+        cake::Entity::find_by_id(1).one(&db).await?;
+        cake::Entity::find_by_id(2).one(&db).await?;
+
+        assert_eq!(conn.into_transaction_log().len(), 2);
+
+        Ok(())
+    }
+
+    #[smol_potat::test]
+    async fn test_ttl_and_size_bound() -> Result<(), DbErr> {
+        let conn = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([[cake_model()], [cake_model()]])
+            .into_connection();
+        let db = CachedConnection::new(conn.clone(), Duration::ZERO, 16);
+        cake::Entity::find().all(&db).await?;
+        cake::Entity::find().all(&db).await?;
+        assert_eq!(conn.into_transaction_log().len(), 2);
+
+        let conn = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([[cake_model()], [cake_model()], [cake_model()]])
+            .into_connection();
+        let db = CachedConnection::new(conn.clone(), Duration::from_secs(60), 1);
+        cake::Entity::find().all(&db).await?;
+        cake::Entity::find()
+            .filter(cake::Column::Id.eq(1))
+            .all(&db)
+            .await?;
+        cake::Entity::find().all(&db).await?;
+        assert_eq!(conn.into_transaction_log().len(), 3);
+
+        Ok(())
+    }
+
+    #[smol_potat::test]
+    async fn test_transaction_invalidates_on_commit() -> Result<(), DbErr> {
+        let conn = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([[cake_model()], [cake_model()]])
+            .append_exec_results([exec_result()])
+            .into_connection();
+        let db = CachedConnection::new(conn.clone(), Duration::from_secs(60), 16);
+
+        cake::Entity::find().all(&db).await?;
+
+        let txn = db.begin().await?;
+        cake::Entity::delete_many().exec(&txn).await?;
+        // not committed yet, other readers still see the cached rows
+        cake::Entity::find().all(&db).await?;
+        txn.commit().await?;
+
+        cake::Entity::find().all(&db).await?;
+
+        assert_eq!(
+            conn.into_transaction_log(),
+            [
+                Transaction::one(select_cake()),
+                Transaction::many([
+                    Statement::from_string(DbBackend::Postgres, "BEGIN"),
+                    Statement::from_sql_and_values(
+                        DbBackend::Postgres,
+                        r#"DELETE FROM "cake""#,
+                        []
+                    ),
+                    Statement::from_string(DbBackend::Postgres, "COMMIT"),
+                ]),
+                Transaction::one(select_cake()),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[smol_potat::test]
+    async fn test_raw_write_clears_cache() -> Result<(), DbErr> {
+        let conn = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([[cake_model()], [cake_model()]])
+            .append_exec_results([exec_result()])
+            .into_connection();
+        let db = CachedConnection::new(conn.clone(), Duration::from_secs(60), 16);
+
+        cake::Entity::find().all(&db).await?;
+        db.execute_unprepared("TRUNCATE cake").await?;
+        cake::Entity::find().all(&db).await?;
+
+        assert_eq!(conn.into_transaction_log().len(), 3);
+
+        Ok(())
+    }
+}
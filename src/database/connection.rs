@@ -1,10 +1,12 @@
-use std::{future::Future, pin::Pin};
+use std::{future::Future, pin::Pin, time::Duration};
 
 #[cfg(feature = "stream")]
 use futures_util::Stream;
 
+use super::timeout::{map_timeout_err, with_max_execution_time};
 use crate::{
-    DbBackend, DbErr, ExecResult, QueryResult, Statement, StatementBuilder, TransactionError,
+    DatabaseTransaction, DbBackend, DbErr, ExecResult, QueryResult, Statement, StatementBuilder,
+    TransactionError,
 };
 
 /// A connection (or transaction) that can run queries against the database.
@@ -52,6 +54,132 @@ pub trait ConnectionTrait: Sync {
         self.query_all_raw(stmt).await
     }
 
+    /// Execute a [Statement], cancelling it once it has run for longer than `timeout`.
+    ///
+    /// Uses the native mechanism of the backend: `SET LOCAL statement_timeout` on
+    /// Postgres, a `MAX_EXECUTION_TIME` hint on MySQL (which only bounds `SELECT`s)
+    /// and a progress handler on SQLite. A statement that runs out of time fails with
+    /// [`DbErr::QueryTimeout`].
+    ///
+    /// The default implementation only applies the MySQL hint; connections that can
+    /// do better override it.
+    async fn execute_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<ExecResult, DbErr> {
+        let stmt = with_max_execution_time(stmt, timeout);
+        map_timeout_err(self.execute_raw(stmt).await, timeout)
+    }
+
+    /// Execute a [`StatementBuilder`], cancelling it once it has run for longer than
+    /// `timeout`. See [`execute_raw_with_timeout`](Self::execute_raw_with_timeout).
+    async fn execute_with_timeout<S: StatementBuilder>(
+        &self,
+        stmt: &S,
+        timeout: Duration,
+    ) -> Result<ExecResult, DbErr> {
+        let db_backend = self.get_database_backend();
+        let stmt = db_backend.build(stmt);
+        self.execute_raw_with_timeout(stmt, timeout).await
+    }
+
+    /// Execute a [Statement] and return a single row of `QueryResult`, cancelling it
+    /// once it has run for longer than `timeout`. See
+    /// [`execute_raw_with_timeout`](Self::execute_raw_with_timeout).
+    async fn query_one_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<Option<QueryResult>, DbErr> {
+        let stmt = with_max_execution_time(stmt, timeout);
+        map_timeout_err(self.query_one_raw(stmt).await, timeout)
+    }
+
+    /// Execute a [`StatementBuilder`] and return a single row of `QueryResult`,
+    /// cancelling it once it has run for longer than `timeout`. See
+    /// [`execute_raw_with_timeout`](Self::execute_raw_with_timeout).
+    async fn query_one_with_timeout<S: StatementBuilder>(
+        &self,
+        stmt: &S,
+        timeout: Duration,
+    ) -> Result<Option<QueryResult>, DbErr> {
+        let db_backend = self.get_database_backend();
+        let stmt = db_backend.build(stmt);
+        self.query_one_raw_with_timeout(stmt, timeout).await
+    }
+
+    /// Execute a [Statement] and return a vector of `QueryResult`, cancelling it once
+    /// it has run for longer than `timeout`. See
+    /// [`execute_raw_with_timeout`](Self::execute_raw_with_timeout).
+    async fn query_all_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<Vec<QueryResult>, DbErr> {
+        let stmt = with_max_execution_time(stmt, timeout);
+        map_timeout_err(self.query_all_raw(stmt).await, timeout)
+    }
+
+    /// Execute a [`StatementBuilder`] and return a vector of `QueryResult`, cancelling
+    /// it once it has run for longer than `timeout`. See
+    /// [`execute_raw_with_timeout`](Self::execute_raw_with_timeout).
+    async fn query_all_with_timeout<S: StatementBuilder>(
+        &self,
+        stmt: &S,
+        timeout: Duration,
+    ) -> Result<Vec<QueryResult>, DbErr> {
+        let db_backend = self.get_database_backend();
+        let stmt = db_backend.build(stmt);
+        self.query_all_raw_with_timeout(stmt, timeout).await
+    }
+
+    /// Begin a transaction to run several statements atomically in, such as the
+    /// statements an [`InsertMany`](crate::InsertMany) is split into, or `None` if the
+    /// connection is atomic by itself. Within a
+    /// [`DatabaseTransaction`](crate::DatabaseTransaction) this begins a nested one.
+    ///
+    /// Connections that cannot hand out a [`DatabaseTransaction`] without bypassing
+    /// themselves fail with [`DbErr::BackendNotSupported`], rather than running the
+    /// statements one by one.
+    async fn begin_atomic(&self) -> Result<Option<DatabaseTransaction>, DbErr> {
+        Err(DbErr::BackendNotSupported {
+            db: self.get_database_backend().as_str(),
+            ctx: "atomic multi-statement INSERT",
+        })
+    }
+
+    /// Run a Postgres `COPY ... FROM STDIN` statement, sending it `data` chunk by chunk,
+    /// and return the number of rows copied. An error in place of a chunk aborts the
+    /// `COPY` and is returned. Only Postgres connections support this; others fail with
+    /// [`DbErr::BackendNotSupported`].
+    async fn copy_in_raw(
+        &self,
+        sql: &str,
+        data: &mut (dyn Iterator<Item = Result<Vec<u8>, DbErr>> + Send),
+    ) -> Result<u64, DbErr> {
+        let _ = (sql, data);
+        Err(DbErr::BackendNotSupported {
+            db: self.get_database_backend().as_str(),
+            ctx: "COPY",
+        })
+    }
+
+    /// Run a Postgres `COPY ... TO STDOUT` statement, writing its output to `writer`.
+    /// Only Postgres connections support this; others fail with
+    /// [`DbErr::BackendNotSupported`].
+    async fn copy_out_raw(
+        &self,
+        sql: &str,
+        writer: &mut (dyn std::io::Write + Send),
+    ) -> Result<(), DbErr> {
+        let _ = (sql, writer);
+        Err(DbErr::BackendNotSupported {
+            db: self.get_database_backend().as_str(),
+            ctx: "COPY",
+        })
+    }
+
     /// Check if the connection supports `RETURNING` syntax on insert and update
     fn support_returning(&self) -> bool {
         let db_backend = self.get_database_backend();
@@ -92,6 +220,17 @@ pub trait StreamTrait: Send + Sync {
         let stmt = db_backend.build(stmt);
         self.stream_raw(stmt)
     }
+
+    /// Execute a [Statement] and return a stream of results, cancelling it once it has
+    /// run for longer than `timeout`. Only MySQL can bound a statement by itself, so by
+    /// default the other databases stream without a bound.
+    fn stream_raw_with_timeout<'a>(
+        &'a self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Pin<Box<dyn Future<Output = Result<Self::Stream<'a>, DbErr>> + 'a + Send>> {
+        self.stream_raw(with_max_execution_time(stmt, timeout))
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
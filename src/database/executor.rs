@@ -7,6 +7,7 @@ use crate::{
 use crate::{Schema, SchemaBuilder};
 use std::future::Future;
 use std::pin::Pin;
+use std::time::Duration;
 
 /// Either a borrowed [`DatabaseConnection`] / [`DatabaseTransaction`], or an
 /// owned [`DatabaseTransaction`].
@@ -79,6 +80,68 @@ impl ConnectionTrait for DatabaseExecutor<'_> {
             DatabaseExecutor::OwnedTransaction(trans) => trans.query_all_raw(stmt).await,
         }
     }
+
+    async fn execute_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<ExecResult, DbErr> {
+        match self {
+            DatabaseExecutor::Connection(conn) => {
+                conn.execute_raw_with_timeout(stmt, timeout).await
+            }
+            DatabaseExecutor::Transaction(trans) => {
+                trans.execute_raw_with_timeout(stmt, timeout).await
+            }
+            DatabaseExecutor::OwnedTransaction(trans) => {
+                trans.execute_raw_with_timeout(stmt, timeout).await
+            }
+        }
+    }
+
+    async fn query_one_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<Option<QueryResult>, DbErr> {
+        match self {
+            DatabaseExecutor::Connection(conn) => {
+                conn.query_one_raw_with_timeout(stmt, timeout).await
+            }
+            DatabaseExecutor::Transaction(trans) => {
+                trans.query_one_raw_with_timeout(stmt, timeout).await
+            }
+            DatabaseExecutor::OwnedTransaction(trans) => {
+                trans.query_one_raw_with_timeout(stmt, timeout).await
+            }
+        }
+    }
+
+    async fn query_all_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<Vec<QueryResult>, DbErr> {
+        match self {
+            DatabaseExecutor::Connection(conn) => {
+                conn.query_all_raw_with_timeout(stmt, timeout).await
+            }
+            DatabaseExecutor::Transaction(trans) => {
+                trans.query_all_raw_with_timeout(stmt, timeout).await
+            }
+            DatabaseExecutor::OwnedTransaction(trans) => {
+                trans.query_all_raw_with_timeout(stmt, timeout).await
+            }
+        }
+    }
+
+    async fn begin_atomic(&self) -> Result<Option<DatabaseTransaction>, DbErr> {
+        match self {
+            DatabaseExecutor::Connection(conn) => conn.begin_atomic().await,
+            DatabaseExecutor::Transaction(trans) => trans.begin_atomic().await,
+            DatabaseExecutor::OwnedTransaction(trans) => trans.begin_atomic().await,
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -0,0 +1,865 @@
+//! An in-memory database behind [`ProxyDatabaseTrait`], for unit tests that need real
+//! query semantics but not a real database.
+
+mod engine;
+mod parser;
+
+use crate::{
+    DatabaseConnection, DatabaseConnectionType, DbBackend, EntityTrait, ProxyDatabaseConnection,
+    ProxyDatabaseTrait, ProxyExecResult, ProxyRow, Schema, Statement, error::*,
+};
+use engine::{Column, Database, Table};
+use parser::{Expr, SelectItem};
+use sea_query::{Query, SimpleExpr, TableCreateStatement, TableName, TableRef};
+use std::sync::{Arc, Mutex};
+
+/// A database that keeps its tables in memory and runs the statements SeaORM builds
+/// against them, with filters, joins, ordering, limits, aggregates, upserts and
+/// `RETURNING` evaluated as the chosen backend would.
+///
+/// Tables are created from entities with [`MemoryDatabase::with_entity`], the same way
+/// [`Schema::create_table_from_entity`] creates them, including primary keys, unique
+/// keys, auto-increment and column defaults. Foreign keys are not enforced.
+///
+/// A transaction works on a snapshot of the tables taken when it begins, and its changes
+/// replace the tables when it commits. As in SQLite, one transaction writes at a time: a
+/// write fails with `database is locked` while another transaction has written, and in a
+/// transaction once another write has been committed since it began.
+///
+/// ```
+/// # use sea_orm::{DbBackend, DbErr, MemoryDatabase, entity::*, query::*, tests_cfg::*};
+/// # async fn function() -> Result<(), DbErr> {
+/// let db = MemoryDatabase::new(DbBackend::Postgres)
+///     .with_entity(cake::Entity)
+///     .into_connection();
+///
+/// cake::ActiveModel {
+///     name: Set("Chocolate Forest".to_owned()),
+///     ..Default::default()
+/// }
+/// .insert(&db)
+/// .await?;
+///
+/// assert_eq!(cake::Entity::find().count(&db).await?, 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MemoryDatabase {
+    db_backend: DbBackend,
+    state: Arc<Mutex<State>>,
+}
+
+/// The committed tables
+#[derive(Debug)]
+struct State {
+    db: Database,
+    /// Counts the writes committed, for a transaction to tell whether its snapshot is stale
+    version: u64,
+    /// Whether a transaction has written, so that nothing else may
+    locked: bool,
+}
+
+/// A transaction, on a connection of its own
+#[derive(Debug)]
+struct MemoryTransaction {
+    db_backend: DbBackend,
+    state: Arc<Mutex<State>>,
+    txn: Mutex<Transaction>,
+}
+
+#[derive(Debug)]
+struct Transaction {
+    /// The tables as this transaction sees them
+    db: Database,
+    /// The version of the committed tables its snapshot was taken from
+    version: u64,
+    /// Whether it holds the write lock
+    writing: bool,
+    /// The tables as they were when the transaction, each transaction nested within it and
+    /// each savepoint began; savepoints are named
+    snapshots: Vec<(Option<String>, Database)>,
+}
+
+const LOCKED: &str = "database is locked";
+
+impl MemoryDatabase {
+    /// Create an empty database for the given backend, whose SQL dialect it accepts
+    pub fn new(db_backend: DbBackend) -> Self {
+        Self {
+            db_backend,
+            state: Arc::new(Mutex::new(State {
+                db: Database::new(db_backend),
+                version: 0,
+                locked: false,
+            })),
+        }
+    }
+
+    /// Create the table of an entity, with its unique keys
+    ///
+    /// # Panics
+    ///
+    /// Panics if a column default is an expression the database cannot evaluate
+    pub fn with_entity<E>(self, entity: E) -> Self
+    where
+        E: EntityTrait,
+    {
+        let schema = Schema::new(self.db_backend);
+        let mut stmt = schema.create_table_from_entity(entity);
+        for mut index in schema.create_index_from_entity(entity) {
+            if index.is_unique_key() {
+                stmt.index(&mut index);
+            }
+        }
+        if let Err(err) = self.create_table(&stmt) {
+            panic!("Failed to create table for {}: {err}", entity.table_name());
+        }
+        self
+    }
+
+    /// Create a table, replacing any table of the same name. Unique indexes that are part
+    /// of the statement become unique keys.
+    pub fn create_table(&self, stmt: &TableCreateStatement) -> Result<(), DbErr> {
+        let name = match stmt.get_table_name() {
+            Some(TableRef::Table(TableName(_, name), _)) => name.to_string(),
+            _ => return Err(exec_err("Table name should not be empty")),
+        };
+        let mut columns = Vec::new();
+        let mut primary_key = Vec::new();
+        let mut unique_keys = Vec::new();
+        for column_def in stmt.get_columns() {
+            let spec = column_def.get_column_spec();
+            let column_name = column_def.get_column_name();
+            let col_type = column_def
+                .get_column_type()
+                .cloned()
+                .ok_or_else(|| exec_err(format!("Column {column_name} has no type")))?;
+            let default = match &spec.default {
+                Some(default) => Some(self.parse_default(default)?),
+                None => None,
+            };
+            if spec.primary_key {
+                primary_key.push(column_name.clone());
+            }
+            if spec.unique {
+                unique_keys.push(vec![column_name.clone()]);
+            }
+            columns.push(Column::new(
+                column_name,
+                col_type,
+                spec.nullable != Some(false),
+                spec.auto_increment,
+                default,
+            ));
+        }
+        for index in stmt.get_indexes() {
+            let column_names = index.get_index_spec().get_column_names();
+            if index.is_primary_key() {
+                primary_key = column_names;
+            } else if index.is_unique_key() {
+                unique_keys.push(column_names);
+            }
+        }
+
+        let table = Table::new(name, columns, &primary_key, &unique_keys).map_err(exec_err)?;
+        let mut state = self.state.lock().map_err(exec_err)?;
+        state.db.create_table(table);
+        state.version += 1;
+        Ok(())
+    }
+
+    /// Create a database connection
+    pub fn into_connection(self) -> DatabaseConnection {
+        DatabaseConnectionType::ProxyDatabaseConnection(Arc::new(ProxyDatabaseConnection::new(
+            self.db_backend,
+            Arc::new(Box::new(self)),
+        )))
+        .into()
+    }
+
+    /// Turn a column default into an expression, by building it into SQL as
+    /// `SELECT <default>` and parsing that back
+    fn parse_default(&self, default: &SimpleExpr) -> Result<Expr, DbErr> {
+        let stmt = self
+            .db_backend
+            .build(&Query::select().expr(default.clone()).to_owned());
+        let values = stmt.values.map(|values| values.0).unwrap_or_default();
+        match parser::parse(self.db_backend, &stmt.sql, &values).map_err(exec_err)? {
+            parser::Statement::Select(mut select) if select.items.len() == 1 => {
+                match select.items.remove(0) {
+                    SelectItem::Expr(expr, _) => Ok(expr),
+                    SelectItem::Wildcard(_) => Err(exec_err("Unsupported column default")),
+                }
+            }
+            _ => Err(exec_err(format!(
+                "Unsupported column default: {}",
+                stmt.sql
+            ))),
+        }
+    }
+
+    fn run(&self, statement: &Statement) -> Result<engine::Output, String> {
+        let parsed = parse(self.db_backend, statement)?;
+        let mut state = self.state.lock().map_err(|e| e.to_string())?;
+        match parsed {
+            parser::Statement::Savepoint(_)
+            | parser::Statement::RollbackTo(_)
+            | parser::Statement::Release(_) => Err("No transaction is open".to_owned()),
+            parsed @ parser::Statement::Select(_) => state.db.execute(&parsed),
+            parsed => {
+                if state.locked {
+                    return Err(LOCKED.to_owned());
+                }
+                state.version += 1;
+                state.db.execute(&parsed)
+            }
+        }
+    }
+}
+
+fn parse(db_backend: DbBackend, statement: &Statement) -> Result<parser::Statement, String> {
+    let values = match &statement.values {
+        Some(values) => values.0.as_slice(),
+        None => &[],
+    };
+    parser::parse(db_backend, &statement.sql, values)
+}
+
+impl MemoryTransaction {
+    fn run(&self, statement: &Statement) -> Result<engine::Output, String> {
+        let parsed = parse(self.db_backend, statement)?;
+        let mut txn = self.txn.lock().map_err(|e| e.to_string())?;
+        if txn.snapshots.is_empty() {
+            return Err("The transaction is closed".to_owned());
+        }
+        match parsed {
+            parser::Statement::Savepoint(name) => {
+                let snapshot = txn.db.clone();
+                txn.snapshots.push((Some(name), snapshot));
+                Ok(Default::default())
+            }
+            parser::Statement::RollbackTo(name) => {
+                let i = txn.savepoint(&name)?;
+                // the savepoint remains, so that it can be rolled back to again
+                txn.snapshots.truncate(i + 1);
+                txn.db = txn.snapshots[i].1.clone();
+                Ok(Default::default())
+            }
+            parser::Statement::Release(name) => {
+                let i = txn.savepoint(&name)?;
+                txn.snapshots.truncate(i);
+                Ok(Default::default())
+            }
+            parsed @ parser::Statement::Select(_) => txn.db.execute(&parsed),
+            parsed => {
+                if !txn.writing {
+                    let mut state = self.state.lock().map_err(|e| e.to_string())?;
+                    if state.locked || state.version != txn.version {
+                        return Err(LOCKED.to_owned());
+                    }
+                    state.locked = true;
+                    txn.writing = true;
+                }
+                txn.db.execute(&parsed)
+            }
+        }
+    }
+
+    /// Close the innermost transaction; closing the outermost one commits its changes, or
+    /// discards them, and gives up the write lock
+    fn end(&self, commit: bool) {
+        let Ok(mut txn) = self.txn.lock() else {
+            return;
+        };
+        let Some(snapshot) = txn.end_transaction() else {
+            return;
+        };
+        if !commit {
+            txn.db = snapshot;
+        }
+        if txn.snapshots.is_empty() && txn.writing {
+            txn.writing = false;
+            if let Ok(mut state) = self.state.lock() {
+                if commit {
+                    state.db = txn.db.clone();
+                    state.version += 1;
+                }
+                state.locked = false;
+            }
+        }
+    }
+}
+
+impl Transaction {
+    /// The position of the latest savepoint called `name`
+    fn savepoint(&self, name: &str) -> Result<usize, String> {
+        self.snapshots
+            .iter()
+            .rposition(|(savepoint, _)| savepoint.as_deref() == Some(name))
+            .ok_or_else(|| format!("No such savepoint: {name}"))
+    }
+
+    /// Close the innermost transaction and the savepoints within it, returning the
+    /// tables as they were when it began
+    fn end_transaction(&mut self) -> Option<Database> {
+        while let Some((name, snapshot)) = self.snapshots.pop() {
+            if name.is_none() {
+                return Some(snapshot);
+            }
+        }
+        None
+    }
+}
+
+#[async_trait::async_trait]
+impl ProxyDatabaseTrait for MemoryDatabase {
+    async fn query(&self, statement: Statement) -> Result<Vec<ProxyRow>, DbErr> {
+        let output = self.run(&statement).map_err(query_err)?;
+        Ok(output.rows.into_iter().map(ProxyRow::new).collect())
+    }
+
+    async fn execute(&self, statement: Statement) -> Result<ProxyExecResult, DbErr> {
+        let output = self.run(&statement).map_err(exec_err)?;
+        Ok(ProxyExecResult::new(
+            output.last_insert_id,
+            output.rows_affected,
+        ))
+    }
+
+    async fn transaction_connection(&self) -> Option<Arc<Box<dyn ProxyDatabaseTrait>>> {
+        let state = self.state.lock().expect("MemoryDatabase lock is poisoned");
+        let txn = Transaction {
+            db: state.db.clone(),
+            version: state.version,
+            writing: false,
+            snapshots: Vec::new(),
+        };
+        Some(Arc::new(Box::new(MemoryTransaction {
+            db_backend: self.db_backend,
+            state: Arc::clone(&self.state),
+            txn: Mutex::new(txn),
+        })))
+    }
+}
+
+#[async_trait::async_trait]
+impl ProxyDatabaseTrait for MemoryTransaction {
+    async fn query(&self, statement: Statement) -> Result<Vec<ProxyRow>, DbErr> {
+        let output = self.run(&statement).map_err(query_err)?;
+        Ok(output.rows.into_iter().map(ProxyRow::new).collect())
+    }
+
+    async fn execute(&self, statement: Statement) -> Result<ProxyExecResult, DbErr> {
+        let output = self.run(&statement).map_err(exec_err)?;
+        Ok(ProxyExecResult::new(
+            output.last_insert_id,
+            output.rows_affected,
+        ))
+    }
+
+    async fn begin(&self) {
+        let mut txn = self.txn.lock().expect("MemoryDatabase lock is poisoned");
+        let snapshot = txn.db.clone();
+        txn.snapshots.push((None, snapshot));
+    }
+
+    async fn commit(&self) {
+        self.end(true);
+    }
+
+    async fn rollback(&self) {
+        self.end(false);
+    }
+
+    fn start_rollback(&self) {
+        self.end(false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MemoryDatabase;
+    use crate::{
+        ConnectionTrait, DbBackend, DbErr, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect,
+        Statement, TransactionTrait, entity::*, sea_query::OnConflict, tests_cfg::*,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn new_db(db_backend: DbBackend) -> crate::DatabaseConnection {
+        MemoryDatabase::new(db_backend)
+            .with_entity(cake::Entity)
+            .with_entity(fruit::Entity)
+            .into_connection()
+    }
+
+    async fn seed(db: &crate::DatabaseConnection) -> Result<(), DbErr> {
+        cake::Entity::insert_many([
+            cake::ActiveModel {
+                name: Set("Chocolate Forest".to_owned()),
+                ..Default::default()
+            },
+            cake::ActiveModel {
+                name: Set("New York Cheese".to_owned()),
+                ..Default::default()
+            },
+        ])
+        .exec(db)
+        .await?;
+        fruit::Entity::insert_many([
+            fruit::ActiveModel {
+                name: Set("Cherry".to_owned()),
+                cake_id: Set(Some(1)),
+                ..Default::default()
+            },
+            fruit::ActiveModel {
+                name: Set("Blueberry".to_owned()),
+                cake_id: Set(Some(1)),
+                ..Default::default()
+            },
+            fruit::ActiveModel {
+                name: Set("Apple".to_owned()),
+                cake_id: Set(None),
+                ..Default::default()
+            },
+        ])
+        .exec(db)
+        .await?;
+        Ok(())
+    }
+
+    /// Run `sql` and read column `col` of every row
+    async fn column<T>(
+        db: &crate::DatabaseConnection,
+        sql: &str,
+        col: &str,
+    ) -> Result<Vec<T>, DbErr>
+    where
+        T: crate::TryGetable,
+    {
+        db.query_all_raw(Statement::from_string(db.get_database_backend(), sql))
+            .await?
+            .iter()
+            .map(|row| row.try_get("", col))
+            .collect()
+    }
+
+    #[smol_potat::test]
+    async fn test_crud() -> Result<(), DbErr> {
+        for db_backend in [DbBackend::MySql, DbBackend::Postgres, DbBackend::Sqlite] {
+            let db = new_db(db_backend);
+            seed(&db).await?;
+
+            let cheese = cake::Entity::find()
+                .filter(cake::Column::Name.contains("Cheese"))
+                .one(&db)
+                .await?
+                .unwrap();
+            assert_eq!(cheese.id, 2);
+
+            let mut cheese = cheese.into_active_model();
+            cheese.name = Set("Basque Cheese".to_owned());
+            let cheese = cheese.update(&db).await?;
+            assert_eq!(cheese.name, "Basque Cheese");
+
+            let res = fruit::Entity::delete_many()
+                .filter(fruit::Column::CakeId.is_null())
+                .exec(&db)
+                .await?;
+            assert_eq!(res.rows_affected, 1);
+
+            assert_eq!(
+                fruit::Entity::find()
+                    .order_by_asc(fruit::Column::Name)
+                    .all(&db)
+                    .await?,
+                [
+                    fruit::Model {
+                        id: 2,
+                        name: "Blueberry".to_owned(),
+                        cake_id: Some(1),
+                    },
+                    fruit::Model {
+                        id: 1,
+                        name: "Cherry".to_owned(),
+                        cake_id: Some(1),
+                    },
+                ]
+            );
+        }
+        Ok(())
+    }
+
+    #[smol_potat::test]
+    async fn test_join_and_aggregate() -> Result<(), DbErr> {
+        let db = new_db(DbBackend::Postgres);
+        seed(&db).await?;
+
+        let cakes = cake::Entity::find()
+            .find_with_related(fruit::Entity)
+            .order_by_asc(cake::Column::Id)
+            .all(&db)
+            .await?;
+        assert_eq!(cakes.len(), 2);
+        assert_eq!(cakes[0].1.len(), 2);
+        assert_eq!(cakes[1].1.len(), 0);
+
+        let counts: Vec<(String, i64)> = cake::Entity::find()
+            .select_only()
+            .column(cake::Column::Name)
+            .column_as(fruit::Column::Id.count(), "num_fruits")
+            .left_join(fruit::Entity)
+            .group_by(cake::Column::Name)
+            .order_by_desc(fruit::Column::Id.count())
+            .into_tuple()
+            .all(&db)
+            .await?;
+        assert_eq!(
+            counts,
+            [
+                ("Chocolate Forest".to_owned(), 2),
+                ("New York Cheese".to_owned(), 0),
+            ]
+        );
+
+        assert_eq!(fruit::Entity::find().count(&db).await?, 3);
+        Ok(())
+    }
+
+    #[smol_potat::test]
+    async fn test_upsert_and_returning() -> Result<(), DbErr> {
+        let db = new_db(DbBackend::Postgres);
+        seed(&db).await?;
+
+        let cake = cake::Entity::insert(cake::ActiveModel {
+            id: Set(2),
+            name: Set("Lemon Drizzle".to_owned()),
+        })
+        .on_conflict(
+            OnConflict::column(cake::Column::Id)
+                .update_column(cake::Column::Name)
+                .to_owned(),
+        )
+        .exec_with_returning(&db)
+        .await?;
+        assert_eq!(
+            cake,
+            cake::Model {
+                id: 2,
+                name: "Lemon Drizzle".to_owned(),
+            }
+        );
+        assert_eq!(cake::Entity::find().count(&db).await?, 2);
+
+        let err = cake::Entity::insert(cake::ActiveModel {
+            id: Set(1),
+            name: Set("Duplicate".to_owned()),
+        })
+        .exec(&db)
+        .await
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("UNIQUE constraint failed: cake.id")
+        );
+        Ok(())
+    }
+
+    #[smol_potat::test]
+    async fn test_transaction_rollback() -> Result<(), DbErr> {
+        let db = new_db(DbBackend::Sqlite);
+        seed(&db).await?;
+
+        let txn = db.begin().await?;
+        fruit::Entity::delete_many().exec(&txn).await?;
+        {
+            let nested = txn.begin().await?;
+            cake::Entity::delete_by_id(1).exec(&nested).await?;
+            nested.rollback().await?;
+        }
+        assert_eq!(fruit::Entity::find().count(&txn).await?, 0);
+        assert_eq!(cake::Entity::find().count(&txn).await?, 2);
+        txn.rollback().await?;
+
+        assert_eq!(fruit::Entity::find().count(&db).await?, 3);
+        Ok(())
+    }
+
+    #[smol_potat::test]
+    async fn test_null_semantics() -> Result<(), DbErr> {
+        let db = new_db(DbBackend::Sqlite);
+        seed(&db).await?;
+
+        let sql = "SELECT NULL = NULL AS eq, NULL IS NULL AS is_null, NULL <> 1 AS ne, \
+                   NULL + 1 AS sum, COALESCE(NULL, 2) AS coalesced, NULL IN (1, NULL) AS in_list";
+        for (col, value) in [
+            ("eq", None),
+            ("is_null", Some(true)),
+            ("ne", None),
+            ("in_list", None),
+        ] {
+            assert_eq!(
+                column::<Option<bool>>(&db, sql, col).await?,
+                [value],
+                "{col}"
+            );
+        }
+        assert_eq!(column::<Option<i64>>(&db, sql, "sum").await?, [None]);
+        assert_eq!(
+            column::<Option<i64>>(&db, sql, "coalesced").await?,
+            [Some(2)]
+        );
+
+        // a comparison with NULL matches no row, whichever way round it is
+        for filter in [
+            "cake_id = NULL",
+            "cake_id <> 1",
+            "NOT (cake_id = 1)",
+            "cake_id NOT IN (1)",
+        ] {
+            let sql = format!("SELECT name FROM fruit WHERE {filter}");
+            assert_eq!(
+                column::<String>(&db, &sql, "name").await?,
+                [""; 0],
+                "{filter}"
+            );
+        }
+        let sql = "SELECT name FROM fruit WHERE cake_id IS NOT DISTINCT FROM NULL";
+        let err = column::<String>(&db, sql, "name").await.unwrap_err();
+        assert!(matches!(err, DbErr::Query(_)), "{err}");
+
+        assert_eq!(
+            fruit::Entity::find()
+                .filter(fruit::Column::CakeId.is_not_in([2]))
+                .count(&db)
+                .await?,
+            2
+        );
+
+        // aggregates skip NULLs, and are NULL over no rows, except for COUNT
+        let sql = "SELECT COUNT(*) AS n, COUNT(cake_id) AS n_cake, SUM(cake_id) AS total, \
+                   MAX(cake_id) AS max FROM fruit";
+        assert_eq!(column::<i64>(&db, sql, "n").await?, [3]);
+        assert_eq!(column::<i64>(&db, sql, "n_cake").await?, [2]);
+        assert_eq!(column::<Option<i64>>(&db, sql, "total").await?, [Some(2)]);
+        assert_eq!(column::<Option<i32>>(&db, sql, "max").await?, [Some(1)]);
+        let sql = "SELECT COUNT(cake_id) AS n, SUM(cake_id) AS total FROM fruit WHERE id > 3";
+        assert_eq!(column::<i64>(&db, sql, "n").await?, [0]);
+        assert_eq!(column::<Option<i64>>(&db, sql, "total").await?, [None]);
+
+        // NULLs sort first in ascending order on SQLite and MySQL, last on Postgres
+        let sql = "SELECT name FROM fruit ORDER BY cake_id, id";
+        assert_eq!(
+            column::<String>(&db, sql, "name").await?,
+            ["Apple", "Cherry", "Blueberry"]
+        );
+        Ok(())
+    }
+
+    #[smol_potat::test]
+    async fn test_joins() -> Result<(), DbErr> {
+        let db = new_db(DbBackend::Postgres);
+        seed(&db).await?;
+
+        let fruits = fruit::Entity::find()
+            .find_also_related(cake::Entity)
+            .order_by_asc(fruit::Column::Id)
+            .all(&db)
+            .await?;
+        let names: Vec<_> = fruits
+            .iter()
+            .map(|(fruit, cake)| (fruit.name.as_str(), cake.as_ref().map(|c| c.name.as_str())))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("Cherry", Some("Chocolate Forest")),
+                ("Blueberry", Some("Chocolate Forest")),
+                ("Apple", None),
+            ]
+        );
+
+        let sql = r#"SELECT "fruit"."name" FROM "fruit" INNER JOIN "cake" ON "fruit"."cake_id" = "cake"."id" ORDER BY "fruit"."name""#;
+        assert_eq!(
+            column::<String>(&db, sql, "name").await?,
+            ["Blueberry", "Cherry"]
+        );
+
+        // a condition in ON keeps the unmatched rows of a LEFT JOIN, one in WHERE drops them
+        let sql = r#"SELECT "cake"."name" FROM "cake" LEFT JOIN "fruit" ON "fruit"."cake_id" = "cake"."id" AND "fruit"."name" = 'Apple' ORDER BY "cake"."id""#;
+        assert_eq!(
+            column::<String>(&db, sql, "name").await?,
+            ["Chocolate Forest", "New York Cheese"]
+        );
+        let sql = r#"SELECT "cake"."name" FROM "cake" LEFT JOIN "fruit" ON "fruit"."cake_id" = "cake"."id" WHERE "fruit"."name" = 'Apple'"#;
+        assert_eq!(column::<String>(&db, sql, "name").await?, [""; 0]);
+        Ok(())
+    }
+
+    #[smol_potat::test]
+    async fn test_aggregates() -> Result<(), DbErr> {
+        let db = new_db(DbBackend::MySql);
+        seed(&db).await?;
+
+        // NULL keys form one group
+        let sql =
+            "SELECT `cake_id`, COUNT(*) AS `n` FROM `fruit` GROUP BY `cake_id` ORDER BY `cake_id`";
+        assert_eq!(
+            column::<Option<i32>>(&db, sql, "cake_id").await?,
+            [None, Some(1)]
+        );
+        assert_eq!(column::<i64>(&db, sql, "n").await?, [1, 2]);
+
+        let sql = "SELECT `cake_id` FROM `fruit` GROUP BY `cake_id` HAVING COUNT(*) > 1";
+        assert_eq!(column::<Option<i32>>(&db, sql, "cake_id").await?, [Some(1)]);
+
+        let sql = "SELECT MIN(`name`) AS `first`, AVG(`id`) AS `mean` FROM `fruit`";
+        assert_eq!(column::<String>(&db, sql, "first").await?, ["Apple"]);
+        assert_eq!(column::<f64>(&db, sql, "mean").await?, [2.0]);
+
+        let sql = "SELECT COUNT(DISTINCT `cake_id`) AS `n` FROM `fruit`";
+        assert_eq!(column::<i64>(&db, sql, "n").await?, [1]);
+
+        let sql = "SELECT FROBNICATE(`id`) AS `n` FROM `fruit`";
+        let err = column::<i64>(&db, sql, "n").await.unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Unsupported function FROBNICATE()"),
+            "{err}"
+        );
+        Ok(())
+    }
+
+    #[smol_potat::test]
+    async fn test_upserts() -> Result<(), DbErr> {
+        for db_backend in [DbBackend::MySql, DbBackend::Postgres, DbBackend::Sqlite] {
+            let db = new_db(db_backend);
+            seed(&db).await?;
+
+            let res = cake::Entity::insert(cake::ActiveModel {
+                id: Set(1),
+                name: Set("Ignored".to_owned()),
+            })
+            // MySQL has no DO NOTHING, so a column is set to itself instead
+            .on_conflict(
+                OnConflict::column(cake::Column::Id)
+                    .do_nothing_on([cake::Column::Id])
+                    .to_owned(),
+            )
+            .exec(&db)
+            .await;
+            // which counts as a row found
+            if db_backend != DbBackend::MySql {
+                assert!(
+                    matches!(res, Err(DbErr::RecordNotInserted)),
+                    "{db_backend:?}"
+                );
+            }
+
+            cake::Entity::insert_many([
+                cake::ActiveModel {
+                    id: Set(2),
+                    name: Set("Tiramisu".to_owned()),
+                },
+                cake::ActiveModel {
+                    id: Set(3),
+                    name: Set("Pavlova".to_owned()),
+                },
+            ])
+            .on_conflict(
+                OnConflict::column(cake::Column::Id)
+                    .update_column(cake::Column::Name)
+                    .to_owned(),
+            )
+            .exec(&db)
+            .await?;
+
+            assert_eq!(
+                cake::Entity::find()
+                    .order_by_asc(cake::Column::Id)
+                    .all(&db)
+                    .await?
+                    .into_iter()
+                    .map(|cake| cake.name)
+                    .collect::<Vec<_>>(),
+                ["Chocolate Forest", "Tiramisu", "Pavlova"],
+                "{db_backend:?}"
+            );
+        }
+        Ok(())
+    }
+
+    #[smol_potat::test]
+    async fn test_errors() -> Result<(), DbErr> {
+        let db = new_db(DbBackend::Sqlite);
+        seed(&db).await?;
+
+        for (sql, message) in [
+            ("SELECT * FROM pie", "pie"),
+            ("SELECT flavour FROM cake", "flavour"),
+            ("SELEC * FROM cake", "SELEC"),
+            ("SELECT * FROM cake WHERE", ""),
+        ] {
+            let err = column::<i32>(&db, sql, "id").await.unwrap_err();
+            assert!(matches!(err, DbErr::Query(_)), "{sql}: {err}");
+            assert!(err.to_string().contains(message), "{sql}: {err}");
+        }
+
+        let err = db
+            .execute_unprepared("INSERT INTO fruit (id, cake_id) VALUES (9, 1)")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("NOT NULL"), "{err}");
+
+        let err = db.execute_unprepared("SAVEPOINT sp").await.unwrap_err();
+        assert!(err.to_string().contains("No transaction is open"), "{err}");
+
+        // a failed statement leaves the tables as they were
+        assert_eq!(fruit::Entity::find().count(&db).await?, 3);
+        Ok(())
+    }
+
+    #[smol_potat::test]
+    async fn test_concurrent_transactions() -> Result<(), DbErr> {
+        let db = new_db(DbBackend::Sqlite);
+        seed(&db).await?;
+
+        // a rollback discards the changes of its own transaction only
+        let txn = db.begin().await?;
+        let other = db.begin().await?;
+        cake::Entity::delete_by_id(2).exec(&txn).await?;
+        assert_eq!(cake::Entity::find().count(&txn).await?, 1);
+        assert_eq!(cake::Entity::find().count(&other).await?, 2);
+        assert_eq!(cake::Entity::find().count(&db).await?, 2);
+
+        // one transaction writes at a time
+        let err = fruit::Entity::delete_many().exec(&other).await.unwrap_err();
+        assert!(err.to_string().contains("database is locked"), "{err}");
+        let err = fruit::Entity::delete_many().exec(&db).await.unwrap_err();
+        assert!(err.to_string().contains("database is locked"), "{err}");
+        other.rollback().await?;
+        txn.rollback().await?;
+
+        let txn = db.begin().await?;
+        fruit::Entity::delete_by_id(3).exec(&db).await?;
+        txn.rollback().await?;
+        assert_eq!(fruit::Entity::find().count(&db).await?, 2);
+        assert_eq!(cake::Entity::find().count(&db).await?, 2);
+
+        // a transaction cannot write over changes committed since it began
+        let txn = db.begin().await?;
+        let other = db.begin().await?;
+        cake::Entity::delete_by_id(2).exec(&other).await?;
+        other.commit().await?;
+        let err = cake::Entity::delete_by_id(1).exec(&txn).await.unwrap_err();
+        assert!(err.to_string().contains("database is locked"), "{err}");
+        txn.rollback().await?;
+
+        let txn = db.begin().await?;
+        fruit::Entity::delete_many().exec(&txn).await?;
+        txn.commit().await?;
+        assert_eq!(fruit::Entity::find().count(&db).await?, 0);
+        assert_eq!(cake::Entity::find().count(&db).await?, 1);
+        Ok(())
+    }
+}
@@ -9,38 +9,73 @@ use sqlx::postgres::PgConnectOptions;
 #[cfg(feature = "sqlx-sqlite")]
 use sqlx::sqlite::SqliteConnectOptions;
 
+mod advisory_lock;
+#[cfg(feature = "query-cache")]
+mod cache;
 mod connection;
 mod db_connection;
 mod executor;
+#[cfg(feature = "memory-db")]
+#[cfg_attr(docsrs, doc(cfg(feature = "memory-db")))]
+mod memory;
 #[cfg(feature = "mock")]
 #[cfg_attr(docsrs, doc(cfg(feature = "mock")))]
 mod mock;
 #[cfg(feature = "proxy")]
 #[cfg_attr(docsrs, doc(cfg(feature = "proxy")))]
 mod proxy;
+#[cfg(all(feature = "proxy", feature = "with-json"))]
+mod proxy_protocol;
+#[cfg(feature = "proxy-server")]
+#[cfg_attr(docsrs, doc(cfg(feature = "proxy-server")))]
+mod proxy_server;
+#[cfg(all(feature = "mock", feature = "proxy", feature = "with-json"))]
+mod recording;
+mod replica;
 #[cfg(feature = "rbac")]
 mod restricted_connection;
+mod retry;
 #[cfg(all(feature = "schema-sync", feature = "rusqlite"))]
 mod sea_schema_rusqlite;
 #[cfg(all(feature = "schema-sync", feature = "sqlx-dep"))]
 mod sea_schema_shim;
+mod shard;
 mod statement;
 #[cfg(feature = "stream")]
 mod stream;
-mod tracing_spans;
-mod transaction;
-
+pub(crate) mod timeout;
+pub(crate) mod tracing_spans;
+pub(crate) mod transaction;
+#[cfg(all(feature = "proxy", feature = "with-json"))]
+mod value_serde;
+
+pub use advisory_lock::AdvisoryLock;
+#[cfg(feature = "query-cache")]
+pub use cache::{CachedConnection, CachedTransaction};
 pub use connection::*;
 pub use db_connection::*;
 pub use executor::*;
+#[cfg(feature = "memory-db")]
+#[cfg_attr(docsrs, doc(cfg(feature = "memory-db")))]
+pub use memory::MemoryDatabase;
 #[cfg(feature = "mock")]
 #[cfg_attr(docsrs, doc(cfg(feature = "mock")))]
 pub use mock::*;
 #[cfg(feature = "proxy")]
 #[cfg_attr(docsrs, doc(cfg(feature = "proxy")))]
 pub use proxy::*;
+#[cfg(all(feature = "proxy", feature = "with-json"))]
+pub use proxy_protocol::*;
+#[cfg(feature = "proxy-server")]
+#[cfg_attr(docsrs, doc(cfg(feature = "proxy-server")))]
+pub use proxy_server::*;
+#[cfg(all(feature = "mock", feature = "proxy", feature = "with-json"))]
+pub use recording::*;
+pub use replica::ReplicaSelection;
 #[cfg(feature = "rbac")]
 pub use restricted_connection::*;
+pub use retry::RetryPolicy;
+pub use shard::{ShardedConnection, UnroutedStatement};
 pub use statement::*;
 use std::borrow::Cow;
 #[cfg(feature = "stream")]
@@ -134,6 +169,10 @@ pub struct ConnectOptions {
     /// be created using SQLx's [connect_lazy](https://docs.rs/sqlx/latest/sqlx/struct.Pool.html#method.connect_lazy)
     /// method.
     pub(crate) connect_lazy: bool,
+    /// URIs of read replicas; reads are routed to them, writes stay on `url`
+    pub(crate) read_replicas: Vec<String>,
+    /// How a replica is picked for each read
+    pub(crate) replica_selection: ReplicaSelection,
 
     #[debug(skip)]
     pub(crate) after_connect: AfterConnectCallback,
@@ -178,8 +217,31 @@ impl Database {
     where
         C: Into<ConnectOptions>,
     {
-        let opt: ConnectOptions = opt.into();
+        let mut opt: ConnectOptions = opt.into();
+
+        let replica_selection = opt.replica_selection;
+        let replica_opts: Vec<ConnectOptions> = std::mem::take(&mut opt.read_replicas)
+            .into_iter()
+            .map(|url| {
+                let mut replica_opt = opt.clone();
+                replica_opt.url = url;
+                replica_opt
+            })
+            .collect();
+
+        let conn = Self::connect_one(opt).await?;
+        if replica_opts.is_empty() {
+            return Ok(conn);
+        }
+
+        let mut replicas = Vec::with_capacity(replica_opts.len());
+        for replica_opt in replica_opts {
+            replicas.push(Self::connect_one(replica_opt).await?);
+        }
+        Ok(conn.with_read_replicas(replicas, replica_selection))
+    }
 
+    async fn connect_one(opt: ConnectOptions) -> Result<DatabaseConnection, DbErr> {
         if url::Url::parse(&opt.url).is_err() {
             return Err(conn_err(format!(
                 "The connection string '{}' cannot be parsed.",
@@ -203,6 +265,10 @@ impl Database {
         if DbBackend::Sqlite.is_prefix_of(&opt.url) {
             return crate::driver::rusqlite::RusqliteConnector::connect(opt);
         }
+        #[cfg(feature = "duckdb")]
+        if crate::DuckdbConnector::accepts(&opt.url) {
+            return crate::DuckdbConnector::connect(opt);
+        }
         #[cfg(feature = "mock")]
         if crate::MockDatabaseConnector::accepts(&opt.url) {
             return crate::MockDatabaseConnector::connect(&opt.url).await;
@@ -279,6 +345,8 @@ impl ConnectOptions {
             test_before_acquire: true,
             test_before_acquire_if_idle_for: None,
             connect_lazy: false,
+            read_replicas: Vec::new(),
+            replica_selection: ReplicaSelection::RoundRobin,
             after_connect: None,
             #[cfg(feature = "sqlx-mysql")]
             mysql_pool_opts_fn: None,
@@ -561,6 +629,59 @@ impl ConnectOptions {
         self.connect_lazy
     }
 
+    /// Add the URI of a read replica.
+    ///
+    /// When at least one replica is set, [`Database::connect`] opens a pool for each of them
+    /// with the same options as the primary, and the returned [`DatabaseConnection`] sends
+    /// plain `SELECT` queries to a replica. Writes, [`execute_unprepared`], locking reads and
+    /// everything inside a [`DatabaseTransaction`] go to the primary. Use
+    /// [`DatabaseConnection::use_primary`] to read your own writes.
+    ///
+    /// [`execute_unprepared`]: crate::ConnectionTrait::execute_unprepared
+    ///
+    /// # Example
+    /// ```
+    /// # use sea_orm::{ConnectOptions, ReplicaSelection};
+    /// let mut opt = ConnectOptions::new("postgres://primary/db");
+    /// opt.read_replica("postgres://replica-1/db")
+    ///     .read_replica("postgres://replica-2/db")
+    ///     .replica_selection(ReplicaSelection::LeastBusy);
+    /// assert_eq!(opt.get_read_replicas().len(), 2);
+    /// ```
+    pub fn read_replica<T>(&mut self, url: T) -> &mut Self
+    where
+        T: Into<String>,
+    {
+        self.read_replicas.push(url.into());
+        self
+    }
+
+    /// Add the URIs of several read replicas; see [`read_replica`](Self::read_replica).
+    pub fn read_replicas<I, T>(&mut self, urls: I) -> &mut Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.read_replicas.extend(urls.into_iter().map(Into::into));
+        self
+    }
+
+    /// Get the URIs of the read replicas
+    pub fn get_read_replicas(&self) -> &[String] {
+        &self.read_replicas
+    }
+
+    /// Set how a read replica is picked for each read (default round-robin)
+    pub fn replica_selection(&mut self, value: ReplicaSelection) -> &mut Self {
+        self.replica_selection = value;
+        self
+    }
+
+    /// Get how a read replica is picked for each read
+    pub fn get_replica_selection(&self) -> ReplicaSelection {
+        self.replica_selection
+    }
+
     /// Set a callback function that will be called after a new connection is established.
     pub fn after_connect<F>(&mut self, f: F) -> &mut Self
     where
@@ -1,7 +1,7 @@
 use crate::{ExecResult, ExecResultHolder, QueryResult, QueryResultRow, Statement, error::*};
 
 use sea_query::{Value, ValueType};
-use std::{collections::BTreeMap, fmt::Debug};
+use std::{collections::BTreeMap, fmt::Debug, sync::Arc};
 
 /// Defines the [ProxyDatabaseTrait] to save the functions
 #[async_trait::async_trait]
@@ -12,6 +12,13 @@ pub trait ProxyDatabaseTrait: Send + Sync + Debug {
     /// Execute a command in the [ProxyDatabase], and report the number of rows affected
     async fn execute(&self, statement: Statement) -> Result<ProxyExecResult, DbErr>;
 
+    /// A connection of its own for a new transaction to run on, for a [ProxyDatabase] that
+    /// tells transactions apart. The transaction is begun, run, and committed or rolled back
+    /// on the returned connection. By default it runs on this one.
+    async fn transaction_connection(&self) -> Option<Arc<Box<dyn ProxyDatabaseTrait>>> {
+        None
+    }
+
     /// Begin a transaction in the [ProxyDatabase]
     async fn begin(&self) {}
 
@@ -65,7 +72,13 @@ impl From<ProxyExecResult> for ExecResult {
 
 impl From<ExecResult> for ProxyExecResult {
     fn from(result: ExecResult) -> Self {
-        match result.result {
+        Self::from(&result)
+    }
+}
+
+impl From<&ExecResult> for ProxyExecResult {
+    fn from(result: &ExecResult) -> Self {
+        match &result.result {
             #[cfg(feature = "sqlx-mysql")]
             ExecResultHolder::SqlxMySql(result) => Self {
                 last_insert_id: result.last_insert_id(),
@@ -81,12 +94,26 @@ impl From<ExecResult> for ProxyExecResult {
                 last_insert_id: result.last_insert_rowid() as u64,
                 rows_affected: result.rows_affected(),
             },
+            #[cfg(feature = "rusqlite")]
+            ExecResultHolder::Rusqlite(result) => Self {
+                last_insert_id: result.last_insert_rowid as u64,
+                rows_affected: result.rows_affected,
+            },
+            #[cfg(feature = "duckdb")]
+            ExecResultHolder::Duckdb(result) => Self {
+                last_insert_id: 0,
+                rows_affected: result.rows_affected,
+            },
             #[cfg(feature = "mock")]
             ExecResultHolder::Mock(result) => Self {
                 last_insert_id: result.last_insert_id,
                 rows_affected: result.rows_affected,
             },
-            ExecResultHolder::Proxy(result) => result,
+            ExecResultHolder::Proxy(result) => result.clone(),
+            ExecResultHolder::FannedOut { rows_affected } => Self {
+                last_insert_id: 0,
+                rows_affected: *rows_affected,
+            },
         }
     }
 }
@@ -94,8 +121,10 @@ impl From<ExecResult> for ProxyExecResult {
 /// Defines the structure of a Row for the [ProxyDatabase]
 /// which is just a [BTreeMap]<[String], [Value]>
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "with-json", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProxyRow {
     /// The values of the single row
+    #[cfg_attr(feature = "with-json", serde(with = "super::value_serde::map"))]
     pub values: BTreeMap<String, Value>,
 }
 
@@ -151,6 +180,12 @@ pub fn from_query_result_to_proxy_row(result: &QueryResult) -> ProxyRow {
         QueryResultRow::SqlxPostgres(row) => crate::from_sqlx_postgres_row_to_proxy_row(row),
         #[cfg(feature = "sqlx-sqlite")]
         QueryResultRow::SqlxSqlite(row) => crate::from_sqlx_sqlite_row_to_proxy_row(row),
+        #[cfg(feature = "rusqlite")]
+        QueryResultRow::Rusqlite(row) => {
+            crate::driver::rusqlite::from_rusqlite_row_to_proxy_row(row)
+        }
+        #[cfg(feature = "duckdb")]
+        QueryResultRow::Duckdb(row) => crate::driver::duckdb::from_duckdb_row_to_proxy_row(row),
         #[cfg(feature = "mock")]
         QueryResultRow::Mock(row) => ProxyRow {
             values: row.values.clone(),
@@ -195,9 +230,9 @@ impl ProxyRow {
 mod tests {
     use crate::{
         Database, DbBackend, DbErr, ProxyDatabaseTrait, ProxyExecResult, ProxyRow, Statement,
-        entity::*, tests_cfg::*,
+        TransactionTrait, entity::*, tests_cfg::*,
     };
-    use std::sync::Arc;
+    use std::sync::{Arc, Mutex};
 
     #[derive(Debug)]
     struct ProxyDb {}
@@ -247,4 +282,68 @@ mod tests {
 
         cake::Entity::insert(item).exec(&db).await.unwrap();
     }
+
+    #[derive(Debug)]
+    struct RecordingProxyDb {
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl RecordingProxyDb {
+        fn record(&self, entry: impl Into<String>) {
+            self.log.lock().unwrap().push(entry.into());
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ProxyDatabaseTrait for RecordingProxyDb {
+        async fn query(&self, statement: Statement) -> Result<Vec<ProxyRow>, DbErr> {
+            self.record(statement.sql);
+            Ok(vec![])
+        }
+
+        async fn execute(&self, statement: Statement) -> Result<ProxyExecResult, DbErr> {
+            self.record(statement.sql);
+            Ok(ProxyExecResult::new(0, 0))
+        }
+
+        async fn begin(&self) {
+            self.record("begin");
+        }
+
+        async fn commit(&self) {
+            self.record("commit");
+        }
+
+        async fn rollback(&self) {
+            self.record("rollback");
+        }
+    }
+
+    #[smol_potat::test]
+    async fn named_savepoint() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let proxy = RecordingProxyDb {
+            log: Arc::clone(&log),
+        };
+        let db = Database::connect_proxy(DbBackend::MySql, Arc::new(Box::new(proxy)))
+            .await
+            .unwrap();
+
+        let txn = db.begin().await.unwrap();
+        txn.savepoint("before_import").await.unwrap();
+        txn.rollback_to("before_import").await.unwrap();
+        txn.release("before_import").await.unwrap();
+        txn.commit().await.unwrap();
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            [
+                "begin",
+                "SAVEPOINT `before_import`",
+                "ROLLBACK TO SAVEPOINT `before_import`",
+                "RELEASE SAVEPOINT `before_import`",
+                "commit",
+            ]
+        );
+    }
 }
@@ -0,0 +1,451 @@
+use crate::{DatabaseConnection, DbErr, ProxyServer, ProxySession, ProxyTransport, error::*};
+use std::{io, time::Duration};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+
+/// The largest request accepted, as a line over a Unix socket or a body over HTTP
+const MAX_REQUEST_BODY: usize = 64 * 1024 * 1024;
+
+/// The longest start line or header line of an HTTP request
+const MAX_HEAD_LINE: usize = 64 * 1024;
+
+/// How often the transactions of a served [`ProxyServer`] are checked for being idle
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Expose `db` to [`ProxyClient`](crate::ProxyClient)s through `listener`, a
+/// [`tokio::net::UnixListener`] or a [`tokio::net::TcpListener`], until accepting a
+/// connection fails.
+///
+/// Over a Unix socket, requests and responses are JSON documents, one per line of at most
+/// 64 MiB, and the transactions a connection leaves open are rolled back when it closes.
+/// Over TCP, each request is the body of an HTTP/1.1 `POST` of at most 64 MiB, with
+/// header lines of at most 64 KiB, answered with the response as the body. Either way,
+/// transactions left unused for longer than the [idle timeout](ProxyServer::idle_timeout)
+/// are rolled back. There is no authentication: bind it to a socket only trusted clients
+/// can reach.
+///
+/// ```ignore
+/// let listener = tokio::net::UnixListener::bind("/tmp/sea-orm.sock")?;
+/// tokio::spawn(sea_orm::serve_proxy(db, listener));
+///
+/// let db = ProxyClient::connect(UnixSocketTransport::new("/tmp/sea-orm.sock")).await?;
+/// ```
+pub async fn serve_proxy<L>(db: DatabaseConnection, listener: L) -> Result<(), DbErr>
+where
+    L: ProxyListener,
+{
+    listener.serve(ProxyServer::new(db)).await
+}
+
+/// A listener [`serve_proxy`] can accept connections on
+#[async_trait::async_trait]
+pub trait ProxyListener: Send {
+    /// Answer the requests of every accepted connection with `server`
+    async fn serve(self, server: ProxyServer) -> Result<(), DbErr>;
+}
+
+#[cfg(unix)]
+#[async_trait::async_trait]
+impl ProxyListener for tokio::net::UnixListener {
+    async fn serve(self, server: ProxyServer) -> Result<(), DbErr> {
+        let _idle = roll_back_idle(server.clone());
+        loop {
+            let (stream, _) = self.accept().await.map_err(conn_err)?;
+            let mut session = server.session();
+            tokio::spawn(async move {
+                let res = serve_lines(stream, &mut session).await;
+                session.close().await;
+                if let Err(err) = res {
+                    tracing::warn!("Proxy connection failed: {err}");
+                }
+            });
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ProxyListener for TcpListener {
+    async fn serve(self, server: ProxyServer) -> Result<(), DbErr> {
+        let _idle = roll_back_idle(server.clone());
+        loop {
+            let (stream, _) = self.accept().await.map_err(conn_err)?;
+            let server = server.clone();
+            tokio::spawn(async move {
+                if let Err(err) = serve_http(stream, server).await {
+                    tracing::warn!("Proxy connection failed: {err}");
+                }
+            });
+        }
+    }
+}
+
+/// Roll back the idle transactions of `server` periodically, until the returned guard
+/// is dropped
+fn roll_back_idle(server: ProxyServer) -> AbortOnDrop {
+    AbortOnDrop(tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(IDLE_CHECK_INTERVAL).await;
+            server.roll_back_idle().await;
+        }
+    }))
+}
+
+struct AbortOnDrop(JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+#[cfg(unix)]
+async fn serve_lines(stream: tokio::net::UnixStream, session: &mut ProxySession) -> io::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    while let Some(request) = read_line(&mut reader, MAX_REQUEST_BODY).await? {
+        let mut response = session
+            .handle_json(&request)
+            .await
+            .map_err(io::Error::other)?;
+        response.push('\n');
+        writer.write_all(response.as_bytes()).await?;
+    }
+    Ok(())
+}
+
+async fn serve_http(stream: TcpStream, server: ProxyServer) -> io::Result<()> {
+    let mut stream = BufReader::new(stream);
+    loop {
+        let Some((request_line, headers)) = read_head(&mut stream).await? else {
+            return Ok(());
+        };
+        let mut close =
+            header(&headers, "connection").is_some_and(|v| v.eq_ignore_ascii_case("close"));
+
+        // a body that is not read leaves the stream out of step, so refusing a request
+        // closes the connection
+        let refusal = if !request_line.starts_with("POST ") {
+            Some("405 Method Not Allowed")
+        } else if header(&headers, "transfer-encoding").is_some() {
+            Some("411 Length Required")
+        } else {
+            match content_length(&headers) {
+                Ok(len) if len > MAX_REQUEST_BODY => Some("413 Content Too Large"),
+                Ok(_) => None,
+                Err(_) => Some("400 Bad Request"),
+            }
+        };
+        let (status, body) = match refusal {
+            Some(status) => {
+                close = true;
+                (status, String::new())
+            }
+            None => {
+                let mut body = vec![0; content_length(&headers)?];
+                stream.read_exact(&mut body).await?;
+                match String::from_utf8(body) {
+                    Ok(request) => (
+                        "200 OK",
+                        server
+                            .handle_json(&request)
+                            .await
+                            .map_err(io::Error::other)?,
+                    ),
+                    Err(_) => ("400 Bad Request", String::new()),
+                }
+            }
+        };
+        let connection = if close { "close" } else { "keep-alive" };
+        let response = format!(
+            "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: {connection}\r\n\r\n{body}",
+            body.len()
+        );
+        stream.get_mut().write_all(response.as_bytes()).await?;
+        if close {
+            return Ok(());
+        }
+    }
+}
+
+/// Read the start line and the headers of an HTTP message, `None` at the end of the stream
+async fn read_head<R>(reader: &mut R) -> io::Result<Option<(String, Vec<(String, String)>)>>
+where
+    R: AsyncBufReadExt + Unpin,
+{
+    let Some(start_line) = read_line(reader, MAX_HEAD_LINE).await? else {
+        return Ok(None);
+    };
+    let mut headers = Vec::new();
+    loop {
+        let Some(line) = read_line(reader, MAX_HEAD_LINE).await? else {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        };
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_owned()));
+        }
+    }
+    Ok(Some((start_line, headers)))
+}
+
+/// Read a line of at most `limit` bytes, without its line ending, `None` at the end of
+/// the stream. A longer line is an error, so that a client cannot exhaust our memory.
+async fn read_line<R>(reader: &mut R, limit: usize) -> io::Result<Option<String>>
+where
+    R: AsyncBufReadExt + Unpin,
+{
+    let mut line = Vec::new();
+    let len = (&mut *reader)
+        .take(limit as u64 + 1)
+        .read_until(b'\n', &mut line)
+        .await?;
+    if len == 0 {
+        return Ok(None);
+    }
+    if line.last() == Some(&b'\n') {
+        line.pop();
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+    } else if line.len() > limit {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("request line longer than {limit} bytes"),
+        ));
+    }
+    String::from_utf8(line)
+        .map(Some)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, value)| value.as_str())
+}
+
+fn content_length(headers: &[(String, String)]) -> io::Result<usize> {
+    header(headers, "content-length")
+        .map_or(Ok(0), str::parse)
+        .map_err(io::Error::other)
+}
+
+/// A [`ProxyTransport`] to a [`serve_proxy`] listening on a Unix socket, over one
+/// connection kept open between requests
+#[cfg(unix)]
+#[derive(Debug)]
+pub struct UnixSocketTransport {
+    path: std::path::PathBuf,
+    stream: futures_util::lock::Mutex<Option<BufReader<tokio::net::UnixStream>>>,
+}
+
+#[cfg(unix)]
+impl UnixSocketTransport {
+    /// Create a transport connecting to the socket at `path` on the first request
+    pub fn new<P>(path: P) -> Self
+    where
+        P: Into<std::path::PathBuf>,
+    {
+        Self {
+            path: path.into(),
+            stream: Default::default(),
+        }
+    }
+
+    async fn exchange(
+        &self,
+        stream: &mut BufReader<tokio::net::UnixStream>,
+        request: &str,
+    ) -> io::Result<String> {
+        stream.get_mut().write_all(request.as_bytes()).await?;
+        stream.get_mut().write_all(b"\n").await?;
+        let mut response = String::new();
+        if stream.read_line(&mut response).await? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(unix)]
+#[async_trait::async_trait]
+impl ProxyTransport for UnixSocketTransport {
+    async fn send(&self, request: String) -> Result<String, DbErr> {
+        let mut slot = self.stream.lock().await;
+        let mut stream = match slot.take() {
+            Some(stream) => stream,
+            None => BufReader::new(
+                tokio::net::UnixStream::connect(&self.path)
+                    .await
+                    .map_err(conn_err)?,
+            ),
+        };
+        // the connection is dropped on error, and opened again on the next request
+        let response = self
+            .exchange(&mut stream, &request)
+            .await
+            .map_err(conn_err)?;
+        *slot = Some(stream);
+        Ok(response)
+    }
+}
+
+/// A [`ProxyTransport`] to a [`serve_proxy`] listening on TCP, sending each request as
+/// an HTTP/1.1 `POST` on a new connection
+#[derive(Debug, Clone)]
+pub struct HttpTransport {
+    addr: String,
+}
+
+impl HttpTransport {
+    /// Create a transport to the server at `addr`, e.g. `127.0.0.1:8080`
+    pub fn new<A>(addr: A) -> Self
+    where
+        A: Into<String>,
+    {
+        Self { addr: addr.into() }
+    }
+
+    async fn exchange(&self, request: &str) -> io::Result<String> {
+        let mut stream = BufReader::new(TcpStream::connect(&self.addr).await?);
+        let request = format!(
+            "POST / HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{request}",
+            self.addr,
+            request.len()
+        );
+        stream.get_mut().write_all(request.as_bytes()).await?;
+
+        let Some((status_line, headers)) = read_head(&mut stream).await? else {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        };
+        if status_line.split_whitespace().nth(1) != Some("200") {
+            return Err(io::Error::other(format!(
+                "Unexpected proxy response: {status_line}"
+            )));
+        }
+        let mut body = vec![0; content_length(&headers)?];
+        stream.read_exact(&mut body).await?;
+        String::from_utf8(body).map_err(io::Error::other)
+    }
+}
+
+#[async_trait::async_trait]
+impl ProxyTransport for HttpTransport {
+    async fn send(&self, request: String) -> Result<String, DbErr> {
+        self.exchange(&request).await.map_err(conn_err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ConnectionTrait, DbBackend, MockDatabase, ProxyClient, entity::*, tests_cfg::cake,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn mock_db() -> DatabaseConnection {
+        MockDatabase::new(DbBackend::Sqlite)
+            .append_query_results([[cake::Model {
+                id: 1,
+                name: "Cheese Cake".to_owned(),
+            }]])
+            .into_connection()
+    }
+
+    async fn find_cakes(db: &DatabaseConnection) -> Result<(), DbErr> {
+        assert_eq!(db.get_database_backend(), DbBackend::Sqlite);
+        assert_eq!(
+            cake::Entity::find().all(db).await?,
+            [cake::Model {
+                id: 1,
+                name: "Cheese Cake".to_owned(),
+            }]
+        );
+        db.ping().await
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_serve_proxy_unix() -> Result<(), DbErr> {
+        let path = std::env::temp_dir().join(format!("sea-orm-proxy-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = tokio::net::UnixListener::bind(&path).map_err(conn_err)?;
+        tokio::spawn(serve_proxy(mock_db(), listener));
+
+        let db = ProxyClient::connect(UnixSocketTransport::new(&path)).await?;
+        find_cakes(&db).await?;
+
+        std::fs::remove_file(&path).map_err(conn_err)
+    }
+
+    #[tokio::test]
+    async fn test_serve_proxy_http() -> Result<(), DbErr> {
+        let listener = TcpListener::bind("127.0.0.1:0").await.map_err(conn_err)?;
+        let addr = listener.local_addr().map_err(conn_err)?;
+        tokio::spawn(serve_proxy(mock_db(), listener));
+
+        let db = ProxyClient::connect(HttpTransport::new(addr.to_string())).await?;
+        find_cakes(&db).await
+    }
+
+    #[tokio::test]
+    async fn test_read_line() -> io::Result<()> {
+        let mut reader = "{}\r\nabcd\nabcde\n".as_bytes();
+        assert_eq!(read_line(&mut reader, 4).await?, Some("{}".to_owned()));
+        assert_eq!(read_line(&mut reader, 4).await?, Some("abcd".to_owned()));
+        let err = read_line(&mut reader, 4)
+            .await
+            .expect_err("the line is longer than the limit");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let mut reader = "last".as_bytes();
+        assert_eq!(read_line(&mut reader, 4).await?, Some("last".to_owned()));
+        assert_eq!(read_line(&mut reader, 4).await?, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_serve_proxy_http_refused() -> Result<(), DbErr> {
+        let listener = TcpListener::bind("127.0.0.1:0").await.map_err(conn_err)?;
+        let addr = listener.local_addr().map_err(conn_err)?;
+        tokio::spawn(serve_proxy(mock_db(), listener));
+
+        for (request, status) in [
+            (
+                "GET / HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}".to_owned(),
+                "405",
+            ),
+            (
+                format!(
+                    "POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+                    MAX_REQUEST_BODY + 1
+                ),
+                "413",
+            ),
+        ] {
+            let mut stream = TcpStream::connect(addr).await.map_err(conn_err)?;
+            stream
+                .write_all(request.as_bytes())
+                .await
+                .map_err(conn_err)?;
+            // the connection is closed after the response
+            let mut response = String::new();
+            stream
+                .read_to_string(&mut response)
+                .await
+                .map_err(conn_err)?;
+            assert_eq!(response.split_whitespace().nth(1), Some(status));
+            assert!(response.contains("Connection: close"));
+        }
+
+        Ok(())
+    }
+}
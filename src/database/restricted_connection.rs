@@ -17,6 +17,7 @@ use std::{
     future::Future,
     pin::Pin,
     sync::{Arc, RwLock},
+    time::Duration,
 };
 use tracing::instrument;
 
@@ -89,6 +90,33 @@ impl ConnectionTrait for RestrictedConnection {
         self.user_can_run(stmt)?;
         self.conn.query_all(stmt).await
     }
+
+    async fn execute_with_timeout<S: StatementBuilder>(
+        &self,
+        stmt: &S,
+        timeout: Duration,
+    ) -> Result<ExecResult, DbErr> {
+        self.user_can_run(stmt)?;
+        self.conn.execute_with_timeout(stmt, timeout).await
+    }
+
+    async fn query_one_with_timeout<S: StatementBuilder>(
+        &self,
+        stmt: &S,
+        timeout: Duration,
+    ) -> Result<Option<QueryResult>, DbErr> {
+        self.user_can_run(stmt)?;
+        self.conn.query_one_with_timeout(stmt, timeout).await
+    }
+
+    async fn query_all_with_timeout<S: StatementBuilder>(
+        &self,
+        stmt: &S,
+        timeout: Duration,
+    ) -> Result<Vec<QueryResult>, DbErr> {
+        self.user_can_run(stmt)?;
+        self.conn.query_all_with_timeout(stmt, timeout).await
+    }
 }
 
 #[async_trait::async_trait]
@@ -135,6 +163,33 @@ impl ConnectionTrait for RestrictedTransaction {
         self.user_can_run(stmt)?;
         self.conn.query_all(stmt).await
     }
+
+    async fn execute_with_timeout<S: StatementBuilder>(
+        &self,
+        stmt: &S,
+        timeout: Duration,
+    ) -> Result<ExecResult, DbErr> {
+        self.user_can_run(stmt)?;
+        self.conn.execute_with_timeout(stmt, timeout).await
+    }
+
+    async fn query_one_with_timeout<S: StatementBuilder>(
+        &self,
+        stmt: &S,
+        timeout: Duration,
+    ) -> Result<Option<QueryResult>, DbErr> {
+        self.user_can_run(stmt)?;
+        self.conn.query_one_with_timeout(stmt, timeout).await
+    }
+
+    async fn query_all_with_timeout<S: StatementBuilder>(
+        &self,
+        stmt: &S,
+        timeout: Duration,
+    ) -> Result<Vec<QueryResult>, DbErr> {
+        self.user_can_run(stmt)?;
+        self.conn.query_all_with_timeout(stmt, timeout).await
+    }
 }
 
 impl RestrictedConnection {
@@ -494,6 +549,25 @@ impl RestrictedTransaction {
     pub async fn rollback(self) -> Result<(), DbErr> {
         self.conn.rollback().await
     }
+
+    /// Set a savepoint named `name`, see [`DatabaseTransaction::savepoint`]
+    #[instrument(level = "trace")]
+    pub async fn savepoint(&self, name: &str) -> Result<(), DbErr> {
+        self.conn.savepoint(name).await
+    }
+
+    /// Undo everything done since the savepoint `name` was set, see
+    /// [`DatabaseTransaction::rollback_to`]
+    #[instrument(level = "trace")]
+    pub async fn rollback_to(&self, name: &str) -> Result<(), DbErr> {
+        self.conn.rollback_to(name).await
+    }
+
+    /// Remove the savepoint `name`, see [`DatabaseTransaction::release`]
+    #[instrument(level = "trace")]
+    pub async fn release(&self, name: &str) -> Result<(), DbErr> {
+        self.conn.release(name).await
+    }
 }
 
 impl RbacEngineMount {
@@ -0,0 +1,310 @@
+use super::transaction::run_async_transaction_callback;
+use crate::{
+    DbErr, SqlErr, TransactionError, TransactionOptions, TransactionSession, TransactionTrait,
+};
+use std::time::Duration;
+
+/// When and how often [`DatabaseConnection::transaction_with_retry`](crate::DatabaseConnection::transaction_with_retry)
+/// re-runs a transaction that failed with a transient error.
+///
+/// Between attempts it backs off exponentially: `initial_backoff` before the first
+/// retry, doubling on every further retry up to `max_backoff`.
+#[derive(Copy, Clone, Debug)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+    /// Upper bound of the delay between two attempts
+    pub max_backoff: Duration,
+    /// Pick each delay at random between half and all of its value, so that
+    /// conflicting transactions do not retry in lockstep
+    pub jitter: bool,
+    /// Retry on [`SqlErr::SerializationFailure`]
+    pub serialization_failure: bool,
+    /// Retry on [`SqlErr::Deadlock`]
+    pub deadlock: bool,
+    /// Also retry on any error this returns `true` for
+    pub retry_if: Option<fn(&DbErr) -> bool>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_secs(1),
+            jitter: true,
+            serialization_failure: true,
+            deadlock: true,
+            retry_if: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Whether a transaction that failed with `err` should be retried
+    pub fn is_retryable(&self, err: &DbErr) -> bool {
+        match err.sql_err() {
+            Some(SqlErr::SerializationFailure(_)) if self.serialization_failure => true,
+            Some(SqlErr::Deadlock(_)) if self.deadlock => true,
+            _ => self.retry_if.is_some_and(|retry_if| retry_if(err)),
+        }
+    }
+
+    /// Whether to retry after `err`. A [`DbErr`] returned by the closure may be
+    /// wrapped in its error's [`source`](std::error::Error::source) chain.
+    fn should_retry<E>(&self, err: &TransactionError<E>) -> bool
+    where
+        E: std::error::Error + 'static,
+    {
+        match err {
+            TransactionError::Connection(err) => self.is_retryable(err),
+            TransactionError::Transaction(err) => {
+                let mut source: Option<&(dyn std::error::Error + 'static)> = Some(err);
+                while let Some(err) = source {
+                    if let Some(err) = err.downcast_ref::<DbErr>() {
+                        return self.is_retryable(err);
+                    }
+                    source = err.source();
+                }
+                false
+            }
+        }
+    }
+
+    /// The delay before retry number `retry`, counting from 1
+    fn backoff(&self, retry: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .min(self.max_backoff);
+        if self.jitter {
+            backoff / 2 + (backoff / 2).mul_f64(random_fraction())
+        } else {
+            backoff
+        }
+    }
+}
+
+/// Run `callback` in a transaction started with `options`, starting over in a fresh
+/// transaction whenever it fails with an error `policy` deems retryable.
+pub(crate) async fn run_with_retry<C, F, T, E>(
+    conn: &C,
+    options: TransactionOptions,
+    policy: RetryPolicy,
+    mut callback: F,
+) -> Result<T, TransactionError<E>>
+where
+    C: TransactionTrait + Sync,
+    C::Transaction: TransactionSession + Send + Sync,
+    F: for<'c> AsyncFnMut(&'c C::Transaction) -> Result<T, E> + Send,
+    T: Send,
+    E: std::error::Error + Send + 'static,
+{
+    let mut attempt = 1;
+    loop {
+        let transaction = conn
+            .begin_with_options(options)
+            .await
+            .map_err(TransactionError::Connection)?;
+        match run_async_transaction_callback(transaction, &mut callback).await {
+            Err(err) if attempt < policy.max_attempts && policy.should_retry(&err) => {
+                tracing::debug!("Retrying transaction after attempt {attempt} failed: {err}");
+                sleep(policy.backoff(attempt)).await;
+                attempt += 1;
+            }
+            res => return res,
+        }
+    }
+}
+
+/// A number in `[0, 1)`, random enough to spread out retries.
+fn random_fraction() -> f64 {
+    use std::hash::{BuildHasher, Hasher};
+
+    let random = std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish();
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Waits out `duration` with the timer of the async runtime chosen by the `runtime-*`
+/// features, or on a helper thread if none is.
+#[cfg(not(feature = "sync"))]
+pub(super) async fn sleep(duration: Duration) {
+    if duration.is_zero() {
+        return;
+    }
+    #[cfg(feature = "runtime-tokio")]
+    tokio::time::sleep(duration).await;
+    #[cfg(all(feature = "runtime-async-std", not(feature = "runtime-tokio")))]
+    async_std::task::sleep(duration).await;
+    #[cfg(not(any(feature = "runtime-tokio", feature = "runtime-async-std")))]
+    sleep_on_thread(duration).await;
+}
+
+#[cfg(not(any(feature = "sync", feature = "runtime-tokio", feature = "runtime-async-std")))]
+async fn sleep_on_thread(duration: Duration) {
+    use std::{
+        sync::{Arc, Mutex},
+        task::{Poll, Waker},
+    };
+
+    let state: Arc<Mutex<(bool, Option<Waker>)>> = Default::default();
+    let timer = state.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(duration);
+        let mut timer = timer.lock().unwrap_or_else(|err| err.into_inner());
+        timer.0 = true;
+        if let Some(waker) = timer.1.take() {
+            waker.wake();
+        }
+    });
+    std::future::poll_fn(|cx| {
+        let mut state = state.lock().unwrap_or_else(|err| err.into_inner());
+        if state.0 {
+            Poll::Ready(())
+        } else {
+            state.1 = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+#[cfg(feature = "sync")]
+pub(super) fn sleep(duration: Duration) {
+    std::thread::sleep(duration)
+}
+
+#[cfg(test)]
+#[cfg(feature = "mock")]
+mod tests {
+    use super::RetryPolicy;
+    use crate::{
+        DbBackend, DbErr, EntityTrait, MockDatabase, MockExecResult, RuntimeErr, Statement,
+        Transaction, TransactionError, TransactionOptions, tests_cfg::*,
+    };
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    fn conflict() -> DbErr {
+        DbErr::Exec(RuntimeErr::Internal(
+            "could not serialize access".to_owned(),
+        ))
+    }
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            initial_backoff: Duration::from_millis(1),
+            retry_if: Some(|err| *err == conflict()),
+            ..Default::default()
+        }
+    }
+
+    fn delete_cake() -> Transaction {
+        Transaction::many([
+            Statement::from_string(DbBackend::Postgres, "BEGIN"),
+            Statement::from_sql_and_values(DbBackend::Postgres, r#"DELETE FROM "cake""#, []),
+        ])
+    }
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(50),
+            jitter: false,
+            ..Default::default()
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(10));
+        assert_eq!(policy.backoff(2), Duration::from_millis(20));
+        assert_eq!(policy.backoff(3), Duration::from_millis(40));
+        assert_eq!(policy.backoff(4), Duration::from_millis(50));
+        assert_eq!(policy.backoff(100), Duration::from_millis(50));
+
+        let jittered = RetryPolicy {
+            jitter: true,
+            ..policy
+        };
+        for retry in 1..5 {
+            let backoff = jittered.backoff(retry);
+            assert!(backoff >= policy.backoff(retry) / 2);
+            assert!(backoff <= policy.backoff(retry));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_until_success() -> Result<(), DbErr> {
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_exec_errors([conflict(), conflict()])
+            .append_exec_results([MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .into_connection();
+
+        let mut attempts = 0;
+        let rows_affected = db
+            .transaction_with_retry(TransactionOptions::default(), policy(), async |txn| {
+                attempts += 1;
+                let res = cake::Entity::delete_many().exec(txn).await?;
+                Ok::<_, DbErr>(res.rows_affected)
+            })
+            .await
+            .unwrap();
+        assert_eq!(rows_affected, 1);
+        assert_eq!(attempts, 3);
+
+        let log = db.into_transaction_log();
+        assert_eq!(log.len(), 3);
+        for txn in log {
+            assert_eq!(txn.statements()[..2], delete_cake().statements()[..]);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_give_up() -> Result<(), DbErr> {
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_exec_errors([conflict(), conflict()])
+            .into_connection();
+
+        let res = db
+            .transaction_with_retry(
+                TransactionOptions::default(),
+                RetryPolicy {
+                    max_attempts: 2,
+                    ..policy()
+                },
+                async |txn| cake::Entity::delete_many().exec(txn).await,
+            )
+            .await;
+        assert!(matches!(res, Err(TransactionError::Transaction(err)) if err == conflict()));
+        assert_eq!(db.into_transaction_log().len(), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_on_other_errors() -> Result<(), DbErr> {
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_exec_errors([DbErr::Custom("bad data".to_owned())])
+            .into_connection();
+
+        let res = db
+            .transaction_with_retry(TransactionOptions::default(), policy(), async |txn| {
+                cake::Entity::delete_many().exec(txn).await
+            })
+            .await;
+        assert!(matches!(
+            res,
+            Err(TransactionError::Transaction(DbErr::Custom(_)))
+        ));
+        assert_eq!(db.into_transaction_log().len(), 1);
+
+        Ok(())
+    }
+}
@@ -74,19 +74,19 @@ impl sea_schema::Connection for crate::DatabaseExecutor<'_> {
 
 fn map_result(result: Result<Vec<QueryResult>, DbErr>) -> Result<Vec<SqlxRow>, SqlxError> {
     match result {
-        Ok(rows) => Ok(rows
+        Ok(rows) => rows
             .into_iter()
             .filter_map(|r| match r.row {
                 #[cfg(feature = "sqlx-mysql")]
-                QueryResultRow::SqlxMySql(r) => Some(SqlxRow::MySql(r)),
+                QueryResultRow::SqlxMySql(r) => Some(into_row(r).map(SqlxRow::MySql)),
                 #[cfg(feature = "sqlx-postgres")]
-                QueryResultRow::SqlxPostgres(r) => Some(SqlxRow::Postgres(r)),
+                QueryResultRow::SqlxPostgres(r) => Some(into_row(r).map(SqlxRow::Postgres)),
                 #[cfg(feature = "sqlx-sqlite")]
-                QueryResultRow::SqlxSqlite(r) => Some(SqlxRow::Sqlite(r)),
+                QueryResultRow::SqlxSqlite(r) => Some(into_row(r).map(SqlxRow::Sqlite)),
                 #[allow(unreachable_patterns)]
                 _ => None,
             })
-            .collect()),
+            .collect(),
         Err(err) => Err(match err {
             DbErr::Conn(RuntimeErr::SqlxError(err)) => {
                 Arc::into_inner(err).expect("Should only have one owner")
@@ -101,3 +101,14 @@ fn map_result(result: Result<Vec<QueryResult>, DbErr>) -> Result<Vec<SqlxRow>, S
         }),
     }
 }
+
+/// Take a row out of its `Arc`. SQLx rows can't be cloned, so a row shared with a
+/// cloned [`QueryResult`] (e.g. one held by the query cache) is an error.
+#[allow(dead_code)]
+fn into_row<R>(row: Arc<R>) -> Result<R, SqlxError> {
+    Arc::into_inner(row).ok_or_else(|| {
+        SqlxError::AnyDriverError(Box::new(DbErr::Custom(
+            "Cannot take a row shared with another QueryResult".to_owned(),
+        )))
+    })
+}
@@ -1,9 +1,11 @@
-use crate::DbBackend;
-#[cfg(feature = "rbac")]
+use crate::{DbBackend, query::WhereCondition};
+#[cfg(any(feature = "rbac", feature = "query-cache"))]
 pub use sea_query::audit::{AuditTrait, Error as AuditError, QueryAccessAudit};
-use sea_query::{MysqlQueryBuilder, PostgresQueryBuilder, SqliteQueryBuilder, inject_parameters};
+use sea_query::{
+    Condition, MysqlQueryBuilder, PostgresQueryBuilder, SqliteQueryBuilder, inject_parameters,
+};
 pub use sea_query::{Value, Values};
-use std::fmt;
+use std::{cell::RefCell, fmt};
 
 /// A SQL string together with its bound parameters, ready to send to a
 /// connection. Build one yourself with
@@ -32,12 +34,42 @@ pub trait StatementBuilder: Sync {
     /// Render `self` into a [`Statement`] for `db_backend`.
     fn build(&self, db_backend: &DbBackend) -> Statement;
 
-    #[cfg(feature = "rbac")]
-    /// Inspect the statement and produce the access request that
-    /// [`RbacEngine`](crate::rbac::RbacEngine) needs to authorise it.
+    #[cfg(any(feature = "rbac", feature = "query-cache"))]
+    /// Inspect the statement and list the tables it reads or writes. Used by
+    /// the RBAC engine to authorise it and by the query cache to invalidate.
     fn audit(&self) -> Result<QueryAccessAudit, AuditError>;
 }
 
+thread_local! {
+    /// Set while [`build_with_condition`] builds a statement, to the `WHERE` condition
+    /// of it once known
+    static CONDITION: RefCell<Option<Option<Condition>>> = const { RefCell::new(None) };
+}
+
+/// Build `stmt`, along with its `WHERE` condition if it is a `SELECT`, `UPDATE` or
+/// `DELETE`. [`StatementBuilder`] is implemented outside of the crate too, so the
+/// condition is handed back by `build` on the side.
+pub(crate) fn build_with_condition<S>(
+    stmt: &S,
+    db_backend: &DbBackend,
+) -> (Statement, Option<Condition>)
+where
+    S: StatementBuilder + ?Sized,
+{
+    CONDITION.set(Some(None));
+    let statement = stmt.build(db_backend);
+    (statement, CONDITION.take().flatten())
+}
+
+/// Keep the `WHERE` condition of the statement being built by [`build_with_condition`]
+fn keep_condition(stmt: &impl WhereCondition) {
+    CONDITION.with_borrow_mut(|kept| {
+        if let Some(kept @ None) = kept {
+            *kept = Some(stmt.where_condition());
+        }
+    });
+}
+
 impl Statement {
     /// Create a [Statement] from a [crate::DatabaseBackend] and a raw SQL statement
     pub fn from_string<T>(db_backend: DbBackend, stmt: T) -> Statement
@@ -115,14 +147,15 @@ macro_rules! build_postgres_stmt {
 }
 
 macro_rules! build_query_stmt {
-    ($stmt: ty) => {
+    ($stmt: ty $(, $keep_condition: ident)?) => {
         impl StatementBuilder for $stmt {
             fn build(&self, db_backend: &DbBackend) -> Statement {
+                $($keep_condition(self);)?
                 let stmt = build_any_stmt!(self, db_backend);
                 Statement::from_string_values_tuple(*db_backend, stmt)
             }
 
-            #[cfg(feature = "rbac")]
+            #[cfg(any(feature = "rbac", feature = "query-cache"))]
             fn audit(&self) -> Result<QueryAccessAudit, AuditError> {
                 AuditTrait::audit(self)
             }
@@ -131,9 +164,9 @@ macro_rules! build_query_stmt {
 }
 
 build_query_stmt!(sea_query::InsertStatement);
-build_query_stmt!(sea_query::SelectStatement);
-build_query_stmt!(sea_query::UpdateStatement);
-build_query_stmt!(sea_query::DeleteStatement);
+build_query_stmt!(sea_query::SelectStatement, keep_condition);
+build_query_stmt!(sea_query::UpdateStatement, keep_condition);
+build_query_stmt!(sea_query::DeleteStatement, keep_condition);
 build_query_stmt!(sea_query::WithQuery);
 
 macro_rules! build_schema_stmt {
@@ -144,9 +177,9 @@ macro_rules! build_schema_stmt {
                 Statement::from_string(*db_backend, stmt)
             }
 
-            #[cfg(feature = "rbac")]
+            #[cfg(any(feature = "rbac", feature = "query-cache"))]
             fn audit(&self) -> Result<QueryAccessAudit, AuditError> {
-                todo!("Audit not supported for {} yet", stringify!($stmt))
+                Err(AuditError::UnsupportedQuery)
             }
         }
     };
@@ -170,7 +203,7 @@ macro_rules! build_type_stmt {
                 Statement::from_string(*db_backend, stmt)
             }
 
-            #[cfg(feature = "rbac")]
+            #[cfg(any(feature = "rbac", feature = "query-cache"))]
             fn audit(&self) -> Result<QueryAccessAudit, AuditError> {
                 Err(AuditError::UnsupportedQuery)
             }
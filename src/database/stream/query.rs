@@ -11,6 +11,7 @@ use futures_util::TryStreamExt;
 use sqlx::Executor;
 
 use super::metric::MetricStream;
+use crate::database::timeout::DeadlineGuard;
 #[cfg(feature = "sqlx-dep")]
 use crate::driver::*;
 use crate::{DbErr, InnerConnection, QueryResult, Statement};
@@ -21,6 +22,8 @@ pub struct QueryStream {
     stmt: Statement,
     conn: InnerConnection,
     metric_callback: Option<crate::metric::Callback>,
+    /// Disarms the deadline of a statement bounded by a timeout once dropped
+    deadline: Option<DeadlineGuard>,
     #[borrows(mut conn, stmt, metric_callback)]
     #[not_covariant]
     stream: MetricStream<'this>,
@@ -39,11 +42,25 @@ impl QueryStream {
         stmt: Statement,
         conn: InnerConnection,
         metric_callback: Option<crate::metric::Callback>,
+    ) -> QueryStream {
+        Self::build_with_deadline(stmt, conn, metric_callback, None)
+    }
+
+    /// [`build`](Self::build), keeping the guard of the deadline the statement runs
+    /// under armed for as long as the stream lives
+    #[allow(dead_code)]
+    #[instrument(level = "trace", skip(metric_callback, deadline))]
+    pub(crate) fn build_with_deadline(
+        stmt: Statement,
+        conn: InnerConnection,
+        metric_callback: Option<crate::metric::Callback>,
+        deadline: Option<DeadlineGuard>,
     ) -> QueryStream {
         QueryStreamBuilder {
             stmt,
             conn,
             metric_callback,
+            deadline,
             stream_builder: |conn, stmt, _metric_callback| match conn {
                 #[cfg(feature = "sqlx-mysql")]
                 InnerConnection::MySql(c) => {
@@ -54,7 +71,7 @@ impl QueryStream {
                         .map_ok(Into::into)
                         .map_err(sqlx_error_to_query_err);
                     let elapsed = start.map(|s| s.elapsed().unwrap_or_default());
-                    MetricStream::new(_metric_callback, stmt, elapsed, stream)
+                    MetricStream::new(_metric_callback, stmt, false, elapsed, stream)
                 }
                 #[cfg(feature = "sqlx-postgres")]
                 InnerConnection::Postgres(c) => {
@@ -65,7 +82,7 @@ impl QueryStream {
                         .map_ok(Into::into)
                         .map_err(sqlx_error_to_query_err);
                     let elapsed = start.map(|s| s.elapsed().unwrap_or_default());
-                    MetricStream::new(_metric_callback, stmt, elapsed, stream)
+                    MetricStream::new(_metric_callback, stmt, false, elapsed, stream)
                 }
                 #[cfg(feature = "sqlx-sqlite")]
                 InnerConnection::Sqlite(c) => {
@@ -76,7 +93,7 @@ impl QueryStream {
                         .map_ok(Into::into)
                         .map_err(sqlx_error_to_query_err);
                     let elapsed = start.map(|s| s.elapsed().unwrap_or_default());
-                    MetricStream::new(_metric_callback, stmt, elapsed, stream)
+                    MetricStream::new(_metric_callback, stmt, false, elapsed, stream)
                 }
                 #[cfg(feature = "rusqlite")]
                 InnerConnection::Rusqlite(conn) => {
@@ -87,14 +104,25 @@ impl QueryStream {
                         Err(err) => Either::Right(std::iter::once(Err(err))),
                     };
                     let elapsed = start.map(|s| s.elapsed().unwrap_or_default());
-                    MetricStream::new(_metric_callback, stmt, elapsed, stream)
+                    MetricStream::new(_metric_callback, stmt, false, elapsed, stream)
+                }
+                #[cfg(feature = "duckdb")]
+                InnerConnection::Duckdb(conn) => {
+                    use itertools::Either;
+                    let start = _metric_callback.is_some().then(std::time::SystemTime::now);
+                    let stream = match conn.stream(stmt) {
+                        Ok(rows) => Either::Left(rows.into_iter().map(Ok)),
+                        Err(err) => Either::Right(std::iter::once(Err(err))),
+                    };
+                    let elapsed = start.map(|s| s.elapsed().unwrap_or_default());
+                    MetricStream::new(_metric_callback, stmt, false, elapsed, stream)
                 }
                 #[cfg(feature = "mock")]
                 InnerConnection::Mock(c) => {
                     let start = _metric_callback.is_some().then(std::time::SystemTime::now);
                     let stream = c.fetch(stmt);
                     let elapsed = start.map(|s| s.elapsed().unwrap_or_default());
-                    MetricStream::new(_metric_callback, stmt, elapsed, stream)
+                    MetricStream::new(_metric_callback, stmt, false, elapsed, stream)
                 }
                 #[cfg(feature = "proxy")]
                 InnerConnection::Proxy(c) => {
@@ -106,7 +134,7 @@ impl QueryStream {
                         })
                     });
                     let elapsed = start.map(|s| s.elapsed().unwrap_or_default());
-                    MetricStream::new(_metric_callback, stmt, elapsed, stream)
+                    MetricStream::new(_metric_callback, stmt, false, elapsed, stream)
                 }
                 #[allow(unreachable_patterns)]
                 _ => unreachable!(),
@@ -0,0 +1,243 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+use crate::{
+    ConnectionTrait, DatabaseTransaction, DbBackend, DbErr, ExecResult, QueryResult, SqlErr,
+    Statement, StatementBuilder,
+};
+
+/// Number of SQLite virtual machine instructions between deadline checks.
+#[cfg_attr(
+    not(any(feature = "sqlx-sqlite", feature = "rusqlite")),
+    allow(dead_code)
+)]
+pub(crate) const PROGRESS_HANDLER_OPS: i32 = 1000;
+
+/// A timeout in whole milliseconds, at least 1: a limit of 0 means "no limit"
+/// to both Postgres and MySQL.
+pub(crate) fn timeout_millis(timeout: Duration) -> u128 {
+    timeout.as_millis().max(1)
+}
+
+/// Add a `MAX_EXECUTION_TIME` optimizer hint to a MySQL `SELECT`. MySQL
+/// ignores the hint on anything else, so other statements are left as they are.
+pub(crate) fn with_max_execution_time(mut stmt: Statement, timeout: Duration) -> Statement {
+    if stmt.db_backend != DbBackend::MySql {
+        return stmt;
+    }
+    let sql = stmt.sql.trim_start();
+    if sql
+        .get(..6)
+        .is_some_and(|word| word.eq_ignore_ascii_case("SELECT"))
+    {
+        let offset = stmt.sql.len() - sql.len() + 6;
+        stmt.sql.insert_str(
+            offset,
+            &format!(" /*+ MAX_EXECUTION_TIME({}) */", timeout_millis(timeout)),
+        );
+    }
+    stmt
+}
+
+/// Turn the error of a statement that ran out of time into [`DbErr::QueryTimeout`].
+pub(crate) fn map_timeout_err<T>(res: Result<T, DbErr>, timeout: Duration) -> Result<T, DbErr> {
+    res.map_err(|err| timeout_err(err, timeout))
+}
+
+/// [`DbErr::QueryTimeout`] if `err` is that of a statement that ran out of time.
+pub(crate) fn timeout_err(err: DbErr, timeout: Duration) -> DbErr {
+    match err.sql_err() {
+        Some(SqlErr::StatementTimeout(_)) => DbErr::QueryTimeout(timeout),
+        _ => err,
+    }
+}
+
+/// The point in time after which a SQLite progress handler interrupts the
+/// running statement. Inert once the [`DeadlineGuard`] it came from is dropped,
+/// so a handler left behind on a pooled connection never fires.
+#[cfg_attr(
+    not(any(feature = "sqlx-sqlite", feature = "rusqlite")),
+    allow(dead_code)
+)]
+#[derive(Debug, Clone)]
+pub(crate) struct Deadline {
+    at: Instant,
+    armed: Arc<AtomicBool>,
+}
+
+/// Disarms its [`Deadline`] when dropped.
+#[derive(Debug)]
+pub(crate) struct DeadlineGuard(Arc<AtomicBool>);
+
+#[cfg_attr(
+    not(any(feature = "sqlx-sqlite", feature = "rusqlite")),
+    allow(dead_code)
+)]
+impl Deadline {
+    pub(crate) fn new(timeout: Duration) -> (Self, DeadlineGuard) {
+        let armed = Arc::new(AtomicBool::new(true));
+        let deadline = Self {
+            at: Instant::now() + timeout,
+            armed: Arc::clone(&armed),
+        };
+        (deadline, DeadlineGuard(armed))
+    }
+
+    pub(crate) fn expired(&self) -> bool {
+        self.armed.load(Ordering::Relaxed) && Instant::now() >= self.at
+    }
+}
+
+impl Drop for DeadlineGuard {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+}
+
+/// What has to be undone once a statement bounded by
+/// `DatabaseTransaction::arm_timeout` has finished.
+#[derive(Debug)]
+pub(crate) enum ArmedTimeout {
+    /// The statement carries its own bound, or none could be set
+    None,
+    /// The Postgres `statement_timeout` in effect before
+    #[cfg_attr(not(feature = "sqlx-postgres"), allow(dead_code))]
+    StatementTimeout(String),
+    /// A SQLite progress handler
+    #[allow(dead_code)]
+    Deadline(DeadlineGuard),
+    /// A DuckDB interrupt, sent unless this is dropped first
+    #[cfg(feature = "duckdb")]
+    #[allow(dead_code)]
+    Interrupt(std::sync::mpsc::Sender<()>),
+}
+
+/// Runs every statement through the `*_with_timeout` methods of the wrapped
+/// connection; used by the executors of query builders with a `.timeout(..)`.
+#[derive(Debug)]
+pub(crate) struct TimeoutConnection<'a, C> {
+    conn: &'a C,
+    timeout: Option<Duration>,
+}
+
+impl<'a, C> TimeoutConnection<'a, C>
+where
+    C: ConnectionTrait,
+{
+    pub(crate) fn new(conn: &'a C, timeout: Option<Duration>) -> Self {
+        Self { conn, timeout }
+    }
+}
+
+#[async_trait::async_trait]
+impl<C> ConnectionTrait for TimeoutConnection<'_, C>
+where
+    C: ConnectionTrait,
+{
+    fn get_database_backend(&self) -> DbBackend {
+        self.conn.get_database_backend()
+    }
+
+    async fn execute_raw(&self, stmt: Statement) -> Result<ExecResult, DbErr> {
+        match self.timeout {
+            Some(timeout) => self.conn.execute_raw_with_timeout(stmt, timeout).await,
+            None => self.conn.execute_raw(stmt).await,
+        }
+    }
+
+    async fn execute<S: StatementBuilder>(&self, stmt: &S) -> Result<ExecResult, DbErr> {
+        match self.timeout {
+            Some(timeout) => self.conn.execute_with_timeout(stmt, timeout).await,
+            None => self.conn.execute(stmt).await,
+        }
+    }
+
+    async fn execute_unprepared(&self, sql: &str) -> Result<ExecResult, DbErr> {
+        self.conn.execute_unprepared(sql).await
+    }
+
+    async fn query_one_raw(&self, stmt: Statement) -> Result<Option<QueryResult>, DbErr> {
+        match self.timeout {
+            Some(timeout) => self.conn.query_one_raw_with_timeout(stmt, timeout).await,
+            None => self.conn.query_one_raw(stmt).await,
+        }
+    }
+
+    async fn query_one<S: StatementBuilder>(&self, stmt: &S) -> Result<Option<QueryResult>, DbErr> {
+        match self.timeout {
+            Some(timeout) => self.conn.query_one_with_timeout(stmt, timeout).await,
+            None => self.conn.query_one(stmt).await,
+        }
+    }
+
+    async fn query_all_raw(&self, stmt: Statement) -> Result<Vec<QueryResult>, DbErr> {
+        match self.timeout {
+            Some(timeout) => self.conn.query_all_raw_with_timeout(stmt, timeout).await,
+            None => self.conn.query_all_raw(stmt).await,
+        }
+    }
+
+    async fn query_all<S: StatementBuilder>(&self, stmt: &S) -> Result<Vec<QueryResult>, DbErr> {
+        match self.timeout {
+            Some(timeout) => self.conn.query_all_with_timeout(stmt, timeout).await,
+            None => self.conn.query_all(stmt).await,
+        }
+    }
+
+    async fn begin_atomic(&self) -> Result<Option<DatabaseTransaction>, DbErr> {
+        self.conn.begin_atomic().await
+    }
+
+    fn support_returning(&self) -> bool {
+        self.conn.support_returning()
+    }
+
+    fn is_mock_connection(&self) -> bool {
+        self.conn.is_mock_connection()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_max_execution_time() {
+        let timeout = Duration::from_millis(1500);
+        let stmt = |backend, sql: &str| Statement::from_string(backend, sql);
+
+        assert_eq!(
+            with_max_execution_time(stmt(DbBackend::MySql, "SELECT `id` FROM `cake`"), timeout).sql,
+            "SELECT /*+ MAX_EXECUTION_TIME(1500) */ `id` FROM `cake`"
+        );
+        assert_eq!(
+            with_max_execution_time(stmt(DbBackend::MySql, "DELETE FROM `cake`"), timeout).sql,
+            "DELETE FROM `cake`"
+        );
+        assert_eq!(
+            with_max_execution_time(stmt(DbBackend::Postgres, "SELECT 1"), timeout).sql,
+            "SELECT 1"
+        );
+        assert_eq!(
+            with_max_execution_time(stmt(DbBackend::MySql, "SELECT 1"), Duration::ZERO).sql,
+            "SELECT /*+ MAX_EXECUTION_TIME(1) */ 1"
+        );
+    }
+
+    #[test]
+    fn test_deadline() {
+        let (deadline, guard) = Deadline::new(Duration::ZERO);
+        assert!(deadline.expired());
+        drop(guard);
+        assert!(!deadline.expired());
+    }
+}
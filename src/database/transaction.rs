@@ -1,5 +1,5 @@
 #![allow(unused_assignments)]
-use std::{future::Future, pin::Pin, sync::Arc};
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
 
 use futures_util::lock::Mutex;
 #[cfg(feature = "sqlx-sqlite")]
@@ -8,10 +8,16 @@ use sqlx_core::sql_str::SqlSafeStr;
 use sqlx_core::transaction::TransactionManager;
 use tracing::instrument;
 
+#[cfg(any(feature = "sqlx-sqlite", feature = "rusqlite"))]
+use super::timeout::Deadline;
+#[cfg(feature = "sqlx-postgres")]
+use super::timeout::timeout_millis;
+use super::timeout::{ArmedTimeout, map_timeout_err, with_max_execution_time};
+use super::{db_connection::pg_notify, tracing_spans::DbTarget};
 use crate::{
     AccessMode, ConnectionTrait, DbBackend, DbErr, ExecResult, InnerConnection, IsolationLevel,
-    QueryResult, SqliteTransactionMode, Statement, TransactionOptions, TransactionSession,
-    TransactionTrait, debug_print, error::*,
+    QueryResult, Statement, TransactionOptions, TransactionSession, TransactionTrait, debug_print,
+    error::*,
 };
 #[cfg(feature = "sqlx-dep")]
 use crate::{sqlx_error_to_exec_err, sqlx_error_to_query_err};
@@ -36,6 +42,9 @@ pub struct DatabaseTransaction {
     open: bool,
     metric_callback: Option<crate::metric::Callback>,
     record_stmt_in_spans: bool,
+    target: DbTarget,
+    #[cfg(feature = "tracing-spans")]
+    span: tracing::Span,
 }
 
 #[instrument(level = "trace", skip(transaction, callback))]
@@ -79,22 +88,32 @@ impl DatabaseTransaction {
         backend: DbBackend,
         metric_callback: Option<crate::metric::Callback>,
         record_stmt_in_spans: bool,
-        isolation_level: Option<IsolationLevel>,
-        access_mode: Option<AccessMode>,
-        sqlite_transaction_mode: Option<SqliteTransactionMode>,
+        target: DbTarget,
+        options: TransactionOptions,
     ) -> Result<DatabaseTransaction, DbErr> {
+        #[allow(unused_variables)] // without a driver there is nothing to configure
+        let TransactionOptions {
+            isolation_level,
+            access_mode,
+            sqlite_transaction_mode,
+        } = options;
         let res = DatabaseTransaction {
             conn,
             backend,
             open: true,
             metric_callback,
             record_stmt_in_spans,
+            #[cfg(feature = "tracing-spans")]
+            span: super::tracing_spans::transaction_span(backend, &target),
+            target,
         };
 
         let begin_result: Result<(), DbErr> = super::tracing_spans::with_db_span!(
             "sea_orm.begin",
             backend,
+            &res.target,
             "BEGIN",
+            parent = &res.span,
             record_stmt = false,
             async {
                 #[cfg(not(feature = "sync"))]
@@ -153,6 +172,8 @@ impl DatabaseTransaction {
                     }
                     #[cfg(feature = "rusqlite")]
                     InnerConnection::Rusqlite(c) => c.begin(sqlite_transaction_mode),
+                    #[cfg(feature = "duckdb")]
+                    InnerConnection::Duckdb(c) => c.begin(),
                     #[cfg(feature = "mock")]
                     InnerConnection::Mock(c) => {
                         c.begin();
@@ -170,9 +191,125 @@ impl DatabaseTransaction {
         );
 
         begin_result?;
+        #[cfg(feature = "tracing-spans")]
+        tracing::info!(parent: &res.span, "begin");
         Ok(res)
     }
 
+    /// Begin a nested transaction, its span a child of this transaction's span
+    async fn begin_nested(
+        &self,
+        options: TransactionOptions,
+    ) -> Result<DatabaseTransaction, DbErr> {
+        #[cfg(all(feature = "tracing-spans", feature = "sync"))]
+        let _span = self.span.enter();
+        let begin = DatabaseTransaction::begin(
+            Arc::clone(&self.conn),
+            self.backend,
+            self.metric_callback.clone(),
+            self.record_stmt_in_spans,
+            self.target.clone(),
+            options,
+        );
+        #[cfg(all(feature = "tracing-spans", not(feature = "sync")))]
+        let begin = tracing::Instrument::instrument(begin, self.span.clone());
+        begin.await
+    }
+
+    /// Bound the next statement by `timeout` with the native mechanism of the
+    /// connection: a `MAX_EXECUTION_TIME` hint on the statement itself for MySQL,
+    /// `SET LOCAL statement_timeout` on Postgres, a progress handler on SQLite and an
+    /// interrupt on DuckDB. With `restore`, the Postgres `statement_timeout` in effect
+    /// before is remembered, for [`disarm_timeout`](Self::disarm_timeout) to put back.
+    #[allow(unused_variables)]
+    pub(crate) async fn arm_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+        restore: bool,
+    ) -> Result<(Statement, ArmedTimeout), DbErr> {
+        #[cfg(not(feature = "sync"))]
+        let conn = &mut *self.conn.lock().await;
+        #[cfg(feature = "sync")]
+        let conn = &mut *self.conn.lock().map_err(|_| DbErr::MutexPoisonError)?;
+
+        let armed = match conn {
+            #[cfg(feature = "sqlx-postgres")]
+            InnerConnection::Postgres(c) => {
+                let millis = timeout_millis(timeout).to_string();
+                let conn: &mut sqlx::PgConnection = &mut *c;
+                if restore {
+                    let (previous, _): (String, String) = sqlx::query_as(
+                        "SELECT current_setting('statement_timeout'), \
+                        set_config('statement_timeout', $1, true)",
+                    )
+                    .bind(millis)
+                    .fetch_one(conn)
+                    .await
+                    .map_err(sqlx_error_to_exec_err)?;
+                    ArmedTimeout::StatementTimeout(previous)
+                } else {
+                    sqlx::query("SELECT set_config('statement_timeout', $1, true)")
+                        .bind(millis)
+                        .execute(conn)
+                        .await
+                        .map_err(sqlx_error_to_exec_err)?;
+                    ArmedTimeout::None
+                }
+            }
+            #[cfg(feature = "sqlx-sqlite")]
+            InnerConnection::Sqlite(c) => {
+                let (deadline, guard) = Deadline::new(timeout);
+                crate::driver::sqlx_sqlite::set_deadline(c, Some(deadline)).await?;
+                ArmedTimeout::Deadline(guard)
+            }
+            #[cfg(feature = "rusqlite")]
+            InnerConnection::Rusqlite(c) => {
+                let (deadline, guard) = Deadline::new(timeout);
+                c.set_deadline(deadline)?;
+                ArmedTimeout::Deadline(guard)
+            }
+            #[cfg(feature = "duckdb")]
+            InnerConnection::Duckdb(c) => ArmedTimeout::Interrupt(c.interrupt_after(timeout)),
+            #[allow(unreachable_patterns)]
+            _ => ArmedTimeout::None,
+        };
+        Ok((with_max_execution_time(stmt, timeout), armed))
+    }
+
+    /// Undo [`arm_timeout`](Self::arm_timeout) once the statement has completed.
+    pub(crate) async fn disarm_timeout(&self, armed: ArmedTimeout) -> Result<(), DbErr> {
+        if matches!(armed, ArmedTimeout::None) {
+            return Ok(());
+        }
+
+        #[cfg(not(feature = "sync"))]
+        let conn = &mut *self.conn.lock().await;
+        #[cfg(feature = "sync")]
+        let conn = &mut *self.conn.lock().map_err(|_| DbErr::MutexPoisonError)?;
+
+        match (conn, armed) {
+            #[cfg(feature = "sqlx-postgres")]
+            (InnerConnection::Postgres(c), ArmedTimeout::StatementTimeout(previous)) => {
+                let conn: &mut sqlx::PgConnection = &mut *c;
+                sqlx::query("SELECT set_config('statement_timeout', $1, true)")
+                    .bind(previous)
+                    .execute(conn)
+                    .await
+                    .map_err(sqlx_error_to_exec_err)?;
+            }
+            #[cfg(feature = "sqlx-sqlite")]
+            (InnerConnection::Sqlite(c), ArmedTimeout::Deadline(_)) => {
+                crate::driver::sqlx_sqlite::set_deadline(c, None).await?;
+            }
+            #[cfg(feature = "rusqlite")]
+            (InnerConnection::Rusqlite(c), ArmedTimeout::Deadline(_)) => c.clear_deadline()?,
+            #[allow(unreachable_patterns)]
+            _ => {}
+        }
+        Ok(())
+    }
+
     /// Runs a transaction to completion passing through the result.
     /// Rolling back the transaction on encountering an error.
     #[instrument(level = "trace", skip(callback))]
@@ -239,7 +376,9 @@ impl DatabaseTransaction {
         let result: Result<(), DbErr> = super::tracing_spans::with_db_span!(
             "sea_orm.commit",
             self.backend,
+            &self.target,
             "COMMIT",
+            parent = &self.span,
             record_stmt = false,
             async {
                 #[cfg(not(feature = "sync"))]
@@ -268,6 +407,8 @@ impl DatabaseTransaction {
                     }
                     #[cfg(feature = "rusqlite")]
                     InnerConnection::Rusqlite(c) => c.commit(),
+                    #[cfg(feature = "duckdb")]
+                    InnerConnection::Duckdb(c) => c.commit(),
                     #[cfg(feature = "mock")]
                     InnerConnection::Mock(c) => {
                         c.commit();
@@ -284,7 +425,14 @@ impl DatabaseTransaction {
             }
         );
 
+        #[cfg(feature = "tracing-spans")]
+        self.span.record(
+            "otel.status_code",
+            if result.is_ok() { "OK" } else { "ERROR" },
+        );
         result?;
+        #[cfg(feature = "tracing-spans")]
+        tracing::info!(parent: &self.span, "commit");
         self.open = false; // read by start_rollback
         Ok(())
     }
@@ -296,7 +444,9 @@ impl DatabaseTransaction {
         let result: Result<(), DbErr> = super::tracing_spans::with_db_span!(
             "sea_orm.rollback",
             self.backend,
+            &self.target,
             "ROLLBACK",
+            parent = &self.span,
             record_stmt = false,
             async {
                 #[cfg(not(feature = "sync"))]
@@ -325,6 +475,8 @@ impl DatabaseTransaction {
                     }
                     #[cfg(feature = "rusqlite")]
                     InnerConnection::Rusqlite(c) => c.rollback(),
+                    #[cfg(feature = "duckdb")]
+                    InnerConnection::Duckdb(c) => c.rollback(),
                     #[cfg(feature = "mock")]
                     InnerConnection::Mock(c) => {
                         c.rollback();
@@ -342,14 +494,93 @@ impl DatabaseTransaction {
         );
 
         result?;
+        #[cfg(feature = "tracing-spans")]
+        tracing::info!(parent: &self.span, "rollback");
         self.open = false; // read by start_rollback
         Ok(())
     }
 
+    /// Set a savepoint named `name`, for [`rollback_to`](Self::rollback_to) to return to.
+    /// Unlike a nested [`begin`](TransactionTrait::begin), work carries on in this
+    /// transaction, so a bulk import can skip a bad row without losing the rows before it.
+    /// Setting a savepoint with the name of an existing one shadows it.
+    #[instrument(level = "trace")]
+    pub async fn savepoint(&self, name: &str) -> Result<(), DbErr> {
+        self.run_savepoint_stmt("SAVEPOINT", name).await
+    }
+
+    /// Undo everything done since the savepoint `name` was set. The savepoint is kept,
+    /// so it can be rolled back to again.
+    #[instrument(level = "trace")]
+    pub async fn rollback_to(&self, name: &str) -> Result<(), DbErr> {
+        self.run_savepoint_stmt("ROLLBACK TO SAVEPOINT", name).await
+    }
+
+    /// Remove the savepoint `name`, and any set after it, keeping the changes made since.
+    #[instrument(level = "trace")]
+    pub async fn release(&self, name: &str) -> Result<(), DbErr> {
+        self.run_savepoint_stmt("RELEASE SAVEPOINT", name).await
+    }
+
+    /// Send a Postgres notification with `payload` on `channel`. Listeners receive it
+    /// once the transaction commits, and not at all if it rolls back.
+    #[instrument(level = "trace")]
+    pub async fn notify(&self, channel: &str, payload: &str) -> Result<(), DbErr> {
+        pg_notify(self, channel, payload).await
+    }
+
+    /// Set the Postgres `search_path` to `schema` until the transaction ends
+    pub(crate) async fn set_search_path(&self, schema: &str) -> Result<(), DbErr> {
+        self.execute_raw(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "SELECT set_config('search_path', $1, true)",
+            [quote_identifier(DbBackend::Postgres, schema).into()],
+        ))
+        .await
+        .map(|_| ())
+    }
+
+    /// Close the connection when this transaction is dropped instead of returning it to
+    /// the pool, for session state that outlives the transaction, like a MySQL `GET_LOCK`
+    pub(crate) fn discard_connection(&self) {
+        if let Some(mut conn) = self.conn.try_lock() {
+            #[allow(clippy::single_match)]
+            match &mut *conn {
+                #[cfg(feature = "sqlx-mysql")]
+                InnerConnection::MySql(c) => c.close_on_drop(),
+                #[allow(unreachable_patterns)]
+                _ => {}
+            }
+        }
+    }
+
+    async fn run_savepoint_stmt(&self, command: &str, name: &str) -> Result<(), DbErr> {
+        let sql = format!("{command} {}", quote_identifier(self.backend, name));
+
+        #[cfg(feature = "mock")]
+        {
+            #[cfg(not(feature = "sync"))]
+            let conn = &mut *self.conn.lock().await;
+            #[cfg(feature = "sync")]
+            let conn = &mut *self.conn.lock().map_err(|_| DbErr::MutexPoisonError)?;
+
+            #[allow(irrefutable_let_patterns)]
+            if let InnerConnection::Mock(c) = conn {
+                // recorded in the transaction log without taking up an exec result
+                c.savepoint(Statement::from_string(self.backend, sql));
+                return Ok(());
+            }
+        }
+
+        self.execute_unprepared(&sql).await.map(|_| ())
+    }
+
     // the rollback is queued and will be performed on next async operation, like returning the connection to the pool
     #[instrument(level = "trace")]
     fn start_rollback(&mut self) -> Result<(), DbErr> {
         if self.open {
+            #[cfg(feature = "tracing-spans")]
+            tracing::info!(parent: &self.span, "rollback");
             if let Some(mut conn) = self.conn.try_lock() {
                 match &mut *conn {
                     #[cfg(feature = "sqlx-mysql")]
@@ -368,6 +599,10 @@ impl DatabaseTransaction {
                     InnerConnection::Rusqlite(c) => {
                         c.start_rollback()?;
                     }
+                    #[cfg(feature = "duckdb")]
+                    InnerConnection::Duckdb(c) => {
+                        c.start_rollback()?;
+                    }
                     #[cfg(feature = "mock")]
                     InnerConnection::Mock(c) => {
                         c.rollback();
@@ -388,6 +623,24 @@ impl DatabaseTransaction {
     }
 }
 
+/// Quote a name, e.g. of a savepoint or schema, as an identifier of `backend`
+pub(crate) fn quote_identifier(backend: DbBackend, name: &str) -> String {
+    let quote = match backend {
+        DbBackend::MySql => '`',
+        _ => '"',
+    };
+    let mut quoted = String::with_capacity(name.len() + 2);
+    quoted.push(quote);
+    for c in name.chars() {
+        if c == quote {
+            quoted.push(quote);
+        }
+        quoted.push(c);
+    }
+    quoted.push(quote);
+    quoted
+}
+
 #[async_trait::async_trait]
 impl TransactionSession for DatabaseTransaction {
     async fn commit(self) -> Result<(), DbErr> {
@@ -420,7 +673,9 @@ impl ConnectionTrait for DatabaseTransaction {
         super::tracing_spans::with_db_span!(
             "sea_orm.execute",
             self.backend,
+            &self.target,
             stmt.sql.as_str(),
+            parent = &self.span,
             record_stmt = self.record_stmt_in_spans,
             async {
                 #[cfg(not(feature = "sync"))]
@@ -433,7 +688,7 @@ impl ConnectionTrait for DatabaseTransaction {
                     InnerConnection::MySql(conn) => {
                         let query = crate::driver::sqlx_mysql::sqlx_query(&stmt);
                         let conn: &mut sqlx::MySqlConnection = &mut *conn;
-                        crate::metric::metric!(self.metric_callback, &stmt, {
+                        crate::metric::metric!(self.metric_callback, &stmt, true, None, {
                             query.execute(conn).await.map(Into::into)
                         })
                         .map_err(sqlx_error_to_exec_err)
@@ -442,7 +697,7 @@ impl ConnectionTrait for DatabaseTransaction {
                     InnerConnection::Postgres(conn) => {
                         let query = crate::driver::sqlx_postgres::sqlx_query(&stmt);
                         let conn: &mut sqlx::PgConnection = &mut *conn;
-                        crate::metric::metric!(self.metric_callback, &stmt, {
+                        crate::metric::metric!(self.metric_callback, &stmt, true, None, {
                             query.execute(conn).await.map(Into::into)
                         })
                         .map_err(sqlx_error_to_exec_err)
@@ -451,13 +706,15 @@ impl ConnectionTrait for DatabaseTransaction {
                     InnerConnection::Sqlite(conn) => {
                         let query = crate::driver::sqlx_sqlite::sqlx_query(&stmt);
                         let conn: &mut sqlx::SqliteConnection = &mut *conn;
-                        crate::metric::metric!(self.metric_callback, &stmt, {
+                        crate::metric::metric!(self.metric_callback, &stmt, true, None, {
                             query.execute(conn).await.map(Into::into)
                         })
                         .map_err(sqlx_error_to_exec_err)
                     }
                     #[cfg(feature = "rusqlite")]
                     InnerConnection::Rusqlite(conn) => conn.execute(stmt, &self.metric_callback),
+                    #[cfg(feature = "duckdb")]
+                    InnerConnection::Duckdb(conn) => conn.execute(stmt, &self.metric_callback),
                     #[cfg(feature = "mock")]
                     InnerConnection::Mock(conn) => conn.execute(stmt),
                     #[cfg(feature = "proxy")]
@@ -469,6 +726,17 @@ impl ConnectionTrait for DatabaseTransaction {
         )
     }
 
+    async fn execute_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<ExecResult, DbErr> {
+        let (stmt, armed) = self.arm_timeout(stmt, timeout, true).await?;
+        let res = map_timeout_err(self.execute_raw(stmt).await, timeout)?;
+        self.disarm_timeout(armed).await?;
+        Ok(res)
+    }
+
     #[instrument(level = "trace", skip(sql))]
     #[allow(unused_variables)]
     async fn execute_unprepared(&self, sql: &str) -> Result<ExecResult, DbErr> {
@@ -477,7 +745,9 @@ impl ConnectionTrait for DatabaseTransaction {
         super::tracing_spans::with_db_span!(
             "sea_orm.execute_unprepared",
             self.backend,
+            &self.target,
             sql,
+            parent = &self.span,
             record_stmt = false,
             async {
                 #[cfg(not(feature = "sync"))]
@@ -512,6 +782,8 @@ impl ConnectionTrait for DatabaseTransaction {
                     }
                     #[cfg(feature = "rusqlite")]
                     InnerConnection::Rusqlite(conn) => conn.execute_unprepared(sql),
+                    #[cfg(feature = "duckdb")]
+                    InnerConnection::Duckdb(conn) => conn.execute_unprepared(sql),
                     #[cfg(feature = "mock")]
                     InnerConnection::Mock(conn) => {
                         let db_backend = conn.get_database_backend();
@@ -539,7 +811,9 @@ impl ConnectionTrait for DatabaseTransaction {
         super::tracing_spans::with_db_span!(
             "sea_orm.query_one",
             self.backend,
+            &self.target,
             stmt.sql.as_str(),
+            parent = &self.span,
             record_stmt = self.record_stmt_in_spans,
             async {
                 #[cfg(not(feature = "sync"))]
@@ -552,7 +826,7 @@ impl ConnectionTrait for DatabaseTransaction {
                     InnerConnection::MySql(conn) => {
                         let query = crate::driver::sqlx_mysql::sqlx_query(&stmt);
                         let conn: &mut sqlx::MySqlConnection = &mut *conn;
-                        crate::metric::metric!(self.metric_callback, &stmt, {
+                        crate::metric::metric!(self.metric_callback, &stmt, true, None, {
                             crate::sqlx_map_err_ignore_not_found(
                                 query.fetch_one(conn).await.map(|row| Some(row.into())),
                             )
@@ -562,7 +836,7 @@ impl ConnectionTrait for DatabaseTransaction {
                     InnerConnection::Postgres(conn) => {
                         let query = crate::driver::sqlx_postgres::sqlx_query(&stmt);
                         let conn: &mut sqlx::PgConnection = &mut *conn;
-                        crate::metric::metric!(self.metric_callback, &stmt, {
+                        crate::metric::metric!(self.metric_callback, &stmt, true, None, {
                             crate::sqlx_map_err_ignore_not_found(
                                 query.fetch_one(conn).await.map(|row| Some(row.into())),
                             )
@@ -572,7 +846,7 @@ impl ConnectionTrait for DatabaseTransaction {
                     InnerConnection::Sqlite(conn) => {
                         let query = crate::driver::sqlx_sqlite::sqlx_query(&stmt);
                         let conn: &mut sqlx::SqliteConnection = &mut *conn;
-                        crate::metric::metric!(self.metric_callback, &stmt, {
+                        crate::metric::metric!(self.metric_callback, &stmt, true, None, {
                             crate::sqlx_map_err_ignore_not_found(
                                 query.fetch_one(conn).await.map(|row| Some(row.into())),
                             )
@@ -580,6 +854,8 @@ impl ConnectionTrait for DatabaseTransaction {
                     }
                     #[cfg(feature = "rusqlite")]
                     InnerConnection::Rusqlite(conn) => conn.query_one(stmt, &self.metric_callback),
+                    #[cfg(feature = "duckdb")]
+                    InnerConnection::Duckdb(conn) => conn.query_one(stmt, &self.metric_callback),
                     #[cfg(feature = "mock")]
                     InnerConnection::Mock(conn) => conn.query_one(stmt),
                     #[cfg(feature = "proxy")]
@@ -599,7 +875,9 @@ impl ConnectionTrait for DatabaseTransaction {
         super::tracing_spans::with_db_span!(
             "sea_orm.query_all",
             self.backend,
+            &self.target,
             stmt.sql.as_str(),
+            parent = &self.span,
             record_stmt = self.record_stmt_in_spans,
             async {
                 #[cfg(not(feature = "sync"))]
@@ -612,7 +890,7 @@ impl ConnectionTrait for DatabaseTransaction {
                     InnerConnection::MySql(conn) => {
                         let query = crate::driver::sqlx_mysql::sqlx_query(&stmt);
                         let conn: &mut sqlx::MySqlConnection = &mut *conn;
-                        crate::metric::metric!(self.metric_callback, &stmt, {
+                        crate::metric::metric!(self.metric_callback, &stmt, true, None, {
                             query
                                 .fetch_all(conn)
                                 .await
@@ -624,7 +902,7 @@ impl ConnectionTrait for DatabaseTransaction {
                     InnerConnection::Postgres(conn) => {
                         let query = crate::driver::sqlx_postgres::sqlx_query(&stmt);
                         let conn: &mut sqlx::PgConnection = &mut *conn;
-                        crate::metric::metric!(self.metric_callback, &stmt, {
+                        crate::metric::metric!(self.metric_callback, &stmt, true, None, {
                             query
                                 .fetch_all(conn)
                                 .await
@@ -636,7 +914,7 @@ impl ConnectionTrait for DatabaseTransaction {
                     InnerConnection::Sqlite(conn) => {
                         let query = crate::driver::sqlx_sqlite::sqlx_query(&stmt);
                         let conn: &mut sqlx::SqliteConnection = &mut *conn;
-                        crate::metric::metric!(self.metric_callback, &stmt, {
+                        crate::metric::metric!(self.metric_callback, &stmt, true, None, {
                             query
                                 .fetch_all(conn)
                                 .await
@@ -646,6 +924,8 @@ impl ConnectionTrait for DatabaseTransaction {
                     }
                     #[cfg(feature = "rusqlite")]
                     InnerConnection::Rusqlite(conn) => conn.query_all(stmt, &self.metric_callback),
+                    #[cfg(feature = "duckdb")]
+                    InnerConnection::Duckdb(conn) => conn.query_all(stmt, &self.metric_callback),
                     #[cfg(feature = "mock")]
                     InnerConnection::Mock(conn) => conn.query_all(stmt),
                     #[cfg(feature = "proxy")]
@@ -656,6 +936,82 @@ impl ConnectionTrait for DatabaseTransaction {
             }
         )
     }
+
+    async fn query_one_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<Option<QueryResult>, DbErr> {
+        let (stmt, armed) = self.arm_timeout(stmt, timeout, true).await?;
+        let res = map_timeout_err(self.query_one_raw(stmt).await, timeout)?;
+        self.disarm_timeout(armed).await?;
+        Ok(res)
+    }
+
+    async fn query_all_raw_with_timeout(
+        &self,
+        stmt: Statement,
+        timeout: Duration,
+    ) -> Result<Vec<QueryResult>, DbErr> {
+        let (stmt, armed) = self.arm_timeout(stmt, timeout, true).await?;
+        let res = map_timeout_err(self.query_all_raw(stmt).await, timeout)?;
+        self.disarm_timeout(armed).await?;
+        Ok(res)
+    }
+
+    async fn begin_atomic(&self) -> Result<Option<DatabaseTransaction>, DbErr> {
+        self.begin().await.map(Some)
+    }
+
+    #[cfg(feature = "sqlx-postgres")]
+    async fn copy_in_raw(
+        &self,
+        sql: &str,
+        data: &mut (dyn Iterator<Item = Result<Vec<u8>, DbErr>> + Send),
+    ) -> Result<u64, DbErr> {
+        debug_print!("{}", sql);
+
+        #[cfg(not(feature = "sync"))]
+        let conn = &mut *self.conn.lock().await;
+        #[cfg(feature = "sync")]
+        let conn = &mut *self.conn.lock().map_err(|_| DbErr::MutexPoisonError)?;
+
+        match conn {
+            InnerConnection::Postgres(conn) => {
+                crate::driver::sqlx_postgres::copy_in(conn, sql, data).await
+            }
+            #[allow(unreachable_patterns)]
+            _ => Err(DbErr::BackendNotSupported {
+                db: self.backend.as_str(),
+                ctx: "COPY",
+            }),
+        }
+    }
+
+    #[cfg(feature = "sqlx-postgres")]
+    async fn copy_out_raw(
+        &self,
+        sql: &str,
+        writer: &mut (dyn std::io::Write + Send),
+    ) -> Result<(), DbErr> {
+        debug_print!("{}", sql);
+
+        #[cfg(not(feature = "sync"))]
+        let conn = &mut *self.conn.lock().await;
+        #[cfg(feature = "sync")]
+        let conn = &mut *self.conn.lock().map_err(|_| DbErr::MutexPoisonError)?;
+
+        match conn {
+            InnerConnection::Postgres(conn) => {
+                crate::driver::sqlx_postgres::copy_out(conn, sql, writer).await
+            }
+            #[allow(unreachable_patterns)]
+            _ => Err(DbErr::BackendNotSupported {
+                db: self.backend.as_str(),
+                ctx: "COPY",
+            }),
+        }
+    }
 }
 
 #[cfg(feature = "stream")]
@@ -691,16 +1047,7 @@ impl TransactionTrait for DatabaseTransaction {
 
     #[instrument(level = "trace")]
     async fn begin(&self) -> Result<DatabaseTransaction, DbErr> {
-        DatabaseTransaction::begin(
-            Arc::clone(&self.conn),
-            self.backend,
-            self.metric_callback.clone(),
-            self.record_stmt_in_spans,
-            None,
-            None,
-            None,
-        )
-        .await
+        self.begin_nested(TransactionOptions::default()).await
     }
 
     #[instrument(level = "trace")]
@@ -709,15 +1056,11 @@ impl TransactionTrait for DatabaseTransaction {
         isolation_level: Option<IsolationLevel>,
         access_mode: Option<AccessMode>,
     ) -> Result<DatabaseTransaction, DbErr> {
-        DatabaseTransaction::begin(
-            Arc::clone(&self.conn),
-            self.backend,
-            self.metric_callback.clone(),
-            self.record_stmt_in_spans,
+        self.begin_nested(TransactionOptions {
             isolation_level,
             access_mode,
-            None,
-        )
+            ..Default::default()
+        })
         .await
     }
 
@@ -726,16 +1069,7 @@ impl TransactionTrait for DatabaseTransaction {
         &self,
         options: TransactionOptions,
     ) -> Result<DatabaseTransaction, DbErr> {
-        DatabaseTransaction::begin(
-            Arc::clone(&self.conn),
-            self.backend,
-            self.metric_callback.clone(),
-            self.record_stmt_in_spans,
-            options.isolation_level,
-            options.access_mode,
-            options.sqlite_transaction_mode,
-        )
-        .await
+        self.begin_nested(options).await
     }
 
     /// Execute the async function inside a transaction.
@@ -47,6 +47,12 @@ pub trait MockDatabaseTrait: Send + Debug {
     /// Roll back a transaction since errors were encountered
     fn rollback(&mut self);
 
+    /// Record a `SAVEPOINT`, `ROLLBACK TO SAVEPOINT` or `RELEASE SAVEPOINT` statement
+    /// issued on the open transaction. Nothing is recorded by default.
+    fn savepoint(&mut self, stmt: Statement) {
+        let _ = stmt;
+    }
+
     /// Get all logs from a [MockDatabase] and return a [Transaction]
     fn drain_transaction_log(&mut self) -> Vec<Transaction>;
 
@@ -55,6 +61,12 @@ pub trait MockDatabaseTrait: Send + Debug {
 
     /// Ping the [MockDatabase]
     fn ping(&self) -> Result<(), DbErr>;
+
+    /// Check that every expectation set on the [MockDatabase] was met and no statement
+    /// went unanswered, describing what went wrong otherwise. Always passes by default.
+    fn verify(&self) -> Result<(), String> {
+        Ok(())
+    }
 }
 
 impl MockDatabaseConnector {
@@ -77,6 +89,10 @@ impl MockDatabaseConnector {
         if DbBackend::Sqlite.is_prefix_of(string) {
             return true;
         }
+        #[cfg(feature = "duckdb")]
+        if crate::DuckdbConnector::accepts(string) {
+            return true;
+        }
         false
     }
 
@@ -109,6 +125,10 @@ impl MockDatabaseConnector {
         if crate::driver::rusqlite::RusqliteConnector::accepts(string) {
             return connect_mock_db!(DbBackend::Sqlite);
         }
+        #[cfg(feature = "duckdb")]
+        if crate::DuckdbConnector::accepts(string) {
+            return connect_mock_db!(DbBackend::Postgres);
+        }
         connect_mock_db!(DbBackend::Postgres)
     }
 }
@@ -131,6 +151,22 @@ impl MockDatabaseConnection {
         &self.mocker
     }
 
+    /// Check that every expectation set with [`MockDatabase::expect_query`] or
+    /// [`MockDatabase::expect_exec`] was met, and that every statement run had a result
+    /// to answer it with
+    ///
+    /// # Panics
+    ///
+    /// Panics with a report listing the statements run, marking those that were
+    /// unexpected with `+` and the expectations that were not met with `-`.
+    /// Will also panic if the lock cannot be acquired.
+    pub fn verify(&self) {
+        let res = self.mocker.lock().expect("Fail to acquire mocker").verify();
+        if let Err(report) = res {
+            panic!("{report}");
+        }
+    }
+
     /// Get the [DatabaseBackend](crate::DatabaseBackend) being used by the [MockDatabase]
     ///
     /// # Panics
@@ -236,6 +272,19 @@ impl MockDatabaseConnection {
             .rollback()
     }
 
+    /// Record a named savepoint statement in the open transaction
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock cannot be acquired.
+    #[instrument(level = "trace")]
+    pub fn savepoint(&self, stmt: Statement) {
+        self.mocker
+            .lock()
+            .expect("Failed to acquire mocker")
+            .savepoint(stmt)
+    }
+
     /// Checks if a connection to the database is still valid.
     pub fn ping(&self) -> Result<(), DbErr> {
         self.mocker.lock().map_err(query_err)?.ping()
@@ -273,9 +322,8 @@ impl crate::DatabaseTransaction {
             backend,
             metric_callback,
             true,
-            None,
-            None,
-            None,
+            Default::default(),
+            Default::default(),
         )
         .await
     }
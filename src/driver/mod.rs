@@ -1,3 +1,5 @@
+#[cfg(feature = "duckdb")]
+pub(crate) mod duckdb;
 #[cfg(feature = "mock")]
 mod mock;
 #[cfg(feature = "proxy")]
@@ -15,6 +17,8 @@ pub(crate) mod sqlx_postgres;
 #[cfg(feature = "sqlx-sqlite")]
 pub(crate) mod sqlx_sqlite;
 
+#[cfg(feature = "duckdb")]
+pub use self::duckdb::{DuckdbConnector, DuckdbSharedConnection};
 #[cfg(feature = "mock")]
 pub use mock::*;
 #[cfg(feature = "proxy")]
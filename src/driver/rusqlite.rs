@@ -22,7 +22,13 @@ use sea_query_rusqlite::{RusqliteValue, RusqliteValues, rusqlite};
 use crate::{
     AccessMode, ColIdx, ConnectOptions, DatabaseConnection, DatabaseConnectionType,
     DatabaseTransaction, InnerConnection, IsolationLevel, SqliteTransactionMode, Statement,
-    TransactionError, error::*, executor::*,
+    TransactionError, TransactionOptions,
+    database::{
+        timeout::{Deadline, PROGRESS_HANDLER_OPS},
+        tracing_spans::DbTarget,
+    },
+    error::*,
+    executor::*,
 };
 
 #[cfg(feature = "stream")]
@@ -51,6 +57,7 @@ pub struct RusqliteSharedConnection {
     acquire_timeout: Duration,
     metric_callback: Option<crate::metric::Callback>,
     pub(crate) record_stmt_in_spans: bool,
+    pub(crate) target: DbTarget,
 }
 
 /// A loaned connection that supports nested transactions.
@@ -101,7 +108,7 @@ pub struct RusqliteExecResult {
     pub(crate) last_insert_rowid: i64,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct OwnedRow {
     pub columns: Vec<Arc<str>>,
     pub values: Vec<Value>,
@@ -184,6 +191,7 @@ impl From<RusqliteConnection> for RusqliteSharedConnection {
             acquire_timeout: DEFAULT_ACQUIRE_TIMEOUT,
             metric_callback: None,
             record_stmt_in_spans: true,
+            target: DbTarget::default(),
         }
     }
 }
@@ -254,11 +262,13 @@ impl RusqliteConnector {
         }
         .map_err(conn_err)?;
 
+        let target = DbTarget::new(Some(path), None);
         let conn = RusqliteSharedConnection {
             conn: Arc::new(Mutex::new(State::Idle(conn))),
             acquire_timeout,
             metric_callback: None,
             record_stmt_in_spans,
+            target,
         };
 
         #[cfg(feature = "sqlite-use-returning-for-3_35")]
@@ -329,9 +339,11 @@ impl RusqliteSharedConnection {
         debug!("{}", stmt);
 
         let values = sql_values(&stmt);
+        let start = Instant::now();
         let conn = self.acquire()?;
+        let acquire_elapsed = start.elapsed();
         let conn = conn.conn();
-        crate::metric::metric!(self.metric_callback, &stmt, {
+        crate::metric::metric!(self.metric_callback, &stmt, false, Some(acquire_elapsed), {
             match conn.execute(&stmt.sql, &*values.as_params()) {
                 Ok(rows_affected) => Ok(RusqliteExecResult {
                     rows_affected: rows_affected as u64,
@@ -366,12 +378,14 @@ impl RusqliteSharedConnection {
         debug!("{}", stmt);
 
         let values = sql_values(&stmt);
+        let start = Instant::now();
         let conn = self.acquire()?;
+        let acquire_elapsed = start.elapsed();
         let conn = conn.conn();
         let mut sql = conn.prepare_cached(&stmt.sql).map_err(query_err)?;
         let columns: Vec<Arc<str>> = column_names(&sql);
 
-        crate::metric::metric!(self.metric_callback, &stmt, {
+        crate::metric::metric!(self.metric_callback, &stmt, false, Some(acquire_elapsed), {
             match sql.query(&*values.as_params()) {
                 Ok(mut rows) => {
                     let mut out = None;
@@ -391,12 +405,14 @@ impl RusqliteSharedConnection {
         debug!("{}", stmt);
 
         let values = sql_values(&stmt);
+        let start = Instant::now();
         let conn = self.acquire()?;
+        let acquire_elapsed = start.elapsed();
         let conn = conn.conn();
         let mut sql = conn.prepare_cached(&stmt.sql).map_err(query_err)?;
         let columns: Vec<Arc<str>> = column_names(&sql);
 
-        crate::metric::metric!(self.metric_callback, &stmt, {
+        crate::metric::metric!(self.metric_callback, &stmt, false, Some(acquire_elapsed), {
             match sql.query(&*values.as_params()) {
                 Ok(mut rows) => {
                     let mut out = Vec::new();
@@ -437,9 +453,12 @@ impl RusqliteSharedConnection {
             crate::DbBackend::Sqlite,
             self.metric_callback.clone(),
             self.record_stmt_in_spans,
-            isolation_level,
-            access_mode,
-            sqlite_transaction_mode,
+            self.target.clone(),
+            TransactionOptions {
+                isolation_level,
+                access_mode,
+                sqlite_transaction_mode,
+            },
         )
     }
 
@@ -493,6 +512,22 @@ impl RusqliteSharedConnection {
 }
 
 impl RusqliteInnerConnection {
+    /// Interrupt the statements that follow once `deadline` has expired.
+    pub(crate) fn set_deadline(&self, deadline: Deadline) -> Result<(), DbErr> {
+        self.conn
+            .conn()
+            .progress_handler(PROGRESS_HANDLER_OPS, Some(move || deadline.expired()))
+            .map_err(exec_err)
+    }
+
+    /// Remove the progress handler installed by [`Self::set_deadline`].
+    pub(crate) fn clear_deadline(&self) -> Result<(), DbErr> {
+        self.conn
+            .conn()
+            .progress_handler(0, None::<fn() -> bool>)
+            .map_err(exec_err)
+    }
+
     #[instrument(level = "trace", skip(metric_callback, stmt))]
     pub fn execute(
         &self,
@@ -503,7 +538,7 @@ impl RusqliteInnerConnection {
 
         let values = sql_values(&stmt);
         let conn = self.conn.conn();
-        crate::metric::metric!(metric_callback, &stmt, {
+        crate::metric::metric!(metric_callback, &stmt, true, None, {
             match conn.execute(&stmt.sql, &*values.as_params()) {
                 Ok(rows_affected) => Ok(RusqliteExecResult {
                     rows_affected: rows_affected as u64,
@@ -543,7 +578,7 @@ impl RusqliteInnerConnection {
         let mut sql = conn.prepare_cached(&stmt.sql).map_err(query_err)?;
         let columns: Vec<Arc<str>> = column_names(&sql);
 
-        crate::metric::metric!(metric_callback, &stmt, {
+        crate::metric::metric!(metric_callback, &stmt, true, None, {
             match sql.query(&*values.as_params()) {
                 Ok(mut rows) => {
                     let mut out = None;
@@ -570,7 +605,7 @@ impl RusqliteInnerConnection {
         let mut sql = conn.prepare_cached(&stmt.sql).map_err(query_err)?;
         let columns: Vec<Arc<str>> = column_names(&sql);
 
-        crate::metric::metric!(metric_callback, &stmt, {
+        crate::metric::metric!(metric_callback, &stmt, true, None, {
             match sql.query(&*values.as_params()) {
                 Ok(mut rows) => {
                     let mut out = Vec::new();
@@ -705,6 +740,31 @@ impl From<RusqliteExecResult> for ExecResult {
     }
 }
 
+#[cfg(feature = "proxy")]
+pub(crate) fn from_rusqlite_row_to_proxy_row(row: &OwnedRow) -> crate::ProxyRow {
+    use sea_query::Value as V;
+    crate::ProxyRow {
+        values: row
+            .columns
+            .iter()
+            .zip(&row.values)
+            .map(|(c, v)| {
+                (
+                    c.to_string(),
+                    match v {
+                        // SQLite does not type NULL
+                        Value::Null => V::String(None),
+                        Value::Integer(v) => V::BigInt(Some(*v)),
+                        Value::Real(v) => V::Double(Some(*v)),
+                        Value::Text(v) => V::String(Some(v.clone())),
+                        Value::Blob(v) => V::Bytes(Some(v.clone())),
+                    },
+                )
+            })
+            .collect(),
+    }
+}
+
 pub(crate) fn sql_values(stmt: &Statement) -> RusqliteValues {
     let values = match &stmt.values {
         Some(values) => values.iter().cloned().map(RusqliteValue).collect(),
@@ -1,5 +1,14 @@
-use crate::{ConnAcquireErr, ConnectOptions, DbErr, RuntimeErr};
-use std::{sync::Arc, time::Duration};
+use crate::{
+    ConnAcquireErr, ConnectOptions, DbBackend, DbErr, RuntimeErr, database::tracing_spans::DbTarget,
+};
+use sqlx::pool::PoolConnection;
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    },
+    time::{Duration, Instant},
+};
 
 /// Callback stored for a `before_acquire` hook on [`ConnectOptions`].
 ///
@@ -52,6 +61,52 @@ pub fn sqlx_conn_acquire_err(sqlx_err: sqlx::Error) -> DbErr {
     }
 }
 
+/// Counts the tasks waiting for a connection from a pool, which SQLx does not expose
+#[derive(Debug, Default)]
+pub(crate) struct PoolWaiters(AtomicU32);
+
+impl PoolWaiters {
+    /// Acquire a connection from `pool`, counting the caller as a waiter until it gets one.
+    /// Also returns how long the wait took.
+    #[allow(unused_variables)]
+    pub(crate) async fn acquire<DB>(
+        &self,
+        pool: &sqlx::Pool<DB>,
+        backend: DbBackend,
+        target: &DbTarget,
+    ) -> Result<(PoolConnection<DB>, Duration), DbErr>
+    where
+        DB: sqlx::Database,
+    {
+        struct Waiting<'a>(&'a AtomicU32);
+
+        impl Drop for Waiting<'_> {
+            fn drop(&mut self) {
+                self.0.fetch_sub(1, Ordering::Relaxed);
+            }
+        }
+
+        self.0.fetch_add(1, Ordering::Relaxed);
+        let _waiting = Waiting(&self.0);
+        let start = Instant::now();
+        let acquire = async { pool.acquire().await.map_err(sqlx_conn_acquire_err) };
+        #[cfg(feature = "tracing-spans")]
+        let conn = {
+            let span = crate::database::tracing_spans::acquire_span(backend, target);
+            let conn = tracing::Instrument::instrument(acquire, span.clone()).await;
+            crate::database::tracing_spans::record_query_result(&span, &conn);
+            conn
+        };
+        #[cfg(not(feature = "tracing-spans"))]
+        let conn = acquire.await;
+        Ok((conn?, start.elapsed()))
+    }
+
+    pub(crate) fn count(&self) -> u32 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
 impl ConnectOptions {
     /// Convert [ConnectOptions] into [sqlx::pool::PoolOptions]
     pub fn sqlx_pool_options<DB>(self) -> sqlx::pool::PoolOptions<DB>
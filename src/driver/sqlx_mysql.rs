@@ -1,7 +1,7 @@
 use futures_util::lock::Mutex;
 use log::LevelFilter;
 use sea_query::Values;
-use std::{future::Future, pin::Pin, sync::Arc};
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
 
 use sqlx::{
     Connection, Executor, MySql, MySqlPool,
@@ -14,10 +14,12 @@ use tracing::instrument;
 
 use crate::{
     AccessMode, ConnectOptions, DatabaseConnection, DatabaseConnectionType, DatabaseTransaction,
-    DbBackend, IsolationLevel, Statement, TransactionError, debug_print, error::*, executor::*,
+    DbBackend, IsolationLevel, Statement, TransactionError, TransactionOptions, debug_print,
+    error::*, executor::*,
 };
 
 use super::sqlx_common::*;
+use crate::database::tracing_spans::DbTarget;
 
 #[cfg(feature = "stream")]
 use crate::QueryStream;
@@ -31,7 +33,9 @@ pub struct SqlxMySqlConnector;
 pub struct SqlxMySqlPoolConnection {
     pub(crate) pool: MySqlPool,
     metric_callback: Option<crate::metric::Callback>,
+    waiters: Arc<PoolWaiters>,
     pub(crate) record_stmt_in_spans: bool,
+    pub(crate) target: DbTarget,
 }
 
 impl std::fmt::Debug for SqlxMySqlPoolConnection {
@@ -43,8 +47,10 @@ impl std::fmt::Debug for SqlxMySqlPoolConnection {
 impl From<MySqlPool> for SqlxMySqlPoolConnection {
     fn from(pool: MySqlPool) -> Self {
         SqlxMySqlPoolConnection {
+            target: db_target(&pool),
             pool,
             metric_callback: None,
+            waiters: Default::default(),
             record_stmt_in_spans: true,
         }
     }
@@ -111,8 +117,10 @@ impl SqlxMySqlConnector {
 
         let conn: DatabaseConnection =
             DatabaseConnectionType::SqlxMySqlPoolConnection(SqlxMySqlPoolConnection {
+                target: db_target(&pool),
                 pool,
                 metric_callback: None,
+                waiters: Default::default(),
                 record_stmt_in_spans,
             })
             .into();
@@ -129,23 +137,37 @@ impl SqlxMySqlConnector {
     /// Instantiate a sqlx pool connection to a [DatabaseConnection]
     pub fn from_sqlx_mysql_pool(pool: MySqlPool) -> DatabaseConnection {
         DatabaseConnectionType::SqlxMySqlPoolConnection(SqlxMySqlPoolConnection {
+            target: db_target(&pool),
             pool,
             metric_callback: None,
+            waiters: Default::default(),
             record_stmt_in_spans: true,
         })
         .into()
     }
 }
 
+/// The database `pool` connects to, recorded on spans
+fn db_target(pool: &MySqlPool) -> DbTarget {
+    let options = pool.connect_options();
+    DbTarget::new(options.get_database(), Some(options.get_host()))
+}
+
 impl SqlxMySqlPoolConnection {
+    async fn acquire(&self) -> Result<(PoolConnection<MySql>, Duration), DbErr> {
+        self.waiters
+            .acquire(&self.pool, DbBackend::MySql, &self.target)
+            .await
+    }
+
     /// Execute a [Statement] on a MySQL backend
     #[instrument(level = "trace", skip(stmt))]
     pub async fn execute(&self, stmt: Statement) -> Result<ExecResult, DbErr> {
         debug_print!("{}", stmt);
 
         let query = sqlx_query(&stmt);
-        let mut conn = self.pool.acquire().await.map_err(sqlx_conn_acquire_err)?;
-        crate::metric::metric!(self.metric_callback, &stmt, {
+        let (mut conn, acquire_elapsed) = self.acquire().await?;
+        crate::metric::metric!(self.metric_callback, &stmt, false, Some(acquire_elapsed), {
             match query.execute(&mut *conn).await {
                 Ok(res) => Ok(res.into()),
                 Err(err) => Err(sqlx_error_to_exec_err(err)),
@@ -158,7 +180,7 @@ impl SqlxMySqlPoolConnection {
     pub async fn execute_unprepared(&self, sql: &str) -> Result<ExecResult, DbErr> {
         debug_print!("{}", sql);
 
-        let conn = &mut self.pool.acquire().await.map_err(sqlx_conn_acquire_err)?;
+        let conn = &mut self.acquire().await?.0;
         match conn.execute(sqlx::AssertSqlSafe(sql.to_owned())).await {
             Ok(res) => Ok(res.into()),
             Err(err) => Err(sqlx_error_to_exec_err(err)),
@@ -171,8 +193,8 @@ impl SqlxMySqlPoolConnection {
         debug_print!("{}", stmt);
 
         let query = sqlx_query(&stmt);
-        let mut conn = self.pool.acquire().await.map_err(sqlx_conn_acquire_err)?;
-        crate::metric::metric!(self.metric_callback, &stmt, {
+        let (mut conn, acquire_elapsed) = self.acquire().await?;
+        crate::metric::metric!(self.metric_callback, &stmt, false, Some(acquire_elapsed), {
             match query.fetch_one(&mut *conn).await {
                 Ok(row) => Ok(Some(row.into())),
                 Err(err) => match err {
@@ -189,8 +211,8 @@ impl SqlxMySqlPoolConnection {
         debug_print!("{}", stmt);
 
         let query = sqlx_query(&stmt);
-        let mut conn = self.pool.acquire().await.map_err(sqlx_conn_acquire_err)?;
-        crate::metric::metric!(self.metric_callback, &stmt, {
+        let (mut conn, acquire_elapsed) = self.acquire().await?;
+        crate::metric::metric!(self.metric_callback, &stmt, false, Some(acquire_elapsed), {
             match query.fetch_all(&mut *conn).await {
                 Ok(rows) => Ok(rows.into_iter().map(|r| r.into()).collect()),
                 Err(err) => Err(sqlx_error_to_query_err(err)),
@@ -204,7 +226,7 @@ impl SqlxMySqlPoolConnection {
     pub async fn stream(&self, stmt: Statement) -> Result<QueryStream, DbErr> {
         debug_print!("{}", stmt);
 
-        let conn = self.pool.acquire().await.map_err(sqlx_conn_acquire_err)?;
+        let (conn, _) = self.acquire().await?;
         Ok(QueryStream::from((
             conn,
             stmt,
@@ -219,11 +241,12 @@ impl SqlxMySqlPoolConnection {
         isolation_level: Option<IsolationLevel>,
         access_mode: Option<AccessMode>,
     ) -> Result<DatabaseTransaction, DbErr> {
-        let conn = self.pool.acquire().await.map_err(sqlx_conn_acquire_err)?;
+        let (conn, _) = self.acquire().await?;
         DatabaseTransaction::new_mysql(
             conn,
             self.metric_callback.clone(),
             self.record_stmt_in_spans,
+            self.target.clone(),
             isolation_level,
             access_mode,
         )
@@ -246,11 +269,12 @@ impl SqlxMySqlPoolConnection {
         T: Send,
         E: std::fmt::Display + std::fmt::Debug + Send,
     {
-        let conn = self.pool.acquire().await.map_err(sqlx_conn_acquire_err)?;
+        let (conn, _) = self.acquire().await?;
         let transaction = DatabaseTransaction::new_mysql(
             conn,
             self.metric_callback.clone(),
             self.record_stmt_in_spans,
+            self.target.clone(),
             isolation_level,
             access_mode,
         )
@@ -266,9 +290,19 @@ impl SqlxMySqlPoolConnection {
         self.metric_callback = Some(Arc::new(callback));
     }
 
+    /// Statistics of the connection pool
+    pub fn pool_status(&self) -> crate::metric::PoolStatus {
+        crate::metric::PoolStatus {
+            size: self.pool.size(),
+            idle: self.pool.num_idle() as u32,
+            max_size: self.pool.options().get_max_connections(),
+            waiters: self.waiters.count(),
+        }
+    }
+
     /// Checks if a connection to the database is still valid.
     pub async fn ping(&self) -> Result<(), DbErr> {
-        let conn = &mut self.pool.acquire().await.map_err(sqlx_conn_acquire_err)?;
+        let conn = &mut self.acquire().await?.0;
         match conn.ping().await {
             Ok(_) => Ok(()),
             Err(err) => Err(sqlx_error_to_conn_err(err)),
@@ -291,7 +325,7 @@ impl SqlxMySqlPoolConnection {
 impl From<MySqlRow> for QueryResult {
     fn from(row: MySqlRow) -> QueryResult {
         QueryResult {
-            row: QueryResultRow::SqlxMySql(row),
+            row: QueryResultRow::SqlxMySql(Arc::new(row)),
         }
     }
 }
@@ -363,6 +397,7 @@ impl crate::DatabaseTransaction {
         inner: PoolConnection<sqlx::MySql>,
         metric_callback: Option<crate::metric::Callback>,
         record_stmt_in_spans: bool,
+        target: DbTarget,
         isolation_level: Option<IsolationLevel>,
         access_mode: Option<AccessMode>,
     ) -> Result<crate::DatabaseTransaction, DbErr> {
@@ -371,9 +406,12 @@ impl crate::DatabaseTransaction {
             crate::DbBackend::MySql,
             metric_callback,
             record_stmt_in_spans,
-            isolation_level,
-            access_mode,
-            None,
+            target,
+            TransactionOptions {
+                isolation_level,
+                access_mode,
+                ..Default::default()
+            },
         )
         .await
     }
@@ -436,87 +474,74 @@ pub(crate) fn from_sqlx_mysql_row_to_proxy_row(row: &sqlx::mysql::MySqlRow) -> c
                         "BIT" | "BINARY" | "VARBINARY" | "TINYBLOB" | "BLOB" | "MEDIUMBLOB"
                         | "LONGBLOB" => Value::Bytes(
                             row.try_get::<Option<Vec<u8>>, _>(c.ordinal())
-                                .expect("Failed to get bytes")
-                                .map(Box::new),
+                                .expect("Failed to get bytes"),
                         ),
 
                         "CHAR" | "VARCHAR" | "TINYTEXT" | "TEXT" | "MEDIUMTEXT" | "LONGTEXT" => {
                             Value::String(
                                 row.try_get::<Option<String>, _>(c.ordinal())
-                                    .expect("Failed to get string")
-                                    .map(Box::new),
+                                    .expect("Failed to get string"),
                             )
                         }
 
                         #[cfg(feature = "with-chrono")]
                         "TIMESTAMP" => Value::ChronoDateTimeUtc(
                             row.try_get::<Option<chrono::DateTime<chrono::Utc>>, _>(c.ordinal())
-                                .expect("Failed to get timestamp")
-                                .map(Box::new),
+                                .expect("Failed to get timestamp"),
                         ),
                         #[cfg(all(feature = "with-time", not(feature = "with-chrono")))]
                         "TIMESTAMP" => Value::TimeDateTime(
                             row.try_get::<Option<time::PrimitiveDateTime>, _>(c.ordinal())
-                                .expect("Failed to get timestamp")
-                                .map(Box::new),
+                                .expect("Failed to get timestamp"),
                         ),
 
                         #[cfg(feature = "with-chrono")]
                         "DATE" => Value::ChronoDate(
                             row.try_get::<Option<chrono::NaiveDate>, _>(c.ordinal())
-                                .expect("Failed to get date")
-                                .map(Box::new),
+                                .expect("Failed to get date"),
                         ),
                         #[cfg(all(feature = "with-time", not(feature = "with-chrono")))]
                         "DATE" => Value::TimeDate(
                             row.try_get::<Option<time::Date>, _>(c.ordinal())
-                                .expect("Failed to get date")
-                                .map(Box::new),
+                                .expect("Failed to get date"),
                         ),
 
                         #[cfg(feature = "with-chrono")]
                         "TIME" => Value::ChronoTime(
                             row.try_get::<Option<chrono::NaiveTime>, _>(c.ordinal())
-                                .expect("Failed to get time")
-                                .map(Box::new),
+                                .expect("Failed to get time"),
                         ),
                         #[cfg(all(feature = "with-time", not(feature = "with-chrono")))]
                         "TIME" => Value::TimeTime(
                             row.try_get::<Option<time::Time>, _>(c.ordinal())
-                                .expect("Failed to get time")
-                                .map(Box::new),
+                                .expect("Failed to get time"),
                         ),
 
                         #[cfg(feature = "with-chrono")]
                         "DATETIME" => Value::ChronoDateTime(
                             row.try_get::<Option<chrono::NaiveDateTime>, _>(c.ordinal())
-                                .expect("Failed to get datetime")
-                                .map(Box::new),
+                                .expect("Failed to get datetime"),
                         ),
                         #[cfg(all(feature = "with-time", not(feature = "with-chrono")))]
                         "DATETIME" => Value::TimeDateTime(
                             row.try_get::<Option<time::PrimitiveDateTime>, _>(c.ordinal())
-                                .expect("Failed to get datetime")
-                                .map(Box::new),
+                                .expect("Failed to get datetime"),
                         ),
 
                         #[cfg(feature = "with-chrono")]
                         "YEAR" => Value::ChronoDate(
                             row.try_get::<Option<chrono::NaiveDate>, _>(c.ordinal())
-                                .expect("Failed to get year")
-                                .map(Box::new),
+                                .expect("Failed to get year"),
                         ),
                         #[cfg(all(feature = "with-time", not(feature = "with-chrono")))]
                         "YEAR" => Value::TimeDate(
                             row.try_get::<Option<time::Date>, _>(c.ordinal())
-                                .expect("Failed to get year")
-                                .map(Box::new),
+                                .expect("Failed to get year"),
                         ),
 
                         "ENUM" | "SET" | "GEOMETRY" => Value::String(
                             row.try_get::<Option<String>, _>(c.ordinal())
-                                .expect("Failed to get serialized string")
-                                .map(Box::new),
+                                .expect("Failed to get serialized string"),
                         ),
 
                         #[cfg(feature = "with-bigdecimal")]
@@ -531,8 +556,7 @@ pub(crate) fn from_sqlx_mysql_row_to_proxy_row(row: &sqlx::mysql::MySqlRow) -> c
                         ))]
                         "DECIMAL" => Value::Decimal(
                             row.try_get::<Option<rust_decimal::Decimal>, _>(c.ordinal())
-                                .expect("Failed to get decimal")
-                                .map(Box::new),
+                                .expect("Failed to get decimal"),
                         ),
 
                         #[cfg(feature = "with-json")]
@@ -1,12 +1,12 @@
-use futures_util::lock::Mutex;
+use futures_util::{Stream, StreamExt, lock::Mutex};
 use log::LevelFilter;
 use sea_query::Values;
-use std::{fmt::Write, future::Future, pin::Pin, sync::Arc};
+use std::{fmt::Write, future::Future, pin::Pin, sync::Arc, time::Duration};
 
 use sqlx::{
-    Connection, Executor, PgPool, Postgres,
+    Connection, Executor, PgConnection, PgPool, Postgres,
     pool::PoolConnection,
-    postgres::{PgConnectOptions, PgQueryResult, PgRow},
+    postgres::{PgConnectOptions, PgListener, PgQueryResult, PgRow},
 };
 
 use sea_query_sqlx::SqlxValues;
@@ -14,24 +14,46 @@ use tracing::instrument;
 
 use crate::{
     AccessMode, ConnectOptions, DatabaseConnection, DatabaseConnectionType, DatabaseTransaction,
-    IsolationLevel, Statement, TransactionError, debug_print, error::*, executor::*,
+    IsolationLevel, Statement, TransactionError, TransactionOptions, debug_print, error::*,
+    executor::*,
 };
 
 use super::sqlx_common::*;
+use crate::database::tracing_spans::DbTarget;
 
 #[cfg(feature = "stream")]
-use crate::QueryStream;
+use crate::{
+    DbBackend, QueryStream,
+    database::{timeout::timeout_millis, transaction::quote_identifier},
+};
 
 /// Defines the [sqlx::postgres] connector
 #[derive(Debug)]
 pub struct SqlxPostgresConnector;
 
+/// A notification received on a channel listened to with
+/// [`DatabaseConnection::listen`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgNotification {
+    /// The channel the notification was sent on
+    pub channel: String,
+    /// The payload, empty if none was given
+    pub payload: String,
+    /// Process ID of the backend that sent the notification
+    pub process_id: u32,
+}
+
+/// Notifications on the channels passed to [`DatabaseConnection::listen`]
+pub type PgNotificationStream = Pin<Box<dyn Stream<Item = Result<PgNotification, DbErr>> + Send>>;
+
 /// Defines a sqlx PostgreSQL pool
 #[derive(Clone)]
 pub struct SqlxPostgresPoolConnection {
     pub(crate) pool: PgPool,
     metric_callback: Option<crate::metric::Callback>,
+    waiters: Arc<PoolWaiters>,
     pub(crate) record_stmt_in_spans: bool,
+    pub(crate) target: DbTarget,
 }
 
 impl std::fmt::Debug for SqlxPostgresPoolConnection {
@@ -43,8 +65,10 @@ impl std::fmt::Debug for SqlxPostgresPoolConnection {
 impl From<PgPool> for SqlxPostgresPoolConnection {
     fn from(pool: PgPool) -> Self {
         SqlxPostgresPoolConnection {
+            target: db_target(&pool),
             pool,
             metric_callback: None,
+            waiters: Default::default(),
             record_stmt_in_spans: true,
         }
     }
@@ -150,8 +174,10 @@ impl SqlxPostgresConnector {
 
         let conn: DatabaseConnection =
             DatabaseConnectionType::SqlxPostgresPoolConnection(SqlxPostgresPoolConnection {
+                target: db_target(&pool),
                 pool,
                 metric_callback: None,
+                waiters: Default::default(),
                 record_stmt_in_spans,
             })
             .into();
@@ -168,23 +194,37 @@ impl SqlxPostgresConnector {
     /// Instantiate a sqlx pool connection to a [DatabaseConnection]
     pub fn from_sqlx_postgres_pool(pool: PgPool) -> DatabaseConnection {
         DatabaseConnectionType::SqlxPostgresPoolConnection(SqlxPostgresPoolConnection {
+            target: db_target(&pool),
             pool,
             metric_callback: None,
+            waiters: Default::default(),
             record_stmt_in_spans: true,
         })
         .into()
     }
 }
 
+/// The database `pool` connects to, recorded on spans
+fn db_target(pool: &PgPool) -> DbTarget {
+    let options = pool.connect_options();
+    DbTarget::new(options.get_database(), Some(options.get_host()))
+}
+
 impl SqlxPostgresPoolConnection {
+    async fn acquire(&self) -> Result<(PoolConnection<Postgres>, Duration), DbErr> {
+        self.waiters
+            .acquire(&self.pool, crate::DbBackend::Postgres, &self.target)
+            .await
+    }
+
     /// Execute a [Statement] on a PostgreSQL backend
     #[instrument(level = "trace", skip(stmt))]
     pub async fn execute(&self, stmt: Statement) -> Result<ExecResult, DbErr> {
         debug_print!("{}", stmt);
 
         let query = sqlx_query(&stmt);
-        let mut conn = self.pool.acquire().await.map_err(sqlx_conn_acquire_err)?;
-        crate::metric::metric!(self.metric_callback, &stmt, {
+        let (mut conn, acquire_elapsed) = self.acquire().await?;
+        crate::metric::metric!(self.metric_callback, &stmt, false, Some(acquire_elapsed), {
             match query.execute(&mut *conn).await {
                 Ok(res) => Ok(res.into()),
                 Err(err) => Err(sqlx_error_to_exec_err(err)),
@@ -197,7 +237,7 @@ impl SqlxPostgresPoolConnection {
     pub async fn execute_unprepared(&self, sql: &str) -> Result<ExecResult, DbErr> {
         debug_print!("{}", sql);
 
-        let conn = &mut self.pool.acquire().await.map_err(sqlx_conn_acquire_err)?;
+        let conn = &mut self.acquire().await?.0;
         match conn.execute(sqlx::AssertSqlSafe(sql.to_owned())).await {
             Ok(res) => Ok(res.into()),
             Err(err) => Err(sqlx_error_to_exec_err(err)),
@@ -210,8 +250,8 @@ impl SqlxPostgresPoolConnection {
         debug_print!("{}", stmt);
 
         let query = sqlx_query(&stmt);
-        let mut conn = self.pool.acquire().await.map_err(sqlx_conn_acquire_err)?;
-        crate::metric::metric!(self.metric_callback, &stmt, {
+        let (mut conn, acquire_elapsed) = self.acquire().await?;
+        crate::metric::metric!(self.metric_callback, &stmt, false, Some(acquire_elapsed), {
             match query.fetch_one(&mut *conn).await {
                 Ok(row) => Ok(Some(row.into())),
                 Err(err) => match err {
@@ -228,8 +268,8 @@ impl SqlxPostgresPoolConnection {
         debug_print!("{}", stmt);
 
         let query = sqlx_query(&stmt);
-        let mut conn = self.pool.acquire().await.map_err(sqlx_conn_acquire_err)?;
-        crate::metric::metric!(self.metric_callback, &stmt, {
+        let (mut conn, acquire_elapsed) = self.acquire().await?;
+        crate::metric::metric!(self.metric_callback, &stmt, false, Some(acquire_elapsed), {
             match query.fetch_all(&mut *conn).await {
                 Ok(rows) => Ok(rows.into_iter().map(|r| r.into()).collect()),
                 Err(err) => Err(sqlx_error_to_query_err(err)),
@@ -243,7 +283,66 @@ impl SqlxPostgresPoolConnection {
     pub async fn stream(&self, stmt: Statement) -> Result<QueryStream, DbErr> {
         debug_print!("{}", stmt);
 
-        let conn = self.pool.acquire().await.map_err(sqlx_conn_acquire_err)?;
+        let (conn, _) = self.acquire().await?;
+        Ok(QueryStream::from((
+            conn,
+            stmt,
+            self.metric_callback.clone(),
+        )))
+    }
+
+    /// Stream the results of a query run with `schema` as the `search_path`, on a connection
+    /// that is closed afterwards, so the setting does not find its way back into the pool
+    #[instrument(level = "trace", skip(stmt))]
+    #[cfg(feature = "stream")]
+    pub async fn stream_in_schema(
+        &self,
+        stmt: Statement,
+        schema: &str,
+    ) -> Result<QueryStream, DbErr> {
+        debug_print!("{}", stmt);
+
+        let (mut conn, _) = self.acquire().await?;
+        conn.close_on_drop();
+        sqlx::query("SELECT set_config('search_path', $1, false)")
+            .bind(quote_identifier(DbBackend::Postgres, schema))
+            .execute(&mut *conn)
+            .await
+            .map_err(sqlx_error_to_query_err)?;
+        Ok(QueryStream::from((
+            conn,
+            stmt,
+            self.metric_callback.clone(),
+        )))
+    }
+
+    /// Stream the results of a query cancelled once it has run for longer than `timeout`,
+    /// on a connection that is closed afterwards, so the `statement_timeout` (and the
+    /// `search_path`, if there is a `schema`) does not find its way back into the pool
+    #[instrument(level = "trace", skip(stmt))]
+    #[cfg(feature = "stream")]
+    pub async fn stream_with_timeout(
+        &self,
+        stmt: Statement,
+        schema: Option<&str>,
+        timeout: Duration,
+    ) -> Result<QueryStream, DbErr> {
+        debug_print!("{}", stmt);
+
+        let (mut conn, _) = self.acquire().await?;
+        conn.close_on_drop();
+        sqlx::query("SELECT set_config('statement_timeout', $1, false)")
+            .bind(timeout_millis(timeout).to_string())
+            .execute(&mut *conn)
+            .await
+            .map_err(sqlx_error_to_query_err)?;
+        if let Some(schema) = schema {
+            sqlx::query("SELECT set_config('search_path', $1, false)")
+                .bind(quote_identifier(DbBackend::Postgres, schema))
+                .execute(&mut *conn)
+                .await
+                .map_err(sqlx_error_to_query_err)?;
+        }
         Ok(QueryStream::from((
             conn,
             stmt,
@@ -251,6 +350,33 @@ impl SqlxPostgresPoolConnection {
         )))
     }
 
+    /// Run a `COPY ... FROM STDIN` statement, feeding it the chunks of `data`.
+    /// Returns the number of rows copied.
+    #[instrument(level = "trace", skip(data))]
+    pub async fn copy_in(
+        &self,
+        sql: &str,
+        data: &mut (dyn Iterator<Item = Result<Vec<u8>, DbErr>> + Send),
+    ) -> Result<u64, DbErr> {
+        debug_print!("{}", sql);
+
+        let conn = &mut self.acquire().await?.0;
+        copy_in(conn, sql, data).await
+    }
+
+    /// Run a `COPY ... TO STDOUT` statement, writing what it outputs to `writer`.
+    #[instrument(level = "trace", skip(writer))]
+    pub async fn copy_out(
+        &self,
+        sql: &str,
+        writer: &mut (dyn std::io::Write + Send),
+    ) -> Result<(), DbErr> {
+        debug_print!("{}", sql);
+
+        let conn = &mut self.acquire().await?.0;
+        copy_out(conn, sql, writer).await
+    }
+
     /// Bundle a set of SQL statements that execute together.
     #[instrument(level = "trace")]
     pub async fn begin(
@@ -258,11 +384,12 @@ impl SqlxPostgresPoolConnection {
         isolation_level: Option<IsolationLevel>,
         access_mode: Option<AccessMode>,
     ) -> Result<DatabaseTransaction, DbErr> {
-        let conn = self.pool.acquire().await.map_err(sqlx_conn_acquire_err)?;
+        let (conn, _) = self.acquire().await?;
         DatabaseTransaction::new_postgres(
             conn,
             self.metric_callback.clone(),
             self.record_stmt_in_spans,
+            self.target.clone(),
             isolation_level,
             access_mode,
         )
@@ -285,11 +412,12 @@ impl SqlxPostgresPoolConnection {
         T: Send,
         E: std::fmt::Display + std::fmt::Debug + Send,
     {
-        let conn = self.pool.acquire().await.map_err(sqlx_conn_acquire_err)?;
+        let (conn, _) = self.acquire().await?;
         let transaction = DatabaseTransaction::new_postgres(
             conn,
             self.metric_callback.clone(),
             self.record_stmt_in_spans,
+            self.target.clone(),
             isolation_level,
             access_mode,
         )
@@ -305,9 +433,41 @@ impl SqlxPostgresPoolConnection {
         self.metric_callback = Some(Arc::new(callback));
     }
 
+    /// Statistics of the connection pool
+    pub fn pool_status(&self) -> crate::metric::PoolStatus {
+        crate::metric::PoolStatus {
+            size: self.pool.size(),
+            idle: self.pool.num_idle() as u32,
+            max_size: self.pool.options().get_max_connections(),
+            waiters: self.waiters.count(),
+        }
+    }
+
+    /// Listen for notifications on `channels`, on a connection of its own taken from the
+    /// pool. The connection is re-established should it be lost, but notifications
+    /// sent in the meantime are missed.
+    #[instrument(level = "trace")]
+    pub async fn listen(&self, channels: &[&str]) -> Result<PgNotificationStream, DbErr> {
+        let mut listener = PgListener::connect_with(&self.pool)
+            .await
+            .map_err(sqlx_error_to_conn_err)?;
+        listener
+            .listen_all(channels.iter().copied())
+            .await
+            .map_err(sqlx_error_to_exec_err)?;
+        Ok(Box::pin(listener.into_stream().map(|res| {
+            res.map(|notification| PgNotification {
+                channel: notification.channel().to_owned(),
+                payload: notification.payload().to_owned(),
+                process_id: notification.process_id(),
+            })
+            .map_err(sqlx_error_to_query_err)
+        })))
+    }
+
     /// Checks if a connection to the database is still valid.
     pub async fn ping(&self) -> Result<(), DbErr> {
-        let conn = &mut self.pool.acquire().await.map_err(sqlx_conn_acquire_err)?;
+        let conn = &mut self.acquire().await?.0;
         match conn.ping().await {
             Ok(_) => Ok(()),
             Err(err) => Err(sqlx_error_to_conn_err(err)),
@@ -330,7 +490,7 @@ impl SqlxPostgresPoolConnection {
 impl From<PgRow> for QueryResult {
     fn from(row: PgRow) -> QueryResult {
         QueryResult {
-            row: QueryResultRow::SqlxPostgres(row),
+            row: QueryResultRow::SqlxPostgres(Arc::new(row)),
         }
     }
 }
@@ -376,6 +536,54 @@ pub(crate) async fn set_transaction_config(
     Ok(())
 }
 
+pub(crate) async fn copy_in(
+    conn: &mut PgConnection,
+    sql: &str,
+    data: &mut (dyn Iterator<Item = Result<Vec<u8>, DbErr>> + Send),
+) -> Result<u64, DbErr> {
+    let mut copy = conn
+        .copy_in_raw(sql)
+        .await
+        .map_err(sqlx_error_to_exec_err)?;
+    for chunk in data {
+        let res = match chunk {
+            Ok(chunk) => copy
+                .send(chunk)
+                .await
+                .map(|_| ())
+                .map_err(sqlx_error_to_exec_err),
+            Err(err) => Err(err),
+        };
+        if let Err(err) = res {
+            // end the `COPY`, so that the connection can be used again; the error that made
+            // us give up is the one worth returning
+            let _ = copy.abort(err.to_string()).await;
+            return Err(err);
+        }
+    }
+    copy.finish().await.map_err(sqlx_error_to_exec_err)
+}
+
+pub(crate) async fn copy_out(
+    conn: &mut PgConnection,
+    sql: &str,
+    writer: &mut (dyn std::io::Write + Send),
+) -> Result<(), DbErr> {
+    let mut stream = conn
+        .copy_out_raw(sql)
+        .await
+        .map_err(sqlx_error_to_query_err)?;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(sqlx_error_to_query_err)?;
+        writer
+            .write_all(&chunk)
+            .map_err(|e| DbErr::Query(RuntimeErr::Internal(format!("COPY output: {e}"))))?;
+    }
+    writer
+        .flush()
+        .map_err(|e| DbErr::Query(RuntimeErr::Internal(format!("COPY output: {e}"))))
+}
+
 #[cfg(feature = "stream")]
 impl
     From<(
@@ -404,6 +612,7 @@ impl crate::DatabaseTransaction {
         inner: PoolConnection<sqlx::Postgres>,
         metric_callback: Option<crate::metric::Callback>,
         record_stmt_in_spans: bool,
+        target: DbTarget,
         isolation_level: Option<IsolationLevel>,
         access_mode: Option<AccessMode>,
     ) -> Result<crate::DatabaseTransaction, DbErr> {
@@ -412,9 +621,12 @@ impl crate::DatabaseTransaction {
             crate::DbBackend::Postgres,
             metric_callback,
             record_stmt_in_spans,
-            isolation_level,
-            access_mode,
-            None,
+            target,
+            TransactionOptions {
+                isolation_level,
+                access_mode,
+                ..Default::default()
+            },
         )
         .await
     }
@@ -592,7 +804,8 @@ pub(crate) fn from_sqlx_postgres_row_to_proxy_row(row: &sqlx::postgres::PgRow) -
                         #[cfg(feature = "with-bigdecimal")]
                         "NUMERIC" => Value::BigDecimal(
                             row.try_get::<Option<bigdecimal::BigDecimal>, _>(c.ordinal())
-                                .expect("Failed to get numeric"),
+                                .expect("Failed to get numeric")
+                                .map(Box::new),
                         ),
                         #[cfg(all(
                             feature = "with-rust_decimal",
@@ -610,7 +823,7 @@ pub(crate) fn from_sqlx_postgres_row_to_proxy_row(row: &sqlx::postgres::PgRow) -
                                 .map(|vals| {
                                     Box::new(
                                         vals.into_iter()
-                                            .map(|val| Value::BigDecimal(Some(val)))
+                                            .map(|val| Value::BigDecimal(Some(Box::new(val))))
                                             .collect(),
                                     )
                                 }),
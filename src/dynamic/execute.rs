@@ -1,6 +1,6 @@
 use crate::{
-    ConnectionTrait, DbBackend, DbErr, EntityTrait, FromQueryResult, QueryResult, Select,
-    Statement, dynamic,
+    ConnectionTrait, DbBackend, DbErr, EntityTrait, FromQueryResult, QueryResult, QueryTrait,
+    Select, Statement, dynamic,
 };
 use itertools::Itertools;
 use sea_query::{DynIden, Expr, IntoIden, SelectStatement};
@@ -61,7 +61,7 @@ where
             )));
         }
         DynSelector {
-            query: self.query,
+            query: self.into_query(),
             selector: SelectModelAndDynModel {
                 model: PhantomData,
                 dyn_model,
@@ -9,7 +9,7 @@ use crate::{
         set_key_on_active_model,
     },
 };
-use sea_query::ValueTuple;
+use sea_query::{Keyword, SimpleExpr, ValueTuple};
 use std::fmt::Debug;
 
 /// The editable counterpart of a [`Model`](crate::ModelTrait), used to build
@@ -457,6 +457,78 @@ pub trait ActiveModelTrait: Clone + Debug {
         Ok(delete_res)
     }
 
+    /// Bring back a model that has been soft deleted, by setting its
+    /// [soft delete column](EntityTrait::soft_delete_column) to `NULL`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sea_orm::{error::*, tests_cfg::*, *};
+    /// #
+    /// # #[smol_potat::main]
+    /// # #[cfg(all(feature = "mock", feature = "with-chrono"))]
+    /// # pub async fn main() -> Result<(), DbErr> {
+    /// #
+    /// # let db = MockDatabase::new(DbBackend::Postgres)
+    /// #     .append_exec_results([
+    /// #         MockExecResult {
+    /// #             last_insert_id: 0,
+    /// #             rows_affected: 1,
+    /// #         },
+    /// #     ])
+    /// #     .into_connection();
+    /// #
+    /// use sea_orm::{entity::*, query::*, tests_cfg::cake_review};
+    ///
+    /// let cake_review = cake_review::ActiveModel {
+    ///     id: Set(3),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let restore_result = cake_review.restore(&db).await?;
+    ///
+    /// assert_eq!(restore_result.rows_affected, 1);
+    ///
+    /// assert_eq!(
+    ///     db.into_transaction_log(),
+    ///     [Transaction::from_sql_and_values(
+    ///         DbBackend::Postgres,
+    ///         r#"UPDATE "cake_review" SET "deleted_at" = NULL WHERE "cake_review"."id" = $1"#,
+    ///         [3i32.into()]
+    ///     )]
+    /// );
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    async fn restore<'a, C>(self, db: &'a C) -> Result<UpdateResult, DbErr>
+    where
+        Self: ActiveModelBehavior,
+        C: ConnectionTrait,
+    {
+        let entity = Self::Entity::default();
+        let Some(col) = <Self::Entity as EntityTrait>::soft_delete_column() else {
+            return Err(DbErr::Custom(format!(
+                "Entity `{}` has no soft delete column",
+                entity.table_name()
+            )));
+        };
+        let mut update =
+            Self::Entity::update_many().col_expr(col, SimpleExpr::Keyword(Keyword::Null));
+        for key in <Self::Entity as EntityTrait>::PrimaryKey::iter() {
+            let col = key.into_column();
+            match self.get(col) {
+                ActiveValue::Set(value) | ActiveValue::Unchanged(value) => {
+                    update = update.filter(col.eq(value));
+                }
+                ActiveValue::NotSet => {
+                    return Err(DbErr::PrimaryKeyNotSet { ctx: "restore" });
+                }
+            }
+        }
+        update.exec(db).await
+    }
+
     /// Set the corresponding attributes in the ActiveModel from a JSON value
     ///
     /// Note that this method will not alter the primary key values in ActiveModel.
@@ -1,8 +1,8 @@
 use crate::{
-    ActiveModelBehavior, ActiveModelTrait, ColumnTrait, Delete, DeleteMany, DeleteOne,
-    FromQueryResult, Identity, Insert, InsertMany, ModelTrait, PrimaryKeyArity, PrimaryKeyToColumn,
-    PrimaryKeyTrait, QueryFilter, Related, RelationBuilder, RelationTrait, RelationType, Select,
-    Update, UpdateMany, UpdateOne, ValidatedDeleteOne,
+    ActiveModelBehavior, ActiveModelTrait, ColumnTrait, Condition, CopyIn, Delete, DeleteMany,
+    DeleteOne, FromQueryResult, Identity, Insert, InsertMany, ModelTrait, PrimaryKeyArity,
+    PrimaryKeyToColumn, PrimaryKeyTrait, QueryFilter, Related, RelationBuilder, RelationTrait,
+    RelationType, Select, Update, UpdateMany, UpdateOne, ValidatedDeleteOne,
 };
 use sea_query::{Iden, IntoIden, IntoTableRef, IntoValueTuple, TableRef};
 use std::fmt::Debug;
@@ -95,6 +95,57 @@ pub trait EntityTrait: EntityName {
     #[allow(missing_docs)]
     type PrimaryKey: PrimaryKeyTrait + PrimaryKeyToColumn<Column = Self::Column>;
 
+    /// The timestamp column marking a row as soft deleted, set with
+    /// `#[sea_orm(soft_delete = "deleted_at")]`.
+    ///
+    /// Rows where it is not `NULL` are left out of [`find`](Self::find), loaders,
+    /// related joins and every hop of linked joins, unless asked for with
+    /// [`Select::with_trashed`] or [`Select::only_trashed`]. Deleting sets it to the
+    /// current time instead, unless asked for with [`DeleteMany::force_delete`].
+    fn soft_delete_column() -> Option<Self::Column> {
+        None
+    }
+
+    /// The integer column used for optimistic locking, set with `#[sea_orm(version)]`.
+    ///
+    /// [`update`](crate::ActiveModelTrait::update) and [`save`](crate::ActiveModelTrait::save)
+    /// only update the row if it still has the version the model was loaded with, and
+    /// increment it. Otherwise they fail with [`DbErr::StaleModel`](crate::DbErr::StaleModel).
+    fn version_column() -> Option<Self::Column> {
+        None
+    }
+
+    /// The column set to the current time when a row is inserted, set with
+    /// `#[sea_orm(created_at)]`.
+    ///
+    /// [`insert`](Self::insert) and [`insert_many`](Self::insert_many) fill it unless
+    /// it is set, with [`ColumnTrait::current_timestamp`].
+    fn created_at_column() -> Option<Self::Column> {
+        None
+    }
+
+    /// The column set to the current time when a row is inserted or updated, set
+    /// with `#[sea_orm(updated_at)]`.
+    ///
+    /// Besides inserts, [`update`](Self::update) and [`update_many`](Self::update_many)
+    /// fill it unless it is set or there is nothing to update.
+    fn updated_at_column() -> Option<Self::Column> {
+        None
+    }
+
+    /// Conditions every [`find`](Self::find) of this entity is restricted to, e.g. to
+    /// only return published rows, set with `#[sea_orm(default_scope = "published")]`
+    /// naming a `fn() -> Condition`.
+    ///
+    /// They also apply to [`find_related`](crate::ModelTrait::find_related), loaders, and
+    /// the joins to this entity of [`Select::find_also_related`] and the like, where they
+    /// are added to the `ON` clause. As the conditions name the table itself, a join to
+    /// an aliased table, as of linked entities, matches the rows in scope by primary key.
+    /// [`Select::unscoped`] leaves them out of a query.
+    fn default_scope() -> Option<Condition> {
+        None
+    }
+
     /// Start building a `belongs_to` relation: this table holds the foreign
     /// key pointing at `related`. Call `.from(...).to(...)` on the returned
     /// builder to specify the column mapping.
@@ -711,6 +762,51 @@ pub trait EntityTrait: EntityName {
         InsertMany::many(models)
     }
 
+    /// Bulk-load many models, through `COPY ... FROM STDIN` on Postgres and multi-row
+    /// `INSERT`s elsewhere. See [`CopyIn`] for details.
+    ///
+    /// ```
+    /// # use sea_orm::{error::*, tests_cfg::*, *};
+    /// #
+    /// # #[smol_potat::main]
+    /// # #[cfg(feature = "mock")]
+    /// # pub async fn main() -> Result<(), DbErr> {
+    /// #
+    /// # let db = MockDatabase::new(DbBackend::Sqlite)
+    /// #     .append_exec_results([MockExecResult {
+    /// #         last_insert_id: 2,
+    /// #         rows_affected: 2,
+    /// #     }])
+    /// #     .into_connection();
+    /// #
+    /// use sea_orm::{entity::*, query::*, tests_cfg::cake};
+    ///
+    /// let cakes = ["Apple Pie", "Choco Pie"].map(|name| cake::ActiveModel {
+    ///     id: NotSet,
+    ///     name: Set(name.to_owned()),
+    /// });
+    /// assert_eq!(cake::Entity::copy_in(cakes).exec(&db).await?, 2);
+    ///
+    /// assert_eq!(
+    ///     db.into_transaction_log(),
+    ///     [Transaction::from_sql_and_values(
+    ///         DbBackend::Sqlite,
+    ///         r#"INSERT INTO "cake" ("name") VALUES (?), (?)"#,
+    ///         ["Apple Pie".into(), "Choco Pie".into()]
+    ///     )]
+    /// );
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn copy_in<A, I>(models: I) -> CopyIn<A>
+    where
+        A: ActiveModelTrait<Entity = Self>,
+        I: IntoIterator<Item = A>,
+    {
+        CopyIn::many(models)
+    }
+
     /// Update a model in database
     ///
     /// - To apply where conditions / filters, see [`QueryFilter`](crate::query::QueryFilter)
@@ -0,0 +1,226 @@
+use crate::{
+    ActiveModelTrait, ColumnTrait, ColumnType, DbErr, EntityTrait, IdenStatic, Iterable,
+    PrimaryKeyToColumn, PrimaryKeyTrait, Value,
+};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// An entity whose models can be generated with default values, implemented by
+/// [`DeriveFactory`](crate::DeriveFactory).
+pub trait EntityFactory: EntityTrait {
+    /// Build the next default model, along with the default models of its
+    /// required `belongs_to` parents.
+    fn build_ex() -> Result<Self::ActiveModelEx, DbErr>;
+}
+
+/// The per-column overrides and the sequence counter of a factory generated by
+/// [`DeriveFactory`](crate::DeriveFactory).
+///
+/// Every built model takes the next sequence number `n` of its entity, which
+/// is shared by all factories of the entity, so that unique columns stay unique.
+/// Columns that are not overridden are filled from their [`ColumnType`]:
+///
+/// - auto-increment primary keys are left `NotSet`
+/// - nullable columns are `NULL`
+/// - string columns become `"{column}_{n}"`
+/// - integer, floating point and UUID columns are derived from `n`
+/// - other columns take the value given by
+///   [`ActiveModelTrait::default_values`], if the field type implements `Default`
+pub struct FactoryConfig<E>
+where
+    E: EntityTrait,
+{
+    overrides: Vec<(E::Column, FactoryValue)>,
+    sequence: &'static AtomicU64,
+}
+
+#[derive(Debug, Clone)]
+enum FactoryValue {
+    Value(Value),
+    Sequence(String),
+}
+
+impl<E> FactoryConfig<E>
+where
+    E: EntityTrait,
+{
+    /// Create a config without overrides, taking sequence numbers from `sequence`
+    pub fn new(sequence: &'static AtomicU64) -> Self {
+        Self {
+            overrides: Vec::new(),
+            sequence,
+        }
+    }
+
+    /// Use a fixed value for a column
+    pub fn set<V>(&mut self, col: E::Column, value: V)
+    where
+        V: Into<Value>,
+    {
+        self.push(col, FactoryValue::Value(value.into()));
+    }
+
+    /// Use a string for a column, with every `{n}` replaced by the sequence number
+    pub fn sequence(&mut self, col: E::Column, template: &str) {
+        self.push(col, FactoryValue::Sequence(template.to_owned()));
+    }
+
+    /// Whether a column has been overridden
+    pub fn is_set(&self, col: E::Column) -> bool {
+        self.overrides
+            .iter()
+            .any(|(c, _)| c.as_str() == col.as_str())
+    }
+
+    /// Take the next sequence number
+    pub fn next_sequence(&self) -> u64 {
+        self.sequence.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Build the model for sequence number `n`, without any related models
+    pub fn build<A>(&self, n: u64) -> Result<A, DbErr>
+    where
+        A: ActiveModelTrait<Entity = E>,
+    {
+        let mut model = A::default_values();
+
+        for col in E::Column::iter() {
+            if let Some(value) = self.value(col, n) {
+                model.try_set(col, value)?;
+            } else if is_auto_increment::<E>(col) {
+                model.not_set(col);
+            } else if let Some(value) = default_value(col, n) {
+                // a mismatch means the field is not of the usual Rust type for
+                // its column type; keep what `default_values` gave it
+                let _ = model.try_set(col, value);
+            }
+        }
+
+        Ok(model)
+    }
+
+    fn push(&mut self, col: E::Column, value: FactoryValue) {
+        self.overrides.retain(|(c, _)| c.as_str() != col.as_str());
+        self.overrides.push((col, value));
+    }
+
+    fn value(&self, col: E::Column, n: u64) -> Option<Value> {
+        let (_, value) = self
+            .overrides
+            .iter()
+            .find(|(c, _)| c.as_str() == col.as_str())?;
+        Some(match value {
+            FactoryValue::Value(value) => value.clone(),
+            FactoryValue::Sequence(template) => template.replace("{n}", &n.to_string()).into(),
+        })
+    }
+}
+
+impl<E> Clone for FactoryConfig<E>
+where
+    E: EntityTrait,
+{
+    fn clone(&self) -> Self {
+        Self {
+            overrides: self.overrides.clone(),
+            sequence: self.sequence,
+        }
+    }
+}
+
+impl<E> std::fmt::Debug for FactoryConfig<E>
+where
+    E: EntityTrait,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FactoryConfig")
+            .field("overrides", &self.overrides)
+            .field("sequence", &self.sequence)
+            .finish()
+    }
+}
+
+fn is_auto_increment<E>(col: E::Column) -> bool
+where
+    E: EntityTrait,
+{
+    E::PrimaryKey::auto_increment()
+        && E::PrimaryKey::iter().any(|key| key.into_column().as_str() == col.as_str())
+}
+
+fn default_value<C>(col: C, n: u64) -> Option<Value>
+where
+    C: ColumnTrait,
+{
+    let def = col.def();
+    if def.is_null() {
+        return None;
+    }
+    Some(match def.get_column_type() {
+        ColumnType::Char(_) | ColumnType::String(_) | ColumnType::Text => {
+            format!("{}_{n}", col.as_str()).into()
+        }
+        ColumnType::TinyInteger => i8::try_from(n).ok()?.into(),
+        ColumnType::SmallInteger => i16::try_from(n).ok()?.into(),
+        ColumnType::Integer => i32::try_from(n).ok()?.into(),
+        ColumnType::BigInteger => i64::try_from(n).ok()?.into(),
+        ColumnType::TinyUnsigned => u8::try_from(n).ok()?.into(),
+        ColumnType::SmallUnsigned => u16::try_from(n).ok()?.into(),
+        ColumnType::Unsigned => u32::try_from(n).ok()?.into(),
+        ColumnType::BigUnsigned => n.into(),
+        ColumnType::Float => (n as f32).into(),
+        ColumnType::Double => (n as f64).into(),
+        #[cfg(feature = "with-uuid")]
+        ColumnType::Uuid => uuid::Uuid::from_u128(n as u128).into(),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests_cfg::{cake, fruit};
+    use crate::{ActiveValue::*, FactoryConfig};
+    use pretty_assertions::assert_eq;
+    use std::sync::atomic::AtomicU64;
+
+    #[test]
+    fn test_factory_config_build() {
+        static CAKE_SEQUENCE: AtomicU64 = AtomicU64::new(1);
+        static FRUIT_SEQUENCE: AtomicU64 = AtomicU64::new(1);
+
+        let config = FactoryConfig::<cake::Entity>::new(&CAKE_SEQUENCE);
+        let n = config.next_sequence();
+        assert_eq!(
+            config.build::<cake::ActiveModel>(n).unwrap(),
+            cake::ActiveModel {
+                id: NotSet,
+                name: Set("name_1".to_owned()),
+            }
+        );
+
+        let mut config = FactoryConfig::<fruit::Entity>::new(&FRUIT_SEQUENCE);
+        config.sequence(fruit::Column::Name, "Fruit #{n}");
+        config.clone().next_sequence();
+        let n = config.next_sequence();
+        assert_eq!(
+            config.build::<fruit::ActiveModel>(n).unwrap(),
+            fruit::ActiveModel {
+                id: NotSet,
+                name: Set("Fruit #2".to_owned()),
+                cake_id: Set(None),
+            }
+        );
+
+        config.set(fruit::Column::CakeId, 3);
+        assert_eq!(
+            config.build::<fruit::ActiveModel>(n).unwrap(),
+            fruit::ActiveModel {
+                id: NotSet,
+                name: Set("Fruit #2".to_owned()),
+                cake_id: Set(Some(3)),
+            }
+        );
+
+        config.set(fruit::Column::CakeId, "3");
+        assert!(config.build::<fruit::ActiveModel>(n).is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{EntityTrait, QuerySelect, RelationDef, Select, join_tbl_on_condition};
+use crate::{EntityTrait, QuerySelect, QueryTrait, RelationDef, Select, join_tbl_on_condition};
 use sea_query::{
     Alias, CommonTableExpression, Condition, IntoIden, IntoTableRef, JoinType, UnionType,
 };
@@ -54,8 +54,11 @@ where
         if let Some(f) = rel.on_condition.take() {
             condition = condition.add(f(from_tbl.clone(), to_tbl.clone()));
         }
+        if let Some(scope) = rel.from_scope {
+            condition = condition.add(scope(from_tbl.clone()));
+        }
 
-        select.query().join_as(join, table_ref, from_tbl, condition);
+        QuerySelect::query(&mut select).join_as(join, table_ref, from_tbl, condition);
     }
     select
 }
@@ -76,11 +79,11 @@ where
     let mut recursive_query: Select<E> =
         find_linked(link.into_iter().rev(), JoinType::InnerJoin).select_only();
     initial_query.query.exprs_mut_for_each(|expr| {
-        recursive_query.query.expr(expr.clone());
+        QuerySelect::query(&mut recursive_query).expr(expr.clone());
     });
 
-    let mut cte_query = initial_query.query.clone();
-    cte_query.union(UnionType::All, recursive_query.query);
+    let mut cte_query = QueryTrait::as_query(&initial_query).clone();
+    cte_query.union(UnionType::All, QueryTrait::into_query(recursive_query));
 
     let cte = CommonTableExpression::new()
         .table_name(cte_name.clone())
@@ -89,10 +92,9 @@ where
 
     let mut select = E::find().select_only();
     initial_query.query.exprs_mut_for_each(|expr| {
-        select.query.expr(expr.clone());
+        QuerySelect::query(&mut select).expr(expr.clone());
     });
-    select
-        .query
+    QuerySelect::query(&mut select)
         .from_clear()
         .from_as(cte_name, E::default())
         .with_cte(cte);
@@ -2,7 +2,7 @@ use crate::{
     ActiveModelBehavior, ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, DeleteResult,
     EntityTrait, IntoActiveModel, Iterable, Linked, PrimaryKeyArity, PrimaryKeyToColumn,
     PrimaryKeyTrait, QueryFilter, QueryResult, Related, Select, SelectModel, SelectorRaw,
-    Statement, TryGetError, find_linked_recursive,
+    Statement, TryGetError, UpdateResult, find_linked_recursive,
 };
 pub use sea_query::Value;
 use sea_query::{ArrayType, ValueTuple};
@@ -82,6 +82,16 @@ pub trait ModelTrait: Clone + Send + Debug {
         self.into_active_model().delete(db).await
     }
 
+    /// Bring back a soft deleted model, see [`ActiveModelTrait::restore`]
+    async fn restore<'a, A, C>(self, db: &'a C) -> Result<UpdateResult, DbErr>
+    where
+        Self: IntoActiveModel<A>,
+        C: ConnectionTrait,
+        A: ActiveModelTrait<Entity = Self::Entity> + ActiveModelBehavior + Send + 'a,
+    {
+        self.into_active_model().restore(db).await
+    }
+
     /// Get the primary key value of the Model
     fn get_primary_key_value(&self) -> ValueTuple {
         let mut cols = <Self::Entity as EntityTrait>::PrimaryKey::iter();
@@ -1,5 +1,6 @@
 use crate::{
-    EntityTrait, Identity, IdentityOf, Iterable, QuerySelect, Select, join_tbl_on_condition,
+    EntityTrait, Identity, IdentityOf, Iterable, QuerySelect, Select, join_scope_of,
+    join_tbl_on_condition,
 };
 use core::marker::PhantomData;
 use sea_query::{
@@ -114,6 +115,14 @@ pub struct RelationDef {
     /// How [`on_condition`](Self::on_condition) is combined with the
     /// column equality predicate (`All` = AND, `Any` = OR).
     pub condition_type: ConditionType,
+    /// Conditions the rows of [`from_tbl`](Self::from_tbl) are restricted to when it is
+    /// joined, given the name or alias it is joined as, e.g. to leave out soft deleted rows
+    #[debug("{}", from_scope.is_some())]
+    pub from_scope: Option<fn(DynIden) -> Condition>,
+    /// Conditions the rows of [`to_tbl`](Self::to_tbl) are restricted to when it is
+    /// joined, see [`from_scope`](Self::from_scope)
+    #[debug("{}", to_scope.is_some())]
+    pub to_scope: Option<fn(DynIden) -> Condition>,
 }
 
 /// Idiomatically generate the join condition.
@@ -220,6 +229,8 @@ impl RelationDef {
             on_condition: self.on_condition,
             fk_name: None,
             condition_type: self.condition_type,
+            from_scope: self.to_scope,
+            to_scope: self.from_scope,
         }
     }
 
@@ -478,6 +489,8 @@ where
             on_condition: b.on_condition,
             fk_name: b.fk_name,
             condition_type: b.condition_type,
+            from_scope: join_scope_of::<E>(),
+            to_scope: join_scope_of::<R>(),
         }
     }
 }
@@ -541,6 +554,8 @@ impl From<RelationDef> for ForeignKeyCreateStatement {
 ///     fk_name: Some("foo-bar".to_string()),
 ///     skip_fk: false,
 ///     condition_type: ConditionType::All,
+///     from_scope: None,
+///     to_scope: None,
 /// };
 ///
 /// let mut alter_table = TableAlterStatement::new()
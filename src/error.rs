@@ -10,7 +10,7 @@ pub use sqlx::postgres::PgDatabaseError as SqlxPostgresError;
 #[cfg(feature = "sqlx-sqlite")]
 pub use sqlx::sqlite::SqliteError as SqlxSqliteError;
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use thiserror::Error;
 
 /// An error from unsuccessful database operations
@@ -75,6 +75,10 @@ pub enum DbErr {
     /// May be the table is empty or the record does not exist
     #[error("None of the records are updated")]
     RecordNotUpdated,
+    /// The row of a model with a version column was updated or deleted since the model
+    /// was loaded, so the update did not match it
+    #[error("The model is stale: its row was changed since it was loaded")]
+    StaleModel,
     /// This operation is not supported by the database backend
     #[error("Operation not supported by backend {db}: {ctx}")]
     BackendNotSupported {
@@ -111,6 +115,16 @@ pub enum DbErr {
     /// Mutex was poisoned by another thread
     #[error("Mutex poisoned")]
     MutexPoisonError,
+    /// The statement was cancelled after running longer than the timeout set on the query
+    #[error("Query timed out after {0:?}")]
+    QueryTimeout(Duration),
+    /// A [`ShardedConnection`](crate::ShardedConnection) could not pick the shard to run
+    /// the statement on
+    #[error("Cannot route statement to a shard: {0}")]
+    Unroutable(String),
+    /// The database did not grant the advisory lock on a key, although it was waited for
+    #[error("Failed to acquire advisory lock `{0}`")]
+    AdvisoryLockNotAcquired(String),
 }
 
 /// An error from trying to get a row from a Model
@@ -144,6 +158,10 @@ pub enum RuntimeErr {
     #[cfg(feature = "rusqlite")]
     #[error("{0}")]
     Rusqlite(Arc<crate::driver::rusqlite::RusqliteError>),
+    /// DuckDB Error
+    #[cfg(feature = "duckdb")]
+    #[error("{0}")]
+    Duckdb(Arc<crate::driver::duckdb::DuckdbError>),
     /// Error generated from within SeaORM
     #[error("{0}")]
     Internal(String),
@@ -203,33 +221,169 @@ where
 }
 
 /// A portable, backend-agnostic classification of the most common SQL
-/// constraint violations, produced by [`DbErr::sql_err`].
+/// failures, produced by [`DbErr::sql_err`].
 ///
-/// Only unique-key and foreign-key violations are recognized. For any other
-/// failure, or for backend-specific detail (SQLSTATE, driver error codes,
-/// check constraints, and so on), inspect the underlying driver error instead —
-/// see [`DbErr::sql_err`] for the pattern.
+/// Constraint violations, transaction conflicts, lock and statement timeouts and
+/// out-of-range values are recognized. For any other failure, or for
+/// backend-specific detail (SQLSTATE, driver error codes, and so on), inspect
+/// the underlying driver error instead — see [`DbErr::sql_err`] for the pattern.
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum SqlErr {
     /// Error for duplicate record in unique field or primary key field
     #[error("Unique Constraint Violated: {0}")]
-    UniqueConstraintViolation(String),
+    UniqueConstraintViolation(SqlErrDetail),
     /// Error for Foreign key constraint
     #[error("Foreign Key Constraint Violated: {0}")]
-    ForeignKeyConstraintViolation(String),
+    ForeignKeyConstraintViolation(SqlErrDetail),
+    /// A `NULL` was written to a `NOT NULL` column, or a required column
+    /// without a default was left out
+    #[error("Not Null Constraint Violated: {0}")]
+    NotNullViolation(SqlErrDetail),
+    /// A row failed a `CHECK` constraint
+    #[error("Check Constraint Violated: {0}")]
+    CheckConstraintViolation(SqlErrDetail),
+    /// The transaction could not be serialized with concurrent transactions and
+    /// was aborted; it can be retried
+    #[error("Serialization Failure: {0}")]
+    SerializationFailure(String),
+    /// The transaction was chosen as the victim of a deadlock and was aborted;
+    /// it can be retried
+    #[error("Deadlock Detected: {0}")]
+    Deadlock(String),
+    /// A lock could not be acquired in time, or at once with `NOWAIT`
+    #[error("Lock Timeout: {0}")]
+    LockTimeout(String),
+    /// The statement exceeded its time limit, or was cancelled or interrupted
+    #[error("Statement Timeout: {0}")]
+    StatementTimeout(String),
+    /// A value does not fit the column's type, e.g. a numeric overflow or a
+    /// string longer than the column allows
+    #[error("Value Out Of Range: {0}")]
+    ValueOutOfRange(SqlErrDetail),
+}
+
+/// The error message of a [`SqlErr`], along with the constraint, table and column
+/// names involved, where the driver reports them.
+///
+/// Postgres reports them as separate fields; for MySQL and SQLite they are taken
+/// from the error message, so only the names appearing in it are available.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SqlErrDetail {
+    /// The error message from the database
+    pub message: String,
+    /// Name of the violated constraint
+    pub constraint: Option<String>,
+    /// Name of the table
+    pub table: Option<String>,
+    /// Name of the column
+    pub column: Option<String>,
+}
+
+impl std::fmt::Display for SqlErrDetail {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+#[allow(dead_code)]
+impl SqlErrDetail {
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Detail for a MySQL message naming the column in the first quotes, such as
+    /// `Column 'name' cannot be null`
+    fn with_quoted_column(message: &str) -> Self {
+        Self {
+            column: first_quoted(message),
+            ..Self::new(message)
+        }
+    }
+
+    /// Detail for an SQLite or DuckDB message ending in `table.column`, such as
+    /// `NOT NULL constraint failed: cake.name`. A message listing several columns, such as
+    /// `UNIQUE constraint failed: cake.name, cake.bakery_id`, names their table only.
+    fn with_table_column(message: &str) -> Self {
+        let qualified = message.rsplit(": ").next().unwrap_or_default();
+        let mut columns = qualified.split(", ").filter_map(|c| c.rsplit_once('.'));
+        let (table, column) = match (columns.next(), columns.next()) {
+            (Some((table, column)), None) => (Some(table.to_owned()), Some(column.to_owned())),
+            (Some((table, _)), Some(_)) => (Some(table.to_owned()), None),
+            _ => (None, None),
+        };
+        Self {
+            table,
+            column,
+            ..Self::new(message)
+        }
+    }
+
+    /// Detail for a MySQL duplicate entry message, whose key is quoted last and, since
+    /// MySQL 8.0.19, qualified by its table, such as
+    /// `Duplicate entry '1' for key 'cake.PRIMARY'`
+    fn with_quoted_key(message: &str) -> Self {
+        let key = message
+            .strip_suffix('\'')
+            .and_then(|rest| rest.rsplit_once('\''))
+            .map(|(_, key)| key);
+        let (table, constraint) = match key.map(|key| key.rsplit_once('.')) {
+            Some(Some((table, key))) => (Some(table.to_owned()), Some(key.to_owned())),
+            Some(None) => (None, key.map(Into::into)),
+            None => (None, None),
+        };
+        Self {
+            constraint,
+            table,
+            ..Self::new(message)
+        }
+    }
+
+    /// Detail for a MySQL foreign key message, such as `Cannot add or update a child row:
+    /// a foreign key constraint fails (`db`.`fruit`, CONSTRAINT `fk-fruit-cake` FOREIGN
+    /// KEY (`cake_id`) REFERENCES `cake` (`id`))`
+    fn with_foreign_key(message: &str) -> Self {
+        let backquoted = |after: &str| {
+            let (_, rest) = message.split_once(after)?;
+            let (name, _) = rest.split_once('`')?;
+            Some(name.to_owned())
+        };
+        // the table is qualified by its database: (`db`.`fruit`,
+        let table = message
+            .split_once(" (`")
+            .and_then(|(_, rest)| rest.split_once("`,"))
+            .map(|(table, _)| table.rsplit('`').next().unwrap_or(table).to_owned());
+        Self {
+            constraint: backquoted("CONSTRAINT `"),
+            table,
+            column: backquoted("FOREIGN KEY (`"),
+            ..Self::new(message)
+        }
+    }
+}
+
+/// The text between the first pair of single quotes in `message`
+#[allow(dead_code)]
+fn first_quoted(message: &str) -> Option<String> {
+    let (_, rest) = message.split_once('\'')?;
+    let (quoted, _) = rest.split_once('\'')?;
+    Some(quoted.to_owned())
 }
 
 #[allow(dead_code)]
 impl DbErr {
-    /// Classify this error as a portable [`SqlErr`] — a unique-key or
-    /// foreign-key constraint violation — returning `None` if it is neither, or
-    /// if it did not originate from a database driver.
+    /// Classify this error as a portable [`SqlErr`] — a constraint violation, a
+    /// transaction conflict, a timeout or an out-of-range value — returning `None`
+    /// if it is none of them, or if it did not originate from a database driver.
     ///
-    /// Only the two most common constraint violations are recognized, across
-    /// MySQL, Postgres and SQLite. For anything else (SQLSTATE codes, check
-    /// constraints, other driver-specific detail) match on the underlying
-    /// `RuntimeErr::SqlxError` and inspect the driver error yourself:
+    /// Only these common failures are recognized, across MySQL, Postgres and
+    /// SQLite. For anything else (SQLSTATE codes, other driver-specific detail)
+    /// match on the underlying `RuntimeErr::SqlxError` and inspect the driver
+    /// error yourself:
     ///
     /// ```
     /// # #[cfg(feature = "sqlx-postgres")]
@@ -239,7 +393,7 @@ impl DbErr {
     ///
     /// if let Some(sql_err) = err.sql_err() {
     ///     // Portable across MySQL / Postgres / SQLite.
-    ///     eprintln!("constraint violation: {sql_err}");
+    ///     eprintln!("classified error: {sql_err}");
     /// } else if let DbErr::Query(RuntimeErr::SqlxError(e)) | DbErr::Exec(RuntimeErr::SqlxError(e)) =
     ///     &err
     ///     && let sea_orm::sqlx::Error::Database(db_err) = e.deref()
@@ -270,14 +424,15 @@ impl DbErr {
                     let error_number = e
                         .try_downcast_ref::<sqlx::mysql::MySqlDatabaseError>()?
                         .number();
-                    match error_number {
+                    let message = e.message();
+                    return match error_number {
                         // 1022 Can't write; duplicate key in table '%s'
                         // 1062 Duplicate entry '%s' for key %d
                         // 1169 Can't write, because of unique constraint, to table '%s'
                         // 1586 Duplicate entry '%s' for key '%s'
-                        1022 | 1062 | 1169 | 1586 => {
-                            return Some(SqlErr::UniqueConstraintViolation(e.message().into()));
-                        }
+                        1022 | 1062 | 1169 | 1586 => Some(SqlErr::UniqueConstraintViolation(
+                            SqlErrDetail::with_quoted_key(message),
+                        )),
                         // 1216 Cannot add or update a child row: a foreign key constraint fails
                         // 1217 Cannot delete or update a parent row: a foreign key constraint fails
                         // 1451 Cannot delete or update a parent row: a foreign key constraint fails (%s)
@@ -286,38 +441,65 @@ impl DbErr {
                         // 1761 Foreign key constraint for table '%s', record '%s' would lead to a duplicate entry in table '%s', key '%s'
                         // 1762 Foreign key constraint for table '%s', record '%s' would lead to a duplicate entry in a child table
                         1216 | 1217 | 1451 | 1452 | 1557 | 1761 | 1762 => {
-                            return Some(SqlErr::ForeignKeyConstraintViolation(e.message().into()));
+                            Some(SqlErr::ForeignKeyConstraintViolation(
+                                SqlErrDetail::with_foreign_key(message),
+                            ))
                         }
-                        _ => return None,
-                    }
+                        // 1048 Column '%s' cannot be null
+                        // 1364 Field '%s' doesn't have a default value
+                        1048 | 1364 => Some(SqlErr::NotNullViolation(
+                            SqlErrDetail::with_quoted_column(message),
+                        )),
+                        // 3819 Check constraint '%s' is violated.
+                        3819 => Some(SqlErr::CheckConstraintViolation(SqlErrDetail {
+                            constraint: first_quoted(message),
+                            ..SqlErrDetail::new(message)
+                        })),
+                        // 1213 Deadlock found when trying to get lock; try restarting transaction
+                        1213 => Some(SqlErr::Deadlock(message.into())),
+                        // 1205 Lock wait timeout exceeded; try restarting transaction
+                        // 3572 Statement aborted because lock(s) could not be acquired immediately and NOWAIT is set.
+                        1205 | 3572 => Some(SqlErr::LockTimeout(message.into())),
+                        // 1317 Query execution was interrupted
+                        // 3024 Query execution was interrupted, maximum statement execution time exceeded
+                        1317 | 3024 => Some(SqlErr::StatementTimeout(message.into())),
+                        // 1264 Out of range value for column '%s' at row %ld
+                        // 1265 Data truncated for column '%s' at row %ld
+                        // 1406 Data too long for column '%s' at row %ld
+                        1264 | 1265 | 1406 => Some(SqlErr::ValueOutOfRange(
+                            SqlErrDetail::with_quoted_column(message),
+                        )),
+                        _ => None,
+                    };
                 }
                 #[cfg(feature = "sqlx-postgres")]
-                if e.try_downcast_ref::<sqlx::postgres::PgDatabaseError>()
-                    .is_some()
-                {
-                    match _error_code_expanded {
-                        "23505" => {
-                            return Some(SqlErr::UniqueConstraintViolation(e.message().into()));
-                        }
-                        "23503" => {
-                            return Some(SqlErr::ForeignKeyConstraintViolation(e.message().into()));
-                        }
-                        _ => return None,
-                    }
+                if let Some(pg) = e.try_downcast_ref::<sqlx::postgres::PgDatabaseError>() {
+                    let detail = || SqlErrDetail {
+                        message: pg.message().to_owned(),
+                        constraint: pg.constraint().map(Into::into),
+                        table: pg.table().map(Into::into),
+                        column: pg.column().map(Into::into),
+                    };
+                    return match _error_code_expanded {
+                        "23505" => Some(SqlErr::UniqueConstraintViolation(detail())),
+                        "23503" => Some(SqlErr::ForeignKeyConstraintViolation(detail())),
+                        "23502" => Some(SqlErr::NotNullViolation(detail())),
+                        "23514" => Some(SqlErr::CheckConstraintViolation(detail())),
+                        "40001" => Some(SqlErr::SerializationFailure(e.message().into())),
+                        "40P01" => Some(SqlErr::Deadlock(e.message().into())),
+                        // lock_not_available: `lock_timeout` expired or `NOWAIT` was set
+                        "55P03" => Some(SqlErr::LockTimeout(e.message().into())),
+                        // query_canceled: `statement_timeout` expired or the query was cancelled
+                        "57014" => Some(SqlErr::StatementTimeout(e.message().into())),
+                        // numeric_value_out_of_range, string_data_right_truncation
+                        "22003" | "22001" => Some(SqlErr::ValueOutOfRange(detail())),
+                        _ => None,
+                    };
                 }
                 #[cfg(feature = "sqlx-sqlite")]
                 if e.try_downcast_ref::<sqlx::sqlite::SqliteError>().is_some() {
-                    match _error_code_expanded {
-                        // error code 1555 refers to the primary key's unique constraint violation
-                        // error code 2067 refers to the UNIQUE unique constraint violation
-                        "1555" | "2067" => {
-                            return Some(SqlErr::UniqueConstraintViolation(e.message().into()));
-                        }
-                        "787" => {
-                            return Some(SqlErr::ForeignKeyConstraintViolation(e.message().into()));
-                        }
-                        _ => return None,
-                    }
+                    let code = _error_code_expanded.parse().unwrap_or_default();
+                    return sqlite_sql_err(code, e.message());
                 }
             }
         }
@@ -329,21 +511,206 @@ impl DbErr {
             use std::ops::Deref;
 
             if let RusqliteError::SqliteFailure(err, msg) = err.deref() {
-                match err.extended_code {
-                    1555 | 2067 => {
-                        return Some(SqlErr::UniqueConstraintViolation(
-                            msg.to_owned().unwrap_or_else(|| err.to_string()),
-                        ));
-                    }
-                    787 => {
-                        return Some(SqlErr::ForeignKeyConstraintViolation(
-                            msg.to_owned().unwrap_or_else(|| err.to_string()),
-                        ));
-                    }
-                    _ => (),
+                let msg = msg.to_owned().unwrap_or_else(|| err.to_string());
+                return sqlite_sql_err(err.extended_code, &msg);
+            }
+        }
+        #[cfg(feature = "duckdb")]
+        if let DbErr::Exec(RuntimeErr::Duckdb(err)) | DbErr::Query(RuntimeErr::Duckdb(err)) = self {
+            // DuckDB only reports a generic error type through its C API, so the
+            // kind of failure has to be told apart by the message
+            let msg = err.to_string();
+            if msg.starts_with("Constraint Error:") {
+                if msg.contains("Duplicate key") {
+                    return Some(SqlErr::UniqueConstraintViolation(SqlErrDetail::new(msg)));
+                }
+                if msg.contains("foreign key") {
+                    return Some(SqlErr::ForeignKeyConstraintViolation(SqlErrDetail::new(
+                        msg,
+                    )));
+                }
+                if msg.contains("NOT NULL constraint failed") {
+                    return Some(SqlErr::NotNullViolation(SqlErrDetail::with_table_column(
+                        &msg,
+                    )));
                 }
+                if msg.contains("CHECK constraint failed") {
+                    return Some(SqlErr::CheckConstraintViolation(SqlErrDetail::new(msg)));
+                }
+            }
+            if msg.starts_with("TransactionContext Error:")
+                && msg.to_ascii_lowercase().contains("conflict")
+            {
+                return Some(SqlErr::SerializationFailure(msg));
+            }
+            if msg.starts_with("INTERRUPT Error:") {
+                return Some(SqlErr::StatementTimeout(msg));
+            }
+            if msg.starts_with("Out of Range Error:") {
+                return Some(SqlErr::ValueOutOfRange(SqlErrDetail::new(msg)));
             }
         }
         None
     }
 }
+
+/// Classify an SQLite error by its extended result code; shared by the SQLx and
+/// rusqlite drivers.
+#[cfg(any(feature = "sqlx-sqlite", feature = "rusqlite"))]
+fn sqlite_sql_err(extended_code: i32, msg: &str) -> Option<SqlErr> {
+    match extended_code {
+        // SQLITE_CONSTRAINT_PRIMARYKEY, SQLITE_CONSTRAINT_UNIQUE:
+        // "UNIQUE constraint failed: table.column"
+        1555 | 2067 => Some(SqlErr::UniqueConstraintViolation(
+            SqlErrDetail::with_table_column(msg),
+        )),
+        // SQLITE_CONSTRAINT_FOREIGNKEY: "FOREIGN KEY constraint failed", naming nothing
+        787 => Some(SqlErr::ForeignKeyConstraintViolation(SqlErrDetail::new(
+            msg,
+        ))),
+        // SQLITE_CONSTRAINT_NOTNULL: "NOT NULL constraint failed: table.column"
+        1299 => Some(SqlErr::NotNullViolation(SqlErrDetail::with_table_column(
+            msg,
+        ))),
+        // SQLITE_CONSTRAINT_CHECK: "CHECK constraint failed: name"
+        275 => Some(SqlErr::CheckConstraintViolation(SqlErrDetail {
+            constraint: msg.rsplit(": ").next().map(Into::into),
+            ..SqlErrDetail::new(msg)
+        })),
+        // SQLITE_BUSY_SNAPSHOT: a read transaction in WAL mode can no longer
+        // be upgraded to a write transaction
+        517 => Some(SqlErr::SerializationFailure(msg.into())),
+        // SQLITE_BUSY, SQLITE_LOCKED: the busy timeout expired
+        5 | 6 => Some(SqlErr::LockTimeout(msg.into())),
+        // SQLITE_INTERRUPT
+        9 => Some(SqlErr::StatementTimeout(msg.into())),
+        // SQLITE_TOOBIG
+        18 => Some(SqlErr::ValueOutOfRange(SqlErrDetail::new(msg))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_quoted() {
+        assert_eq!(
+            first_quoted("Column 'name' cannot be null"),
+            Some("name".to_owned())
+        );
+        assert_eq!(
+            first_quoted("Out of range value for column 'a' at row 1").as_deref(),
+            Some("a")
+        );
+        assert_eq!(first_quoted("no quotes"), None);
+        assert_eq!(first_quoted("unterminated 'quote"), None);
+    }
+
+    #[test]
+    fn test_with_table_column() {
+        let detail = SqlErrDetail::with_table_column("NOT NULL constraint failed: cake.name");
+        assert_eq!(detail.message, "NOT NULL constraint failed: cake.name");
+        assert_eq!(detail.table.as_deref(), Some("cake"));
+        assert_eq!(detail.column.as_deref(), Some("name"));
+
+        let detail =
+            SqlErrDetail::with_table_column("UNIQUE constraint failed: cake.name, cake.bakery_id");
+        assert_eq!(detail.table.as_deref(), Some("cake"));
+        assert_eq!(detail.column, None);
+
+        let detail = SqlErrDetail::with_table_column("FOREIGN KEY constraint failed");
+        assert_eq!(detail.table, None);
+        assert_eq!(detail.column, None);
+    }
+
+    #[test]
+    fn test_with_quoted_key() {
+        let detail = SqlErrDetail::with_quoted_key("Duplicate entry '1' for key 'cake.PRIMARY'");
+        assert_eq!(detail.table.as_deref(), Some("cake"));
+        assert_eq!(detail.constraint.as_deref(), Some("PRIMARY"));
+
+        let detail = SqlErrDetail::with_quoted_key("Duplicate entry 'a' for key 'name'");
+        assert_eq!(detail.table, None);
+        assert_eq!(detail.constraint.as_deref(), Some("name"));
+
+        let detail = SqlErrDetail::with_quoted_key("Can't write; duplicate key in table");
+        assert_eq!(detail.table, None);
+        assert_eq!(detail.constraint, None);
+    }
+
+    #[test]
+    fn test_with_foreign_key() {
+        let detail = SqlErrDetail::with_foreign_key(
+            "Cannot add or update a child row: a foreign key constraint fails \
+             (`db`.`fruit`, CONSTRAINT `fk-fruit-cake` FOREIGN KEY (`cake_id`) \
+             REFERENCES `cake` (`id`))",
+        );
+        assert_eq!(detail.constraint.as_deref(), Some("fk-fruit-cake"));
+        assert_eq!(detail.table.as_deref(), Some("fruit"));
+        assert_eq!(detail.column.as_deref(), Some("cake_id"));
+
+        let detail = SqlErrDetail::with_foreign_key("Cannot delete or update a parent row");
+        assert_eq!(detail.constraint, None);
+        assert_eq!(detail.table, None);
+        assert_eq!(detail.column, None);
+    }
+
+    #[test]
+    #[cfg(any(feature = "sqlx-sqlite", feature = "rusqlite"))]
+    fn test_sqlite_sql_err() {
+        let Some(SqlErr::UniqueConstraintViolation(detail)) =
+            sqlite_sql_err(2067, "UNIQUE constraint failed: cake.name")
+        else {
+            panic!("expected a unique constraint violation");
+        };
+        assert_eq!(detail.table.as_deref(), Some("cake"));
+        assert_eq!(detail.column.as_deref(), Some("name"));
+
+        assert!(matches!(
+            sqlite_sql_err(1555, "UNIQUE constraint failed: cake.id"),
+            Some(SqlErr::UniqueConstraintViolation(_))
+        ));
+        assert!(matches!(
+            sqlite_sql_err(787, "FOREIGN KEY constraint failed"),
+            Some(SqlErr::ForeignKeyConstraintViolation(detail)) if detail.table.is_none()
+        ));
+
+        let Some(SqlErr::NotNullViolation(detail)) =
+            sqlite_sql_err(1299, "NOT NULL constraint failed: cake.name")
+        else {
+            panic!("expected a not null violation");
+        };
+        assert_eq!(detail.column.as_deref(), Some("name"));
+
+        let Some(SqlErr::CheckConstraintViolation(detail)) =
+            sqlite_sql_err(275, "CHECK constraint failed: price_positive")
+        else {
+            panic!("expected a check constraint violation");
+        };
+        assert_eq!(detail.constraint.as_deref(), Some("price_positive"));
+
+        assert!(matches!(
+            sqlite_sql_err(517, "database is locked"),
+            Some(SqlErr::SerializationFailure(_))
+        ));
+        assert!(matches!(
+            sqlite_sql_err(5, "database is locked"),
+            Some(SqlErr::LockTimeout(_))
+        ));
+        assert!(matches!(
+            sqlite_sql_err(6, "database table is locked"),
+            Some(SqlErr::LockTimeout(_))
+        ));
+        assert!(matches!(
+            sqlite_sql_err(9, "interrupted"),
+            Some(SqlErr::StatementTimeout(_))
+        ));
+        assert!(matches!(
+            sqlite_sql_err(18, "string or blob too big"),
+            Some(SqlErr::ValueOutOfRange(_))
+        ));
+        assert_eq!(sqlite_sql_err(1, "SQL logic error"), None);
+    }
+}
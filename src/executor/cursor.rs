@@ -1,7 +1,7 @@
 use crate::{
     ConnectionTrait, DbErr, EntityTrait, FromQueryResult, Identity, IdentityOf, IntoIdentity,
-    PartialModelTrait, PrimaryKeyToColumn, QuerySelect, Select, SelectModel, SelectThree,
-    SelectThreeModel, SelectTwo, SelectTwoModel, SelectorTrait, Topology,
+    PartialModelTrait, PrimaryKeyToColumn, QuerySelect, QueryTrait, Select, SelectModel,
+    SelectThree, SelectThreeModel, SelectTwo, SelectTwoModel, SelectorTrait, Topology,
 };
 use sea_query::{
     Condition, DynIden, Expr, ExprTrait, IntoValueTuple, Order, SeaRc, SelectStatement, SimpleExpr,
@@ -389,7 +389,7 @@ where
     where
         C: IntoIdentity,
     {
-        Cursor::new(self.query, SeaRc::new(E::default()), order_columns)
+        Cursor::new(self.into_query(), SeaRc::new(E::default()), order_columns)
     }
 }
 
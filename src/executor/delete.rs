@@ -1,9 +1,10 @@
-use super::{ReturningSelector, SelectModel};
+use super::{ReturningSelector, SelectModel, Updater};
+use crate::database::timeout::TimeoutConnection;
 use crate::{
     ColumnTrait, ConnectionTrait, DeleteMany, DeleteOne, EntityTrait, Iterable, ValidatedDeleteOne,
     error::*,
 };
-use sea_query::{DeleteStatement, Query};
+use sea_query::{DeleteStatement, Query, UpdateStatement};
 
 /// Lower-level executor that runs a raw `sea_query` [`DeleteStatement`] and
 /// collects the result. Most code shouldn't need it directly — prefer
@@ -33,7 +34,10 @@ where
     where
         C: ConnectionTrait,
     {
-        exec_delete_only(self.query, db).await
+        match self.soft_delete_query() {
+            Some(soft_delete) => exec_soft_delete(soft_delete, db).await,
+            None => exec_delete_only(self.query, db).await,
+        }
     }
 
     /// Execute an delete operation and return the deleted model
@@ -41,7 +45,14 @@ where
     where
         C: ConnectionTrait,
     {
-        exec_delete_with_returning_one::<E, _>(self.query, db).await
+        match self.soft_delete_query() {
+            Some(soft_delete) => Ok(Updater::new(soft_delete)
+                .exec_update_with_returning::<E, _>(db)
+                .await?
+                .into_iter()
+                .next()),
+            None => exec_delete_with_returning_one::<E, _>(self.query, db).await,
+        }
     }
 }
 
@@ -75,7 +86,11 @@ where
     where
         C: ConnectionTrait,
     {
-        exec_delete_only(self.query, db).await
+        let db = &TimeoutConnection::new(db, self.timeout);
+        match self.soft_delete_query() {
+            Some(soft_delete) => exec_soft_delete(soft_delete, db).await,
+            None => exec_delete_only(self.query, db).await,
+        }
     }
 
     /// Execute an delete operation and return the deleted model
@@ -84,7 +99,15 @@ where
         E: EntityTrait,
         C: ConnectionTrait,
     {
-        exec_delete_with_returning_many::<E, _>(self.query, db).await
+        let db = &TimeoutConnection::new(db, self.timeout);
+        match self.soft_delete_query() {
+            Some(soft_delete) => {
+                Updater::new(soft_delete)
+                    .exec_update_with_returning::<E, _>(db)
+                    .await
+            }
+            None => exec_delete_with_returning_many::<E, _>(self.query, db).await,
+        }
     }
 }
 
@@ -131,6 +154,16 @@ where
     Deleter::new(query).exec(db).await
 }
 
+async fn exec_soft_delete<C>(query: UpdateStatement, db: &C) -> Result<DeleteResult, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let result = Updater::new(query).exec(db).await?;
+    Ok(DeleteResult {
+        rows_affected: result.rows_affected,
+    })
+}
+
 async fn exec_delete<C>(query: DeleteStatement, db: &C) -> Result<DeleteResult, DbErr>
 where
     C: ConnectionTrait,
@@ -219,4 +252,77 @@ mod tests {
             Err(DbErr::PrimaryKeyNotSet { .. })
         ));
     }
+
+    #[cfg(feature = "with-chrono")]
+    #[smol_potat::test]
+    async fn soft_delete() -> Result<(), crate::DbErr> {
+        use crate::tests_cfg::cake_review;
+        use crate::{
+            ActiveModelTrait, ActiveValue::Set, ColumnTrait, DbBackend, EntityTrait, MockDatabase,
+            MockExecResult, QueryFilter, Transaction,
+        };
+        use pretty_assertions::assert_eq;
+
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_exec_results([
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 2,
+                },
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+            ])
+            .into_connection();
+
+        let cake_review = cake_review::ActiveModel {
+            id: Set(1),
+            ..Default::default()
+        };
+        assert_eq!(cake_review.delete(&db).await?.rows_affected, 1);
+        assert_eq!(
+            cake_review::Entity::delete_many()
+                .filter(cake_review::Column::CakeId.eq(2))
+                .exec(&db)
+                .await?
+                .rows_affected,
+            2
+        );
+        assert_eq!(
+            cake_review::Entity::delete_by_id(3)
+                .force_delete()
+                .exec(&db)
+                .await?
+                .rows_affected,
+            1
+        );
+
+        assert_eq!(
+            db.into_transaction_log(),
+            [
+                Transaction::from_sql_and_values(
+                    DbBackend::Postgres,
+                    r#"UPDATE "cake_review" SET "deleted_at" = CURRENT_TIMESTAMP WHERE "cake_review"."deleted_at" IS NULL AND "cake_review"."id" = $1"#,
+                    [1i32.into()]
+                ),
+                Transaction::from_sql_and_values(
+                    DbBackend::Postgres,
+                    r#"UPDATE "cake_review" SET "deleted_at" = CURRENT_TIMESTAMP WHERE "cake_review"."deleted_at" IS NULL AND "cake_review"."cake_id" = $1"#,
+                    [2i32.into()]
+                ),
+                Transaction::from_sql_and_values(
+                    DbBackend::Postgres,
+                    r#"DELETE FROM "cake_review" WHERE "cake_review"."id" = $1"#,
+                    [3i32.into()]
+                ),
+            ]
+        );
+
+        Ok(())
+    }
 }
@@ -24,12 +24,18 @@ pub(crate) enum ExecResultHolder {
     /// Holds the result of executing an operation on a SQLite database
     #[cfg(feature = "rusqlite")]
     Rusqlite(crate::driver::rusqlite::RusqliteExecResult),
+    /// Holds the result of executing an operation on DuckDB
+    #[cfg(feature = "duckdb")]
+    Duckdb(crate::driver::duckdb::DuckdbExecResult),
     /// Holds the result of executing an operation on the Mock database
     #[cfg(feature = "mock")]
     Mock(crate::MockExecResult),
     /// Holds the result of executing an operation on the Proxy database
     #[cfg(feature = "proxy")]
     Proxy(crate::ProxyExecResult),
+    /// Holds the merged result of a statement run on every shard of a
+    /// [`ShardedConnection`](crate::ShardedConnection)
+    FannedOut { rows_affected: u64 },
 }
 
 // ExecResult //
@@ -68,10 +74,15 @@ impl ExecResult {
                     last_insert_rowid as u64
                 }
             }
+            // DuckDB is driven through the Postgres dialect, which relies on `RETURNING`
+            #[cfg(feature = "duckdb")]
+            ExecResultHolder::Duckdb(_) => 0,
             #[cfg(feature = "mock")]
             ExecResultHolder::Mock(result) => result.last_insert_id,
             #[cfg(feature = "proxy")]
             ExecResultHolder::Proxy(result) => result.last_insert_id,
+            // an INSERT is never fanned out
+            ExecResultHolder::FannedOut { .. } => 0,
             #[allow(unreachable_patterns)]
             _ => unreachable!(),
         }
@@ -88,10 +99,13 @@ impl ExecResult {
             ExecResultHolder::SqlxSqlite(result) => result.rows_affected(),
             #[cfg(feature = "rusqlite")]
             ExecResultHolder::Rusqlite(result) => result.rows_affected,
+            #[cfg(feature = "duckdb")]
+            ExecResultHolder::Duckdb(result) => result.rows_affected,
             #[cfg(feature = "mock")]
             ExecResultHolder::Mock(result) => result.rows_affected,
             #[cfg(feature = "proxy")]
             ExecResultHolder::Proxy(result) => result.rows_affected,
+            ExecResultHolder::FannedOut { rows_affected } => *rows_affected,
             #[allow(unreachable_patterns)]
             _ => unreachable!(),
         }
@@ -12,17 +12,25 @@ where
     G: EntityTrait,
     TOP: Topology,
 {
-    pub(crate) fn new(query: SelectStatement) -> Self {
-        Self::new_without_prepare(query).prepare_select()
+    pub(crate) fn new(query: SelectStatement, timeout: Option<Duration>) -> Self {
+        Self::new_without_prepare(query, timeout).prepare_select()
     }
 
-    pub(crate) fn new_without_prepare(query: SelectStatement) -> Self {
+    pub(crate) fn new_without_prepare(query: SelectStatement, timeout: Option<Duration>) -> Self {
         Self {
             query,
             entity: PhantomData,
+            timeout,
         }
     }
 
+    /// Cancel the query once it has run for longer than `timeout`, see
+    /// [`Select::timeout`](crate::Select::timeout)
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
     fn prepare_select(mut self) -> Self {
         prepare_select_col::<G, _, _>(&mut self, SelectC);
         self
@@ -34,9 +42,11 @@ where
         H: EntityTrait,
         T: EntityTrait + Related<H>,
     {
+        let timeout = self.timeout;
         SelectFour::new(
             self.join_join(JoinType::LeftJoin, T::to(), T::via())
                 .into_query(),
+            timeout,
         )
     }
 }
@@ -116,6 +126,7 @@ where
         Selector {
             query: self.query,
             selector: PhantomData,
+            timeout: self.timeout,
         }
     }
 
@@ -139,6 +150,7 @@ where
         Selector {
             query: self.query,
             selector: PhantomData,
+            timeout: self.timeout,
         }
     }
 
@@ -221,6 +233,7 @@ where
         SelectThreeMany {
             query: self.query,
             entity: self.entity,
+            timeout: self.timeout,
         }
     }
 }
@@ -242,6 +255,7 @@ where
         Selector {
             query: self.query,
             selector: PhantomData,
+            timeout: self.timeout,
         }
     }
 }
@@ -1,6 +1,6 @@
 use crate::{
-    ConnectionTrait, DbErr, EntityTrait, Select, SelectFive, SelectFour, SelectSix, SelectThree,
-    SelectTwo, Selector, SelectorRaw, SelectorTrait, Topology,
+    ConnectionTrait, DbErr, EntityTrait, QueryTrait, Select, SelectFive, SelectFour, SelectSix,
+    SelectThree, SelectTwo, Selector, SelectorRaw, SelectorTrait, Topology,
 };
 use sea_query::{Expr, SelectStatement};
 
@@ -66,7 +66,7 @@ where
     E: EntityTrait,
 {
     fn exists_query(self) -> SelectStatement {
-        into_exists_query(self.query)
+        into_exists_query(self.into_query())
     }
 }
 
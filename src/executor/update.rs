@@ -1,7 +1,8 @@
 use super::ReturningSelector;
+use crate::database::timeout::TimeoutConnection;
 use crate::{
     ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel, Iterable,
-    PrimaryKeyTrait, SelectModel, UpdateMany, UpdateOne, ValidatedUpdateOne, error::*,
+    PrimaryKeyTrait, QueryTrait, SelectModel, UpdateMany, UpdateOne, ValidatedUpdateOne, error::*,
 };
 use sea_query::{FromValueTuple, Query, UpdateStatement};
 
@@ -31,16 +32,19 @@ where
 {
     /// Execute an UPDATE operation without a RETURNING clause, yielding an
     /// [`UpdateResult`] instead of the updated model. Returns
-    /// [`DbErr::RecordNotUpdated`] if no row matches.
+    /// [`DbErr::RecordNotUpdated`] if no row matches, or [`DbErr::StaleModel`]
+    /// if the entity has a version column.
     pub async fn exec_without_returning<C>(self, db: &C) -> Result<UpdateResult, DbErr>
     where
         C: ConnectionTrait,
     {
+        let versioned = self.is_versioned();
         Updater::new(self.query)
             // If nothing is updated, return RecordNotUpdated error
             .check_record_exists()
             .exec(db)
             .await
+            .map_err(|err| stale_model_err(err, versioned))
     }
 
     /// Execute an UPDATE operation on an ActiveModel
@@ -49,9 +53,24 @@ where
         <A::Entity as EntityTrait>::Model: IntoActiveModel<A>,
         C: ConnectionTrait,
     {
+        let versioned = self.is_versioned();
         Updater::new(self.query)
             .exec_update_and_return_updated(self.model, db)
             .await
+            .map_err(|err| stale_model_err(err, versioned))
+    }
+
+    /// Whether the update only matches the row at the version the model was loaded with
+    fn is_versioned(&self) -> bool {
+        <A::Entity as EntityTrait>::version_column().is_some_and(|col| !self.model.is_not_set(col))
+    }
+}
+
+/// A versioned update matching no row means the row has changed since it was loaded
+fn stale_model_err(err: DbErr, versioned: bool) -> DbErr {
+    match err {
+        DbErr::RecordNotUpdated if versioned => DbErr::StaleModel,
+        err => err,
     }
 }
 
@@ -88,7 +107,8 @@ where
     where
         C: ConnectionTrait,
     {
-        Updater::new(self.query).exec(db).await
+        let db = &TimeoutConnection::new(db, self.timeout);
+        Updater::new(self.into_query()).exec(db).await
     }
 
     /// Execute an update operation and return the updated model (use `RETURNING` syntax if supported)
@@ -96,7 +116,8 @@ where
     where
         C: ConnectionTrait,
     {
-        Updater::new(self.query)
+        let db = &TimeoutConnection::new(db, self.timeout);
+        Updater::new(self.into_query())
             .exec_update_with_returning::<E, _>(db)
             .await
     }
@@ -104,7 +125,7 @@ where
 
 impl Updater {
     /// Instantiate an update using an [UpdateStatement]
-    fn new(query: UpdateStatement) -> Self {
+    pub(crate) fn new(query: UpdateStatement) -> Self {
         Self {
             query,
             check_record_exists: false,
@@ -176,7 +197,10 @@ impl Updater {
         }
     }
 
-    async fn exec_update_with_returning<E, C>(mut self, db: &C) -> Result<Vec<E::Model>, DbErr>
+    pub(crate) async fn exec_update_with_returning<E, C>(
+        mut self,
+        db: &C,
+    ) -> Result<Vec<E::Model>, DbErr>
     where
         E: EntityTrait,
         C: ConnectionTrait,
@@ -424,4 +448,50 @@ mod tests {
             Err(DbErr::PrimaryKeyNotSet { .. })
         ));
     }
+
+    #[smol_potat::test]
+    async fn update_versioned() -> Result<(), DbErr> {
+        use crate::{ActiveModelTrait, tests_cfg::document};
+
+        let updated_document = document::Model {
+            id: 1,
+            title: "Final".to_owned(),
+            version: 4,
+        };
+
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([vec![updated_document.clone()], vec![]])
+            .into_connection();
+
+        let document = document::ActiveModel {
+            title: Set("Final".to_owned()),
+            ..document::Model {
+                id: 1,
+                title: "Draft".to_owned(),
+                version: 3,
+            }
+            .into_active_model()
+        };
+
+        assert_eq!(document.clone().update(&db).await?, updated_document);
+        assert_eq!(document.update(&db).await, Err(DbErr::StaleModel));
+
+        assert_eq!(
+            db.into_transaction_log(),
+            [
+                Transaction::from_sql_and_values(
+                    DbBackend::Postgres,
+                    r#"UPDATE "document" SET "title" = $1, "version" = "version" + $2 WHERE "document"."id" = $3 AND "document"."version" = $4 RETURNING "id", "title", "version""#,
+                    ["Final".into(), 1i32.into(), 1i32.into(), 3i32.into()]
+                ),
+                Transaction::from_sql_and_values(
+                    DbBackend::Postgres,
+                    r#"UPDATE "document" SET "title" = $1, "version" = "version" + $2 WHERE "document"."id" = $3 AND "document"."version" = $4 RETURNING "id", "title", "version""#,
+                    ["Final".into(), 1i32.into(), 1i32.into(), 3i32.into()]
+                ),
+            ]
+        );
+
+        Ok(())
+    }
 }
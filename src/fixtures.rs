@@ -0,0 +1,491 @@
+//! Test fixtures (`fixtures` feature): rows for several entities, read from YAML or JSON
+//! files and inserted in foreign key order.
+//!
+//! Rows are grouped by table. A file named after a table (`cake.yaml`) holds the rows
+//! of that table; any other file maps table names to rows, so one file can hold the
+//! whole data set. The rows of a table are a list, or a map from a symbol to the row,
+//! so that other rows can refer to it as `$symbol`:
+//!
+//! ```yaml
+//! cake:
+//!   chocolate:
+//!     name: Chocolate Forest
+//! fruit:
+//!   - name: Cherry
+//!     cake: $chocolate
+//!   - name: Blueberry
+//!     cake_id: $chocolate
+//! ```
+//!
+//! A reference in a column takes the primary key of the row referred to. A reference
+//! under the name of a table the entity `belongs_to` sets the foreign key columns of
+//! that relation. A string that should start with a `$` is written with `$$`.
+//!
+//! Each row becomes an ActiveModel through [`ActiveModelTrait::from_json`], so columns
+//! left out are `NotSet`, and is inserted with [`ActiveModelTrait::insert`]. Tables are
+//! filled parents first, following the `belongs_to` relations of the entities.
+//!
+//! The entities are registered up front with [`Fixtures::register`], since building
+//! their ActiveModels needs their types. There is no `fixtures::load(db, path)` over
+//! the `entity-registry` inventory: its entries only carry the schema of an entity,
+//! and the feature is optional, so the loader would not work without it.
+//! Fixtures are loaded through a [`DatabaseConnection`](crate::DatabaseConnection),
+//! a [`DatabaseTransaction`] or a [`DatabaseExecutor`](crate::DatabaseExecutor):
+//!
+//! ```
+//! # use sea_orm::{DbConn, DbErr, fixtures::Fixtures, tests_cfg::*};
+//! # async fn function(db: &DbConn) -> Result<(), DbErr> {
+//! let loaded = Fixtures::new()
+//!     .register(fruit::Entity)
+//!     .register(cake::Entity)
+//!     .load(db, "tests/fixtures")
+//!     .await?;
+//!
+//! let chocolate: &cake::Model = loaded.get::<cake::Entity>("chocolate").unwrap();
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    ActiveModelBehavior, ActiveModelTrait, ColumnTrait, DatabaseTransaction, DbErr, EntityTrait,
+    IdenStatic, IntoActiveModel, Iterable, ModelTrait, PrimaryKeyToColumn, RelationTrait,
+    TransactionTrait, TryIntoModel, schema::topology::TopologicalSort,
+};
+use sea_query::{TableName, TableRef};
+use serde_json::{Map, Value as JsonValue};
+use std::{
+    any::Any,
+    collections::{BTreeMap, HashMap},
+    fmt,
+    marker::PhantomData,
+    path::Path,
+};
+
+/// The entities fixtures can be loaded into
+#[derive(Default)]
+pub struct Fixtures {
+    entities: Vec<FixtureEntity>,
+}
+
+/// The rows inserted by [`Fixtures::load`], by their symbols
+#[derive(Default)]
+pub struct LoadedFixtures {
+    rows: HashMap<String, LoadedRow>,
+}
+
+struct FixtureEntity {
+    table: String,
+    /// Column names with their JSON keys
+    columns: Vec<(String, String)>,
+    primary_key: Vec<String>,
+    parents: Vec<Parent>,
+    inserter: Box<dyn Inserter>,
+}
+
+/// A `belongs_to` relation
+struct Parent {
+    table: String,
+    from_col: Vec<String>,
+    to_col: Vec<String>,
+}
+
+struct LoadedRow {
+    table: String,
+    primary_key: Vec<String>,
+    model: Box<dyn Any + Send>,
+    /// The values of the inserted row as JSON, by column name
+    values: Map<String, JsonValue>,
+}
+
+#[async_trait::async_trait]
+trait Inserter: Send + Sync {
+    async fn insert(
+        &self,
+        txn: &DatabaseTransaction,
+        row: JsonValue,
+    ) -> Result<(Box<dyn Any + Send>, Map<String, JsonValue>), DbErr>;
+}
+
+struct EntityInserter<E>(PhantomData<fn() -> E>);
+
+#[async_trait::async_trait]
+impl<E> Inserter for EntityInserter<E>
+where
+    E: EntityTrait,
+    E::ActiveModel: ActiveModelBehavior + TryIntoModel<E::Model> + Send,
+    E::Model: IntoActiveModel<E::ActiveModel>
+        + serde::Serialize
+        + for<'de> serde::Deserialize<'de>
+        + 'static,
+{
+    async fn insert(
+        &self,
+        txn: &DatabaseTransaction,
+        row: JsonValue,
+    ) -> Result<(Box<dyn Any + Send>, Map<String, JsonValue>), DbErr> {
+        let model = E::ActiveModel::from_json(row)?.insert(txn).await?;
+        let values = E::Column::iter()
+            .map(|col| {
+                let value = sea_query::sea_value_to_json_value(&model.get(col));
+                (col.as_str().to_owned(), value)
+            })
+            .collect();
+        Ok((Box::new(model), values))
+    }
+}
+
+impl fmt::Debug for Fixtures {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Fixtures")
+            .field(
+                "entities",
+                &self.entities.iter().map(|e| &e.table).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl fmt::Debug for LoadedFixtures {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.rows.iter().map(|(symbol, row)| (symbol, &row.values)))
+            .finish()
+    }
+}
+
+impl Fixtures {
+    /// Create an empty set of entities
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an entity, so that rows can be loaded into its table
+    pub fn register<E>(mut self, entity: E) -> Self
+    where
+        E: EntityTrait,
+        E::ActiveModel: ActiveModelBehavior + TryIntoModel<E::Model> + Send,
+        E::Model: IntoActiveModel<E::ActiveModel>
+            + serde::Serialize
+            + for<'de> serde::Deserialize<'de>
+            + 'static,
+    {
+        let parents = E::Relation::iter()
+            .map(|rel| rel.def())
+            .filter(|rel| !rel.is_owner)
+            .filter_map(|rel| {
+                Some(Parent {
+                    table: table_name(&rel.to_tbl)?,
+                    from_col: rel.from_col.iter().map(|c| c.to_string()).collect(),
+                    to_col: rel.to_col.iter().map(|c| c.to_string()).collect(),
+                })
+            })
+            .collect();
+        self.entities.push(FixtureEntity {
+            table: entity.table_name().to_owned(),
+            columns: E::Column::iter()
+                .map(|col| (col.as_str().to_owned(), col.json_key().to_owned()))
+                .collect(),
+            primary_key: E::PrimaryKey::iter()
+                .map(|pk| pk.into_column().as_str().to_owned())
+                .collect(),
+            parents,
+            inserter: Box::new(EntityInserter::<E>(PhantomData)),
+        });
+        self
+    }
+
+    /// Load the fixtures at `path`, a YAML or JSON file or a directory of them, in one
+    /// transaction. A row can only refer to rows of tables filled before its own, or to
+    /// rows before it in a list.
+    pub async fn load<C, P>(&self, db: &C, path: P) -> Result<LoadedFixtures, DbErr>
+    where
+        C: TransactionTrait<Transaction = DatabaseTransaction>,
+        P: AsRef<Path>,
+    {
+        let mut tables = BTreeMap::<String, Vec<(Option<String>, JsonValue)>>::new();
+        for (name, content) in read_files(path.as_ref())? {
+            let content = match self.entities.iter().any(|e| e.table == name) {
+                true => JsonValue::Object(Map::from_iter([(name, content)])),
+                false => content,
+            };
+            let JsonValue::Object(content) = content else {
+                return Err(fixture_err("expected a map from table names to rows"));
+            };
+            for (table, rows) in content {
+                if !self.entities.iter().any(|e| e.table == table) {
+                    return Err(fixture_err(format!(
+                        "no entity registered for table `{table}`"
+                    )));
+                }
+                let rows: Vec<(Option<String>, JsonValue)> = match rows {
+                    JsonValue::Array(rows) => rows.into_iter().map(|row| (None, row)).collect(),
+                    JsonValue::Object(rows) => rows
+                        .into_iter()
+                        .map(|(symbol, row)| (Some(symbol), row))
+                        .collect(),
+                    JsonValue::Null => Vec::new(),
+                    _ => return Err(fixture_err(format!("expected rows for table `{table}`"))),
+                };
+                tables.entry(table).or_default().extend(rows);
+            }
+        }
+
+        let txn = db.begin().await?;
+        let mut loaded = LoadedFixtures::default();
+        for entity in self.sorted() {
+            for (symbol, row) in tables.remove(&entity.table).unwrap_or_default() {
+                if let Some(symbol) = &symbol
+                    && loaded.rows.contains_key(symbol)
+                {
+                    return Err(fixture_err(format!("`${symbol}` is defined twice")));
+                }
+                let row = loaded.resolve(entity, row)?;
+                let (model, values) = entity.inserter.insert(&txn, row).await?;
+                if let Some(symbol) = symbol {
+                    loaded.rows.insert(
+                        symbol,
+                        LoadedRow {
+                            table: entity.table.clone(),
+                            primary_key: entity.primary_key.clone(),
+                            model,
+                            values,
+                        },
+                    );
+                }
+            }
+        }
+        txn.commit().await?;
+        Ok(loaded)
+    }
+
+    /// The entities, parents before their children
+    fn sorted(&self) -> Vec<&FixtureEntity> {
+        let mut sorter = TopologicalSort::<&str>::new();
+        for entity in &self.entities {
+            sorter.insert(entity.table.as_str());
+        }
+        for entity in &self.entities {
+            for parent in &entity.parents {
+                // a table referring to itself is filled in row order
+                if parent.table != entity.table
+                    && self.entities.iter().any(|e| e.table == parent.table)
+                {
+                    sorter.add_dependency(parent.table.as_str(), entity.table.as_str());
+                }
+            }
+        }
+        let position = |table: &str| self.entities.iter().position(|e| e.table == table);
+        let mut sorted = Vec::new();
+        loop {
+            let mut tables = sorter.pop_all();
+            if tables.is_empty() {
+                break;
+            }
+            tables.sort_by_key(|table| position(table));
+            sorted.extend(tables.into_iter().filter_map(position));
+        }
+        // tables in a cycle go last, so their rows can only refer to rows loaded before
+        for i in 0..self.entities.len() {
+            if !sorted.contains(&i) {
+                sorted.push(i);
+            }
+        }
+        sorted.into_iter().map(|i| &self.entities[i]).collect()
+    }
+}
+
+impl LoadedFixtures {
+    /// The model inserted for the row with the given symbol
+    pub fn get<E>(&self, symbol: &str) -> Option<&E::Model>
+    where
+        E: EntityTrait,
+        E::Model: 'static,
+    {
+        self.rows.get(symbol)?.model.downcast_ref()
+    }
+
+    /// The value of a column of the row with the given symbol, as JSON
+    pub fn value(&self, symbol: &str, column: &str) -> Option<&JsonValue> {
+        self.rows.get(symbol)?.values.get(column)
+    }
+
+    /// Replace the references in a row with the values they refer to, and key it by the
+    /// JSON keys of the entity
+    fn resolve(&self, entity: &FixtureEntity, row: JsonValue) -> Result<JsonValue, DbErr> {
+        let JsonValue::Object(row) = row else {
+            return Err(fixture_err(format!(
+                "expected a map of columns for a row of `{}`",
+                entity.table
+            )));
+        };
+        let json_key = |column: &str| {
+            entity
+                .columns
+                .iter()
+                .find(|(name, key)| name == column || key == column)
+                .map(|(_, key)| key.clone())
+        };
+
+        let mut resolved = Map::new();
+        for (key, value) in row {
+            let reference = match &value {
+                JsonValue::String(s) if s.starts_with("$$") => {
+                    resolved.insert(key, JsonValue::String(s[1..].to_owned()));
+                    continue;
+                }
+                JsonValue::String(s) => s.strip_prefix('$'),
+                _ => None,
+            };
+            let column = json_key(&key);
+            let Some(reference) = reference else {
+                let key = column.ok_or_else(|| {
+                    fixture_err(format!("no column `{key}` in `{}`", entity.table))
+                })?;
+                resolved.insert(key, value);
+                continue;
+            };
+            let target = self.rows.get(reference).ok_or_else(|| {
+                fixture_err(format!(
+                    "`${reference}` is not defined, or is in a table loaded after `{}`",
+                    entity.table
+                ))
+            })?;
+            if let Some(column) = column {
+                // the key the foreign key in this column refers to, or else the primary key
+                let parent = entity.parents.iter().find(|p| {
+                    p.table == target.table
+                        && p.from_col.len() == 1
+                        && json_key(&p.from_col[0]).as_ref() == Some(&column)
+                });
+                let target_col = match (parent, target.primary_key.as_slice()) {
+                    (Some(parent), _) => &parent.to_col[0],
+                    (None, [primary_key]) => primary_key,
+                    (None, _) => {
+                        return Err(fixture_err(format!(
+                            "`${reference}` has no single-column primary key"
+                        )));
+                    }
+                };
+                let value = target.values.get(target_col).cloned();
+                resolved.insert(column, value.unwrap_or(JsonValue::Null));
+            } else {
+                let parent = entity
+                    .parents
+                    .iter()
+                    .find(|p| p.table == key && p.table == target.table)
+                    .ok_or_else(|| {
+                        fixture_err(format!(
+                            "`{}` has no column `{key}` and does not belong to a `{}` row",
+                            entity.table, target.table
+                        ))
+                    })?;
+                for (from, to) in parent.from_col.iter().zip(&parent.to_col) {
+                    let value = target.values.get(to).cloned().unwrap_or(JsonValue::Null);
+                    resolved.insert(json_key(from).unwrap_or_else(|| from.clone()), value);
+                }
+            }
+        }
+        Ok(JsonValue::Object(resolved))
+    }
+}
+
+fn table_name(table_ref: &TableRef) -> Option<String> {
+    match table_ref {
+        TableRef::Table(TableName(_, name), _) => Some(name.to_string()),
+        _ => None,
+    }
+}
+
+/// The contents of the fixture files at `path`, by the stems of their names
+fn read_files(path: &Path) -> Result<Vec<(String, JsonValue)>, DbErr> {
+    let read_err =
+        |path: &Path, e: &dyn fmt::Display| fixture_err(format!("{}: {e}", path.display()));
+    let mut files = Vec::new();
+    if path.is_dir() {
+        for entry in std::fs::read_dir(path).map_err(|e| read_err(path, &e))? {
+            let file = entry.map_err(|e| read_err(path, &e))?.path();
+            if matches!(extension(&file), Some("json" | "yaml" | "yml")) {
+                files.push(file);
+            }
+        }
+        files.sort();
+    } else {
+        files.push(path.to_owned());
+    }
+
+    let mut contents = Vec::new();
+    for file in files {
+        let text = std::fs::read_to_string(&file).map_err(|e| read_err(&file, &e))?;
+        let content: JsonValue = match extension(&file) {
+            Some("json") => serde_json::from_str(&text).map_err(|e| read_err(&file, &e))?,
+            _ => serde_yaml::from_str(&text).map_err(|e| read_err(&file, &e))?,
+        };
+        let stem = file
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default()
+            .to_owned();
+        contents.push((stem, content));
+    }
+    Ok(contents)
+}
+
+fn extension(path: &Path) -> Option<&str> {
+    path.extension().and_then(|ext| ext.to_str())
+}
+
+fn fixture_err<T>(s: T) -> DbErr
+where
+    T: fmt::Display,
+{
+    DbErr::Custom(format!("Failed to load fixtures: {s}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Fixtures;
+    use crate::{DbBackend, DbErr, MockDatabase, Statement, Transaction, tests_cfg::*};
+    use pretty_assertions::assert_eq;
+
+    #[smol_potat::test]
+    async fn test_load_in_dependency_order() -> Result<(), DbErr> {
+        let chocolate = cake::Model {
+            id: 3,
+            name: "Chocolate Forest".to_owned(),
+        };
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([[chocolate.clone()]])
+            .append_query_results([[fruit::Model {
+                id: 1,
+                name: "Cherry".to_owned(),
+                cake_id: Some(3),
+            }]])
+            .into_connection();
+
+        let loaded = Fixtures::new()
+            .register(fruit::Entity)
+            .register(cake::Entity)
+            .load(&db, concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures"))
+            .await?;
+
+        assert_eq!(loaded.get::<cake::Entity>("chocolate"), Some(&chocolate));
+        assert_eq!(
+            db.into_transaction_log(),
+            [Transaction::many([
+                Statement::from_string(DbBackend::Postgres, "BEGIN"),
+                Statement::from_sql_and_values(
+                    DbBackend::Postgres,
+                    r#"INSERT INTO "cake" ("name") VALUES ($1) RETURNING "id", "name""#,
+                    ["Chocolate Forest".into()]
+                ),
+                Statement::from_sql_and_values(
+                    DbBackend::Postgres,
+                    r#"INSERT INTO "fruit" ("name", "cake_id") VALUES ($1, $2) RETURNING "id", "name", "cake_id""#,
+                    ["Cherry".into(), 3i32.into()]
+                ),
+                Statement::from_string(DbBackend::Postgres, "COMMIT"),
+            ])]
+        );
+        Ok(())
+    }
+}
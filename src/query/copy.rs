@@ -0,0 +1,60 @@
+use crate::{ActiveModelTrait, IntoActiveModel};
+
+/// Bulk-load builder, returned by [`EntityTrait::copy_in`](crate::EntityTrait::copy_in).
+/// Dispatch with `.exec(db)`.
+///
+/// On Postgres the rows are streamed through `COPY ... FROM STDIN`, in CSV form unless
+/// [`CopyIn::format`] says otherwise, which is much faster than `INSERT` for large amounts
+/// of data. Other backends, and connections that cannot `COPY` (e.g. mock connections), fall
+/// back to multi-row `INSERT`s, each carrying as many rows as the backend allows bind
+/// parameters for.
+///
+/// Models leaving different columns [`NotSet`](crate::ActiveValue::NotSet) are sent in
+/// runs of consecutive models setting the same columns, in one transaction, so that the
+/// columns they leave out take their defaults.
+#[derive(Debug)]
+pub struct CopyIn<A>
+where
+    A: ActiveModelTrait,
+{
+    pub(crate) models: Vec<A>,
+    pub(crate) format: CopyFormat,
+}
+
+/// The form [`CopyIn`] sends rows to Postgres in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CopyFormat {
+    /// One line of comma separated text per row
+    #[default]
+    Csv,
+    /// Postgres' binary tuple format, which the server does not have to parse. Each value
+    /// must be of the Rust type that maps to the type of its column, and arrays are not
+    /// supported. Without the `sqlx-postgres` feature, rows are inserted instead.
+    Binary,
+}
+
+impl<A> CopyIn<A>
+where
+    A: ActiveModelTrait,
+{
+    /// Copy in many Model or ActiveModel
+    pub fn many<M, I>(models: I) -> Self
+    where
+        M: IntoActiveModel<A>,
+        I: IntoIterator<Item = M>,
+    {
+        Self {
+            models: models
+                .into_iter()
+                .map(IntoActiveModel::into_active_model)
+                .collect(),
+            format: CopyFormat::default(),
+        }
+    }
+
+    /// Set the form the rows are sent to Postgres in
+    pub fn format(mut self, format: CopyFormat) -> Self {
+        self.format = format;
+        self
+    }
+}
@@ -1,10 +1,10 @@
 use crate::{
     ColumnTrait, EntityTrait, IdenStatic, Iterable, Linked, QueryFilter, QuerySelect, QueryTrait,
-    Related, Select, SelectA, SelectB, SelectThree, SelectTwo, SelectTwoMany, SelectTwoRequired,
-    TopologyChain, TopologyStar, find_linked_recursive, join_tbl_on_condition,
+    Related, RelationDef, Select, SelectA, SelectB, SelectThree, SelectTwo, SelectTwoMany,
+    SelectTwoRequired, TopologyChain, TopologyStar, find_linked_recursive, join_tbl_on_condition,
 };
 pub use sea_query::JoinType;
-use sea_query::{Condition, Expr, IntoCondition, IntoIden, SelectExpr};
+use sea_query::{Condition, Expr, IntoCondition, IntoIden, SelectExpr, SelectStatement};
 
 impl<E> Select<E>
 where
@@ -16,7 +16,7 @@ where
         R: EntityTrait,
         E: Related<R>,
     {
-        self.join_join(JoinType::LeftJoin, E::to(), E::via())
+        join_related(self, JoinType::LeftJoin, E::to(), E::via())
     }
 
     /// Right Join with a Related Entity.
@@ -34,7 +34,7 @@ where
         R: EntityTrait,
         E: Related<R>,
     {
-        self.join_join(JoinType::InnerJoin, E::to(), E::via())
+        join_related(self, JoinType::InnerJoin, E::to(), E::via())
     }
 
     /// Join with an Entity Related to me.
@@ -87,7 +87,8 @@ where
         L: Linked<FromEntity = E, ToEntity = T>,
         T: EntityTrait,
     {
-        SelectTwo::new_without_prepare(self.left_join_linked(l).into_query())
+        let timeout = self.timeout;
+        SelectTwo::new_without_prepare(self.left_join_linked(l).into_query(), timeout)
     }
 
     /// Left Join with a Linked Entity and select Entity as a `Vec`.
@@ -96,7 +97,8 @@ where
         L: Linked<FromEntity = E, ToEntity = T>,
         T: EntityTrait,
     {
-        SelectTwoMany::new_without_prepare(self.left_join_linked(l).into_query())
+        let timeout = self.timeout;
+        SelectTwoMany::new_without_prepare(self.left_join_linked(l).into_query(), timeout)
     }
 
     /// Left Join with a Linked Entity.
@@ -125,9 +127,11 @@ where
             if let Some(f) = rel.on_condition.take() {
                 condition = condition.add(f(from_tbl.clone(), to_tbl.clone()));
             }
+            if let Some(scope) = rel.to_scope {
+                condition = condition.add(scope(to_tbl.clone()));
+            }
 
-            self.query
-                .join_as(JoinType::LeftJoin, table_ref, to_tbl, condition);
+            QueryTrait::query(&mut self).join_as(JoinType::LeftJoin, table_ref, to_tbl, condition);
         }
         self = self.apply_alias(SelectA.as_str());
         for col in <T::Column as Iterable>::iter() {
@@ -136,7 +140,7 @@ where
                 format!("r{}", self.linked_index - 1).into_iden(),
                 col.into_iden(),
             ));
-            self.query.expr(SelectExpr {
+            QueryTrait::query(&mut self).expr(SelectExpr {
                 expr: col.select_as(expr),
                 alias: Some(alias.into_iden()),
                 window: None,
@@ -200,7 +204,7 @@ where
             // join the junction table
             subquery.inner_join(to.from_tbl.clone(), to);
         }
-        self.query.cond_where(Expr::exists(subquery));
+        QueryTrait::query(&mut self).cond_where(Expr::exists(subquery));
         self
     }
 
@@ -226,7 +230,8 @@ where
         R: EntityTrait,
     {
         // select also but without join
-        SelectThree::new_without_prepare(self.into_query())
+        let timeout = self.timeout;
+        SelectThree::new_without_prepare(self.into_query(), timeout)
     }
 
     /// Left Join with a Related Entity and select both Entity.
@@ -235,9 +240,10 @@ where
         R: EntityTrait,
         G: EntityTrait + Related<R>,
     {
+        let timeout = self.timeout;
         SelectThree::new(
-            self.join_join(JoinType::LeftJoin, G::to(), G::via())
-                .into_query(),
+            join_related(self, JoinType::LeftJoin, G::to(), G::via()).into_query(),
+            timeout,
         )
     }
 
@@ -247,9 +253,10 @@ where
         R: EntityTrait,
         E: Related<R>,
     {
+        let timeout = self.timeout;
         SelectThree::new(
-            self.join_join(JoinType::LeftJoin, E::to(), E::via())
-                .into_query(),
+            join_related(self, JoinType::LeftJoin, E::to(), E::via()).into_query(),
+            timeout,
         )
     }
 
@@ -259,13 +266,45 @@ where
         R: EntityTrait,
         F: Related<R>,
     {
+        let timeout = self.timeout;
         SelectThree::new(
-            self.join_join(JoinType::LeftJoin, F::to(), F::via())
-                .into_query(),
+            join_related(self, JoinType::LeftJoin, F::to(), F::via()).into_query(),
+            timeout,
         )
     }
 }
 
+/// [`QuerySelect::join_join`], leaving out the rows of the related entity, and of the
+/// junction table in between, that are soft deleted or outside of their default scope
+fn join_related<S>(mut select: S, join: JoinType, rel: RelationDef, via: Option<RelationDef>) -> S
+where
+    S: QuerySelect,
+{
+    if let Some(via) = via {
+        join_scoped(select.query(), join, via);
+    }
+    join_scoped(select.query(), join, rel);
+    select
+}
+
+/// Join `rel.to_tbl`, leaving out the rows that its [`to_scope`](RelationDef::to_scope)
+/// does not match
+fn join_scoped(query: &mut SelectStatement, join: JoinType, rel: RelationDef) {
+    let table_ref = rel.to_tbl.clone();
+    let Some(scope) = rel.to_scope else {
+        query.join(join, table_ref, rel);
+        return;
+    };
+    let to_tbl = match table_ref.sea_orm_table_alias() {
+        Some(alias) => alias,
+        None => table_ref.sea_orm_table(),
+    };
+    let scope = scope(to_tbl.clone());
+    // the relation may combine its own conditions with `OR`, so keep them apart
+    let condition = Condition::all().add(Condition::from(rel)).add(scope);
+    query.join(join, table_ref, condition);
+}
+
 #[cfg(test)]
 mod tests {
     use crate::tests_cfg::{
@@ -817,4 +856,208 @@ mod tests {
             .join(" ")
         );
     }
+
+    #[test]
+    #[cfg(feature = "with-chrono")]
+    fn join_soft_delete() {
+        use crate::tests_cfg::cake_review;
+
+        assert_eq!(
+            cake::Entity::find()
+                .find_also_related(cake_review::Entity)
+                .build(DbBackend::MySql)
+                .to_string(),
+            [
+                "SELECT `cake`.`id` AS `A_id`, `cake`.`name` AS `A_name`,",
+                "`cake_review`.`id` AS `B_id`, `cake_review`.`cake_id` AS `B_cake_id`, `cake_review`.`body` AS `B_body`, `cake_review`.`deleted_at` AS `B_deleted_at`",
+                "FROM `cake` LEFT JOIN `cake_review` ON `cake`.`id` = `cake_review`.`cake_id` AND `cake_review`.`deleted_at` IS NULL",
+            ]
+            .join(" ")
+        );
+        assert_eq!(
+            cake_review::Entity::find()
+                .find_also_related(cake::Entity)
+                .build(DbBackend::MySql)
+                .to_string(),
+            [
+                "SELECT `cake_review`.`id` AS `A_id`, `cake_review`.`cake_id` AS `A_cake_id`, `cake_review`.`body` AS `A_body`, `cake_review`.`deleted_at` AS `A_deleted_at`,",
+                "`cake`.`id` AS `B_id`, `cake`.`name` AS `B_name`",
+                "FROM `cake_review` LEFT JOIN `cake` ON `cake_review`.`cake_id` = `cake`.`id`",
+                "WHERE `cake_review`.`deleted_at` IS NULL",
+            ]
+            .join(" ")
+        );
+        assert_eq!(
+            cake_review::Entity::find()
+                .with_trashed()
+                .inner_join(cake::Entity)
+                .build(DbBackend::MySql)
+                .to_string(),
+            [
+                "SELECT `cake_review`.`id`, `cake_review`.`cake_id`, `cake_review`.`body`, `cake_review`.`deleted_at`",
+                "FROM `cake_review` INNER JOIN `cake` ON `cake_review`.`cake_id` = `cake`.`id`",
+            ]
+            .join(" ")
+        );
+        assert_eq!(
+            cake::Model {
+                id: 1,
+                name: "Chocolate Cake".to_owned(),
+            }
+            .find_related(cake_review::Entity)
+            .build(DbBackend::MySql)
+            .to_string(),
+            [
+                "SELECT `cake_review`.`id`, `cake_review`.`cake_id`, `cake_review`.`body`, `cake_review`.`deleted_at`",
+                "FROM `cake_review` INNER JOIN `cake` ON `cake`.`id` = `cake_review`.`cake_id`",
+                "WHERE `cake`.`id` = 1 AND `cake_review`.`deleted_at` IS NULL",
+            ]
+            .join(" ")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "with-chrono")]
+    fn join_soft_delete_linked() {
+        use crate::tests_cfg::cake_review;
+        use crate::{LinkDef, Linked, QueryTrait};
+        use sea_query::MysqlQueryBuilder;
+
+        /// Cakes reviewed along with a cake
+        struct ReviewedAlong;
+
+        impl Linked for ReviewedAlong {
+            type FromEntity = cake::Entity;
+            type ToEntity = cake::Entity;
+
+            fn link(&self) -> Vec<LinkDef> {
+                vec![
+                    cake_review::Relation::Cake.def().rev(),
+                    cake_review::Relation::Cake.def(),
+                ]
+            }
+        }
+
+        assert_eq!(
+            cake::Entity::find()
+                .find_also_linked(ReviewedAlong)
+                .build(DbBackend::MySql)
+                .to_string(),
+            [
+                "SELECT `cake`.`id` AS `A_id`, `cake`.`name` AS `A_name`,",
+                "`r1`.`id` AS `B_id`, `r1`.`name` AS `B_name` FROM `cake`",
+                "LEFT JOIN `cake_review` AS `r0` ON `cake`.`id` = `r0`.`cake_id` AND `r0`.`deleted_at` IS NULL",
+                "LEFT JOIN `cake` AS `r1` ON `r0`.`cake_id` = `r1`.`id`",
+            ]
+            .join(" ")
+        );
+        assert_eq!(
+            cake::Model {
+                id: 1,
+                name: "Chocolate Cake".to_owned(),
+            }
+            .find_linked(ReviewedAlong)
+            .build(DbBackend::MySql)
+            .to_string(),
+            [
+                "SELECT `cake`.`id`, `cake`.`name` FROM `cake`",
+                "INNER JOIN `cake_review` AS `r0` ON `r0`.`cake_id` = `cake`.`id` AND `r0`.`deleted_at` IS NULL",
+                "INNER JOIN `cake` AS `r1` ON `r1`.`id` = `r0`.`cake_id`",
+                "WHERE `r1`.`id` = 1",
+            ]
+            .join(" ")
+        );
+
+        let select = cake_review::Entity::find();
+        assert_eq!(
+            select.as_query().to_string(MysqlQueryBuilder),
+            select.build(DbBackend::MySql).to_string()
+        );
+        assert_eq!(
+            select.with_trashed().as_query().to_string(MysqlQueryBuilder),
+            [
+                "SELECT `cake_review`.`id`, `cake_review`.`cake_id`, `cake_review`.`body`, `cake_review`.`deleted_at`",
+                "FROM `cake_review`",
+            ]
+            .join(" ")
+        );
+    }
+
+    #[test]
+    fn join_default_scope() {
+        use crate::tests_cfg::{article, user};
+        use crate::{LinkDef, Linked, QueryTrait};
+        use sea_query::MysqlQueryBuilder;
+
+        /// Users, through the articles they wrote
+        struct ThroughArticles;
+
+        impl Linked for ThroughArticles {
+            type FromEntity = user::Entity;
+            type ToEntity = user::Entity;
+
+            fn link(&self) -> Vec<LinkDef> {
+                vec![
+                    article::Relation::User.def().rev(),
+                    article::Relation::User.def(),
+                ]
+            }
+        }
+
+        assert_eq!(
+            user::Entity::find()
+                .inner_join(article::Entity)
+                .build(DbBackend::MySql)
+                .to_string(),
+            [
+                "SELECT `user`.`id`, `user`.`name`, `user`.`email` FROM `user`",
+                "INNER JOIN `article` ON `user`.`id` = `article`.`user_id` AND `article`.`published` = TRUE",
+            ]
+            .join(" ")
+        );
+        assert_eq!(
+            article::Entity::find()
+                .inner_join(user::Entity)
+                .build(DbBackend::MySql)
+                .to_string(),
+            [
+                "SELECT `article`.`id`, `article`.`user_id`, `article`.`title`, `article`.`published`",
+                "FROM `article` INNER JOIN `user` ON `article`.`user_id` = `user`.`id`",
+                "WHERE `article`.`published` = TRUE",
+            ]
+            .join(" ")
+        );
+        assert_eq!(
+            article::Entity::find()
+                .as_query()
+                .to_string(MysqlQueryBuilder),
+            article::Entity::find().build(DbBackend::MySql).to_string()
+        );
+        assert_eq!(
+            article::Entity::find()
+                .unscoped()
+                .inner_join(user::Entity)
+                .build(DbBackend::MySql)
+                .to_string(),
+            [
+                "SELECT `article`.`id`, `article`.`user_id`, `article`.`title`, `article`.`published`",
+                "FROM `article` INNER JOIN `user` ON `article`.`user_id` = `user`.`id`",
+            ]
+            .join(" ")
+        );
+        assert_eq!(
+            user::Entity::find()
+                .find_also_linked(ThroughArticles)
+                .build(DbBackend::MySql)
+                .to_string(),
+            [
+                "SELECT `user`.`id` AS `A_id`, `user`.`name` AS `A_name`, `user`.`email` AS `A_email`,",
+                "`r1`.`id` AS `B_id`, `r1`.`name` AS `B_name`, `r1`.`email` AS `B_email` FROM `user`",
+                "LEFT JOIN `article` AS `r0` ON `user`.`id` = `r0`.`user_id`",
+                "AND `r0`.`id` IN (SELECT `article`.`id` FROM `article` WHERE `article`.`published` = TRUE)",
+                "LEFT JOIN `user` AS `r1` ON `r0`.`user_id` = `r1`.`id`",
+            ]
+            .join(" ")
+        );
+    }
 }
@@ -1,9 +1,10 @@
 use crate::{
-    ActiveModelTrait, ActiveValue, ColumnTrait, DbErr, EntityTrait, Iterable, PrimaryKeyToColumn,
-    QueryFilter, QueryTrait,
+    ActiveModelTrait, ActiveValue, ColumnTrait, DbErr, EntityTrait, IdenStatic, Iterable,
+    PrimaryKeyToColumn, QueryFilter, QueryTrait,
 };
 use core::marker::PhantomData;
 use sea_query::{Expr, IntoIden, SimpleExpr, UpdateStatement};
+use std::{sync::OnceLock, time::Duration};
 
 /// Type-level entry point for `UPDATE` builders, e.g.
 /// `Update::one(model)` and `Update::many(Entity)`. You normally call
@@ -57,6 +58,10 @@ where
 {
     pub(crate) query: UpdateStatement,
     pub(crate) entity: PhantomData<E>,
+    pub(crate) timeout: Option<Duration>,
+    /// `query` with the `updated_at` column of the entity set, built on first use by
+    /// [`QueryTrait::as_query`] and cleared on every change
+    with_updated_at: OnceLock<UpdateStatement>,
 }
 
 impl Update {
@@ -104,9 +109,18 @@ impl Update {
                 }
             }
         }
+        // Only update the row if it still has the version the model was loaded with.
+        let version = E::version_column();
+        if let Some(col) = version
+            && let ActiveValue::Set(value) | ActiveValue::Unchanged(value) = myself.model.get(col)
+        {
+            myself = myself.filter(col.eq(value));
+        }
         // Set the values to update (from the other columns).
         for col in <A::Entity as EntityTrait>::Column::iter() {
-            if <A::Entity as EntityTrait>::PrimaryKey::from_column(col).is_some() {
+            if <A::Entity as EntityTrait>::PrimaryKey::from_column(col).is_some()
+                || version.is_some_and(|version| version.as_str() == col.as_str())
+            {
                 continue;
             }
             match myself.model.get(col) {
@@ -117,6 +131,15 @@ impl Update {
                 ActiveValue::Unchanged(_) | ActiveValue::NotSet => {}
             }
         }
+        set_updated_at::<E>(&mut myself.query);
+        // Bump the version, unless there is nothing to update.
+        if let Some(col) = version
+            && !myself.query.get_values().is_empty()
+        {
+            myself
+                .query
+                .value(col, sea_query::ExprTrait::add(Expr::col(col), 1));
+        }
         UpdateOne(Ok(myself))
     }
 
@@ -141,6 +164,8 @@ impl Update {
         UpdateMany {
             query: UpdateStatement::new().table(entity.table_ref()).to_owned(),
             entity: PhantomData,
+            timeout: None,
+            with_updated_at: OnceLock::new(),
         }
     }
 }
@@ -163,6 +188,7 @@ where
     type QueryStatement = UpdateStatement;
 
     fn query(&mut self) -> &mut UpdateStatement {
+        self.with_updated_at.take();
         &mut self.query
     }
 }
@@ -193,18 +219,47 @@ where
     type QueryStatement = UpdateStatement;
 
     fn query(&mut self) -> &mut UpdateStatement {
+        self.with_updated_at.take();
         &mut self.query
     }
 
+    /// The statement with the `updated_at` column of the entity set, unless there is
+    /// nothing to update or the column is set already
     fn as_query(&self) -> &UpdateStatement {
-        &self.query
+        self.with_updated_at.get_or_init(|| {
+            let mut query = self.query.clone();
+            set_updated_at::<E>(&mut query);
+            query
+        })
     }
 
-    fn into_query(self) -> UpdateStatement {
+    fn into_query(mut self) -> UpdateStatement {
+        if let Some(query) = self.with_updated_at.take() {
+            return query;
+        }
+        set_updated_at::<E>(&mut self.query);
         self.query
     }
 }
 
+/// Set the `updated_at` column of the entity to the current time, unless there is
+/// nothing to update or the column is set already
+fn set_updated_at<E>(query: &mut UpdateStatement)
+where
+    E: EntityTrait,
+{
+    let Some(col) = E::updated_at_column() else {
+        return;
+    };
+    let values = query.get_values();
+    if values.is_empty() || values.iter().any(|(iden, _)| iden.inner() == col.as_str()) {
+        return;
+    }
+    if let Some(now) = col.current_timestamp() {
+        query.value(col, col.save_as(Expr::val(now)));
+    }
+}
+
 impl<E> UpdateMany<E>
 where
     E: EntityTrait,
@@ -218,7 +273,7 @@ where
             match model.get(col) {
                 ActiveValue::Set(value) => {
                     let expr = col.save_as(Expr::val(value));
-                    self.query.value(col, expr);
+                    QueryTrait::query(&mut self).value(col, expr);
                 }
                 ActiveValue::Unchanged(_) | ActiveValue::NotSet => {}
             }
@@ -231,7 +286,16 @@ where
     where
         T: IntoIden,
     {
-        self.query.value(col, expr);
+        QueryTrait::query(&mut self).value(col, expr);
+        self
+    }
+
+    /// Cancel the statement once it has run for longer than `timeout`, failing with
+    /// [`DbErr::QueryTimeout`](crate::DbErr::QueryTimeout). Enforced by the database,
+    /// see [`ConnectionTrait::execute_raw_with_timeout`](crate::ConnectionTrait::execute_raw_with_timeout);
+    /// MySQL can only bound `SELECT`s, so there it has no effect.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
         self
     }
 }
@@ -362,4 +426,56 @@ mod tests {
             r#"UPDATE "lunch_set" SET "tea" = CAST('EverydayTea' AS "tea") WHERE "lunch_set"."id" = 1"#,
         );
     }
+
+    #[test]
+    fn update_timestamps() {
+        use crate::{tests_cfg::note, value::CurrentTimestamp};
+
+        let now = <i64 as CurrentTimestamp>::now();
+        let statement = Update::one(note::ActiveModel {
+            id: Set(1),
+            body: Set("Buy oat milk".to_owned()),
+            ..Default::default()
+        })
+        .validate()
+        .unwrap()
+        .build(DbBackend::Postgres);
+        assert_eq!(
+            statement.sql,
+            r#"UPDATE "note" SET "body" = $1, "updated_at" = $2 WHERE "note"."id" = $3"#,
+        );
+        let values = statement.values.unwrap().0;
+        assert!(matches!(values[1], Value::BigInt(Some(updated_at)) if updated_at >= now));
+
+        let statement = Update::many(note::Entity)
+            .col_expr(note::Column::Body, Expr::value("Buy oat milk"))
+            .filter(note::Column::Id.eq(2))
+            .build(DbBackend::Postgres);
+        assert_eq!(
+            statement.sql,
+            r#"UPDATE "note" SET "body" = $1, "updated_at" = $2 WHERE "note"."id" = $3"#,
+        );
+        let values = statement.values.unwrap().0;
+        assert!(matches!(values[1], Value::BigInt(Some(updated_at)) if updated_at >= now));
+
+        assert_eq!(
+            Update::many(note::Entity)
+                .col_expr(note::Column::UpdatedAt, Expr::value(5i64))
+                .build(DbBackend::Postgres)
+                .to_string(),
+            r#"UPDATE "note" SET "updated_at" = 5"#,
+        );
+
+        let update = Update::many(note::Entity)
+            .col_expr(note::Column::Body, Expr::value("Buy oat milk"))
+            .filter(note::Column::Id.eq(2));
+        assert_eq!(
+            DbBackend::Postgres.build(update.as_query()),
+            update.build(DbBackend::Postgres),
+        );
+        assert_eq!(
+            DbBackend::Postgres.build(&update.into_query()).sql,
+            r#"UPDATE "note" SET "body" = $1, "updated_at" = $2 WHERE "note"."id" = $3"#,
+        );
+    }
 }
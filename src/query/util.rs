@@ -1,8 +1,10 @@
 use crate::{
     ActiveModelTrait, ColumnTrait, Condition, DbBackend, DbErr, EntityTrait, ExprTrait, IdenStatic,
-    Identity, ModelTrait, Value,
+    Identity, Iterable, ModelTrait, PrimaryKeyToColumn, Value,
+};
+use sea_query::{
+    ColumnRef, DynIden, Expr, IntoColumnRef, IntoIden, OnConflict, Query, TableRef, ValueTuple,
 };
-use sea_query::{ColumnRef, DynIden, Expr, IntoColumnRef, TableRef, ValueTuple};
 use std::str::FromStr;
 
 #[derive(Default)]
@@ -35,6 +37,91 @@ impl ValueTupleBuilder {
     }
 }
 
+/// Set the `created_at` / `updated_at` columns of a model to be inserted to the current
+/// time, unless they are set already
+pub fn set_insert_timestamps<A>(model: &mut A)
+where
+    A: ActiveModelTrait,
+{
+    let created_at = <A::Entity as EntityTrait>::created_at_column();
+    let updated_at = <A::Entity as EntityTrait>::updated_at_column();
+    for col in created_at.into_iter().chain(updated_at) {
+        if model.is_not_set(col)
+            && let Some(now) = col.current_timestamp()
+        {
+            model.set(col, now);
+        }
+    }
+}
+
+/// Bump the `updated_at` column of an upsert to the inserted value, when the conflict is
+/// resolved by updating the row and the column is not updated already
+pub fn set_upsert_updated_at<E>(on_conflict: &mut OnConflict)
+where
+    E: EntityTrait,
+{
+    let Some(col) = E::updated_at_column() else {
+        return;
+    };
+    // `OnConflict` keeps its action private: appending no columns is a no-op only for an
+    // update action, and its `Debug` output is the only way to see the updated columns
+    let mut probe = on_conflict.clone();
+    probe.update_columns::<DynIden, _>([]);
+    if probe != *on_conflict {
+        return;
+    }
+    let debug = format!("{on_conflict:?}");
+    let action = debug
+        .split_once("action: ")
+        .map_or("", |(_, action)| action);
+    if action.contains(&format!("{:?}", col.into_iden())) {
+        return;
+    }
+    on_conflict.update_column(col);
+}
+
+/// Conditions leaving out the rows of `E`, joined as `tbl`, that are soft deleted or
+/// outside of its default scope
+pub fn join_scope<E>(tbl: DynIden) -> Condition
+where
+    E: EntityTrait,
+{
+    let soft_delete = E::soft_delete_column().map(|col| Expr::col((tbl.clone(), col)).is_null());
+    let default_scope = E::default_scope().map(|scope| {
+        if tbl.inner() == E::default().table_name() {
+            return scope;
+        }
+        // the default scope names the table itself, so an alias is matched against the
+        // rows of the table in scope by primary key
+        let mut keys: Vec<Expr> = E::PrimaryKey::iter()
+            .map(|key| Expr::col((tbl.clone(), key.into_column())))
+            .collect();
+        let key = if keys.len() == 1 {
+            keys.remove(0)
+        } else {
+            Expr::tuple(keys)
+        };
+        let subquery = Query::select()
+            .columns(E::PrimaryKey::iter().map(|key| (E::default(), key.into_column())))
+            .from(E::default().table_ref())
+            .cond_where(scope)
+            .to_owned();
+        Condition::all().add(key.in_subquery(subquery))
+    });
+    Condition::all()
+        .add_option(soft_delete)
+        .add_option(default_scope)
+}
+
+/// [`join_scope`] of `E`, unless it leaves out no rows
+pub fn join_scope_of<E>() -> Option<fn(DynIden) -> Condition>
+where
+    E: EntityTrait,
+{
+    (E::soft_delete_column().is_some() || E::default_scope().is_some())
+        .then_some(join_scope::<E> as fn(DynIden) -> Condition)
+}
+
 pub fn get_key_from_model<Model>(columns: &Identity, model: &Model) -> Result<ValueTuple, DbErr>
 where
     Model: ModelTrait,
@@ -0,0 +1,42 @@
+use crate as sea_orm;
+use crate::Condition;
+use crate::entity::prelude::*;
+
+#[sea_orm::compact_model]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "article", default_scope = "published")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub user_id: i32,
+    pub title: String,
+    pub published: bool,
+}
+
+fn published() -> Condition {
+    Condition::all().add(Column::Published.eq(true))
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl Related<Entity> for super::user::Entity {
+    fn to() -> RelationDef {
+        Relation::User.def().rev()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
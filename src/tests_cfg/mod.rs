@@ -5,11 +5,15 @@
 #[cfg(feature = "entity-registry")]
 mod registry;
 
+pub mod article;
 pub mod cake;
 pub mod cake_compact;
 pub mod cake_expanded;
 pub mod cake_filling;
 pub mod cake_filling_price;
+#[cfg(feature = "with-chrono")]
+pub mod cake_review;
+pub mod document;
 pub mod entity_linked;
 pub mod filling;
 pub mod fruit;
@@ -17,6 +21,7 @@ pub mod indexes;
 pub mod ingredient;
 pub mod lunch_set;
 pub mod lunch_set_expanded;
+pub mod note;
 pub mod rust_keyword;
 pub mod sea_orm_active_enums;
 #[cfg(feature = "with-json")]
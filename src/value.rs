@@ -129,6 +129,44 @@ where
     }
 }
 
+/// The current time as a value of a `created_at` / `updated_at` column, see
+/// [`EntityTrait::created_at_column`](crate::EntityTrait::created_at_column).
+///
+/// Implemented for the date time types of `chrono` and `time`, for Unix timestamps
+/// in seconds as `i32`, `u32`, `i64`, `u64` or as `ChronoUnixTimestamp` / `TimeUnixTimestamp` (and
+/// their milliseconds counterparts), and for `Option`s of these.
+pub trait CurrentTimestamp {
+    /// The current time.
+    fn now() -> Self;
+}
+
+macro_rules! impl_current_timestamp_epoch {
+    ($($ty:ty),*) => {
+        $(
+            impl CurrentTimestamp for $ty {
+                fn now() -> Self {
+                    std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map_or(0, |duration| {
+                            duration.as_secs().try_into().unwrap_or(<$ty>::MAX)
+                        })
+                }
+            }
+        )*
+    };
+}
+
+impl_current_timestamp_epoch!(i32, u32, i64, u64);
+
+impl<T> CurrentTimestamp for Option<T>
+where
+    T: CurrentTimestamp,
+{
+    fn now() -> Self {
+        Some(T::now())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -151,4 +189,14 @@ mod test {
         let v = (&ActiveValue::<TimeDateTime>::NotSet).default_value();
         assert!(matches!(v, ActiveValue::Set(_)));
     }
+
+    #[test]
+    fn test_current_timestamp_epoch() {
+        let secs = <i64 as CurrentTimestamp>::now();
+        assert!(secs > 1_700_000_000);
+        assert!(i64::from(<i32 as CurrentTimestamp>::now()) >= secs);
+        assert!(i64::from(<u32 as CurrentTimestamp>::now()) >= secs);
+        assert!(<u64 as CurrentTimestamp>::now() >= secs as u64);
+        assert!(matches!(<Option<i32> as CurrentTimestamp>::now(), Some(now) if now > 0));
+    }
 }
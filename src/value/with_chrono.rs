@@ -1,6 +1,10 @@
 use super::impl_timestamp;
 use crate as sea_orm;
-use crate::{DbErr, TryGetError, prelude::ChronoDateTimeUtc};
+use crate::{
+    DbErr, TryGetError,
+    prelude::{ChronoDateTime, ChronoDateTimeLocal, ChronoDateTimeUtc, ChronoDateTimeWithTimeZone},
+    value::CurrentTimestamp,
+};
 use std::ops::{Deref, DerefMut};
 
 /// A `DateTime<Utc>` stored as a Unix timestamp (seconds) `i64` in the database.
@@ -33,6 +37,42 @@ super::impl_serde_with_i64!(
     to_timestamp_millis
 );
 
+impl CurrentTimestamp for ChronoDateTime {
+    fn now() -> Self {
+        chrono::Utc::now().naive_utc()
+    }
+}
+
+impl CurrentTimestamp for ChronoDateTimeUtc {
+    fn now() -> Self {
+        chrono::Utc::now()
+    }
+}
+
+impl CurrentTimestamp for ChronoDateTimeWithTimeZone {
+    fn now() -> Self {
+        chrono::Utc::now().into()
+    }
+}
+
+impl CurrentTimestamp for ChronoDateTimeLocal {
+    fn now() -> Self {
+        chrono::Local::now()
+    }
+}
+
+impl CurrentTimestamp for ChronoUnixTimestamp {
+    fn now() -> Self {
+        Self(chrono::Utc::now())
+    }
+}
+
+impl CurrentTimestamp for ChronoUnixTimestampMillis {
+    fn now() -> Self {
+        Self(chrono::Utc::now())
+    }
+}
+
 fn from_timestamp(ts: i64) -> Option<ChronoUnixTimestamp> {
     ChronoDateTimeUtc::from_timestamp(ts, 0).map(ChronoUnixTimestamp)
 }
@@ -0,0 +1,60 @@
+#![allow(unused_imports, dead_code)]
+
+pub mod common;
+
+pub use common::{TestContext, features::*, setup::*};
+use pretty_assertions::assert_eq;
+use sea_orm::{DatabaseConnection, entity::prelude::*, entity::*};
+
+#[sea_orm_macros::test]
+async fn main() -> Result<(), DbErr> {
+    let ctx = TestContext::new("advisory_lock_tests").await;
+    create_insert_default_table(&ctx.db).await?;
+    work_through_lock(&ctx.db).await?;
+    #[cfg(any(feature = "sqlx-mysql", feature = "sqlx-postgres"))]
+    lock_is_exclusive(&ctx.db).await?;
+    ctx.delete().await;
+
+    Ok(())
+}
+
+pub async fn work_through_lock(db: &DatabaseConnection) -> Result<(), DbErr> {
+    use insert_default::*;
+
+    let lock = db.advisory_lock("insert_default").await?;
+    assert_eq!(lock.key(), "insert_default");
+    ActiveModel { id: Set(1) }.insert(&*lock).await?;
+    lock.commit().await?;
+
+    // dropping the guard rolls back the work done through it
+    let lock = db.advisory_lock("insert_default").await?;
+    ActiveModel { id: Set(2) }.insert(&*lock).await?;
+    drop(lock);
+
+    assert_eq!(Entity::find().all(db).await?, [Model { id: 1 }]);
+
+    Ok(())
+}
+
+pub async fn lock_is_exclusive(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let lock = db.advisory_lock("leader").await?;
+    assert!(db.try_advisory_lock("leader").await?.is_none());
+
+    // a different key is not held
+    let other = db.try_advisory_lock("follower").await?;
+    assert!(other.is_some());
+    drop(other);
+
+    // committing the guard releases the lock, and so does dropping it
+    lock.commit().await?;
+    let lock = db.try_advisory_lock("leader").await?;
+    assert!(lock.is_some());
+    drop(lock);
+    let lock = db
+        .try_advisory_lock("leader")
+        .await?
+        .expect("the lock is released when its guard is dropped");
+    lock.commit().await?;
+
+    Ok(())
+}
@@ -171,3 +171,76 @@ pub async fn rusqlite_connects_file_uri_with_sqlite_query_parameters() {
 
     db.ping().await.unwrap();
 }
+
+#[sea_orm_macros::test]
+pub async fn connection_metric_info() {
+    use sea_orm::{ConnectionTrait, Statement, TransactionTrait, metric::Operation};
+    use std::sync::{Arc, Mutex};
+
+    let ctx = TestContext::new("connection_metric_info").await;
+    let mut db = ctx.db.clone();
+    let backend = db.get_database_backend();
+
+    let infos = Arc::new(Mutex::new(Vec::new()));
+    let sink = infos.clone();
+    db.set_metric_callback(move |info| {
+        sink.lock().unwrap().push((
+            info.operation(),
+            info.tables(),
+            info.rows,
+            info.in_transaction,
+            info.acquire_elapsed.is_some(),
+        ))
+    });
+
+    let stmt = |sql: &str| Statement::from_string(backend, sql);
+    db.execute_raw(stmt("CREATE TABLE metric_info (id integer)"))
+        .await
+        .unwrap();
+    db.execute_raw(stmt("INSERT INTO metric_info (id) VALUES (1), (2)"))
+        .await
+        .unwrap();
+    db.query_all_raw(stmt("SELECT id FROM metric_info"))
+        .await
+        .unwrap();
+    let txn = db.begin().await.unwrap();
+    txn.execute_raw(stmt("DELETE FROM metric_info WHERE id = 1"))
+        .await
+        .unwrap();
+    txn.commit().await.unwrap();
+
+    let table = || vec!["metric_info".to_owned()];
+    assert_eq!(
+        infos.lock().unwrap()[..],
+        [
+            (Operation::Ddl, table(), Some(0), false, true),
+            (Operation::Insert, table(), Some(2), false, true),
+            (Operation::Select, table(), Some(2), false, true),
+            (Operation::Delete, table(), Some(1), true, false),
+        ]
+    );
+
+    ctx.delete().await;
+}
+
+#[sea_orm_macros::test]
+pub async fn connection_pool_status() {
+    let ctx = TestContext::new("connection_pool_status").await;
+
+    let status = ctx.db.pool_status();
+    if cfg!(any(
+        feature = "sqlx-mysql",
+        feature = "sqlx-postgres",
+        feature = "sqlx-sqlite"
+    )) {
+        let status = status.expect("pool-backed connection");
+        assert!(status.size >= 1);
+        assert!(status.idle <= status.size);
+        assert!(status.size <= status.max_size);
+        assert_eq!(status.waiters, 0);
+    } else {
+        assert_eq!(status, None);
+    }
+
+    ctx.delete().await;
+}
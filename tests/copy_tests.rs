@@ -0,0 +1,142 @@
+#![allow(unused_imports, dead_code)]
+
+pub mod common;
+
+pub use common::{TestContext, features::*, setup::*};
+use pretty_assertions::assert_eq;
+use sea_orm::{CopyFormat, DatabaseConnection, QueryOrder, entity::prelude::*, entity::*};
+
+#[sea_orm_macros::test]
+async fn main() -> Result<(), DbErr> {
+    let ctx = TestContext::new("copy_tests").await;
+    create_metadata_table(&ctx.db).await?;
+    create_satellites_table(&ctx.db).await?;
+    copy_in_and_out(&ctx.db).await?;
+    copy_in_binary(&ctx.db).await?;
+    copy_in_defaults(&ctx.db).await?;
+    ctx.delete().await;
+
+    Ok(())
+}
+
+pub async fn copy_in_and_out(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let models: Vec<_> = (0..2500u128)
+        .map(|i| metadata::Model {
+            uuid: Uuid::from_u128(i),
+            ty: ["", "a,b", "say \"hi\"", "line\nbreak", "\\."][i as usize % 5].to_owned(),
+            key: format!("key {i}"),
+            value: "NULL".to_owned(),
+            bytes: vec![i as u8, 0, 255],
+            date: (i % 2 == 0).then(|| Date::from_ymd_opt(2024, 2, 29).unwrap()),
+            time: (i % 3 == 0).then(|| Time::from_hms_opt(12, 30, 0).unwrap()),
+        })
+        .collect();
+
+    let rows = metadata::Entity::copy_in(
+        models
+            .iter()
+            .cloned()
+            .map(IntoActiveModel::into_active_model),
+    )
+    .exec(db)
+    .await?;
+    assert_eq!(rows, 2500);
+    assert_eq!(
+        metadata::Entity::find()
+            .order_by_asc(metadata::Column::Uuid)
+            .all(db)
+            .await?,
+        models
+    );
+
+    let mut csv = Vec::new();
+    metadata::Entity::find()
+        .filter(metadata::Column::Uuid.lte(Uuid::from_u128(4)))
+        .order_by_asc(metadata::Column::Uuid)
+        .copy_out(db, &mut csv)
+        .await?;
+    let csv = String::from_utf8(csv).unwrap();
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("uuid,type,key,value,bytes,date,time"));
+    assert!(lines.next().unwrap().starts_with(
+        r#"00000000-0000-0000-0000-000000000000,"",key 0,NULL,\x0000ff,2024-02-29,12:30:00"#
+    ));
+    assert!(lines.next().unwrap().contains(r#","a,b","#));
+    assert!(lines.next().unwrap().contains(r#","say ""hi""","#));
+    assert_eq!(
+        lines.next(),
+        Some(r#"00000000-0000-0000-0000-000000000003,"line"#)
+    );
+    assert!(
+        lines
+            .next()
+            .unwrap()
+            .starts_with(r#"break",key 3,NULL,\x0300ff,,12:30:00"#)
+    );
+    assert!(lines.next().unwrap().contains(r"\."));
+    assert_eq!(lines.next(), None);
+
+    Ok(())
+}
+
+pub async fn copy_in_binary(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let models: Vec<_> = (2500..3000u128)
+        .map(|i| metadata::Model {
+            uuid: Uuid::from_u128(i),
+            ty: "binary".to_owned(),
+            key: format!("key {i}"),
+            value: String::new(),
+            bytes: vec![i as u8, 0, 255],
+            date: (i % 2 == 0).then(|| Date::from_ymd_opt(2024, 2, 29).unwrap()),
+            time: (i % 3 == 0).then(|| Time::from_hms_opt(12, 30, 0).unwrap()),
+        })
+        .collect();
+
+    let rows = metadata::Entity::copy_in(
+        models
+            .iter()
+            .cloned()
+            .map(IntoActiveModel::into_active_model),
+    )
+    .format(CopyFormat::Binary)
+    .exec(db)
+    .await?;
+    assert_eq!(rows, 500);
+    assert_eq!(
+        metadata::Entity::find()
+            .filter(metadata::Column::Type.eq("binary"))
+            .order_by_asc(metadata::Column::Uuid)
+            .all(db)
+            .await?,
+        models
+    );
+
+    Ok(())
+}
+
+pub async fn copy_in_defaults(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let launch_date = "2024-02-29T12:30:00Z".parse::<DateTimeUtc>().unwrap();
+
+    // `COPY` would write NULL where a row leaves out a column others set
+    let models = (1..=4).map(|i| satellite::ActiveModel {
+        satellite_name: Set(format!("Sat {i}")),
+        launch_date: if i % 2 == 0 { Set(launch_date) } else { NotSet },
+        ..Default::default()
+    });
+    assert_eq!(satellite::Entity::copy_in(models).exec(db).await?, 4);
+
+    let satellites = satellite::Entity::find()
+        .order_by_asc(satellite::Column::Id)
+        .all(db)
+        .await?;
+    assert_eq!(satellites.len(), 4);
+    for (i, satellite) in satellites.iter().enumerate() {
+        if i % 2 == 1 {
+            assert_eq!(satellite.launch_date, launch_date);
+        } else {
+            assert_ne!(satellite.launch_date, launch_date);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,191 @@
+#![cfg(feature = "duckdb")]
+//! The DuckDB driver is blocking, so only `sea-orm-sync` builds it; `build-tools/make-sync.sh`
+//! copies these tests there. Run them from `sea-orm-sync`:
+//!
+//! cargo t --test duckdb_tests --features=duckdb,duckdb/bundled
+//! cargo t --test duckdb_tests --features=duckdb,duckdb/bundled,with-arrow
+use pretty_assertions::assert_eq;
+use sea_orm::{
+    ConnectionTrait, Database, DatabaseConnection, DbBackend, DbErr, QueryOrder, QuerySelect,
+    Schema, Set, SqlErr, TransactionTrait, entity::prelude::*,
+};
+
+mod bakery {
+    use sea_orm::entity::prelude::*;
+
+    #[sea_orm::model]
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    #[sea_orm(table_name = "bakery")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i32,
+        #[sea_orm(unique)]
+        pub name: String,
+        pub profit_margin: f64,
+        pub is_open: bool,
+        pub opened_at: ChronoDateTime,
+        pub manager_id: Option<Uuid>,
+        pub rating: Option<i32>,
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+fn setup() -> Result<DatabaseConnection, DbErr> {
+    let db = Database::connect("duckdb::memory:")?;
+    assert_eq!(db.get_database_backend(), DbBackend::Postgres);
+
+    let schema = Schema::new(db.get_database_backend());
+    db.execute(&schema.create_table_from_entity(bakery::Entity))?;
+
+    Ok(db)
+}
+
+fn new_bakery(name: &str, rating: Option<i32>) -> bakery::ActiveModel {
+    bakery::ActiveModel {
+        name: Set(name.to_owned()),
+        profit_margin: Set(10.4),
+        is_open: Set(true),
+        opened_at: Set("2025-01-02T03:04:05.678".parse().unwrap()),
+        manager_id: Set(Some(
+            "67e55044-10b1-426f-9247-bb680e5fe0c8".parse().unwrap(),
+        )),
+        rating: Set(rating),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn crud() -> Result<(), DbErr> {
+    let db = setup()?;
+
+    let first = new_bakery("SeaSide Bakery", Some(5)).insert(&db)?;
+    let second = new_bakery("LakeSide Bakery", None).insert(&db)?;
+    assert_eq!(first.id, 1);
+    assert_eq!(second.id, 2);
+    assert_eq!(second.rating, None);
+
+    let found = bakery::Entity::find_by_id(1).one(&db)?;
+    assert_eq!(found, Some(first.clone()));
+
+    let mut active: bakery::ActiveModel = first.into();
+    active.is_open = Set(false);
+    active.update(&db)?;
+
+    let open = bakery::Entity::find()
+        .filter(bakery::Column::IsOpen.eq(true))
+        .all(&db)?;
+    assert_eq!(open, [second]);
+
+    let res = bakery::Entity::delete_many().exec(&db)?;
+    assert_eq!(res.rows_affected, 2);
+    assert_eq!(bakery::Entity::find().count(&db)?, 0);
+
+    Ok(())
+}
+
+#[test]
+fn read_only_file() -> Result<(), DbErr> {
+    let path = std::env::temp_dir().join(format!("sea-orm-duckdb-{}.db", std::process::id()));
+    let url = format!("duckdb://{}", path.display());
+
+    let db = Database::connect(&url)?;
+    let schema = Schema::new(db.get_database_backend());
+    db.execute(&schema.create_table_from_entity(bakery::Entity))?;
+    new_bakery("SeaSide Bakery", None).insert(&db)?;
+    drop(db);
+
+    let db = Database::connect(format!("{url}?access_mode=read_only"))?;
+    assert_eq!(bakery::Entity::find().count(&db)?, 1);
+    new_bakery("LakeSide Bakery", None)
+        .insert(&db)
+        .expect_err("the database is read only");
+    drop(db);
+
+    std::fs::remove_file(path).unwrap();
+    Ok(())
+}
+
+#[test]
+fn transaction() -> Result<(), DbErr> {
+    let db = setup()?;
+
+    let txn = db.begin()?;
+    new_bakery("SeaSide Bakery", None).insert(&txn)?;
+    txn.rollback()?;
+    assert_eq!(bakery::Entity::find().count(&db)?, 0);
+
+    let txn = db.begin()?;
+    new_bakery("SeaSide Bakery", None).insert(&txn)?;
+    txn.commit()?;
+    assert_eq!(bakery::Entity::find().count(&db)?, 1);
+
+    Ok(())
+}
+
+#[test]
+fn unique_constraint_violation() -> Result<(), DbErr> {
+    let db = setup()?;
+
+    new_bakery("SeaSide Bakery", None).insert(&db)?;
+    let err = new_bakery("SeaSide Bakery", None)
+        .insert(&db)
+        .expect_err("duplicate name");
+    assert!(matches!(
+        err.sql_err(),
+        Some(SqlErr::UniqueConstraintViolation(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn json() -> Result<(), DbErr> {
+    let db = setup()?;
+
+    new_bakery("SeaSide Bakery", Some(5)).insert(&db)?;
+    let json = bakery::Entity::find()
+        .select_only()
+        .column(bakery::Column::Name)
+        .column(bakery::Column::Rating)
+        .into_json()
+        .one(&db)?;
+    assert_eq!(
+        json,
+        Some(serde_json::json!({
+            "name": "SeaSide Bakery",
+            "rating": 5,
+        }))
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "with-arrow")]
+#[test]
+fn query_arrow() -> Result<(), DbErr> {
+    use sea_orm::{ActiveModelTrait, QueryTrait};
+
+    let db = setup()?;
+
+    new_bakery("SeaSide Bakery", Some(5)).insert(&db)?;
+    new_bakery("LakeSide Bakery", None).insert(&db)?;
+
+    let stmt = bakery::Entity::find()
+        .select_only()
+        .column(bakery::Column::Id)
+        .column(bakery::Column::Name)
+        .column(bakery::Column::Rating)
+        .order_by_asc(bakery::Column::Id)
+        .build(DbBackend::Postgres);
+    let batches = db.query_arrow(stmt)?;
+    let rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+    assert_eq!(rows, 2);
+
+    let models = bakery::ActiveModel::from_arrow(&batches[0])?;
+    assert_eq!(models.len(), 2);
+    assert_eq!(models[0].name, Set("SeaSide Bakery".to_owned()));
+    assert_eq!(models[1].rating, Set(None));
+
+    Ok(())
+}
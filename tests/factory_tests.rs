@@ -0,0 +1,117 @@
+#![allow(unused_imports, dead_code)]
+
+mod common;
+
+use crate::common::TestContext;
+use sea_orm::{DbErr, entity::*, prelude::*, query::*};
+use tracing::info;
+
+mod user {
+    use sea_orm::entity::prelude::*;
+
+    #[sea_orm::model]
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, DeriveFactory)]
+    #[sea_orm(table_name = "factory_user")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i32,
+        pub name: String,
+        #[sea_orm(unique)]
+        pub email: String,
+        pub nickname: Option<String>,
+        #[sea_orm(has_many)]
+        pub posts: HasMany<super::post::Entity>,
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+mod post {
+    use sea_orm::entity::prelude::*;
+
+    #[sea_orm::model]
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, DeriveFactory)]
+    #[sea_orm(table_name = "factory_post")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i32,
+        pub user_id: i32,
+        pub title: String,
+        pub views: i64,
+        #[sea_orm(belongs_to, from = "user_id", to = "id")]
+        pub author: BelongsTo<super::user::Entity>,
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+#[sea_orm_macros::test]
+async fn test_factory() -> Result<(), DbErr> {
+    let ctx = TestContext::new("test_factory").await;
+    let db = &ctx.db;
+
+    db.get_schema_builder()
+        .register(user::Entity)
+        .register(post::Entity)
+        .apply(db)
+        .await?;
+
+    info!("fill columns with default values");
+    let user = user::Entity::factory().create(db).await?;
+
+    assert_eq!(user.id, 1);
+    assert_eq!(user.name, "name_1");
+    assert_eq!(user.email, "email_1");
+    assert_eq!(user.nickname, None);
+
+    info!("sequences and overrides, numbered on from the factory above");
+    let users = user::Entity::factory()
+        .sequence(user::Column::Email, "user{n}@x.com")
+        .set(user::Column::Nickname, "sea")
+        .create_many(3, db)
+        .await?;
+
+    assert_eq!(
+        users
+            .iter()
+            .map(|user| (user.email.as_str(), user.nickname.as_deref()))
+            .collect::<Vec<_>>(),
+        [
+            ("user2@x.com", Some("sea")),
+            ("user3@x.com", Some("sea")),
+            ("user4@x.com", Some("sea")),
+        ]
+    );
+
+    info!("build the required parent along with the model");
+    let factory = post::Entity::factory().sequence(post::Column::Title, "Post #{n}");
+    let post = factory.create(db).await?;
+
+    assert_eq!(post.title, "Post #1");
+    assert_eq!(post.views, 1);
+    let author = post.author.as_ref().expect("author is saved with the post");
+    assert_eq!(post.user_id, author.id);
+    // the author is the fifth user built by a user factory
+    assert_eq!(author.email, "email_5");
+    assert_eq!(author.id, 5);
+
+    let post = factory.create(db).await?;
+
+    assert_eq!(post.title, "Post #2");
+    assert_eq!(post.user_id, 6);
+
+    info!("skip the parent when the foreign key is overridden");
+    let posts = post::Entity::factory()
+        .set(post::Column::UserId, users[0].id)
+        .create_many(2, db)
+        .await?;
+
+    assert!(posts.iter().all(|post| post.user_id == users[0].id));
+    assert!(posts.iter().all(|post| post.author.is_unloaded()));
+    assert_eq!(user::Entity::find().count(db).await?, 6);
+    assert_eq!(post::Entity::find().count(db).await?, 4);
+
+    ctx.delete().await;
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+#![allow(unused_imports, dead_code)]
+
+pub mod common;
+
+pub use common::{TestContext, features::*, setup::*};
+use pretty_assertions::assert_eq;
+use sea_orm::{DatabaseConnection, entity::prelude::*, entity::*};
+
+#[sea_orm_macros::test]
+async fn main() -> Result<(), DbErr> {
+    let ctx = TestContext::new("insert_many_chunk_tests").await;
+    create_insert_default_table(&ctx.db).await?;
+    insert_past_bind_limit(&ctx.db).await?;
+    ctx.delete().await;
+
+    Ok(())
+}
+
+pub async fn insert_past_bind_limit(db: &DatabaseConnection) -> Result<(), DbErr> {
+    use insert_default::*;
+
+    // more rows than there can be bind parameters in one statement on any backend
+    let models = (1..=70000).map(|id| ActiveModel { id: Set(id) });
+    let keys = Entity::insert_many(models).exec_with_returning_keys(db).await?;
+    assert_eq!(keys, (1..=70000).collect::<Vec<_>>());
+    assert_eq!(Entity::find().count(db).await?, 70000);
+
+    // the statements run in one transaction, so a conflict in the last leaves no rows
+    let models = (70001..=140001).map(|id| ActiveModel {
+        id: Set(if id == 140001 { 1 } else { id }),
+    });
+    assert!(Entity::insert_many(models).exec(db).await.is_err());
+    assert_eq!(Entity::find().count(db).await?, 70000);
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+#![allow(unused_imports, dead_code)]
+
+pub mod common;
+
+pub use common::{TestContext, setup::*};
+use futures_util::StreamExt;
+use pretty_assertions::assert_eq;
+use sea_orm::{DbErr, TransactionTrait};
+
+#[sea_orm_macros::test]
+#[cfg(feature = "sqlx-postgres")]
+async fn listen_notify() -> Result<(), DbErr> {
+    use sea_orm::PgNotification;
+
+    let ctx = TestContext::new("listen_notify_tests").await;
+    let mut notifications = ctx.db.listen(&["jobs", "cache"]).await?;
+
+    ctx.db.notify("jobs", "42").await?;
+
+    // Only sent once the transaction commits
+    let txn = ctx.db.begin().await?;
+    txn.notify("cache", "cake").await?;
+    txn.rollback().await?;
+
+    let txn = ctx.db.begin().await?;
+    txn.notify("cache", "fruit").await?;
+    txn.commit().await?;
+
+    let mut next = async || {
+        let PgNotification {
+            channel, payload, ..
+        } = notifications.next().await.unwrap()?;
+        Ok::<_, DbErr>((channel, payload))
+    };
+    assert_eq!(next().await?, ("jobs".to_owned(), "42".to_owned()));
+    assert_eq!(next().await?, ("cache".to_owned(), "fruit".to_owned()));
+
+    drop(notifications);
+    ctx.delete().await;
+
+    Ok(())
+}
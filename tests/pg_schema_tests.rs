@@ -0,0 +1,80 @@
+#![allow(unused_imports, dead_code)]
+
+pub mod common;
+
+pub use common::{TestContext, features::*, setup::*};
+use futures_util::TryStreamExt;
+use pretty_assertions::assert_eq;
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, DbErr, Schema, Statement, TransactionTrait,
+    entity::prelude::*, entity::*,
+};
+
+#[sea_orm_macros::test]
+#[cfg(feature = "sqlx-postgres")]
+async fn main() -> Result<(), DbErr> {
+    let ctx = TestContext::new("pg_schema_tests").await;
+    let tenant_a = create_tenant(&ctx.db, "tenant_a").await?;
+    let tenant_b = create_tenant(&ctx.db, "tenant_b").await?;
+    tenants_are_isolated(&tenant_a, &tenant_b).await?;
+    search_path_is_not_leaked(&ctx.db).await?;
+    ctx.delete().await;
+
+    Ok(())
+}
+
+pub async fn create_tenant(
+    db: &DatabaseConnection,
+    schema: &str,
+) -> Result<DatabaseConnection, DbErr> {
+    db.execute_unprepared(&format!(r#"CREATE SCHEMA "{schema}""#))
+        .await?;
+    let tenant = db.with_schema(schema)?;
+    let backend = tenant.get_database_backend();
+    tenant
+        .execute(&Schema::new(backend).create_table_from_entity(insert_default::Entity))
+        .await?;
+
+    Ok(tenant)
+}
+
+pub async fn tenants_are_isolated(
+    tenant_a: &DatabaseConnection,
+    tenant_b: &DatabaseConnection,
+) -> Result<(), DbErr> {
+    use insert_default::*;
+
+    ActiveModel { id: Set(1) }.insert(tenant_a).await?;
+    let txn = tenant_b.begin().await?;
+    ActiveModel { id: Set(2) }.insert(&txn).await?;
+    ActiveModel { id: Set(3) }.insert(&txn).await?;
+    txn.commit().await?;
+
+    assert_eq!(Entity::find().all(tenant_a).await?, [Model { id: 1 }]);
+    assert_eq!(
+        Entity::find()
+            .stream(tenant_b)
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?,
+        [Model { id: 2 }, Model { id: 3 }]
+    );
+
+    Ok(())
+}
+
+pub async fn search_path_is_not_leaked(db: &DatabaseConnection) -> Result<(), DbErr> {
+    for _ in 0..5 {
+        let row = db
+            .query_one_raw(Statement::from_string(
+                db.get_database_backend(),
+                "SELECT current_setting('search_path') AS search_path",
+            ))
+            .await?
+            .unwrap();
+        let search_path: String = row.try_get("", "search_path")?;
+        assert!(!search_path.contains("tenant"), "{search_path}");
+    }
+
+    Ok(())
+}
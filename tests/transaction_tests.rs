@@ -1018,3 +1018,36 @@ pub async fn transaction_begin_with_options() -> Result<(), DbErr> {
     ctx.delete().await;
     Ok(())
 }
+
+#[sea_orm_macros::test]
+pub async fn transaction_named_savepoint() -> Result<(), DbErr> {
+    let ctx = TestContext::new("transaction_named_savepoint_test").await;
+    create_bakery_table(&ctx.db).await?;
+
+    let txn = ctx.db.begin().await?;
+
+    seaside_bakery().save(&txn).await?;
+
+    txn.savepoint("before_import").await?;
+    top_bakery().save(&txn).await?;
+    assert_eq!(bakery::Entity::find().all(&txn).await?.len(), 2);
+
+    // Skip the bad row, keeping the work done before the savepoint
+    txn.rollback_to("before_import").await?;
+    assert_eq!(bakery::Entity::find().all(&txn).await?.len(), 1);
+
+    // The savepoint survives a rollback to it
+    top_bakery().save(&txn).await?;
+    txn.rollback_to("before_import").await?;
+    assert_eq!(bakery::Entity::find().all(&txn).await?.len(), 1);
+
+    top_bakery().save(&txn).await?;
+    txn.release("before_import").await?;
+
+    txn.commit().await?;
+
+    assert_eq!(bakery::Entity::find().all(&ctx.db).await?.len(), 2);
+
+    ctx.delete().await;
+    Ok(())
+}